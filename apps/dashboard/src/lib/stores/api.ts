@@ -1,23 +1,62 @@
 import type {
 	MemoryListResponse,
 	Memory,
+	MemoryRevisionsResponse,
+	RevisionDiff,
+	CreateMemoryRequest,
+	CreateMemoryResponse,
+	UpdateMemoryResponse,
+	BulkAction,
+	BulkResponse,
+	ReviewRating,
+	ReviewQueueResponse,
+	ReviewResponse,
 	SearchResult,
+	RankingExplanation,
+	RankingPreset,
 	SystemStats,
 	HealthCheck,
+	DiagnosticsReport,
 	TimelineResponse,
 	GraphResponse,
+	KnowledgeEdge,
+	KnowledgeEdgesResponse,
+	ContradictionsResponse,
 	DreamResult,
 	ImportanceScore,
 	RetentionDistribution,
 	ConsolidationResult,
+	SessionOverview,
+	AccessAnalytics,
+	Forecast,
 	IntentionItem
 } from '$types';
 
 const BASE = '/api';
+const TOKEN_KEY = 'vestige-token';
+
+/**
+ * Dashboard token when the server has token auth on. The server injects it
+ * into a meta tag when the page is opened with ?token=...; it is kept in
+ * sessionStorage so reloads and client-side navigation keep working.
+ */
+export function dashboardToken(): string | null {
+	if (typeof document === 'undefined') return null;
+	const meta = document.querySelector<HTMLMetaElement>('meta[name="vestige-token"]')?.content;
+	if (meta) {
+		sessionStorage.setItem(TOKEN_KEY, meta);
+		return meta;
+	}
+	return sessionStorage.getItem(TOKEN_KEY);
+}
 
 async function fetcher<T>(path: string, options?: RequestInit): Promise<T> {
+	const token = dashboardToken();
 	const res = await fetch(`${BASE}${path}`, {
-		headers: { 'Content-Type': 'application/json' },
+		headers: {
+			'Content-Type': 'application/json',
+			...(token ? { Authorization: `Bearer ${token}` } : {})
+		},
 		...options
 	});
 	if (!res.ok) throw new Error(`API ${res.status}: ${res.statusText}`);
@@ -32,25 +71,78 @@ export const api = {
 			return fetcher<MemoryListResponse>(`/memories${qs}`);
 		},
 		get: (id: string) => fetcher<Memory>(`/memories/${id}`),
+		create: (memory: CreateMemoryRequest) =>
+			fetcher<CreateMemoryResponse>('/memories', {
+				method: 'POST',
+				body: JSON.stringify(memory)
+			}),
+		update: (id: string, changes: { content?: string; tags?: string[] }) =>
+			fetcher<UpdateMemoryResponse>(`/memories/${id}`, {
+				method: 'PUT',
+				body: JSON.stringify(changes)
+			}),
 		delete: (id: string) => fetcher<{ deleted: boolean }>(`/memories/${id}`, { method: 'DELETE' }),
 		promote: (id: string) => fetcher<Memory>(`/memories/${id}/promote`, { method: 'POST' }),
-		demote: (id: string) => fetcher<Memory>(`/memories/${id}/demote`, { method: 'POST' })
+		demote: (id: string) => fetcher<Memory>(`/memories/${id}/demote`, { method: 'POST' }),
+		bulk: (ids: string[], action: BulkAction, tags?: string[]) =>
+			fetcher<BulkResponse>('/memories/bulk', {
+				method: 'POST',
+				body: JSON.stringify({ ids, action, tags })
+			}),
+		revisions: (id: string) => fetcher<MemoryRevisionsResponse>(`/memories/${id}/revisions`),
+		diffRevisions: (id: string, from: number, to?: number) =>
+			fetcher<RevisionDiff>(
+				`/memories/${id}/revisions/diff?from=${from}` + (to !== undefined ? `&to=${to}` : '')
+			),
+		revert: (id: string, revision: number) =>
+			fetcher<{ reverted: boolean; revision: number; memory: Memory }>(
+				`/memories/${id}/revisions/${revision}/revert`,
+				{ method: 'POST' }
+			)
+	},
+
+	// Spaced-repetition review
+	review: {
+		queue: (limit = 20) => fetcher<ReviewQueueResponse>(`/review/queue?limit=${limit}`),
+		submit: (id: string, rating: ReviewRating) =>
+			fetcher<ReviewResponse>(`/review/${id}`, {
+				method: 'POST',
+				body: JSON.stringify({ rating })
+			})
 	},
 
 	// Search
-	search: (q: string, limit = 20) =>
-		fetcher<SearchResult>(`/search?q=${encodeURIComponent(q)}&limit=${limit}`),
+	search: (q: string, limit = 20, ranking?: RankingPreset, explain = false) =>
+		fetcher<SearchResult>(
+			`/search?q=${encodeURIComponent(q)}&limit=${limit}` +
+				(ranking ? `&ranking=${ranking}` : '') +
+				(explain ? '&explain=true' : '')
+		),
+	explainRanking: (q: string, id: string, ranking?: RankingPreset) =>
+		fetcher<RankingExplanation>(
+			`/search/explain?q=${encodeURIComponent(q)}&id=${encodeURIComponent(id)}` +
+				(ranking ? `&ranking=${ranking}` : '')
+		),
 
 	// Stats & Health
 	stats: () => fetcher<SystemStats>('/stats'),
 	health: () => fetcher<HealthCheck>('/health'),
+	deepHealth: () => fetcher<DiagnosticsReport>('/health/deep'),
 
 	// Timeline
 	timeline: (days = 7, limit = 200) =>
 		fetcher<TimelineResponse>(`/timeline?days=${days}&limit=${limit}`),
 
 	// Graph
-	graph: (params?: { query?: string; center_id?: string; depth?: number; max_nodes?: number }) => {
+	graph: (params?: {
+		query?: string;
+		center?: string;
+		depth?: number;
+		max_nodes?: number;
+		min_strength?: number;
+		edge_types?: string;
+		mode?: 'summary';
+	}) => {
 		const qs = params ? '?' + new URLSearchParams(
 			Object.entries(params)
 				.filter(([, v]) => v !== undefined)
@@ -59,6 +151,35 @@ export const api = {
 		return fetcher<GraphResponse>(`/graph${qs}`);
 	},
 
+	// Typed knowledge edges
+	edges: {
+		list: (nodeId: string) =>
+			fetcher<KnowledgeEdgesResponse>(`/edges?node=${encodeURIComponent(nodeId)}`),
+		create: (edge: {
+			source_id: string;
+			target_id: string;
+			edge_type: string;
+			weight?: number;
+			confidence?: number;
+			metadata?: string;
+		}) =>
+			fetcher<{ created: boolean; edge: KnowledgeEdge }>('/edges', {
+				method: 'POST',
+				body: JSON.stringify(edge)
+			}),
+		delete: (id: string) =>
+			fetcher<{ deleted: boolean; id: string }>(`/edges/${id}`, { method: 'DELETE' })
+	},
+
+	// Unresolved contradictions flagged at ingest
+	contradictions: {
+		list: (limit = 50) => fetcher<ContradictionsResponse>(`/contradictions?limit=${limit}`),
+		resolve: (id: string) =>
+			fetcher<{ resolved: boolean; id: string }>(`/contradictions/${id}/resolve`, {
+				method: 'POST'
+			})
+	},
+
 	// Cognitive operations
 	dream: () => fetcher<DreamResult>('/dream', { method: 'POST' }),
 
@@ -80,6 +201,15 @@ export const api = {
 
 	retentionDistribution: () => fetcher<RetentionDistribution>('/retention-distribution'),
 
+	accessAnalytics: (days = 30) => fetcher<AccessAnalytics>(`/analytics/access?days=${days}`),
+
+	forecast: (days = 30) => fetcher<Forecast>(`/forecast?days=${days}`),
+
+	sessionOverview: (budget = 500, codebase?: string) =>
+		fetcher<SessionOverview>(
+			`/session/overview?budget=${budget}${codebase ? `&codebase=${encodeURIComponent(codebase)}` : ''}`
+		),
+
 	// Intentions
 	intentions: (status = 'active') =>
 		fetcher<{ intentions: IntentionItem[]; total: number; filter: string }>(`/intentions?status=${status}`)
@@ -1,5 +1,6 @@
 import { writable, derived } from 'svelte/store';
 import type { VestigeEvent } from '$types';
+import { dashboardToken } from './api';
 
 const MAX_EVENTS = 200;
 
@@ -21,9 +22,10 @@ function createWebSocketStore() {
 	let reconnectAttempts = 0;
 
 	function connect(url?: string) {
+		const token = dashboardToken();
 		const wsUrl = url || (window.location.port === '5173'
 			? `ws://${window.location.hostname}:3927/ws`
-			: `ws://${window.location.host}/ws`);
+			: `ws://${window.location.host}/ws`) + (token ? `?token=${encodeURIComponent(token)}` : '');
 
 		if (ws?.readyState === WebSocket.OPEN) return;
 
@@ -13,6 +13,7 @@ export interface Memory {
 	source?: string;
 	reviewCount?: number;
 	combinedScore?: number;
+	scoreBreakdown?: ScoreBreakdown | null;
 	sentimentScore?: number;
 	sentimentMagnitude?: number;
 	lastAccessedAt?: string;
@@ -21,13 +22,123 @@ export interface Memory {
 	validUntil?: string;
 }
 
+export interface MemoryRevision {
+	id: number;
+	nodeId: string;
+	oldContent: string;
+	newContentHash: string;
+	changedAt: string;
+	changeSource: string;
+	diff?: string | null;
+}
+
+export interface MemoryRevisionsResponse {
+	id: string;
+	total: number;
+	revisions: MemoryRevision[];
+}
+
+export interface RevisionDiff {
+	nodeId: string;
+	from: number;
+	to: number | null;
+	diff: string;
+	similarity: number | null;
+}
+
+export type EdgeType =
+	| 'semantic'
+	| 'temporal'
+	| 'causal'
+	| 'derived'
+	| 'contradiction'
+	| 'refinement'
+	| 'partof'
+	| 'custom';
+
+export interface KnowledgeEdge {
+	id: string;
+	sourceId: string;
+	targetId: string;
+	edgeType: EdgeType;
+	weight: number;
+	validFrom?: string | null;
+	validUntil?: string | null;
+	createdAt: string;
+	createdBy?: string | null;
+	confidence: number;
+	metadata?: string | null;
+}
+
+export interface KnowledgeEdgesResponse {
+	node: string;
+	total: number;
+	edges: KnowledgeEdge[];
+}
+
+export interface ContradictionSide {
+	id: string;
+	content: string | null;
+}
+
+export interface Contradiction {
+	id: string;
+	newer: ContradictionSide;
+	older: ContradictionSide;
+	confidence: number;
+	metadata: { kind?: string; detail?: string } | null;
+	createdAt: string;
+}
+
+export interface ContradictionsResponse {
+	total: number;
+	contradictions: Contradiction[];
+}
+
+export type RankingPreset = 'balanced' | 'recent' | 'established';
+
+export interface RankingProfile {
+	recency_weight: number;
+	importance_weight: number;
+	relevance_weight: number;
+	recency_half_life_hours: number;
+}
+
+export interface ScoreBreakdown {
+	ftsRank?: number | null;
+	keywordRank?: number | null;
+	keywordScore?: number | null;
+	semanticRank?: number | null;
+	semanticSimilarity?: number | null;
+	hydeExpanded: boolean;
+	relevance: number;
+	recency: number;
+	activation: number;
+	importance: number;
+	recencyTerm: number;
+	importanceTerm: number;
+	relevanceTerm: number;
+	trust: number;
+	trustTerm: number;
+	finalScore: number;
+	intentBoost?: number;
+}
+
 export interface SearchResult {
 	query: string;
+	ranking: RankingProfile | null;
 	total: number;
 	durationMs: number;
 	results: Memory[];
 }
 
+export interface RankingExplanation {
+	query: string;
+	id: string;
+	ranking: RankingProfile | null;
+	scoreBreakdown: ScoreBreakdown;
+}
+
 export interface MemoryListResponse {
 	total: number;
 	memories: Memory[];
@@ -46,13 +157,42 @@ export interface SystemStats {
 	newestMemory?: string;
 }
 
+export interface EmbeddingBackfillStatus {
+	done: boolean;
+	percentComplete: number;
+	processed: number;
+	remaining: number;
+	failed: number;
+	resumeToken: string | null;
+	updatedAt: string | null;
+}
+
 export interface HealthCheck {
 	status: 'healthy' | 'degraded' | 'critical' | 'empty';
 	totalMemories: number;
 	averageRetention: number;
+	embeddingBackfill: EmbeddingBackfillStatus | null;
 	version: string;
 }
 
+export interface DiagnosticCheck {
+	name: string;
+	status: 'ok' | 'warn' | 'error';
+	detail: string;
+	remediation: string | null;
+}
+
+export interface DiagnosticsReport {
+	status: 'ok' | 'warn' | 'error';
+	generatedAt: string;
+	dbSizeBytes: number;
+	walSizeBytes: number;
+	pageCount: number;
+	pageSize: number;
+	freelistCount: number;
+	checks: DiagnosticCheck[];
+}
+
 export interface TimelineDay {
 	date: string;
 	count: number;
@@ -70,10 +210,16 @@ export interface GraphNode {
 	label: string;
 	type: string;
 	retention: number;
+	importance: number | null;
+	activation: number | null;
+	/** active | dormant | silent | unavailable */
+	state: string;
 	tags: string[];
 	createdAt: string;
 	updatedAt: string;
 	isCenter: boolean;
+	/** Index into `communities` (summary mode only) */
+	community?: number;
 }
 
 export interface GraphEdge {
@@ -81,15 +227,24 @@ export interface GraphEdge {
 	target: string;
 	weight: number;
 	type: string;
+	/** 'connection' = activation-network link, 'edge' = typed knowledge edge */
+	kind: 'connection' | 'edge';
+}
+
+export interface GraphCommunity {
+	id: number;
+	size: number;
 }
 
 export interface GraphResponse {
 	nodes: GraphNode[];
 	edges: GraphEdge[];
-	center_id: string;
+	center_id?: string;
 	depth: number;
 	nodeCount: number;
 	edgeCount: number;
+	communities?: GraphCommunity[];
+	truncated: boolean;
 }
 
 export interface DreamResult {
@@ -97,16 +252,26 @@ export interface DreamResult {
 	memoriesReplayed: number;
 	connectionsPersisted: number;
 	insights: DreamInsight[];
+	phases: DreamPhaseResult[];
 	stats: {
 		newConnectionsFound: number;
 		connectionsPersisted: number;
 		memoriesStrengthened: number;
 		memoriesCompressed: number;
+		memoriesDownscaled: number;
+		emotionalProcessed: number;
 		insightsGenerated: number;
 		durationMs: number;
 	};
 }
 
+export interface DreamPhaseResult {
+	phase: string;
+	durationMs: number;
+	memoriesProcessed: number;
+	actions: string[];
+}
+
 export interface DreamInsight {
 	type: string;
 	insight: string;
@@ -133,6 +298,46 @@ export interface RetentionDistribution {
 	total: number;
 }
 
+export interface AccessAnalytics {
+	windowStart: string;
+	windowEnd: string;
+	windowTruncated: boolean;
+	totalAccesses: number;
+	topAccessed: { id: string; preview: string; accesses: number; lastAccessed: string }[];
+	accessesByDay: { date: string; accesses: number }[];
+	accessesByType: Record<string, number>;
+	promoteRatio: number | null;
+	neverAccessedPct: number;
+	medianHoursToFirstAccess: number | null;
+}
+
+export interface ForecastDay {
+	date: string;
+	dueCount: number;
+	projectedAvgRetention: number;
+}
+
+export interface Forecast {
+	generatedAt: string;
+	w20: number;
+	totalMemories: number;
+	days: ForecastDay[];
+}
+
+export interface SessionPacket {
+	title: string;
+	previews: string[];
+	memoryIds: string[];
+	importance: number;
+}
+
+export interface SessionOverview {
+	packets: SessionPacket[];
+	droppedClusters: number;
+	estimatedTokens: number;
+	budgetTokens: number;
+}
+
 export interface ConsolidationResult {
 	nodesProcessed: number;
 	decayApplied: number;
@@ -142,6 +347,73 @@ export interface ConsolidationResult {
 	durationMs: number;
 }
 
+// Dashboard memory writes
+export interface CreateMemoryRequest {
+	content: string;
+	node_type?: string;
+	tags?: string[];
+	source?: string;
+	sentiment?: number;
+}
+
+export interface CreateMemoryResponse {
+	created: boolean;
+	decision: string;
+	reason: string;
+	similarity?: number;
+	supersededId?: string;
+	contradicts?: string[];
+	memory: Memory;
+}
+
+export interface UpdateMemoryResponse {
+	updated: boolean;
+	fields: string[];
+	memory: Memory;
+}
+
+// Bulk memory operations
+export type BulkAction = 'delete' | 'promote' | 'demote' | 'retag';
+
+export interface BulkOutcome {
+	id: string;
+	ok: boolean;
+	error?: string;
+	retentionStrength?: number;
+}
+
+export interface BulkResponse {
+	action: BulkAction;
+	succeeded: number;
+	failed: number;
+	results: BulkOutcome[];
+}
+
+// Spaced-repetition review
+export type ReviewRating = 'again' | 'hard' | 'good' | 'easy';
+
+export interface ReviewQueueItem {
+	retrievability: number;
+	/** Days until the next review for each rating */
+	intervals: Record<ReviewRating, number>;
+	memory: Memory;
+}
+
+export interface ReviewQueueResponse {
+	total: number;
+	queue: ReviewQueueItem[];
+}
+
+export interface ReviewResponse {
+	reviewed: boolean;
+	rating: ReviewRating;
+	/** True when the memory was not yet due */
+	early: boolean;
+	nextReview: string | null;
+	dueCount: number;
+	memory: Memory;
+}
+
 // WebSocket event types
 export type VestigeEventType =
 	| 'Connected'
@@ -150,14 +422,18 @@ export type VestigeEventType =
 	| 'MemoryDeleted'
 	| 'MemoryPromoted'
 	| 'MemoryDemoted'
+	| 'MemoriesBulkUpdated'
+	| 'MemoryReviewed'
 	| 'SearchPerformed'
 	| 'DreamStarted'
 	| 'DreamProgress'
 	| 'DreamCompleted'
 	| 'ConsolidationStarted'
+	| 'ConsolidationProgress'
 	| 'ConsolidationCompleted'
 	| 'RetentionDecayed'
 	| 'ConnectionDiscovered'
+	| 'EdgeCreated'
 	| 'ActivationSpread'
 	| 'ImportanceScored'
 	| 'Heartbeat';
@@ -198,14 +474,18 @@ export const EVENT_TYPE_COLORS: Record<string, string> = {
 	MemoryDeleted: '#ef4444',
 	MemoryPromoted: '#22c55e',
 	MemoryDemoted: '#f97316',
+	MemoriesBulkUpdated: '#a3a3a3',
+	MemoryReviewed: '#84cc16',
 	SearchPerformed: '#6366f1',
 	DreamStarted: '#8b5cf6',
 	DreamProgress: '#7c3aed',
 	DreamCompleted: '#a855f7',
 	ConsolidationStarted: '#f59e0b',
+	ConsolidationProgress: '#fbbf24',
 	ConsolidationCompleted: '#f97316',
 	RetentionDecayed: '#ef4444',
 	ConnectionDiscovered: '#06b6d4',
+	EdgeCreated: '#0ea5e9',
 	ActivationSpread: '#14b8a6',
 	ImportanceScored: '#ec4899',
 	Heartbeat: '#6b7280',
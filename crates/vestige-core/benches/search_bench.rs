@@ -5,8 +5,11 @@
 
 use criterion::{criterion_group, criterion_main, Criterion, black_box};
 use vestige_core::search::hyde::{classify_intent, expand_query, centroid_embedding};
-use vestige_core::search::{reciprocal_rank_fusion, linear_combination, sanitize_fts5_query};
+use vestige_core::search::{
+    reciprocal_rank_fusion, linear_combination, sanitize_fts5_query, VectorIndex, VectorIndexConfig,
+};
 use vestige_core::embeddings::cosine_similarity;
+use vestige_core::testing::seeded_storage;
 
 fn bench_classify_intent(c: &mut Criterion) {
     let queries = [
@@ -100,6 +103,50 @@ fn bench_cosine_similarity(c: &mut Criterion) {
     });
 }
 
+fn bench_hnsw_ef_search(c: &mut Criterion) {
+    let mut index = VectorIndex::with_config(VectorIndexConfig::default()).unwrap();
+    index.reserve(5000).unwrap();
+    for i in 0..5000 {
+        let v: Vec<f32> = (0..256).map(|j| ((i * 256 + j) as f32 * 0.37).sin()).collect();
+        index.add(&format!("doc-{i}"), &v).unwrap();
+    }
+    let query: Vec<f32> = (0..256).map(|j| (j as f32 * 0.11).cos()).collect();
+
+    for ef in [16, 64, 256] {
+        index.set_ef_search(ef);
+        c.bench_function(&format!("hnsw_search_5k_ef{ef}"), |b| {
+            b.iter(|| {
+                black_box(index.search(&query, 10).unwrap());
+            })
+        });
+    }
+}
+
+fn bench_strengthen_on_access(c: &mut Criterion) {
+    // A 20-result recall's worth of accesses
+    let storage = seeded_storage(200).unwrap();
+    let ids: Vec<String> = storage
+        .get_all_nodes(20, 0)
+        .unwrap()
+        .into_iter()
+        .map(|n| n.id)
+        .collect();
+    let refs: Vec<&str> = ids.iter().map(String::as_str).collect();
+
+    c.bench_function("strengthen_on_access_x20", |b| {
+        b.iter(|| {
+            for id in &refs {
+                storage.strengthen_on_access(id).unwrap();
+            }
+        })
+    });
+    c.bench_function("strengthen_batch_on_access_20", |b| {
+        b.iter(|| {
+            storage.strengthen_batch_on_access(black_box(&refs)).unwrap();
+        })
+    });
+}
+
 criterion_group!(
     benches,
     bench_classify_intent,
@@ -109,5 +156,7 @@ criterion_group!(
     bench_linear_combination,
     bench_sanitize_fts5,
     bench_cosine_similarity,
+    bench_hnsw_ef_search,
+    bench_strengthen_on_access,
 );
 criterion_main!(benches);
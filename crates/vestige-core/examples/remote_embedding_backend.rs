@@ -0,0 +1,150 @@
+//! Remote Embedding Backend
+//!
+//! Plugs an OpenAI-compatible `/v1/embeddings` endpoint (llama.cpp server,
+//! Ollama, vLLM, text-embeddings-inference...) into `Storage` in place of the
+//! local fastembed model.
+//!
+//! ```text
+//! EMBEDDING_URL=http://127.0.0.1:8080/v1/embeddings \
+//! EMBEDDING_MODEL=nomic-embed-text EMBEDDING_DIMENSIONS=768 \
+//!     cargo run --example remote_embedding_backend
+//! ```
+//!
+//! Only plain `http://` URLs are handled, to keep the example dependency
+//! free; put a TLS-terminating proxy in front of hosted APIs.
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use vestige_core::{EmbeddingBackend, EmbeddingError, IngestInput, Storage};
+
+/// Embeds through an OpenAI-compatible HTTP endpoint
+struct RemoteEmbeddingBackend {
+    host: String,
+    path: String,
+    model: String,
+    dimensions: usize,
+}
+
+impl RemoteEmbeddingBackend {
+    fn new(url: &str, model: &str, dimensions: usize) -> Result<Self, EmbeddingError> {
+        let rest = url.strip_prefix("http://").ok_or_else(|| {
+            EmbeddingError::ModelInit(format!("only http:// endpoints are supported, got {}", url))
+        })?;
+        let (host, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/v1/embeddings"),
+        };
+        Ok(Self {
+            host: host.to_string(),
+            path: path.to_string(),
+            model: model.to_string(),
+            dimensions,
+        })
+    }
+
+    /// POST `body` and return the parsed JSON response
+    fn post(&self, body: &serde_json::Value) -> Result<serde_json::Value, EmbeddingError> {
+        let failed = |e: std::io::Error| EmbeddingError::EmbeddingFailed(format!("{}: {}", self.host, e));
+        let body = body.to_string();
+
+        let mut stream = TcpStream::connect(&self.host).map_err(failed)?;
+        stream.set_read_timeout(Some(Duration::from_secs(30))).map_err(failed)?;
+        write!(
+            stream,
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.path,
+            self.host,
+            body.len(),
+            body
+        )
+        .map_err(failed)?;
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).map_err(failed)?;
+        let (head, payload) = response.split_once("\r\n\r\n").ok_or_else(|| {
+            EmbeddingError::EmbeddingFailed("malformed HTTP response".to_string())
+        })?;
+        let status = head.split_whitespace().nth(1).unwrap_or("");
+        if status != "200" {
+            return Err(EmbeddingError::EmbeddingFailed(format!(
+                "{} answered {}: {}",
+                self.host, status, payload
+            )));
+        }
+        // Chunked transfer encoding is not handled; servers answer
+        // `Connection: close` requests with a plain body
+        serde_json::from_str(payload).map_err(|e| EmbeddingError::EmbeddingFailed(e.to_string()))
+    }
+}
+
+impl EmbeddingBackend for RemoteEmbeddingBackend {
+    fn embed(&self, text: &str) -> Result<Vec<f32>, EmbeddingError> {
+        self.embed_batch(&[text])?
+            .pop()
+            .ok_or_else(|| EmbeddingError::EmbeddingFailed("No embedding generated".to_string()))
+    }
+
+    fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        let response = self.post(&serde_json::json!({
+            "model": self.model,
+            "input": texts,
+        }))?;
+        let data = response["data"].as_array().ok_or_else(|| {
+            EmbeddingError::EmbeddingFailed("response has no data array".to_string())
+        })?;
+        data.iter()
+            .map(|item| {
+                serde_json::from_value(item["embedding"].clone())
+                    .map_err(|e| EmbeddingError::EmbeddingFailed(e.to_string()))
+            })
+            .collect()
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn model_name(&self) -> &str {
+        &self.model
+    }
+
+    fn is_ready(&self) -> bool {
+        TcpStream::connect(&self.host).is_ok()
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let url = std::env::var("EMBEDDING_URL")
+        .unwrap_or_else(|_| "http://127.0.0.1:8080/v1/embeddings".to_string());
+    let model = std::env::var("EMBEDDING_MODEL").unwrap_or_else(|_| "nomic-embed-text".to_string());
+    let dimensions = std::env::var("EMBEDDING_DIMENSIONS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(768);
+
+    let backend = RemoteEmbeddingBackend::new(&url, &model, dimensions)?;
+    backend.init()?;
+
+    let dir = tempfile::tempdir()?;
+    let storage = Storage::with_embedding_backend(Some(dir.path().join("remote.db")), Box::new(backend))?;
+
+    for content in [
+        "The checkout service retries payment calls three times",
+        "Connection pool exhaustion caused Friday's outage",
+        "Team lunch is on Thursdays",
+    ] {
+        storage.ingest(IngestInput {
+            content: content.to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        })?;
+    }
+
+    for result in storage.semantic_search("why did the outage happen", 3, 0.0)? {
+        println!("{:.3}  {}", result.similarity, result.node.content);
+    }
+    Ok(())
+}
@@ -94,6 +94,78 @@ impl Language {
         }
     }
 
+    /// Lowercase language name (as used for pattern languages)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::Python => "python",
+            Self::JavaScript => "javascript",
+            Self::TypeScript => "typescript",
+            Self::Go => "go",
+            Self::Java => "java",
+            Self::Cpp => "cpp",
+            Self::CSharp => "csharp",
+            Self::Ruby => "ruby",
+            Self::Swift => "swift",
+            Self::Kotlin => "kotlin",
+            Self::Sql => "sql",
+            Self::Shell => "shell",
+            Self::Web => "web",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    /// Parse a lowercase language name as returned by `as_str`
+    pub fn from_name(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "rust" => Self::Rust,
+            "python" => Self::Python,
+            "javascript" => Self::JavaScript,
+            "typescript" => Self::TypeScript,
+            "go" => Self::Go,
+            "java" => Self::Java,
+            "cpp" | "c" => Self::Cpp,
+            "csharp" => Self::CSharp,
+            "ruby" => Self::Ruby,
+            "swift" => Self::Swift,
+            "kotlin" => Self::Kotlin,
+            "sql" => Self::Sql,
+            "shell" | "bash" => Self::Shell,
+            "web" => Self::Web,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Whether a trimmed line holds nothing but a comment in this language
+    ///
+    /// `#` only starts a comment where the language says so, so C
+    /// preprocessor directives and Rust attributes are code; a leading `*`
+    /// only continues a block comment when followed by a space or `/`, so
+    /// `*ptr = 0;` is code too.
+    pub fn is_comment_line(&self, line: &str) -> bool {
+        let block = line.starts_with("/*")
+            || line == "*"
+            || line.starts_with("* ")
+            || line.starts_with("*/");
+        match self {
+            Self::Python | Self::Ruby | Self::Shell => {
+                line.starts_with('#') && !line.starts_with("#!")
+            }
+            Self::Sql => line.starts_with("--") || block,
+            Self::Rust
+            | Self::JavaScript
+            | Self::TypeScript
+            | Self::Go
+            | Self::Java
+            | Self::Cpp
+            | Self::CSharp
+            | Self::Swift
+            | Self::Kotlin
+            | Self::Web
+            | Self::Unknown => line.starts_with("//") || block,
+        }
+    }
+
     /// Get common keywords for this language
     pub fn keywords(&self) -> &[&str] {
         match self {
@@ -222,10 +294,19 @@ impl ContentType {
             ContentType::NaturalLanguage
         }
     }
+
+    /// Detected programming language, for code whose language was recognized
+    pub fn language(&self) -> Option<&Language> {
+        match self {
+            ContentType::Code(Language::Unknown) => None,
+            ContentType::Code(lang) => Some(lang),
+            _ => None,
+        }
+    }
 }
 
 /// Embedding strategy to use
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EmbeddingStrategy {
     /// Standard sentence transformer (all-MiniLM-L6-v2)
     SentenceTransformer,
@@ -250,6 +331,29 @@ impl EmbeddingStrategy {
             Self::StructuredEmbedding => DEFAULT_DIMENSIONS,
         }
     }
+
+    /// Stable name, as recorded in `node_embeddings.strategy`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SentenceTransformer => "sentence_transformer",
+            Self::CodeEmbedding => "code",
+            Self::TechnicalEmbedding => "technical",
+            Self::HybridEmbedding => "hybrid",
+            Self::StructuredEmbedding => "structured",
+        }
+    }
+
+    /// Parse a name produced by [`EmbeddingStrategy::as_str`]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "sentence_transformer" => Some(Self::SentenceTransformer),
+            "code" => Some(Self::CodeEmbedding),
+            "technical" => Some(Self::TechnicalEmbedding),
+            "hybrid" => Some(Self::HybridEmbedding),
+            "structured" => Some(Self::StructuredEmbedding),
+            _ => None,
+        }
+    }
 }
 
 /// Analysis results for content
@@ -493,6 +597,20 @@ impl AdaptiveEmbedder {
         self.embed(content, content_type)
     }
 
+    /// Pick a strategy for content and return the text the model should embed
+    ///
+    /// Used by storage so stored vectors come from the same preprocessing the
+    /// strategy describes (e.g. comment-free, whitespace-normalized code).
+    pub fn prepare(&self, content: &str) -> (EmbeddingStrategy, String) {
+        self.prepare_as(content, &ContentType::detect(content))
+    }
+
+    /// [`AdaptiveEmbedder::prepare`] for an already detected content type
+    pub fn prepare_as(&self, content: &str, content_type: &ContentType) -> (EmbeddingStrategy, String) {
+        let strategy = self.select_strategy(content_type);
+        (strategy, self.preprocess(content, content_type))
+    }
+
     /// Get statistics about strategy usage
     pub fn stats(&self) -> &HashMap<String, usize> {
         &self.strategy_stats
@@ -541,19 +659,19 @@ impl AdaptiveEmbedder {
     }
 
     fn preprocess_code(&self, content: &str, lang: &Language) -> String {
-        let mut result = content.to_string();
-
-        // Normalize whitespace
-        result = result
+        // Drop comment-only and blank lines, collapse whitespace within lines
+        let code = content
             .lines()
-            .map(|l| l.trim())
+            .map(|l| l.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|l| !l.is_empty() && !lang.is_comment_line(l))
             .collect::<Vec<_>>()
             .join("\n");
 
         // Add language context
-        result = format!("[{}] {}", format!("{:?}", lang).to_uppercase(), result);
-
-        result
+        match lang {
+            Language::Unknown => code,
+            lang => format!("[{}] {}", format!("{:?}", lang).to_uppercase(), code),
+        }
     }
 
     fn preprocess_error_log(&self, content: &str) -> String {
@@ -768,4 +886,51 @@ fn main() -> Result<(), std::io::Error> {
         let stats = embedder.stats();
         assert!(stats.len() > 0);
     }
+
+    #[test]
+    fn test_prepare_normalizes_code() {
+        let code = "use std::time::Duration;\n// Retry with backoff\nfn retry_request(attempts: u32) -> Result<(), Error> {\n    let   delay = Duration::from_secs(2u64.pow(attempts));\n    std::thread::sleep(delay);\n    tracing::warn!(\"retrying\");\n    send_request()?;\n}\n";
+        let (strategy, text) = AdaptiveEmbedder::new().prepare(code);
+        assert_eq!(strategy, EmbeddingStrategy::CodeEmbedding);
+        assert!(!text.contains("Retry with backoff"));
+        assert!(text.contains("let delay = Duration::from_secs(2u64.pow(attempts));"));
+        assert_eq!(ContentType::detect(code).language(), Some(&Language::Rust));
+
+        let (strategy, text) = AdaptiveEmbedder::new().prepare("The deploy finished without issues.");
+        assert!(ContentType::detect(&text).language().is_none());
+        assert_eq!(strategy, EmbeddingStrategy::SentenceTransformer);
+        assert_eq!(text, "The deploy finished without issues.");
+    }
+
+    #[test]
+    fn test_comment_lines_follow_language() {
+        assert!(!Language::Cpp.is_comment_line("#include <stdio.h>"));
+        assert!(!Language::Cpp.is_comment_line("#define MAX 4"));
+        assert!(!Language::Cpp.is_comment_line("*ptr = 0;"));
+        assert!(Language::Cpp.is_comment_line("* continues a block comment"));
+        assert!(Language::Cpp.is_comment_line("// line comment"));
+        assert!(!Language::Rust.is_comment_line("#[derive(Debug)]"));
+        assert!(Language::Python.is_comment_line("# note"));
+        assert!(!Language::Python.is_comment_line("#!/usr/bin/env python"));
+        assert!(!Language::Python.is_comment_line("x = a // b"));
+        assert!(Language::Sql.is_comment_line("-- note"));
+        assert!(!Language::Rust.is_comment_line("-- not sql"));
+        for lang in [Language::Rust, Language::Cpp, Language::Shell, Language::Unknown] {
+            assert_eq!(Language::from_name(lang.as_str()), lang);
+        }
+    }
+
+    #[test]
+    fn test_strategy_name_roundtrip() {
+        for strategy in [
+            EmbeddingStrategy::SentenceTransformer,
+            EmbeddingStrategy::CodeEmbedding,
+            EmbeddingStrategy::TechnicalEmbedding,
+            EmbeddingStrategy::HybridEmbedding,
+            EmbeddingStrategy::StructuredEmbedding,
+        ] {
+            assert_eq!(EmbeddingStrategy::from_name(strategy.as_str()), Some(strategy));
+        }
+        assert_eq!(EmbeddingStrategy::from_name("unknown"), None);
+    }
 }
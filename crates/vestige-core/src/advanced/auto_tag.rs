@@ -0,0 +1,243 @@
+//! # Tag Suggestion
+//!
+//! Suggests tags for untagged content, so memories arriving from MCP
+//! without tags can still be filtered later.
+//!
+//! ## Signals
+//!
+//! - **Keywords**: terms weighted by TF-IDF against the corpus. Terms that are
+//!   already in use as tags are trusted more than new vocabulary
+//! - **Neighbors**: the tags of the most similar existing memories, weighted by
+//!   similarity ("a Rust async question looks like the memories tagged `async`")
+//!
+//! When both signals suggest a tag their confidences are combined as
+//! independent evidence (noisy-or).
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Confidence at which `IngestInput::auto_tag` applies a suggestion
+pub const AUTO_TAG_THRESHOLD: f64 = 0.6;
+
+/// Most suggestions returned
+pub const MAX_TAG_SUGGESTIONS: usize = 8;
+
+/// Confidence of the best keyword when it is already used as a tag
+const KNOWN_TAG_KEYWORD_CONFIDENCE: f64 = 0.8;
+
+/// Confidence of the best keyword that is new vocabulary (below the
+/// auto-tag threshold, so new tags are only ever suggested)
+const NEW_KEYWORD_CONFIDENCE: f64 = 0.4;
+
+/// Words never suggested as tags
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "but", "for", "with", "from", "into", "onto", "over", "was", "are", "were",
+    "been", "being", "have", "has", "had", "does", "did", "will", "would", "could", "should",
+    "may", "might", "must", "shall", "can", "this", "that", "these", "those", "its", "they",
+    "them", "their", "our", "you", "your", "his", "her", "what", "which", "who", "whom", "when",
+    "where", "why", "how", "not", "all", "any", "each", "some", "such", "than", "then", "too",
+    "very", "just", "also", "only", "own", "same", "need", "needs", "use", "used", "uses",
+    "using", "get", "gets", "got", "make", "makes", "made", "about", "after", "before", "there",
+    "here", "while", "because", "more", "most", "other", "one", "two", "out", "via",
+];
+
+/// Where a suggested tag came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TagSource {
+    Keyword,
+    Neighbors,
+    /// Suggested by both keywords and neighbors
+    Both,
+}
+
+/// A suggested tag with its confidence (0-1)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagSuggestion {
+    pub tag: String,
+    pub confidence: f64,
+    pub source: TagSource,
+}
+
+/// Candidate keywords in `content` with their term frequency, most frequent first
+pub fn extract_terms(content: &str) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for word in content.split_whitespace() {
+        let term = word
+            .trim_matches(|c: char| !c.is_alphanumeric() && c != '-' && c != '_')
+            .to_lowercase();
+        let keep = term.chars().count() >= 3
+            && term.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+            && term.chars().any(|c| c.is_alphabetic())
+            && !STOP_WORDS.contains(&term.as_str());
+        if keep {
+            *counts.entry(term).or_default() += 1;
+        }
+    }
+    let mut terms: Vec<(String, usize)> = counts.into_iter().collect();
+    terms.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    terms
+}
+
+/// Keyword suggestions from TF-IDF weights
+///
+/// `doc_freq` is the number of the `corpus_size` memories containing each
+/// term. `known_tags` are the tags already in use (lowercase).
+pub fn keyword_suggestions(
+    terms: &[(String, usize)],
+    doc_freq: &HashMap<String, usize>,
+    corpus_size: usize,
+    known_tags: &HashSet<String>,
+) -> Vec<TagSuggestion> {
+    let weighted: Vec<(&str, f64)> = terms
+        .iter()
+        .map(|(term, tf)| {
+            let df = doc_freq.get(term).copied().unwrap_or(0);
+            let idf = ((corpus_size as f64 + 1.0) / (df as f64 + 1.0)).ln() + 1.0;
+            (term.as_str(), *tf as f64 * idf)
+        })
+        .collect();
+    let best = weighted.iter().map(|(_, w)| *w).fold(0.0_f64, f64::max);
+    if best <= 0.0 {
+        return vec![];
+    }
+
+    let mut suggestions: Vec<TagSuggestion> = weighted
+        .into_iter()
+        .map(|(term, weight)| {
+            let scale = if known_tags.contains(term) {
+                KNOWN_TAG_KEYWORD_CONFIDENCE
+            } else {
+                NEW_KEYWORD_CONFIDENCE
+            };
+            TagSuggestion {
+                tag: term.to_string(),
+                confidence: scale * weight / best,
+                source: TagSource::Keyword,
+            }
+        })
+        .collect();
+    sort_suggestions(&mut suggestions);
+    suggestions
+}
+
+/// Neighbor suggestions: each tag's share of the neighbors' total similarity
+///
+/// `neighbors` pairs each similar memory's similarity (0-1) with its tags.
+/// Prefixed tags (`intent:...`, `codebase:...`) describe how a memory was
+/// made rather than its topic, so they are not passed on.
+pub fn neighbor_suggestions(neighbors: &[(f32, Vec<String>)]) -> Vec<TagSuggestion> {
+    let total: f64 = neighbors.iter().map(|(s, _)| s.max(0.0) as f64).sum();
+    if total <= 0.0 {
+        return vec![];
+    }
+
+    let mut weights: HashMap<&str, f64> = HashMap::new();
+    for (similarity, tags) in neighbors {
+        let unique: HashSet<&str> = tags
+            .iter()
+            .map(String::as_str)
+            .filter(|tag| !tag.contains(':'))
+            .collect();
+        for tag in unique {
+            *weights.entry(tag).or_default() += similarity.max(0.0) as f64;
+        }
+    }
+
+    let mut suggestions: Vec<TagSuggestion> = weights
+        .into_iter()
+        .map(|(tag, weight)| TagSuggestion {
+            tag: tag.to_string(),
+            confidence: weight / total,
+            source: TagSource::Neighbors,
+        })
+        .collect();
+    sort_suggestions(&mut suggestions);
+    suggestions
+}
+
+/// Combine keyword and neighbor suggestions, best first
+pub fn merge_suggestions(
+    keyword: Vec<TagSuggestion>,
+    neighbors: Vec<TagSuggestion>,
+) -> Vec<TagSuggestion> {
+    let mut merged: Vec<TagSuggestion> = neighbors;
+    for suggestion in keyword {
+        match merged.iter_mut().find(|m| m.tag.eq_ignore_ascii_case(&suggestion.tag)) {
+            Some(existing) => {
+                existing.confidence =
+                    1.0 - (1.0 - existing.confidence) * (1.0 - suggestion.confidence);
+                existing.source = TagSource::Both;
+            }
+            None => merged.push(suggestion),
+        }
+    }
+    sort_suggestions(&mut merged);
+    merged.truncate(MAX_TAG_SUGGESTIONS);
+    merged
+}
+
+/// Add suggestions at or above `AUTO_TAG_THRESHOLD` to `tags`, returning the ones added
+pub fn apply_suggestions(tags: &mut Vec<String>, suggestions: &[TagSuggestion]) -> Vec<String> {
+    let mut applied = Vec::new();
+    for suggestion in suggestions {
+        if suggestion.confidence >= AUTO_TAG_THRESHOLD
+            && !tags.iter().any(|t| t.eq_ignore_ascii_case(&suggestion.tag))
+        {
+            tags.push(suggestion.tag.clone());
+            applied.push(suggestion.tag.clone());
+        }
+    }
+    applied
+}
+
+fn sort_suggestions(suggestions: &mut [TagSuggestion]) {
+    suggestions.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then_with(|| a.tag.cmp(&b.tag))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_terms_skips_stop_words_and_numbers() {
+        let terms = extract_terms("The async runtime polls async futures, 42 times.");
+        assert_eq!(terms[0], ("async".to_string(), 2));
+        assert!(terms.iter().all(|(t, _)| t != "the" && t != "42"));
+    }
+
+    #[test]
+    fn test_async_memory_inherits_neighbor_tag() {
+        let neighbors = vec![
+            (0.9, vec!["rust".to_string(), "async".to_string()]),
+            (0.8, vec!["async".to_string(), "tokio".to_string()]),
+            (0.3, vec!["python".to_string()]),
+        ];
+        let terms = extract_terms("How do Rust async tasks get cancelled when dropped?");
+        let known: HashSet<String> =
+            ["rust", "async", "tokio", "python"].iter().map(|s| s.to_string()).collect();
+        let keyword = keyword_suggestions(&terms, &HashMap::new(), 10, &known);
+
+        let suggestions = merge_suggestions(keyword, neighbor_suggestions(&neighbors));
+        let async_tag = suggestions.iter().find(|s| s.tag == "async").unwrap();
+        assert_eq!(async_tag.source, TagSource::Both);
+        assert!(async_tag.confidence > AUTO_TAG_THRESHOLD);
+
+        let mut tags = vec!["Rust".to_string()];
+        let applied = apply_suggestions(&mut tags, &suggestions);
+        assert!(applied.contains(&"async".to_string()));
+        assert!(!applied.iter().any(|t| t == "rust" || t == "python"));
+    }
+
+    #[test]
+    fn test_new_keywords_are_only_suggested() {
+        let terms = extract_terms("Kubernetes ingress annotations");
+        let suggestions = keyword_suggestions(&terms, &HashMap::new(), 5, &HashSet::new());
+        assert!(!suggestions.is_empty());
+        assert!(suggestions.iter().all(|s| s.confidence < AUTO_TAG_THRESHOLD));
+    }
+}
@@ -20,15 +20,17 @@
 //!
 //! ## Example
 //!
-//! ```rust,ignore
-//! let builder = MemoryChainBuilder::new();
+//! Stored memories are chained through `Storage::build_chain`, which loads
+//! persisted connections (plus close vector neighbors) into a builder and
+//! returns `None` when no path exists within `max_len` connections:
 //!
+//! ```rust,ignore
 //! // Build a reasoning chain from "database" to "performance"
-//! let chain = builder.build_chain("database", "performance");
-//!
-//! // Shows: database -> indexes -> query optimization -> performance
-//! for step in chain.steps {
-//!     println!("{}: {} -> {}", step.reasoning, step.memory, step.connection_type);
+//! if let Some(chain) = storage.build_chain(&database_id, &performance_id, 4)? {
+//!     // Shows: database -> indexes -> query optimization -> performance
+//!     for step in &chain.steps {
+//!         println!("{}: {} ({:?})", step.reasoning, step.memory_preview, step.connection_type);
+//!     }
 //! }
 //! ```
 
@@ -94,6 +96,19 @@ impl ConnectionType {
         }
     }
 
+    /// Map a persisted `memory_connections.link_type` to a connection type
+    pub fn from_link_type(link_type: &str) -> Self {
+        match link_type {
+            "semantic" => Self::SemanticSimilarity,
+            "temporal" | "sequential" => Self::TemporalProximity,
+            "causal" => Self::Causal,
+            "part_of" => Self::PartOf,
+            "shared_concepts" | "pattern" => Self::SharedTopic,
+            "cross_reference" => Self::Elaborates,
+            other => Self::Custom(other.to_string()),
+        }
+    }
+
     /// Get default strength for this connection type
     pub fn default_strength(&self) -> f64 {
         match self {
@@ -236,6 +251,8 @@ pub struct MemoryChainBuilder {
     graph: HashMap<String, MemoryNode>,
     /// Reverse index: tag -> memory IDs
     tag_index: HashMap<String, Vec<String>>,
+    /// Maximum hops explored when searching for paths
+    max_depth: usize,
 }
 
 impl MemoryChainBuilder {
@@ -244,9 +261,16 @@ impl MemoryChainBuilder {
         Self {
             graph: HashMap::new(),
             tag_index: HashMap::new(),
+            max_depth: MAX_CHAIN_DEPTH,
         }
     }
 
+    /// Limit how many hops a chain may take (clamped to 1..=MAX_CHAIN_DEPTH)
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth.clamp(1, MAX_CHAIN_DEPTH);
+        self
+    }
+
     /// Load a memory node into the graph
     pub fn add_memory(&mut self, node: MemoryNode) {
         // Update tag index
@@ -401,7 +425,7 @@ impl MemoryChainBuilder {
             }
 
             // Don't revisit or go too deep
-            if state.depth >= MAX_CHAIN_DEPTH {
+            if state.depth >= self.max_depth {
                 continue;
             }
 
@@ -505,22 +529,17 @@ impl MemoryChainBuilder {
                 .map(|n| n.content_preview.clone())
                 .unwrap_or_default();
 
+            // The hop into this step is the previous step's outgoing connection
             let reasoning = if i == 0 {
                 format!("Starting from '{}'", preview)
             } else {
                 format!(
                     "'{}' {} '{}'",
                     self.graph
-                        .get(
-                            &path
-                                .memories
-                                .get(i.saturating_sub(1))
-                                .cloned()
-                                .unwrap_or_default()
-                        )
+                        .get(&path.memories[i - 1])
                         .map(|n| n.content_preview.as_str())
                         .unwrap_or(""),
-                    conn.connection_type.description(),
+                    path.connections[i - 1].connection_type.description(),
                     preview
                 )
             };
@@ -684,4 +703,24 @@ mod tests {
             bridges.contains(&"indexes".to_string()) || bridges.contains(&"query-opt".to_string())
         );
     }
+
+    #[test]
+    fn test_max_depth_limits_chain() {
+        // "perf" is two hops away via the shared "performance" tag
+        let builder = build_test_graph().with_max_depth(1);
+        assert!(builder.build_chain("database", "perf").is_none());
+
+        let chain = build_test_graph().with_max_depth(3).build_chain("database", "perf").unwrap();
+        assert_eq!(chain.path_ids(), vec!["database", "indexes", "query-opt", "perf"]);
+        assert!(chain.steps[1].reasoning.contains("is part of"));
+    }
+
+    #[test]
+    fn test_from_link_type() {
+        assert_eq!(ConnectionType::from_link_type("causal"), ConnectionType::Causal);
+        assert_eq!(
+            ConnectionType::from_link_type("user_defined"),
+            ConnectionType::Custom("user_defined".to_string())
+        );
+    }
 }
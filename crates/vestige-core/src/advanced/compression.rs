@@ -274,7 +274,7 @@ impl MemoryCompressor {
         // Update stats
         self.stats.memories_compressed += memories.len();
         self.stats.compressions_created += 1;
-        self.stats.bytes_saved += original_size - compressed.compressed_size;
+        self.stats.bytes_saved += original_size.saturating_sub(compressed.compressed_size);
         self.stats.operations += 1;
         self.update_average_stats(&compressed);
 
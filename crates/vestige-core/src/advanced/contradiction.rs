@@ -0,0 +1,217 @@
+//! # Contradiction Detection
+//!
+//! Flags probable contradictions between a new memory and a similar
+//! existing one, so "we decided to use Postgres" does not sit silently
+//! next to "we decided to use SQLite".
+//!
+//! ## Heuristics
+//!
+//! - **Negation**: one statement negates the other ("X is enabled" / "X is not enabled")
+//! - **Antonym**: the statements differ by an opposing word ("enabled" / "disabled")
+//! - **Value change**: the same key takes a different value ("timeout is 30s" /
+//!   "timeout is 5s"), or the statements differ only in a short span
+//!   ("use Postgres" / "use SQLite")
+//!
+//! The heuristics only make sense for content that is already known to be
+//! about the same thing, so callers should apply them to high-similarity
+//! candidates. A [`ContradictionScorer`] (e.g. an NLI cross-encoder) can
+//! replace them when available.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Words that negate a statement
+const NEGATIONS: &[&str] = &[
+    "not", "no", "never", "don't", "doesn't", "isn't", "aren't", "shouldn't", "can't", "cannot",
+    "won't", "wasn't", "didn't",
+];
+
+/// Opposing word pairs
+const ANTONYMS: &[(&str, &str)] = &[
+    ("enabled", "disabled"),
+    ("enable", "disable"),
+    ("true", "false"),
+    ("on", "off"),
+    ("allow", "deny"),
+    ("allowed", "denied"),
+    ("always", "never"),
+    ("use", "avoid"),
+    ("required", "optional"),
+    ("increase", "decrease"),
+    ("sync", "async"),
+    ("synchronous", "asynchronous"),
+    ("public", "private"),
+    ("success", "failure"),
+    ("works", "fails"),
+];
+
+/// Minimum overlap of the remaining words for negation/antonym checks
+const MIN_OVERLAP: f64 = 0.5;
+
+/// Longest differing span treated as a substituted value
+const MAX_SUBSTITUTION_TOKENS: usize = 3;
+
+/// Kind of contradiction found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContradictionKind {
+    Negation,
+    Antonym,
+    ValueChange,
+    /// Reported by a [`ContradictionScorer`]
+    Model,
+}
+
+/// A probable contradiction between two statements
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Contradiction {
+    pub kind: ContradictionKind,
+    /// What differs, e.g. "30s -> 5s"
+    pub detail: String,
+}
+
+/// Hook for a model-based contradiction check (e.g. an NLI cross-encoder)
+pub trait ContradictionScorer: Send + Sync {
+    /// Probability (0-1) that `new` contradicts `old`, or None to fall back
+    /// to the heuristics
+    fn contradiction_probability(&self, new: &str, old: &str) -> Option<f32>;
+}
+
+/// Check whether `new` probably contradicts `old` using word-level heuristics
+pub fn detect_contradiction(new: &str, old: &str) -> Option<Contradiction> {
+    let new = tokens(new);
+    let old = tokens(old);
+    if new.is_empty() || old.is_empty() || new == old {
+        return None;
+    }
+    negation(&new, &old)
+        .or_else(|| antonym(&new, &old))
+        .or_else(|| value_change(&new, &old))
+}
+
+fn tokens(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|w| {
+            w.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'')
+                .to_lowercase()
+        })
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+fn is_value(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Jaccard overlap of the two token sets, ignoring `skip`
+fn overlap(a: &[String], b: &[String], skip: &[&str]) -> f64 {
+    let set = |tokens: &[String]| -> HashSet<String> {
+        tokens.iter().filter(|t| !skip.contains(&t.as_str())).cloned().collect()
+    };
+    let (a, b) = (set(a), set(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+fn negation(new: &[String], old: &[String]) -> Option<Contradiction> {
+    let negated = |tokens: &[String]| tokens.iter().any(|t| NEGATIONS.contains(&t.as_str()));
+    let (new_negated, old_negated) = (negated(new), negated(old));
+    (new_negated != old_negated && overlap(new, old, NEGATIONS) >= MIN_OVERLAP).then(|| Contradiction {
+        kind: ContradictionKind::Negation,
+        detail: if new_negated { "negates existing statement" } else { "affirms negated statement" }
+            .to_string(),
+    })
+}
+
+fn antonym(new: &[String], old: &[String]) -> Option<Contradiction> {
+    let has = |tokens: &[String], word: &str| tokens.iter().any(|t| t == word);
+    ANTONYMS.iter().find_map(|&(x, y)| {
+        let flipped = [(x, y), (y, x)].into_iter().find(|&(n, o)| {
+            has(new, n) && has(old, o) && !has(new, o) && !has(old, n)
+        })?;
+        (overlap(new, old, &[x, y]) >= MIN_OVERLAP).then(|| Contradiction {
+            kind: ContradictionKind::Antonym,
+            detail: format!("{} -> {}", flipped.1, flipped.0),
+        })
+    })
+}
+
+fn value_change(new: &[String], old: &[String]) -> Option<Contradiction> {
+    // The same key (two preceding words) followed by a different value
+    let keyed = |tokens: &[String]| -> Vec<(Vec<String>, String)> {
+        (1..tokens.len())
+            .filter(|&i| is_value(&tokens[i]))
+            .map(|i| (tokens[i.saturating_sub(2)..i].to_vec(), tokens[i].clone()))
+            .collect()
+    };
+    let old_values = keyed(old);
+    for (key, value) in keyed(new) {
+        if let Some((_, old_value)) = old_values.iter().find(|(k, v)| *k == key && *v != value) {
+            return Some(Contradiction {
+                kind: ContradictionKind::ValueChange,
+                detail: format!("{} {} -> {}", key.join(" "), old_value, value),
+            });
+        }
+    }
+
+    // Identical framing around a short substituted span
+    let prefix = new.iter().zip(old).take_while(|(a, b)| a == b).count();
+    let suffix = new[prefix..]
+        .iter()
+        .rev()
+        .zip(old[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let new_span = &new[prefix..new.len() - suffix];
+    let old_span = &old[prefix..old.len() - suffix];
+    let substituted = prefix >= 2
+        && (1..=MAX_SUBSTITUTION_TOKENS).contains(&new_span.len())
+        && (1..=MAX_SUBSTITUTION_TOKENS).contains(&old_span.len())
+        && new_span.iter().all(|t| !old_span.contains(t));
+    substituted.then(|| Contradiction {
+        kind: ContradictionKind::ValueChange,
+        detail: format!("{} -> {}", old_span.join(" "), new_span.join(" ")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_flip_is_flagged() {
+        let found = detect_contradiction("The request timeout is 5s", "The request timeout is 30s").unwrap();
+        assert_eq!(found.kind, ContradictionKind::ValueChange);
+        assert_eq!(found.detail, "timeout is 30s -> 5s");
+
+        let found = detect_contradiction("We decided to use Postgres", "We decided to use SQLite").unwrap();
+        assert_eq!(found.detail, "sqlite -> postgres");
+    }
+
+    #[test]
+    fn test_negation_and_antonym() {
+        let found = detect_contradiction("Telemetry is not enabled by default", "Telemetry is enabled by default");
+        assert_eq!(found.unwrap().kind, ContradictionKind::Negation);
+
+        let found = detect_contradiction("Telemetry is disabled by default", "Telemetry is enabled by default");
+        assert_eq!(found.unwrap().kind, ContradictionKind::Antonym);
+    }
+
+    #[test]
+    fn test_compatible_statements_pass() {
+        assert!(detect_contradiction("The request timeout is 30s", "The request timeout is 30s.").is_none());
+        assert!(detect_contradiction(
+            "Use async/await for performance",
+            "Use async patterns when needed"
+        )
+        .is_none());
+        assert!(detect_contradiction(
+            "The API runs on port 8080 in staging",
+            "Deploys go out every Tuesday afternoon"
+        )
+        .is_none());
+    }
+}
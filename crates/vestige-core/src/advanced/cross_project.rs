@@ -42,6 +42,12 @@ const MIN_PROJECTS_FOR_UNIVERSAL: usize = 2;
 /// Minimum success rate for pattern recommendations
 const MIN_SUCCESS_RATE: f64 = 0.6;
 
+/// Common words that recur across projects without indicating a shared pattern
+const GENERIC_WORDS: &[&str] = &[
+    "always", "another", "because", "before", "better", "during", "instead", "should",
+    "prefer", "pattern", "patterns", "project", "projects", "rather", "through", "without",
+];
+
 /// A universal pattern found across multiple projects
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UniversalPattern {
@@ -111,6 +117,44 @@ pub enum PatternCategory {
     Custom(String),
 }
 
+impl PatternCategory {
+    /// Short identifier for this category (used in pattern IDs and storage)
+    pub fn as_str(&self) -> &'static str {
+        category_to_string(self)
+    }
+
+    /// Guess a category from memory content and tags
+    ///
+    /// Falls back to `Architecture`, since most project learnings that don't
+    /// match a more specific keyword are about code organization.
+    pub fn infer(content: &str, tags: &[String]) -> Self {
+        let text = format!("{} {}", content, tags.join(" ")).to_lowercase();
+        let has = |words: &[&str]| words.iter().any(|w| text.contains(w));
+
+        if has(&["error", "panic", "exception", "result<"]) {
+            Self::ErrorHandling
+        } else if has(&["async", "await", "thread", "mutex", "concurren"]) {
+            Self::AsyncConcurrency
+        } else if has(&["test", "mock", "fixture", "assert"]) {
+            Self::Testing
+        } else if has(&["security", "auth", "secret", "vulnerab", "sanitiz"]) {
+            Self::Security
+        } else if has(&["perf", "latency", "cache", "optimi", "benchmark"]) {
+            Self::Performance
+        } else if has(&["debug", "bisect", "stack trace", "logging"]) {
+            Self::Debugging
+        } else if has(&["refactor", "rename", "extract"]) {
+            Self::Refactoring
+        } else if has(&["docs", "documentation", "readme", "comment"]) {
+            Self::Documentation
+        } else if has(&["build system", "build script", "pipeline", "lint", "clippy", "tooling"]) {
+            Self::Tooling
+        } else {
+            Self::Architecture
+        }
+    }
+}
+
 /// Conditions that trigger pattern applicability
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PatternTrigger {
@@ -231,6 +275,8 @@ pub struct CrossProjectLearner {
     project_memories: Arc<RwLock<Vec<ProjectMemory>>>,
     /// Pattern application outcomes
     outcomes: Arc<RwLock<Vec<PatternOutcome>>>,
+    /// Memories each pattern was extracted from
+    pattern_memories: Arc<RwLock<HashMap<String, Vec<String>>>>,
 }
 
 /// Outcome of applying a pattern
@@ -249,6 +295,7 @@ impl CrossProjectLearner {
             patterns: Arc::new(RwLock::new(HashMap::new())),
             project_memories: Arc::new(RwLock::new(Vec::new())),
             outcomes: Arc::new(RwLock::new(Vec::new())),
+            pattern_memories: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -377,6 +424,15 @@ impl CrossProjectLearner {
             .unwrap_or_default()
     }
 
+    /// Get the IDs of memories a pattern was learned from
+    pub fn get_supporting_memories(&self, pattern_id: &str) -> Vec<String> {
+        self.pattern_memories
+            .read()
+            .ok()
+            .and_then(|m| m.get(pattern_id).cloned())
+            .unwrap_or_default()
+    }
+
     /// Get patterns by category
     pub fn get_patterns_by_category(&self, category: &PatternCategory) -> Vec<UniversalPattern> {
         self.patterns
@@ -439,6 +495,21 @@ impl CrossProjectLearner {
             }
         }
 
+        // Patterns recurring in other projects are likely to transfer here too
+        let seen_here = context
+            .name
+            .as_ref()
+            .is_some_and(|name| pattern.projects_seen_in.contains(name));
+        if !seen_here && pattern.projects_seen_in.len() >= MIN_PROJECTS_FOR_UNIVERSAL {
+            let projects = pattern.projects_seen_in.len() as f64;
+            match_scores.push(projects / (projects + 1.0));
+            match_reasons.push(format!(
+                "Seen in {} other projects ({})",
+                pattern.projects_seen_in.len(),
+                pattern.projects_seen_in.join(", ")
+            ));
+        }
+
         if match_scores.is_empty() {
             return None;
         }
@@ -446,8 +517,13 @@ impl CrossProjectLearner {
         // Calculate overall confidence
         let avg_confidence = match_scores.iter().sum::<f64>() / match_scores.len() as f64;
 
-        // Boost confidence based on pattern's track record
-        let adjusted_confidence = avg_confidence * pattern.success_rate * pattern.confidence;
+        // Boost confidence based on pattern's track record (once it has one)
+        let track_record = if pattern.application_count > 0 {
+            pattern.success_rate
+        } else {
+            1.0
+        };
+        let adjusted_confidence = avg_confidence * track_record * pattern.confidence;
 
         if adjusted_confidence < 0.3 {
             return None;
@@ -461,7 +537,7 @@ impl CrossProjectLearner {
             match_reason: match_reasons.join("; "),
             applicability_confidence: adjusted_confidence,
             suggestions,
-            supporting_memories: Vec::new(), // Would be filled from storage
+            supporting_memories: self.get_supporting_memories(&pattern.id),
         })
     }
 
@@ -576,6 +652,7 @@ impl CrossProjectLearner {
 
         // Simple pattern: look for common keywords in content
         let mut keyword_projects: HashMap<String, HashSet<&str>> = HashMap::new();
+        let mut keyword_memories: HashMap<String, HashSet<&str>> = HashMap::new();
 
         for (project, project_memories) in &by_project {
             for memory in project_memories {
@@ -583,7 +660,11 @@ impl CrossProjectLearner {
                     let clean = word
                         .trim_matches(|c: char| !c.is_alphanumeric())
                         .to_lowercase();
-                    if clean.len() > 5 {
+                    if clean.len() > 5 && !GENERIC_WORDS.contains(&clean.as_str()) {
+                        keyword_memories
+                            .entry(clean.clone())
+                            .or_default()
+                            .insert(memory.id.as_str());
                         keyword_projects.entry(clean).or_default().insert(project);
                     }
                 }
@@ -596,6 +677,14 @@ impl CrossProjectLearner {
                 // Create a potential pattern (simplified)
                 let pattern_id = format!("auto-{}-{}", category_to_string(&category), keyword);
 
+                if let Some(ids) = keyword_memories.get(&keyword)
+                    && let Ok(mut memories) = self.pattern_memories.write()
+                {
+                    let mut ids: Vec<String> = ids.iter().map(|s| s.to_string()).collect();
+                    ids.sort();
+                    memories.insert(pattern_id.clone(), ids);
+                }
+
                 if let Ok(mut patterns) = self.patterns.write() {
                     if !patterns.contains_key(&pattern_id) {
                         patterns.insert(
@@ -775,4 +864,20 @@ mod tests {
         assert_eq!(universal.len(), 1);
         assert_eq!(universal[0].id, "universal");
     }
+
+    #[test]
+    fn test_infer_category() {
+        assert_eq!(
+            PatternCategory::infer("Wrap IO failures in a custom error enum", &[]),
+            PatternCategory::ErrorHandling
+        );
+        assert_eq!(
+            PatternCategory::infer("Spawn blocking work off the tokio runtime", &["async".to_string()]),
+            PatternCategory::AsyncConcurrency
+        );
+        assert_eq!(
+            PatternCategory::infer("Prefer builder pattern for config structs", &[]),
+            PatternCategory::Architecture
+        );
+    }
 }
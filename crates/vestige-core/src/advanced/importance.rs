@@ -183,30 +183,46 @@ impl ImportanceTracker {
 
     /// Update importance when a memory is retrieved
     pub fn on_retrieved(&self, memory_id: &str, was_helpful: bool) {
-        let now = Utc::now();
+        self.record_event(UsageEvent {
+            memory_id: memory_id.to_string(),
+            was_helpful,
+            context: None,
+            timestamp: Utc::now(),
+        });
+    }
+
+    /// Update importance with additional context
+    pub fn on_retrieved_with_context(&self, memory_id: &str, was_helpful: bool, context: &str) {
+        self.record_event(UsageEvent {
+            memory_id: memory_id.to_string(),
+            was_helpful,
+            context: Some(context.to_string()),
+            timestamp: Utc::now(),
+        });
+    }
+
+    /// Apply a usage event as of its own timestamp (e.g. replaying persisted events)
+    pub fn record_event(&self, event: UsageEvent) {
+        let memory_id = event.memory_id.clone();
+        let (was_helpful, timestamp) = (event.was_helpful, event.timestamp);
 
         // Record the event
         if let Ok(mut events) = self.recent_events.write() {
-            events.push(UsageEvent {
-                memory_id: memory_id.to_string(),
-                was_helpful,
-                context: None,
-                timestamp: now,
-            });
+            events.push(event);
 
             // Keep only recent events (last 30 days)
-            let cutoff = now - Duration::days(30);
+            let cutoff = Utc::now() - Duration::days(30);
             events.retain(|e| e.timestamp > cutoff);
         }
 
         // Update importance score
         if let Ok(mut scores) = self.scores.write() {
             let score = scores
-                .entry(memory_id.to_string())
-                .or_insert_with(|| ImportanceScore::new(memory_id));
+                .entry(memory_id.clone())
+                .or_insert_with(|| ImportanceScore::new(&memory_id));
 
             score.retrieval_count += 1;
-            score.last_accessed = Some(now);
+            score.last_accessed = Some(score.last_accessed.map_or(timestamp, |t| t.max(timestamp)));
 
             if was_helpful {
                 score.helpful_count += 1;
@@ -225,37 +241,35 @@ impl ImportanceTracker {
         }
     }
 
-    /// Update importance with additional context
-    pub fn on_retrieved_with_context(&self, memory_id: &str, was_helpful: bool, context: &str) {
-        self.on_retrieved(memory_id, was_helpful);
-
-        // Store context with event
-        if let Ok(mut events) = self.recent_events.write() {
-            if let Some(event) = events.last_mut() {
-                if event.memory_id == memory_id {
-                    event.context = Some(context.to_string());
-                }
-            }
+    /// Restore a previously computed score (e.g. loaded from storage)
+    pub fn restore_score(&self, score: ImportanceScore) {
+        if let Ok(mut scores) = self.scores.write() {
+            scores.insert(score.memory_id.clone(), score);
         }
     }
 
     /// Apply importance decay to all memories
+    ///
+    /// Usage decay is incremental: only the inactive time since the score was
+    /// last calculated is applied, so running this repeatedly (e.g. on every
+    /// consolidation) doesn't compound the same idle days.
     pub fn apply_importance_decay(&self) {
         let now = Utc::now();
+        let days_between =
+            |from: DateTime<Utc>, to: DateTime<Utc>| (to - from).num_seconds().max(0) as f64 / 86_400.0;
 
         if let Ok(mut scores) = self.scores.write() {
             for score in scores.values_mut() {
-                // Calculate days since last access
-                let days_inactive = score
-                    .last_accessed
-                    .map(|last| (now - last).num_days() as u32)
-                    .unwrap_or(self.config.grace_period_days + 1);
-
-                // Apply decay if past grace period
-                if days_inactive > self.config.grace_period_days {
-                    let decay_days = days_inactive - self.config.grace_period_days;
-                    let decay_factor = self.config.decay_rate.powi(decay_days as i32);
-
+                // Decay starts once the grace period after the last access is over
+                let decay_days = match score.last_accessed {
+                    Some(last) => {
+                        let decay_start = last + Duration::days(self.config.grace_period_days as i64);
+                        days_between(decay_start.max(score.calculated_at), now)
+                    }
+                    None => days_between(score.calculated_at, now),
+                };
+                if decay_days > 0.0 {
+                    let decay_factor = self.config.decay_rate.powf(decay_days);
                     score.usage_importance =
                         (score.usage_importance * decay_factor).max(self.config.min_importance);
                 }
@@ -263,7 +277,7 @@ impl ImportanceTracker {
                 // Apply recency decay
                 let recency_days = score
                     .last_accessed
-                    .map(|last| (now - last).num_days() as f64)
+                    .map(|last| days_between(last, now))
                     .unwrap_or(self.config.recency_half_life_days * 2.0);
 
                 score.recency_importance =
@@ -465,6 +479,41 @@ mod tests {
         assert!(score.usage_importance < 0.5); // Should be penalized
     }
 
+    #[test]
+    fn test_decay_is_incremental_across_runs() {
+        let tracker = ImportanceTracker::new();
+        let mut score = ImportanceScore::new("idle");
+        score.usage_importance = 0.8;
+        score.last_accessed = Some(Utc::now() - Duration::days(37));
+        score.calculated_at = Utc::now() - Duration::days(37);
+        tracker.restore_score(score);
+
+        // 30 days past the 7-day grace period
+        tracker.apply_importance_decay();
+        let once = tracker.get_importance("idle").unwrap();
+        assert!((once.usage_importance - 0.8 * 0.95_f64.powi(30)).abs() < 1e-3);
+
+        // Running again immediately must not re-apply the same idle days
+        tracker.apply_importance_decay();
+        let twice = tracker.get_importance("idle").unwrap();
+        assert!((twice.usage_importance - once.usage_importance).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_record_event_uses_event_timestamp() {
+        let tracker = ImportanceTracker::new();
+        let at = Utc::now() - Duration::days(3);
+        tracker.record_event(UsageEvent {
+            memory_id: "mem-1".to_string(),
+            was_helpful: true,
+            context: Some("promote".to_string()),
+            timestamp: at,
+        });
+        let score = tracker.get_importance("mem-1").unwrap();
+        assert_eq!(score.last_accessed, Some(at));
+        assert_eq!(score.helpful_count, 1);
+    }
+
     #[test]
     fn test_helpfulness_ratio() {
         let mut score = ImportanceScore::new("test");
@@ -44,7 +44,7 @@ use std::sync::{Arc, RwLock};
 const MAX_ACTION_HISTORY: usize = 100;
 
 /// Time window for intent detection (minutes)
-const INTENT_WINDOW_MINUTES: i64 = 30;
+pub(crate) const INTENT_WINDOW_MINUTES: i64 = 30;
 
 /// Minimum confidence for intent detection
 const MIN_INTENT_CONFIDENCE: f64 = 0.4;
@@ -148,6 +148,38 @@ impl DetectedIntent {
         }
     }
 
+    /// Short snake_case label for this kind of intent
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Debugging { .. } => "debugging",
+            Self::Refactoring { .. } => "refactoring",
+            Self::NewFeature { .. } => "new_feature",
+            Self::Learning { .. } => "learning",
+            Self::Maintenance { .. } => "maintenance",
+            Self::CodeReview { .. } => "code_review",
+            Self::Documentation { .. } => "documentation",
+            Self::Optimization { .. } => "optimization",
+            Self::Integration { .. } => "integration",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    /// Node types whose memories usually serve this intent
+    pub fn preferred_node_types(&self) -> &'static [&'static str] {
+        match self {
+            Self::Debugging { .. } => &["bug_fix", "error"],
+            Self::Learning { .. } => &["concept", "fact"],
+            Self::NewFeature { .. } => &["pattern", "decision", "procedure"],
+            Self::Refactoring { .. } => &["pattern", "decision"],
+            Self::CodeReview { .. } => &["decision", "pattern"],
+            Self::Documentation { .. } => &["concept", "procedure"],
+            Self::Optimization { .. } => &["pattern", "insight"],
+            Self::Maintenance { .. } => &["procedure"],
+            Self::Integration { .. } => &["procedure", "fact"],
+            Self::Unknown => &[],
+        }
+    }
+
     /// Get relevant tags for memory search
     pub fn relevant_tags(&self) -> Vec<String> {
         match self {
@@ -485,6 +517,116 @@ impl IntentDetector {
         }
     }
 
+    /// Classify a single search query without touching the action history
+    ///
+    /// Keyword heuristics: strong signals ("panicking", "how to") score 0.5,
+    /// weak ones ("why", "example") 0.2, and the best intent above
+    /// `MIN_INTENT_CONFIDENCE` wins.
+    pub fn classify_query(query: &str) -> IntentDetectionResult {
+        let lower = query.to_lowercase();
+        let topic = query.trim().trim_end_matches('?').to_string();
+
+        let candidates: Vec<(DetectedIntent, &[&str], &[&str])> = vec![
+            (
+                DetectedIntent::Debugging {
+                    suspected_area: "unknown".to_string(),
+                    symptoms: vec![topic.clone()],
+                },
+                &[
+                    "panic", "crash", "error", "bug", "exception", "fail", "broken",
+                    "segfault", "stack trace", "not working", "hangs", "deadlock",
+                ],
+                &["why", "wrong", "fix", "issue"],
+            ),
+            (
+                DetectedIntent::Learning {
+                    topic: topic.clone(),
+                    level: LearningLevel::Intermediate,
+                },
+                &[
+                    "how to", "how does", "what is", "what are", "explain", "tutorial",
+                    "guide", "understand", "learn",
+                ],
+                &["example", "concept", "difference between", "overview"],
+            ),
+            (
+                DetectedIntent::NewFeature {
+                    feature_description: topic.clone(),
+                    related_components: vec![],
+                },
+                &["implement", "add support", "new feature", "build a", "create a"],
+                &["add", "support for", "integrate"],
+            ),
+            (
+                DetectedIntent::CodeReview {
+                    files: vec![],
+                    depth: ReviewDepth::Standard,
+                },
+                &["review", "audit", "code quality"],
+                &["pull request", "look over", "feedback"],
+            ),
+            (
+                DetectedIntent::Refactoring {
+                    target: topic.clone(),
+                    goal: "improve structure".to_string(),
+                },
+                &["refactor", "rename", "extract", "clean up", "restructure"],
+                &["simplify", "reorganize"],
+            ),
+            (
+                DetectedIntent::Optimization {
+                    target: topic.clone(),
+                    optimization_type: OptimizationType::Speed,
+                },
+                &["optimize", "performance", "too slow", "latency", "speed up"],
+                &["slow", "faster", "benchmark"],
+            ),
+        ];
+
+        let mut scores: Vec<(DetectedIntent, f64, Vec<String>)> = candidates
+            .into_iter()
+            .map(|(intent, strong, weak)| {
+                let mut evidence = Vec::new();
+                let mut score: f64 = 0.0;
+                for kw in strong.iter().filter(|kw| lower.contains(*kw)) {
+                    score += 0.5;
+                    evidence.push(format!("Query mentions '{}'", kw));
+                }
+                for kw in weak.iter().filter(|kw| lower.contains(*kw)) {
+                    score += 0.2;
+                    evidence.push(format!("Query mentions '{}'", kw));
+                }
+                (intent, score.min(1.0), evidence)
+            })
+            .filter(|(_, score, _)| *score >= MIN_INTENT_CONFIDENCE)
+            .collect();
+
+        scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        if scores.is_empty() {
+            return IntentDetectionResult {
+                primary_intent: DetectedIntent::Unknown,
+                confidence: 0.0,
+                alternatives: vec![],
+                evidence: vec![],
+                detected_at: Utc::now(),
+            };
+        }
+
+        let (primary_intent, confidence, evidence) = scores.remove(0);
+        IntentDetectionResult {
+            primary_intent,
+            confidence,
+            alternatives: scores
+                .into_iter()
+                .map(|(intent, score, _)| (intent, score))
+                .take(3)
+                .collect(),
+            evidence,
+            detected_at: Utc::now(),
+        }
+    }
+
     /// Get memories relevant to detected intent
     pub fn memories_for_intent(&self, intent: &DetectedIntent) -> IntentMemoryQuery {
         let tags = intent.relevant_tags();
@@ -904,6 +1046,21 @@ mod tests {
         assert!(tags.contains(&"error".to_string()));
     }
 
+    #[test]
+    fn test_classify_query() {
+        let result = IntentDetector::classify_query("why is the scheduler panicking");
+        assert_eq!(result.primary_intent.kind(), "debugging");
+        assert!(result.confidence >= MIN_INTENT_CONFIDENCE);
+        assert!(result.primary_intent.preferred_node_types().contains(&"bug_fix"));
+
+        let result = IntentDetector::classify_query("what is spaced repetition");
+        assert_eq!(result.primary_intent.kind(), "learning");
+
+        let result = IntentDetector::classify_query("vestige");
+        assert!(matches!(result.primary_intent, DetectedIntent::Unknown));
+        assert_eq!(result.confidence, 0.0);
+    }
+
     #[test]
     fn test_action_creation() {
         let action = UserAction::file_opened("/src/main.rs").with_metadata("project", "vestige");
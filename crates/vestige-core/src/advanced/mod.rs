@@ -15,22 +15,32 @@
 //! - **Memory Dreams**: Enhanced consolidation that creates NEW insights
 //! - **Sleep Consolidation**: Automatic background consolidation during idle periods
 //! - **Reconsolidation**: Memories become modifiable on retrieval (Nader's theory)
+//! - **Contradiction Detection**: Flag new memories that conflict with existing ones
+//! - **Tag Suggestion**: Suggest tags from keywords and similar memories
+//! - **Session Packets**: Summarize the session's working set into topical clusters
 
 pub mod adaptive_embedding;
+pub mod auto_tag;
 pub mod chains;
 pub mod compression;
+pub mod contradiction;
 pub mod cross_project;
 pub mod dreams;
 pub mod importance;
 pub mod intent;
 pub mod prediction_error;
 pub mod reconsolidation;
+pub mod session_packet;
 pub mod speculative;
 
 // Re-exports for convenient access
 pub use adaptive_embedding::{AdaptiveEmbedder, ContentType, EmbeddingStrategy, Language};
+pub use auto_tag::{TagSource, TagSuggestion, AUTO_TAG_THRESHOLD};
 pub use chains::{ChainStep, ConnectionType, MemoryChainBuilder, MemoryPath, ReasoningChain};
 pub use compression::{CompressedMemory, CompressionConfig, CompressionStats, MemoryCompressor};
+pub use contradiction::{
+    detect_contradiction, Contradiction, ContradictionKind, ContradictionScorer,
+};
 pub use cross_project::{
     ApplicableKnowledge, CrossProjectLearner, ProjectContext, UniversalPattern,
 };
@@ -57,12 +67,15 @@ pub use dreams::{
     SynthesizedInsight,
 };
 pub use importance::{ImportanceDecayConfig, ImportanceScore, ImportanceTracker, UsageEvent};
-pub use intent::{ActionType, DetectedIntent, IntentDetector, MaintenanceType, UserAction};
+pub use intent::{
+    ActionType, DetectedIntent, IntentDetectionResult, IntentDetector, MaintenanceType, UserAction,
+};
 pub use reconsolidation::{
     AccessContext, AccessTrigger, AppliedModification, ChangeSummary, LabileState, MemorySnapshot,
     Modification, ReconsolidatedMemory, ReconsolidationManager, ReconsolidationStats,
     RelationshipType, RetrievalRecord,
 };
+pub use session_packet::{SessionItem, SessionOverview, SessionPacket};
 pub use prediction_error::{
     CandidateMemory, CreateReason, EvaluationIntent, GateDecision, GateStats, MergeStrategy,
     PredictionErrorConfig, PredictionErrorGate, SimilarityResult, SupersedeReason, UpdateType,
@@ -524,7 +524,7 @@ impl PredictionErrorGate {
     /// Detect if two pieces of content appear contradictory
     ///
     /// Uses simple heuristics; could be enhanced with NLI model
-    fn detect_contradiction(&self, new_content: &str, old_content: &str) -> bool {
+    pub fn detect_contradiction(&self, new_content: &str, old_content: &str) -> bool {
         let new_lower = new_content.to_lowercase();
         let old_lower = old_content.to_lowercase();
 
@@ -480,6 +480,28 @@ impl ReconsolidationManager {
         self.stats.total_marked_labile += 1;
     }
 
+    /// Mark a memory as labile as of a given access time
+    ///
+    /// Used when the retrieval happened earlier than now (e.g. replayed
+    /// access events); the window is measured from `accessed_at`.
+    pub fn mark_labile_at(
+        &mut self,
+        memory_id: &str,
+        snapshot: MemorySnapshot,
+        context: Option<AccessContext>,
+        accessed_at: DateTime<Utc>,
+    ) {
+        if !self.enabled {
+            return;
+        }
+
+        let mut state = LabileState::new(memory_id.to_string(), snapshot);
+        state.accessed_at = accessed_at;
+        state.access_context = context;
+        self.labile_memories.insert(memory_id.to_string(), state);
+        self.stats.total_marked_labile += 1;
+    }
+
     /// Check if a memory is currently labile (modifiable)
     pub fn is_labile(&self, memory_id: &str) -> bool {
         self.labile_memories
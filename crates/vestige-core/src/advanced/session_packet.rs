@@ -0,0 +1,267 @@
+//! # Session Packets
+//!
+//! Summarizes a session's working set (recently accessed memories, memories
+//! behind active intentions, the current project's memories) into compact
+//! topical packets, so long sessions fit in a prompt.
+//!
+//! ## How it works
+//!
+//! 1. **Clustering**: memories are visited by importance and join the first
+//!    cluster whose leader is similar enough (embedding cosine, or word
+//!    overlap when either side has no embedding), otherwise they lead a new one
+//! 2. **Packets**: each cluster becomes a representative title, previews of
+//!    its two most important memories, and every member ID for drill-down
+//! 3. **Budgeting**: packets are dropped lowest-importance first until the
+//!    rendered overview fits the token budget (approximated as chars / 4)
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use super::prediction_error::cosine_similarity;
+
+/// Embedding cosine at which a memory joins a cluster
+pub const EMBEDDING_CLUSTER_THRESHOLD: f32 = 0.75;
+
+/// Word overlap (Jaccard) at which a memory without an embedding joins a cluster
+pub const WORD_CLUSTER_THRESHOLD: f32 = 0.3;
+
+/// Previews shown per packet
+const PREVIEWS_PER_PACKET: usize = 2;
+
+/// Longest title, in characters
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Longest preview, in characters
+const MAX_PREVIEW_CHARS: usize = 150;
+
+/// A memory in the session's working set
+#[derive(Debug, Clone)]
+pub struct SessionItem {
+    pub id: String,
+    pub content: String,
+    /// Importance (0-1) used to order members and rank clusters
+    pub importance: f64,
+    pub embedding: Option<Vec<f32>>,
+}
+
+/// One topical cluster of the working set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionPacket {
+    /// Opening of the cluster's most important memory
+    pub title: String,
+    /// Previews of the most important memories
+    pub previews: Vec<String>,
+    /// Every member, most important first
+    pub memory_ids: Vec<String>,
+    /// Summed importance of the members
+    pub importance: f64,
+}
+
+impl SessionPacket {
+    /// Render as a markdown list item with indented previews
+    pub fn to_prompt(&self) -> String {
+        let mut lines = vec![format!("- **{}** ({} memories)", self.title, self.memory_ids.len())];
+        lines.extend(self.previews.iter().map(|p| format!("  - {}", p)));
+        lines.join("\n")
+    }
+
+    /// Approximate size of `to_prompt()` plus its separating newline
+    pub fn estimated_tokens(&self) -> usize {
+        (self.to_prompt().chars().count() + 1).div_ceil(4)
+    }
+}
+
+/// Packets kept within a token budget
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionOverview {
+    /// Kept packets, most important first
+    pub packets: Vec<SessionPacket>,
+    /// Clusters left out to stay within the budget
+    pub dropped_clusters: usize,
+    /// Approximate size of `to_prompt()`
+    pub estimated_tokens: usize,
+    pub budget_tokens: usize,
+}
+
+impl SessionOverview {
+    /// Render every kept packet, one per list item
+    pub fn to_prompt(&self) -> String {
+        self.packets.iter().map(SessionPacket::to_prompt).collect::<Vec<_>>().join("\n")
+    }
+}
+
+/// Group items into clusters of indices, each ordered most important first
+///
+/// Deterministic for a given set of items regardless of their input order:
+/// items are visited by importance (ties by ID).
+pub fn cluster_items(items: &[SessionItem]) -> Vec<Vec<usize>> {
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by(|&a, &b| {
+        items[b]
+            .importance
+            .total_cmp(&items[a].importance)
+            .then_with(|| items[a].id.cmp(&items[b].id))
+    });
+
+    let mut clusters: Vec<Vec<usize>> = Vec::new();
+    for i in order {
+        match clusters.iter_mut().find(|c| similar(&items[c[0]], &items[i])) {
+            Some(cluster) => cluster.push(i),
+            None => clusters.push(vec![i]),
+        }
+    }
+    clusters
+}
+
+/// Cluster the items and keep the most important packets that fit `budget_tokens`
+pub fn build_overview(items: &[SessionItem], budget_tokens: usize) -> SessionOverview {
+    let mut packets: Vec<SessionPacket> = cluster_items(items)
+        .into_iter()
+        .map(|cluster| SessionPacket {
+            title: truncate(first_sentence(&items[cluster[0]].content), MAX_TITLE_CHARS),
+            previews: cluster
+                .iter()
+                .take(PREVIEWS_PER_PACKET)
+                .map(|&i| truncate(items[i].content.trim(), MAX_PREVIEW_CHARS))
+                .collect(),
+            memory_ids: cluster.iter().map(|&i| items[i].id.clone()).collect(),
+            importance: cluster.iter().map(|&i| items[i].importance).sum(),
+        })
+        .collect();
+    packets.sort_by(|a, b| b.importance.total_cmp(&a.importance));
+
+    let mut estimated_tokens: usize = packets.iter().map(SessionPacket::estimated_tokens).sum();
+    let mut dropped_clusters = 0;
+    while estimated_tokens > budget_tokens {
+        let Some(packet) = packets.pop() else { break };
+        estimated_tokens -= packet.estimated_tokens();
+        dropped_clusters += 1;
+    }
+
+    SessionOverview {
+        packets,
+        dropped_clusters,
+        estimated_tokens,
+        budget_tokens,
+    }
+}
+
+fn similar(a: &SessionItem, b: &SessionItem) -> bool {
+    match (&a.embedding, &b.embedding) {
+        (Some(x), Some(y)) if x.len() == y.len() => {
+            cosine_similarity(x, y) >= EMBEDDING_CLUSTER_THRESHOLD
+        }
+        _ => word_overlap(&a.content, &b.content) >= WORD_CLUSTER_THRESHOLD,
+    }
+}
+
+fn word_overlap(a: &str, b: &str) -> f32 {
+    let words = |s: &str| -> HashSet<String> {
+        s.split_whitespace()
+            .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+            .filter(|w| w.chars().count() > 3)
+            .collect()
+    };
+    let (a, b) = (words(a), words(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f32 / union as f32
+}
+
+fn first_sentence(content: &str) -> &str {
+    let content = content.trim();
+    let end = content
+        .find(". ")
+        .map(|i| i + 1)
+        .or_else(|| content.find('\n'))
+        .unwrap_or(content.len());
+    &content[..end]
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", text[..end].trim_end()),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, content: &str, importance: f64) -> SessionItem {
+        SessionItem {
+            id: id.to_string(),
+            content: content.to_string(),
+            importance,
+            embedding: None,
+        }
+    }
+
+    fn corpus() -> Vec<SessionItem> {
+        vec![
+            item("a1", "Postgres connection pool exhausted under load testing", 0.9),
+            item("a2", "Raised Postgres connection pool limit to fix load testing errors", 0.6),
+            item("b1", "Dashboard graph view renders memory nodes with force layout", 0.5),
+            item("b2", "Force layout for dashboard graph view jitters with many memory nodes", 0.4),
+            item("c1", "Lunch order preferences for the team offsite", 0.1),
+        ]
+    }
+
+    #[test]
+    fn test_clusters_are_stable_across_input_order() {
+        let items = corpus();
+        let ids = |items: &[SessionItem]| -> Vec<Vec<String>> {
+            cluster_items(items)
+                .into_iter()
+                .map(|c| c.into_iter().map(|i| items[i].id.clone()).collect())
+                .collect()
+        };
+
+        let expected = vec![
+            vec!["a1".to_string(), "a2".to_string()],
+            vec!["b1".to_string(), "b2".to_string()],
+            vec!["c1".to_string()],
+        ];
+        assert_eq!(ids(&items), expected);
+        let reversed: Vec<SessionItem> = items.into_iter().rev().collect();
+        assert_eq!(ids(&reversed), expected);
+    }
+
+    #[test]
+    fn test_embeddings_take_precedence_over_words() {
+        let mut items = vec![
+            item("x", "alpha beta gamma delta", 0.5),
+            item("y", "completely different wording here", 0.4),
+        ];
+        items[0].embedding = Some(vec![1.0, 0.0]);
+        items[1].embedding = Some(vec![0.9, 0.1]);
+        assert_eq!(cluster_items(&items).len(), 1);
+    }
+
+    #[test]
+    fn test_budget_drops_lowest_importance_clusters_first() {
+        let items = corpus();
+        let full = build_overview(&items, 10_000);
+        assert_eq!(full.packets.len(), 3);
+        assert_eq!(full.dropped_clusters, 0);
+        assert_eq!(full.packets[0].memory_ids, vec!["a1", "a2"]);
+        assert_eq!(full.packets[0].previews.len(), 2);
+        assert!(full.packets[0].title.starts_with("Postgres connection pool"));
+
+        let budget = full.packets[0].estimated_tokens() + full.packets[1].estimated_tokens();
+        let tight = build_overview(&items, budget);
+        assert_eq!(tight.packets.len(), 2);
+        assert_eq!(tight.dropped_clusters, 1);
+        assert!(tight.estimated_tokens <= budget);
+        assert!(tight.packets.iter().all(|p| !p.memory_ids.contains(&"c1".to_string())));
+
+        let none = build_overview(&items, 5);
+        assert!(none.packets.is_empty());
+        assert_eq!(none.estimated_tokens, 0);
+    }
+}
@@ -158,6 +158,7 @@ struct AccessEvent {
     memory_id: String,
     file_context: Option<String>,
     query_context: Option<String>,
+    project: Option<String>,
     timestamp: DateTime<Utc>,
     was_helpful: Option<bool>,
 }
@@ -189,7 +190,7 @@ impl SpeculativeRetriever {
         predictions.extend(self.predict_from_queries(context, now));
 
         // 4. Temporal pattern predictions
-        predictions.extend(self.predict_from_time(now));
+        predictions.extend(self.predict_from_time(context, now));
 
         // Deduplicate and sort by confidence
         predictions = self.deduplicate_predictions(predictions);
@@ -247,16 +248,61 @@ impl SpeculativeRetriever {
         query_context: Option<&str>,
         was_helpful: Option<bool>,
     ) {
-        let event = AccessEvent {
+        self.push_access_event(AccessEvent {
             memory_id: memory_id.to_string(),
             file_context: file_context.map(String::from),
             query_context: query_context.map(String::from),
+            project: None,
             timestamp: Utc::now(),
             was_helpful,
-        };
+        });
+    }
+
+    /// Record a memory access that happened at a given time in a project
+    ///
+    /// Used to feed tool invocations (and replay persisted usage history) so
+    /// time-of-day patterns are learned per project.
+    pub fn record_access_at(
+        &self,
+        memory_id: &str,
+        query_context: Option<&str>,
+        project: Option<&str>,
+        timestamp: DateTime<Utc>,
+    ) {
+        self.push_access_event(AccessEvent {
+            memory_id: memory_id.to_string(),
+            file_context: None,
+            query_context: query_context.map(String::from),
+            project: project.map(String::from),
+            timestamp,
+            was_helpful: None,
+        });
+    }
+
+    /// Queries that previously led to these memories, most frequent first
+    pub fn related_queries(&self, memory_ids: &[String], limit: usize) -> Vec<String> {
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        if let Ok(sequence) = self.access_sequence.read() {
+            for event in sequence.iter() {
+                if let Some(query) = &event.query_context
+                    && memory_ids.contains(&event.memory_id)
+                {
+                    *counts.entry(query.clone()).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut queries: Vec<_> = counts.into_iter().collect();
+        queries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        queries.into_iter().take(limit).map(|(query, _)| query).collect()
+    }
+
+    fn push_access_event(&self, event: AccessEvent) {
+        let memory_id = event.memory_id.clone();
+        let file_context = event.file_context.clone();
 
         if let Ok(mut sequence) = self.access_sequence.write() {
-            sequence.push_back(event.clone());
+            sequence.push_back(event);
 
             // Trim old events
             while sequence.len() > MAX_PATTERN_HISTORY {
@@ -267,9 +313,9 @@ impl SpeculativeRetriever {
         // Update file-memory associations
         if let Some(file) = file_context {
             if let Ok(mut map) = self.file_memory_map.write() {
-                map.entry(file.to_string())
+                map.entry(file)
                     .or_insert_with(Vec::new)
-                    .push(memory_id.to_string());
+                    .push(memory_id);
             }
         }
     }
@@ -409,15 +455,30 @@ impl SpeculativeRetriever {
         predictions
     }
 
-    fn predict_from_time(&self, now: DateTime<Utc>) -> Vec<PredictedMemory> {
+    fn predict_from_time(
+        &self,
+        context: &PredictionContext,
+        now: DateTime<Utc>,
+    ) -> Vec<PredictedMemory> {
         let mut predictions = Vec::new();
         let hour = now.hour();
+        let project = context
+            .project_path
+            .as_ref()
+            .and_then(|p| p.file_name())
+            .map(|name| name.to_string_lossy().to_string());
 
         if let Ok(sequence) = self.access_sequence.read() {
             // Find memories frequently accessed at this time of day
             let mut time_counts: HashMap<String, u32> = HashMap::new();
 
             for event in sequence.iter() {
+                // Events from a different project don't count toward this one's routine
+                if let (Some(current), Some(event_project)) = (&project, &event.project)
+                    && current != event_project
+                {
+                    continue;
+                }
                 if (event.timestamp.hour() as i32 - hour as i32).abs() <= 1 {
                     *time_counts.entry(event.memory_id.clone()).or_insert(0) += 1;
                 }
@@ -601,4 +662,32 @@ mod tests {
         assert!(patterns.contains_key("mem-1"));
         assert!(patterns.contains_key("mem-2"));
     }
+
+    #[test]
+    fn test_daily_pattern_predicted_per_project() {
+        let retriever = SpeculativeRetriever::new();
+        let now = Utc::now();
+        for days_ago in 1..=3 {
+            let at = now - chrono::Duration::days(days_ago);
+            retriever.record_access_at("standup-notes", Some("standup notes"), Some("vestige"), at);
+            retriever.record_access_at("other-notes", Some("release plan"), Some("other"), at);
+        }
+
+        let context = PredictionContext {
+            timestamp: Some(now),
+            project_path: Some(PathBuf::from("/work/vestige")),
+            ..Default::default()
+        };
+        let ids: Vec<_> = retriever
+            .predict_needed(&context)
+            .into_iter()
+            .map(|p| p.memory_id)
+            .collect();
+        assert_eq!(ids, vec!["standup-notes"]);
+
+        assert_eq!(
+            retriever.related_queries(&["standup-notes".to_string()], 5),
+            vec!["standup notes"]
+        );
+    }
 }
@@ -0,0 +1,83 @@
+//! Time source for time-dependent logic
+//!
+//! Decay, review scheduling, memory states and consolidation read the
+//! current time through a [`Clock`] instead of calling `Utc::now()`, so tests
+//! can move time forward instead of sleeping or rewriting timestamps:
+//!
+//! ```
+//! use std::sync::Arc;
+//! use chrono::{Duration, TimeZone, Utc};
+//! use vestige_core::{Clock, MockClock};
+//!
+//! let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()));
+//! clock.advance(Duration::days(30));
+//! assert_eq!(clock.now(), Utc.with_ymd_and_hms(2025, 1, 31, 0, 0, 0).unwrap());
+//! ```
+//!
+//! Everything defaults to [`SystemClock`]; pass a clock through
+//! `StorageConfig::clock` or the `with_clock` builders to replace it.
+
+use std::fmt::Debug;
+use std::sync::{Arc, RwLock};
+
+use chrono::{DateTime, Duration, Utc};
+
+/// Source of the current time
+pub trait Clock: Debug + Send + Sync {
+    /// The current time
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The real wall clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// The system clock as a shareable `Arc<dyn Clock>`
+pub fn system_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
+
+/// A clock that only moves when told to
+#[derive(Debug)]
+pub struct MockClock {
+    now: RwLock<DateTime<Utc>>,
+}
+
+impl MockClock {
+    /// A clock stopped at `start`
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            now: RwLock::new(start),
+        }
+    }
+
+    /// Move the clock forward by `by` (backward if negative)
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.now.write().unwrap_or_else(|e| e.into_inner());
+        *now += by;
+    }
+
+    /// Jump the clock to `to`
+    pub fn set(&self, to: DateTime<Utc>) {
+        *self.now.write().unwrap_or_else(|e| e.into_inner()) = to;
+    }
+}
+
+impl Default for MockClock {
+    /// A clock stopped at the current system time
+    fn default() -> Self {
+        Self::new(Utc::now())
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.read().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -9,7 +9,7 @@
 //! without requiring explicit user input.
 
 use chrono::{DateTime, TimeZone, Utc};
-use git2::{Commit, Repository, Sort};
+use git2::{Commit, Oid, Repository, Sort};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
@@ -99,6 +99,24 @@ impl GitAnalyzer {
         Ok(Self { repo_path })
     }
 
+    /// Create a GitAnalyzer for the repository containing `path`
+    ///
+    /// Walks up parent directories like `git` does, so any path inside a
+    /// working tree resolves to its repository root.
+    pub fn discover(path: &Path) -> Result<Self> {
+        let repo = Repository::discover(path)?;
+        let repo_path = repo
+            .workdir()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| repo.path().to_path_buf());
+        Ok(Self { repo_path })
+    }
+
+    /// Root of the repository's working directory
+    pub fn repo_path(&self) -> &Path {
+        &self.repo_path
+    }
+
     /// Open the repository
     fn open_repo(&self) -> Result<Repository> {
         Repository::open(&self.repo_path).map_err(GitError::from)
@@ -298,38 +316,29 @@ impl GitAnalyzer {
         min_cooccurrence: f64,
     ) -> Result<Vec<FileRelationship>> {
         let repo = self.open_repo()?;
+        let commits = self.walk_commits(&repo, since, None)?;
+        self.cochange_patterns_in(&repo, &commits, min_cooccurrence)
+    }
 
+    /// Co-change relationships among `commits`
+    fn cochange_patterns_in(
+        &self,
+        repo: &Repository,
+        commits: &[Commit],
+        min_cooccurrence: f64,
+    ) -> Result<Vec<FileRelationship>> {
         // Track how often each pair of files changes together
         let mut cochange_counts: HashMap<(PathBuf, PathBuf), u32> = HashMap::new();
         let mut file_change_counts: HashMap<PathBuf, u32> = HashMap::new();
         let mut total_commits = 0u32;
 
-        let mut revwalk = repo.revwalk()?;
-        revwalk.push_head()?;
-        revwalk.set_sorting(Sort::TIME)?;
-
-        for oid in revwalk {
-            let oid = oid?;
-            let commit = repo.find_commit(oid)?;
-
-            // Check if commit is after 'since' timestamp
-            if let Some(since_time) = since {
-                let commit_time = Utc
-                    .timestamp_opt(commit.time().seconds(), 0)
-                    .single()
-                    .unwrap_or_else(Utc::now);
-
-                if commit_time < since_time {
-                    continue;
-                }
-            }
-
+        for commit in commits {
             // Skip merge commits
             if commit.parent_count() > 1 {
                 continue;
             }
 
-            let files = self.get_commit_files(&commit, &repo)?;
+            let files = self.get_commit_files(commit, repo)?;
 
             // Filter to relevant file types
             let relevant_files: Vec<_> = files
@@ -474,35 +483,21 @@ impl GitAnalyzer {
     /// - Messages containing "fixes #123"
     pub fn extract_bug_fixes(&self, since: Option<DateTime<Utc>>) -> Result<Vec<BugFix>> {
         let repo = self.open_repo()?;
-        let mut bug_fixes = Vec::new();
-
-        let mut revwalk = repo.revwalk()?;
-        revwalk.push_head()?;
-        revwalk.set_sorting(Sort::TIME)?;
+        let commits = self.walk_commits(&repo, since, None)?;
+        self.bug_fixes_in(&repo, &commits)
+    }
 
+    /// Bug fixes among `commits`
+    fn bug_fixes_in(&self, repo: &Repository, commits: &[Commit]) -> Result<Vec<BugFix>> {
+        let mut bug_fixes = Vec::new();
         let mut id_counter = 0u32;
 
-        for oid in revwalk {
-            let oid = oid?;
-            let commit = repo.find_commit(oid)?;
-
-            // Check timestamp
-            let commit_time = Utc
-                .timestamp_opt(commit.time().seconds(), 0)
-                .single()
-                .unwrap_or_else(Utc::now);
-
-            if let Some(since_time) = since {
-                if commit_time < since_time {
-                    continue;
-                }
-            }
-
+        for commit in commits {
             let message = commit.message().map(|m| m.to_string()).unwrap_or_default();
 
             // Check if this looks like a bug fix commit
             if let Some(bug_fix) =
-                self.parse_bug_fix_commit(&message, &commit, &repo, &mut id_counter)?
+                self.parse_bug_fix_commit(&message, commit, repo, &mut id_counter)?
             {
                 bug_fixes.push(bug_fix);
             }
@@ -511,6 +506,40 @@ impl GitAnalyzer {
         Ok(bug_fixes)
     }
 
+    /// Commits reachable from HEAD, newest first
+    ///
+    /// Skips commits older than `since`, and `after` plus everything
+    /// reachable from it.
+    fn walk_commits<'r>(
+        &self,
+        repo: &'r Repository,
+        since: Option<DateTime<Utc>>,
+        after: Option<Oid>,
+    ) -> Result<Vec<Commit<'r>>> {
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push_head()?;
+        if let Some(oid) = after {
+            revwalk.hide(oid)?;
+        }
+        revwalk.set_sorting(Sort::TIME)?;
+
+        let mut commits = Vec::new();
+        for oid in revwalk {
+            let commit = repo.find_commit(oid?)?;
+            if let Some(since_time) = since {
+                let commit_time = Utc
+                    .timestamp_opt(commit.time().seconds(), 0)
+                    .single()
+                    .unwrap_or_else(Utc::now);
+                if commit_time < since_time {
+                    continue;
+                }
+            }
+            commits.push(commit);
+        }
+        Ok(commits)
+    }
+
     /// Parse a commit message to extract bug fix information
     fn parse_bug_fix_commit(
         &self,
@@ -646,40 +675,108 @@ impl GitAnalyzer {
         let file_relationships = self.find_cochange_patterns(since, 0.3)?;
 
         // Get recent activity summary
-        let recent_commits = {
-            let repo = self.open_repo()?;
-            self.get_recent_commits(&repo, 50)?
-        };
-
-        // Calculate activity stats
-        let mut author_counts: HashMap<String, u32> = HashMap::new();
-        let mut file_counts: HashMap<PathBuf, u32> = HashMap::new();
-
-        for commit in &recent_commits {
-            *author_counts.entry(commit.author.clone()).or_insert(0) += 1;
-            for file in &commit.files_changed {
-                *file_counts.entry(file.clone()).or_insert(0) += 1;
-            }
-        }
-
-        // Top contributors
-        let mut top_contributors: Vec<_> = author_counts.into_iter().collect();
-        top_contributors.sort_by(|a, b| b.1.cmp(&a.1));
+        let repo = self.open_repo()?;
+        let recent_commits = self.get_recent_commits(&repo, 50)?;
+        let head_commit = repo
+            .head()
+            .and_then(|h| h.peel_to_commit())
+            .ok()
+            .map(|c| c.id().to_string());
 
-        // Hot files (most frequently changed)
-        let mut hot_files: Vec<_> = file_counts.into_iter().collect();
-        hot_files.sort_by(|a, b| b.1.cmp(&a.1));
+        let (top_contributors, hot_files) = activity_stats(&recent_commits);
 
         Ok(HistoryAnalysis {
             bug_fixes,
             file_relationships,
             commit_count: recent_commits.len(),
-            top_contributors: top_contributors.into_iter().take(5).collect(),
-            hot_files: hot_files.into_iter().take(10).collect(),
+            top_contributors,
+            hot_files,
             analyzed_since: since,
+            head_commit,
+        })
+    }
+
+    /// Analyze only the commits made since `last_sha`
+    ///
+    /// Processes commits reachable from HEAD but not from `last_sha`; pass
+    /// `None` (or a SHA that no longer resolves, e.g. after a history
+    /// rewrite) to analyze everything. Store the returned `head_commit` as
+    /// the next cursor (see `Storage::set_git_cursor`). Co-change patterns
+    /// only count commits inside the analyzed range.
+    pub fn analyze_since(&self, last_sha: Option<&str>) -> Result<HistoryAnalysis> {
+        let repo = self.open_repo()?;
+        let head = repo
+            .head()
+            .and_then(|h| h.peel_to_commit())
+            .map_err(|_| GitError::NoCommits)?;
+        let after = last_sha
+            .and_then(|sha| repo.revparse_single(sha).ok())
+            .and_then(|obj| obj.peel_to_commit().ok())
+            .map(|commit| commit.id());
+
+        let commits = self.walk_commits(&repo, None, after)?;
+        let bug_fixes = self.bug_fixes_in(&repo, &commits)?;
+        let file_relationships = self.cochange_patterns_in(&repo, &commits, 0.3)?;
+        let recent_commits = commits
+            .iter()
+            .take(50)
+            .map(|commit| self.commit_to_info(commit, &repo))
+            .collect::<Result<Vec<_>>>()?;
+        let (top_contributors, hot_files) = activity_stats(&recent_commits);
+
+        Ok(HistoryAnalysis {
+            bug_fixes,
+            file_relationships,
+            commit_count: commits.len(),
+            top_contributors,
+            hot_files,
+            analyzed_since: None,
+            head_commit: Some(head.id().to_string()),
         })
     }
 
+    /// Resolve provenance for a memory recorded right now
+    ///
+    /// Returns the full HEAD SHA and the files being worked on: uncommitted and
+    /// staged changes if there are any, otherwise the files touched by HEAD.
+    pub fn head_provenance(&self) -> Result<(String, Vec<PathBuf>)> {
+        let repo = self.open_repo()?;
+        let head = repo
+            .head()
+            .and_then(|h| h.peel_to_commit())
+            .map_err(|_| GitError::NoCommits)?;
+
+        let mut files: Vec<PathBuf> = Vec::new();
+        for entry in repo.statuses(None)?.iter() {
+            let status = entry.status();
+            if status.is_wt_new() || status.is_ignored() {
+                continue;
+            }
+            if let Some(path) = entry.path().map(PathBuf::from)
+                && !files.contains(&path)
+            {
+                files.push(path);
+            }
+        }
+
+        if files.is_empty() {
+            files = self.get_commit_files(&head, &repo)?;
+        }
+
+        Ok((head.id().to_string(), files))
+    }
+
+    /// Resolve provenance for a memory linked to an explicit commit
+    ///
+    /// Returns the full SHA of `rev` and the files that commit touched; the
+    /// working tree plays no part.
+    pub fn commit_provenance(&self, rev: &str) -> Result<(String, Vec<PathBuf>)> {
+        let repo = self.open_repo()?;
+        let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+        let files = self.get_commit_files(&commit, &repo)?;
+        Ok((commit.id().to_string(), files))
+    }
+
     /// Get files changed since a specific commit
     pub fn get_files_changed_since(&self, commit_sha: &str) -> Result<Vec<PathBuf>> {
         let repo = self.open_repo()?;
@@ -739,6 +836,36 @@ pub struct HistoryAnalysis {
     pub hot_files: Vec<(PathBuf, u32)>,
     /// Time period analyzed from
     pub analyzed_since: Option<DateTime<Utc>>,
+    /// HEAD at analysis time: the cursor for the next `analyze_since`
+    pub head_commit: Option<String>,
+}
+
+/// Top contributors and hot files, each with their commit counts
+type ActivityStats = (Vec<(String, u32)>, Vec<(PathBuf, u32)>);
+
+/// Top 5 contributors and top 10 most changed files among `commits`
+fn activity_stats(commits: &[CommitInfo]) -> ActivityStats {
+    let mut author_counts: HashMap<String, u32> = HashMap::new();
+    let mut file_counts: HashMap<PathBuf, u32> = HashMap::new();
+
+    for commit in commits {
+        *author_counts.entry(commit.author.clone()).or_insert(0) += 1;
+        for file in &commit.files_changed {
+            *file_counts.entry(file.clone()).or_insert(0) += 1;
+        }
+    }
+
+    // Top contributors
+    let mut top_contributors: Vec<_> = author_counts.into_iter().collect();
+    top_contributors.sort_by_key(|x| std::cmp::Reverse(x.1));
+    top_contributors.truncate(5);
+
+    // Hot files (most frequently changed)
+    let mut hot_files: Vec<_> = file_counts.into_iter().collect();
+    hot_files.sort_by_key(|x| std::cmp::Reverse(x.1));
+    hot_files.truncate(10);
+
+    (top_contributors, hot_files)
 }
 
 // ============================================================================
@@ -788,6 +915,91 @@ mod tests {
         assert!(!context.head_commit.is_empty());
     }
 
+    #[test]
+    fn test_head_provenance() {
+        let (dir, repo) = create_test_repo();
+        let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+
+        std::fs::write(dir.path().join("lib.rs"), "pub fn a() {}").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("lib.rs")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        let oid = repo
+            .commit(Some("HEAD"), &sig, &sig, "Add lib", &tree, &[&parent])
+            .unwrap();
+
+        // Clean tree: files come from the HEAD commit
+        let analyzer = GitAnalyzer::discover(dir.path()).unwrap();
+        let (sha, files) = analyzer.head_provenance().unwrap();
+        assert_eq!(sha, oid.to_string());
+        assert_eq!(files, vec![PathBuf::from("lib.rs")]);
+
+        // Dirty tree: files come from the working changes
+        std::fs::write(dir.path().join("lib.rs"), "pub fn b() {}").unwrap();
+        let (_, files) = analyzer.head_provenance().unwrap();
+        assert_eq!(files, vec![PathBuf::from("lib.rs")]);
+    }
+
+    #[test]
+    fn test_commit_provenance_ignores_working_tree() {
+        let (dir, repo) = create_test_repo();
+        let first = commit_files(&dir, &repo, &["a.rs"], "Add a");
+        commit_files(&dir, &repo, &["b.rs"], "Add b");
+        std::fs::write(dir.path().join("b.rs"), "dirty").unwrap();
+
+        let analyzer = GitAnalyzer::discover(dir.path()).unwrap();
+        let short = first.to_string()[..8].to_string();
+        let (sha, files) = analyzer.commit_provenance(&short).unwrap();
+        assert_eq!(sha, first.to_string());
+        assert_eq!(files, vec![PathBuf::from("a.rs")]);
+        assert!(analyzer.commit_provenance("no-such-rev").is_err());
+    }
+
+    /// Commit `files` (written with `message` as content) on top of HEAD
+    fn commit_files(dir: &TempDir, repo: &Repository, files: &[&str], message: &str) -> Oid {
+        let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+        let mut index = repo.index().unwrap();
+        for file in files {
+            std::fs::write(dir.path().join(file), message).unwrap();
+            index.add_path(Path::new(file)).unwrap();
+        }
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &[&parent])
+            .unwrap()
+    }
+
+    #[test]
+    fn test_analyze_since_only_new_commits() {
+        let (dir, repo) = create_test_repo();
+        commit_files(&dir, &repo, &["a.rs"], "fix: crash on empty config");
+        let analyzer = GitAnalyzer::new(dir.path().to_path_buf()).unwrap();
+
+        let first = analyzer.analyze_since(None).unwrap();
+        assert_eq!(first.commit_count, 2);
+        assert_eq!(first.bug_fixes.len(), 1);
+        let cursor = first.head_commit.unwrap();
+
+        let unchanged = analyzer.analyze_since(Some(&cursor)).unwrap();
+        assert_eq!(unchanged.commit_count, 0);
+        assert!(unchanged.bug_fixes.is_empty());
+        assert_eq!(unchanged.head_commit.as_deref(), Some(cursor.as_str()));
+
+        let head = commit_files(&dir, &repo, &["b.rs"], "fix(net): retry on timeout");
+        let next = analyzer.analyze_since(Some(&cursor[..10])).unwrap();
+        assert_eq!(next.commit_count, 1);
+        assert_eq!(next.bug_fixes.len(), 1);
+        assert_eq!(next.bug_fixes[0].commit_sha, head.to_string());
+        assert_eq!(next.head_commit, Some(head.to_string()));
+
+        // A cursor that no longer resolves falls back to a full scan
+        let rescanned = analyzer.analyze_since(Some("0123456789abcdef")).unwrap();
+        assert_eq!(rescanned.commit_count, 3);
+    }
+
     #[test]
     fn test_is_relevant_file() {
         let analyzer = GitAnalyzer {
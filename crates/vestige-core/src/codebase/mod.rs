@@ -72,13 +72,14 @@ pub mod context;
 pub mod git;
 pub mod patterns;
 pub mod relationships;
+pub mod tags;
 pub mod types;
 pub mod watcher;
 
 // Re-export main types
 pub use context::{ContextCapture, FileContext, Framework, ProjectType, WorkingContext};
 pub use git::{CommitInfo, GitAnalyzer, GitContext, HistoryAnalysis};
-pub use patterns::{PatternDetector, PatternMatch, PatternSuggestion};
+pub use patterns::{PatternAnalysisConfig, PatternDetector, PatternMatch, PatternSuggestion};
 pub use relationships::{
     GraphEdge, GraphMetadata, GraphNode, RelatedFile, RelationshipGraph, RelationshipTracker,
 };
@@ -87,15 +88,19 @@ pub use types::{
     CodingPreference, DecisionStatus, EntityType, FileRelationship, PreferenceSource, RelationType,
     RelationshipSource, WorkContext, WorkStatus,
 };
-pub use watcher::{CodebaseWatcher, FileEvent, FileEventKind, WatcherConfig};
+pub use watcher::{CodebaseWatcher, FileEvent, FileEventKind, WatcherConfig, WatcherStats};
 
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use chrono::{DateTime, Utc};
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
+use crate::memory::KnowledgeNode;
+use crate::storage::Storage;
+
 // ============================================================================
 // ERRORS
 // ============================================================================
@@ -121,6 +126,10 @@ pub enum CodebaseError {
 
 pub type Result<T> = std::result::Result<T, CodebaseError>;
 
+fn storage_error(e: crate::storage::StorageError) -> CodebaseError {
+    CodebaseError::Storage(e.to_string())
+}
+
 // ============================================================================
 // LEARNING RESULT
 // ============================================================================
@@ -140,6 +149,12 @@ pub struct LearningResult {
     pub commits_analyzed: usize,
     /// Duration of analysis
     pub duration_ms: u64,
+    /// Memories newly written to `Storage` (incremental learning only)
+    pub memories_created: usize,
+    /// Re-derived memories that reinforced an existing one instead
+    pub memories_reinforced: usize,
+    /// HEAD the analysis ran up to
+    pub last_commit: Option<String>,
 }
 
 // ============================================================================
@@ -220,13 +235,14 @@ impl CodebaseMemory {
         files_affected: Vec<PathBuf>,
     ) -> Result<String> {
         let id = format!("adr-{}", Uuid::new_v4());
+        let (commit_sha, files_affected) = self.with_provenance(files_affected);
 
         let node = CodebaseNode::ArchitecturalDecision(ArchitecturalDecision {
             id: id.clone(),
             decision: decision.to_string(),
             rationale: rationale.to_string(),
             files_affected,
-            commit_sha: self.git.get_current_context().ok().map(|c| c.head_commit),
+            commit_sha,
             created_at: Utc::now(),
             updated_at: None,
             context: None,
@@ -248,6 +264,17 @@ impl CodebaseMemory {
         Ok(id)
     }
 
+    /// Link a memory to HEAD, defaulting its files to the ones being worked on
+    fn with_provenance(&self, files: Vec<PathBuf>) -> (Option<String>, Vec<PathBuf>) {
+        match self.git.head_provenance() {
+            Ok((sha, changed)) => {
+                let files = if files.is_empty() { changed } else { files };
+                (Some(sha), files)
+            }
+            Err(_) => (None, files),
+        }
+    }
+
     // ========================================================================
     // BUG FIX MANAGEMENT
     // ========================================================================
@@ -268,11 +295,8 @@ impl CodebaseMemory {
         files_changed: Vec<PathBuf>,
     ) -> Result<String> {
         let id = format!("bug-{}", Uuid::new_v4());
-        let commit_sha = self
-            .git
-            .get_current_context()
-            .map(|c| c.head_commit)
-            .unwrap_or_default();
+        let (commit_sha, files_changed) = self.with_provenance(files_changed);
+        let commit_sha = commit_sha.unwrap_or_default();
 
         let fix = BugFix::new(
             id.clone(),
@@ -350,6 +374,84 @@ impl CodebaseMemory {
         Ok(id)
     }
 
+    // ========================================================================
+    // STORAGE INGEST
+    // ========================================================================
+
+    /// Codebase name used in `codebase:<name>` tags (the repository directory)
+    pub fn name(&self) -> String {
+        self.repo_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Persist an architectural decision as a semantic `decision` memory
+    pub fn ingest_decision(
+        &self,
+        storage: &Storage,
+        decision: ArchitecturalDecision,
+    ) -> Result<KnowledgeNode> {
+        self.ingest_node(storage, CodebaseNode::ArchitecturalDecision(decision))
+    }
+
+    /// Persist a bug fix as an episodic `bug_fix` memory
+    pub fn ingest_bug_fix(&self, storage: &Storage, fix: BugFix) -> Result<KnowledgeNode> {
+        self.ingest_node(storage, CodebaseNode::BugFix(fix))
+    }
+
+    /// Persist a code pattern as a procedural `pattern` memory
+    pub fn ingest_pattern(&self, storage: &Storage, pattern: CodePattern) -> Result<KnowledgeNode> {
+        self.ingest_node(storage, CodebaseNode::CodePattern(pattern))
+    }
+
+    /// Persist any codebase node into `storage`
+    ///
+    /// The node type, tags and memory system come from the scheme in
+    /// [`tags`], so the memory can be found again with
+    /// `Storage::get_nodes_by_type_and_tag(node_type, Some(&tags::codebase_tag(name)), ..)`.
+    /// Unlike the `remember_*` methods this does not keep an in-memory copy.
+    pub fn ingest_node(&self, storage: &Storage, node: CodebaseNode) -> Result<KnowledgeNode> {
+        self.store_node(storage, &node).map(|(stored, _)| stored)
+    }
+
+    /// Write `node`, reinforcing an existing memory when it was derived before
+    ///
+    /// Goes through `smart_ingest` when embeddings are available, so a
+    /// re-derived memory strengthens its near-duplicate; otherwise exact
+    /// duplicates are strengthened on access. Returns whether it reinforced.
+    fn store_node(&self, storage: &Storage, node: &CodebaseNode) -> Result<(KnowledgeNode, bool)> {
+        let name = self.name();
+        let codebase = Some(name.as_str()).filter(|n| !n.is_empty());
+
+        storage
+            .register_node_type(node.memory_node_type())
+            .map_err(storage_error)?;
+        let input = node.to_ingest_input(codebase);
+
+        #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+        let (stored, reinforced) = {
+            let result = storage.smart_ingest(input).map_err(storage_error)?;
+            let reinforced = result.decision != "create";
+            (result.node, reinforced)
+        };
+        #[cfg(not(all(feature = "embeddings", feature = "vector-search")))]
+        let (stored, reinforced) = {
+            let outcome = storage.ingest_with_outcome(input).map_err(storage_error)?;
+            if outcome.returned_existing {
+                storage
+                    .strengthen_on_access(&outcome.node.id)
+                    .map_err(storage_error)?;
+            }
+            (outcome.node, outcome.returned_existing)
+        };
+
+        storage
+            .set_memory_system(&stored.id, node.memory_system())
+            .map_err(storage_error)?;
+        Ok((stored, reinforced))
+    }
+
     // ========================================================================
     // RELATIONSHIP MANAGEMENT
     // ========================================================================
@@ -478,6 +580,40 @@ impl CodebaseMemory {
         Ok(nodes.iter().find(|n| n.id() == id).cloned())
     }
 
+    /// Find decisions and bug fixes linked to a commit (full or abbreviated SHA)
+    pub fn find_by_commit(&self, sha: &str) -> Result<Vec<CodebaseNode>> {
+        let sha = sha.trim().to_lowercase();
+        if sha.is_empty() {
+            return Ok(vec![]);
+        }
+        let nodes = self.nodes.blocking_read();
+        Ok(nodes
+            .iter()
+            .filter(|n| {
+                n.commit_sha().is_some_and(|c| {
+                    let c = c.to_lowercase();
+                    c.starts_with(&sha) || sha.starts_with(&c)
+                })
+            })
+            .cloned()
+            .collect())
+    }
+
+    /// Find memories that touch a file (repo-relative or absolute path)
+    pub fn find_by_file(&self, path: &std::path::Path) -> Result<Vec<CodebaseNode>> {
+        let relative = path.strip_prefix(&self.repo_path).unwrap_or(path);
+        let nodes = self.nodes.blocking_read();
+        Ok(nodes
+            .iter()
+            .filter(|n| {
+                n.associated_files()
+                    .iter()
+                    .any(|f| f.as_path() == relative || f.as_path() == path)
+            })
+            .cloned()
+            .collect())
+    }
+
     /// Get all nodes of a specific type
     pub fn get_nodes_by_type(&self, node_type: &str) -> Result<Vec<CodebaseNode>> {
         let nodes = self.nodes.blocking_read();
@@ -520,6 +656,9 @@ impl CodebaseMemory {
             analyzed_since: analysis.analyzed_since,
             commits_analyzed: analysis.commit_count,
             duration_ms,
+            memories_created: 0,
+            memories_reinforced: 0,
+            last_commit: analysis.head_commit,
         })
     }
 
@@ -548,6 +687,148 @@ impl CodebaseMemory {
             analyzed_since: Some(since),
             commits_analyzed: analysis.commit_count,
             duration_ms,
+            memories_created: 0,
+            memories_reinforced: 0,
+            last_commit: analysis.head_commit,
+        })
+    }
+
+    /// Learn from commits made since the last run and persist the results
+    ///
+    /// Reads the repository's cursor from `storage`, analyzes only newer
+    /// commits (everything on the first run), stores the bug fixes found as
+    /// codebase memories and advances the cursor to HEAD. Running it again
+    /// with no new commits does nothing, which makes it cheap enough to call
+    /// whenever the repository changes.
+    pub async fn learn_incremental(&self, storage: &Storage) -> Result<LearningResult> {
+        let start = std::time::Instant::now();
+
+        let cursor = storage.git_cursor(&self.repo_path).map_err(storage_error)?;
+        let analysis = self.git.analyze_since(cursor.as_deref())?;
+
+        let (mut created, mut reinforced) = (0, 0);
+        let mut nodes = self.nodes.write().await;
+        for fix in &analysis.bug_fixes {
+            let node = CodebaseNode::BugFix(fix.clone());
+            if self.store_node(storage, &node)?.1 {
+                reinforced += 1;
+            } else {
+                created += 1;
+            }
+            nodes.push(node);
+        }
+
+        let mut tracker = self.relationships.write().await;
+        for rel in &analysis.file_relationships {
+            let _ = tracker.add_relationship(rel.clone());
+        }
+
+        if let Some(ref head) = analysis.head_commit {
+            storage
+                .set_git_cursor(&self.repo_path, head)
+                .map_err(storage_error)?;
+        }
+
+        Ok(LearningResult {
+            bug_fixes_found: analysis.bug_fixes.len(),
+            relationships_found: analysis.file_relationships.len(),
+            patterns_detected: 0,
+            analyzed_since: None,
+            commits_analyzed: analysis.commit_count,
+            duration_ms: start.elapsed().as_millis() as u64,
+            memories_created: created,
+            memories_reinforced: reinforced,
+            last_commit: analysis.head_commit,
+        })
+    }
+
+    /// Scan a source tree for recurring patterns and store the top ones
+    ///
+    /// Walks source files under `root` (skipping hidden and .gitignored paths),
+    /// runs the pattern detector per file language, and keeps patterns seen in
+    /// at least `config.min_occurrences` files. The top `config.top_n` are
+    /// stored as `CodePattern` nodes tagged `codebase:<name>`; re-running
+    /// updates those nodes in place instead of duplicating them.
+    pub fn analyze_patterns(
+        &self,
+        root: &Path,
+        config: &PatternAnalysisConfig,
+    ) -> Result<LearningResult> {
+        use crate::advanced::Language;
+
+        let start = std::time::Instant::now();
+        let codebase = config.codebase.clone().unwrap_or_else(|| {
+            root.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| "default".to_string())
+        });
+
+        let files = collect_source_files(root, config);
+
+        // pattern id → (pattern, files it occurs in, summed confidence)
+        let mut occurrences: HashMap<String, (CodePattern, Vec<PathBuf>, f64)> = HashMap::new();
+        {
+            let detector = self.patterns.blocking_read();
+            for file in &files {
+                let language = file
+                    .extension()
+                    .map(|e| Language::from_extension(&e.to_string_lossy()))
+                    .unwrap_or(Language::Unknown);
+                if language == Language::Unknown {
+                    continue;
+                }
+                let Ok(code) = std::fs::read_to_string(file) else {
+                    continue;
+                };
+                let relative = file.strip_prefix(root).unwrap_or(file).to_path_buf();
+
+                for m in detector.detect_patterns(&code, language.as_str())? {
+                    if m.confidence < config.min_confidence {
+                        continue;
+                    }
+                    let entry = occurrences
+                        .entry(m.pattern.id.clone())
+                        .or_insert_with(|| (m.pattern.clone(), Vec::new(), 0.0));
+                    entry.1.push(relative.clone());
+                    entry.2 += m.confidence;
+                }
+            }
+        }
+
+        // Rank by summed confidence (frequency weighted by mean confidence)
+        let mut recurring: Vec<_> = occurrences
+            .into_values()
+            .filter(|(_, files, _)| files.len() >= config.min_occurrences.max(1))
+            .collect();
+        recurring.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+        recurring.truncate(config.top_n);
+
+        let codebase_tag = tags::codebase_tag(&codebase);
+        let mut nodes = self.nodes.blocking_write();
+        for (mut pattern, files, _) in recurring.iter().cloned() {
+            pattern.usage_count = files.len() as u32;
+            pattern.example_files = files;
+            if !pattern.tags.contains(&codebase_tag) {
+                pattern.tags.push(codebase_tag.clone());
+            }
+
+            let node = CodebaseNode::CodePattern(pattern);
+            match nodes.iter_mut().find(|n| n.id() == node.id()) {
+                Some(existing) => *existing = node,
+                None => nodes.push(node),
+            }
+        }
+
+        Ok(LearningResult {
+            bug_fixes_found: 0,
+            relationships_found: 0,
+            patterns_detected: recurring.len(),
+            analyzed_since: None,
+            commits_analyzed: 0,
+            duration_ms: start.elapsed().as_millis() as u64,
+            memories_created: 0,
+            memories_reinforced: 0,
+            last_commit: None,
         })
     }
 
@@ -573,6 +854,25 @@ impl CodebaseMemory {
         Ok(())
     }
 
+    /// Ingest patterns found in changed files into `storage`
+    ///
+    /// Call before `start_watching`; without a watcher this does nothing.
+    pub async fn watch_into(&self, storage: Arc<Storage>) {
+        if let Some(ref watcher) = self.watcher {
+            let name = self.name();
+            let codebase = Some(name).filter(|n| !n.is_empty());
+            watcher.write().await.set_storage(storage, codebase);
+        }
+    }
+
+    /// Watcher event counters, if watching is enabled
+    pub async fn watcher_stats(&self) -> Option<WatcherStats> {
+        match self.watcher {
+            Some(ref watcher) => Some(watcher.read().await.stats()),
+            None => None,
+        }
+    }
+
     // ========================================================================
     // SERIALIZATION
     // ========================================================================
@@ -642,6 +942,50 @@ impl CodebaseMemory {
     }
 }
 
+/// Collect source files under `root`, skipping hidden and .gitignored paths
+fn collect_source_files(root: &Path, config: &PatternAnalysisConfig) -> Vec<PathBuf> {
+    let repo = git2::Repository::discover(root).ok();
+    let workdir = repo.as_ref().and_then(|r| r.workdir().map(|p| p.to_path_buf()));
+    let is_ignored = |path: &Path| match (&repo, &workdir) {
+        (Some(repo), Some(workdir)) => path
+            .strip_prefix(workdir)
+            .ok()
+            .is_some_and(|rel| repo.is_path_ignored(rel).unwrap_or(false)),
+        _ => false,
+    };
+
+    let mut files = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
+        entries.sort_by_key(|e| e.file_name());
+
+        for entry in entries {
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') || is_ignored(&path) {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                stack.push(path);
+            } else if file_type.is_file()
+                && entry.metadata().is_ok_and(|m| m.len() <= config.max_file_bytes)
+            {
+                files.push(path);
+                if files.len() >= config.max_files {
+                    return files;
+                }
+            }
+        }
+    }
+    files
+}
+
 /// Statistics about codebase memory
 #[derive(Debug, Clone)]
 pub struct CodebaseStats {
@@ -746,6 +1090,136 @@ version = "0.1.0"
         assert_eq!(results.len(), 1);
     }
 
+    #[test]
+    fn test_find_by_commit_and_file() {
+        let dir = create_test_repo();
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("src/main.rs")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let oid = repo
+            .commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[])
+            .unwrap();
+
+        let memory = CodebaseMemory::new(dir.path().to_path_buf()).unwrap();
+
+        // No files given: provenance fills in HEAD and its changed files
+        let id = memory.remember_decision("Keep main tiny", "Simplicity", vec![]).unwrap();
+        memory
+            .remember_decision("Unrelated", "Other", vec![PathBuf::from("src/other.rs")])
+            .unwrap();
+
+        let short_sha = &oid.to_string()[..8];
+        let by_commit = memory.find_by_commit(short_sha).unwrap();
+        assert_eq!(by_commit.len(), 2);
+
+        let by_file = memory.find_by_file(std::path::Path::new("src/main.rs")).unwrap();
+        assert_eq!(by_file.len(), 1);
+        assert_eq!(by_file[0].id(), id);
+
+        let absolute = dir.path().join("src/other.rs");
+        assert_eq!(memory.find_by_file(&absolute).unwrap().len(), 1);
+        assert!(memory.find_by_commit("deadbeef").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_ingest_typed_nodes_into_storage() {
+        let dir = create_test_repo();
+        let memory = CodebaseMemory::new(dir.path().to_path_buf()).unwrap();
+        let storage = Storage::new(Some(dir.path().join("vestige.db"))).unwrap();
+        let codebase = tags::codebase_tag(&memory.name());
+
+        let decision = memory
+            .ingest_decision(
+                &storage,
+                ArchitecturalDecision::new("adr-1".into(), "Use WAL mode".into(), "Concurrent readers".into())
+                    .with_files(vec![PathBuf::from("src/main.rs")]),
+            )
+            .unwrap();
+        let fix = memory
+            .ingest_bug_fix(
+                &storage,
+                BugFix::new("bug-1".into(), "Crash".into(), "Null config".into(), "Check".into(), "abc123".into())
+                    .with_severity(BugSeverity::High),
+            )
+            .unwrap();
+        memory
+            .ingest_pattern(
+                &storage,
+                CodePattern::new("pat-1".into(), "Builder".into(), "Fluent setup".into(), "Many options".into()),
+            )
+            .unwrap();
+        memory
+            .ingest_node(
+                &storage,
+                CodebaseNode::CodingPreference(CodingPreference::new(
+                    "pref-1".into(),
+                    "errors".into(),
+                    "thiserror".into(),
+                )),
+            )
+            .unwrap();
+
+        let decisions = storage
+            .get_nodes_by_type_and_tag("decision", Some(&codebase), 10)
+            .unwrap();
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].id, decision.id);
+        assert!(decisions[0].tags.contains(&"file:src/main.rs".to_string()));
+        assert_eq!(storage.memory_system(&decision.id).unwrap(), crate::memory::MemorySystem::Semantic);
+
+        let fixes = storage
+            .get_nodes_by_type_and_tag("bug_fix", Some("commit:abc123"), 10)
+            .unwrap();
+        assert_eq!(fixes.len(), 1);
+        assert!(fixes[0].tags.contains(&"severity:high".to_string()));
+        assert_eq!(storage.memory_system(&fix.id).unwrap(), crate::memory::MemorySystem::Episodic);
+
+        assert_eq!(storage.get_nodes_by_type_and_tag("pattern", Some(&codebase), 10).unwrap().len(), 1);
+        assert_eq!(storage.get_nodes_by_type_and_tag("preference", Some(&codebase), 10).unwrap().len(), 1);
+        assert!(storage.memory_system("missing").is_err());
+    }
+
+    #[test]
+    fn test_learn_incremental_advances_cursor() {
+        let dir = create_test_repo();
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+        let commit = |message: &str| {
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("src/main.rs")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parents: Vec<_> = repo.head().ok().and_then(|h| h.peel_to_commit().ok()).into_iter().collect();
+            let parents: Vec<_> = parents.iter().collect();
+            repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents).unwrap()
+        };
+        commit("Initial commit");
+        commit("fix: panic when config is empty");
+
+        let memory = CodebaseMemory::new(dir.path().to_path_buf()).unwrap();
+        let storage = Storage::new(Some(dir.path().join("vestige.db"))).unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+
+        let first = runtime.block_on(memory.learn_incremental(&storage)).unwrap();
+        assert_eq!(first.commits_analyzed, 2);
+        assert_eq!((first.memories_created, first.memories_reinforced), (1, 0));
+        let head = first.last_commit.clone().unwrap();
+        assert_eq!(storage.git_cursor(dir.path()).unwrap(), Some(head));
+
+        // Nothing new since the cursor
+        let again = runtime.block_on(memory.learn_incremental(&storage)).unwrap();
+        assert_eq!((again.commits_analyzed, again.bug_fixes_found), (0, 0));
+
+        // A full rescan re-derives the fix and reinforces it instead of duplicating
+        assert!(storage.clear_git_cursor(dir.path()).unwrap());
+        let rescan = runtime.block_on(memory.learn_incremental(&storage)).unwrap();
+        assert_eq!((rescan.memories_created, rescan.memories_reinforced), (0, 1));
+        assert_eq!(storage.get_nodes_by_type_and_tag("bug_fix", None, 10).unwrap().len(), 1);
+    }
+
     #[test]
     fn test_get_context() {
         let dir = create_test_repo();
@@ -84,6 +84,40 @@ pub struct PatternSuggestion {
     pub example: Option<String>,
 }
 
+// ============================================================================
+// PATTERN ANALYSIS CONFIG
+// ============================================================================
+
+/// Configuration for scanning a repository for recurring patterns
+#[derive(Debug, Clone)]
+pub struct PatternAnalysisConfig {
+    /// Codebase name used for the `codebase:<name>` tag (defaults to the root directory name)
+    pub codebase: Option<String>,
+    /// Maximum number of source files to scan
+    pub max_files: usize,
+    /// Skip files larger than this many bytes
+    pub max_file_bytes: u64,
+    /// Minimum number of files a pattern must appear in to count as recurring
+    pub min_occurrences: usize,
+    /// Minimum detector confidence for a file to count as an occurrence
+    pub min_confidence: f64,
+    /// Number of top patterns to store
+    pub top_n: usize,
+}
+
+impl Default for PatternAnalysisConfig {
+    fn default() -> Self {
+        Self {
+            codebase: None,
+            max_files: 2000,
+            max_file_bytes: 256 * 1024,
+            min_occurrences: 2,
+            min_confidence: 0.3,
+            top_n: 10,
+        }
+    }
+}
+
 // ============================================================================
 // PATTERN DETECTOR
 // ============================================================================
@@ -0,0 +1,222 @@
+//! Tag scheme for codebase memories kept in `Storage`
+//!
+//! Codebase knowledge is stored as ordinary knowledge nodes and found again
+//! with `Storage::get_nodes_by_type_and_tag`, so every writer has to agree on
+//! node types and tag strings. They are all built here:
+//!
+//! | Tag                | Meaning                                        |
+//! |--------------------|------------------------------------------------|
+//! | `codebase`         | Any codebase memory                            |
+//! | `codebase:<name>`  | Memory belongs to the named codebase           |
+//! | `file:<path>`      | Memory concerns a (repo-relative) file         |
+//! | `commit:<sha>`     | Memory is linked to a commit                   |
+//! | `status:<status>`  | Architectural decision status                  |
+//! | `severity:<level>` | Bug fix severity                               |
+//! | `language:<lang>`  | Language a pattern or preference applies to    |
+
+use std::path::Path;
+
+use super::types::{BugSeverity, CodebaseNode, DecisionStatus};
+use crate::memory::{IngestInput, MemorySystem};
+
+/// Tag carried by every codebase memory
+pub const CODEBASE_TAG: &str = "codebase";
+/// Prefix of the tag naming the codebase a memory belongs to
+pub const CODEBASE_PREFIX: &str = "codebase:";
+/// Prefix of file provenance tags
+pub const FILE_PREFIX: &str = "file:";
+/// Prefix of commit provenance tags
+pub const COMMIT_PREFIX: &str = "commit:";
+
+/// Storage node type for architectural decisions
+pub const DECISION_NODE_TYPE: &str = "decision";
+/// Storage node type for bug fixes
+pub const BUG_FIX_NODE_TYPE: &str = "bug_fix";
+/// Storage node type for code patterns
+pub const PATTERN_NODE_TYPE: &str = "pattern";
+
+/// `codebase:<name>`
+pub fn codebase_tag(name: &str) -> String {
+    format!("{}{}", CODEBASE_PREFIX, name)
+}
+
+/// `file:<path>`
+pub fn file_tag(path: impl AsRef<Path>) -> String {
+    format!("{}{}", FILE_PREFIX, path.as_ref().to_string_lossy())
+}
+
+/// `commit:<sha>`
+pub fn commit_tag(sha: &str) -> String {
+    format!("{}{}", COMMIT_PREFIX, sha)
+}
+
+/// Codebase name from a `codebase:<name>` tag
+pub fn parse_codebase_tag(tag: &str) -> Option<&str> {
+    tag.strip_prefix(CODEBASE_PREFIX).filter(|name| !name.is_empty())
+}
+
+/// Tags every codebase memory gets: `codebase`, `codebase:<name>` when a
+/// codebase is known, then file and commit provenance
+pub fn provenance_tags<P: AsRef<Path>>(
+    codebase: Option<&str>,
+    commit: Option<&str>,
+    files: &[P],
+) -> Vec<String> {
+    let mut tags = vec![CODEBASE_TAG.to_string()];
+    if let Some(name) = codebase.filter(|n| !n.is_empty()) {
+        tags.push(codebase_tag(name));
+    }
+    tags.extend(files.iter().map(file_tag));
+    if let Some(sha) = commit.filter(|s| !s.is_empty()) {
+        tags.push(commit_tag(sha));
+    }
+    tags
+}
+
+fn decision_status(status: DecisionStatus) -> &'static str {
+    match status {
+        DecisionStatus::Proposed => "proposed",
+        DecisionStatus::Accepted => "accepted",
+        DecisionStatus::Superseded => "superseded",
+        DecisionStatus::Deprecated => "deprecated",
+    }
+}
+
+fn bug_severity(severity: BugSeverity) -> &'static str {
+    match severity {
+        BugSeverity::Critical => "critical",
+        BugSeverity::High => "high",
+        BugSeverity::Medium => "medium",
+        BugSeverity::Low => "low",
+        BugSeverity::Trivial => "trivial",
+    }
+}
+
+impl CodebaseNode {
+    /// `node_type` this node is stored under in `Storage`
+    pub fn memory_node_type(&self) -> &'static str {
+        match self {
+            Self::ArchitecturalDecision(_) => DECISION_NODE_TYPE,
+            Self::BugFix(_) => BUG_FIX_NODE_TYPE,
+            Self::CodePattern(_) => PATTERN_NODE_TYPE,
+            Self::FileRelationship(_) => "relationship",
+            Self::CodingPreference(_) => "preference",
+            Self::CodeEntity(_) => "code",
+            Self::WorkContext(_) => "event",
+        }
+    }
+
+    /// Memory system the node belongs to
+    ///
+    /// Bug fixes and work context record what happened (episodic), patterns
+    /// are how-to knowledge (procedural), everything else is semantic.
+    pub fn memory_system(&self) -> MemorySystem {
+        match self {
+            Self::BugFix(_) | Self::WorkContext(_) => MemorySystem::Episodic,
+            Self::CodePattern(_) => MemorySystem::Procedural,
+            _ => MemorySystem::Semantic,
+        }
+    }
+
+    /// Full tag set for storing this node under `codebase`
+    pub fn memory_tags(&self, codebase: Option<&str>) -> Vec<String> {
+        let mut tags: Vec<String> = match self {
+            Self::ArchitecturalDecision(_) => vec![DECISION_NODE_TYPE.into(), "architecture".into()],
+            _ => vec![self.memory_node_type().to_string()],
+        };
+        let files: Vec<_> = self.associated_files();
+        tags.extend(provenance_tags(codebase, self.commit_sha(), &files));
+
+        match self {
+            Self::ArchitecturalDecision(n) => {
+                tags.push(format!("status:{}", decision_status(n.status)));
+                tags.extend(n.tags.iter().cloned());
+            }
+            Self::BugFix(n) => {
+                tags.push(format!("severity:{}", bug_severity(n.severity)));
+                tags.extend(n.tags.iter().cloned());
+            }
+            Self::CodePattern(n) => {
+                tags.extend(n.language.iter().map(|l| format!("language:{}", l)));
+                tags.extend(n.tags.iter().cloned());
+            }
+            Self::CodingPreference(n) => {
+                tags.extend(n.language.iter().map(|l| format!("language:{}", l)));
+            }
+            _ => {}
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        tags.retain(|t| seen.insert(t.clone()));
+        tags
+    }
+
+    /// `IngestInput` storing this node under `codebase`
+    ///
+    /// The memory system is not part of `IngestInput`; set it afterwards
+    /// with `Storage::set_memory_system` (as `CodebaseMemory::ingest_node` does).
+    pub fn to_ingest_input(&self, codebase: Option<&str>) -> IngestInput {
+        IngestInput {
+            content: self.to_searchable_text(),
+            node_type: self.memory_node_type().to_string(),
+            source: codebase.map(str::to_string),
+            tags: self.memory_tags(codebase),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codebase::{ArchitecturalDecision, BugFix, CodePattern};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_node_mapping_and_tags() {
+        let decision = CodebaseNode::ArchitecturalDecision(
+            ArchitecturalDecision::new("adr-1".into(), "Use WAL".into(), "Readers".into())
+                .with_files(vec![PathBuf::from("src/db.rs")])
+                .with_commit("abc123".into())
+                .with_tags(vec!["sqlite".into(), "architecture".into()]),
+        );
+        assert_eq!(decision.memory_node_type(), "decision");
+        assert_eq!(decision.memory_system(), MemorySystem::Semantic);
+        assert_eq!(
+            decision.memory_tags(Some("vestige")),
+            vec![
+                "decision",
+                "architecture",
+                "codebase",
+                "codebase:vestige",
+                "file:src/db.rs",
+                "commit:abc123",
+                "status:accepted",
+                "sqlite",
+            ]
+        );
+
+        let fix = CodebaseNode::BugFix(BugFix::new(
+            "bug-1".into(),
+            "Crash".into(),
+            "Null".into(),
+            "Check".into(),
+            String::new(),
+        ));
+        assert_eq!(fix.memory_system(), MemorySystem::Episodic);
+        let input = fix.to_ingest_input(None);
+        assert_eq!(input.node_type, "bug_fix");
+        assert_eq!(input.tags, vec!["bug_fix", "codebase", "severity:medium"]);
+
+        let pattern = CodebaseNode::CodePattern(
+            CodePattern::new("p".into(), "Builder".into(), "d".into(), "w".into())
+                .with_language("rust".into()),
+        );
+        assert_eq!(pattern.memory_system(), MemorySystem::Procedural);
+        assert!(pattern.memory_tags(None).contains(&"language:rust".to_string()));
+
+        assert_eq!(parse_codebase_tag("codebase:vestige"), Some("vestige"));
+        assert_eq!(parse_codebase_tag("codebase:"), None);
+        assert_eq!(parse_codebase_tag("file:x"), None);
+    }
+}
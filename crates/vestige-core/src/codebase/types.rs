@@ -103,6 +103,16 @@ impl CodebaseNode {
         }
     }
 
+    /// Get the git commit this node is linked to, if any
+    pub fn commit_sha(&self) -> Option<&str> {
+        let sha = match self {
+            Self::ArchitecturalDecision(n) => n.commit_sha.as_deref(),
+            Self::BugFix(n) => Some(n.commit_sha.as_str()),
+            _ => None,
+        };
+        sha.filter(|s| !s.is_empty())
+    }
+
     /// Get all file paths associated with this node
     pub fn associated_files(&self) -> Vec<&PathBuf> {
         match self {
@@ -624,6 +634,11 @@ impl BugFix {
         self.issue_link = Some(link);
         self
     }
+
+    pub fn with_commit(mut self, sha: String) -> Self {
+        self.commit_sha = sha;
+        self
+    }
 }
 
 impl CodePattern {
@@ -7,12 +7,16 @@
 //!
 //! This enables Vestige to learn continuously from developer behavior
 //! without requiring explicit user input.
+//!
+//! Builds and checkouts touch many files at once, so events are coalesced
+//! per path until they have been quiet for `WatcherConfig::debounce_interval`
+//! and then handled as one batch.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use chrono::{DateTime, Utc};
 use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
@@ -20,6 +24,10 @@ use tokio::sync::{broadcast, mpsc, RwLock};
 
 use super::patterns::PatternDetector;
 use super::relationships::RelationshipTracker;
+use super::tags;
+use super::types::CodebaseNode;
+use crate::memory::IngestInput;
+use crate::storage::Storage;
 
 // ============================================================================
 // ERRORS
@@ -92,7 +100,7 @@ impl From<EventKind> for FileEventKind {
 /// Configuration for the codebase watcher
 #[derive(Debug, Clone)]
 pub struct WatcherConfig {
-    /// Debounce interval for batching events
+    /// How long a path must be quiet before its coalesced change is handled
     pub debounce_interval: Duration,
     /// Patterns to ignore (gitignore-style)
     pub ignore_patterns: Vec<String>,
@@ -145,6 +153,148 @@ impl Default for WatcherConfig {
     }
 }
 
+// ============================================================================
+// WATCHER STATS
+// ============================================================================
+
+/// How many filesystem events the watcher received, absorbed and acted on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WatcherStats {
+    /// Per-path events reported by the filesystem
+    pub events_received: u64,
+    /// Events dropped by `ignore_patterns` or `watch_extensions`
+    pub events_ignored: u64,
+    /// Events merged into a change already pending for the same path
+    pub events_coalesced: u64,
+    /// Coalesced changes handled after the debounce window
+    pub changes_processed: u64,
+    /// Batches of changes handled
+    pub batches: u64,
+    /// New memories written by batched ingests
+    pub memories_ingested: u64,
+}
+
+#[derive(Debug, Default)]
+struct StatCounters {
+    events_received: AtomicU64,
+    events_ignored: AtomicU64,
+    events_coalesced: AtomicU64,
+    changes_processed: AtomicU64,
+    batches: AtomicU64,
+    memories_ingested: AtomicU64,
+}
+
+impl StatCounters {
+    fn add(counter: &AtomicU64, n: u64) {
+        counter.fetch_add(n, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> WatcherStats {
+        WatcherStats {
+            events_received: self.events_received.load(Ordering::Relaxed),
+            events_ignored: self.events_ignored.load(Ordering::Relaxed),
+            events_coalesced: self.events_coalesced.load(Ordering::Relaxed),
+            changes_processed: self.changes_processed.load(Ordering::Relaxed),
+            batches: self.batches.load(Ordering::Relaxed),
+            memories_ingested: self.memories_ingested.load(Ordering::Relaxed),
+        }
+    }
+}
+
+// ============================================================================
+// DEBOUNCER
+// ============================================================================
+
+/// Change waiting for its path to go quiet
+#[derive(Debug)]
+struct PendingChange {
+    kind: FileEventKind,
+    last_seen: Instant,
+    timestamp: DateTime<Utc>,
+}
+
+/// Coalesces events per path until the path has been quiet for the window
+#[derive(Debug, Default)]
+struct Debouncer {
+    pending: HashMap<PathBuf, PendingChange>,
+}
+
+impl Debouncer {
+    /// Record `event`, dropping ignored paths and merging repeats
+    fn push(&mut self, event: FileEvent, config: &WatcherConfig, stats: &StatCounters, now: Instant) {
+        for path in event.paths {
+            StatCounters::add(&stats.events_received, 1);
+            if !CodebaseWatcher::should_process(&path, config) {
+                StatCounters::add(&stats.events_ignored, 1);
+                continue;
+            }
+            match self.pending.get_mut(&path) {
+                Some(change) => {
+                    change.kind = coalesce(change.kind, event.kind);
+                    change.last_seen = now;
+                    change.timestamp = event.timestamp;
+                    StatCounters::add(&stats.events_coalesced, 1);
+                }
+                None => {
+                    self.pending.insert(
+                        path,
+                        PendingChange {
+                            kind: event.kind,
+                            last_seen: now,
+                            timestamp: event.timestamp,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    /// Take the changes quiet for at least `window`, one event per path
+    fn drain_ready(&mut self, now: Instant, window: Duration) -> Vec<FileEvent> {
+        let ready: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, change)| now.saturating_duration_since(change.last_seen) >= window)
+            .map(|(path, _)| path.clone())
+            .collect();
+        self.take(ready)
+    }
+
+    /// Take every pending change regardless of age
+    fn drain_all(&mut self) -> Vec<FileEvent> {
+        let all: Vec<PathBuf> = self.pending.keys().cloned().collect();
+        self.take(all)
+    }
+
+    fn take(&mut self, mut paths: Vec<PathBuf>) -> Vec<FileEvent> {
+        paths.sort();
+        paths
+            .into_iter()
+            .filter_map(|path| {
+                let change = self.pending.remove(&path)?;
+                Some(FileEvent {
+                    kind: change.kind,
+                    paths: vec![path],
+                    timestamp: change.timestamp,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Net effect of `next` following `previous` on the same path
+fn coalesce(previous: FileEventKind, next: FileEventKind) -> FileEventKind {
+    use FileEventKind::*;
+    match (previous, next) {
+        (_, Deleted) => Deleted,
+        // Deleted and written again, as editors do when saving via rename
+        (Deleted, Created | Modified) => Modified,
+        (Created, _) => Created,
+        (_, Accessed) => previous,
+        (_, next) => next,
+    }
+}
+
 // ============================================================================
 // EDIT SESSION
 // ============================================================================
@@ -189,6 +339,122 @@ impl EditSession {
     }
 }
 
+// ============================================================================
+// BATCH PROCESSING
+// ============================================================================
+
+/// Storage that patterns detected in changed files are ingested into
+#[derive(Clone)]
+struct IngestSink {
+    storage: Arc<Storage>,
+    /// Name for `codebase:<name>` tags
+    codebase: Option<String>,
+}
+
+/// Handles the debounced batches of one watched directory
+struct BatchProcessor {
+    tracker: Arc<RwLock<RelationshipTracker>>,
+    detector: Arc<RwLock<PatternDetector>>,
+    config: WatcherConfig,
+    sink: Option<IngestSink>,
+    stats: Arc<StatCounters>,
+    /// Watched directory, for repo-relative `file:` tags
+    root: PathBuf,
+}
+
+impl BatchProcessor {
+    /// Track and scan every change in `batch`, then ingest what was found at once
+    async fn process(&self, batch: Vec<FileEvent>, session: &mut EditSession) {
+        StatCounters::add(&self.stats.batches, 1);
+        let mut found = Vec::new();
+
+        for event in batch {
+            for path in &event.paths {
+                StatCounters::add(&self.stats.changes_processed, 1);
+                match event.kind {
+                    FileEventKind::Modified | FileEventKind::Created => {
+                        if self.config.track_relationships {
+                            session.add_file(path.clone());
+                        }
+                        if self.config.detect_patterns {
+                            found.extend(self.detect(path).await);
+                        }
+                    }
+                    FileEventKind::Deleted => {
+                        session.files.remove(path);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if let Some(sink) = &self.sink {
+            self.ingest(sink, found);
+        }
+    }
+
+    /// Patterns detected in `path`, with the repo-relative path
+    async fn detect(&self, path: &Path) -> Vec<(CodebaseNode, PathBuf)> {
+        let Ok(content) = std::fs::read_to_string(path) else {
+            return Vec::new();
+        };
+        let language = CodebaseWatcher::detect_language(path);
+        let matches = self
+            .detector
+            .read()
+            .await
+            .detect_patterns(&content, &language)
+            .unwrap_or_default();
+        let relative = path.strip_prefix(&self.root).unwrap_or(path).to_path_buf();
+        matches
+            .into_iter()
+            .map(|m| (CodebaseNode::CodePattern(m.pattern), relative.clone()))
+            .collect()
+    }
+
+    /// Write the batch's patterns through `Storage::ingest_batch`
+    fn ingest(&self, sink: &IngestSink, found: Vec<(CodebaseNode, PathBuf)>) {
+        if found.is_empty() {
+            return;
+        }
+        let (inputs, systems): (Vec<IngestInput>, Vec<_>) = found
+            .iter()
+            .map(|(node, file)| {
+                let mut input = node.to_ingest_input(sink.codebase.as_deref());
+                let file = tags::file_tag(file);
+                if !input.tags.contains(&file) {
+                    input.tags.push(file);
+                }
+                (input, node.memory_system())
+            })
+            .unzip();
+
+        match sink.storage.ingest_batch(inputs) {
+            Ok(outcomes) => {
+                let mut created = 0;
+                for (outcome, system) in outcomes.iter().zip(systems) {
+                    if !outcome.returned_existing {
+                        let _ = sink.storage.set_memory_system(&outcome.node.id, system);
+                        created += 1;
+                    }
+                }
+                StatCounters::add(&self.stats.memories_ingested, created);
+            }
+            Err(e) => tracing::warn!("Watcher ingest of {} patterns failed: {}", found.len(), e),
+        }
+    }
+
+    /// Record the session's files as edited together
+    fn record_session(&self, session: &EditSession) {
+        if session.files.len() >= 2 {
+            let files = session.files_list();
+            if let Ok(mut tracker) = self.tracker.try_write() {
+                let _ = tracker.record_coedit(&files);
+            }
+        }
+    }
+}
+
 // ============================================================================
 // CODEBASE WATCHER
 // ============================================================================
@@ -207,6 +473,10 @@ pub struct CodebaseWatcher {
     shutdown_tx: Option<broadcast::Sender<()>>,
     /// Flag to signal watcher thread to stop
     running: Arc<AtomicBool>,
+    /// Event counters, kept across `watch` calls
+    stats: Arc<StatCounters>,
+    /// Where detected patterns are ingested, if anywhere
+    sink: Option<IngestSink>,
 }
 
 impl CodebaseWatcher {
@@ -231,9 +501,24 @@ impl CodebaseWatcher {
             watched_paths: Arc::new(RwLock::new(HashSet::new())),
             shutdown_tx: None,
             running: Arc::new(AtomicBool::new(false)),
+            stats: Arc::new(StatCounters::default()),
+            sink: None,
         }
     }
 
+    /// Ingest patterns detected in changed files into `storage`
+    ///
+    /// Applies to directories watched after the call. `codebase` names the
+    /// `codebase:<name>` tag.
+    pub fn set_storage(&mut self, storage: Arc<Storage>, codebase: Option<String>) {
+        self.sink = Some(IngestSink { storage, codebase });
+    }
+
+    /// Event counters since the watcher was created
+    pub fn stats(&self) -> WatcherStats {
+        self.stats.snapshot()
+    }
+
     /// Start watching a directory
     pub async fn watch(&mut self, path: &Path) -> Result<()> {
         let path = path.canonicalize()?;
@@ -253,23 +538,27 @@ impl CodebaseWatcher {
         let (shutdown_tx, mut shutdown_rx) = broadcast::channel::<()>(1);
         self.shutdown_tx = Some(shutdown_tx);
 
-        // Create event channel
-        let (event_tx, mut event_rx) = mpsc::channel::<FileEvent>(100);
+        // Batches of coalesced events, flushed by the watcher thread
+        let (batch_tx, mut batch_rx) = mpsc::channel::<Vec<FileEvent>>(100);
+        let debouncer = Arc::new(Mutex::new(Debouncer::default()));
 
         // Clone for move into watcher thread
         let config = self.config.clone();
         let watch_path = path.clone();
+        let stats = Arc::clone(&self.stats);
 
         // Set running flag to true and clone for thread
         self.running.store(true, Ordering::SeqCst);
         let running = Arc::clone(&self.running);
 
         // Spawn watcher thread
-        let event_tx_clone = event_tx.clone();
+        let thread_debouncer = Arc::clone(&debouncer);
         std::thread::spawn(move || {
             let config_notify = Config::default().with_poll_interval(config.debounce_interval);
+            let window = config.debounce_interval;
 
-            let tx = event_tx_clone.clone();
+            let callback_debouncer = Arc::clone(&thread_debouncer);
+            let callback_config = config.clone();
             let mut watcher = match RecommendedWatcher::new(
                 move |res: std::result::Result<Event, notify::Error>| {
                     if let Ok(event) = res {
@@ -278,7 +567,9 @@ impl CodebaseWatcher {
                             paths: event.paths,
                             timestamp: Utc::now(),
                         };
-                        let _ = tx.blocking_send(file_event);
+                        if let Ok(mut debouncer) = callback_debouncer.lock() {
+                            debouncer.push(file_event, &callback_config, &stats, Instant::now());
+                        }
                     }
                 },
                 config_notify,
@@ -295,16 +586,34 @@ impl CodebaseWatcher {
                 return;
             }
 
-            // Keep thread alive until shutdown signal
+            // Flush quiet paths until shutdown, then whatever is left
+            let tick = (window / 2).clamp(Duration::from_millis(10), Duration::from_millis(100));
             while running.load(Ordering::SeqCst) {
-                std::thread::sleep(Duration::from_millis(100));
+                std::thread::sleep(tick);
+                let batch = match thread_debouncer.lock() {
+                    Ok(mut debouncer) => debouncer.drain_ready(Instant::now(), window),
+                    Err(_) => break,
+                };
+                if !batch.is_empty() && batch_tx.blocking_send(batch).is_err() {
+                    return;
+                }
+            }
+            if let Ok(mut debouncer) = thread_debouncer.lock() {
+                let batch = debouncer.drain_all();
+                if !batch.is_empty() {
+                    let _ = batch_tx.blocking_send(batch);
+                }
             }
         });
 
-        // Clone for move into handler task
-        let tracker = Arc::clone(&self.tracker);
-        let detector = Arc::clone(&self.detector);
-        let config = self.config.clone();
+        let processor = BatchProcessor {
+            tracker: Arc::clone(&self.tracker),
+            detector: Arc::clone(&self.detector),
+            config: self.config.clone(),
+            sink: self.sink.clone(),
+            stats: Arc::clone(&self.stats),
+            root: path.clone(),
+        };
 
         // Spawn event handler task
         tokio::spawn(async move {
@@ -313,56 +622,19 @@ impl CodebaseWatcher {
 
             loop {
                 tokio::select! {
-                    Some(event) = event_rx.recv() => {
+                    Some(batch) = batch_rx.recv() => {
                         // Check session expiry
                         if session.is_expired(session_timeout) {
                             // Record co-edits from expired session
-                            if session.files.len() >= 2 {
-                                let files = session.files_list();
-                                if let Ok(mut tracker) = tracker.try_write() {
-                                    let _ = tracker.record_coedit(&files);
-                                }
-                            }
+                            processor.record_session(&session);
                             session = EditSession::new();
                         }
 
-                        // Process event
-                        for path in &event.paths {
-                            if Self::should_process(path, &config) {
-                                match event.kind {
-                                    FileEventKind::Modified | FileEventKind::Created => {
-                                        // Track in session
-                                        if config.track_relationships {
-                                            session.add_file(path.clone());
-                                        }
-
-                                        // Detect patterns if enabled
-                                        if config.detect_patterns {
-                                            if let Ok(content) = std::fs::read_to_string(path) {
-                                                let language = Self::detect_language(path);
-                                                if let Ok(detector) = detector.try_read() {
-                                                    let _ = detector.detect_patterns(&content, &language);
-                                                }
-                                            }
-                                        }
-                                    }
-                                    FileEventKind::Deleted => {
-                                        // File was deleted, remove from session
-                                        session.files.remove(path);
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        }
+                        processor.process(batch, &mut session).await;
                     }
                     _ = shutdown_rx.recv() => {
                         // Finalize session before shutdown
-                        if session.files.len() >= 2 {
-                            let files = session.files_list();
-                            if let Ok(mut tracker) = tracker.try_write() {
-                                let _ = tracker.record_coedit(&files);
-                            }
-                        }
+                        processor.record_session(&session);
                         break;
                     }
                 }
@@ -726,4 +998,123 @@ mod tests {
         assert!(config.detect_patterns);
         assert!(config.track_relationships);
     }
+
+    #[test]
+    fn test_debouncer_coalesces_per_path() {
+        let config = WatcherConfig::default();
+        let stats = StatCounters::default();
+        let mut debouncer = Debouncer::default();
+        let start = Instant::now();
+        let event = |kind, paths: &[&str]| FileEvent {
+            kind,
+            paths: paths.iter().map(PathBuf::from).collect(),
+            timestamp: Utc::now(),
+        };
+
+        // A build storm: repeated saves, target/ output and a lock file
+        for _ in 0..5 {
+            debouncer.push(event(FileEventKind::Modified, &["/p/src/lib.rs"]), &config, &stats, start);
+        }
+        debouncer.push(
+            event(FileEventKind::Created, &["/p/target/debug/out.rs", "/p/Cargo.lock"]),
+            &config,
+            &stats,
+            start,
+        );
+        debouncer.push(event(FileEventKind::Created, &["/p/src/new.rs"]), &config, &stats, start);
+        debouncer.push(
+            event(FileEventKind::Modified, &["/p/src/new.rs"]),
+            &config,
+            &stats,
+            start + Duration::from_millis(400),
+        );
+
+        let window = Duration::from_millis(500);
+        let ready = debouncer.drain_ready(start + Duration::from_millis(500), window);
+        assert_eq!(ready.len(), 1, "new.rs changed again and is not quiet yet");
+        assert_eq!(ready[0].paths, vec![PathBuf::from("/p/src/lib.rs")]);
+        assert_eq!(ready[0].kind, FileEventKind::Modified);
+
+        let ready = debouncer.drain_ready(start + Duration::from_millis(900), window);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].kind, FileEventKind::Created, "created then modified is a creation");
+        assert!(debouncer.drain_all().is_empty());
+
+        assert_eq!(
+            stats.snapshot(),
+            WatcherStats {
+                events_received: 9,
+                events_ignored: 2,
+                events_coalesced: 5,
+                ..Default::default()
+            }
+        );
+
+        assert_eq!(coalesce(FileEventKind::Modified, FileEventKind::Deleted), FileEventKind::Deleted);
+        assert_eq!(coalesce(FileEventKind::Deleted, FileEventKind::Created), FileEventKind::Modified);
+        assert_eq!(coalesce(FileEventKind::Modified, FileEventKind::Accessed), FileEventKind::Modified);
+    }
+
+    #[test]
+    fn test_batch_ingests_detected_patterns() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(Storage::new(Some(dir.path().join("watch.db"))).unwrap());
+        let root = dir.path().join("repo");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        let code = "// Retry failed calls with exponential backoff\nfn retry_loop() {}\n";
+        for name in ["a.rs", "b.rs"] {
+            std::fs::write(root.join("src").join(name), code).unwrap();
+        }
+
+        let mut detector = PatternDetector::new();
+        detector
+            .learn_pattern(
+                super::super::CodePattern::new(
+                    "retry".into(),
+                    "Retry Backoff Loop".into(),
+                    "Retry failed calls with exponential backoff".into(),
+                    "Flaky network calls".into(),
+                )
+                .with_language("rust".into()),
+            )
+            .unwrap();
+        let stats = Arc::new(StatCounters::default());
+        let processor = BatchProcessor {
+            tracker: Arc::new(RwLock::new(RelationshipTracker::new())),
+            detector: Arc::new(RwLock::new(detector)),
+            config: WatcherConfig::default(),
+            sink: Some(IngestSink {
+                storage: Arc::clone(&storage),
+                codebase: Some("repo".to_string()),
+            }),
+            stats: Arc::clone(&stats),
+            root: root.clone(),
+        };
+        let batch: Vec<FileEvent> = ["a.rs", "b.rs"]
+            .iter()
+            .map(|name| FileEvent {
+                kind: FileEventKind::Modified,
+                paths: vec![root.join("src").join(name)],
+                timestamp: Utc::now(),
+            })
+            .collect();
+
+        let mut session = EditSession::new();
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(processor.process(batch, &mut session));
+
+        assert_eq!(session.files.len(), 2);
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.batches, 1);
+        assert_eq!(snapshot.changes_processed, 2);
+        assert_eq!(snapshot.memories_ingested, 1);
+
+        let patterns = storage
+            .get_nodes_by_type_and_tag("pattern", Some("codebase:repo"), 10)
+            .unwrap();
+        assert_eq!(patterns.len(), 1, "the second file's copy of a pattern is the same memory");
+        assert!(patterns[0].tags.contains(&"file:src/a.rs".to_string()));
+    }
 }
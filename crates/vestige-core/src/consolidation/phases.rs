@@ -22,6 +22,9 @@ use crate::neuroscience::emotional_memory::{EmotionalMemory, EmotionCategory};
 use crate::neuroscience::importance_signals::ImportanceSignals;
 use crate::neuroscience::synaptic_tagging::SynapticTaggingSystem;
 
+/// Integration: ranking bonus for insights drawing on a flashbulb memory
+const FLASHBULB_INSIGHT_BONUS: f64 = 0.5;
+
 // ============================================================================
 // PHASE RESULTS
 // ============================================================================
@@ -116,6 +119,18 @@ pub enum CreativeConnectionType {
     Contradictory,
 }
 
+impl CreativeConnectionType {
+    /// Link type recorded when the connection is persisted
+    pub fn as_link_type(&self) -> &'static str {
+        match self {
+            CreativeConnectionType::CrossDomain => "cross_domain",
+            CreativeConnectionType::Causal => "causal",
+            CreativeConnectionType::Complementary => "complementary",
+            CreativeConnectionType::Contradictory => "contradiction",
+        }
+    }
+}
+
 /// A validated insight from the Integration phase
 #[derive(Debug, Clone)]
 pub struct DreamInsight {
@@ -242,11 +257,16 @@ impl DreamEngine {
             let score = importance_signals.compute_importance(&node.content, &ctx);
             let importance = score.composite;
 
-            // Evaluate emotional content
+            // Evaluate emotional content, keeping what was recorded at encoding
             let emotional = emotional_memory.evaluate_content(&node.content);
+            let is_flashbulb = emotional.is_flashbulb || node.flashbulb == Some(true);
 
-            // Categorize
-            let category = self.categorize_memory(node, importance, &emotional.category);
+            // Categorize (flashbulb memories are always emotional)
+            let category = if is_flashbulb {
+                TriageCategory::Emotional
+            } else {
+                self.categorize_memory(node, importance, &emotional.category)
+            };
 
             triaged.push(TriagedMemory {
                 id: node.id.clone(),
@@ -256,13 +276,18 @@ impl DreamEngine {
                 tags: node.tags.clone(),
                 created_at: node.created_at,
                 retention_strength: node.retention_strength,
-                emotional_valence: emotional.valence,
-                is_flashbulb: emotional.is_flashbulb,
+                emotional_valence: node.emotional_valence.unwrap_or(emotional.valence),
+                is_flashbulb,
             });
         }
 
-        // Sort by importance (highest first)
-        triaged.sort_by(|a, b| b.importance.partial_cmp(&a.importance).unwrap_or(std::cmp::Ordering::Equal));
+        // Sort flashbulb memories first, then by importance (highest first), so
+        // they lead the replay queue and are sampled first in REM
+        triaged.sort_by(|a, b| {
+            b.is_flashbulb
+                .cmp(&a.is_flashbulb)
+                .then(b.importance.partial_cmp(&a.importance).unwrap_or(std::cmp::Ordering::Equal))
+        });
 
         // Build replay queue: 70% high-value, 30% random noise floor
         let high_value_count = (triaged.len() as f64 * self.high_value_ratio).ceil() as usize;
@@ -313,7 +338,10 @@ impl DreamEngine {
 
         let flashbulb_count = triaged.iter().filter(|m| m.is_flashbulb).count();
         if flashbulb_count > 0 {
-            actions.push(format!("Flashbulb memories detected: {}", flashbulb_count));
+            actions.push(format!(
+                "Flashbulb memories prioritized for replay: {}",
+                flashbulb_count
+            ));
         }
 
         let phase = PhaseResult {
@@ -515,20 +543,26 @@ impl DreamEngine {
         ));
 
         // Emotional processing: reduce intensity of error/frustration memories
+        // and re-encode flashbulb memories for tag-and-capture
+        let mut intensity_reduced = 0;
         for tm in triaged {
             if tm.category == TriageCategory::Emotional && tm.emotional_valence < -0.3 {
                 // Process negative emotional memories — extract the lesson, reduce raw emotion
                 // In practice: the insight extraction above captures the lesson,
                 // and we record the emotional processing for the engine
                 emotional_memory.record_encoding(&tm.id, tm.emotional_valence * 0.7, 0.3);
+                intensity_reduced += 1;
+                emotional_processed += 1;
+            } else if tm.is_flashbulb {
+                emotional_memory.record_encoding(&tm.id, tm.emotional_valence, 1.0);
                 emotional_processed += 1;
             }
         }
 
         if emotional_processed > 0 {
             actions.push(format!(
-                "Emotional processing: {} negative memories had intensity reduced",
-                emotional_processed
+                "Emotional processing: {} memories replayed, {} negative ones had intensity reduced",
+                emotional_processed, intensity_reduced
             ));
         }
 
@@ -738,12 +772,17 @@ impl DreamEngine {
             }
         });
 
-        // Sort by confidence * novelty (most interesting first)
-        insights.sort_by(|a, b| {
-            let score_a = a.confidence * a.novelty;
-            let score_b = b.confidence * b.novelty;
-            score_b.partial_cmp(&score_a).unwrap_or(std::cmp::Ordering::Equal)
-        });
+        // Sort by confidence * novelty (most interesting first), with insights
+        // drawing on flashbulb memories ahead of the rest
+        let flashbulbs: HashSet<&str> = triaged.iter()
+            .filter(|m| m.is_flashbulb)
+            .map(|m| m.id.as_str())
+            .collect();
+        let score = |i: &DreamInsight| {
+            let emotional = i.source_memory_ids.iter().any(|id| flashbulbs.contains(id.as_str()));
+            i.confidence * i.novelty + if emotional { FLASHBULB_INSIGHT_BONUS } else { 0.0 }
+        };
+        insights.sort_by(|a, b| score(b).partial_cmp(&score(a)).unwrap_or(std::cmp::Ordering::Equal));
 
         // Cap at 20 insights
         insights.truncate(20);
@@ -840,9 +879,11 @@ mod tests {
             times_useful: None,
             emotional_valence: None,
             flashbulb: None,
+            importance: None,
             temporal_level: None,
             has_embedding: None,
             embedding_model: None,
+            namespace: None,
         }
     }
 
@@ -1183,4 +1224,33 @@ mod tests {
         // Flashbulb detection depends on importance signals — just verify triage runs
         assert_eq!(phase.phase, DreamPhase::Nrem1);
     }
+
+    #[test]
+    fn test_recorded_flashbulbs_lead_replay_and_count_as_emotional() {
+        let engine = DreamEngine::new();
+        let mut emotional = EmotionalMemory::new();
+        let importance = ImportanceSignals::new();
+        let mut synaptic = SynapticTaggingSystem::new();
+
+        let mut memories: Vec<KnowledgeNode> = (0..8).map(|i| {
+            make_test_node(
+                &format!("mem-{}", i),
+                &format!("Novel architecture decision number {} about caching layers", i),
+                &["architecture"],
+            )
+        }).collect();
+        // Flagged at encoding, with content that reads as neutral now
+        let mut flash = make_test_node("flash-1", "The release went out on Friday", &["release"]);
+        flash.flashbulb = Some(true);
+        flash.emotional_valence = Some(0.6);
+        memories.push(flash);
+
+        let (triaged, queue, _phase) = engine.phase_nrem1(&memories, &mut emotional, &importance);
+        assert_eq!(triaged[0].id, "flash-1");
+        assert_eq!(triaged[0].category, TriageCategory::Emotional);
+        assert_eq!(queue[0], "flash-1");
+
+        let result = engine.run(&memories, &mut emotional, &importance, &mut synaptic);
+        assert_eq!(result.emotional_processed, 1);
+    }
 }
@@ -189,9 +189,18 @@ impl ConsolidationRun {
             duration_ms: self.start_time.elapsed().as_millis() as i64,
             embeddings_generated: self.embeddings_generated,
             duplicates_merged: 0,
+            dedup_clusters_examined: 0,
+            dedup_clusters_merged: 0,
             neighbors_reinforced: 0,
+            pins_expired: 0,
             activations_computed: 0,
             w20_optimized: None,
+            steps: Default::default(),
+            step_timings: Vec::new(),
+            step_errors: Vec::new(),
+            skipped: None,
+            stopped_early: None,
+            steps_not_run: Vec::new(),
         }
     }
 }
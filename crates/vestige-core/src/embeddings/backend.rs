@@ -0,0 +1,60 @@
+//! Pluggable Embedding Backends
+//!
+//! `EmbeddingService` handles input limits, normalization and Matryoshka
+//! truncation; an `EmbeddingBackend` only turns text into raw vectors. The
+//! default backend runs nomic-embed-text locally through fastembed, but any
+//! model (a remote HTTP API, a different local runtime) can be plugged in
+//! with `EmbeddingService::with_backend` / `Storage::with_embedding_backend`.
+//!
+//! See `examples/remote_embedding_backend.rs` for a backend that calls an
+//! OpenAI-compatible `/v1/embeddings` endpoint.
+
+use super::local::EmbeddingError;
+
+/// Source of text embeddings
+///
+/// Implementations must be cheap to call from several threads at once and
+/// always return vectors of exactly `dimensions()` components.
+pub trait EmbeddingBackend: Send + Sync {
+    /// Embed a single text at the backend's full output width
+    fn embed(&self, text: &str) -> Result<Vec<f32>, EmbeddingError>;
+
+    /// Embed several texts, in order
+    ///
+    /// The default embeds them one at a time; override it when the model or
+    /// API has a batch call.
+    fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        texts.iter().map(|text| self.embed(text)).collect()
+    }
+
+    /// Width of the vectors `embed` returns
+    fn dimensions(&self) -> usize;
+
+    /// Model identifier recorded alongside every stored embedding
+    fn model_name(&self) -> &str;
+
+    /// Whether `embed` can currently succeed
+    fn is_ready(&self) -> bool;
+
+    /// Prepare the backend (load or download a model, check credentials)
+    ///
+    /// The default only reports `is_ready`; override it to surface the
+    /// underlying failure.
+    fn init(&self) -> Result<(), EmbeddingError> {
+        if self.is_ready() {
+            Ok(())
+        } else {
+            Err(EmbeddingError::ModelInit(format!(
+                "embedding backend {} is not ready",
+                self.model_name()
+            )))
+        }
+    }
+
+    /// Widths the output stays meaningful at when truncated (Matryoshka)
+    ///
+    /// Defaults to the full width only, i.e. no truncation.
+    fn truncation_dimensions(&self) -> Vec<usize> {
+        vec![self.dimensions()]
+    }
+}
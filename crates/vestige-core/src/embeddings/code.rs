@@ -8,6 +8,7 @@
 //! Future: Support for code-specific embedding models.
 
 use super::local::{Embedding, EmbeddingError, EmbeddingService};
+use crate::advanced::adaptive_embedding::Language;
 
 // ============================================================================
 // CODE EMBEDDING
@@ -53,12 +54,16 @@ impl CodeEmbedding {
         language: Option<&str>,
     ) -> Result<Embedding, EmbeddingError> {
         // Preprocess code for better embedding
-        let processed = self.preprocess_code(code, language);
+        let processed = Self::preprocess(code, language);
         self.service.embed(&processed)
     }
 
-    /// Preprocess code for embedding
-    fn preprocess_code(&self, code: &str, language: Option<&str>) -> String {
+    /// Text a model should embed for `code`
+    ///
+    /// Adds a `[LANG]` hint and drops blank and comment-only lines. Storage
+    /// uses this to embed code with its own text model, so code and prose
+    /// vectors stay comparable.
+    pub fn preprocess(code: &str, language: Option<&str>) -> String {
         let mut result = String::new();
 
         // Add language hint if available
@@ -67,33 +72,25 @@ impl CodeEmbedding {
         }
 
         // Clean and normalize code
-        let cleaned = self.clean_code(code);
+        let lang = language.map_or(Language::Unknown, Language::from_name);
+        let cleaned = Self::clean_code(code, &lang);
         result.push_str(&cleaned);
 
         result
     }
 
     /// Clean code by removing excessive whitespace and normalizing
-    fn clean_code(&self, code: &str) -> String {
+    fn clean_code(code: &str, lang: &Language) -> String {
         let lines: Vec<&str> = code
             .lines()
             .map(|l| l.trim())
             .filter(|l| !l.is_empty())
-            .filter(|l| !self.is_comment_only(l))
+            .filter(|l| !lang.is_comment_line(l))
             .collect();
 
         lines.join(" ")
     }
 
-    /// Check if a line is only a comment
-    fn is_comment_only(&self, line: &str) -> bool {
-        let trimmed = line.trim();
-        trimmed.starts_with("//")
-            || trimmed.starts_with('#')
-            || trimmed.starts_with("/*")
-            || trimmed.starts_with('*')
-    }
-
     /// Extract semantic chunks from code
     ///
     /// Splits code into meaningful chunks for separate embedding.
@@ -230,17 +227,30 @@ mod tests {
 
     #[test]
     fn test_clean_code() {
-        let ce = CodeEmbedding::new();
         let code = r#"
             // This is a comment
+            #[inline]
             fn hello() {
                 println!("Hello");
             }
         "#;
 
-        let cleaned = ce.clean_code(code);
+        let cleaned = CodeEmbedding::clean_code(code, &Language::Rust);
         assert!(!cleaned.contains("// This is a comment"));
+        assert!(cleaned.contains("#[inline]"));
         assert!(cleaned.contains("fn hello()"));
+        assert!(CodeEmbedding::preprocess(code, Some("rust")).starts_with("[RUST] #[inline]"));
+    }
+
+    #[test]
+    fn test_clean_code_keeps_preprocessor_and_derefs() {
+        let code = "/* Swap in place\n * without a temporary\n */\n#include <stdio.h>\n#define MAX 4\n*ptr = MAX;";
+        let cleaned = CodeEmbedding::clean_code(code, &Language::Cpp);
+        assert_eq!(cleaned, "#include <stdio.h> #define MAX 4 *ptr = MAX;");
+
+        let script = "#!/usr/bin/env python\n# Greeting\nprint('hi')";
+        let cleaned = CodeEmbedding::clean_code(script, &Language::Python);
+        assert_eq!(cleaned, "#!/usr/bin/env python print('hi')");
     }
 
     #[test]
@@ -9,6 +9,8 @@
 //!   Enable with `nomic-v2` feature flag + `metal` for Apple Silicon acceleration.
 
 use fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
+use super::backend::EmbeddingBackend;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Mutex, OnceLock};
 
 // ============================================================================
@@ -20,6 +22,19 @@ use std::sync::{Mutex, OnceLock};
 /// (Matryoshka Representation Learning — the first N dims ARE the N-dim representation)
 pub const EMBEDDING_DIMENSIONS: usize = 256;
 
+/// Dimensions of the model's full (untruncated) output
+pub const MODEL_DIMENSIONS: usize = 768;
+
+/// Matryoshka operating points the model was trained to support
+///
+/// Smaller vectors search faster and take less memory at some cost in
+/// recall: nomic-embed-text v1.5 loses roughly 2% MTEB retrieval quality at
+/// 256 dims versus 768, while HNSW search time and index size scale about
+/// linearly with dimensions (~3x between the two). Pick with
+/// `VESTIGE_EMBEDDING_DIMS`; 256 suits most personal memory stores, 768
+/// large or highly similar corpora where near-duplicates must rank apart.
+pub const MATRYOSHKA_DIMENSIONS: [usize; 4] = [256, 384, 512, 768];
+
 /// Maximum text length for embedding (truncated if longer)
 pub const MAX_TEXT_LENGTH: usize = 8192;
 
@@ -192,13 +207,110 @@ impl Embedding {
     }
 }
 
+// ============================================================================
+// FASTEMBED BACKEND
+// ============================================================================
+
+/// The default backend: nomic-embed-text run locally through fastembed
+///
+/// Returns the model's full `MODEL_DIMENSIONS` output, which supports
+/// truncation to any of `MATRYOSHKA_DIMENSIONS`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FastembedBackend;
+
+impl EmbeddingBackend for FastembedBackend {
+    fn embed(&self, text: &str) -> Result<Vec<f32>, EmbeddingError> {
+        self.embed_batch(&[text])?
+            .pop()
+            .ok_or_else(|| EmbeddingError::EmbeddingFailed("No embedding generated".to_string()))
+    }
+
+    fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        let mut model = get_model()?;
+        let mut all_embeddings = Vec::with_capacity(texts.len());
+
+        // Process in batches for efficiency
+        for chunk in texts.chunks(BATCH_SIZE) {
+            let embeddings = model
+                .embed(chunk, None)
+                .map_err(|e| EmbeddingError::EmbeddingFailed(e.to_string()))?;
+            all_embeddings.extend(embeddings);
+        }
+
+        Ok(all_embeddings)
+    }
+
+    fn dimensions(&self) -> usize {
+        MODEL_DIMENSIONS
+    }
+
+    fn model_name(&self) -> &str {
+        #[cfg(feature = "nomic-v2")]
+        { "nomic-ai/nomic-embed-text-v2-moe" }
+        #[cfg(not(feature = "nomic-v2"))]
+        { "nomic-ai/nomic-embed-text-v1.5" }
+    }
+
+    fn is_ready(&self) -> bool {
+        match get_model() {
+            Ok(_) => true,
+            Err(e) => {
+                tracing::warn!("Embedding model not ready: {}", e);
+                false
+            }
+        }
+    }
+
+    fn init(&self) -> Result<(), EmbeddingError> {
+        get_model().map(|_| ()) // Loads (downloading if necessary) and returns any init errors
+    }
+
+    fn truncation_dimensions(&self) -> Vec<usize> {
+        MATRYOSHKA_DIMENSIONS.to_vec()
+    }
+}
+
+/// Backend that never embeds, behind `EmbeddingService::disabled`
+///
+/// Has the fastembed model's shape so databases embedded with it still
+/// open, but is never ready and fails every call without loading anything.
+#[derive(Debug, Clone, Copy, Default)]
+struct DisabledBackend;
+
+impl EmbeddingBackend for DisabledBackend {
+    fn embed(&self, _text: &str) -> Result<Vec<f32>, EmbeddingError> {
+        Err(EmbeddingError::ModelInit("embeddings are disabled".to_string()))
+    }
+
+    fn dimensions(&self) -> usize {
+        MODEL_DIMENSIONS
+    }
+
+    fn model_name(&self) -> &str {
+        "disabled"
+    }
+
+    fn is_ready(&self) -> bool {
+        false
+    }
+
+    fn truncation_dimensions(&self) -> Vec<usize> {
+        MATRYOSHKA_DIMENSIONS.to_vec()
+    }
+}
+
 // ============================================================================
 // EMBEDDING SERVICE
 // ============================================================================
 
 /// Service for generating and managing embeddings
+///
+/// Wraps an `EmbeddingBackend` (fastembed by default). `embed` and
+/// `embed_batch` return unit vectors truncated to the configured Matryoshka
+/// dimensions; `embed_full` returns the backend's full output.
 pub struct EmbeddingService {
-    _unused: (),
+    backend: Box<dyn EmbeddingBackend>,
+    dimensions: AtomicUsize,
 }
 
 impl Default for EmbeddingService {
@@ -208,76 +320,130 @@ impl Default for EmbeddingService {
 }
 
 impl EmbeddingService {
-    /// Create a new embedding service
+    /// Create a new embedding service using the local fastembed model
     pub fn new() -> Self {
+        Self::with_backend(Box::new(FastembedBackend))
+    }
+
+    /// Create a service embedding through `backend`
+    ///
+    /// Starts at `EMBEDDING_DIMENSIONS` when the backend can be truncated to
+    /// it, otherwise at the backend's full width.
+    pub fn with_backend(backend: Box<dyn EmbeddingBackend>) -> Self {
+        let dimensions = if backend.truncation_dimensions().contains(&EMBEDDING_DIMENSIONS) {
+            EMBEDDING_DIMENSIONS
+        } else {
+            backend.dimensions()
+        };
         Self {
-            _unused: (),
+            backend,
+            dimensions: AtomicUsize::new(dimensions),
         }
     }
 
+    /// Create a service backed by deterministic hash-based vectors
+    ///
+    /// Never loads the model, so tests and offline CI can exercise embedding
+    /// code paths without network access. Inject it with
+    /// `Storage::with_embedding_service`.
+    #[cfg(any(test, feature = "test-embeddings"))]
+    pub fn mock() -> Self {
+        Self::with_backend(Box::new(super::mock::MockEmbeddingService::new()))
+    }
+
+    /// Create a service that never embeds
+    ///
+    /// `is_ready` is always false, so storage skips embedding generation and
+    /// semantic search reports `EmbeddingUnavailable`.
+    pub fn disabled() -> Self {
+        Self::with_backend(Box::new(DisabledBackend))
+    }
+
     /// Check if the model is ready
     pub fn is_ready(&self) -> bool {
-        match get_model() {
-            Ok(_) => true,
-            Err(e) => {
-                tracing::warn!("Embedding model not ready: {}", e);
-                false
-            }
-        }
+        self.backend.is_ready()
     }
 
     /// Check if the model is ready and return the error if not
     pub fn check_ready(&self) -> Result<(), EmbeddingError> {
-        get_model().map(|_| ())
+        self.backend.init()
     }
 
     /// Initialize the model (downloads if necessary)
     pub fn init(&self) -> Result<(), EmbeddingError> {
-        let _model = get_model()?; // Ensures model is loaded and returns any init errors
-        Ok(())
+        self.backend.init()
     }
 
     /// Get the model name
-    pub fn model_name(&self) -> &'static str {
-        #[cfg(feature = "nomic-v2")]
-        { "nomic-ai/nomic-embed-text-v2-moe" }
-        #[cfg(not(feature = "nomic-v2"))]
-        { "nomic-ai/nomic-embed-text-v1.5" }
+    pub fn model_name(&self) -> &str {
+        self.backend.model_name()
+    }
+
+    /// Width of the backend's untruncated output
+    pub fn full_dimensions(&self) -> usize {
+        self.backend.dimensions()
+    }
+
+    /// Dimensions `set_dimensions` accepts for this backend
+    pub fn supported_dimensions(&self) -> Vec<usize> {
+        self.backend.truncation_dimensions()
     }
 
     /// Get the embedding dimensions
     pub fn dimensions(&self) -> usize {
-        EMBEDDING_DIMENSIONS
+        self.dimensions.load(Ordering::Relaxed)
+    }
+
+    /// Change the Matryoshka operating point (one of `supported_dimensions`)
+    pub fn set_dimensions(&self, dimensions: usize) -> Result<(), EmbeddingError> {
+        let supported = self.supported_dimensions();
+        if !supported.contains(&dimensions) {
+            return Err(EmbeddingError::InvalidInput(format!(
+                "unsupported embedding dimensions {} for {} (expected one of {:?})",
+                dimensions,
+                self.model_name(),
+                supported
+            )));
+        }
+        self.dimensions.store(dimensions, Ordering::Relaxed);
+        Ok(())
     }
 
     /// Generate embedding for a single text
     pub fn embed(&self, text: &str) -> Result<Embedding, EmbeddingError> {
+        self.embed_truncated(text, self.dimensions())
+    }
+
+    /// Generate an embedding truncated to `dimensions` (one of
+    /// `supported_dimensions`), whatever the service's current setting
+    pub fn embed_truncated(&self, text: &str, dimensions: usize) -> Result<Embedding, EmbeddingError> {
+        let supported = self.supported_dimensions();
+        if !supported.contains(&dimensions) {
+            return Err(EmbeddingError::InvalidInput(format!(
+                "unsupported embedding dimensions {} for {} (expected one of {:?})",
+                dimensions,
+                self.model_name(),
+                supported
+            )));
+        }
+        let full = self.embed_full(text)?;
+        Ok(Embedding::new(matryoshka_truncate_to(full.vector, dimensions)))
+    }
+
+    /// Generate the full, untruncated embedding for a single text
+    ///
+    /// Stored alongside the truncated index vector so the operating point can
+    /// later be changed without re-running the model.
+    pub fn embed_full(&self, text: &str) -> Result<Embedding, EmbeddingError> {
         if text.is_empty() {
             return Err(EmbeddingError::InvalidInput(
                 "Text cannot be empty".to_string(),
             ));
         }
 
-        let mut model = get_model()?;
-
-        // Truncate if too long
-        let text = if text.len() > MAX_TEXT_LENGTH {
-            &text[..MAX_TEXT_LENGTH]
-        } else {
-            text
-        };
-
-        let embeddings = model
-            .embed(vec![text], None)
-            .map_err(|e| EmbeddingError::EmbeddingFailed(e.to_string()))?;
-
-        if embeddings.is_empty() {
-            return Err(EmbeddingError::EmbeddingFailed(
-                "No embedding generated".to_string(),
-            ));
-        }
-
-        Ok(Embedding::new(matryoshka_truncate(embeddings[0].clone())))
+        let vector = self.backend.embed(clip_text(text))?;
+        let vector = self.checked_width(vector)?;
+        Ok(Embedding::new(matryoshka_truncate_to(vector, self.full_dimensions())))
     }
 
     /// Generate embeddings for multiple texts (batch processing)
@@ -286,32 +452,37 @@ impl EmbeddingService {
             return Ok(vec![]);
         }
 
-        let mut model = get_model()?;
-        let mut all_embeddings = Vec::with_capacity(texts.len());
-
-        // Process in batches for efficiency
-        for chunk in texts.chunks(BATCH_SIZE) {
-            let truncated: Vec<&str> = chunk
-                .iter()
-                .map(|t| {
-                    if t.len() > MAX_TEXT_LENGTH {
-                        &t[..MAX_TEXT_LENGTH]
-                    } else {
-                        *t
-                    }
-                })
-                .collect();
-
-            let embeddings = model
-                .embed(truncated, None)
-                .map_err(|e| EmbeddingError::EmbeddingFailed(e.to_string()))?;
-
-            for emb in embeddings {
-                all_embeddings.push(Embedding::new(matryoshka_truncate(emb)));
-            }
+        let clipped: Vec<&str> = texts.iter().map(|t| clip_text(t)).collect();
+        let vectors = self.backend.embed_batch(&clipped)?;
+        if vectors.len() != texts.len() {
+            return Err(EmbeddingError::EmbeddingFailed(format!(
+                "{} returned {} embeddings for {} texts",
+                self.model_name(),
+                vectors.len(),
+                texts.len()
+            )));
         }
 
-        Ok(all_embeddings)
+        vectors
+            .into_iter()
+            .map(|vector| {
+                let vector = self.checked_width(vector)?;
+                Ok(Embedding::new(matryoshka_truncate_to(vector, self.dimensions())))
+            })
+            .collect()
+    }
+
+    /// Reject backend output whose width differs from what it declared
+    fn checked_width(&self, vector: Vec<f32>) -> Result<Vec<f32>, EmbeddingError> {
+        if vector.len() != self.full_dimensions() {
+            return Err(EmbeddingError::EmbeddingFailed(format!(
+                "{} returned a {}-dimensional embedding but declares {} dimensions",
+                self.model_name(),
+                vector.len(),
+                self.full_dimensions()
+            )));
+        }
+        Ok(vector)
     }
 
     /// Find most similar embeddings to a query
@@ -334,6 +505,15 @@ impl EmbeddingService {
     }
 }
 
+/// Cut `text` to at most `MAX_TEXT_LENGTH` bytes, on a char boundary
+fn clip_text(text: &str) -> &str {
+    let mut end = text.len().min(MAX_TEXT_LENGTH);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
 // ============================================================================
 // SIMILARITY FUNCTIONS
 // ============================================================================
@@ -344,9 +524,17 @@ impl EmbeddingService {
 /// meaning the first N dimensions of the 768-dim output ARE a valid
 /// N-dimensional embedding with minimal quality loss (~2% on MTEB for 256-dim).
 #[inline]
-pub fn matryoshka_truncate(mut vector: Vec<f32>) -> Vec<f32> {
-    if vector.len() > EMBEDDING_DIMENSIONS {
-        vector.truncate(EMBEDDING_DIMENSIONS);
+pub fn matryoshka_truncate(vector: Vec<f32>) -> Vec<f32> {
+    matryoshka_truncate_to(vector, EMBEDDING_DIMENSIONS)
+}
+
+/// Truncate to `dimensions` and L2-normalize
+///
+/// Vectors already shorter than `dimensions` are only normalized.
+#[inline]
+pub fn matryoshka_truncate_to(mut vector: Vec<f32>, dimensions: usize) -> Vec<f32> {
+    if vector.len() > dimensions {
+        vector.truncate(dimensions);
     }
     // L2-normalize the truncated vector
     let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
@@ -494,4 +682,89 @@ mod tests {
         assert_eq!(results[0].0, 0); // First candidate should be most similar
         assert!((results[0].1 - 1.0).abs() < 0.0001);
     }
+
+    #[test]
+    fn test_matryoshka_truncate_to_operating_point() {
+        let full: Vec<f32> = (0..MODEL_DIMENSIONS).map(|i| 1.0 / (1.0 + i as f32)).collect();
+        for dims in MATRYOSHKA_DIMENSIONS {
+            let truncated = Embedding::new(matryoshka_truncate_to(full.clone(), dims));
+            assert_eq!(truncated.dimensions, dims);
+            assert!(truncated.is_normalized());
+        }
+        // Shorter vectors are never padded
+        assert_eq!(matryoshka_truncate_to(vec![3.0, 4.0], 256), vec![0.6, 0.8]);
+
+        let service = EmbeddingService::new();
+        assert_eq!(service.dimensions(), EMBEDDING_DIMENSIONS);
+        service.set_dimensions(512).unwrap();
+        assert_eq!(service.dimensions(), 512);
+        assert!(service.set_dimensions(300).is_err());
+        assert_eq!(service.dimensions(), 512);
+    }
+
+    /// Mean share of the 768-dim top-k that a 256-dim search also returns
+    fn mean_top_k_overlap(service: &EmbeddingService, corpus: &[&str], queries: &[&str], k: usize) -> f64 {
+        let top_k = |dimensions: usize, query: &str| -> Vec<usize> {
+            let q = service.embed_truncated(query, dimensions).unwrap();
+            let docs: Vec<Embedding> = corpus
+                .iter()
+                .map(|doc| service.embed_truncated(doc, dimensions).unwrap())
+                .collect();
+            service.find_similar(&q, &docs, k).into_iter().map(|(i, _)| i).collect()
+        };
+        let total: f64 = queries
+            .iter()
+            .map(|query| {
+                let full = top_k(768, query);
+                let truncated = top_k(256, query);
+                full.iter().filter(|i| truncated.contains(i)).count() as f64 / k as f64
+            })
+            .sum();
+        total / queries.len() as f64
+    }
+
+    const OVERLAP_CORPUS: [&str; 12] = [
+        "The deploy pipeline runs integration tests before every release",
+        "Connection pool exhaustion caused the checkout outage on Friday",
+        "Rust ownership rules prevent data races at compile time",
+        "The payment worker retries failed charges three times",
+        "Staging mirrors production data every Sunday night",
+        "Feature flags are removed one sprint after full rollout",
+        "The search service caches query embeddings in memory",
+        "Database migrations must be backwards compatible for one release",
+        "On-call engineers rotate every Monday morning",
+        "The API gateway rate limits clients to one hundred requests per second",
+        "Team lunch happens on Thursdays at the taco place",
+        "Memory leaks in the indexer were traced to an unbounded cache",
+    ];
+    const OVERLAP_QUERIES: [&str; 5] = [
+        "why did checkout have an outage",
+        "how often do payment charges retry",
+        "when are feature flags removed",
+        "what does the deploy pipeline run before release",
+        "how is the api rate limited",
+    ];
+
+    #[test]
+    fn test_embed_truncated_to_matryoshka_dimensions() {
+        let service = EmbeddingService::mock();
+        assert_eq!(service.embed_truncated(OVERLAP_QUERIES[0], 256).unwrap().dimensions, 256);
+        assert!(service.embed_truncated(OVERLAP_QUERIES[0], 300).is_err());
+
+        // The mock's hashed buckets carry no Matryoshka ordering, so this only
+        // checks the measurement runs; the real figures need the model below
+        let overlap = mean_top_k_overlap(&service, &OVERLAP_CORPUS, &OVERLAP_QUERIES, 3);
+        assert!((0.0..=1.0).contains(&overlap), "overlap@3 = {overlap}");
+    }
+
+    /// Backs the 256-vs-768 recall figure in docs/CONFIGURATION.md
+    #[test]
+    #[ignore = "needs the local embedding model"]
+    fn test_matryoshka_top_k_overlap_256_vs_768() {
+        let service = EmbeddingService::new();
+        service.init().expect("local embedding model failed to load");
+
+        let overlap = mean_top_k_overlap(&service, &OVERLAP_CORPUS, &OVERLAP_QUERIES, 3);
+        assert!(overlap >= 0.9, "overlap@3 = {overlap}");
+    }
 }
@@ -0,0 +1,138 @@
+//! Deterministic Mock Embeddings
+//!
+//! Hash-based stand-in for the fastembed model so embedding code paths
+//! (smart ingest, dedup, hybrid search) can run in tests and offline CI
+//! without downloading model weights.
+//!
+//! Each lowercased word is hashed into one of `MODEL_DIMENSIONS` buckets with
+//! a hashed sign (the "hashing trick"), then the vector is L2-normalized.
+//! Identical texts get identical vectors and texts sharing most of their
+//! words score a high cosine similarity, which is enough to exercise the
+//! prediction error gate and similarity clustering. The vectors carry no
+//! semantics beyond word overlap.
+
+use super::backend::EmbeddingBackend;
+use super::local::{
+    Embedding, EmbeddingError, MATRYOSHKA_DIMENSIONS, MAX_TEXT_LENGTH, MODEL_DIMENSIONS,
+};
+
+/// Model name recorded for embeddings produced by the mock
+pub const MOCK_MODEL_NAME: &str = "vestige/mock-hash-embedding";
+
+/// Deterministic hash-based embedding generator
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MockEmbeddingService;
+
+impl MockEmbeddingService {
+    /// Create a new mock embedding generator
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Model name recorded alongside mock embeddings
+    pub fn model_name(&self) -> &'static str {
+        MOCK_MODEL_NAME
+    }
+
+    /// Generate a full-width (`MODEL_DIMENSIONS`) unit vector for `text`
+    pub fn embed_full(&self, text: &str) -> Result<Embedding, EmbeddingError> {
+        if text.is_empty() {
+            return Err(EmbeddingError::InvalidInput(
+                "Text cannot be empty".to_string(),
+            ));
+        }
+
+        let mut end = text.len().min(MAX_TEXT_LENGTH);
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        let text = &text[..end];
+
+        let mut vector = vec![0.0_f32; MODEL_DIMENSIONS];
+        let mut words = 0;
+        for word in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+        {
+            Self::add_feature(&mut vector, &word.to_lowercase());
+            words += 1;
+        }
+        if words == 0 {
+            // Punctuation-only text still needs a non-zero vector
+            Self::add_feature(&mut vector, text);
+        }
+
+        let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+        for x in &mut vector {
+            *x /= norm;
+        }
+        Ok(Embedding::new(vector))
+    }
+
+    /// Hash `feature` into a signed bucket of `vector`
+    fn add_feature(vector: &mut [f32], feature: &str) {
+        let hash = fnv1a(feature.as_bytes());
+        let bucket = (hash % vector.len() as u64) as usize;
+        let sign = if (hash >> 63) == 0 { 1.0 } else { -1.0 };
+        vector[bucket] += sign;
+    }
+}
+
+impl EmbeddingBackend for MockEmbeddingService {
+    fn embed(&self, text: &str) -> Result<Vec<f32>, EmbeddingError> {
+        self.embed_full(text).map(|embedding| embedding.vector)
+    }
+
+    fn dimensions(&self) -> usize {
+        MODEL_DIMENSIONS
+    }
+
+    fn model_name(&self) -> &str {
+        MOCK_MODEL_NAME
+    }
+
+    fn is_ready(&self) -> bool {
+        true
+    }
+
+    fn truncation_dimensions(&self) -> Vec<usize> {
+        MATRYOSHKA_DIMENSIONS.to_vec()
+    }
+}
+
+/// 64-bit FNV-1a, stable across platforms and releases (unlike `DefaultHasher`)
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_embeddings_are_deterministic_unit_vectors() {
+        let mock = MockEmbeddingService::new();
+        let a = mock.embed_full("Rust ownership prevents data races").unwrap();
+        let b = mock.embed_full("rust OWNERSHIP prevents data races!").unwrap();
+        assert_eq!(a.dimensions, MODEL_DIMENSIONS);
+        assert!(a.is_normalized());
+        assert_eq!(a.vector, b.vector);
+        assert!(mock.embed_full("").is_err());
+        assert!(mock.embed_full("?!").unwrap().is_normalized());
+    }
+
+    #[test]
+    fn test_mock_similarity_tracks_word_overlap() {
+        let mock = MockEmbeddingService::new();
+        let base = mock.embed_full("The deploy pipeline runs integration tests before release").unwrap();
+        let close = mock.embed_full("The deploy pipeline runs integration tests before each release").unwrap();
+        let far = mock.embed_full("Grandma's lasagna recipe needs fresh basil").unwrap();
+        assert!(base.cosine_similarity(&close) > 0.9);
+        assert!(base.cosine_similarity(&far) < 0.3);
+    }
+}
@@ -8,15 +8,26 @@
 //! - Cosine similarity computation
 //! - Batch embedding for efficiency
 //! - Hybrid multi-model fusion (future)
+//! - Pluggable backends (`EmbeddingBackend`) for remote or alternative models
+//! - Deterministic mock embeddings for tests (`test-embeddings` feature)
 
+mod backend;
 mod code;
 mod hybrid;
 mod local;
+#[cfg(any(test, feature = "test-embeddings"))]
+mod mock;
 
+pub use backend::EmbeddingBackend;
 pub use local::{
-    cosine_similarity, dot_product, euclidean_distance, matryoshka_truncate, Embedding,
-    EmbeddingError, EmbeddingService, BATCH_SIZE, EMBEDDING_DIMENSIONS, MAX_TEXT_LENGTH,
+    cosine_similarity, dot_product, euclidean_distance, matryoshka_truncate,
+    matryoshka_truncate_to, Embedding, EmbeddingError, EmbeddingService, FastembedBackend,
+    BATCH_SIZE,
+    EMBEDDING_DIMENSIONS, MATRYOSHKA_DIMENSIONS, MAX_TEXT_LENGTH, MODEL_DIMENSIONS,
 };
 
 pub use code::CodeEmbedding;
 pub use hybrid::HybridEmbedding;
+
+#[cfg(any(test, feature = "test-embeddings"))]
+pub use mock::{MockEmbeddingService, MOCK_MODEL_NAME};
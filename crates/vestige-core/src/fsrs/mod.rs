@@ -49,7 +49,8 @@ pub use algorithm::{
 };
 
 pub use scheduler::{
-    FSRSParameters, FSRSScheduler, FSRSState, LearningState, PreviewResults, Rating, ReviewResult,
+    format_interval, FSRSParameters, FSRSScheduler, FSRSState, LearningState, PreviewResults,
+    Rating, ReviewResult,
 };
 
 pub use optimizer::{FSRSOptimizer, ReviewLog};
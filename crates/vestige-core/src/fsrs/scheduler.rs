@@ -3,7 +3,9 @@
 //! High-level scheduler that manages review state and produces
 //! optimal scheduling decisions.
 
-use chrono::{DateTime, Utc};
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 
 use super::algorithm::{
@@ -133,6 +135,36 @@ pub struct PreviewResults {
     pub easy: ReviewResult,
 }
 
+impl PreviewResults {
+    /// Scheduled interval for each rating, Again through Easy
+    pub fn as_intervals(&self) -> [(Rating, Duration); 4] {
+        [
+            (Rating::Again, Duration::days(self.again.interval.into())),
+            (Rating::Hard, Duration::days(self.hard.interval.into())),
+            (Rating::Good, Duration::days(self.good.interval.into())),
+            (Rating::Easy, Duration::days(self.easy.interval.into())),
+        ]
+    }
+}
+
+/// Compact label for a review interval: "<1d", "12d", "3mo", "1.5y"
+///
+/// FSRS schedules in whole days, so anything under a day (an Again that
+/// is due again today) is "<1d".
+pub fn format_interval(interval: Duration) -> String {
+    let days = interval.num_days().max(0);
+    if days < 1 {
+        "<1d".to_string()
+    } else if days < 30 {
+        format!("{}d", days)
+    } else if days < 365 {
+        format!("{}mo", (days as f64 / 30.0).round() as i64)
+    } else {
+        let years = format!("{:.1}", days as f64 / 365.0);
+        format!("{}y", years.trim_end_matches(".0"))
+    }
+}
+
 /// User-personalizable FSRS parameters
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -158,6 +190,99 @@ impl Default for FSRSParameters {
     }
 }
 
+/// On-disk shape of [`FSRSParameters`]; weights are a plain list so a
+/// wrong-length vector gets a readable error instead of a serde one
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FSRSParametersFile {
+    weights: Vec<f64>,
+    #[serde(default = "default_retention")]
+    desired_retention: f64,
+    #[serde(default = "default_max_interval")]
+    max_interval: i32,
+    #[serde(default = "default_enable_fuzz")]
+    enable_fuzz: bool,
+}
+
+fn default_retention() -> f64 {
+    DEFAULT_RETENTION
+}
+
+fn default_max_interval() -> i32 {
+    MAX_STABILITY as i32
+}
+
+fn default_enable_fuzz() -> bool {
+    true
+}
+
+impl FSRSParameters {
+    /// Build parameters from a weight list, checking it is a full FSRS-6 vector
+    pub fn from_weights(weights: &[f64]) -> Result<Self, String> {
+        let weights: [f64; 21] = weights.try_into().map_err(|_| {
+            format!("expected 21 FSRS-6 weights, got {}", weights.len())
+        })?;
+        let params = Self {
+            weights,
+            ..Self::default()
+        };
+        params.validate()?;
+        Ok(params)
+    }
+
+    /// Check weights and settings are usable by the scheduler
+    ///
+    /// Weights must be finite and non-negative, the decay (w20) strictly
+    /// positive, retention within (0, 1) and the maximum interval at least a day.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some((i, w)) = self
+            .weights
+            .iter()
+            .enumerate()
+            .find(|(_, w)| !w.is_finite() || **w < 0.0)
+        {
+            return Err(format!("weight w{} must be a finite non-negative number, got {}", i, w));
+        }
+        if self.weights[20] <= 0.0 {
+            return Err("weight w20 (decay) must be greater than 0".to_string());
+        }
+        if !(self.desired_retention > 0.0 && self.desired_retention < 1.0) {
+            return Err(format!(
+                "desiredRetention must be between 0 and 1, got {}",
+                self.desired_retention
+            ));
+        }
+        if self.max_interval < 1 {
+            return Err(format!("maxInterval must be at least 1 day, got {}", self.max_interval));
+        }
+        Ok(())
+    }
+
+    /// Load parameters from a JSON file such as one written by [`Self::to_json_file`]
+    ///
+    /// Only `weights` is required; the other fields fall back to defaults so
+    /// weights exported from another FSRS optimizer can be dropped in as-is.
+    pub fn from_json_file(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let invalid = |msg: String| std::io::Error::new(std::io::ErrorKind::InvalidData, msg);
+        let text = std::fs::read_to_string(path)?;
+        let file: FSRSParametersFile =
+            serde_json::from_str(&text).map_err(|e| invalid(e.to_string()))?;
+        let mut params = Self::from_weights(&file.weights).map_err(invalid)?;
+        params.desired_retention = file.desired_retention;
+        params.max_interval = file.max_interval;
+        params.enable_fuzz = file.enable_fuzz;
+        params.validate().map_err(invalid)?;
+        Ok(params)
+    }
+
+    /// Write parameters as pretty-printed JSON
+    pub fn to_json_file(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, json)
+    }
+}
+
 // ============================================================================
 // SCHEDULER
 // ============================================================================
@@ -362,7 +487,11 @@ impl FSRSScheduler {
 
     /// Calculate days since last review
     pub fn days_since_review(&self, last_review: &DateTime<Utc>) -> f64 {
-        let now = Utc::now();
+        self.days_since_review_at(last_review, Utc::now())
+    }
+
+    /// Days between the last review and `now`
+    pub fn days_since_review_at(&self, last_review: &DateTime<Utc>, now: DateTime<Utc>) -> f64 {
         let diff = now.signed_duration_since(*last_review);
         (diff.num_seconds() as f64 / 86400.0).max(0.0)
     }
@@ -377,6 +506,11 @@ impl FSRSScheduler {
         self.params.weights = weights;
     }
 
+    /// Replace all parameters, keeping the sentiment boost settings
+    pub fn set_params(&mut self, params: FSRSParameters) {
+        self.params = params;
+    }
+
     /// Get current parameters
     pub fn params(&self) -> &FSRSParameters {
         &self.params
@@ -476,4 +610,56 @@ mod tests {
         // Easy should have longest interval
         assert!(preview.easy.interval > preview.good.interval);
     }
+
+    #[test]
+    fn test_preview_intervals_and_formatting() {
+        let scheduler = FSRSScheduler::default();
+        let preview = scheduler.preview_reviews(&scheduler.new_card(), 0.0);
+        let intervals = preview.as_intervals();
+        assert_eq!(
+            intervals.map(|(rating, _)| rating),
+            [Rating::Again, Rating::Hard, Rating::Good, Rating::Easy]
+        );
+        assert_eq!(intervals[2].1, Duration::days(preview.good.interval.into()));
+
+        assert_eq!(format_interval(Duration::zero()), "<1d");
+        assert_eq!(format_interval(Duration::minutes(150)), "<1d");
+        assert_eq!(format_interval(Duration::days(5)), "5d");
+        assert_eq!(format_interval(Duration::days(90)), "3mo");
+        assert_eq!(format_interval(Duration::days(365)), "1y");
+        assert_eq!(format_interval(Duration::days(548)), "1.5y");
+    }
+
+    #[test]
+    fn test_parameters_json_file_roundtrip_and_validation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fsrs.json");
+
+        let mut params = FSRSParameters::default();
+        params.weights[20] = 0.3;
+        params.desired_retention = 0.85;
+        params.to_json_file(&path).unwrap();
+
+        let loaded = FSRSParameters::from_json_file(&path).unwrap();
+        assert_eq!(loaded.weights, params.weights);
+        assert_eq!(loaded.desired_retention, 0.85);
+
+        // A bare weight list from another optimizer is accepted
+        let weights: Vec<String> = FSRS6_WEIGHTS.iter().map(|w| w.to_string()).collect();
+        std::fs::write(&path, format!("{{\"weights\": [{}]}}", weights.join(","))).unwrap();
+        let loaded = FSRSParameters::from_json_file(&path).unwrap();
+        assert_eq!(loaded.desired_retention, DEFAULT_RETENTION);
+
+        // FSRS-5 sized vectors and out-of-range values are rejected
+        std::fs::write(&path, format!("{{\"weights\": [{}]}}", weights[..19].join(","))).unwrap();
+        let err = FSRSParameters::from_json_file(&path).unwrap_err();
+        assert!(err.to_string().contains("expected 21"));
+
+        let mut bad = FSRSParameters::default();
+        bad.weights[3] = -1.0;
+        assert!(bad.validate().unwrap_err().contains("w3"));
+        bad = FSRSParameters::default();
+        bad.desired_retention = 1.0;
+        assert!(bad.validate().is_err());
+    }
 }
@@ -80,6 +80,7 @@
 // MODULES
 // ============================================================================
 
+pub mod clock;
 pub mod consolidation;
 pub mod fsrs;
 pub mod memory;
@@ -107,20 +108,29 @@ pub mod codebase;
 /// - Spreading activation networks
 pub mod neuroscience;
 
+/// Fixtures for tests written against `Storage`
+pub mod testing;
+
 // ============================================================================
 // PUBLIC API RE-EXPORTS
 // ============================================================================
 
 // Memory types
 pub use memory::{
-    ConsolidationResult, EmbeddingResult, IngestInput, KnowledgeNode, MatchType, MemoryStats,
-    NodeType, RecallInput, SearchMode, SearchResult, SimilarityResult, TemporalRange,
+    ConsolidationProgress, ConsolidationResult, DuplicatePolicy, EmbeddingResult, IngestInput,
+    KnowledgeNode, MatchType, MemoryStats, NodeType, QueryExpansionMode, RecallInput,
+    ScoreBreakdown, SearchMode, SearchResult, SearchSyntax, SimilarityResult, StepError,
+    StepResult, StepTiming, TemporalRange,
     // GOD TIER 2026: New types
     EdgeType, KnowledgeEdge, MemoryScope, MemorySystem,
 };
 
+// Time source
+pub use clock::{Clock, MockClock, SystemClock};
+
 // FSRS-6 algorithm
 pub use fsrs::{
+    format_interval,
     initial_difficulty,
     initial_stability,
     next_interval,
@@ -138,8 +148,18 @@ pub use fsrs::{
 
 // Storage layer
 pub use storage::{
-    ConnectionRecord, ConsolidationHistoryRecord, DreamHistoryRecord, InsightRecord,
-    IntentionRecord, Result, SmartIngestResult, StateTransitionRecord, Storage, StorageError,
+    AccessAnalytics, AccessKind, AccessRecord, AccessedMemory, BackfillOptions, BackfillProgress, BackupConfig, BackupInfo,
+    BulkAction, BulkOutcome, CancellationToken, ConnectionRecord, ConsolidationConfig,
+    ConsolidationHistoryRecord, ConsolidationLimits, ConsolidationPipeline, ConsolidationStep,
+    ContentUpdateMode,
+    ContextPacket, ContextSection, DailyAccessCount, DiagnosticCheck, DiagnosticStatus,
+    DiagnosticsReport, DreamHistoryRecord, FeedbackOptions, FeedbackRecord, Forecast, ForecastDay,
+    GraphFilter, HealthReport, ImportanceHistoryPoint, IngestOutcome, InsightRecord,
+    IntentionRecord, MetricsSnapshot, NamespacedStorage, NodeFilter, NodeGraphState, NodeImportance, NodeRevision,
+    OutcomeRecord, PinnedMemory, QueryCacheStats, ReasoningChainRecord, ReinforcementConfig, RepairReport, Result,
+    RetentionPolicy, RetrievalForgettingPolicy, RetruncateReport, RevisionDiff, SmartIngestResult,
+    SourceTrust, StateTransitionRecord, StatsFilter, StepContext, Storage, StorageConfig,
+    StorageError, UsageRecord,
 };
 
 // Consolidation (sleep-inspired memory processing)
@@ -171,6 +191,10 @@ pub use advanced::{
     ConnectionStats,
     ConnectionType,
     ConsolidationReport,
+    // Contradiction detection
+    Contradiction,
+    ContradictionKind,
+    ContradictionScorer,
     // Sleep consolidation (automatic background consolidation)
     ConsolidationScheduler,
     ContentType,
@@ -189,6 +213,7 @@ pub use advanced::{
     // Importance tracking
     ImportanceTracker,
     // Intent detection
+    IntentDetectionResult,
     IntentDetector,
     LabileState,
     Language,
@@ -235,6 +260,14 @@ pub use advanced::{
     SimilarityResult as PredictionSimilarityResult,
     SupersedeReason,
     UpdateType,
+    // Tag suggestion
+    TagSource,
+    TagSuggestion,
+    AUTO_TAG_THRESHOLD,
+    // Session packets
+    SessionItem,
+    SessionOverview,
+    SessionPacket,
 };
 
 // Codebase memory (Vestige's killer differentiator)
@@ -264,6 +297,7 @@ pub use codebase::{
     HistoryAnalysis,
     LearningResult,
     // Patterns
+    PatternAnalysisConfig,
     PatternDetector,
     PatternMatch,
     PatternSuggestion,
@@ -273,6 +307,7 @@ pub use codebase::{
     RelationshipGraph,
     RelationshipTracker,
     WatcherConfig,
+    WatcherStats,
     WorkContext,
     WorkingContext,
 };
@@ -384,20 +419,30 @@ pub use neuroscience::{
 // Embeddings (when feature enabled)
 #[cfg(feature = "embeddings")]
 pub use embeddings::{
-    cosine_similarity, euclidean_distance, Embedding, EmbeddingError, EmbeddingService,
-    EMBEDDING_DIMENSIONS,
+    cosine_similarity, euclidean_distance, matryoshka_truncate_to, Embedding, EmbeddingBackend,
+    EmbeddingError, EmbeddingService, FastembedBackend, EMBEDDING_DIMENSIONS,
+    MATRYOSHKA_DIMENSIONS,
 };
 
+#[cfg(feature = "test-embeddings")]
+pub use embeddings::MockEmbeddingService;
+
 // Search (when feature enabled)
 #[cfg(feature = "vector-search")]
 pub use search::{
     linear_combination,
     reciprocal_rank_fusion,
+    FusionMethod,
     HybridSearchConfig,
+    // HyDE query expansion
+    QueryExpansion,
+    QueryIntent,
     // Hybrid search
     HybridSearcher,
+    RankingProfile,
     // Keyword search
     KeywordSearcher,
+    IndexMetric,
     VectorIndex,
     VectorIndexConfig,
     VectorIndexStats,
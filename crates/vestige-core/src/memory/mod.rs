@@ -10,12 +10,16 @@ mod node;
 mod strength;
 mod temporal;
 
-pub use node::{IngestInput, KnowledgeNode, NodeType, RecallInput, SearchMode};
+pub use node::{
+    DuplicatePolicy, IngestInput, KnowledgeNode, NodeType, QueryExpansionMode, RecallInput,
+    SearchMode, SearchSyntax,
+};
 pub use strength::{DualStrength, StrengthDecay};
 pub use temporal::{TemporalRange, TemporalValidity};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 // ============================================================================
 // GOD TIER 2026: MEMORY SCOPES (Like Mem0)
@@ -291,12 +295,154 @@ pub struct ConsolidationResult {
     // v1.4.0: FSRS-6 upgrade
     /// Number of duplicate memories merged during episodic→semantic consolidation
     pub duplicates_merged: i64,
-    /// Number of neighbor memories reinforced (tracked per-access, not consolidation)
+    /// Memories whose neighborhoods dedup checked for duplicates
+    #[serde(default)]
+    pub dedup_clusters_examined: i64,
+    /// Duplicate clusters dedup merged
+    #[serde(default)]
+    pub dedup_clusters_merged: i64,
+    /// Neighbor memories reinforced from accesses queued before the run
     pub neighbors_reinforced: i64,
+    /// Pins whose TTL had passed, released before the run
+    #[serde(default)]
+    pub pins_expired: i64,
     /// Number of ACT-R activations computed from access history
     pub activations_computed: i64,
     /// Personalized w20 if optimized this cycle
     pub w20_optimized: Option<f64>,
+    /// Each step that ran, keyed by step name; the fields above sum these up
+    #[serde(default)]
+    pub steps: BTreeMap<String, StepResult>,
+    /// Every step attempted, in run order, with how long it took
+    #[serde(default)]
+    pub step_timings: Vec<StepTiming>,
+    /// Steps that failed; later steps still ran
+    #[serde(default)]
+    pub step_errors: Vec<StepError>,
+    /// Why the run was skipped without doing any work (e.g. "no changes")
+    #[serde(default)]
+    pub skipped: Option<String>,
+    /// Why the run stopped before every step ran ("deadline" or "cancelled")
+    #[serde(default)]
+    pub stopped_early: Option<String>,
+    /// Steps left out when the run stopped early, in run order
+    #[serde(default)]
+    pub steps_not_run: Vec<String>,
+}
+
+impl ConsolidationResult {
+    /// Summarize step results into the aggregate counts
+    pub fn from_steps(steps: BTreeMap<String, StepResult>) -> Self {
+        let mut result = Self::default();
+        for step in steps.values() {
+            match *step {
+                StepResult::Decay { decay_applied, .. } => {
+                    result.decay_applied = decay_applied;
+                    result.nodes_processed = decay_applied;
+                }
+                StepResult::PromoteEmotional { promoted } | StepResult::AutoPromote { promoted } => {
+                    result.nodes_promoted += promoted
+                }
+                StepResult::Embeddings { generated } => result.embeddings_generated = generated,
+                StepResult::Dedup { merged, clusters_examined, clusters_merged, .. } => {
+                    result.duplicates_merged = merged;
+                    result.dedup_clusters_examined = clusters_examined;
+                    result.dedup_clusters_merged = clusters_merged;
+                }
+                StepResult::Actr { activations_computed } => {
+                    result.activations_computed = activations_computed
+                }
+                StepResult::W20 { optimized } => result.w20_optimized = optimized,
+                _ => {}
+            }
+        }
+        result.steps = steps;
+        result
+    }
+}
+
+/// How long one consolidation step took, and whether it succeeded
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepTiming {
+    pub step: String,
+    pub duration_ms: i64,
+    pub succeeded: bool,
+}
+
+/// A consolidation step that failed, and why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepError {
+    pub step: String,
+    pub error: String,
+}
+
+/// What one consolidation step did
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case", rename_all_fields = "camelCase")]
+pub enum StepResult {
+    /// FSRS-6 decay
+    Decay { decay_applied: i64 },
+    /// Storage-strength boost for emotional memories
+    PromoteEmotional { promoted: i64 },
+    /// Embeddings generated for memories missing one
+    Embeddings { generated: i64 },
+    /// Near-duplicates merged out of the neighborhoods examined, whether the
+    /// time budget cut the pass short, and whether the vector index was
+    /// compacted
+    Dedup {
+        merged: i64,
+        clusters_examined: i64,
+        clusters_merged: i64,
+        budget_exhausted: bool,
+        index_rebuilt: bool,
+    },
+    /// ACT-R base-level activations recomputed from access history
+    Actr { activations_computed: i64 },
+    /// Access log entries past retention removed
+    AccessLog { pruned: i64 },
+    /// Personalized w20, when enough reviews existed to fit it
+    W20 { optimized: Option<f64> },
+    /// Insights synthesized from recent memories
+    Dreams { insights_generated: i64 },
+    /// Old, weak memories folded into compressed groups
+    Compression { memories_compressed: i64 },
+    /// Active/Dormant/Silent state transitions applied
+    States { transitions: i64 },
+    /// Tagged memories captured by synaptic tagging
+    Stc { captured: i64 },
+    /// Universal patterns learned across codebases
+    CrossProject { patterns: i64 },
+    /// Weak hippocampal index links pruned
+    Hippocampal { links_pruned: i64 },
+    /// Usage events replayed into importance, and scores persisted
+    Importance { events_replayed: i64, scores_updated: i64 },
+    /// Replayed usage events and importance history past retention removed
+    UsageLog { events_pruned: i64, history_pruned: i64 },
+    /// Weak connections decayed away
+    Connections { pruned: i64 },
+    /// FTS5 segments merged and planner statistics refreshed
+    FtsOptimize,
+    /// Memories promoted for frequent recent access
+    AutoPromote { promoted: i64 },
+    /// Retention target check and any garbage collection it triggered
+    RetentionGc {
+        avg_retention: f64,
+        below_target: i64,
+        collected: i64,
+    },
+}
+
+/// Progress report emitted as each consolidation step completes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ConsolidationProgress {
+    /// 1-based number of the step that just finished
+    pub step: usize,
+    /// Total number of steps in the cycle
+    pub total: usize,
+    /// Human-readable step name
+    pub label: &'static str,
 }
 
 
@@ -318,6 +464,64 @@ pub struct SearchResult {
     pub combined_score: f32,
     /// How the result was matched
     pub match_type: MatchType,
+    /// Per-signal score explanation (only when explain mode is requested)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score_breakdown: Option<ScoreBreakdown>,
+    /// Source trust multiplier applied during reranking (1.0 = neutral)
+    #[serde(default = "neutral_trust")]
+    pub source_trust: f64,
+}
+
+fn neutral_trust() -> f64 {
+    1.0
+}
+
+/// Explanation of how a hybrid search score was computed
+///
+/// final = recency_weight·recency + importance_weight·importance + relevance_weight·relevance +
+/// trust_weight·trust, with the weights renormalized to sum to 1, plus a fixed pin bonus and
+/// the intent boost (three-signal rerank, Park et al. Generative Agents 2023, plus source trust)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoreBreakdown {
+    /// Raw FTS5 BM25 rank (more negative is a better match)
+    pub fts_rank: Option<f64>,
+    /// 1-based position in the keyword (BM25) result list
+    pub keyword_rank: Option<usize>,
+    /// Normalized BM25 score (0.0 to 1.0)
+    pub keyword_score: Option<f32>,
+    /// 1-based position in the semantic result list
+    pub semantic_rank: Option<usize>,
+    /// Cosine similarity from semantic search
+    pub semantic_similarity: Option<f32>,
+    /// Whether the query was embedded as a HyDE-expanded centroid
+    pub hyde_expanded: bool,
+    /// Fused keyword + semantic relevance before reranking
+    pub relevance: f64,
+    /// Recency factor (decay base ^ hours since last access)
+    pub recency: f64,
+    /// Raw ACT-R activation
+    pub activation: f64,
+    /// ACT-R activation normalized from [-2, 5] to [0, 1]
+    pub importance: f64,
+    /// Weighted recency term
+    pub recency_term: f64,
+    /// Weighted importance term
+    pub importance_term: f64,
+    /// Weighted relevance term
+    pub relevance_term: f64,
+    /// Source trust multiplier (0.5 to 1.5, 1.0 when no pattern matches)
+    pub trust: f64,
+    /// Weighted trust term
+    pub trust_term: f64,
+    /// Fixed bonus for pinned memories (0.0 when not pinned)
+    #[serde(default)]
+    pub pin_bonus: f64,
+    /// Score added for the detected query intent (0.0 without intent detection)
+    #[serde(default)]
+    pub intent_boost: f64,
+    /// Sum of the weighted terms, the pin bonus and the intent boost
+    pub final_score: f64,
 }
 
 /// How a search result was matched
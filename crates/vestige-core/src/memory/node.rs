@@ -41,6 +41,19 @@ pub enum NodeType {
 }
 
 impl NodeType {
+    /// Every built-in node type
+    pub const ALL: [NodeType; 9] = [
+        NodeType::Fact,
+        NodeType::Concept,
+        NodeType::Procedure,
+        NodeType::Event,
+        NodeType::Relationship,
+        NodeType::Quote,
+        NodeType::Code,
+        NodeType::Question,
+        NodeType::Insight,
+    ];
+
     /// Convert to string representation
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -56,20 +69,15 @@ impl NodeType {
         }
     }
 
-    /// Parse from string name
+    /// Parse from string name, falling back to `Fact` for unknown names
     pub fn parse_name(s: &str) -> Self {
-        match s.to_lowercase().as_str() {
-            "fact" => NodeType::Fact,
-            "concept" => NodeType::Concept,
-            "procedure" => NodeType::Procedure,
-            "event" => NodeType::Event,
-            "relationship" => NodeType::Relationship,
-            "quote" => NodeType::Quote,
-            "code" => NodeType::Code,
-            "question" => NodeType::Question,
-            "insight" => NodeType::Insight,
-            _ => NodeType::Fact,
-        }
+        Self::from_name(s).unwrap_or_default()
+    }
+
+    /// Parse from string name, or `None` if it is not a built-in type
+    pub fn from_name(s: &str) -> Option<Self> {
+        let name = s.trim().to_lowercase();
+        Self::ALL.into_iter().find(|t| t.as_str() == name)
     }
 }
 
@@ -167,6 +175,11 @@ pub struct KnowledgeNode {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub flashbulb: Option<bool>,
 
+    // ========== Importance ==========
+    /// Composite importance (0.0 to 1.0), refreshed during consolidation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub importance: Option<f64>,
+
     // ========== Temporal Hierarchy (v2.0.0) ==========
     /// Temporal level for summary nodes: None=leaf, "daily"/"weekly"/"monthly"
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -179,6 +192,11 @@ pub struct KnowledgeNode {
     /// Which model generated the embedding
     #[serde(skip_serializing_if = "Option::is_none")]
     pub embedding_model: Option<String>,
+
+    // ========== Isolation ==========
+    /// Namespace the memory belongs to (None = default namespace)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
 }
 
 impl Default for KnowledgeNode {
@@ -210,9 +228,11 @@ impl Default for KnowledgeNode {
             times_useful: None,
             emotional_valence: None,
             flashbulb: None,
+            importance: None,
             temporal_level: None,
             has_embedding: None,
             embedding_model: None,
+            namespace: None,
         }
     }
 }
@@ -253,6 +273,22 @@ impl KnowledgeNode {
 // INPUT TYPES
 // ============================================================================
 
+/// What plain ingest does when the content exactly matches an existing memory
+///
+/// Matching compares whitespace-normalized content within the same node type
+/// and namespace, so it works without embeddings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum DuplicatePolicy {
+    /// Always create a new memory
+    CreateAnyway,
+    /// Return the existing memory instead of creating another
+    #[default]
+    ReturnExisting,
+    /// Fail with `StorageError::Duplicate`
+    Error,
+}
+
 /// Input for creating a new memory
 ///
 /// Uses `deny_unknown_fields` to prevent field injection attacks.
@@ -280,6 +316,15 @@ pub struct IngestInput {
     /// When this knowledge stops being valid
     #[serde(skip_serializing_if = "Option::is_none")]
     pub valid_until: Option<DateTime<Utc>>,
+    /// Namespace to store the memory in (None = default namespace)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// Add suggested tags at or above `AUTO_TAG_THRESHOLD` (see `Storage::suggest_tags`)
+    #[serde(default)]
+    pub auto_tag: bool,
+    /// What to do when identical content already exists (default: return it)
+    #[serde(default)]
+    pub on_duplicate: DuplicatePolicy,
 }
 
 impl Default for IngestInput {
@@ -293,6 +338,9 @@ impl Default for IngestInput {
             tags: vec![],
             valid_from: None,
             valid_until: None,
+            namespace: None,
+            auto_tag: false,
+            on_duplicate: DuplicatePolicy::default(),
         }
     }
 }
@@ -310,6 +358,32 @@ pub enum SearchMode {
     Hybrid,
 }
 
+/// FTS5 query syntax accepted by keyword search
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchSyntax {
+    /// The whole query is one literal phrase (default)
+    #[default]
+    Plain,
+    /// Balanced "quoted phrases", trailing-asterisk prefix terms
+    /// (`consolid*`) and `-term` / `NOT term` exclusions; other words must
+    /// all match
+    Advanced,
+}
+
+/// HyDE query expansion control for semantic and hybrid search
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum QueryExpansionMode {
+    /// Expand definition, how-to, reasoning and lookup queries (default)
+    #[default]
+    Auto,
+    /// Embed the literal query only
+    Off,
+    /// Expand every query regardless of intent
+    Force,
+}
+
 /// Input for recalling memories
 ///
 /// Uses `deny_unknown_fields` to prevent field injection attacks.
@@ -326,9 +400,22 @@ pub struct RecallInput {
     /// Search mode (keyword, semantic, or hybrid)
     #[serde(default)]
     pub search_mode: SearchMode,
+    /// FTS5 syntax for the keyword part of the search
+    #[serde(default)]
+    pub syntax: SearchSyntax,
+    /// HyDE query expansion for semantic and hybrid modes
+    #[serde(default)]
+    pub query_expansion: QueryExpansionMode,
     /// Only return results valid at this time
     #[serde(skip_serializing_if = "Option::is_none")]
     pub valid_at: Option<DateTime<Utc>>,
+    /// Only return memories in this namespace (None = all namespaces)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// Leave out memories in the `Unavailable` state (suppressed by
+    /// retrieval competition or the user)
+    #[serde(default)]
+    pub exclude_unavailable: bool,
 }
 
 impl Default for RecallInput {
@@ -338,7 +425,11 @@ impl Default for RecallInput {
             limit: 10,
             min_retention: 0.0,
             search_mode: SearchMode::Hybrid,
+            syntax: SearchSyntax::Plain,
+            query_expansion: QueryExpansionMode::Auto,
             valid_at: None,
+            namespace: None,
+            exclude_unavailable: false,
         }
     }
 }
@@ -65,6 +65,10 @@ const MOOD_CONGRUENCE_THRESHOLD: f64 = 0.3;
 /// Maximum number of recent emotions to track for mood state
 const MOOD_HISTORY_CAPACITY: usize = 20;
 
+/// Arousal at which a memory is replayed preferentially in the next dream
+/// even without flashbulb encoding
+const REPLAY_AROUSAL_THRESHOLD: f64 = 0.7;
+
 // ============================================================================
 // TYPES
 // ============================================================================
@@ -86,6 +90,14 @@ pub struct EmotionalEvaluation {
     pub confidence: f64,
 }
 
+impl EmotionalEvaluation {
+    /// Whether the memory should be tagged for preferential dream replay
+    /// (flashbulb or high-arousal encoding)
+    pub fn needs_replay(&self) -> bool {
+        self.is_flashbulb || self.arousal >= REPLAY_AROUSAL_THRESHOLD
+    }
+}
+
 /// Emotion categories for classification
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum EmotionCategory {
@@ -593,6 +605,7 @@ mod tests {
             0.9, // High arousal
         );
         assert!(eval.is_flashbulb, "Should detect flashbulb with high novelty + arousal");
+        assert!(eval.needs_replay());
     }
 
     #[test]
@@ -604,6 +617,7 @@ mod tests {
             0.1, // Low arousal
         );
         assert!(!eval.is_flashbulb);
+        assert!(!eval.needs_replay());
     }
 
     #[test]
@@ -1759,6 +1759,45 @@ impl HippocampalIndex {
         Ok(())
     }
 
+    /// Mark or clear a memory as user-starred (pinned); returns whether the
+    /// memory is indexed
+    pub fn set_user_starred(&self, memory_id: &str, starred: bool) -> Result<bool> {
+        let mut indices = self
+            .indices
+            .write()
+            .map_err(|e| HippocampalIndexError::LockError(e.to_string()))?;
+
+        Ok(match indices.get_mut(memory_id) {
+            Some(index) => {
+                index.importance_flags.set_user_starred(starred);
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// Make the user-starred flag match the set of pinned memories
+    ///
+    /// Stars indexed memories in `pinned` and clears the flag on the rest, so
+    /// pins that expired or were removed elsewhere stop counting. Returns how
+    /// many memories changed.
+    pub fn sync_user_starred(&self, pinned: &std::collections::HashSet<String>) -> Result<usize> {
+        let mut indices = self
+            .indices
+            .write()
+            .map_err(|e| HippocampalIndexError::LockError(e.to_string()))?;
+
+        let mut changed = 0;
+        for (memory_id, index) in indices.iter_mut() {
+            let starred = pinned.contains(memory_id);
+            if index.importance_flags.is_user_starred() != starred {
+                index.importance_flags.set_user_starred(starred);
+                changed += 1;
+            }
+        }
+        Ok(changed)
+    }
+
     /// Prune weak association links
     pub fn prune_weak_links(&self) -> Result<usize> {
         let mut indices = self
@@ -1939,7 +1978,12 @@ impl HippocampalIndex {
                 node.retention_strength,
                 node.sentiment_magnitude,
             ) {
-                Ok(_) => result.migrated += 1,
+                Ok(_) => {
+                    if node.pinned {
+                        let _ = self.set_user_starred(&node.id, true);
+                    }
+                    result.migrated += 1;
+                }
                 Err(HippocampalIndexError::MigrationError(msg))
                     if msg == "Node already indexed" =>
                 {
@@ -2033,6 +2077,8 @@ pub struct MigrationNode {
     pub retention_strength: f64,
     /// Sentiment magnitude
     pub sentiment_magnitude: f64,
+    /// Whether the memory is pinned; sets the user-starred flag
+    pub pinned: bool,
 }
 
 // ============================================================================
@@ -2188,6 +2234,7 @@ mod tests {
                 embedding: None,
                 retention_strength: 0.8,
                 sentiment_magnitude: 0.6,
+                pinned: true,
             },
             MigrationNode {
                 id: "node-2".to_string(),
@@ -2197,6 +2244,7 @@ mod tests {
                 embedding: None,
                 retention_strength: 0.3,
                 sentiment_magnitude: 0.1,
+                pinned: false,
             },
         ];
 
@@ -2211,9 +2259,17 @@ mod tests {
         assert!(idx1.importance_flags.has_high_retention());
         assert!(idx1.importance_flags.is_emotional());
 
+        assert!(idx1.importance_flags.is_user_starred());
+
         let idx2 = index.get_index("node-2").unwrap().unwrap();
         assert!(!idx2.importance_flags.has_high_retention());
         assert!(!idx2.importance_flags.is_emotional());
+        assert!(!idx2.importance_flags.is_user_starred());
+
+        // Unpinning clears the flag; unknown memories are reported as such
+        assert!(index.set_user_starred("node-1", false).unwrap());
+        assert!(!index.get_index("node-1").unwrap().unwrap().importance_flags.is_user_starred());
+        assert!(!index.set_user_starred("missing", true).unwrap());
     }
 
     #[test]
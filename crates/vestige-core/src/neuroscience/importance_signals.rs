@@ -1333,6 +1333,52 @@ pub enum OutcomeType {
     Harmful,
 }
 
+impl OutcomeType {
+    /// Convert to string representation.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutcomeType::VeryHelpful => "very_helpful",
+            OutcomeType::Helpful => "helpful",
+            OutcomeType::Neutral => "neutral",
+            OutcomeType::NotHelpful => "not_helpful",
+            OutcomeType::Harmful => "harmful",
+        }
+    }
+
+    /// Signed reward in [-1, 1]: positive outcomes strengthen, negative weaken
+    pub fn reward(&self) -> f64 {
+        match self {
+            OutcomeType::VeryHelpful => 1.0,
+            OutcomeType::Helpful => 0.6,
+            OutcomeType::Neutral => 0.0,
+            OutcomeType::NotHelpful => -0.6,
+            OutcomeType::Harmful => -1.0,
+        }
+    }
+}
+
+impl std::fmt::Display for OutcomeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for OutcomeType {
+    type Err = String;
+
+    /// Accepts the `as_str` names plus `success` / `failure` shorthands
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "very_helpful" => Ok(OutcomeType::VeryHelpful),
+            "helpful" | "success" => Ok(OutcomeType::Helpful),
+            "neutral" => Ok(OutcomeType::Neutral),
+            "not_helpful" | "failure" => Ok(OutcomeType::NotHelpful),
+            "harmful" => Ok(OutcomeType::Harmful),
+            _ => Err(format!("Unknown outcome: {}", s)),
+        }
+    }
+}
+
 /// Learned pattern that predicts reward
 #[derive(Debug, Clone)]
 struct RewardPattern {
@@ -1841,6 +1887,18 @@ impl ImportanceSignals {
         self.compute_composite(novelty, arousal, reward, attention, content, context)
     }
 
+    /// Score a stored memory with every channel explained
+    ///
+    /// Like `compute_importance_explicit`, but the reward channel and its
+    /// explanation both come from `memory_id`'s own outcome history, so the
+    /// result shows why that memory scored as it did.
+    pub fn explain_memory(&self, content: &str, memory_id: &str, context: &Context) -> ImportanceScore {
+        let mut context = context.clone();
+        context.recent_memory_ids.retain(|id| id != memory_id);
+        context.recent_memory_ids.insert(0, memory_id.to_string());
+        self.compute_importance_explicit(content, &context, Some(memory_id), None)
+    }
+
     /// Update novelty model (learning)
     pub fn learn_content(&mut self, content: &str) {
         self.novelty.update_model(content);
@@ -2338,6 +2396,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_explain_memory_uses_memory_reward_history() {
+        let signals = ImportanceSignals::new();
+        signals.record_outcome("mem-1", OutcomeType::VeryHelpful);
+        signals.record_outcome("mem-1", OutcomeType::Helpful);
+        let context = Context::current();
+
+        let score = signals.explain_memory("Deploy checklist for the API", "mem-1", &context);
+        let reward = score.reward_explanation.as_ref().expect("reward explained");
+        assert_eq!(reward.total_count, 2);
+        assert!((reward.score - score.reward).abs() < f64::EPSILON);
+        assert!(score.novelty_explanation.is_some());
+        assert!(score.arousal_explanation.is_some());
+        assert!(score.attention_explanation.is_some());
+    }
+
     #[test]
     fn test_composite_weights() {
         let weights = CompositeWeights::new(1.0, 2.0, 1.0, 1.0);
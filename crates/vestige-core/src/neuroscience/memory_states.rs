@@ -28,6 +28,9 @@
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::sync::Arc;
+
+use crate::clock::{system_clock, Clock};
 
 // ============================================================================
 // CONSTANTS
@@ -241,6 +244,21 @@ pub enum StateTransitionReason {
 }
 
 impl StateTransitionReason {
+    /// Short label stored as the transition's reason type.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StateTransitionReason::Access => "access",
+            StateTransitionReason::TimeDecay => "time_decay",
+            StateTransitionReason::CueReactivation { .. } => "cue_reactivation",
+            StateTransitionReason::CompetitionLoss { .. } => "competition_loss",
+            StateTransitionReason::InterferenceResolved => "interference_resolved",
+            StateTransitionReason::UserSuppression { .. } => "user_suppression",
+            StateTransitionReason::SuppressionExpired => "suppression_expired",
+            StateTransitionReason::ManualOverride { .. } => "manual_override",
+            StateTransitionReason::SystemInit => "system_init",
+        }
+    }
+
     /// Get a human-readable description of the reason.
     pub fn description(&self) -> String {
         match self {
@@ -302,11 +320,21 @@ impl StateTransition {
         from_state: MemoryState,
         to_state: MemoryState,
         reason: StateTransitionReason,
+    ) -> Self {
+        Self::at(from_state, to_state, reason, Utc::now())
+    }
+
+    /// Create a transition record stamped with `timestamp`.
+    pub fn at(
+        from_state: MemoryState,
+        to_state: MemoryState,
+        reason: StateTransitionReason,
+        timestamp: DateTime<Utc>,
     ) -> Self {
         Self {
             from_state,
             to_state,
-            timestamp: Utc::now(),
+            timestamp,
             reason,
         }
     }
@@ -480,12 +508,21 @@ impl MemoryLifecycle {
 
     /// Transition to a new state with a reason.
     pub fn transition_to(&mut self, new_state: MemoryState, reason: StateTransitionReason) {
+        self.transition_to_at(new_state, reason, Utc::now());
+    }
+
+    /// Transition to a new state as of `now`.
+    pub fn transition_to_at(
+        &mut self,
+        new_state: MemoryState,
+        reason: StateTransitionReason,
+        now: DateTime<Utc>,
+    ) {
         if self.state == new_state {
             return; // No change
         }
 
         // Update time accumulator
-        let now = Utc::now();
         let time_in_current = now
             .signed_duration_since(self.state_entered_at)
             .num_seconds()
@@ -493,7 +530,7 @@ impl MemoryLifecycle {
         self.time_in_states.add(self.state, time_in_current);
 
         // Record transition
-        let transition = StateTransition::new(self.state, new_state, reason);
+        let transition = StateTransition::at(self.state, new_state, reason, now);
         self.state_history.push_back(transition);
 
         // Trim history if needed
@@ -551,8 +588,13 @@ impl MemoryLifecycle {
 
     /// Check if suppression has expired.
     pub fn is_suppression_expired(&self) -> bool {
+        self.is_suppression_expired_at(Utc::now())
+    }
+
+    /// Check if suppression has expired as of `now`.
+    pub fn is_suppression_expired_at(&self, now: DateTime<Utc>) -> bool {
         self.suppression_until
-            .map(|until| Utc::now() >= until)
+            .map(|until| now >= until)
             .unwrap_or(true)
     }
 
@@ -563,11 +605,16 @@ impl MemoryLifecycle {
 
     /// Check if this memory should decay from Active to Dormant.
     pub fn should_decay_to_dormant(&self, config: &StateDecayConfig) -> bool {
+        self.should_decay_to_dormant_at(config, Utc::now())
+    }
+
+    /// Check if this memory should decay from Active to Dormant as of `now`.
+    pub fn should_decay_to_dormant_at(&self, config: &StateDecayConfig, now: DateTime<Utc>) -> bool {
         if self.state != MemoryState::Active {
             return false;
         }
 
-        let hours_since_access = Utc::now()
+        let hours_since_access = now
             .signed_duration_since(self.last_access)
             .num_hours();
         hours_since_access >= config.active_decay_hours
@@ -575,11 +622,16 @@ impl MemoryLifecycle {
 
     /// Check if this memory should decay from Dormant to Silent.
     pub fn should_decay_to_silent(&self, config: &StateDecayConfig) -> bool {
+        self.should_decay_to_silent_at(config, Utc::now())
+    }
+
+    /// Check if this memory should decay from Dormant to Silent as of `now`.
+    pub fn should_decay_to_silent_at(&self, config: &StateDecayConfig, now: DateTime<Utc>) -> bool {
         if self.state != MemoryState::Dormant {
             return false;
         }
 
-        let days_since_access = Utc::now()
+        let days_since_access = now
             .signed_duration_since(self.last_access)
             .num_days();
         days_since_access >= config.dormant_decay_days
@@ -999,6 +1051,7 @@ pub struct CompetitionResult {
 #[derive(Debug, Clone)]
 pub struct StateUpdateService {
     config: StateDecayConfig,
+    clock: Arc<dyn Clock>,
 }
 
 impl Default for StateUpdateService {
@@ -1010,14 +1063,21 @@ impl Default for StateUpdateService {
 impl StateUpdateService {
     /// Create a new update service with default config.
     pub fn new() -> Self {
-        Self {
-            config: StateDecayConfig::default(),
-        }
+        Self::with_config(StateDecayConfig::default())
     }
 
     /// Create with custom configuration.
     pub fn with_config(config: StateDecayConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            clock: system_clock(),
+        }
+    }
+
+    /// Read the current time from `clock` instead of the system clock.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
     }
 
     /// Get the configuration.
@@ -1031,44 +1091,49 @@ impl StateUpdateService {
     ///
     /// List of transitions that were applied.
     pub fn update_lifecycle(&self, lifecycle: &mut MemoryLifecycle) -> Vec<StateTransition> {
+        let now = self.clock.now();
         let mut transitions = Vec::new();
 
         // Check for suppression expiry first
         if lifecycle.state == MemoryState::Unavailable
-            && lifecycle.is_suppression_expired()
+            && lifecycle.is_suppression_expired_at(now)
             && self.config.auto_resolve_suppression
         {
             let from = lifecycle.state;
-            lifecycle.transition_to(
+            lifecycle.transition_to_at(
                 MemoryState::Dormant,
                 StateTransitionReason::SuppressionExpired,
+                now,
             );
-            transitions.push(StateTransition::new(
+            transitions.push(StateTransition::at(
                 from,
                 MemoryState::Dormant,
                 StateTransitionReason::SuppressionExpired,
+                now,
             ));
         }
 
         // Check for Active -> Dormant decay
-        if lifecycle.should_decay_to_dormant(&self.config) {
+        if lifecycle.should_decay_to_dormant_at(&self.config, now) {
             let from = lifecycle.state;
-            lifecycle.transition_to(MemoryState::Dormant, StateTransitionReason::TimeDecay);
-            transitions.push(StateTransition::new(
+            lifecycle.transition_to_at(MemoryState::Dormant, StateTransitionReason::TimeDecay, now);
+            transitions.push(StateTransition::at(
                 from,
                 MemoryState::Dormant,
                 StateTransitionReason::TimeDecay,
+                now,
             ));
         }
 
         // Check for Dormant -> Silent decay
-        if lifecycle.should_decay_to_silent(&self.config) {
+        if lifecycle.should_decay_to_silent_at(&self.config, now) {
             let from = lifecycle.state;
-            lifecycle.transition_to(MemoryState::Silent, StateTransitionReason::TimeDecay);
-            transitions.push(StateTransition::new(
+            lifecycle.transition_to_at(MemoryState::Silent, StateTransitionReason::TimeDecay, now);
+            transitions.push(StateTransition::at(
                 from,
                 MemoryState::Silent,
                 StateTransitionReason::TimeDecay,
+                now,
             ));
         }
 
@@ -1164,10 +1229,20 @@ impl AccessibilityCalculator {
     ///
     /// Adjusted score factoring in accessibility (0.0-1.0).
     pub fn calculate(&self, lifecycle: &MemoryLifecycle, base_score: f64) -> f64 {
+        self.calculate_at(lifecycle, base_score, Utc::now())
+    }
+
+    /// Calculate the effective accessibility score as of `now`.
+    pub fn calculate_at(
+        &self,
+        lifecycle: &MemoryLifecycle,
+        base_score: f64,
+        now: DateTime<Utc>,
+    ) -> f64 {
         let state_multiplier = lifecycle.state.accessibility_multiplier();
 
         // Recency boost: exponential decay based on time since last access
-        let hours_since_access = Utc::now()
+        let hours_since_access = now
             .signed_duration_since(lifecycle.last_access)
             .num_minutes() as f64
             / 60.0;
@@ -1235,7 +1310,11 @@ pub struct MemoryStateInfo {
 impl MemoryStateInfo {
     /// Create state info from a lifecycle.
     pub fn from_lifecycle(lifecycle: &MemoryLifecycle) -> Self {
-        let now = Utc::now();
+        Self::from_lifecycle_at(lifecycle, Utc::now())
+    }
+
+    /// Create state info from a lifecycle as of `now`.
+    pub fn from_lifecycle_at(lifecycle: &MemoryLifecycle, now: DateTime<Utc>) -> Self {
         let duration_since_access = now.signed_duration_since(lifecycle.last_access);
 
         // Format time since access
@@ -1592,6 +1671,39 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_state_update_service_follows_clock() {
+        use crate::clock::MockClock;
+
+        let clock = Arc::new(MockClock::default());
+        let service = StateUpdateService::new().with_clock(clock.clone());
+        let mut lifecycle = MemoryLifecycle::new();
+        lifecycle.last_access = clock.now();
+        lifecycle.transition_to_at(
+            MemoryState::Unavailable,
+            StateTransitionReason::UserSuppression { reason: None },
+            clock.now(),
+        );
+        lifecycle.suppression_until = Some(clock.now() + Duration::hours(2));
+
+        clock.advance(Duration::hours(1));
+        assert!(service.update_lifecycle(&mut lifecycle).is_empty());
+        assert_eq!(lifecycle.state, MemoryState::Unavailable);
+
+        // Suppression lifts at its deadline; the memory is past the Active
+        // window by then, so it settles in Dormant
+        clock.advance(Duration::hours(1));
+        let transitions = service.update_lifecycle(&mut lifecycle);
+        assert_eq!(lifecycle.state, MemoryState::Dormant);
+        assert!(matches!(transitions[0].reason, StateTransitionReason::SuppressionExpired));
+        assert_eq!(transitions[0].timestamp, clock.now());
+        assert_eq!(lifecycle.state_entered_at, clock.now());
+
+        clock.advance(Duration::days(DEFAULT_DORMANT_DECAY_DAYS));
+        service.update_lifecycle(&mut lifecycle);
+        assert_eq!(lifecycle.state, MemoryState::Silent);
+    }
+
     #[test]
     fn test_batch_update() {
         let service = StateUpdateService::with_config(StateDecayConfig {
@@ -1657,6 +1769,21 @@ mod tests {
         assert!(approx_eq(unavailable_score, 0.05, 0.01));
     }
 
+    #[test]
+    fn test_accessibility_at_fixed_time() {
+        let calc = AccessibilityCalculator {
+            frequency_weight: 0.0,
+            ..Default::default()
+        };
+        let lifecycle = MemoryLifecycle::new();
+
+        // One recency half-life after the access, half the recency boost is left
+        let later = lifecycle.last_access + Duration::hours(24);
+        let score = calc.calculate_at(&lifecycle, 0.5, later);
+        assert!(approx_eq(score, 0.5 + calc.recency_weight / 2.0, 0.001));
+        assert!(calc.calculate_at(&lifecycle, 0.5, lifecycle.last_access) > score);
+    }
+
     // ==================== State Time Accumulator Tests ====================
 
     #[test]
@@ -1701,6 +1828,12 @@ mod tests {
         assert_eq!(info.state, MemoryState::Unavailable);
         assert!(info.accessible_after.is_some());
         assert!(!info.recommendations.is_empty());
+
+        // Once the suppression has run out it is no longer recommended as a wait
+        let after = lifecycle.suppression_until.unwrap() + Duration::minutes(1);
+        let info = MemoryStateInfo::from_lifecycle_at(&lifecycle, after);
+        assert!(!info.recommendations.iter().any(|r| r.contains("temporarily suppressed")));
+        assert_eq!(info.time_since_access, "2 hours ago");
     }
 
     // ==================== Serialization Tests ====================
@@ -57,6 +57,20 @@ pub enum LinkType {
     UserDefined,
 }
 
+impl LinkType {
+    /// Map a persisted `memory_connections.link_type` to a link type
+    pub fn from_link_type(link_type: &str) -> Self {
+        match link_type {
+            "semantic" | "shared_concepts" => Self::Semantic,
+            "temporal" | "sequential" => Self::Temporal,
+            "spatial" => Self::Spatial,
+            "causal" => Self::Causal,
+            "part_of" => Self::PartOf,
+            _ => Self::UserDefined,
+        }
+    }
+}
+
 
 // ============================================================================
 // ASSOCIATION EDGE
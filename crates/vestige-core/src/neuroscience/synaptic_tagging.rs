@@ -69,6 +69,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
+use crate::clock::{system_clock, Clock};
+
 // ============================================================================
 // CONSTANTS
 // ============================================================================
@@ -249,8 +251,18 @@ impl SynapticTag {
 
     /// Calculate current tag strength with decay
     pub fn current_strength(&self, decay_fn: DecayFunction, lifetime_hours: f64) -> f64 {
+        self.strength_at(Utc::now(), decay_fn, lifetime_hours)
+    }
+
+    /// Calculate the tag strength at a given moment (e.g. when PRPs arrive)
+    pub fn strength_at(
+        &self,
+        at: DateTime<Utc>,
+        decay_fn: DecayFunction,
+        lifetime_hours: f64,
+    ) -> f64 {
         // Use milliseconds for precise timing (important for tests with short lifetimes)
-        let hours_elapsed = (Utc::now() - self.created_at).num_milliseconds() as f64 / 3_600_000.0;
+        let hours_elapsed = (at - self.created_at).num_milliseconds() as f64 / 3_600_000.0;
         decay_fn.apply(self.initial_strength, hours_elapsed, lifetime_hours)
     }
 
@@ -261,14 +273,30 @@ impl SynapticTag {
         lifetime_hours: f64,
         min_strength: f64,
     ) -> bool {
-        !self.captured && self.current_strength(decay_fn, lifetime_hours) >= min_strength
+        self.is_active_at(Utc::now(), decay_fn, lifetime_hours, min_strength)
+    }
+
+    /// Check if the tag is active at a given moment
+    pub fn is_active_at(
+        &self,
+        at: DateTime<Utc>,
+        decay_fn: DecayFunction,
+        lifetime_hours: f64,
+        min_strength: f64,
+    ) -> bool {
+        !self.captured && self.strength_at(at, decay_fn, lifetime_hours) >= min_strength
     }
 
     /// Mark this tag as captured
     pub fn capture(&mut self, event_id: &str) {
+        self.capture_at(event_id, Utc::now());
+    }
+
+    /// Mark this tag as captured at a given moment
+    pub fn capture_at(&mut self, event_id: &str, at: DateTime<Utc>) {
         self.captured = true;
         self.capture_event = Some(event_id.to_string());
-        self.captured_at = Some(Utc::now());
+        self.captured_at = Some(at);
     }
 
     /// Get the age of this tag in hours
@@ -451,6 +479,19 @@ impl ImportanceEventType {
             ImportanceEventType::NoveltySpike => 0.7,
         }
     }
+
+    /// Parse the name produced by `Display`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "user_flag" => Some(ImportanceEventType::UserFlag),
+            "emotional" => Some(ImportanceEventType::EmotionalContent),
+            "novelty" => Some(ImportanceEventType::NoveltySpike),
+            "repeated" => Some(ImportanceEventType::RepeatedAccess),
+            "cross_ref" => Some(ImportanceEventType::CrossReference),
+            "temporal" => Some(ImportanceEventType::TemporalProximity),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for ImportanceEventType {
@@ -770,6 +811,24 @@ pub struct SynapticTaggingConfig {
     pub cleanup_interval_hours: f64,
 }
 
+impl SynapticTaggingConfig {
+    /// Configuration for captures applied during consolidation
+    ///
+    /// Power-law decay keeps weak tags capturable across the full 9-hour
+    /// backward window; with exponential decay a tag is too weak to be
+    /// captured about an hour after encoding.
+    pub fn for_consolidation() -> Self {
+        Self {
+            capture_window: CaptureWindow::with_decay(
+                DEFAULT_BACKWARD_HOURS,
+                DEFAULT_FORWARD_HOURS,
+                DecayFunction::Power,
+            ),
+            ..Self::default()
+        }
+    }
+}
+
 impl Default for SynapticTaggingConfig {
     fn default() -> Self {
         Self {
@@ -852,6 +911,8 @@ pub struct SynapticTaggingSystem {
     config: SynapticTaggingConfig,
     /// Statistics
     stats: Arc<RwLock<TaggingStats>>,
+    /// Source of the current time
+    clock: Arc<dyn Clock>,
 }
 
 impl Default for SynapticTaggingSystem {
@@ -873,9 +934,16 @@ impl SynapticTaggingSystem {
             clusters: Arc::new(RwLock::new(Vec::new())),
             config,
             stats: Arc::new(RwLock::new(TaggingStats::default())),
+            clock: system_clock(),
         }
     }
 
+    /// Read the current time from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Get current configuration
     pub fn config(&self) -> &SynapticTaggingConfig {
         &self.config
@@ -898,7 +966,8 @@ impl SynapticTaggingSystem {
     /// # Returns
     /// The created synaptic tag
     pub fn tag_memory(&mut self, memory_id: &str) -> SynapticTag {
-        let tag = SynapticTag::new(memory_id);
+        let mut tag = SynapticTag::new(memory_id);
+        tag.created_at = self.clock.now();
 
         if let Ok(mut tags) = self.tags.write() {
             tags.insert(memory_id.to_string(), tag.clone());
@@ -917,7 +986,8 @@ impl SynapticTaggingSystem {
     /// Use this for memories that have initial importance signals (e.g., emotional content)
     /// but haven't crossed the threshold for full importance yet.
     pub fn tag_memory_with_strength(&mut self, memory_id: &str, strength: f64) -> SynapticTag {
-        let tag = SynapticTag::with_strength(memory_id, strength);
+        let mut tag = SynapticTag::with_strength(memory_id, strength);
+        tag.created_at = self.clock.now();
 
         if let Ok(mut tags) = self.tags.write() {
             tags.insert(memory_id.to_string(), tag.clone());
@@ -931,9 +1001,21 @@ impl SynapticTaggingSystem {
         tag
     }
 
+    /// Reinstate a previously persisted tag, keeping its original timestamps
+    pub fn restore_tag(&mut self, tag: SynapticTag) {
+        if let Ok(mut tags) = self.tags.write() {
+            tags.insert(tag.memory_id.clone(), tag);
+        }
+
+        if let Ok(mut stats) = self.stats.write() {
+            stats.active_tags = self.tags.read().map(|t| t.len()).unwrap_or(0);
+        }
+    }
+
     /// Tag a memory with encoding context
     pub fn tag_memory_with_context(&mut self, memory_id: &str, context: &str) -> SynapticTag {
-        let tag = SynapticTag::with_context(memory_id, context);
+        let mut tag = SynapticTag::with_context(memory_id, context);
+        tag.created_at = self.clock.now();
 
         if let Ok(mut tags) = self.tags.write() {
             tags.insert(memory_id.to_string(), tag.clone());
@@ -996,7 +1078,8 @@ impl SynapticTaggingSystem {
 
         // Create cluster if enabled and we have captures
         let cluster = if self.config.enable_clustering && !captured.is_empty() {
-            let cluster = ImportanceCluster::new(&event, &captured);
+            let mut cluster = ImportanceCluster::new(&event, &captured);
+            cluster.created_at = self.clock.now();
 
             if let Ok(mut clusters) = self.clusters.write() {
                 clusters.push(cluster.clone());
@@ -1033,8 +1116,13 @@ impl SynapticTaggingSystem {
         let effective_backward = self.config.capture_window.backward_hours * multiplier;
         let effective_forward = self.config.capture_window.forward_hours * multiplier;
 
-        let effective_window = CaptureWindow::new(effective_backward, effective_forward);
+        let effective_window = CaptureWindow::with_decay(
+            effective_backward,
+            effective_forward,
+            self.config.capture_window.decay_function,
+        );
 
+        let now = self.clock.now();
         if let Ok(mut tags) = self.tags.write() {
             let event_id = event.event_id();
 
@@ -1051,8 +1139,9 @@ impl SynapticTaggingSystem {
 
                 considered += 1;
 
-                // Calculate current tag strength
-                let current_strength = tag.current_strength(
+                // Tag strength when the PRPs arrive
+                let current_strength = tag.strength_at(
+                    event.timestamp,
                     self.config.capture_window.decay_function,
                     self.config.tag_lifetime_hours,
                 );
@@ -1080,14 +1169,14 @@ impl SynapticTaggingSystem {
                         (capture_score * 0.6 + event.strength * 0.4).min(1.0);
 
                     // Mark tag as captured
-                    tag.capture(&event_id);
+                    tag.capture_at(&event_id, now);
 
                     captured.push(CapturedMemory {
                         memory_id: tag.memory_id.clone(),
                         encoded_at: tag.created_at,
                         capture_event_id: event_id.clone(),
                         capture_event_type: event.event_type,
-                        captured_at: Utc::now(),
+                        captured_at: now,
                         capture_probability: capture_prob,
                         tag_strength_at_capture: current_strength,
                         consolidated_importance,
@@ -1117,6 +1206,7 @@ impl SynapticTaggingSystem {
     /// List of memory IDs that could be captured
     pub fn sweep_for_capture(&mut self, center_time: DateTime<Utc>) -> Vec<String> {
         let mut eligible = Vec::new();
+        let now = self.clock.now();
 
         if let Ok(tags) = self.tags.read() {
             for tag in tags.values() {
@@ -1132,7 +1222,8 @@ impl SynapticTaggingSystem {
                     continue;
                 }
 
-                let current_strength = tag.current_strength(
+                let current_strength = tag.strength_at(
+                    now,
                     self.config.capture_window.decay_function,
                     self.config.tag_lifetime_hours,
                 );
@@ -1154,6 +1245,7 @@ impl SynapticTaggingSystem {
     /// 3. Remove captured tags that are no longer needed
     pub fn decay_tags(&mut self) {
         let mut expired_count = 0;
+        let now = self.clock.now();
 
         if let Ok(mut tags) = self.tags.write() {
             tags.retain(|_, tag| {
@@ -1161,13 +1253,14 @@ impl SynapticTaggingSystem {
                 if tag.captured {
                     // Keep for 24 hours after capture
                     if let Some(captured_at) = tag.captured_at {
-                        return (Utc::now() - captured_at).num_hours() < 24;
+                        return (now - captured_at).num_hours() < 24;
                     }
                     return false;
                 }
 
                 // Check if tag has decayed
-                let current_strength = tag.current_strength(
+                let current_strength = tag.strength_at(
+                    now,
                     self.config.capture_window.decay_function,
                     self.config.tag_lifetime_hours,
                 );
@@ -1186,7 +1279,7 @@ impl SynapticTaggingSystem {
         if let Ok(mut stats) = self.stats.write() {
             stats.tags_expired += expired_count;
             stats.active_tags = self.tags.read().map(|t| t.len()).unwrap_or(0);
-            stats.last_cleanup = Some(Utc::now());
+            stats.last_cleanup = Some(now);
         }
     }
 
@@ -1202,7 +1295,8 @@ impl SynapticTaggingSystem {
             .ok()
             .and_then(|tags| tags.get(memory_id).cloned())
             .map(|tag| {
-                tag.is_active(
+                tag.is_active_at(
+                    self.clock.now(),
                     self.config.capture_window.decay_function,
                     self.config.tag_lifetime_hours,
                     self.config.min_tag_strength,
@@ -1228,7 +1322,8 @@ impl SynapticTaggingSystem {
             .map(|tags| {
                 tags.values()
                     .filter(|tag| {
-                        tag.is_active(
+                        tag.is_active_at(
+                            self.clock.now(),
                             self.config.capture_window.decay_function,
                             self.config.tag_lifetime_hours,
                             self.config.min_tag_strength,
@@ -1309,7 +1404,8 @@ impl SynapticTaggingSystem {
                         !tag.captured
                             && tag.created_at >= start
                             && tag.created_at <= end
-                            && tag.is_active(
+                            && tag.is_active_at(
+                                self.clock.now(),
                                 self.config.capture_window.decay_function,
                                 self.config.tag_lifetime_hours,
                                 self.config.min_tag_strength,
@@ -1506,6 +1602,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_tags_decay_on_mock_clock() {
+        use crate::clock::MockClock;
+
+        let clock = Arc::new(MockClock::default());
+        let mut stc = SynapticTaggingSystem::new().with_clock(clock.clone());
+        let lifetime = stc.config().tag_lifetime_hours;
+
+        let tag = stc.tag_memory("mem-123");
+        assert_eq!(tag.created_at, clock.now());
+        assert!(stc.has_active_tag("mem-123"));
+
+        // Well past the tag's lifetime without sleeping
+        clock.advance(Duration::hours((lifetime * 4.0) as i64));
+        assert!(!stc.has_active_tag("mem-123"));
+        stc.decay_tags();
+        assert!(stc.get_tag("mem-123").is_none());
+        assert_eq!(stc.stats().last_cleanup, Some(clock.now()));
+    }
+
     #[test]
     fn test_stats_tracking() {
         let mut stc = SynapticTaggingSystem::new();
@@ -1607,4 +1723,29 @@ mod tests {
         let candidates = stc.get_capture_candidates(start, end);
         assert_eq!(candidates.len(), 2);
     }
+
+    #[test]
+    fn test_restored_tag_captured_across_nine_hour_window() {
+        let mut stc = SynapticTaggingSystem::with_config(SynapticTaggingConfig::for_consolidation());
+        let event_time = Utc::now();
+
+        let mut recent = SynapticTag::new("vacation-chat");
+        recent.created_at = event_time - Duration::minutes(510);
+        stc.restore_tag(recent);
+        let mut stale = SynapticTag::new("old-chat");
+        stale.created_at = event_time - Duration::hours(10);
+        stc.restore_tag(stale);
+
+        let mut event = ImportanceEvent::user_flag("resignation", None);
+        event.timestamp = event_time;
+        let result = stc.trigger_prp(event);
+
+        let captured: Vec<&str> = result
+            .captured_memories
+            .iter()
+            .map(|m| m.memory_id.as_str())
+            .collect();
+        assert_eq!(captured, vec!["vacation-chat"]);
+        assert!(result.captured_memories[0].is_backward_capture());
+    }
 }
@@ -5,6 +5,10 @@
 
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
+
+use crate::memory::{QueryExpansionMode, SearchSyntax};
+
 // ============================================================================
 // FUSION ALGORITHMS
 // ============================================================================
@@ -99,31 +103,262 @@ pub fn linear_combination(
 // HYBRID SEARCH CONFIGURATION
 // ============================================================================
 
+/// Default RRF constant: dampens the advantage of the very top ranks
+pub const DEFAULT_RRF_K: u32 = 60;
+
+/// How hybrid search merges the keyword and semantic result lists
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "camelCase")]
+pub enum FusionMethod {
+    /// Reciprocal rank fusion: each list adds 1/(k + rank), so only ranks
+    /// matter and BM25 and cosine scales cannot swamp each other
+    Rrf {
+        /// Fusion constant (higher = more uniform weighting)
+        k: u32,
+    },
+    /// Weighted sum of each list's scores, normalized to its best match
+    Linear {
+        /// Weight for keyword (BM25/FTS5) scores
+        kw_weight: f32,
+        /// Weight for semantic (cosine) scores
+        sem_weight: f32,
+    },
+}
+
+impl Default for FusionMethod {
+    fn default() -> Self {
+        Self::Rrf { k: DEFAULT_RRF_K }
+    }
+}
+
+impl FusionMethod {
+    /// Merge the two lists, best first
+    ///
+    /// RRF scores are scaled so a result ranked first in both lists scores
+    /// 1.0, keeping them on the same 0..1 footing as linear scores when they
+    /// feed the rerank's relevance signal.
+    pub fn fuse(
+        &self,
+        keyword_results: &[(String, f32)],
+        semantic_results: &[(String, f32)],
+    ) -> Vec<(String, f32)> {
+        match *self {
+            Self::Rrf { k } => reciprocal_rank_fusion(keyword_results, semantic_results, k as f32)
+                .into_iter()
+                .map(|(id, score)| (id, score * k as f32 / 2.0))
+                .collect(),
+            Self::Linear { kw_weight, sem_weight } => {
+                linear_combination(keyword_results, semantic_results, kw_weight, sem_weight)
+            }
+        }
+    }
+
+    /// Relevance of one result from its 0-based rank and score in each list
+    ///
+    /// RRF uses only the ranks and matches `fuse`; linear uses only the
+    /// scores, so a result outside a candidate list still counts its score.
+    pub fn relevance(
+        &self,
+        keyword_rank: Option<usize>,
+        keyword_score: Option<f32>,
+        semantic_rank: Option<usize>,
+        semantic_score: Option<f32>,
+    ) -> f32 {
+        match *self {
+            Self::Rrf { k } => {
+                let k = k as f32;
+                let contribution = |rank: Option<usize>| rank.map_or(0.0, |r| 1.0 / (k + r as f32));
+                (contribution(keyword_rank) + contribution(semantic_rank)) * k / 2.0
+            }
+            Self::Linear { kw_weight, sem_weight } => {
+                keyword_score.unwrap_or(0.0) * kw_weight + semantic_score.unwrap_or(0.0) * sem_weight
+            }
+        }
+    }
+
+    /// RRF needs a positive k; linear weights must be finite and non-negative
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Self::Rrf { k } => k > 0,
+            Self::Linear { kw_weight, sem_weight } => [kw_weight, sem_weight]
+                .iter()
+                .all(|w| w.is_finite() && *w >= 0.0),
+        }
+    }
+}
+
+/// Default `pin_bonus`: enough to lift a pinned memory over an unpinned one
+/// that matches about as well but is fresher and more important
+pub const DEFAULT_PIN_BONUS: f64 = 0.3;
+
 /// Configuration for hybrid search
 #[derive(Debug, Clone)]
 pub struct HybridSearchConfig {
-    /// Weight for keyword (BM25/FTS5) results
+    /// How keyword and semantic results are merged (RRF by default)
+    pub fusion: FusionMethod,
+    /// Keyword weight `fuse_linear` falls back to when `fusion` is RRF
+    #[deprecated(note = "set `fusion: FusionMethod::Linear { kw_weight, sem_weight }` instead")]
     pub keyword_weight: f32,
-    /// Weight for semantic (embedding) results
+    /// Semantic weight `fuse_linear` falls back to when `fusion` is RRF
+    #[deprecated(note = "set `fusion: FusionMethod::Linear { kw_weight, sem_weight }` instead")]
     pub semantic_weight: f32,
-    /// RRF constant (higher = more uniform weighting)
+    /// RRF constant `fuse_rrf` falls back to when `fusion` is linear
+    #[deprecated(note = "set `fusion: FusionMethod::Rrf { k }` instead")]
     pub rrf_k: f32,
-    /// Minimum semantic similarity threshold
+    /// Minimum semantic similarity for a semantic hit to count toward the
+    /// candidate set `Storage::search_with_count` pages through
     pub min_semantic_similarity: f32,
     /// Number of results to fetch from each source before fusion
     pub source_limit_multiplier: usize,
+    /// Attach a per-signal `ScoreBreakdown` to every result
+    pub explain: bool,
+    /// Rerank weight for recency (how recently the memory was accessed)
+    pub recency_weight: f64,
+    /// Rerank weight for importance (normalized ACT-R activation)
+    pub importance_weight: f64,
+    /// Rerank weight for relevance (the fused keyword/semantic score)
+    pub relevance_weight: f64,
+    /// Rerank weight for source trust; all four weights are renormalized to
+    /// sum to 1 when applied
+    pub trust_weight: f64,
+    /// Per-hour decay base for the recency signal (recency = base^hours)
+    pub recency_decay_base: f64,
+    /// Added to the final score of pinned memories, outside the weighted sum
+    pub pin_bonus: f64,
+    /// When to embed HyDE-expanded query variants instead of the literal query
+    pub query_expansion: QueryExpansionMode,
+    /// FTS5 syntax for the keyword half (phrases and prefixes with `Advanced`)
+    pub syntax: SearchSyntax,
+    /// Only search memories in this namespace (None = all namespaces)
+    pub namespace: Option<String>,
 }
 
 impl Default for HybridSearchConfig {
+    #[allow(deprecated)]
     fn default() -> Self {
         Self {
+            fusion: FusionMethod::default(),
             keyword_weight: 0.3,
             semantic_weight: 0.7,
-            rrf_k: 60.0,
+            rrf_k: DEFAULT_RRF_K as f32,
             min_semantic_similarity: 0.3,
             source_limit_multiplier: 2,
+            explain: false,
+            // Generative Agents (Park et al. 2023) three-signal rerank
+            recency_weight: 0.2,
+            importance_weight: 0.3,
+            relevance_weight: 0.5,
+            trust_weight: 0.1,
+            recency_decay_base: 0.995,
+            pin_bonus: DEFAULT_PIN_BONUS,
+            query_expansion: QueryExpansionMode::Auto,
+            syntax: SearchSyntax::Plain,
+            namespace: None,
+        }
+    }
+}
+
+impl HybridSearchConfig {
+    /// Use the rerank weights and recency half-life of a ranking profile
+    pub fn with_profile(mut self, profile: RankingProfile) -> Self {
+        self.recency_weight = profile.recency_weight;
+        self.importance_weight = profile.importance_weight;
+        self.relevance_weight = profile.relevance_weight;
+        self.recency_decay_base = profile.recency_decay_base();
+        self
+    }
+
+    /// Validate that the recency/importance/relevance weights sum to
+    /// approximately 1.0, the trust weight is non-negative, the recency
+    /// decay base lies in (0, 1] and the fusion method is usable
+    pub fn is_valid(&self) -> bool {
+        let weights = [self.recency_weight, self.importance_weight, self.relevance_weight];
+        let sum: f64 = weights.iter().sum();
+        self.fusion.is_valid()
+            && weights.iter().all(|w| *w >= 0.0)
+            && (sum - 1.0).abs() < 0.01
+            && self.trust_weight >= 0.0
+            && self.recency_decay_base > 0.0
+            && self.recency_decay_base <= 1.0
+            && self.pin_bonus >= 0.0
+    }
+}
+
+// ============================================================================
+// RANKING PROFILES
+// ============================================================================
+
+/// Rerank weights and recency half-life chosen per query
+///
+/// The presets trade recency against established knowledge: `RECENT` for
+/// "what did we change this week", `ESTABLISHED` for well-consolidated
+/// memories regardless of when they were last touched.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RankingProfile {
+    /// Weight for recency (how recently the memory was accessed)
+    pub recency_weight: f64,
+    /// Weight for importance (normalized ACT-R activation)
+    pub importance_weight: f64,
+    /// Weight for relevance (the fused keyword/semantic score)
+    pub relevance_weight: f64,
+    /// Hours for the recency signal to halve
+    pub recency_half_life_hours: f64,
+}
+
+impl RankingProfile {
+    /// The default rerank (0.2 / 0.3 / 0.5, ≈0.995 decay per hour)
+    pub const BALANCED: Self = Self {
+        recency_weight: 0.2,
+        importance_weight: 0.3,
+        relevance_weight: 0.5,
+        recency_half_life_hours: 138.3,
+    };
+
+    /// Heavily favor memories accessed in the last day or two
+    pub const RECENT: Self = Self {
+        recency_weight: 0.5,
+        importance_weight: 0.1,
+        relevance_weight: 0.4,
+        recency_half_life_hours: 24.0,
+    };
+
+    /// Favor high-activation memories and ignore recency
+    pub const ESTABLISHED: Self = Self {
+        recency_weight: 0.0,
+        importance_weight: 0.5,
+        relevance_weight: 0.5,
+        recency_half_life_hours: 720.0,
+    };
+
+    /// Preset by name: `balanced`, `recent` or `established`
+    pub fn preset(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "balanced" => Some(Self::BALANCED),
+            "recent" => Some(Self::RECENT),
+            "established" => Some(Self::ESTABLISHED),
+            _ => None,
         }
     }
+
+    /// Per-hour decay base with this half-life (recency = base^hours)
+    pub fn recency_decay_base(&self) -> f64 {
+        0.5_f64.powf(1.0 / self.recency_half_life_hours)
+    }
+
+    /// Validate that the weights are non-negative and sum to approximately
+    /// 1.0, and the half-life is positive
+    pub fn is_valid(&self) -> bool {
+        let weights = [self.recency_weight, self.importance_weight, self.relevance_weight];
+        weights.iter().all(|w| *w >= 0.0)
+            && (weights.iter().sum::<f64>() - 1.0).abs() < 0.01
+            && self.recency_half_life_hours > 0.0
+    }
+}
+
+impl Default for RankingProfile {
+    fn default() -> Self {
+        Self::BALANCED
+    }
 }
 
 // ============================================================================
@@ -159,27 +394,47 @@ impl HybridSearcher {
         &self.config
     }
 
+    /// Fuse keyword and semantic results with the configured method
+    pub fn fuse(
+        &self,
+        keyword_results: &[(String, f32)],
+        semantic_results: &[(String, f32)],
+    ) -> Vec<(String, f32)> {
+        self.config.fusion.fuse(keyword_results, semantic_results)
+    }
+
     /// Fuse keyword and semantic results using RRF
+    ///
+    /// Uses the configured k, or the deprecated `rrf_k` when the config fuses
+    /// linearly.
+    #[allow(deprecated)]
     pub fn fuse_rrf(
         &self,
         keyword_results: &[(String, f32)],
         semantic_results: &[(String, f32)],
     ) -> Vec<(String, f32)> {
-        reciprocal_rank_fusion(keyword_results, semantic_results, self.config.rrf_k)
+        let k = match self.config.fusion {
+            FusionMethod::Rrf { k } => k as f32,
+            FusionMethod::Linear { .. } => self.config.rrf_k,
+        };
+        reciprocal_rank_fusion(keyword_results, semantic_results, k)
     }
 
     /// Fuse results using linear combination
+    ///
+    /// Uses the configured weights, or the deprecated `keyword_weight` /
+    /// `semantic_weight` when the config uses RRF.
+    #[allow(deprecated)]
     pub fn fuse_linear(
         &self,
         keyword_results: &[(String, f32)],
         semantic_results: &[(String, f32)],
     ) -> Vec<(String, f32)> {
-        linear_combination(
-            keyword_results,
-            semantic_results,
-            self.config.keyword_weight,
-            self.config.semantic_weight,
-        )
+        let (kw_weight, sem_weight) = match self.config.fusion {
+            FusionMethod::Linear { kw_weight, sem_weight } => (kw_weight, sem_weight),
+            FusionMethod::Rrf { .. } => (self.config.keyword_weight, self.config.semantic_weight),
+        };
+        linear_combination(keyword_results, semantic_results, kw_weight, sem_weight)
     }
 
     /// Determine if semantic search should be used based on query
@@ -290,6 +545,72 @@ mod tests {
         assert_eq!(results[0].0, "doc-1");
     }
 
+    #[test]
+    fn test_rerank_weights_validation() {
+        assert!(HybridSearchConfig::default().is_valid());
+
+        let relevance_heavy = HybridSearchConfig {
+            recency_weight: 0.05,
+            importance_weight: 0.05,
+            relevance_weight: 0.9,
+            ..Default::default()
+        };
+        assert!(relevance_heavy.is_valid());
+
+        let unbalanced = HybridSearchConfig {
+            relevance_weight: 0.9,
+            ..Default::default()
+        };
+        assert!(!unbalanced.is_valid());
+
+        let bad_decay = HybridSearchConfig {
+            recency_decay_base: 1.5,
+            ..Default::default()
+        };
+        assert!(!bad_decay.is_valid());
+
+        let zero_k = HybridSearchConfig {
+            fusion: FusionMethod::Rrf { k: 0 },
+            ..Default::default()
+        };
+        assert!(!zero_k.is_valid());
+    }
+
+    #[test]
+    fn test_rrf_rank_stable_when_one_modality_scores_dominate() {
+        // `x` is the keyword outlier; `a` and `b` are the better matches overall
+        let semantic = vec![
+            ("a".to_string(), 0.80),
+            ("b".to_string(), 0.79),
+            ("x".to_string(), 0.10),
+        ];
+        let keyword_with_outlier = |outlier: f32| {
+            vec![
+                ("x".to_string(), outlier),
+                ("a".to_string(), 10.0),
+                ("b".to_string(), 9.0),
+            ]
+        };
+        let order = |fused: Vec<(String, f32)>| fused.into_iter().map(|(id, _)| id).collect::<Vec<_>>();
+
+        let rrf = FusionMethod::default();
+        let modest = order(rrf.fuse(&keyword_with_outlier(11.0), &semantic));
+        let inflated = order(rrf.fuse(&keyword_with_outlier(1000.0), &semantic));
+        assert_eq!(modest, inflated);
+        assert_eq!(modest[0], "a");
+
+        // Linear fusion lets the one inflated BM25 score take over the ranking
+        let linear = FusionMethod::Linear { kw_weight: 0.5, sem_weight: 0.5 };
+        assert_eq!(order(linear.fuse(&keyword_with_outlier(11.0), &semantic))[0], "a");
+        assert_eq!(order(linear.fuse(&keyword_with_outlier(1000.0), &semantic))[0], "x");
+
+        // RRF relevance agrees with `fuse`, scaled so first in both lists is 1.0
+        assert!((rrf.relevance(Some(0), None, Some(0), None) - 1.0).abs() < 1e-6);
+        let fused = rrf.fuse(&keyword_with_outlier(11.0), &semantic);
+        let a = fused.iter().find(|(id, _)| id == "a").unwrap().1;
+        assert!((rrf.relevance(Some(1), Some(10.0), Some(0), Some(0.8)) - a).abs() < 1e-6);
+    }
+
     #[test]
     fn test_linear_with_unequal_weights() {
         let keyword = vec![("doc-1".to_string(), 1.0)];
@@ -304,4 +625,48 @@ mod tests {
 
         assert!(doc1_score.unwrap() > doc2_score.unwrap());
     }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_weights_still_drive_explicit_fusion() {
+        let keyword = vec![("doc-1".to_string(), 1.0)];
+        let semantic = vec![("doc-2".to_string(), 1.0)];
+        let config = HybridSearchConfig {
+            keyword_weight: 0.9,
+            semantic_weight: 0.1,
+            rrf_k: 1.0,
+            ..Default::default()
+        };
+
+        // Fusing with RRF, so linear falls back to the legacy weights
+        let searcher = HybridSearcher::with_config(config.clone());
+        assert_eq!(searcher.fuse_linear(&keyword, &semantic)[0], ("doc-1".to_string(), 0.9));
+
+        // Fusing linearly, so RRF falls back to the legacy k
+        let searcher = HybridSearcher::with_config(HybridSearchConfig {
+            fusion: FusionMethod::Linear { kw_weight: 0.5, sem_weight: 0.5 },
+            ..config
+        });
+        assert_eq!(searcher.fuse_rrf(&keyword, &semantic)[0].1, 1.0);
+    }
+
+    #[test]
+    fn test_ranking_profiles() {
+        for name in ["balanced", "Recent", "ESTABLISHED"] {
+            let profile = RankingProfile::preset(name).unwrap();
+            assert!(profile.is_valid());
+            assert!(HybridSearchConfig::default().with_profile(profile).is_valid());
+        }
+        assert!(RankingProfile::preset("newest").is_none());
+
+        let balanced = HybridSearchConfig::default().with_profile(RankingProfile::BALANCED);
+        assert!((balanced.recency_decay_base - HybridSearchConfig::default().recency_decay_base).abs() < 1e-4);
+        assert!((RankingProfile::RECENT.recency_decay_base().powf(24.0) - 0.5).abs() < 1e-9);
+
+        let unbalanced = RankingProfile {
+            relevance_weight: 0.9,
+            ..RankingProfile::BALANCED
+        };
+        assert!(!unbalanced.is_valid());
+    }
 }
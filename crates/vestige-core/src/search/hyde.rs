@@ -18,8 +18,16 @@
 //! The centroid embedding captures a broader semantic space than the raw query,
 //! improving recall for conceptual and question-style queries.
 
+use serde::Serialize;
+
+use crate::memory::QueryExpansionMode;
+
+/// Maximum number of variants (including the original query) embedded per query
+pub const MAX_EXPANSION_VARIANTS: usize = 4;
+
 /// Query intent classification
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub enum QueryIntent {
     /// "What is X?" / "Explain X"
     Definition,
@@ -35,6 +43,19 @@ pub enum QueryIntent {
     Technical,
 }
 
+impl QueryIntent {
+    /// Whether queries with this intent are expanded before embedding
+    ///
+    /// Temporal and technical queries stay close to the literal query, since
+    /// template variants tend to drift away from dates and identifiers.
+    pub fn uses_expansion(&self) -> bool {
+        matches!(
+            self,
+            Self::Definition | Self::HowTo | Self::Reasoning | Self::Lookup
+        )
+    }
+}
+
 /// Classify query intent from the raw query string
 pub fn classify_intent(query: &str) -> QueryIntent {
     let lower = query.to_lowercase();
@@ -113,6 +134,44 @@ pub fn expand_query(query: &str) -> Vec<String> {
     variants
 }
 
+/// How a query is embedded for semantic search
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryExpansion {
+    /// Classified query intent
+    pub intent: QueryIntent,
+    /// Whether expanded variants are averaged into a centroid
+    pub expanded: bool,
+    /// Texts that are embedded (just the query when not expanded)
+    pub variants: Vec<String>,
+}
+
+/// Decide how a query is embedded under the given expansion mode
+///
+/// `Auto` expands only intents where `uses_expansion` holds, `Off` never
+/// expands and `Force` always does. At most `MAX_EXPANSION_VARIANTS` texts
+/// are embedded.
+pub fn plan_expansion(query: &str, mode: QueryExpansionMode) -> QueryExpansion {
+    let intent = classify_intent(query);
+    let expanded = match mode {
+        QueryExpansionMode::Auto => intent.uses_expansion(),
+        QueryExpansionMode::Off => false,
+        QueryExpansionMode::Force => true,
+    };
+    let variants = if expanded {
+        let mut variants = expand_query(query);
+        variants.truncate(MAX_EXPANSION_VARIANTS);
+        variants
+    } else {
+        vec![query.to_string()]
+    };
+    QueryExpansion {
+        intent,
+        expanded,
+        variants,
+    }
+}
+
 /// Average multiple embedding vectors to create a centroid
 ///
 /// The centroid captures the "semantic center" of all expanded queries,
@@ -191,6 +250,14 @@ mod tests {
         assert_eq!(classify_intent("vestige memory system"), QueryIntent::Lookup);
     }
 
+    #[test]
+    fn test_uses_expansion() {
+        assert!(QueryIntent::Definition.uses_expansion());
+        assert!(QueryIntent::Lookup.uses_expansion());
+        assert!(!QueryIntent::Temporal.uses_expansion());
+        assert!(!QueryIntent::Technical.uses_expansion());
+    }
+
     #[test]
     fn test_expand_query_produces_variants() {
         let variants = expand_query("What is FSRS?");
@@ -198,6 +265,47 @@ mod tests {
         assert_eq!(variants[0], "What is FSRS?");
     }
 
+    #[test]
+    fn test_plan_expansion_off_embeds_literal_query() {
+        for query in ["What is FSRS?", "how to rotate keys", "Storage::ingest", "when did it ship"] {
+            let plan = plan_expansion(query, QueryExpansionMode::Off);
+            assert!(!plan.expanded);
+            assert_eq!(plan.variants, vec![query.to_string()]);
+        }
+    }
+
+    #[test]
+    fn test_plan_expansion_auto_expands_four_intents() {
+        let cases = [
+            ("What is FSRS?", QueryIntent::Definition, true),
+            ("how to rotate keys", QueryIntent::HowTo, true),
+            ("why does retention decay", QueryIntent::Reasoning, true),
+            ("vestige memory system", QueryIntent::Lookup, true),
+            ("when did the last consolidation run", QueryIntent::Temporal, false),
+            ("Storage::ingest", QueryIntent::Technical, false),
+        ];
+        for (query, intent, expanded) in cases {
+            let plan = plan_expansion(query, QueryExpansionMode::Auto);
+            assert_eq!(plan.intent, intent, "{query}");
+            assert_eq!(plan.expanded, expanded, "{query}");
+            assert_eq!(plan.variants.len() > 1, expanded, "{query}");
+        }
+    }
+
+    #[test]
+    fn test_plan_expansion_force_and_variant_cap() {
+        let plan = plan_expansion("Storage::ingest", QueryExpansionMode::Force);
+        assert_eq!(plan.intent, QueryIntent::Technical);
+        assert!(plan.expanded);
+        assert!(plan.variants.len() > 1);
+
+        for query in ["What is FSRS?", "how to rotate keys", "when did it ship"] {
+            let plan = plan_expansion(query, QueryExpansionMode::Force);
+            assert!(plan.variants.len() <= MAX_EXPANSION_VARIANTS);
+            assert_eq!(plan.variants[0], query);
+        }
+    }
+
     #[test]
     fn test_centroid_embedding() {
         let embeddings = vec![
@@ -3,6 +3,8 @@
 //! Provides keyword-based search using SQLite FTS5.
 //! Includes query sanitization for security.
 
+use crate::memory::SearchSyntax;
+
 // ============================================================================
 // FTS5 QUERY SANITIZATION
 // ============================================================================
@@ -10,6 +12,12 @@
 /// Dangerous FTS5 operators that could be used for injection or DoS
 const FTS5_OPERATORS: &[&str] = &["OR", "AND", "NOT", "NEAR"];
 
+/// Shortest stem allowed before a prefix wildcard (`ab*` would expand to most of the index)
+const MIN_PREFIX_LEN: usize = 3;
+
+/// Maximum number of terms kept from an advanced query
+const MAX_ADVANCED_TERMS: usize = 32;
+
 /// Sanitize input for FTS5 MATCH queries
 ///
 /// Prevents:
@@ -19,10 +27,10 @@ const FTS5_OPERATORS: &[&str] = &["OR", "AND", "NOT", "NEAR"];
 /// - DoS via complex query patterns
 pub fn sanitize_fts5_query(query: &str) -> String {
     // Limit query length to prevent DoS (char-aware to avoid UTF-8 boundary issues)
-    let limited: String = query.chars().take(1000).collect();
+    let limited = limit_query(query);
 
     // Remove FTS5 special characters and operators
-    let mut sanitized = limited.to_string();
+    let mut sanitized = limited;
 
     // Remove special characters: * : ^ - " ( )
     sanitized = sanitized
@@ -65,6 +73,127 @@ pub fn sanitize_fts5_query(query: &str) -> String {
     format!("\"{}\"", sanitized)
 }
 
+/// Truncate to 1000 characters and blank out control characters, which
+/// FTS5 rejects (a NUL ends the query string early)
+fn limit_query(query: &str) -> String {
+    query
+        .chars()
+        .take(1000)
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect()
+}
+
+/// Sanitize a query for FTS5 MATCH using the requested syntax
+pub fn sanitize_fts5_query_with(query: &str, syntax: SearchSyntax) -> String {
+    match syntax {
+        SearchSyntax::Plain => sanitize_fts5_query(query),
+        SearchSyntax::Advanced => sanitize_fts5_advanced_query(query),
+    }
+}
+
+/// Sanitize input for FTS5 MATCH, keeping phrases, prefix terms and negation
+///
+/// Balanced `"double-quoted phrases"` stay exact phrases and words ending in
+/// `*` with a stem of at least `MIN_PREFIX_LEN` word characters become prefix
+/// terms; every other word is quoted as a literal. All terms must match,
+/// except terms preceded by `-` (`-flaky`, `-"pool exhausted"`) or an
+/// uppercase `NOT`, which must not. A query of only negated terms matches
+/// nothing, since FTS5 cannot express "everything except". Column filters,
+/// NEAR and other boolean operators, parentheses and an unbalanced trailing
+/// quote are neutralized exactly as in `sanitize_fts5_query`.
+pub fn sanitize_fts5_advanced_query(query: &str) -> String {
+    let limited = limit_query(query);
+    // (FTS5 term, negated)
+    let mut terms: Vec<(String, bool)> = Vec::new();
+    let mut negate_next = false;
+
+    let mut rest = limited.as_str();
+    while !rest.is_empty() {
+        let (outside, quoted, remaining) = match rest.split_once('"') {
+            Some((before, after)) => match after.split_once('"') {
+                Some((phrase, after)) => (before, Some(phrase), after),
+                // Unbalanced quote: the remainder is plain words
+                None => (before, None, after),
+            },
+            None => (rest, None, ""),
+        };
+
+        for word in outside.split_whitespace() {
+            if word == "NOT" {
+                negate_next = true;
+                continue;
+            }
+            let negated = std::mem::take(&mut negate_next) || word.starts_with('-');
+            push_advanced_word(word.trim_start_matches('-'), negated, &mut terms);
+        }
+        if let Some(phrase) = quoted {
+            // `-"phrase"`: the hyphen is the last word before the quote
+            let hyphenated = outside.ends_with('-')
+                && outside
+                    .split_whitespace()
+                    .last()
+                    .is_some_and(|w| w.chars().all(|c| c == '-'));
+            let negated = std::mem::take(&mut negate_next) || hyphenated;
+            let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !phrase.is_empty() {
+                terms.push((format!("\"{}\"", phrase), negated));
+            }
+        }
+        rest = remaining;
+    }
+
+    terms.truncate(MAX_ADVANCED_TERMS);
+    let (excluded, included): (Vec<_>, Vec<_>) = terms.into_iter().partition(|(_, negated)| *negated);
+    if included.is_empty() {
+        return "\"\"".to_string();
+    }
+    let included: Vec<String> = included.into_iter().map(|(term, _)| term).collect();
+    let mut sanitized = if excluded.is_empty() || included.len() == 1 {
+        included.join(" ")
+    } else {
+        format!("({})", included.join(" "))
+    };
+    for (term, _) in excluded {
+        sanitized.push_str(" NOT ");
+        sanitized.push_str(&term);
+    }
+    sanitized
+}
+
+/// Append the FTS5 term(s) for one unquoted word of an advanced query
+///
+/// A negated word that splits into several parts (`-content:secret`) is
+/// excluded as one phrase rather than part by part.
+fn push_advanced_word(word: &str, negated: bool, terms: &mut Vec<(String, bool)>) {
+    let stem = word.trim_end_matches('*');
+    if stem.len() < word.len()
+        && stem.chars().count() >= MIN_PREFIX_LEN
+        && stem.chars().all(|c| c.is_alphanumeric() || c == '_')
+    {
+        terms.push((format!("\"{}\"*", stem), negated));
+        return;
+    }
+
+    let cleaned: String = word
+        .chars()
+        .map(|c| match c {
+            '*' | ':' | '^' | '-' | '(' | ')' | '{' | '}' | '[' | ']' => ' ',
+            _ => c,
+        })
+        .collect();
+    let parts: Vec<&str> = cleaned
+        .split_whitespace()
+        .filter(|part| !FTS5_OPERATORS.iter().any(|op| op.eq_ignore_ascii_case(part)))
+        .collect();
+    if negated {
+        if !parts.is_empty() {
+            terms.push((format!("\"{}\"", parts.join(" ")), true));
+        }
+    } else {
+        terms.extend(parts.into_iter().map(|part| (format!("\"{}\"", part), false)));
+    }
+}
+
 // ============================================================================
 // KEYWORD SEARCHER
 // ============================================================================
@@ -230,6 +359,64 @@ mod tests {
         assert!(sanitized.len() <= 1004);
     }
 
+    #[test]
+    fn test_advanced_query_phrases_and_prefixes() {
+        assert_eq!(
+            sanitize_fts5_advanced_query("\"connection pool exhausted\""),
+            "\"connection pool exhausted\""
+        );
+        assert_eq!(sanitize_fts5_advanced_query("consolid*"), "\"consolid\"*");
+        assert_eq!(
+            sanitize_fts5_advanced_query("\"pool  exhausted\" retry consolid*"),
+            "\"pool exhausted\" \"retry\" \"consolid\"*"
+        );
+        // Stems too short for a prefix are searched literally
+        assert_eq!(sanitize_fts5_advanced_query("ab*"), "\"ab\"");
+        assert_eq!(sanitize_fts5_query_with("a b", SearchSyntax::Plain), "\"a b\"");
+    }
+
+    #[test]
+    fn test_advanced_query_negation() {
+        assert_eq!(sanitize_fts5_advanced_query("pool -flaky"), "\"pool\" NOT \"flaky\"");
+        assert_eq!(sanitize_fts5_advanced_query("pool NOT flaky"), "\"pool\" NOT \"flaky\"");
+        assert_eq!(
+            sanitize_fts5_advanced_query("retry pool -\"connection reset\" -timeout*"),
+            "(\"retry\" \"pool\") NOT \"connection reset\" NOT \"timeout\"*"
+        );
+        assert_eq!(
+            sanitize_fts5_advanced_query("NOT \"connection reset\" pool"),
+            "\"pool\" NOT \"connection reset\""
+        );
+        // Only uppercase NOT is an operator; hyphens inside words are not
+        assert_eq!(sanitize_fts5_advanced_query("do not retry"), "\"do\" \"retry\"");
+        assert_eq!(sanitize_fts5_advanced_query("well-known"), "\"well\" \"known\"");
+        // Nothing left to include: match nothing
+        assert_eq!(sanitize_fts5_advanced_query("-flaky NOT pool"), "\"\"");
+        assert_eq!(sanitize_fts5_advanced_query("NOT NOT pool"), "\"\"");
+    }
+
+    #[test]
+    fn test_advanced_query_neutralizes_hostile_input() {
+        assert_eq!(sanitize_fts5_advanced_query("content:secret"), "\"content\" \"secret\"");
+        assert_eq!(sanitize_fts5_advanced_query("OR OR"), "\"\"");
+        assert_eq!(sanitize_fts5_advanced_query("a NEAR(b c)"), "\"a\" \"b\" \"c\"");
+        assert_eq!(sanitize_fts5_advanced_query("\"unbalanced phrase"), "\"unbalanced\" \"phrase\"");
+        assert_eq!(sanitize_fts5_advanced_query("\"\""), "\"\"");
+        assert_eq!(sanitize_fts5_advanced_query("***"), "\"\"");
+
+        // Operators and hyphens that leave no term to negate are dropped
+        assert_eq!(sanitize_fts5_advanced_query("pool NOT"), "\"pool\"");
+        assert_eq!(sanitize_fts5_advanced_query("pool - -- NOT -"), "\"pool\"");
+        assert_eq!(sanitize_fts5_advanced_query("pool -content:secret"), "\"pool\" NOT \"content secret\"");
+        assert_eq!(sanitize_fts5_advanced_query("pool -{id}:secret"), "\"pool\" NOT \"id secret\"");
+
+        let many = "word ".repeat(100);
+        assert_eq!(
+            sanitize_fts5_advanced_query(&many).split_whitespace().count(),
+            MAX_ADVANCED_TERMS
+        );
+    }
+
     #[test]
     fn test_tokenize() {
         let searcher = KeywordSearcher::new();
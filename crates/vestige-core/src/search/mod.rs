@@ -15,15 +15,20 @@ mod temporal;
 mod vector;
 
 pub use vector::{
-    VectorIndex, VectorIndexConfig, VectorIndexStats, VectorSearchError, DEFAULT_CONNECTIVITY,
-    DEFAULT_DIMENSIONS,
+    IndexMetric, VectorIndex, VectorIndexConfig, VectorIndexStats, VectorSearchError,
+    DEFAULT_COMPACTION_THRESHOLD, DEFAULT_CONNECTIVITY, DEFAULT_DIMENSIONS,
 };
 
-pub use keyword::{sanitize_fts5_query, KeywordSearcher};
+pub use keyword::{
+    sanitize_fts5_advanced_query, sanitize_fts5_query, sanitize_fts5_query_with, KeywordSearcher,
+};
 
-pub use hybrid::{linear_combination, reciprocal_rank_fusion, HybridSearchConfig, HybridSearcher};
+pub use hybrid::{
+    linear_combination, reciprocal_rank_fusion, FusionMethod, HybridSearchConfig, HybridSearcher,
+    RankingProfile, DEFAULT_PIN_BONUS, DEFAULT_RRF_K,
+};
 
-pub use temporal::TemporalSearcher;
+pub use temporal::{parse_time_expression, TemporalSearcher};
 
 // GOD TIER 2026: Reranking for +15-20% precision
 pub use reranker::{
@@ -32,4 +37,7 @@ pub use reranker::{
 };
 
 // v2.0: HyDE-inspired query expansion for improved semantic search
-pub use hyde::{classify_intent, expand_query, centroid_embedding, QueryIntent};
+pub use hyde::{
+    centroid_embedding, classify_intent, expand_query, plan_expansion, QueryExpansion,
+    QueryIntent, MAX_EXPANSION_VARIANTS,
+};
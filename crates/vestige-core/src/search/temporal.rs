@@ -5,7 +5,7 @@
 //! - Boost recent results
 //! - Query historical states
 
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Duration, Months, NaiveDate, Utc};
 
 // ============================================================================
 // TEMPORAL SEARCH CONFIGURATION
@@ -65,8 +65,12 @@ impl TemporalSearcher {
     /// Returns a multiplier between 0.0 and 1.0
     /// Recent items get higher values
     pub fn recency_boost(&self, timestamp: DateTime<Utc>) -> f64 {
-        let now = Utc::now();
-        let age_days = (now - timestamp).num_days();
+        self.recency_boost_at(timestamp, Utc::now())
+    }
+
+    /// Recency boost as it would have been at `at` (for as-of queries)
+    pub fn recency_boost_at(&self, timestamp: DateTime<Utc>, at: DateTime<Utc>) -> f64 {
+        let age_days = (at - timestamp).num_days();
 
         if age_days < 0 {
             // Future timestamp, no boost
@@ -97,20 +101,22 @@ impl TemporalSearcher {
     ) -> f64 {
         let check_time = at_time.unwrap_or_else(Utc::now);
 
-        let is_valid = match (valid_from, valid_until) {
-            (None, None) => true, // Always valid
-            (Some(from), None) => check_time >= from,
-            (None, Some(until)) => check_time <= until,
-            (Some(from), Some(until)) => check_time >= from && check_time <= until,
-        };
-
-        if is_valid {
+        if Self::is_valid_at(valid_from, valid_until, check_time) {
             self.config.validity_boost
         } else {
             0.0 // Exclude invalid results
         }
     }
 
+    /// Whether a validity window contains `at` (open ends are unbounded)
+    pub fn is_valid_at(
+        valid_from: Option<DateTime<Utc>>,
+        valid_until: Option<DateTime<Utc>>,
+        at: DateTime<Utc>,
+    ) -> bool {
+        valid_from.is_none_or(|from| at >= from) && valid_until.is_none_or(|until| at <= until)
+    }
+
     /// Apply temporal scoring to search results
     ///
     /// Combines base score with recency and validity boosts
@@ -236,6 +242,52 @@ pub struct TemporalFilter {
     pub require_valid: bool,
 }
 
+// ============================================================================
+// TIME EXPRESSIONS
+// ============================================================================
+
+/// Parse a point in time relative to `now`
+///
+/// Accepts RFC 3339 datetimes, `YYYY-MM-DD` dates (midnight UTC), `now`,
+/// `today`, `yesterday`, `last week|month|year`, and `<n> <unit>s ago` where
+/// the unit is minute, hour, day, week, month, or year and `n` may be `a`/`an`.
+/// Months and years are calendar months.
+pub fn parse_time_expression(input: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let input = input.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return date.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc());
+    }
+
+    let lower = input.to_lowercase();
+    let words: Vec<&str> = lower.split_whitespace().collect();
+    let (count, unit) = match words.as_slice() {
+        ["now"] | ["today"] => return Some(now),
+        ["yesterday"] => return Some(now - Duration::days(1)),
+        ["last", unit] => (1, *unit),
+        [count, unit, "ago"] => {
+            let count = match *count {
+                "a" | "an" | "one" => 1,
+                n => n.parse::<u32>().ok()?,
+            };
+            (count, *unit)
+        }
+        _ => return None,
+    };
+
+    match unit.strip_suffix('s').unwrap_or(unit) {
+        "minute" => Some(now - Duration::minutes(count.into())),
+        "hour" => Some(now - Duration::hours(count.into())),
+        "day" => Some(now - Duration::days(count.into())),
+        "week" => Some(now - Duration::weeks(count.into())),
+        "month" => now.checked_sub_months(Months::new(count)),
+        "year" => now.checked_sub_months(Months::new(count.checked_mul(12)?)),
+        _ => None,
+    }
+}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -331,4 +383,38 @@ mod tests {
         assert!(all.start.is_none());
         assert!(all.end.is_none());
     }
+
+    #[test]
+    fn test_parse_time_expression() {
+        let now = DateTime::parse_from_rfc3339("2026-05-31T12:00:00Z").unwrap().with_timezone(&Utc);
+        let at = |s: &str| parse_time_expression(s, now).map(|dt| dt.to_rfc3339());
+
+        assert_eq!(at("2026-01-15").as_deref(), Some("2026-01-15T00:00:00+00:00"));
+        assert_eq!(at("2026-01-15T08:30:00Z").as_deref(), Some("2026-01-15T08:30:00+00:00"));
+        assert_eq!(at("3 months ago").as_deref(), Some("2026-02-28T12:00:00+00:00"));
+        assert_eq!(at("a year ago").as_deref(), Some("2025-05-31T12:00:00+00:00"));
+        assert_eq!(at("2 Weeks Ago").as_deref(), Some("2026-05-17T12:00:00+00:00"));
+        assert_eq!(at("last month").as_deref(), Some("2026-04-30T12:00:00+00:00"));
+        assert_eq!(at("yesterday").as_deref(), Some("2026-05-30T12:00:00+00:00"));
+        assert_eq!(at("1 hour ago").as_deref(), Some("2026-05-31T11:00:00+00:00"));
+
+        assert!(at("3 fortnights ago").is_none());
+        assert!(at("soon").is_none());
+        assert!(at("-2 days ago").is_none());
+    }
+
+    #[test]
+    fn test_is_valid_at_and_recency_at() {
+        let now = Utc::now();
+        let searcher = TemporalSearcher::new();
+        let past = now - Duration::days(60);
+
+        assert!(TemporalSearcher::is_valid_at(None, Some(now - Duration::days(30)), past));
+        assert!(!TemporalSearcher::is_valid_at(None, Some(now - Duration::days(30)), now));
+        assert!(!TemporalSearcher::is_valid_at(Some(now - Duration::days(30)), None, past));
+
+        // Relative to the as-of time, a memory from that day is fresh
+        assert!((searcher.recency_boost_at(past, past) - 1.0).abs() < 0.01);
+        assert!(searcher.recency_boost(past) < 0.5);
+    }
 }
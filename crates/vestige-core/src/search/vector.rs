@@ -5,7 +5,7 @@
 //!
 //! Features:
 //! - Sub-millisecond query times
-//! - Cosine similarity by default
+//! - Cosine similarity by default; dot product or euclidean on request
 //! - Incremental index updates
 //! - Persistence to disk
 
@@ -30,6 +30,10 @@ pub const DEFAULT_EXPANSION_ADD: usize = 128;
 /// HNSW expansion factor for search (higher = better recall, slower)
 pub const DEFAULT_EXPANSION_SEARCH: usize = 64;
 
+/// Fraction of removed slots above which a rebuild is worthwhile
+/// (USearch only tombstones removed vectors, so the graph degrades with churn)
+pub const DEFAULT_COMPACTION_THRESHOLD: f64 = 0.2;
+
 // ============================================================================
 // ERROR TYPES
 // ============================================================================
@@ -73,6 +77,84 @@ impl std::error::Error for VectorSearchError {}
 // CONFIGURATION
 // ============================================================================
 
+/// How `VectorIndex` compares vectors
+///
+/// Search scores are similarities whatever the metric, higher meaning
+/// closer, and for unit-length vectors all three give the same score:
+/// - `Cosine`: cosine similarity; safe for any model (default)
+/// - `Dot`: inner product; equal to cosine for unit-length vectors, without
+///   computing norms
+/// - `Euclidean`: 1 - d²/2 for squared L2 distance d²; also equal to cosine
+///   for unit-length vectors, and the metric for models trained on L2
+///
+/// Cosine indexes store int8-quantized vectors; `Dot` and `Euclidean` store
+/// f16, since quantization would rescale their distances, and so take twice
+/// the memory.
+///
+/// The shipped models, nomic-embed-text v1.5 and v2 MoE, are trained for
+/// cosine, and `EmbeddingService` L2-normalizes every vector (after
+/// Matryoshka truncation, and for pluggable backends too), so all three
+/// metrics rank their embeddings the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IndexMetric {
+    #[default]
+    Cosine,
+    Dot,
+    Euclidean,
+}
+
+impl IndexMetric {
+    /// The USearch metric this corresponds to
+    fn kind(self) -> MetricKind {
+        match self {
+            IndexMetric::Cosine => MetricKind::Cos,
+            IndexMetric::Dot => MetricKind::IP,
+            IndexMetric::Euclidean => MetricKind::L2sq,
+        }
+    }
+
+    /// How vectors are stored: int8 quantization rescales vectors, which
+    /// only cosine is insensitive to
+    fn quantization(self) -> ScalarKind {
+        match self {
+            IndexMetric::Cosine => ScalarKind::I8,
+            IndexMetric::Dot | IndexMetric::Euclidean => ScalarKind::F16,
+        }
+    }
+
+    /// Convert a USearch distance into a similarity score
+    pub fn similarity(self, distance: f32) -> f32 {
+        match self {
+            // USearch reports 1 - cos and 1 - dot
+            IndexMetric::Cosine | IndexMetric::Dot => 1.0 - distance,
+            IndexMetric::Euclidean => 1.0 - distance / 2.0,
+        }
+    }
+}
+
+impl std::fmt::Display for IndexMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IndexMetric::Cosine => write!(f, "cosine"),
+            IndexMetric::Dot => write!(f, "dot"),
+            IndexMetric::Euclidean => write!(f, "euclidean"),
+        }
+    }
+}
+
+impl std::str::FromStr for IndexMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "cosine" | "cos" => Ok(IndexMetric::Cosine),
+            "dot" | "ip" => Ok(IndexMetric::Dot),
+            "euclidean" | "l2" => Ok(IndexMetric::Euclidean),
+            _ => Err(format!("Unknown index metric: {}", s)),
+        }
+    }
+}
+
 /// Configuration for vector index
 #[derive(Debug, Clone)]
 pub struct VectorIndexConfig {
@@ -84,8 +166,8 @@ pub struct VectorIndexConfig {
     pub expansion_add: usize,
     /// Expansion factor for searching
     pub expansion_search: usize,
-    /// Distance metric
-    pub metric: MetricKind,
+    /// Similarity metric
+    pub metric: IndexMetric,
 }
 
 impl Default for VectorIndexConfig {
@@ -95,7 +177,7 @@ impl Default for VectorIndexConfig {
             connectivity: DEFAULT_CONNECTIVITY,
             expansion_add: DEFAULT_EXPANSION_ADD,
             expansion_search: DEFAULT_EXPANSION_SEARCH,
-            metric: MetricKind::Cos, // Cosine similarity
+            metric: IndexMetric::Cosine,
         }
     }
 }
@@ -111,6 +193,27 @@ pub struct VectorIndexStats {
     pub connectivity: usize,
     /// Estimated memory usage in bytes
     pub memory_bytes: usize,
+    /// Reserved capacity of the underlying index
+    pub capacity: usize,
+    /// Slots vacated by removals/updates since the last rebuild
+    pub removed_slots: usize,
+}
+
+impl VectorIndexStats {
+    /// Fraction of all slots ever written that are now removed (0.0 to 1.0)
+    pub fn removed_fraction(&self) -> f64 {
+        let total = self.total_vectors + self.removed_slots;
+        if total == 0 {
+            0.0
+        } else {
+            self.removed_slots as f64 / total as f64
+        }
+    }
+
+    /// Whether compaction is worthwhile at the given removed-fraction threshold
+    pub fn needs_compaction(&self, threshold: f64) -> bool {
+        self.removed_slots > 0 && self.removed_fraction() > threshold
+    }
 }
 
 // ============================================================================
@@ -124,6 +227,7 @@ pub struct VectorIndex {
     key_to_id: HashMap<String, u64>,
     id_to_key: HashMap<u64, String>,
     next_id: u64,
+    removed_slots: usize,
 }
 
 impl VectorIndex {
@@ -134,26 +238,45 @@ impl VectorIndex {
 
     /// Create a new vector index with custom configuration
     pub fn with_config(config: VectorIndexConfig) -> Result<Self, VectorSearchError> {
+        let index = Self::create_index(&config)?;
+
+        Ok(Self {
+            index,
+            config,
+            key_to_id: HashMap::new(),
+            id_to_key: HashMap::new(),
+            next_id: 0,
+            removed_slots: 0,
+        })
+    }
+
+    /// Build an empty USearch index from a configuration
+    fn create_index(config: &VectorIndexConfig) -> Result<Index, VectorSearchError> {
         let options = IndexOptions {
             dimensions: config.dimensions,
-            metric: config.metric,
-            quantization: ScalarKind::I8,
+            metric: config.metric.kind(),
+            quantization: config.metric.quantization(),
             connectivity: config.connectivity,
             expansion_add: config.expansion_add,
             expansion_search: config.expansion_search,
             multi: false,
         };
 
-        let index =
-            Index::new(&options).map_err(|e| VectorSearchError::IndexCreation(e.to_string()))?;
+        Index::new(&options).map_err(|e| VectorSearchError::IndexCreation(e.to_string()))
+    }
 
-        Ok(Self {
-            index,
-            config,
-            key_to_id: HashMap::new(),
-            id_to_key: HashMap::new(),
-            next_id: 0,
-        })
+    /// Get the configuration this index was built with
+    pub fn config(&self) -> &VectorIndexConfig {
+        &self.config
+    }
+
+    /// Change the HNSW search expansion factor (ef) without rebuilding
+    ///
+    /// Higher values trade query latency for recall.
+    pub fn set_ef_search(&mut self, ef: usize) {
+        let ef = ef.max(1);
+        self.index.change_expansion_search(ef);
+        self.config.expansion_search = ef;
     }
 
     /// Get the number of vectors in the index
@@ -194,6 +317,7 @@ impl VectorIndex {
             self.index
                 .remove(existing_id)
                 .map_err(|e| VectorSearchError::IndexAdd(e.to_string()))?;
+            self.removed_slots += 1;
             // Reserve capacity for the re-add
             self.reserve(self.index.size() + 1)?;
             self.index
@@ -233,12 +357,57 @@ impl VectorIndex {
             self.index
                 .remove(id)
                 .map_err(|e| VectorSearchError::IndexAdd(e.to_string()))?;
+            self.removed_slots += 1;
             Ok(true)
         } else {
             Ok(false)
         }
     }
 
+    /// Rebuild the HNSW graph from the currently-live vectors
+    ///
+    /// Removed slots are never reclaimed by USearch and degrade graph recall
+    /// over time. This re-inserts every live vector into a fresh index with
+    /// dense ids. Returns the number of vectors kept.
+    ///
+    /// Vectors are read back from the quantized index; use
+    /// `Storage::rebuild_vector_index` to rebuild from full-precision embeddings.
+    pub fn compact(&mut self) -> Result<usize, VectorSearchError> {
+        let fresh = Self::create_index(&self.config)?;
+        fresh
+            .reserve(std::cmp::max(self.key_to_id.len(), 16))
+            .map_err(|e| VectorSearchError::IndexCreation(format!("Failed to reserve capacity: {}", e)))?;
+
+        let mut key_to_id = HashMap::with_capacity(self.key_to_id.len());
+        let mut id_to_key = HashMap::with_capacity(self.key_to_id.len());
+        let mut buffer = vec![0.0f32; self.config.dimensions];
+        let mut next_id = 0u64;
+
+        for (old_id, key) in &self.id_to_key {
+            let found = self
+                .index
+                .get(*old_id, &mut buffer)
+                .map_err(|e| VectorSearchError::IndexSearch(e.to_string()))?;
+            if found == 0 {
+                continue;
+            }
+            fresh
+                .add(next_id, &buffer)
+                .map_err(|e| VectorSearchError::IndexAdd(e.to_string()))?;
+            key_to_id.insert(key.clone(), next_id);
+            id_to_key.insert(next_id, key.clone());
+            next_id += 1;
+        }
+
+        self.index = fresh;
+        self.key_to_id = key_to_id;
+        self.id_to_key = id_to_key;
+        self.next_id = next_id;
+        self.removed_slots = 0;
+
+        Ok(self.key_to_id.len())
+    }
+
     /// Check if a key exists in the index
     pub fn contains(&self, key: &str) -> bool {
         self.key_to_id.contains_key(key)
@@ -269,15 +438,55 @@ impl VectorIndex {
         let mut search_results = Vec::with_capacity(results.keys.len());
         for (key, distance) in results.keys.iter().zip(results.distances.iter()) {
             if let Some(string_key) = self.id_to_key.get(key) {
-                // Convert distance to similarity (1 - distance for cosine)
-                let score = 1.0 - distance;
-                search_results.push((string_key.clone(), score));
+                search_results.push((string_key.clone(), self.config.metric.similarity(*distance)));
             }
         }
 
         Ok(search_results)
     }
 
+    /// Search for similar vectors among the keys a filter accepts
+    ///
+    /// The filter is applied while traversing the graph, so up to `limit`
+    /// accepted keys are returned even when most of the index is rejected.
+    pub fn search_filtered<F>(
+        &self,
+        query: &[f32],
+        limit: usize,
+        filter: F,
+    ) -> Result<Vec<(String, f32)>, VectorSearchError>
+    where
+        F: Fn(&str) -> bool,
+    {
+        if query.len() != self.config.dimensions {
+            return Err(VectorSearchError::InvalidDimensions(
+                self.config.dimensions,
+                query.len(),
+            ));
+        }
+
+        if self.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let results = self
+            .index
+            .filtered_search(query, limit, |key| {
+                self.id_to_key.get(&key).is_some_and(|id| filter(id))
+            })
+            .map_err(|e| VectorSearchError::IndexSearch(e.to_string()))?;
+
+        Ok(results
+            .keys
+            .iter()
+            .zip(results.distances.iter())
+            .filter_map(|(key, distance)| {
+                let id = self.id_to_key.get(key)?;
+                Some((id.clone(), self.config.metric.similarity(*distance)))
+            })
+            .collect())
+    }
+
     /// Search with minimum similarity threshold
     pub fn search_with_threshold(
         &self,
@@ -322,18 +531,7 @@ impl VectorIndex {
             .to_str()
             .ok_or_else(|| VectorSearchError::IndexPersistence("Invalid path".to_string()))?;
 
-        let options = IndexOptions {
-            dimensions: config.dimensions,
-            metric: config.metric,
-            quantization: ScalarKind::I8,
-            connectivity: config.connectivity,
-            expansion_add: config.expansion_add,
-            expansion_search: config.expansion_search,
-            multi: false,
-        };
-
-        let index =
-            Index::new(&options).map_err(|e| VectorSearchError::IndexCreation(e.to_string()))?;
+        let index = Self::create_index(&config)?;
 
         index
             .load(path_str)
@@ -363,6 +561,7 @@ impl VectorIndex {
             key_to_id,
             id_to_key,
             next_id,
+            removed_slots: 0,
         })
     }
 
@@ -373,6 +572,8 @@ impl VectorIndex {
             dimensions: self.config.dimensions,
             connectivity: self.config.connectivity,
             memory_bytes: self.index.serialized_length(),
+            capacity: self.index.capacity(),
+            removed_slots: self.removed_slots,
         }
     }
 }
@@ -459,6 +660,37 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_metrics_agree_on_unit_vectors() {
+        let unit = |v: Vec<f32>| {
+            let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+            v.into_iter().map(|x| x / norm).collect::<Vec<f32>>()
+        };
+        let a = unit(create_test_vector(1.0));
+        // Alternating signs make `c` nearly orthogonal to `a`, and `b` sits between
+        let c: Vec<f32> = a.iter().enumerate().map(|(i, x)| if i % 2 == 0 { *x } else { -x }).collect();
+        let b = unit(a.iter().zip(&c).map(|(x, y)| x + 0.3 * y).collect());
+        let cosine: f32 = a.iter().zip(&c).map(|(x, y)| x * y).sum();
+
+        for metric in [IndexMetric::Cosine, IndexMetric::Dot, IndexMetric::Euclidean] {
+            let mut index = VectorIndex::with_config(VectorIndexConfig { metric, ..Default::default() }).unwrap();
+            index.add("a", &a).unwrap();
+            index.add("b", &b).unwrap();
+            index.add("c", &c).unwrap();
+
+            let results = index.search(&a, 3).unwrap();
+            let keys: Vec<&str> = results.iter().map(|(k, _)| k.as_str()).collect();
+            assert_eq!(keys, ["a", "b", "c"], "{}", metric);
+            assert!((results[0].1 - 1.0).abs() < 0.02, "{}: {}", metric, results[0].1);
+            assert!((results[2].1 - cosine).abs() < 0.02, "{}: {} vs {}", metric, results[2].1, cosine);
+
+            let close = index.search_with_threshold(&a, 3, 0.9).unwrap();
+            assert!(close.iter().all(|(k, _)| k != "c"), "{}", metric);
+        }
+        assert_eq!("dot".parse::<IndexMetric>(), Ok(IndexMetric::Dot));
+        assert!("manhattan".parse::<IndexMetric>().is_err());
+    }
+
     #[test]
     fn test_search_with_threshold() {
         let mut index = VectorIndex::new().unwrap();
@@ -475,6 +707,23 @@ mod tests {
         assert!(results.iter().any(|(k, _)| k == "similar"));
     }
 
+    #[test]
+    fn test_search_filtered_skips_rejected_keys() {
+        let mut index = VectorIndex::new().unwrap();
+
+        let v1 = create_test_vector(1.0);
+        index.add("acme-1", &v1).unwrap();
+        index.add("other-1", &create_test_vector(1.5)).unwrap();
+        index.add("acme-2", &create_test_vector(100.0)).unwrap();
+
+        let results = index
+            .search_filtered(&v1, 2, |id| id.starts_with("acme-"))
+            .unwrap();
+
+        let ids: Vec<&str> = results.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["acme-1", "acme-2"]);
+    }
+
     #[test]
     fn test_stats() {
         let mut index = VectorIndex::new().unwrap();
@@ -485,5 +734,108 @@ mod tests {
         let stats = index.stats();
         assert_eq!(stats.total_vectors, 1);
         assert_eq!(stats.dimensions, DEFAULT_DIMENSIONS);
+        assert_eq!(stats.removed_slots, 0);
+    }
+
+    #[test]
+    fn test_set_ef_search() {
+        let mut index = VectorIndex::new().unwrap();
+        index.set_ef_search(256);
+        assert_eq!(index.config().expansion_search, 256);
+        index.set_ef_search(0);
+        assert_eq!(index.config().expansion_search, 1);
+    }
+
+    #[test]
+    fn test_recall_vs_ef_search() {
+        // Deterministic pseudo-random unit vectors (xorshift)
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 2000) as f32 / 1000.0 - 1.0
+        };
+        let vectors: Vec<Vec<f32>> = (0..1000)
+            .map(|_| (0..DEFAULT_DIMENSIONS).map(|_| next()).collect())
+            .collect();
+        let queries: Vec<Vec<f32>> = (0..20)
+            .map(|_| (0..DEFAULT_DIMENSIONS).map(|_| next()).collect())
+            .collect();
+
+        let mut index = VectorIndex::with_config(VectorIndexConfig {
+            connectivity: 8,
+            ..Default::default()
+        })
+        .unwrap();
+        index.reserve(vectors.len()).unwrap();
+        for (i, v) in vectors.iter().enumerate() {
+            index.add(&format!("v{}", i), v).unwrap();
+        }
+
+        // Exact top-10 by brute force
+        let k = 10;
+        let truth: Vec<Vec<String>> = queries
+            .iter()
+            .map(|q| {
+                let mut scored: Vec<(usize, f32)> = vectors
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| {
+                        let dot: f32 = q.iter().zip(v).map(|(a, b)| a * b).sum();
+                        let norm = |x: &[f32]| x.iter().map(|a| a * a).sum::<f32>().sqrt();
+                        (i, dot / (norm(q) * norm(v)))
+                    })
+                    .collect();
+                scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+                scored.iter().take(k).map(|(i, _)| format!("v{}", i)).collect()
+            })
+            .collect();
+
+        let mut recalls = Vec::new();
+        for ef in [16, 64, 256] {
+            index.set_ef_search(ef);
+            let mut hits = 0;
+            for (q, expected) in queries.iter().zip(&truth) {
+                let results = index.search(q, k).unwrap();
+                hits += results.iter().filter(|(key, _)| expected.contains(key)).count();
+            }
+            recalls.push(hits as f64 / (queries.len() * k) as f64);
+        }
+
+        // Each wider search finds more of the exact top-k, and the widest is near-exact
+        assert!(recalls[0] < recalls[1] && recalls[1] < recalls[2], "recall by ef: {:?}", recalls);
+        assert!(recalls[2] >= 0.8, "recall at ef=256 too low: {:?}", recalls);
+    }
+
+    #[test]
+    fn test_compact_reclaims_removed_slots() {
+        let mut index = VectorIndex::new().unwrap();
+        for i in 0..10 {
+            index.add(&format!("node-{}", i), &create_test_vector(i as f32 * 10.0)).unwrap();
+        }
+        for i in 0..4 {
+            index.remove(&format!("node-{}", i)).unwrap();
+        }
+        // Updating in place also vacates a slot
+        index.add("node-9", &create_test_vector(95.0)).unwrap();
+
+        let stats = index.stats();
+        assert_eq!(stats.removed_slots, 5);
+        assert!(stats.needs_compaction(DEFAULT_COMPACTION_THRESHOLD));
+
+        let kept = index.compact().unwrap();
+        assert_eq!(kept, 6);
+        assert_eq!(index.len(), 6);
+        assert_eq!(index.stats().removed_slots, 0);
+        assert!(!index.stats().needs_compaction(DEFAULT_COMPACTION_THRESHOLD));
+        assert!(!index.contains("node-0"));
+
+        let results = index.search(&create_test_vector(50.0), 1).unwrap();
+        assert_eq!(results[0].0, "node-5");
+
+        // New ids continue after the compacted range
+        index.add("node-new", &create_test_vector(500.0)).unwrap();
+        assert_eq!(index.len(), 7);
     }
 }
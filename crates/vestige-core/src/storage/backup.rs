@@ -0,0 +1,202 @@
+//! Backup files: naming, listing and rotation policy
+
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Backup file names are `vestige-YYYYMMDD-HHMMSS.db` (UTC)
+const BACKUP_PREFIX: &str = "vestige-";
+const BACKUP_SUFFIX: &str = ".db";
+const BACKUP_TIME_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// Automatic backup policy
+///
+/// Rotation keeps the `keep_recent` newest backups plus the newest backup
+/// of each of the last `keep_weeks` weeks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupConfig {
+    /// Hours between automatic backups; 0 disables them
+    pub interval_hours: u64,
+    /// Most recent backups always kept
+    pub keep_recent: usize,
+    /// Weeks for which one weekly backup is kept
+    pub keep_weeks: usize,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            interval_hours: 24,
+            keep_recent: 7,
+            keep_weeks: 4,
+        }
+    }
+}
+
+impl BackupConfig {
+    /// Defaults overridden by VESTIGE_BACKUP_INTERVAL_HOURS,
+    /// VESTIGE_BACKUP_KEEP and VESTIGE_BACKUP_KEEP_WEEKS
+    pub fn from_env() -> Self {
+        let env = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        let defaults = Self::default();
+        Self {
+            interval_hours: env("VESTIGE_BACKUP_INTERVAL_HOURS").unwrap_or(defaults.interval_hours),
+            keep_recent: env("VESTIGE_BACKUP_KEEP")
+                .map(|v| v as usize)
+                .unwrap_or(defaults.keep_recent),
+            keep_weeks: env("VESTIGE_BACKUP_KEEP_WEEKS")
+                .map(|v| v as usize)
+                .unwrap_or(defaults.keep_weeks),
+        }
+    }
+
+    /// Whether automatic backups are enabled
+    pub fn enabled(&self) -> bool {
+        self.interval_hours > 0
+    }
+
+    /// Time between automatic backups
+    pub fn interval(&self) -> Duration {
+        Duration::hours(self.interval_hours as i64)
+    }
+
+    /// Time left until the backup after `last_backup` is due; zero when it
+    /// already is, or when there is no previous backup
+    pub fn until_next(&self, last_backup: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Duration {
+        last_backup
+            .map(|last| last + self.interval() - now)
+            .filter(|wait| *wait > Duration::zero())
+            .unwrap_or_else(Duration::zero)
+    }
+}
+
+/// A backup file in the backups directory
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub path: PathBuf,
+    /// Parsed from the file name
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+/// Standard backups directory, next to the data directory
+pub fn default_backup_dir() -> Option<PathBuf> {
+    let proj_dirs = directories::ProjectDirs::from("com", "vestige", "core")?;
+    Some(proj_dirs.data_dir().parent()?.join("backups"))
+}
+
+/// File name for a backup taken at `at`
+pub fn backup_file_name(at: DateTime<Utc>) -> String {
+    format!("{}{}{}", BACKUP_PREFIX, at.format(BACKUP_TIME_FORMAT), BACKUP_SUFFIX)
+}
+
+/// Parse the timestamp out of a `vestige-YYYYMMDD-HHMMSS.db` file name
+pub fn parse_backup_file_name(name: &str) -> Option<DateTime<Utc>> {
+    let ts = name.strip_prefix(BACKUP_PREFIX)?.strip_suffix(BACKUP_SUFFIX)?;
+    chrono::NaiveDateTime::parse_from_str(ts, BACKUP_TIME_FORMAT)
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+/// Backups in `dir`, newest first; a missing directory has none
+pub fn list_backups_in(dir: &Path) -> std::io::Result<Vec<BackupInfo>> {
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut backups: Vec<BackupInfo> = std::fs::read_dir(dir)?
+        .flatten()
+        .filter_map(|entry| {
+            let created_at = parse_backup_file_name(&entry.file_name().to_string_lossy())?;
+            Some(BackupInfo {
+                path: entry.path(),
+                created_at,
+                size_bytes: entry.metadata().map(|m| m.len()).unwrap_or(0),
+            })
+        })
+        .collect();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+    Ok(backups)
+}
+
+/// Backups the rotation policy would delete
+///
+/// `backups` must be newest first, as returned by `list_backups_in`.
+pub fn backups_to_prune(backups: &[BackupInfo], config: &BackupConfig, now: DateTime<Utc>) -> Vec<PathBuf> {
+    let mut keep: HashSet<usize> = (0..backups.len().min(config.keep_recent)).collect();
+    let mut weeks_seen = HashSet::new();
+    for (i, backup) in backups.iter().enumerate() {
+        let week = (now - backup.created_at).num_days().max(0) / 7;
+        if (week as usize) < config.keep_weeks && weeks_seen.insert(week) {
+            keep.insert(i);
+        }
+    }
+    backups
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !keep.contains(i))
+        .map(|(_, b)| b.path.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backup_at(now: DateTime<Utc>, hours_ago: i64) -> BackupInfo {
+        let created_at = now - Duration::hours(hours_ago);
+        BackupInfo {
+            path: PathBuf::from(backup_file_name(created_at)),
+            created_at,
+            size_bytes: 0,
+        }
+    }
+
+    #[test]
+    fn test_backup_file_name_round_trip() {
+        let at = DateTime::parse_from_rfc3339("2026-03-14T09:26:53Z").unwrap().with_timezone(&Utc);
+        let name = backup_file_name(at);
+        assert_eq!(name, "vestige-20260314-092653.db");
+        assert_eq!(parse_backup_file_name(&name), Some(at));
+        assert_eq!(parse_backup_file_name("vestige-latest.db"), None);
+        assert_eq!(parse_backup_file_name("other-20260314-092653.db"), None);
+    }
+
+    #[test]
+    fn test_rotation_keeps_recent_and_weekly() {
+        let now = Utc::now();
+        // Daily backups for five weeks, newest first
+        let backups: Vec<BackupInfo> = (0..35).map(|d| backup_at(now, d * 24 + 1)).collect();
+        let config = BackupConfig {
+            interval_hours: 24,
+            keep_recent: 3,
+            keep_weeks: 4,
+        };
+
+        let pruned = backups_to_prune(&backups, &config, now);
+        let kept: Vec<&BackupInfo> = backups.iter().filter(|b| !pruned.contains(&b.path)).collect();
+        let kept_days: Vec<i64> = kept.iter().map(|b| (now - b.created_at).num_days()).collect();
+        // Three newest, plus the newest of weeks 1, 2 and 3 (week 0 is already covered)
+        assert_eq!(kept_days, vec![0, 1, 2, 7, 14, 21]);
+        assert_eq!(pruned.len(), 29);
+
+        let nothing_kept = BackupConfig {
+            keep_recent: 0,
+            keep_weeks: 0,
+            ..config
+        };
+        assert_eq!(backups_to_prune(&backups, &nothing_kept, now).len(), 35);
+    }
+
+    #[test]
+    fn test_until_next_counts_from_last_backup() {
+        let now = Utc::now();
+        let config = BackupConfig {
+            interval_hours: 24,
+            ..Default::default()
+        };
+        assert_eq!(config.until_next(None, now), Duration::zero());
+        assert_eq!(config.until_next(Some(now - Duration::hours(20)), now), Duration::hours(4));
+        assert_eq!(config.until_next(Some(now - Duration::hours(30)), now), Duration::zero());
+    }
+}
@@ -0,0 +1,186 @@
+//! Line-based unified diffs for memory content revisions
+
+/// Unchanged lines shown around each hunk
+pub const DIFF_CONTEXT_LINES: usize = 3;
+
+/// Beyond this many line pairs the LCS table is skipped and the changed
+/// region is shown as a wholesale replacement
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op<'a> {
+    Equal(&'a str),
+    Delete(&'a str),
+    Insert(&'a str),
+}
+
+impl Op<'_> {
+    fn in_old(&self) -> bool {
+        !matches!(self, Op::Insert(_))
+    }
+
+    fn in_new(&self) -> bool {
+        !matches!(self, Op::Delete(_))
+    }
+}
+
+/// Edit script between two line sequences (longest common subsequence)
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Op<'a>> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+
+    let mut ops: Vec<Op<'a>> = old[..prefix].iter().map(|l| Op::Equal(l)).collect();
+    let (mut i, mut j) = (0, 0);
+    if a.len().saturating_mul(b.len()) <= MAX_LCS_CELLS {
+        // lcs[i * w + j] = LCS length of a[i..] and b[j..]
+        let w = b.len() + 1;
+        let mut lcs = vec![0usize; (a.len() + 1) * w];
+        for i in (0..a.len()).rev() {
+            for j in (0..b.len()).rev() {
+                lcs[i * w + j] = if a[i] == b[j] {
+                    lcs[(i + 1) * w + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * w + j].max(lcs[i * w + j + 1])
+                };
+            }
+        }
+        while i < a.len() && j < b.len() {
+            if a[i] == b[j] {
+                ops.push(Op::Equal(a[i]));
+                i += 1;
+                j += 1;
+            } else if lcs[(i + 1) * w + j] >= lcs[i * w + j + 1] {
+                ops.push(Op::Delete(a[i]));
+                i += 1;
+            } else {
+                ops.push(Op::Insert(b[j]));
+                j += 1;
+            }
+        }
+    }
+    ops.extend(a[i..].iter().map(|l| Op::Delete(l)));
+    ops.extend(b[j..].iter().map(|l| Op::Insert(l)));
+    ops.extend(old[old.len() - suffix..].iter().map(|l| Op::Equal(l)));
+    ops
+}
+
+/// Unified diff from `old` to `new` with `DIFF_CONTEXT_LINES` of context
+///
+/// File headers are omitted; each hunk starts with `@@ -l,n +l,n @@`.
+/// Identical texts produce an empty string.
+pub fn unified_diff(old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    render_hunks(&diff_lines(&old_lines, &new_lines))
+}
+
+/// Group changed positions into hunks (merging changes separated by no more
+/// than twice the context) and render them
+fn render_hunks(ops: &[Op<'_>]) -> String {
+    let changed: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| !matches!(op, Op::Equal(_)))
+        .map(|(i, _)| i)
+        .collect();
+
+    let mut out = String::new();
+    let mut k = 0;
+    while k < changed.len() {
+        let first = changed[k];
+        let mut last = first;
+        while k + 1 < changed.len() && changed[k + 1] - last - 1 <= 2 * DIFF_CONTEXT_LINES {
+            k += 1;
+            last = changed[k];
+        }
+        k += 1;
+
+        let start = first.saturating_sub(DIFF_CONTEXT_LINES);
+        let end = (last + DIFF_CONTEXT_LINES + 1).min(ops.len());
+        let old_before = ops[..start].iter().filter(|op| op.in_old()).count();
+        let new_before = ops[..start].iter().filter(|op| op.in_new()).count();
+        let old_len = ops[start..end].iter().filter(|op| op.in_old()).count();
+        let new_len = ops[start..end].iter().filter(|op| op.in_new()).count();
+
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_before + usize::from(old_len > 0),
+            old_len,
+            new_before + usize::from(new_len > 0),
+            new_len
+        ));
+        for op in &ops[start..end] {
+            let (marker, line) = match op {
+                Op::Equal(l) => (' ', l),
+                Op::Delete(l) => ('-', l),
+                Op::Insert(l) => ('+', l),
+            };
+            out.push(marker);
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Stable 64-bit FNV-1a hash of content, as 16 hex digits
+///
+/// Persisted alongside revisions, so it must not change between builds
+/// (unlike `DefaultHasher`).
+pub fn content_hash(content: &str) -> String {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+    let hash = content
+        .bytes()
+        .fold(FNV_OFFSET, |h, b| (h ^ u64::from(b)).wrapping_mul(FNV_PRIME));
+    format!("{:016x}", hash)
+}
+
+/// Content with surrounding whitespace trimmed and inner runs collapsed to one space
+pub fn normalize_whitespace(content: &str) -> String {
+    content.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// `content_hash` of `normalize_whitespace(content)`, so retried ingests hash the same
+pub fn normalized_content_hash(content: &str) -> String {
+    content_hash(&normalize_whitespace(content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff_hunks() {
+        assert_eq!(unified_diff("same\ntext", "same\ntext"), "");
+
+        let diff = unified_diff("a\nb\nc", "a\nB\nc\nd");
+        assert_eq!(diff, "@@ -1,3 +1,4 @@\n a\n-b\n+B\n c\n+d\n");
+
+        // Distant changes get separate hunks with three lines of context
+        let old: Vec<String> = (1..=20).map(|i| format!("line {i}")).collect();
+        let mut new = old.clone();
+        new[1] = "changed 2".to_string();
+        new[17] = "changed 18".to_string();
+        let diff = unified_diff(&old.join("\n"), &new.join("\n"));
+        let headers: Vec<&str> = diff.lines().filter(|l| l.starts_with("@@")).collect();
+        assert_eq!(headers, vec!["@@ -1,5 +1,5 @@", "@@ -15,6 +15,6 @@"]);
+
+        assert_eq!(unified_diff("", "new"), "@@ -0,0 +1,1 @@\n+new\n");
+    }
+
+    #[test]
+    fn test_content_hash_is_stable() {
+        assert_eq!(content_hash(""), "cbf29ce484222325");
+        assert_eq!(content_hash("a"), "af63dc4c8601ec8c");
+        assert_ne!(content_hash("memory"), content_hash("Memory"));
+        assert_eq!(normalized_content_hash("  a \n b\t"), normalized_content_hash("a b"));
+    }
+}
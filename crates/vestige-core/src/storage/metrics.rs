@@ -0,0 +1,150 @@
+//! Storage Metrics
+//!
+//! Lightweight counters kept by `Storage` for diagnosing production
+//! behaviour, read with `Storage::metrics_snapshot`. Counters are relaxed
+//! atomics bumped on the hot path; the spans around ingest, search, decay
+//! and consolidation carry the per-call detail (durations, node counts,
+//! cache hits) for whichever tracing subscriber is installed.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, TryLockError};
+use std::time::{Duration, Instant};
+
+use tracing::Span;
+
+use super::sqlite::{Result, StorageError};
+
+/// Lock waits at least this long count as slow
+pub const SLOW_LOCK_WAIT: Duration = Duration::from_millis(100);
+
+/// Point-in-time copy of a `Storage`'s counters, all since it was opened
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsSnapshot {
+    /// Memories written by ingest, smart ingest and imports
+    pub nodes_ingested: u64,
+    /// Keyword, semantic and hybrid searches answered
+    pub searches_served: u64,
+    /// Memory embeddings generated and indexed
+    pub embeddings_generated: u64,
+    /// Query embeddings served from the query cache
+    pub query_cache_hits: u64,
+    /// Database connection locks that took `SLOW_LOCK_WAIT` or longer to acquire
+    pub slow_lock_waits: u64,
+    /// Consolidation cycles and pipelines run (skipped cycles excluded)
+    pub consolidation_runs: u64,
+}
+
+/// Live counters behind `MetricsSnapshot`
+#[derive(Debug, Default)]
+pub(crate) struct StorageMetrics {
+    nodes_ingested: AtomicU64,
+    searches_served: AtomicU64,
+    embeddings_generated: AtomicU64,
+    query_cache_hits: AtomicU64,
+    slow_lock_waits: AtomicU64,
+    consolidation_runs: AtomicU64,
+}
+
+#[cfg_attr(not(feature = "embeddings"), allow(dead_code))]
+impl StorageMetrics {
+    pub(crate) fn node_ingested(&self) {
+        self.nodes_ingested.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn search_served(&self) {
+        self.searches_served.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn embedding_generated(&self) {
+        self.embeddings_generated.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn query_cache_hit(&self) {
+        self.query_cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn consolidation_run(&self) {
+        self.consolidation_runs.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Lock `mutex`, counting the wait if it was slow
+    ///
+    /// The uncontended case is a single `try_lock`, so the clock is only
+    /// read when the lock is actually held elsewhere.
+    pub(crate) fn lock<'a, T>(&self, mutex: &'a Mutex<T>, name: &str) -> Result<MutexGuard<'a, T>> {
+        match mutex.try_lock() {
+            Ok(guard) => return Ok(guard),
+            Err(TryLockError::Poisoned(_)) => return Err(StorageError::LockPoisoned(name.into())),
+            Err(TryLockError::WouldBlock) => {}
+        }
+        let started = Instant::now();
+        let guard = mutex.lock().map_err(|_| StorageError::LockPoisoned(name.into()))?;
+        let waited = started.elapsed();
+        if waited >= SLOW_LOCK_WAIT {
+            self.slow_lock_waits.fetch_add(1, Ordering::Relaxed);
+            tracing::debug!(lock = name, waited_ms = waited.as_millis() as u64, "Slow lock wait");
+        }
+        Ok(guard)
+    }
+
+    pub(crate) fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            nodes_ingested: self.nodes_ingested.load(Ordering::Relaxed),
+            searches_served: self.searches_served.load(Ordering::Relaxed),
+            embeddings_generated: self.embeddings_generated.load(Ordering::Relaxed),
+            query_cache_hits: self.query_cache_hits.load(Ordering::Relaxed),
+            slow_lock_waits: self.slow_lock_waits.load(Ordering::Relaxed),
+            consolidation_runs: self.consolidation_runs.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Records `elapsed_ms` on the current span when dropped, whichever way
+/// the instrumented function returns
+pub(crate) struct SpanTimer {
+    span: Span,
+    started: Instant,
+}
+
+impl SpanTimer {
+    pub(crate) fn start() -> Self {
+        Self {
+            span: Span::current(),
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Drop for SpanTimer {
+    fn drop(&mut self) {
+        if !self.span.is_disabled() {
+            self.span.record("elapsed_ms", self.started.elapsed().as_millis() as u64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_counts_only_slow_waits() {
+        let metrics = StorageMetrics::default();
+        let mutex = Mutex::new(0);
+        drop(metrics.lock(&mutex, "Test").unwrap());
+        assert_eq!(metrics.snapshot().slow_lock_waits, 0);
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                let _held = mutex.lock().unwrap();
+                tx.send(()).unwrap();
+                std::thread::sleep(SLOW_LOCK_WAIT + Duration::from_millis(20));
+            });
+            rx.recv().unwrap();
+            drop(metrics.lock(&mutex, "Test").unwrap());
+        });
+        assert_eq!(metrics.snapshot().slow_lock_waits, 1);
+    }
+}
@@ -49,6 +49,136 @@ pub const MIGRATIONS: &[Migration] = &[
         description: "v2.0.0 Cognitive Leap: emotional memory, flashbulb encoding, temporal hierarchy",
         up: MIGRATION_V9_UP,
     },
+    Migration {
+        version: 10,
+        description: "Cross-project learning: persisted universal patterns",
+        up: MIGRATION_V10_UP,
+    },
+    Migration {
+        version: 11,
+        description: "Memory chains: persisted reasoning chains and their steps",
+        up: MIGRATION_V11_UP,
+    },
+    Migration {
+        version: 12,
+        description: "Speculative retrieval: persisted tool usage history",
+        up: MIGRATION_V12_UP,
+    },
+    Migration {
+        version: 13,
+        description: "Adaptive embedding: record the strategy each embedding was made with",
+        up: MIGRATION_V13_UP,
+    },
+    Migration {
+        version: 14,
+        description: "Weighted access log entries",
+        up: MIGRATION_V14_UP,
+    },
+    Migration {
+        version: 15,
+        description: "Composite per-node importance refreshed during consolidation",
+        up: MIGRATION_V15_UP,
+    },
+    Migration {
+        version: 16,
+        description: "Synaptic tagging: persisted tags and pending importance events",
+        up: MIGRATION_V16_UP,
+    },
+    Migration {
+        version: 17,
+        description: "Node content revisions for changelog diffs",
+        up: MIGRATION_V17_UP,
+    },
+    Migration {
+        version: 18,
+        description: "Support counts for universal patterns",
+        up: MIGRATION_V18_UP,
+    },
+    Migration {
+        version: 19,
+        description: "Namespaces for isolating memories within one database",
+        up: MIGRATION_V19_UP,
+    },
+    Migration {
+        version: 20,
+        description: "Per-source trust multipliers for search ranking",
+        up: MIGRATION_V20_UP,
+    },
+    Migration {
+        version: 21,
+        description: "Embeddings of revised node content",
+        up: MIGRATION_V21_UP,
+    },
+    Migration {
+        version: 22,
+        description: "Usage events and evolving importance scores",
+        up: MIGRATION_V22_UP,
+    },
+    Migration {
+        version: 23,
+        description: "Meta table recording the schema version",
+        up: MIGRATION_V23_UP,
+    },
+    Migration {
+        version: 24,
+        description: "Content hash for exact-duplicate detection on ingest",
+        up: MIGRATION_V24_UP,
+    },
+    Migration {
+        version: 25,
+        description: "Index-ready Matryoshka-truncated embedding vectors",
+        up: MIGRATION_V25_UP,
+    },
+    Migration {
+        version: 26,
+        description: "Free-text details (feedback reasons) on access log entries",
+        up: MIGRATION_V26_UP,
+    },
+    Migration {
+        version: 27,
+        description: "Task outcomes reported against memories",
+        up: MIGRATION_V27_UP,
+    },
+    Migration {
+        version: 28,
+        description: "Pinned memories with optional expiry",
+        up: MIGRATION_V28_UP,
+    },
+    Migration {
+        version: 29,
+        description: "Per-repository cursors for incremental git analysis",
+        up: MIGRATION_V29_UP,
+    },
+    Migration {
+        version: 30,
+        description: "Per-step timings and errors in consolidation history",
+        up: MIGRATION_V30_UP,
+    },
+    Migration {
+        version: 31,
+        description: "Skipped consolidation runs in history",
+        up: MIGRATION_V31_UP,
+    },
+    Migration {
+        version: 32,
+        description: "Detected code language per embedding",
+        up: MIGRATION_V32_UP,
+    },
+    Migration {
+        version: 33,
+        description: "Last decay time per memory",
+        up: MIGRATION_V33_UP,
+    },
+    Migration {
+        version: 34,
+        description: "Consolidation runs stopped early",
+        up: MIGRATION_V34_UP,
+    },
+    Migration {
+        version: 35,
+        description: "Incremental ACT-R activation state",
+        up: MIGRATION_V35_UP,
+    },
 ];
 
 /// A database migration
@@ -605,25 +735,448 @@ ALTER TABLE dream_history ADD COLUMN creative_connections_found INTEGER DEFAULT
 UPDATE schema_version SET version = 9, applied_at = datetime('now');
 "#;
 
+/// V10: Cross-project learning — universal patterns detected during consolidation
+///
+/// Patterns are merged incrementally: each consolidation unions the contributing
+/// projects and memories into the existing row instead of replacing it.
+const MIGRATION_V10_UP: &str = r#"
+CREATE TABLE IF NOT EXISTS universal_patterns (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    category TEXT NOT NULL,
+    pattern_json TEXT NOT NULL,
+    projects_seen_in TEXT NOT NULL DEFAULT '[]',
+    supporting_memories TEXT NOT NULL DEFAULT '[]',
+    success_rate REAL NOT NULL DEFAULT 0.5,
+    applicability TEXT NOT NULL DEFAULT '',
+    confidence REAL NOT NULL DEFAULT 0.5,
+    application_count INTEGER NOT NULL DEFAULT 0,
+    first_seen TEXT NOT NULL,
+    last_seen TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_universal_patterns_category ON universal_patterns(category);
+CREATE INDEX IF NOT EXISTS idx_universal_patterns_confidence ON universal_patterns(confidence);
+
+UPDATE schema_version SET version = 10, applied_at = datetime('now');
+"#;
+
+/// V11: Memory chains — reasoning paths between memories, one row per hop
+const MIGRATION_V11_UP: &str = r#"
+CREATE TABLE IF NOT EXISTS reasoning_chains (
+    id TEXT PRIMARY KEY,
+    from_id TEXT NOT NULL,
+    to_id TEXT NOT NULL,
+    confidence REAL NOT NULL DEFAULT 0.0,
+    total_hops INTEGER NOT NULL DEFAULT 0,
+    explanation TEXT NOT NULL DEFAULT '',
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS reasoning_chain_steps (
+    chain_id TEXT NOT NULL REFERENCES reasoning_chains(id) ON DELETE CASCADE,
+    step_index INTEGER NOT NULL,
+    memory_id TEXT NOT NULL,
+    memory_preview TEXT NOT NULL DEFAULT '',
+    connection_type TEXT NOT NULL,  -- JSON-encoded ConnectionType to the next step
+    connection_strength REAL NOT NULL DEFAULT 0.0,
+    reasoning TEXT NOT NULL DEFAULT '',
+    PRIMARY KEY (chain_id, step_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_reasoning_chains_from ON reasoning_chains(from_id);
+CREATE INDEX IF NOT EXISTS idx_reasoning_chains_to ON reasoning_chains(to_id);
+CREATE INDEX IF NOT EXISTS idx_chain_steps_memory ON reasoning_chain_steps(memory_id);
+
+UPDATE schema_version SET version = 11, applied_at = datetime('now');
+"#;
+
+/// V12: Usage history — tool invocations that feed speculative retrieval
+const MIGRATION_V12_UP: &str = r#"
+CREATE TABLE IF NOT EXISTS usage_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tool TEXT NOT NULL,
+    query TEXT,
+    memory_ids TEXT NOT NULL DEFAULT '[]',
+    project TEXT,
+    hour_of_day INTEGER NOT NULL,
+    recorded_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_usage_history_recorded ON usage_history(recorded_at);
+CREATE INDEX IF NOT EXISTS idx_usage_history_project_hour ON usage_history(project, hour_of_day);
+
+UPDATE schema_version SET version = 12, applied_at = datetime('now');
+"#;
+
+/// V13: Embedding strategy per node, so re-embedding can target one strategy
+/// (existing vectors were all made from raw text with the sentence model)
+const MIGRATION_V13_UP: &str = r#"
+ALTER TABLE node_embeddings ADD COLUMN strategy TEXT NOT NULL DEFAULT 'sentence_transformer';
+
+CREATE INDEX IF NOT EXISTS idx_node_embeddings_strategy ON node_embeddings(strategy);
+
+UPDATE schema_version SET version = 13, applied_at = datetime('now');
+"#;
+
+/// V14: A weight per access log entry, scaling its ACT-R contribution on
+/// top of its access type's weight
+const MIGRATION_V14_UP: &str = r#"
+ALTER TABLE memory_access_log ADD COLUMN weight REAL NOT NULL DEFAULT 1.0;
+
+UPDATE schema_version SET version = 14, applied_at = datetime('now');
+"#;
+
+/// V15: Composite importance (utility, ACT-R activation, emotion, repetition),
+/// NULL until the node's first consolidation
+const MIGRATION_V15_UP: &str = r#"
+ALTER TABLE knowledge_nodes ADD COLUMN importance REAL;
+
+CREATE INDEX IF NOT EXISTS idx_nodes_importance ON knowledge_nodes(importance);
+
+UPDATE schema_version SET version = 15, applied_at = datetime('now');
+"#;
+
+/// V16: Synaptic tags on weakly encoded memories and the importance events
+/// that capture them retroactively during consolidation
+const MIGRATION_V16_UP: &str = r#"
+CREATE TABLE IF NOT EXISTS synaptic_tags (
+    memory_id TEXT PRIMARY KEY REFERENCES knowledge_nodes(id) ON DELETE CASCADE,
+    tagged_at TEXT NOT NULL,
+    initial_strength REAL NOT NULL DEFAULT 1.0,
+    captured_at TEXT,
+    capture_event TEXT,
+    consolidated_importance REAL
+);
+
+CREATE INDEX IF NOT EXISTS idx_synaptic_tags_tagged_at ON synaptic_tags(tagged_at);
+
+CREATE TABLE IF NOT EXISTS importance_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_type TEXT NOT NULL,
+    memory_id TEXT,
+    strength REAL NOT NULL,
+    context TEXT,
+    occurred_at TEXT NOT NULL,
+    processed_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_importance_events_processed ON importance_events(processed_at);
+
+UPDATE schema_version SET version = 16, applied_at = datetime('now');
+"#;
+
+/// V17: Content replaced by each update, newest revisions kept per node
+const MIGRATION_V17_UP: &str = r#"
+CREATE TABLE IF NOT EXISTS node_revisions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    node_id TEXT NOT NULL REFERENCES knowledge_nodes(id) ON DELETE CASCADE,
+    old_content TEXT NOT NULL,
+    new_content_hash TEXT NOT NULL,
+    changed_at TEXT NOT NULL,
+    change_source TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_node_revisions_node ON node_revisions(node_id, id);
+
+UPDATE schema_version SET version = 17, applied_at = datetime('now');
+"#;
+
+/// V18: Support count per universal pattern — the number of distinct memories
+/// that have evidenced it, backfilled from the stored supporting memories
+const MIGRATION_V18_UP: &str = r#"
+ALTER TABLE universal_patterns ADD COLUMN support INTEGER NOT NULL DEFAULT 0;
+
+UPDATE universal_patterns SET support = json_array_length(supporting_memories);
+
+CREATE INDEX IF NOT EXISTS idx_universal_patterns_support ON universal_patterns(support);
+
+UPDATE schema_version SET version = 18, applied_at = datetime('now');
+"#;
+
+/// V19: Namespaces — NULL is the default namespace, so existing data stays
+/// where it was
+const MIGRATION_V19_UP: &str = r#"
+ALTER TABLE knowledge_nodes ADD COLUMN namespace TEXT;
+ALTER TABLE intentions ADD COLUMN namespace TEXT;
+ALTER TABLE insights ADD COLUMN namespace TEXT;
+ALTER TABLE memory_connections ADD COLUMN namespace TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_nodes_namespace ON knowledge_nodes(namespace);
+CREATE INDEX IF NOT EXISTS idx_intentions_namespace ON intentions(namespace);
+CREATE INDEX IF NOT EXISTS idx_insights_namespace ON insights(namespace);
+CREATE INDEX IF NOT EXISTS idx_connections_namespace ON memory_connections(namespace);
+
+UPDATE schema_version SET version = 19, applied_at = datetime('now');
+"#;
+
+/// V20: Trust multiplier per source pattern — an exact source, or a prefix
+/// when the pattern ends in `*`
+const MIGRATION_V20_UP: &str = r#"
+CREATE TABLE IF NOT EXISTS source_trust (
+    pattern TEXT PRIMARY KEY,
+    weight REAL NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+UPDATE schema_version SET version = 20, applied_at = datetime('now');
+"#;
+
+/// V21: Embedding of each revision's content, so revisions can be compared
+/// semantically after the node is re-embedded
+const MIGRATION_V21_UP: &str = r#"
+ALTER TABLE node_revisions ADD COLUMN embedding BLOB;
+
+UPDATE schema_version SET version = 21, applied_at = datetime('now');
+"#;
+
+/// V22: Usage events (accesses, promotions, demotions, usefulness marks)
+/// replayed by consolidation into per-node importance scores, with a history
+/// of how each score moved
+const MIGRATION_V22_UP: &str = r#"
+CREATE TABLE IF NOT EXISTS usage_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    memory_id TEXT NOT NULL REFERENCES knowledge_nodes(id) ON DELETE CASCADE,
+    was_helpful INTEGER NOT NULL,
+    context TEXT,
+    occurred_at TEXT NOT NULL,
+    processed_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_usage_events_processed ON usage_events(processed_at);
+
+CREATE TABLE IF NOT EXISTS importance_scores (
+    memory_id TEXT PRIMARY KEY REFERENCES knowledge_nodes(id) ON DELETE CASCADE,
+    base_importance REAL NOT NULL,
+    usage_importance REAL NOT NULL,
+    recency_importance REAL NOT NULL,
+    connection_importance REAL NOT NULL,
+    final_score REAL NOT NULL,
+    retrieval_count INTEGER NOT NULL DEFAULT 0,
+    helpful_count INTEGER NOT NULL DEFAULT 0,
+    last_accessed TEXT,
+    calculated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS importance_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    memory_id TEXT NOT NULL REFERENCES knowledge_nodes(id) ON DELETE CASCADE,
+    final_score REAL NOT NULL,
+    usage_importance REAL NOT NULL,
+    recency_importance REAL NOT NULL,
+    cause TEXT NOT NULL,
+    recorded_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_importance_history_memory ON importance_history(memory_id, recorded_at);
+
+UPDATE schema_version SET version = 22, applied_at = datetime('now');
+"#;
+
+/// V23: Key/value metadata about the database itself (schema and crate version)
+const MIGRATION_V23_UP: &str = r#"
+CREATE TABLE IF NOT EXISTS meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+UPDATE schema_version SET version = 23, applied_at = datetime('now');
+"#;
+
+/// V24: Hash of normalized content, looked up by plain ingest to skip exact duplicates
+const MIGRATION_V24_UP: &str = r#"
+ALTER TABLE knowledge_nodes ADD COLUMN content_hash TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_nodes_content_hash ON knowledge_nodes(content_hash, node_type);
+
+UPDATE schema_version SET version = 24, applied_at = datetime('now');
+"#;
+
+/// V25: Embeddings truncated to the vector index's dimensions, so loading the
+/// index is a straight copy; `embedding` keeps the longest vector available
+const MIGRATION_V25_UP: &str = r#"
+ALTER TABLE node_embeddings ADD COLUMN index_vector BLOB;
+ALTER TABLE node_embeddings ADD COLUMN index_dimensions INTEGER;
+
+UPDATE schema_version SET version = 25, applied_at = datetime('now');
+"#;
+
+/// V26: Why a memory was promoted or demoted, alongside the access
+const MIGRATION_V26_UP: &str = r#"
+ALTER TABLE memory_access_log ADD COLUMN details TEXT;
+
+UPDATE schema_version SET version = 26, applied_at = datetime('now');
+"#;
+
+/// V27: Outcomes (successful deploy, failed build...) attributed to the
+/// memories that informed the task
+const MIGRATION_V27_UP: &str = r#"
+CREATE TABLE IF NOT EXISTS memory_outcomes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    memory_id TEXT NOT NULL REFERENCES knowledge_nodes(id) ON DELETE CASCADE,
+    outcome TEXT NOT NULL,
+    magnitude REAL NOT NULL,
+    note TEXT,
+    recorded_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_memory_outcomes_memory ON memory_outcomes(memory_id, recorded_at);
+
+UPDATE schema_version SET version = 27, applied_at = datetime('now');
+"#;
+
+/// V28: Pins keep memories surfacing, exempt from decay and GC, until
+/// `pinned_until` passes (NULL = until unpinned)
+const MIGRATION_V28_UP: &str = r#"
+CREATE TABLE IF NOT EXISTS memory_pins (
+    memory_id TEXT PRIMARY KEY REFERENCES knowledge_nodes(id) ON DELETE CASCADE,
+    pinned_at TEXT NOT NULL,
+    pinned_until TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_memory_pins_until ON memory_pins(pinned_until);
+
+UPDATE schema_version SET version = 28, applied_at = datetime('now');
+"#;
+
+/// V29: Last commit analyzed per repository, so git history analysis only
+/// processes new commits
+const MIGRATION_V29_UP: &str = r#"
+CREATE TABLE IF NOT EXISTS git_cursors (
+    repo_path TEXT PRIMARY KEY,
+    last_commit TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+UPDATE schema_version SET version = 29, applied_at = datetime('now');
+"#;
+
+/// V30: Consolidation steps run in isolation; keep how long each took and
+/// which failed (JSON arrays)
+const MIGRATION_V30_UP: &str = r#"
+ALTER TABLE consolidation_history ADD COLUMN step_timings TEXT DEFAULT '[]';
+ALTER TABLE consolidation_history ADD COLUMN step_errors TEXT DEFAULT '[]';
+
+UPDATE schema_version SET version = 30, applied_at = datetime('now');
+"#;
+
+/// V31: Auto-consolidation skips runs when nothing changed; record why
+/// (NULL for runs that did the work)
+const MIGRATION_V31_UP: &str = r#"
+ALTER TABLE consolidation_history ADD COLUMN skipped_reason TEXT;
+
+UPDATE schema_version SET version = 31, applied_at = datetime('now');
+"#;
+
+/// V32: Language of code embedded with the code strategy (NULL for prose
+/// and unrecognized languages)
+const MIGRATION_V32_UP: &str = r#"
+ALTER TABLE node_embeddings ADD COLUMN language TEXT;
+
+UPDATE schema_version SET version = 32, applied_at = datetime('now');
+"#;
+
+/// V33: When decay last rewrote each memory's strengths, so back-to-back
+/// decay passes can skip memories decayed moments ago
+const MIGRATION_V33_UP: &str = r#"
+ALTER TABLE knowledge_nodes ADD COLUMN last_decayed_at TEXT;
+
+UPDATE schema_version SET version = 33, applied_at = datetime('now');
+"#;
+
+/// V34: Why a consolidation run stopped before every step ran, and the
+/// steps it left out
+const MIGRATION_V34_UP: &str = r#"
+ALTER TABLE consolidation_history ADD COLUMN stopped_reason TEXT;
+ALTER TABLE consolidation_history ADD COLUMN steps_not_run TEXT DEFAULT '[]';
+
+UPDATE schema_version SET version = 34, applied_at = datetime('now');
+"#;
+
+/// V35: Running ACT-R access sums, so consolidation only reads the access
+/// log rows added since a memory's last computation
+const MIGRATION_V35_UP: &str = r#"
+CREATE TABLE IF NOT EXISTS actr_state (
+    node_id TEXT PRIMARY KEY REFERENCES knowledge_nodes(id) ON DELETE CASCADE,
+    recent_accesses TEXT NOT NULL DEFAULT '[]',  -- JSON array, oldest first
+    tail_sum REAL NOT NULL DEFAULT 0,
+    tail_count INTEGER NOT NULL DEFAULT 0,
+    tail_newest TEXT,
+    tail_oldest TEXT,
+    computed_at TEXT NOT NULL,
+    last_log_id INTEGER NOT NULL DEFAULT 0,
+    error_bound REAL NOT NULL DEFAULT 0
+);
+
+-- Covers the node_id lookups of idx_access_log_node too
+CREATE INDEX IF NOT EXISTS idx_access_log_node_time ON memory_access_log(node_id, accessed_at);
+DROP INDEX IF EXISTS idx_access_log_node;
+
+UPDATE schema_version SET version = 35, applied_at = datetime('now');
+"#;
+
+/// Fill `content_hash` for nodes written before V24
+fn backfill_content_hashes(conn: &rusqlite::Connection) -> rusqlite::Result<usize> {
+    let rows: Vec<(String, String)> = conn
+        .prepare("SELECT id, content FROM knowledge_nodes WHERE content_hash IS NULL")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    let mut stmt = conn.prepare("UPDATE knowledge_nodes SET content_hash = ?1 WHERE id = ?2")?;
+    for (id, content) in &rows {
+        stmt.execute(rusqlite::params![super::diff::normalized_content_hash(content), id])?;
+    }
+    Ok(rows.len())
+}
+
+/// Newest schema version this build knows how to use
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
 /// Get current schema version from database
+///
+/// Prefers the `meta` table, falling back to `schema_version` for databases
+/// written before V23.
 pub fn get_current_version(conn: &rusqlite::Connection) -> rusqlite::Result<u32> {
-    conn.query_row(
-        "SELECT COALESCE(MAX(version), 0) FROM schema_version",
-        [],
-        |row| row.get(0),
-    )
-    .or(Ok(0))
+    let from_meta: Option<u32> = conn
+        .query_row(
+            "SELECT CAST(value AS INTEGER) FROM meta WHERE key = 'schema_version'",
+            [],
+            |row| row.get(0),
+        )
+        .ok();
+    let from_table: u32 = conn
+        .query_row(
+            "SELECT COALESCE(MAX(version), 0) FROM schema_version",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+    Ok(from_meta.unwrap_or(0).max(from_table))
+}
+
+/// Crate version that last migrated the database, if recorded
+pub fn get_written_by(conn: &rusqlite::Connection) -> Option<String> {
+    conn.query_row("SELECT value FROM meta WHERE key = 'crate_version'", [], |row| row.get(0))
+        .ok()
 }
 
 /// Apply pending migrations
+///
+/// Databases already past `latest_version()` are left untouched; callers
+/// check for that first (see `Storage::new`).
 pub fn apply_migrations(conn: &rusqlite::Connection) -> rusqlite::Result<u32> {
     let current_version = get_current_version(conn)?;
+    if current_version > latest_version() {
+        return Ok(0);
+    }
     let mut applied = 0;
 
-    for migration in MIGRATIONS {
+    for (index, migration) in MIGRATIONS.iter().enumerate() {
         if migration.version > current_version {
             tracing::info!(
-                "Applying migration v{}: {}",
+                "Applying migration #{} (v{}): {}",
+                index,
                 migration.version,
                 migration.description
             );
@@ -639,9 +1192,24 @@ pub fn apply_migrations(conn: &rusqlite::Connection) -> rusqlite::Result<u32> {
                 tracing::info!("Database page_size upgraded to 8192 via VACUUM");
             }
 
+            // V24: Content hashes are computed in Rust, so existing rows are filled here
+            if migration.version == 24 {
+                let filled = backfill_content_hashes(conn)?;
+                tracing::info!("Backfilled content hashes for {} memories", filled);
+            }
+
             applied += 1;
         }
     }
 
+    conn.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', ?1)",
+        [latest_version().to_string()],
+    )?;
+    conn.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES ('crate_version', ?1)",
+        [env!("CARGO_PKG_VERSION")],
+    )?;
+
     Ok(applied)
 }
@@ -6,11 +6,37 @@
 //! - FSRS-6 state management
 //! - Temporal memory support
 
+mod backup;
+mod diff;
+mod metrics;
 mod migrations;
+mod namespace;
+mod pipeline;
+mod query_cache;
+mod reinforcement;
 mod sqlite;
 
+pub use backup::{
+    backup_file_name, backups_to_prune, default_backup_dir, list_backups_in,
+    parse_backup_file_name, BackupConfig, BackupInfo,
+};
+pub use diff::{content_hash, normalize_whitespace, normalized_content_hash, unified_diff};
+pub use metrics::{MetricsSnapshot, SLOW_LOCK_WAIT};
 pub use migrations::MIGRATIONS;
+pub use query_cache::{normalize_query, QueryCacheStats, DEFAULT_QUERY_CACHE_BYTES};
+pub use namespace::NamespacedStorage;
+pub use pipeline::{
+    CancellationToken, ConsolidationConfig, ConsolidationLimits, ConsolidationPipeline,
+    ConsolidationStep, StepContext,
+};
 pub use sqlite::{
-    ConnectionRecord, ConsolidationHistoryRecord, DreamHistoryRecord, InsightRecord,
-    IntentionRecord, Result, SmartIngestResult, StateTransitionRecord, Storage, StorageError,
+    AccessAnalytics, AccessKind, AccessRecord, AccessedMemory, BackfillOptions, BackfillProgress, BulkAction, BulkOutcome,
+    ConnectionRecord, ConsolidationHistoryRecord, ContentUpdateMode, ContextPacket, ContextSection,
+    DailyAccessCount, DiagnosticCheck, DiagnosticStatus, DiagnosticsReport, DreamHistoryRecord,
+    FeedbackOptions, FeedbackRecord, Forecast, ForecastDay, GraphFilter, HealthReport,
+    ImportanceHistoryPoint, IngestOutcome, InsightRecord, IntentionRecord, NodeFilter, NodeGraphState,
+    NodeImportance, NodeRevision, OutcomeRecord, PinnedMemory, ReasoningChainRecord, ReinforcementConfig,
+    RepairReport, Result, RetentionPolicy, RetrievalForgettingPolicy, RetruncateReport,
+    RevisionDiff, SmartIngestResult, SourceTrust, StateTransitionRecord, StatsFilter, Storage,
+    StorageConfig, StorageError, UsageRecord,
 };
@@ -0,0 +1,175 @@
+//! Namespaced views for isolating memories within one database
+
+use super::pipeline::ConsolidationLimits;
+use super::sqlite::{IngestOutcome, InsightRecord, IntentionRecord, Result, Storage};
+#[cfg(all(feature = "embeddings", feature = "vector-search"))]
+use super::sqlite::SmartIngestResult;
+use crate::advanced::session_packet::SessionOverview;
+use crate::consolidation::FourPhaseDreamResult;
+use crate::memory::{
+    ConsolidationResult, IngestInput, KnowledgeNode, MemoryStats, RecallInput, StepResult,
+};
+#[cfg(all(feature = "embeddings", feature = "vector-search"))]
+use crate::memory::SearchResult;
+#[cfg(all(feature = "embeddings", feature = "vector-search"))]
+use crate::search::HybridSearchConfig;
+use chrono::{DateTime, Utc};
+
+/// A view of `Storage` confined to one namespace
+///
+/// Memories ingested through the view are stored in its namespace, and
+/// recall, search, stats, consolidation, intentions and insights only see
+/// that namespace. Created with `Storage::with_namespace`.
+pub struct NamespacedStorage<'a> {
+    storage: &'a Storage,
+    namespace: String,
+}
+
+impl<'a> NamespacedStorage<'a> {
+    pub(crate) fn new(storage: &'a Storage, namespace: String) -> Self {
+        Self { storage, namespace }
+    }
+
+    /// The namespace this view is confined to
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// Ingest a new memory into this namespace
+    pub fn ingest(&self, mut input: IngestInput) -> Result<KnowledgeNode> {
+        input.namespace = Some(self.namespace.clone());
+        self.storage.ingest(input)
+    }
+
+    /// `ingest_with_outcome` into this namespace
+    pub fn ingest_with_outcome(&self, mut input: IngestInput) -> Result<IngestOutcome> {
+        input.namespace = Some(self.namespace.clone());
+        self.storage.ingest_with_outcome(input)
+    }
+
+    /// Smart ingest, only ever updating memories in this namespace
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    pub fn smart_ingest(&self, mut input: IngestInput) -> Result<SmartIngestResult> {
+        input.namespace = Some(self.namespace.clone());
+        self.storage.smart_ingest(input)
+    }
+
+    /// Recall memories from this namespace only
+    pub fn recall(&self, mut input: RecallInput) -> Result<Vec<KnowledgeNode>> {
+        input.namespace = Some(self.namespace.clone());
+        self.storage.recall(input)
+    }
+
+    /// `recall_without_strengthen` limited to this namespace
+    pub fn recall_without_strengthen(&self, mut input: RecallInput) -> Result<Vec<KnowledgeNode>> {
+        input.namespace = Some(self.namespace.clone());
+        self.storage.recall_without_strengthen(input)
+    }
+
+    /// Get a node by ID; nodes in other namespaces are not found
+    pub fn get_node(&self, id: &str) -> Result<Option<KnowledgeNode>> {
+        Ok(self
+            .storage
+            .get_node(id)?
+            .filter(|node| node.namespace.as_deref() == Some(self.namespace.as_str())))
+    }
+
+    /// Nodes in this namespace, newest first (paginated)
+    pub fn get_all_nodes(&self, limit: i32, offset: i32) -> Result<Vec<KnowledgeNode>> {
+        self.storage.get_all_nodes_in(Some(&self.namespace), limit, offset)
+    }
+
+    /// Nodes in this namespace created in a time range, newest first
+    pub fn query_time_range(
+        &self,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+        limit: i32,
+    ) -> Result<Vec<KnowledgeNode>> {
+        self.storage.query_time_range_in(Some(&self.namespace), start, end, limit)
+    }
+
+    /// Hybrid search over this namespace's memories
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    pub fn hybrid_search_with_config(
+        &self,
+        query: &str,
+        limit: i32,
+        config: &HybridSearchConfig,
+    ) -> Result<Vec<SearchResult>> {
+        let config = HybridSearchConfig { namespace: Some(self.namespace.clone()), ..config.clone() };
+        self.storage.hybrid_search_with_config(query, limit, &config)
+    }
+
+    /// Session working set built from this namespace's memories and intentions
+    pub fn build_session_overview(
+        &self,
+        project_tag: Option<&str>,
+        budget_tokens: usize,
+    ) -> Result<SessionOverview> {
+        self.storage.build_session_overview_in(Some(&self.namespace), project_tag, budget_tokens)
+    }
+
+    /// Nodes in this namespace of a type, optionally carrying a tag
+    pub fn get_nodes_by_type_and_tag(
+        &self,
+        node_type: &str,
+        tag_filter: Option<&str>,
+        limit: i32,
+    ) -> Result<Vec<KnowledgeNode>> {
+        self.storage.get_nodes_by_type_and_tag_in(Some(&self.namespace), node_type, tag_filter, limit)
+    }
+
+    /// Memory statistics for this namespace
+    pub fn get_stats(&self) -> Result<MemoryStats> {
+        self.storage.get_stats_in(Some(&self.namespace))
+    }
+
+    /// Consolidate this namespace's memories
+    ///
+    /// Shared maintenance (vector index, access log, FTS) still runs
+    /// database-wide.
+    pub fn run_consolidation(&self) -> Result<ConsolidationResult> {
+        self.storage.run_consolidation_in(Some(&self.namespace), &ConsolidationLimits::default(), &mut |_| {})
+    }
+
+    /// Run one consolidation step by name over this namespace's memories
+    pub fn run_consolidation_step(&self, name: &str) -> Result<StepResult> {
+        self.storage.run_consolidation_step_in(Some(&self.namespace), name)
+    }
+
+    /// Run the 4-phase dream cycle over this namespace's memories
+    pub fn run_dream_cycle(&self) -> Result<FourPhaseDreamResult> {
+        self.storage.run_dream_cycle_in(Some(&self.namespace))
+    }
+
+    /// Save an intention into this namespace
+    pub fn save_intention(&self, intention: &IntentionRecord) -> Result<()> {
+        self.storage.save_intention_in(intention, Some(&self.namespace))
+    }
+
+    /// Active intentions in this namespace
+    pub fn get_active_intentions(&self) -> Result<Vec<IntentionRecord>> {
+        self.storage.get_active_intentions_in(Some(&self.namespace))
+    }
+
+    /// Get an intention by ID; intentions in other namespaces are not found
+    pub fn get_intention(&self, id: &str) -> Result<Option<IntentionRecord>> {
+        self.storage.get_intention_in(Some(&self.namespace), id)
+    }
+
+    /// Intentions in this namespace with a status
+    pub fn get_intentions_by_status(&self, status: &str) -> Result<Vec<IntentionRecord>> {
+        self.storage.get_intentions_by_status_in(Some(&self.namespace), status)
+    }
+
+    /// Save an insight into this namespace
+    pub fn save_insight(&self, insight: &InsightRecord) -> Result<()> {
+        self.storage.save_insight_in(insight, Some(&self.namespace))
+    }
+
+    /// Insights generated from this namespace, newest first
+    pub fn get_insights(&self, limit: i32) -> Result<Vec<InsightRecord>> {
+        self.storage.get_insights_in(Some(&self.namespace), limit)
+    }
+}
@@ -0,0 +1,1050 @@
+//! Consolidation Pipeline
+//!
+//! `Storage::run_consolidation` runs a fixed sequence of named steps (decay,
+//! dedup, dreams, compression...). Each step implements `ConsolidationStep`
+//! and reports a `StepResult`; `ConsolidationConfig` chooses which steps run
+//! and tunes their parameters. The config is saved in the database, can be
+//! overridden from the environment, and `Storage::run_consolidation_step`
+//! runs one step on demand.
+//!
+//! Each step runs in its own error boundary: a step that fails or panics is
+//! reported in `ConsolidationResult::step_errors` and the rest still run.
+//! `ConsolidationLimits` bound a run: between steps it stops once its
+//! deadline has passed or its `CancellationToken` is cancelled.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::memory::{ConsolidationProgress, ConsolidationResult, StepError, StepResult, StepTiming};
+
+use super::sqlite::{InsightRecord, NodeFilter, Result, Storage, StorageError};
+
+/// Consolidation settings, persisted with `Storage::set_consolidation_config`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ConsolidationConfig {
+    /// Names of the steps that run (see `ConsolidationPipeline::step_names`)
+    pub enabled_steps: BTreeSet<String>,
+    /// Cosine similarity at or above which `dedup` merges memories
+    pub dedup_threshold: f32,
+    /// Seconds `dedup` may spend per run; memories it had no time for are
+    /// examined first next run
+    pub dedup_time_budget_secs: u64,
+    /// Most recent memories of each namespace `dreams` draws insights from
+    pub dream_sample_size: i32,
+    /// Memories a namespace needs in the sample before `dreams` runs on it
+    pub dream_min_memories: usize,
+    /// `compression` only considers memories older than this
+    pub compression_min_age_days: i64,
+    /// `compression` only considers memories below this retention
+    pub compression_max_retention: f64,
+    /// Most groups `compression` folds per run
+    pub compression_max_groups: usize,
+    /// `connections` drops connections weaker than this
+    pub connection_prune_threshold: f64,
+    /// `run_consolidation` skips the run while fewer memories than this
+    /// changed since the last one (0 never skips)
+    pub skip_below_mutations: u64,
+    /// Hours after the last run when skipping stops, since decay moves on
+    /// even when nothing changed
+    pub max_skip_hours: i64,
+    /// `decay` skips memories it decayed less than this many minutes ago,
+    /// unless they were accessed since (0 decays every memory every run)
+    pub decay_freshness_minutes: i64,
+}
+
+impl Default for ConsolidationConfig {
+    fn default() -> Self {
+        Self {
+            enabled_steps: ConsolidationPipeline::step_names()
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            dedup_threshold: 0.85,
+            dedup_time_budget_secs: 30,
+            dream_sample_size: 100,
+            dream_min_memories: 5,
+            compression_min_age_days: 30,
+            compression_max_retention: 0.5,
+            compression_max_groups: 5,
+            connection_prune_threshold: 0.05,
+            skip_below_mutations: 1,
+            max_skip_hours: 24,
+            decay_freshness_minutes: 60,
+        }
+    }
+}
+
+impl ConsolidationConfig {
+    /// Whether the step named `name` runs
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.enabled_steps.contains(name)
+    }
+
+    /// Apply VESTIGE_CONSOLIDATION_STEPS (comma-separated steps to run,
+    /// replacing the enabled set) and VESTIGE_CONSOLIDATION_DISABLED_STEPS
+    /// (comma-separated steps to skip); unknown names are ignored
+    pub fn with_env_overrides(mut self) -> Self {
+        let names = |var: &str| -> Option<BTreeSet<String>> {
+            let value = std::env::var(var).ok()?;
+            Some(
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .filter(|name| {
+                        let known = ConsolidationPipeline::standard().step(name).is_some();
+                        if !known {
+                            tracing::warn!(step = name, var, "Ignoring unknown consolidation step");
+                        }
+                        known
+                    })
+                    .map(str::to_string)
+                    .collect(),
+            )
+        };
+        if let Some(enabled) = names("VESTIGE_CONSOLIDATION_STEPS") {
+            self.enabled_steps = enabled;
+        }
+        if let Some(disabled) = names("VESTIGE_CONSOLIDATION_DISABLED_STEPS") {
+            self.enabled_steps.retain(|name| !disabled.contains(name));
+        }
+        self
+    }
+
+    /// Reject unknown step names and out-of-range parameters
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |field: &'static str, reason: String| {
+            Err(StorageError::InvalidInput { field, reason })
+        };
+        let pipeline = ConsolidationPipeline::standard();
+        if let Some(unknown) = self.enabled_steps.iter().find(|name| pipeline.step(name).is_none()) {
+            return invalid(
+                "enabled_steps",
+                format!(
+                    "unknown step '{}'; expected one of {}",
+                    unknown,
+                    ConsolidationPipeline::step_names().join(", ")
+                ),
+            );
+        }
+        if !(self.dedup_threshold > 0.0 && self.dedup_threshold <= 1.0) {
+            return invalid("dedup_threshold", "must be in (0, 1]".to_string());
+        }
+        if self.dedup_time_budget_secs == 0 {
+            return invalid("dedup_time_budget_secs", "must be positive".to_string());
+        }
+        if self.dream_sample_size < 0 {
+            return invalid("dream_sample_size", "must not be negative".to_string());
+        }
+        if self.compression_min_age_days < 0 {
+            return invalid("compression_min_age_days", "must not be negative".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.compression_max_retention) {
+            return invalid("compression_max_retention", "must be in [0, 1]".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.connection_prune_threshold) {
+            return invalid("connection_prune_threshold", "must be in [0, 1]".to_string());
+        }
+        if self.max_skip_hours < 0 {
+            return invalid("max_skip_hours", "must not be negative".to_string());
+        }
+        if self.decay_freshness_minutes < 0 {
+            return invalid("decay_freshness_minutes", "must not be negative".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Cancels consolidation runs it was given to, e.g. on shutdown
+///
+/// Clones share one flag. A run checks it between steps, so the step in
+/// progress finishes first.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every run holding this token to stop after its current step
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Bounds on one consolidation run
+#[derive(Debug, Clone, Default)]
+pub struct ConsolidationLimits {
+    /// No further steps start once the run has taken this long
+    pub deadline: Option<std::time::Duration>,
+    /// No further steps start once this is cancelled
+    pub cancel: Option<CancellationToken>,
+}
+
+impl ConsolidationLimits {
+    /// Why a run started at `started` must stop before its next step, if it must
+    fn stop_reason(&self, started: Instant) -> Option<&'static str> {
+        if self.cancel.as_ref().is_some_and(CancellationToken::is_cancelled) {
+            Some("cancelled")
+        } else if self.deadline.is_some_and(|deadline| started.elapsed() >= deadline) {
+            Some("deadline")
+        } else {
+            None
+        }
+    }
+}
+
+/// What a step may look at while it runs
+pub struct StepContext<'a> {
+    /// Namespace being consolidated, or `None` for all of them
+    pub namespace: Option<&'a str>,
+    pub config: &'a ConsolidationConfig,
+}
+
+/// One named consolidation step
+pub trait ConsolidationStep: Send + Sync {
+    /// Stable name used in `ConsolidationConfig` and `run_consolidation_step`
+    fn name(&self) -> &'static str;
+
+    /// Human-readable label reported through `ConsolidationProgress`
+    fn label(&self) -> &'static str;
+
+    /// Run the step against `storage`
+    fn run(&self, storage: &Storage, ctx: &StepContext<'_>) -> Result<StepResult>;
+}
+
+/// The consolidation steps, in the order they run
+pub struct ConsolidationPipeline {
+    steps: Vec<Box<dyn ConsolidationStep>>,
+}
+
+impl ConsolidationPipeline {
+    /// The standard pipeline `Storage::run_consolidation` runs
+    pub fn standard() -> Self {
+        Self {
+            steps: vec![
+                Box::new(DecayStep),
+                Box::new(PromoteEmotionalStep),
+                Box::new(EmbeddingsStep),
+                Box::new(DedupStep),
+                Box::new(ActrStep),
+                Box::new(AccessLogStep),
+                Box::new(W20Step),
+                Box::new(DreamsStep),
+                Box::new(CompressionStep),
+                Box::new(StatesStep),
+                Box::new(StcStep),
+                Box::new(CrossProjectStep),
+                Box::new(HippocampalStep),
+                Box::new(ImportanceStep),
+                Box::new(UsageLogStep),
+                Box::new(ConnectionsStep),
+                Box::new(FtsOptimizeStep),
+                Box::new(AutoPromoteStep),
+                Box::new(RetentionGcStep),
+            ],
+        }
+    }
+
+    /// Names of the standard steps, in order
+    pub fn step_names() -> Vec<&'static str> {
+        Self::standard().steps().map(|step| step.name()).collect()
+    }
+
+    /// All steps, in order
+    pub fn steps(&self) -> impl Iterator<Item = &dyn ConsolidationStep> {
+        self.steps.iter().map(|step| step.as_ref())
+    }
+
+    /// The step named `name`
+    pub fn step(&self, name: &str) -> Option<&dyn ConsolidationStep> {
+        self.steps().find(|step| step.name() == name)
+    }
+
+    /// A pipeline of the given steps, e.g. the standard ones plus custom ones
+    /// (see `Storage::run_consolidation_pipeline`)
+    pub fn new(steps: Vec<Box<dyn ConsolidationStep>>) -> Self {
+        Self { steps }
+    }
+
+    /// Run the steps in order: standard steps when `ctx.config` enables
+    /// them, custom steps always
+    ///
+    /// Never fails as a whole: an error or panic inside a step lands in
+    /// `step_errors`, and every attempted step gets a `step_timings` entry.
+    /// When `limits` stop the run early, the steps left out are listed in
+    /// `steps_not_run` and the reason in `stopped_early`.
+    pub(crate) fn run(
+        &self,
+        storage: &Storage,
+        ctx: &StepContext<'_>,
+        limits: &ConsolidationLimits,
+        progress: &mut dyn FnMut(ConsolidationProgress),
+    ) -> ConsolidationResult {
+        let run_started = Instant::now();
+        let standard = Self::step_names();
+        let enabled: Vec<&dyn ConsolidationStep> = self
+            .steps()
+            .filter(|step| ctx.config.is_enabled(step.name()) || !standard.contains(&step.name()))
+            .collect();
+
+        let mut steps = BTreeMap::new();
+        let mut step_timings = Vec::with_capacity(enabled.len());
+        let mut step_errors = Vec::new();
+        let mut stopped_early = None;
+        let mut steps_not_run = Vec::new();
+        for (i, step) in enabled.iter().enumerate() {
+            if let Some(reason) = limits.stop_reason(run_started) {
+                tracing::info!(reason, remaining = enabled.len() - i, "Consolidation stopped early");
+                stopped_early = Some(reason.to_string());
+                steps_not_run = enabled[i..].iter().map(|step| step.name().to_string()).collect();
+                break;
+            }
+            let started = Instant::now();
+            let outcome = match catch_unwind(AssertUnwindSafe(|| step.run(storage, ctx))) {
+                Ok(Ok(result)) => Ok(result),
+                Ok(Err(e)) => Err(e.to_string()),
+                Err(panic) => Err(format!("panicked: {}", panic_message(panic.as_ref()))),
+            };
+            let duration_ms = started.elapsed().as_millis() as i64;
+
+            step_timings.push(StepTiming {
+                step: step.name().to_string(),
+                duration_ms,
+                succeeded: outcome.is_ok(),
+            });
+            match outcome {
+                Ok(result) => {
+                    steps.insert(step.name().to_string(), result);
+                }
+                Err(error) => {
+                    tracing::warn!(step = step.name(), %error, "Consolidation step failed");
+                    step_errors.push(StepError { step: step.name().to_string(), error });
+                }
+            }
+            progress(ConsolidationProgress {
+                step: i + 1,
+                total: enabled.len(),
+                label: step.label(),
+            });
+        }
+
+        let mut result = ConsolidationResult::from_steps(steps);
+        result.step_timings = step_timings;
+        result.step_errors = step_errors;
+        result.stopped_early = stopped_early;
+        result.steps_not_run = steps_not_run;
+        result
+    }
+}
+
+/// Text of a caught panic payload
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+struct DecayStep;
+
+impl ConsolidationStep for DecayStep {
+    fn name(&self) -> &'static str {
+        "decay"
+    }
+
+    fn label(&self) -> &'static str {
+        "FSRS decay"
+    }
+
+    fn run(&self, storage: &Storage, ctx: &StepContext<'_>) -> Result<StepResult> {
+        let freshness = Duration::minutes(ctx.config.decay_freshness_minutes);
+        let decay_applied = storage.apply_decay_in(ctx.namespace, freshness)? as i64;
+        Ok(StepResult::Decay { decay_applied })
+    }
+}
+
+struct PromoteEmotionalStep;
+
+impl ConsolidationStep for PromoteEmotionalStep {
+    fn name(&self) -> &'static str {
+        "promote_emotional"
+    }
+
+    fn label(&self) -> &'static str {
+        "Emotional promotion"
+    }
+
+    fn run(&self, storage: &Storage, ctx: &StepContext<'_>) -> Result<StepResult> {
+        let promoted = storage.promote_emotional_in(ctx.namespace)?;
+        Ok(StepResult::PromoteEmotional { promoted })
+    }
+}
+
+struct EmbeddingsStep;
+
+impl ConsolidationStep for EmbeddingsStep {
+    fn name(&self) -> &'static str {
+        "embeddings"
+    }
+
+    fn label(&self) -> &'static str {
+        "Embedding generation"
+    }
+
+    fn run(&self, storage: &Storage, _ctx: &StepContext<'_>) -> Result<StepResult> {
+        #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+        let generated = storage.generate_missing_embeddings()?;
+        #[cfg(not(all(feature = "embeddings", feature = "vector-search")))]
+        let generated = {
+            let _ = storage;
+            0
+        };
+        Ok(StepResult::Embeddings { generated })
+    }
+}
+
+struct DedupStep;
+
+impl ConsolidationStep for DedupStep {
+    fn name(&self) -> &'static str {
+        "dedup"
+    }
+
+    fn label(&self) -> &'static str {
+        "Deduplication"
+    }
+
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn run(&self, storage: &Storage, ctx: &StepContext<'_>) -> Result<StepResult> {
+        let budget = std::time::Duration::from_secs(ctx.config.dedup_time_budget_secs);
+        let outcome = storage
+            .auto_dedup_consolidation(ctx.namespace, ctx.config.dedup_threshold, budget)?;
+
+        // Compact the vector index once churn leaves too many removed slots
+        let mut index_rebuilt = false;
+        if let Ok(stats) = storage.vector_index_stats()
+            && stats.needs_compaction(crate::search::DEFAULT_COMPACTION_THRESHOLD)
+        {
+            match storage.rebuild_vector_index() {
+                Ok(count) => {
+                    index_rebuilt = true;
+                    tracing::info!(
+                        removed_slots = stats.removed_slots,
+                        vectors = count,
+                        "Rebuilt vector index"
+                    )
+                }
+                Err(e) => tracing::warn!("Vector index rebuild failed: {}", e),
+            }
+        }
+        Ok(StepResult::Dedup {
+            merged: outcome.merged,
+            clusters_examined: outcome.clusters_examined,
+            clusters_merged: outcome.clusters_merged,
+            budget_exhausted: outcome.budget_exhausted,
+            index_rebuilt,
+        })
+    }
+
+    #[cfg(not(all(feature = "embeddings", feature = "vector-search")))]
+    fn run(&self, _storage: &Storage, _ctx: &StepContext<'_>) -> Result<StepResult> {
+        Ok(StepResult::Dedup {
+            merged: 0,
+            clusters_examined: 0,
+            clusters_merged: 0,
+            budget_exhausted: false,
+            index_rebuilt: false,
+        })
+    }
+}
+
+struct ActrStep;
+
+impl ConsolidationStep for ActrStep {
+    fn name(&self) -> &'static str {
+        "actr"
+    }
+
+    fn label(&self) -> &'static str {
+        "ACT-R activations"
+    }
+
+    fn run(&self, storage: &Storage, _ctx: &StepContext<'_>) -> Result<StepResult> {
+        let activations_computed = storage.compute_act_r_activations()?;
+        Ok(StepResult::Actr { activations_computed })
+    }
+}
+
+struct AccessLogStep;
+
+impl ConsolidationStep for AccessLogStep {
+    fn name(&self) -> &'static str {
+        "access_log"
+    }
+
+    fn label(&self) -> &'static str {
+        "Access log pruning"
+    }
+
+    fn run(&self, storage: &Storage, _ctx: &StepContext<'_>) -> Result<StepResult> {
+        let pruned = storage.prune_access_log()?;
+        Ok(StepResult::AccessLog { pruned })
+    }
+}
+
+struct W20Step;
+
+impl ConsolidationStep for W20Step {
+    fn name(&self) -> &'static str {
+        "w20"
+    }
+
+    fn label(&self) -> &'static str {
+        "w20 optimization"
+    }
+
+    fn run(&self, storage: &Storage, _ctx: &StepContext<'_>) -> Result<StepResult> {
+        let optimized = storage.optimize_w20_if_ready()?;
+        Ok(StepResult::W20 { optimized })
+    }
+}
+
+struct DreamsStep;
+
+impl ConsolidationStep for DreamsStep {
+    fn name(&self) -> &'static str {
+        "dreams"
+    }
+
+    fn label(&self) -> &'static str {
+        "Dream insights"
+    }
+
+    /// Synthesize insights (sync path), one namespace at a time, from each
+    /// namespace's most recent memories
+    fn run(&self, storage: &Storage, ctx: &StepContext<'_>) -> Result<StepResult> {
+        use crate::advanced::dreams::{DreamMemory, MemoryDreamer};
+
+        fn keep_newest(memories: &mut Vec<DreamMemory>, n: usize) {
+            memories.sort_by_key(|m| std::cmp::Reverse(m.created_at));
+            memories.truncate(n);
+        }
+
+        let dreamer = MemoryDreamer::new();
+        let sample_size = ctx.config.dream_sample_size.max(0) as usize;
+        let filter = NodeFilter {
+            namespace: ctx.namespace.map(str::to_string),
+            ..Default::default()
+        };
+        let mut by_namespace: HashMap<Option<String>, Vec<DreamMemory>> = HashMap::new();
+        let scan = storage.for_each_node(&filter, |n| {
+            let memories = by_namespace.entry(n.namespace).or_default();
+            memories.push(DreamMemory {
+                id: n.id,
+                content: n.content,
+                embedding: None,
+                tags: n.tags,
+                created_at: n.created_at,
+                access_count: n.reps as u32,
+            });
+            // Trim in batches so each namespace holds at most twice the sample
+            if memories.len() >= 2 * sample_size.max(1) {
+                keep_newest(memories, sample_size);
+            }
+            Ok(())
+        });
+        scan?;
+        for memories in by_namespace.values_mut() {
+            keep_newest(memories, sample_size);
+        }
+
+        let mut insights_generated = 0i64;
+        for (dream_namespace, dream_memories) in &by_namespace {
+            if dream_memories.len() < ctx.config.dream_min_memories {
+                continue;
+            }
+            let insights = dreamer.synthesize_insights(dream_memories);
+            insights_generated += insights.len() as i64;
+            for insight in &insights {
+                let record = InsightRecord {
+                    id: Uuid::new_v4().to_string(),
+                    insight: insight.insight.clone(),
+                    source_memories: insight.source_memories.clone(),
+                    confidence: insight.confidence,
+                    novelty_score: insight.novelty_score,
+                    insight_type: format!("{:?}", insight.insight_type),
+                    generated_at: storage.now(),
+                    tags: vec![],
+                    feedback: None,
+                    applied_count: 0,
+                };
+                storage.save_insight_in(&record, dream_namespace.as_deref())?;
+            }
+        }
+        Ok(StepResult::Dreams { insights_generated })
+    }
+}
+
+struct CompressionStep;
+
+impl ConsolidationStep for CompressionStep {
+    fn name(&self) -> &'static str {
+        "compression"
+    }
+
+    fn label(&self) -> &'static str {
+        "Compression"
+    }
+
+    /// Fold groups of old, weak memories into summaries; pinned memories are
+    /// left alone
+    fn run(&self, storage: &Storage, ctx: &StepContext<'_>) -> Result<StepResult> {
+        use crate::advanced::compression::{MemoryCompressor, MemoryForCompression};
+
+        let mut compressor = MemoryCompressor::new();
+        let pinned = storage.active_pin_ids()?;
+        let filter = NodeFilter {
+            namespace: ctx.namespace.map(str::to_string),
+            created_before: Some(storage.now() - Duration::days(ctx.config.compression_min_age_days)),
+            max_retention: Some(ctx.config.compression_max_retention),
+        };
+        let mut old_memories = Vec::new();
+        let scan = storage.for_each_node(&filter, |n| {
+            if !pinned.contains(&n.id) {
+                old_memories.push(MemoryForCompression {
+                    id: n.id,
+                    content: n.content,
+                    tags: n.tags,
+                    created_at: n.created_at,
+                    last_accessed: Some(n.last_accessed),
+                    embedding: None,
+                });
+            }
+            Ok(())
+        });
+        scan?;
+
+        let mut memories_compressed = 0i64;
+        if old_memories.len() >= 3 {
+            let groups = compressor.find_compressible_groups(&old_memories);
+            for group_ids in groups.iter().take(ctx.config.compression_max_groups) {
+                let group: Vec<_> = old_memories
+                    .iter()
+                    .filter(|m| group_ids.contains(&m.id))
+                    .cloned()
+                    .collect();
+                if compressor.compress(&group).is_some() {
+                    memories_compressed += group.len() as i64;
+                }
+            }
+        }
+        Ok(StepResult::Compression { memories_compressed })
+    }
+}
+
+struct StatesStep;
+
+impl ConsolidationStep for StatesStep {
+    fn name(&self) -> &'static str {
+        "states"
+    }
+
+    fn label(&self) -> &'static str {
+        "State transitions"
+    }
+
+    /// Active→Dormant→Silent transitions, expired suppressions resolved
+    fn run(&self, storage: &Storage, ctx: &StepContext<'_>) -> Result<StepResult> {
+        let transitions = storage.update_memory_states_in(ctx.namespace)?;
+        if transitions > 0 {
+            tracing::info!(transitions, "Applied memory state transitions");
+        }
+        Ok(StepResult::States { transitions: transitions as i64 })
+    }
+}
+
+struct StcStep;
+
+impl ConsolidationStep for StcStep {
+    fn name(&self) -> &'static str {
+        "stc"
+    }
+
+    fn label(&self) -> &'static str {
+        "Synaptic capture"
+    }
+
+    /// Synaptic tagging and capture (retroactive importance)
+    fn run(&self, storage: &Storage, _ctx: &StepContext<'_>) -> Result<StepResult> {
+        let captured = storage.apply_synaptic_captures()?.len();
+        if captured > 0 {
+            tracing::info!(captured, "Captured tagged memories");
+        }
+        Ok(StepResult::Stc { captured: captured as i64 })
+    }
+}
+
+struct CrossProjectStep;
+
+impl ConsolidationStep for CrossProjectStep {
+    fn name(&self) -> &'static str {
+        "cross_project"
+    }
+
+    fn label(&self) -> &'static str {
+        "Cross-project learning"
+    }
+
+    /// Persist universal patterns across codebases
+    fn run(&self, storage: &Storage, _ctx: &StepContext<'_>) -> Result<StepResult> {
+        let patterns = storage.learn_cross_project_patterns()?;
+        if patterns > 0 {
+            tracing::info!(patterns, "Updated cross-project patterns");
+        }
+        Ok(StepResult::CrossProject { patterns: patterns as i64 })
+    }
+}
+
+struct HippocampalStep;
+
+impl ConsolidationStep for HippocampalStep {
+    fn name(&self) -> &'static str {
+        "hippocampal"
+    }
+
+    fn label(&self) -> &'static str {
+        "Hippocampal index"
+    }
+
+    fn run(&self, _storage: &Storage, _ctx: &StepContext<'_>) -> Result<StepResult> {
+        let index = crate::neuroscience::hippocampal_index::HippocampalIndex::new();
+        let links_pruned = index.prune_weak_links().unwrap_or(0) as i64;
+        Ok(StepResult::Hippocampal { links_pruned })
+    }
+}
+
+struct ImportanceStep;
+
+impl ConsolidationStep for ImportanceStep {
+    fn name(&self) -> &'static str {
+        "importance"
+    }
+
+    fn label(&self) -> &'static str {
+        "Importance evolution"
+    }
+
+    /// Replay usage, decay stale importance, persist composite importance
+    fn run(&self, storage: &Storage, _ctx: &StepContext<'_>) -> Result<StepResult> {
+        let events_replayed = storage.evolve_importance()?;
+        if events_replayed > 0 {
+            tracing::info!(events = events_replayed, "Replayed usage events into importance");
+        }
+        let scores_updated = storage.update_importance_scores()?;
+        Ok(StepResult::Importance {
+            events_replayed: events_replayed as i64,
+            scores_updated,
+        })
+    }
+}
+
+struct UsageLogStep;
+
+impl ConsolidationStep for UsageLogStep {
+    fn name(&self) -> &'static str {
+        "usage_log"
+    }
+
+    fn label(&self) -> &'static str {
+        "Usage history pruning"
+    }
+
+    fn run(&self, storage: &Storage, _ctx: &StepContext<'_>) -> Result<StepResult> {
+        let (events_pruned, history_pruned) = storage.prune_usage_history()?;
+        Ok(StepResult::UsageLog { events_pruned, history_pruned })
+    }
+}
+
+struct ConnectionsStep;
+
+impl ConsolidationStep for ConnectionsStep {
+    fn name(&self) -> &'static str {
+        "connections"
+    }
+
+    fn label(&self) -> &'static str {
+        "Connection maintenance"
+    }
+
+    /// Decay and prune weak connections
+    fn run(&self, storage: &Storage, ctx: &StepContext<'_>) -> Result<StepResult> {
+        let pruned = storage
+            .prune_weak_connections(ctx.config.connection_prune_threshold)? as i64;
+        Ok(StepResult::Connections { pruned })
+    }
+}
+
+struct FtsOptimizeStep;
+
+impl ConsolidationStep for FtsOptimizeStep {
+    fn name(&self) -> &'static str {
+        "fts_optimize"
+    }
+
+    fn label(&self) -> &'static str {
+        "Search index optimization"
+    }
+
+    fn run(&self, storage: &Storage, _ctx: &StepContext<'_>) -> Result<StepResult> {
+        storage.optimize_search_index()?;
+        Ok(StepResult::FtsOptimize)
+    }
+}
+
+struct AutoPromoteStep;
+
+impl ConsolidationStep for AutoPromoteStep {
+    fn name(&self) -> &'static str {
+        "auto_promote"
+    }
+
+    fn label(&self) -> &'static str {
+        "Auto-promotion"
+    }
+
+    /// Promote memories with 3+ accesses in 24h (frequency-dependent potentiation)
+    fn run(&self, storage: &Storage, _ctx: &StepContext<'_>) -> Result<StepResult> {
+        let promoted = storage.auto_promote_frequent_access()?;
+        Ok(StepResult::AutoPromote { promoted })
+    }
+}
+
+struct RetentionGcStep;
+
+impl ConsolidationStep for RetentionGcStep {
+    fn name(&self) -> &'static str {
+        "retention_gc"
+    }
+
+    fn label(&self) -> &'static str {
+        "Retention target"
+    }
+
+    /// Collect low-retention memories when average retention is below the
+    /// policy target, then save a retention snapshot for trend tracking
+    ///
+    /// A namespaced run measures and collects only that namespace, and skips
+    /// the snapshot so the trend keeps tracking the whole database.
+    fn run(&self, storage: &Storage, ctx: &StepContext<'_>) -> Result<StepResult> {
+        let policy = storage.retention_policy();
+        let stats = storage.get_stats_in(ctx.namespace)?;
+        let (avg_retention, total) = (stats.average_retention, stats.total_nodes);
+        let below_target =
+            storage.count_memories_below_retention_in(ctx.namespace, policy.gc_below)?;
+
+        let mut collected = 0;
+        if policy.enabled && avg_retention < policy.target && below_target > 0 {
+            collected = storage.gc_below_retention_in(
+                ctx.namespace,
+                policy.gc_below,
+                policy.min_age_days,
+            )?;
+            if collected > 0 {
+                tracing::info!(
+                    avg_retention = avg_retention,
+                    target = policy.target,
+                    gc_count = collected,
+                    "Retention target auto-GC: removed {} low-retention memories",
+                    collected
+                );
+            }
+        }
+
+        if ctx.namespace.is_none() {
+            storage.save_retention_snapshot(avg_retention, total, below_target, collected > 0)?;
+        }
+        Ok(StepResult::RetentionGc { avg_retention, below_target, collected })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_pipeline_names_are_unique_and_enabled_by_default() {
+        let names = ConsolidationPipeline::step_names();
+        let unique: BTreeSet<_> = names.iter().collect();
+        assert_eq!(names.len(), unique.len());
+        assert_eq!(names.first(), Some(&"decay"));
+        assert_eq!(names.last(), Some(&"retention_gc"));
+
+        let config = ConsolidationConfig::default();
+        assert!(names.iter().all(|name| config.is_enabled(name)));
+        assert!(config.validate().is_ok());
+    }
+
+    struct FailingStep;
+
+    impl ConsolidationStep for FailingStep {
+        fn name(&self) -> &'static str {
+            "failing"
+        }
+
+        fn label(&self) -> &'static str {
+            "Always fails"
+        }
+
+        fn run(&self, _storage: &Storage, _ctx: &StepContext<'_>) -> Result<StepResult> {
+            Err(StorageError::LockPoisoned("Compression".into()))
+        }
+    }
+
+    struct PanickingStep;
+
+    impl ConsolidationStep for PanickingStep {
+        fn name(&self) -> &'static str {
+            "panicking"
+        }
+
+        fn label(&self) -> &'static str {
+            "Always panics"
+        }
+
+        fn run(&self, _storage: &Storage, _ctx: &StepContext<'_>) -> Result<StepResult> {
+            panic!("index out of bounds")
+        }
+    }
+
+    #[test]
+    fn test_failing_steps_are_isolated_and_timed() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::new(Some(dir.path().join("pipeline.db"))).unwrap();
+        let config = ConsolidationConfig::default();
+        let ctx = StepContext { namespace: None, config: &config };
+
+        let pipeline = ConsolidationPipeline::new(vec![
+            Box::new(DecayStep),
+            Box::new(FailingStep),
+            Box::new(PanickingStep),
+            Box::new(FtsOptimizeStep),
+        ]);
+        let mut reported = Vec::new();
+        let result = pipeline.run(&storage, &ctx, &ConsolidationLimits::default(), &mut |p| reported.push(p.step));
+
+        assert_eq!(reported, vec![1, 2, 3, 4]);
+        assert!(result.steps.contains_key("decay"));
+        assert_eq!(result.steps.get("fts_optimize"), Some(&StepResult::FtsOptimize));
+        let names: Vec<_> = result.step_timings.iter().map(|t| (t.step.as_str(), t.succeeded)).collect();
+        assert_eq!(
+            names,
+            vec![("decay", true), ("failing", false), ("panicking", false), ("fts_optimize", true)]
+        );
+        assert_eq!(result.step_errors.len(), 2);
+        assert_eq!(result.step_errors[0].step, "failing");
+        assert!(result.step_errors[0].error.contains("Compression"));
+        assert_eq!(result.step_errors[1].error, "panicked: index out of bounds");
+
+        // Full runs record the table in the consolidation history
+        storage.run_consolidation_pipeline(&pipeline).unwrap();
+        let history = storage.get_consolidation_history(1).unwrap();
+        assert_eq!(history[0].step_timings.len(), 4);
+        assert_eq!(history[0].step_errors, result.step_errors);
+    }
+
+    struct SlowStep;
+
+    impl ConsolidationStep for SlowStep {
+        fn name(&self) -> &'static str {
+            "slow"
+        }
+
+        fn label(&self) -> &'static str {
+            "Takes a while"
+        }
+
+        fn run(&self, _storage: &Storage, _ctx: &StepContext<'_>) -> Result<StepResult> {
+            std::thread::sleep(std::time::Duration::from_millis(30));
+            Ok(StepResult::FtsOptimize)
+        }
+    }
+
+    #[test]
+    fn test_limits_stop_runs_between_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::new(Some(dir.path().join("pipeline.db"))).unwrap();
+        let config = ConsolidationConfig::default();
+        let ctx = StepContext { namespace: None, config: &config };
+
+        // The step in progress finishes; the ones after the deadline never start
+        let pipeline = ConsolidationPipeline::new(vec![
+            Box::new(SlowStep),
+            Box::new(DecayStep),
+            Box::new(FtsOptimizeStep),
+        ]);
+        let limits = ConsolidationLimits {
+            deadline: Some(std::time::Duration::from_millis(10)),
+            ..Default::default()
+        };
+        let result = pipeline.run(&storage, &ctx, &limits, &mut |_| {});
+        assert_eq!(result.step_timings.len(), 1);
+        assert!(result.steps.contains_key("slow"));
+        assert_eq!(result.stopped_early.as_deref(), Some("deadline"));
+        assert_eq!(result.steps_not_run, vec!["decay", "fts_optimize"]);
+
+        // A cancelled run starts nothing, is recorded, and is not a full run
+        let token = CancellationToken::new();
+        token.clone().cancel();
+        let limits = ConsolidationLimits { cancel: Some(token), ..Default::default() };
+        let result = storage.run_consolidation_with_limits(&limits, &mut |_| {}).unwrap();
+        assert_eq!(result.stopped_early.as_deref(), Some("cancelled"));
+        assert!(result.step_timings.is_empty());
+        assert_eq!(result.steps_not_run, ConsolidationPipeline::step_names());
+
+        let history = storage.get_consolidation_history(1).unwrap();
+        assert_eq!(history[0].stopped_reason.as_deref(), Some("cancelled"));
+        assert_eq!(history[0].steps_not_run, result.steps_not_run);
+        assert!(storage.get_last_consolidation().unwrap().is_none());
+
+        let result = storage.run_consolidation_with_limits(&ConsolidationLimits::default(), &mut |_| {}).unwrap();
+        assert!(result.stopped_early.is_none() && result.steps_not_run.is_empty());
+        assert!(storage.get_last_consolidation().unwrap().is_some());
+    }
+
+    #[test]
+    fn test_config_validation_rejects_unknown_steps_and_bad_params() {
+        let mut config = ConsolidationConfig::default();
+        config.enabled_steps.insert("defrag".to_string());
+        assert!(matches!(
+            config.validate(),
+            Err(StorageError::InvalidInput { field: "enabled_steps", .. })
+        ));
+
+        let config = ConsolidationConfig { dedup_threshold: 0.0, ..Default::default() };
+        assert!(matches!(
+            config.validate(),
+            Err(StorageError::InvalidInput { field: "dedup_threshold", .. })
+        ));
+
+        // Saved configs may omit fields
+        let config: ConsolidationConfig =
+            serde_json::from_str(r#"{"enabledSteps": ["decay", "dedup"]}"#).unwrap();
+        assert!(config.is_enabled("dedup") && !config.is_enabled("compression"));
+        assert_eq!(config.dedup_threshold, 0.85);
+    }
+}
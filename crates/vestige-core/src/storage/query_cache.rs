@@ -0,0 +1,206 @@
+//! Query Embedding Cache
+//!
+//! LRU cache of query embeddings bounded by the bytes its entries occupy
+//! rather than an entry count, so switching to larger embedding dimensions
+//! cannot silently multiply its memory footprint. Keys are normalized
+//! queries scoped to the model that produced the vector; the first lookup
+//! under a different model drops everything cached by the previous one.
+
+use lru::LruCache;
+
+/// Default cache budget (4 MiB, ~4000 entries of 256-dim vectors)
+pub const DEFAULT_QUERY_CACHE_BYTES: usize = 4 * 1024 * 1024;
+
+/// Cache key: (normalized query, HyDE-expanded); entries all belong to `model`
+type QueryCacheKey = (String, bool);
+
+/// Normalize a query for cache lookup: trim, collapse whitespace, lowercase
+///
+/// The embedding model's tokenizer is uncased and whitespace-insensitive,
+/// so queries differing only in these respects embed identically.
+pub fn normalize_query(query: &str) -> String {
+    query.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// Occupancy and hit/miss counters of the query embedding cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryCacheStats {
+    pub entries: usize,
+    /// Bytes held by cached queries and vectors
+    pub bytes: usize,
+    pub capacity_bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl QueryCacheStats {
+    /// Fraction of lookups served from the cache (0.0 before any lookup)
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups > 0 { self.hits as f64 / lookups as f64 } else { 0.0 }
+    }
+}
+
+/// Byte-bounded LRU cache of query embeddings
+#[cfg_attr(not(feature = "embeddings"), allow(dead_code))]
+#[derive(Debug)]
+pub(crate) struct QueryEmbeddingCache {
+    entries: LruCache<QueryCacheKey, Vec<f32>>,
+    /// Model whose vectors are currently cached
+    model: Option<String>,
+    bytes: usize,
+    capacity_bytes: usize,
+    hits: u64,
+    misses: u64,
+}
+
+#[cfg_attr(not(feature = "embeddings"), allow(dead_code))]
+impl QueryEmbeddingCache {
+    pub(crate) fn new(capacity_bytes: usize) -> Self {
+        Self {
+            entries: LruCache::unbounded(),
+            model: None,
+            bytes: 0,
+            capacity_bytes,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Bytes charged for an entry: its key text plus its vector
+    fn entry_bytes(query: &str, vector: &[f32]) -> usize {
+        query.len() + std::mem::size_of_val(vector)
+    }
+
+    /// Drop everything cached under another model before serving `model`
+    fn switch_model(&mut self, model: &str) {
+        if self.model.as_deref() != Some(model) {
+            self.entries.clear();
+            self.bytes = 0;
+            self.model = Some(model.to_string());
+        }
+    }
+
+    /// Look up the vector `model` produced for `query`, counting the hit or miss
+    pub(crate) fn get(&mut self, model: &str, query: &str, expanded: bool) -> Option<Vec<f32>> {
+        self.switch_model(model);
+        let cached = self.entries.get(&(normalize_query(query), expanded)).cloned();
+        if cached.is_some() {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+        cached
+    }
+
+    /// Cache the vector `model` produced for `query`, evicting least recently
+    /// used entries to stay within capacity
+    ///
+    /// A vector too large to fit on its own is not cached.
+    pub(crate) fn put(&mut self, model: &str, query: &str, expanded: bool, vector: Vec<f32>) {
+        self.switch_model(model);
+        let key = normalize_query(query);
+        let size = Self::entry_bytes(&key, &vector);
+        if size > self.capacity_bytes {
+            return;
+        }
+        if let Some(old) = self.entries.put((key.clone(), expanded), vector) {
+            self.bytes -= Self::entry_bytes(&key, &old);
+        }
+        self.bytes += size;
+        self.evict_to(self.capacity_bytes);
+    }
+
+    fn evict_to(&mut self, capacity_bytes: usize) {
+        while self.bytes > capacity_bytes {
+            match self.entries.pop_lru() {
+                Some(((query, _), vector)) => self.bytes -= Self::entry_bytes(&query, &vector),
+                None => break,
+            }
+        }
+    }
+
+    /// Change the byte budget, evicting entries that no longer fit
+    pub(crate) fn set_capacity(&mut self, capacity_bytes: usize) {
+        self.capacity_bytes = capacity_bytes;
+        self.evict_to(capacity_bytes);
+    }
+
+    /// Drop all entries; hit/miss counters are kept
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.bytes = 0;
+    }
+
+    pub(crate) fn stats(&self) -> QueryCacheStats {
+        QueryCacheStats {
+            entries: self.entries.len(),
+            bytes: self.bytes,
+            capacity_bytes: self.capacity_bytes,
+            hits: self.hits,
+            misses: self.misses,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalized_queries_share_entries() {
+        assert_eq!(normalize_query("  Rust   Ownership\trules \n"), "rust ownership rules");
+
+        let mut cache = QueryEmbeddingCache::new(DEFAULT_QUERY_CACHE_BYTES);
+        cache.put("model", "Rust ownership", false, vec![1.0, 0.0]);
+        assert_eq!(cache.get("model", "  rust   OWNERSHIP ", false), Some(vec![1.0, 0.0]));
+        assert!(cache.get("model", "rust ownership", true).is_none());
+
+        // Re-caching the same normalized query replaces rather than duplicates
+        cache.put("model", "RUST ownership", false, vec![0.0, 1.0]);
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 1);
+        assert_eq!(stats.bytes, "rust ownership".len() + 8);
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+    }
+
+    #[test]
+    fn test_model_switch_invalidates() {
+        let mut cache = QueryEmbeddingCache::new(DEFAULT_QUERY_CACHE_BYTES);
+        cache.put("old-model", "query", false, vec![1.0, 0.0]);
+        cache.put("old-model", "other", false, vec![0.0, 1.0]);
+
+        assert!(cache.get("new-model", "query", false).is_none());
+        assert_eq!(cache.stats().entries, 0);
+        assert_eq!(cache.stats().bytes, 0);
+
+        // Switching back does not resurrect the dropped vectors
+        assert!(cache.get("old-model", "query", false).is_none());
+    }
+
+    #[test]
+    fn test_capacity_is_measured_in_bytes() {
+        // Each entry is 1 byte of query plus 16 bytes of vector
+        let mut cache = QueryEmbeddingCache::new(40);
+        for query in ["a", "b", "c"] {
+            cache.put("model", query, false, vec![0.0; 4]);
+        }
+        assert_eq!(cache.stats().entries, 2);
+        assert_eq!(cache.stats().bytes, 34);
+        assert!(cache.get("model", "a", false).is_none(), "least recently used entry evicted");
+
+        // Oversized vectors are never cached
+        cache.put("model", "d", false, vec![0.0; 16]);
+        assert!(cache.get("model", "d", false).is_none());
+        assert_eq!(cache.stats().entries, 2);
+
+        cache.set_capacity(17);
+        assert_eq!(cache.stats().entries, 1);
+        assert!(cache.get("model", "c", false).is_some());
+
+        cache.clear();
+        assert_eq!(cache.stats().bytes, 0);
+        assert_eq!(cache.stats().capacity_bytes, 17);
+    }
+}
@@ -0,0 +1,122 @@
+//! Deferred Neighbor Reinforcement
+//!
+//! Accessing a memory also nudges its semantic neighbors, which takes a
+//! vector search and a handful of writes per accessed memory. Doing that
+//! inline made every recall pay for it, so accesses are queued instead and
+//! the queue is drained at most once per
+//! `ReinforcementConfig::neighbor_flush_secs` (and at the start of every
+//! consolidation run): one vector search per distinct memory, one update per
+//! distinct neighbor. A memory accessed several times between drains
+//! reinforces its neighbors as many times over, so the aggregate boost
+//! matches reinforcing on every access, up to the hourly cap each neighbor
+//! is held to.
+//!
+//! The queue lives only in memory. Dropping the `Storage` drains it, but a
+//! crash or kill loses the accesses queued since the last drain (their own
+//! boosts and access log rows are already written; only the neighbor nudges
+//! are lost), and every restart starts each neighbor's hourly cap afresh.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+/// Accesses waiting to reinforce their neighbors, plus the neighbor boosts
+/// each memory has received in its current hour
+#[derive(Debug, Default)]
+pub(crate) struct NeighborQueue {
+    /// Accessed memory → accesses since the last drain
+    pending: HashMap<String, u32>,
+    last_drain: Option<DateTime<Utc>>,
+    /// Neighbor → (start of its hour window, retrieval boost received in it)
+    received: HashMap<String, (DateTime<Utc>, f64)>,
+}
+
+#[cfg_attr(not(all(feature = "embeddings", feature = "vector-search")), allow(dead_code))]
+impl NeighborQueue {
+    /// Queue one access of `id`
+    pub(crate) fn push(&mut self, id: &str) {
+        *self.pending.entry(id.to_string()).or_insert(0) += 1;
+    }
+
+    /// Accesses waiting for the next drain
+    pub(crate) fn pending(&self) -> usize {
+        self.pending.values().map(|&n| n as usize).sum()
+    }
+
+    /// Whether queued accesses should be drained now, at most once per
+    /// `interval`
+    pub(crate) fn is_due(&self, now: DateTime<Utc>, interval: Duration) -> bool {
+        !self.pending.is_empty() && self.last_drain.is_none_or(|last| now - last >= interval)
+    }
+
+    /// Take every queued access as (memory, access count)
+    pub(crate) fn drain(&mut self, now: DateTime<Utc>) -> Vec<(String, u32)> {
+        self.last_drain = Some(now);
+        self.pending.drain().collect()
+    }
+
+    /// How much of a retrieval boost `neighbor` may still receive this hour
+    /// under `hourly_cap`, recording what it is granted
+    pub(crate) fn grant(&mut self, neighbor: &str, boost: f64, hourly_cap: f64, now: DateTime<Utc>) -> f64 {
+        let (window, received) = self
+            .received
+            .entry(neighbor.to_string())
+            .or_insert((now, 0.0));
+        if now - *window >= Duration::hours(1) {
+            *window = now;
+            *received = 0.0;
+        }
+        let granted = boost.min(hourly_cap - *received).max(0.0);
+        *received += granted;
+        granted
+    }
+
+    /// Forget hour windows that have ended
+    pub(crate) fn prune(&mut self, now: DateTime<Utc>) {
+        self.received.retain(|_, (window, _)| now - *window < Duration::hours(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_counts_repeat_accesses_and_throttles_drains() {
+        let start = Utc::now();
+        let mut queue = NeighborQueue::default();
+        assert!(!queue.is_due(start, Duration::seconds(30)));
+
+        queue.push("a");
+        queue.push("a");
+        queue.push("b");
+        assert_eq!(queue.pending(), 3);
+        assert!(queue.is_due(start, Duration::seconds(30)));
+
+        let mut drained = queue.drain(start);
+        drained.sort();
+        assert_eq!(drained, vec![("a".to_string(), 2), ("b".to_string(), 1)]);
+        assert_eq!(queue.pending(), 0);
+
+        queue.push("a");
+        assert!(!queue.is_due(start + Duration::seconds(10), Duration::seconds(30)));
+        assert!(queue.is_due(start + Duration::seconds(30), Duration::seconds(30)));
+        assert!(queue.is_due(start, Duration::zero()));
+    }
+
+    #[test]
+    fn test_grant_caps_boosts_per_hour() {
+        let start = Utc::now();
+        let mut queue = NeighborQueue::default();
+        assert_eq!(queue.grant("n", 0.06, 0.1, start), 0.06);
+        assert!((queue.grant("n", 0.06, 0.1, start) - 0.04).abs() < 1e-12);
+        assert_eq!(queue.grant("n", 0.06, 0.1, start + Duration::minutes(59)), 0.0);
+        // Other memories have their own budget
+        assert_eq!(queue.grant("m", 0.06, 0.1, start), 0.06);
+
+        // A new hour starts a new budget
+        assert_eq!(queue.grant("n", 0.06, 0.1, start + Duration::minutes(61)), 0.06);
+        queue.prune(start + Duration::minutes(65));
+        assert_eq!(queue.received.len(), 1);
+    }
+}
@@ -2,33 +2,102 @@
 //!
 //! Core storage layer with integrated embeddings and vector search.
 
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Duration, Timelike, Utc};
 use directories::ProjectDirs;
-use lru::LruCache;
 use rusqlite::{params, Connection, OptionalExtension};
-use std::num::NonZeroUsize;
-use std::path::PathBuf;
-use std::sync::Mutex;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use uuid::Uuid;
 
 use crate::fsrs::{
-    retrievability_with_decay, DEFAULT_DECAY,
-    FSRSScheduler, FSRSState, LearningState, Rating,
+    next_interval, retrievability_with_decay, DEFAULT_DECAY, DEFAULT_RETENTION, MIN_STABILITY,
+    FSRSParameters, FSRSScheduler, FSRSState, LearningState, Rating,
 };
+use crate::clock::{system_clock, Clock};
 use crate::memory::{
-    ConsolidationResult, EmbeddingResult, IngestInput, KnowledgeNode, MatchType, MemoryStats,
-    RecallInput, SearchMode, SearchResult, SimilarityResult,
+    ConsolidationProgress, ConsolidationResult, EdgeType, EmbeddingResult, IngestInput, KnowledgeEdge, KnowledgeNode,
+    DuplicatePolicy, MatchType, MemoryStats, MemorySystem, NodeType, QueryExpansionMode, RecallInput, ScoreBreakdown, SearchMode,
+    SearchResult, SearchSyntax, SimilarityResult, StepError, StepResult, StepTiming,
 };
-use crate::search::sanitize_fts5_query;
+use crate::advanced::adaptive_embedding::EmbeddingStrategy;
+use crate::advanced::auto_tag::{self, TagSuggestion};
+use crate::advanced::session_packet::{self, SessionItem, SessionOverview};
+use crate::advanced::importance::{ImportanceScore, ImportanceTracker, UsageEvent};
+use crate::advanced::chains::{
+    ChainStep, Connection as ChainConnection, ConnectionType, MemoryChainBuilder, MemoryNode,
+    ReasoningChain,
+};
+use crate::advanced::cross_project::{ApplicableKnowledge, ProjectContext, UniversalPattern};
+use crate::advanced::speculative::{PredictedMemory, PredictionContext, PredictionTrigger};
+use crate::advanced::intent::{
+    DetectedIntent, IntentDetectionResult, IntentDetector, UserAction, INTENT_WINDOW_MINUTES,
+};
+use crate::neuroscience::spreading_activation::{
+    ActivatedMemory, ActivationConfig, ActivationNetwork, LinkType,
+};
+use crate::advanced::reconsolidation::{
+    AccessContext, AccessTrigger, MemorySnapshot, Modification, ReconsolidatedMemory,
+    ReconsolidationManager, ReconsolidationStats,
+};
+use crate::consolidation::{DreamEngine, DreamPhase, FourPhaseDreamResult};
+use crate::neuroscience::emotional_memory::EmotionalMemory;
+use crate::neuroscience::importance_signals::{ImportanceSignals, OutcomeType};
+use crate::neuroscience::memory_states::{
+    CompetitionCandidate, CompetitionConfig, CompetitionEvent, CompetitionManager,
+    MemoryLifecycle, MemoryState, StateTransition, StateTransitionReason, StateUpdateService,
+};
+use crate::neuroscience::synaptic_tagging::{
+    CapturedMemory, ImportanceEvent, ImportanceEventType, SynapticTag, SynapticTaggingConfig,
+    SynapticTaggingSystem,
+};
+use crate::search::sanitize_fts5_query_with;
+
+use super::backup::{
+    backup_file_name, backups_to_prune, default_backup_dir, list_backups_in, BackupConfig,
+    BackupInfo,
+};
+use super::metrics::{MetricsSnapshot, SpanTimer, StorageMetrics};
+use super::diff::{content_hash, normalize_whitespace, normalized_content_hash, unified_diff};
+use super::pipeline::{ConsolidationConfig, ConsolidationLimits, ConsolidationPipeline, StepContext};
+use super::namespace::NamespacedStorage;
+use super::query_cache::QueryCacheStats;
+use super::reinforcement::NeighborQueue;
+#[cfg(feature = "embeddings")]
+use super::query_cache::{QueryEmbeddingCache, DEFAULT_QUERY_CACHE_BYTES};
 
 #[cfg(feature = "embeddings")]
-use crate::embeddings::{matryoshka_truncate, Embedding, EmbeddingService, EMBEDDING_DIMENSIONS};
+use crate::embeddings::{matryoshka_truncate_to, Embedding, EmbeddingBackend, EmbeddingService};
 
 #[cfg(feature = "vector-search")]
-use crate::search::{linear_combination, VectorIndex};
+use crate::search::{HybridSearchConfig, VectorIndex, VectorIndexConfig, VectorIndexStats};
+
+#[cfg(all(feature = "embeddings", feature = "vector-search"))]
+use crate::search::FusionMethod;
+
+#[cfg(all(feature = "embeddings", feature = "vector-search"))]
+use crate::search::hyde::{self, QueryExpansion};
+
+#[cfg(all(feature = "embeddings", feature = "vector-search"))]
+use crate::search::TemporalSearcher;
+
+#[cfg(all(feature = "embeddings", feature = "vector-search"))]
+use crate::advanced::adaptive_embedding::{AdaptiveEmbedder, ContentType};
+
+#[cfg(all(feature = "embeddings", feature = "vector-search"))]
+use crate::embeddings::CodeEmbedding;
+
+#[cfg(all(feature = "embeddings", feature = "vector-search"))]
+use crate::advanced::prediction_error::{
+    cosine_similarity, CandidateMemory, GateDecision, PredictionErrorConfig, PredictionErrorGate,
+    UpdateType,
+};
 
 #[cfg(all(feature = "embeddings", feature = "vector-search"))]
-use crate::search::hyde;
+use crate::advanced::contradiction::{
+    detect_contradiction, Contradiction, ContradictionKind, ContradictionScorer,
+};
 
 // ============================================================================
 // ERROR TYPES
@@ -53,6 +122,43 @@ pub enum StorageError {
     /// Initialization error
     #[error("Initialization error: {0}")]
     Init(String),
+    /// Embedding model not loaded, or it failed to embed
+    #[error("Embeddings unavailable: {0}")]
+    EmbeddingUnavailable(String),
+    /// Vector index creation, search or update failed
+    #[error("Vector index error: {0}")]
+    VectorIndex(String),
+    /// A storage lock (named) is unusable because a holder panicked
+    #[error("{0} lock poisoned")]
+    LockPoisoned(String),
+    /// Ingest found identical content and `DuplicatePolicy::Error` was set
+    #[error("Duplicate of existing memory {existing_id}")]
+    Duplicate { existing_id: String },
+    /// A caller-supplied argument was rejected
+    #[error("Invalid {field}: {reason}")]
+    InvalidInput {
+        field: &'static str,
+        reason: String,
+    },
+    /// Configuration missing or unusable for the requested operation
+    #[error("Configuration error: {0}")]
+    Config(String),
+    /// A memory changed underneath a merge or supersede
+    #[error("Conflict: {0}")]
+    Conflict(String),
+    /// Operation needs SQLCipher, but the `encryption` feature is disabled
+    #[error("Encryption unavailable: build with the `encryption` feature")]
+    EncryptionDisabled,
+    /// Database was migrated by a newer release than this one
+    #[error(
+        "Database schema v{found} (written by vestige {written_by}) is newer than v{supported} \
+         supported by this build; upgrade vestige to open it"
+    )]
+    SchemaTooNew {
+        found: u32,
+        supported: u32,
+        written_by: String,
+    },
 }
 
 /// Storage result type
@@ -68,18 +174,181 @@ pub struct SmartIngestResult {
     pub node: KnowledgeNode,
     /// ID of superseded memory (if any)
     pub superseded_id: Option<String>,
+    /// Existing memories this one probably contradicts (linked by
+    /// `Contradiction` edges); the caller may want to supersede them
+    #[serde(default)]
+    pub contradicts: Vec<String>,
     /// Similarity to closest existing memory (0.0 - 1.0)
     pub similarity: Option<f32>,
     /// Prediction error (1.0 - similarity)
     pub prediction_error: Option<f32>,
     /// Human-readable explanation of the decision
     pub reason: String,
+    /// Set when the update landed inside the target's reconsolidation window
+    pub reconsolidation: Option<ReconsolidatedMemory>,
+}
+
+/// Result of a plain ingest
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestOutcome {
+    /// The new node, or the existing one its content duplicates
+    pub node: KnowledgeNode,
+    /// True when `DuplicatePolicy::ReturnExisting` returned an existing memory
+    pub returned_existing: bool,
+}
+
+/// Result of moving the vector index to another Matryoshka operating point
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetruncateReport {
+    /// Index dimensions before the change
+    pub previous_dimensions: usize,
+    /// Index dimensions now in use
+    pub dimensions: usize,
+    /// Stored vectors re-truncated to the new dimensions
+    pub retruncated: usize,
+    /// Embeddings too short for the new dimensions, dropped until re-embedded
+    pub needs_reembedding: usize,
+    /// Vectors in the rebuilt HNSW index
+    pub indexed: usize,
+    /// Wall-clock time taken
+    pub duration_ms: u64,
+}
+
+/// Prediction error gate verdict for a smart ingest, computed before any write
+#[cfg(all(feature = "embeddings", feature = "vector-search"))]
+struct GatePlan {
+    gate: PredictionErrorGate,
+    decision: GateDecision,
+    /// Similar memories the content probably contradicts, linked not merged
+    conflicts: Vec<(String, Contradiction)>,
+    /// Best candidate, when it is still inside its reconsolidation window
+    labile_target: Option<CandidateMemory>,
 }
 
 // ============================================================================
 // STORAGE
 // ============================================================================
 
+/// Promotion boost: +0.2 retrieval, +0.1 retention, 1.5x stability, each
+/// scaled by the feedback strength (?1 = now, ?2 = id, ?3 = strength 0..=1)
+const PROMOTE_SQL: &str = "UPDATE knowledge_nodes SET
+        last_accessed = ?1,
+        retrieval_strength = MIN(1.0, retrieval_strength + 0.20 * ?3),
+        retention_strength = MIN(1.0, retention_strength + 0.10 * ?3),
+        stability = stability * (1.0 + 0.5 * ?3)
+    WHERE id = ?2";
+
+/// `created_by` / `change_source` for supersede edges and revisions
+const SUPERSEDE_SOURCE: &str = "smart_ingest:supersede";
+
+/// Demotion penalty: -0.3 retrieval, -0.15 retention, halved stability, each
+/// scaled by the feedback strength (?1 = now, ?2 = id, ?3 = strength 0..=1)
+const DEMOTE_SQL: &str = "UPDATE knowledge_nodes SET
+        last_accessed = ?1,
+        retrieval_strength = MAX(0.05, retrieval_strength - 0.30 * ?3),
+        retention_strength = MAX(0.05, retention_strength - 0.15 * ?3),
+        stability = stability * (1.0 - 0.5 * ?3)
+    WHERE id = ?2";
+
+/// Outcome reward: +0.25 retrieval, +0.15 retention, 1.75x stability and
+/// +0.2 importance at full reward, above a plain promotion
+/// (?1 = now, ?2 = id, ?3 = reward 0..=1)
+const OUTCOME_REWARD_SQL: &str = "UPDATE knowledge_nodes SET
+        last_accessed = ?1,
+        retrieval_strength = MIN(1.0, retrieval_strength + 0.25 * ?3),
+        retention_strength = MIN(1.0, retention_strength + 0.15 * ?3),
+        stability = stability * (1.0 + 0.75 * ?3),
+        importance = MIN(1.0, COALESCE(importance, 0.5) + 0.20 * ?3)
+    WHERE id = ?2";
+
+/// Outcome penalty on top of `DEMOTE_SQL`: -0.2 importance at full penalty,
+/// and the memory falls due for review (?1 = now, ?2 = id, ?3 = penalty 0..=1)
+const OUTCOME_PENALTY_SQL: &str = "UPDATE knowledge_nodes SET
+        importance = MAX(0.0, COALESCE(importance, 0.5) - 0.20 * ?3),
+        next_review = ?1
+    WHERE id = ?2";
+
+/// Node types the bundled tools write, accepted alongside the built-in `NodeType`s
+const EXTRA_NODE_TYPES: &[&str] = &["person", "place", "note", "pattern", "decision", "bug_fix"];
+
+/// `meta` key holding the vector index's Matryoshka dimensions
+#[cfg(all(feature = "embeddings", feature = "vector-search"))]
+const INDEX_DIMENSIONS_META_KEY: &str = "index_dimensions";
+
+/// Environment variables choosing the Matryoshka dimensions, by precedence
+#[cfg(all(feature = "embeddings", feature = "vector-search"))]
+const EMBEDDING_DIMS_ENV_VARS: &[&str] = &["VESTIGE_EMBEDDING_DIMS", "VESTIGE_INDEX_DIMENSIONS"];
+
+/// `meta` key holding the embedding backfill job state
+const BACKFILL_META_KEY: &str = "embedding_backfill";
+
+/// `meta` key holding the saved `ConsolidationConfig`
+const CONSOLIDATION_CONFIG_META_KEY: &str = "consolidation_config";
+
+/// `meta` key holding the mutation count since the last consolidation
+const MUTATIONS_META_KEY: &str = "mutations_since_consolidation";
+
+/// The mutation count is written through on the first mutation after a
+/// run (so a restart still knows the store is dirty) and every this many
+const MUTATION_PERSIST_EVERY: u64 = 32;
+
+/// Default for `max_content_bytes` (override with `VESTIGE_MAX_CONTENT_BYTES`)
+const DEFAULT_MAX_CONTENT_BYTES: usize = 100 * 1024;
+
+/// `meta` key holding when the next dedup pass starts looking for changed
+/// memories (suffixed with `:<namespace>` for namespace-scoped passes)
+#[cfg(all(feature = "embeddings", feature = "vector-search"))]
+const DEDUP_CURSOR_META_KEY: &str = "dedup_cursor";
+
+/// Nearest neighbors checked for duplicates around each changed memory
+#[cfg(all(feature = "embeddings", feature = "vector-search"))]
+const DEDUP_NEIGHBORS: usize = 10;
+
+/// How far one `scan_duplicate_clusters` pass got
+#[cfg(all(feature = "embeddings", feature = "vector-search"))]
+#[derive(Debug, Default)]
+struct DedupScan {
+    /// Memories whose neighbors were checked
+    examined: i64,
+    /// Duplicate clusters found
+    clusters: i64,
+    /// Change time of the first memory left unexamined when the deadline passed
+    resume_from: Option<String>,
+}
+
+/// What one auto-dedup pass examined and merged
+#[cfg(all(feature = "embeddings", feature = "vector-search"))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DedupOutcome {
+    /// Memories whose neighborhoods were checked for duplicates
+    pub clusters_examined: i64,
+    /// Duplicate clusters merged into one memory each
+    pub clusters_merged: i64,
+    /// Memories merged away
+    pub merged: i64,
+    /// Whether the time budget ran out before every changed memory was checked
+    pub budget_exhausted: bool,
+}
+
+/// How `Storage::with_config` opens the database
+#[derive(Debug, Clone, Default)]
+pub struct StorageConfig {
+    /// Database file; `None` uses the platform data directory
+    pub db_path: Option<PathBuf>,
+    /// Keep the database in memory instead of `db_path`; it is gone once
+    /// the `Storage` is dropped
+    pub in_memory: bool,
+    /// Never load an embedding model: embedding generation is skipped,
+    /// semantic search returns `EmbeddingUnavailable` and hybrid search and
+    /// smart ingest fall back to their keyword-only behaviour
+    pub disable_embeddings: bool,
+    /// Time source for decay, scheduling, memory states and consolidation;
+    /// `None` uses the system clock
+    pub clock: Option<Arc<dyn Clock>>,
+}
+
 /// Main storage struct with integrated embedding and vector search
 ///
 /// Uses separate reader/writer connections for interior mutability.
@@ -93,22 +362,55 @@ pub struct Storage {
     embedding_service: EmbeddingService,
     #[cfg(feature = "vector-search")]
     vector_index: Mutex<VectorIndex>,
-    /// LRU cache for query embeddings to avoid re-embedding repeated queries
+    /// Byte-bounded LRU cache for query embeddings to avoid re-embedding
+    /// repeated queries, keyed by (model name, normalized query, expanded) so
+    /// vectors never outlive their model and HyDE centroids never stand in
+    /// for literal query embeddings
     #[cfg(feature = "embeddings")]
-    query_cache: Mutex<LruCache<String, Vec<f32>>>,
+    query_cache: Mutex<QueryEmbeddingCache>,
+    /// Labile windows opened by retrieval (Nader reconsolidation)
+    reconsolidation: Mutex<ReconsolidationManager>,
+    /// Intent recorded by `detect_intent(.., persist = true)`, biasing recall
+    session_intent: Mutex<Option<IntentDetectionResult>>,
+    /// Retention target and auto-GC policy used by consolidation
+    retention_policy: Mutex<RetentionPolicy>,
+    /// Whether and how `recall` suppresses competitors of its top result
+    retrieval_forgetting: Mutex<RetrievalForgettingPolicy>,
+    /// Retrieval competitions run by `recall`, for bounding repeat suppression
+    competition: Mutex<CompetitionManager>,
+    /// Boosts `strengthen_on_access` gives an accessed memory and its neighbors
+    reinforcement: Mutex<ReinforcementConfig>,
+    /// Accesses waiting to reinforce their neighbors
+    neighbor_queue: Mutex<NeighborQueue>,
+    /// Node types accepted by ingest on top of the built-in `NodeType`s
+    custom_node_types: Mutex<BTreeSet<String>>,
+    /// Largest content, in bytes, ingest accepts
+    max_content_bytes: AtomicUsize,
+    /// Ingests, updates, deletes, reviews and feedback since the last
+    /// consolidation, persisted periodically in `meta`
+    mutations_since_consolidation: AtomicU64,
+    /// Source of "now" for every time-dependent read and write
+    clock: Arc<dyn Clock>,
+    /// Whether the database lives in a memdb database instead of a file
+    in_memory: bool,
+    /// Search, ingest, embedding, lock and consolidation counters
+    metrics: StorageMetrics,
+    /// Optional model-based contradiction check used by smart_ingest
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    contradiction_scorer: Mutex<Option<std::sync::Arc<dyn ContradictionScorer>>>,
+    /// SQLCipher key the connections are currently opened with
+    #[cfg(feature = "encryption")]
+    encryption_key: Mutex<Option<String>>,
 }
 
 impl Storage {
     /// Apply PRAGMAs and optional encryption to a connection
-    fn configure_connection(conn: &Connection) -> Result<()> {
+    #[cfg_attr(not(feature = "encryption"), allow(unused_variables))]
+    fn configure_connection(conn: &Connection, key: Option<&str>) -> Result<()> {
         // Apply encryption key if SQLCipher is enabled and key is provided
         #[cfg(feature = "encryption")]
-        {
-            if let Ok(key) = std::env::var("VESTIGE_ENCRYPTION_KEY") {
-                if !key.is_empty() {
-                    conn.pragma_update(None, "key", &key)?;
-                }
-            }
+        if let Some(key) = key {
+            conn.pragma_update(None, "key", key)?;
         }
 
         // Configure SQLite for performance
@@ -127,13 +429,134 @@ impl Storage {
         Ok(())
     }
 
+    /// Non-empty VESTIGE_ENCRYPTION_KEY, used when the `encryption` feature is on
+    fn encryption_key_from_env() -> Option<String> {
+        std::env::var("VESTIGE_ENCRYPTION_KEY").ok().filter(|k| !k.is_empty())
+    }
+
     /// Create new storage instance
     pub fn new(db_path: Option<PathBuf>) -> Result<Self> {
+        Self::open(
+            db_path,
+            Self::encryption_key_from_env(),
+            #[cfg(feature = "embeddings")]
+            EmbeddingService::new(),
+        )
+    }
+
+    /// Create a storage instance that embeds with `embedding_service`
+    ///
+    /// Lets tests and offline CI inject `EmbeddingService::mock()` so
+    /// smart ingest, dedup and hybrid search run without the local model.
+    #[cfg(feature = "embeddings")]
+    pub fn with_embedding_service(
+        db_path: Option<PathBuf>,
+        embedding_service: EmbeddingService,
+    ) -> Result<Self> {
+        Self::open(db_path, Self::encryption_key_from_env(), embedding_service)
+    }
+
+    /// Create a storage instance embedding through a custom backend
+    ///
+    /// Opening fails with `StorageError::Config` when the database's vector
+    /// index was built at dimensions the backend cannot produce.
+    #[cfg(feature = "embeddings")]
+    pub fn with_embedding_backend(
+        db_path: Option<PathBuf>,
+        backend: Box<dyn EmbeddingBackend>,
+    ) -> Result<Self> {
+        Self::with_embedding_service(db_path, EmbeddingService::with_backend(backend))
+    }
+
+    /// Create a storage instance encrypted with an explicit SQLCipher key
+    /// instead of VESTIGE_ENCRYPTION_KEY
+    #[cfg(feature = "encryption")]
+    pub fn new_with_key(db_path: Option<PathBuf>, key: &str) -> Result<Self> {
+        Self::open(
+            db_path,
+            Some(key.to_string()).filter(|k| !k.is_empty()),
+            #[cfg(feature = "embeddings")]
+            EmbeddingService::new(),
+        )
+    }
+
+    /// Open storage as described by `config`
+    ///
+    /// In-memory databases are never encrypted.
+    pub fn with_config(config: StorageConfig) -> Result<Self> {
+        let (db_path, key) = if config.in_memory {
+            // A uniquely named memdb database, so the reader and writer
+            // connections see the same data and other instances see none of it.
+            // Unlike shared-cache, memdb reports lock conflicts as SQLITE_BUSY,
+            // which busy_timeout retries, so the reader never reads uncommitted data
+            let uri = format!("file:/vestige-{}?vfs=memdb", Uuid::new_v4());
+            (Some(PathBuf::from(uri)), None)
+        } else {
+            (config.db_path, Self::encryption_key_from_env())
+        };
+        let mut storage = Self::open(
+            db_path,
+            key,
+            #[cfg(feature = "embeddings")]
+            if config.disable_embeddings {
+                EmbeddingService::disabled()
+            } else {
+                EmbeddingService::new()
+            },
+        )?;
+        storage.in_memory = config.in_memory;
+        if let Some(clock) = config.clock {
+            storage.clock = clock;
+        }
+        Ok(storage)
+    }
+
+    /// The clock this storage reads the current time from
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        Arc::clone(&self.clock)
+    }
+
+    /// Current time according to the storage's clock
+    pub(crate) fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
+    /// Counters accumulated since this storage was opened
+    pub fn metrics_snapshot(&self) -> MetricsSnapshot {
+        self.metrics.snapshot()
+    }
+
+    /// Lock the writer connection, counting slow waits
+    fn lock_writer(&self) -> Result<MutexGuard<'_, Connection>> {
+        self.metrics.lock(&self.writer, "Writer")
+    }
+
+    /// Lock the reader connection, counting slow waits
+    fn lock_reader(&self) -> Result<MutexGuard<'_, Connection>> {
+        self.metrics.lock(&self.reader, "Reader")
+    }
+
+    /// Storage in a private in-memory database, for tests and scratch use
+    ///
+    /// Embeds like `new`; combine `in_memory` with `disable_embeddings` in a
+    /// `StorageConfig` to keep the model out of it.
+    pub fn new_in_memory() -> Result<Self> {
+        Self::with_config(StorageConfig {
+            in_memory: true,
+            ..Default::default()
+        })
+    }
+
+    fn open(
+        db_path: Option<PathBuf>,
+        key: Option<String>,
+        #[cfg(feature = "embeddings")] embedding_service: EmbeddingService,
+    ) -> Result<Self> {
         let path = match db_path {
             Some(p) => p,
             None => {
                 let proj_dirs = ProjectDirs::from("com", "vestige", "core").ok_or_else(|| {
-                    StorageError::Init("Could not determine project directories".to_string())
+                    StorageError::Config("Could not determine project directories".to_string())
                 })?;
 
                 let data_dir = proj_dirs.data_dir();
@@ -160,152 +583,429 @@ impl Storage {
             let _ = std::fs::set_permissions(&path, perms);
         }
 
-        Self::configure_connection(&writer_conn)?;
+        Self::configure_connection(&writer_conn, key.as_deref())?;
 
-        // Apply migrations on writer only
+        // Apply migrations on writer only, refusing schemas from newer releases
+        Self::check_schema_supported(&writer_conn)?;
         super::migrations::apply_migrations(&writer_conn)?;
+        let fsrs_params = Self::load_fsrs_parameters(&writer_conn)?.unwrap_or_default();
+        let mutations = Self::load_mutation_count(&writer_conn)?;
 
         // Open reader connection to same path
         let reader_conn = Connection::open(&path)?;
-        Self::configure_connection(&reader_conn)?;
+        Self::configure_connection(&reader_conn, key.as_deref())?;
 
-        #[cfg(feature = "embeddings")]
-        let embedding_service = EmbeddingService::new();
+        #[cfg(feature = "vector-search")]
+        #[cfg_attr(not(feature = "embeddings"), allow(unused_mut))]
+        let mut index_config = Self::vector_index_config_from_env();
+        #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+        let index_dimensions = {
+            let dimensions = Self::index_dimensions_setting(&writer_conn, &embedding_service)?;
+            let foreign = Self::count_foreign_embeddings(&writer_conn, &embedding_service)?;
+            if foreign > 0 {
+                tracing::warn!(
+                    foreign,
+                    model = embedding_service.model_name(),
+                    expected = embedding_service.full_dimensions(),
+                    "Stored embeddings do not match the embedding backend's dimensions; re-embed them"
+                );
+            }
+            embedding_service
+                .set_dimensions(dimensions)
+                .map_err(|e| StorageError::Config(e.to_string()))?;
+            index_config.dimensions = dimensions;
+            dimensions
+        };
 
         #[cfg(feature = "vector-search")]
-        let vector_index = VectorIndex::new()
-            .map_err(|e| StorageError::Init(format!("Failed to create vector index: {}", e)))?;
+        let vector_index = VectorIndex::with_config(index_config)
+            .map_err(|e| StorageError::VectorIndex(format!("Failed to create vector index: {}", e)))?;
 
-        // Initialize LRU cache for query embeddings (capacity: 100 queries)
-        // SAFETY: 100 is always non-zero, this cannot fail
         #[cfg(feature = "embeddings")]
-        let query_cache = Mutex::new(LruCache::new(
-            NonZeroUsize::new(100).expect("100 is non-zero"),
-        ));
+        let query_cache = Mutex::new(QueryEmbeddingCache::new(Self::query_cache_bytes_from_env()));
 
         let storage = Self {
             writer: Mutex::new(writer_conn),
             reader: Mutex::new(reader_conn),
-            scheduler: Mutex::new(FSRSScheduler::default()),
+            scheduler: Mutex::new(FSRSScheduler::new(fsrs_params)),
             #[cfg(feature = "embeddings")]
             embedding_service,
             #[cfg(feature = "vector-search")]
             vector_index: Mutex::new(vector_index),
             #[cfg(feature = "embeddings")]
             query_cache,
+            reconsolidation: Mutex::new(ReconsolidationManager::with_window(
+                Self::labile_window_from_env(),
+            )),
+            session_intent: Mutex::new(None),
+            retention_policy: Mutex::new(RetentionPolicy::from_env()),
+            retrieval_forgetting: Mutex::new(RetrievalForgettingPolicy::from_env()),
+            competition: Mutex::new(CompetitionManager::new()),
+            reinforcement: Mutex::new(ReinforcementConfig::from_env()),
+            neighbor_queue: Mutex::new(NeighborQueue::default()),
+            custom_node_types: Mutex::new(
+                EXTRA_NODE_TYPES.iter().map(|t| t.to_string()).collect(),
+            ),
+            max_content_bytes: AtomicUsize::new(Self::max_content_bytes_from_env()),
+            mutations_since_consolidation: AtomicU64::new(mutations),
+            clock: system_clock(),
+            in_memory: false,
+            metrics: StorageMetrics::default(),
+            #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+            contradiction_scorer: Mutex::new(None),
+            #[cfg(feature = "encryption")]
+            encryption_key: Mutex::new(key),
         };
 
         #[cfg(all(feature = "embeddings", feature = "vector-search"))]
-        storage.load_embeddings_into_index()?;
+        {
+            storage.sync_index_vectors(index_dimensions)?;
+            storage.load_embeddings_into_index()?;
+        }
 
         Ok(storage)
     }
 
+    /// HNSW parameters, overridable via VESTIGE_HNSW_CONNECTIVITY / VESTIGE_HNSW_EF_SEARCH,
+    /// and the similarity metric, via VESTIGE_VECTOR_METRIC
+    ///
+    /// Small collections do fine with the defaults; large ones (100k+) benefit
+    /// from higher connectivity and ef at the cost of memory and latency.
+    #[cfg(feature = "vector-search")]
+    fn vector_index_config_from_env() -> VectorIndexConfig {
+        let env_usize = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .filter(|v| *v > 0)
+        };
+
+        let mut config = VectorIndexConfig::default();
+        if let Some(connectivity) = env_usize("VESTIGE_HNSW_CONNECTIVITY") {
+            config.connectivity = connectivity;
+        }
+        if let Some(ef_search) = env_usize("VESTIGE_HNSW_EF_SEARCH") {
+            config.expansion_search = ef_search;
+        }
+        if let Ok(metric) = std::env::var("VESTIGE_VECTOR_METRIC") {
+            match metric.parse() {
+                Ok(metric) => config.metric = metric,
+                Err(e) => tracing::warn!("Ignoring VESTIGE_VECTOR_METRIC: {}", e),
+            }
+        }
+        config
+    }
+
+    /// Tune HNSW search expansion (ef) at query time without rebuilding the index
+    #[cfg(feature = "vector-search")]
+    pub fn set_vector_ef_search(&self, ef: usize) -> Result<()> {
+        let mut index = self
+            .vector_index
+            .lock()
+            .map_err(|_| StorageError::LockPoisoned("Vector index".into()))?;
+        index.set_ef_search(ef);
+        Ok(())
+    }
+
     /// Load existing embeddings into vector index
     #[cfg(all(feature = "embeddings", feature = "vector-search"))]
     fn load_embeddings_into_index(&self) -> Result<()> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
+        let vectors = self.load_index_vectors(self.index_dimensions())?;
 
-        let mut stmt = reader
-            .prepare("SELECT node_id, embedding FROM node_embeddings")?;
+        let mut index = self
+            .vector_index
+            .lock()
+            .map_err(|_| StorageError::LockPoisoned("Vector index".into()))?;
+
+        for (node_id, vector) in vectors {
+            if let Err(e) = index.add(&node_id, &vector) {
+                tracing::warn!("Failed to load embedding for {}: {}", node_id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read the stored index vectors truncated to `dimensions`
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn load_index_vectors(&self, dimensions: usize) -> Result<Vec<(String, Vec<f32>)>> {
+        let reader = self.lock_reader()?;
+
+        let mut stmt = reader.prepare(
+            "SELECT node_id, index_vector FROM node_embeddings WHERE index_dimensions = ?1",
+        )?;
 
         let embeddings: Vec<(String, Vec<u8>)> = stmt
-            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .query_map(params![dimensions as i64], |row| Ok((row.get(0)?, row.get(1)?)))?
             .filter_map(|r| r.ok())
             .collect();
 
-        drop(stmt);
-        drop(reader);
+        Ok(embeddings
+            .into_iter()
+            .filter_map(|(node_id, bytes)| Some((node_id, Embedding::from_bytes(&bytes)?.vector)))
+            .collect())
+    }
+
+    /// Matryoshka dimensions of the vector index and query embeddings
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    pub fn index_dimensions(&self) -> usize {
+        self.embedding_service.dimensions()
+    }
+
+    /// Index dimensions to open with
+    ///
+    /// VESTIGE_EMBEDDING_DIMS (256, 384, 512 or 768 for the default model;
+    /// VESTIGE_INDEX_DIMENSIONS is accepted too) wins; otherwise the
+    /// dimensions this database was last used with, then the embedding
+    /// service's default. A saved size the backend cannot produce is a
+    /// configuration error rather than a silently broken index. See
+    /// `MATRYOSHKA_DIMENSIONS` for the recall/latency tradeoff.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn index_dimensions_setting(conn: &Connection, service: &EmbeddingService) -> Result<usize> {
+        let supported = service.supported_dimensions();
+        for var in EMBEDDING_DIMS_ENV_VARS {
+            if let Ok(value) = std::env::var(var) {
+                match value.trim().parse::<usize>() {
+                    Ok(dimensions) if supported.contains(&dimensions) => return Ok(dimensions),
+                    _ => tracing::warn!(
+                        "Ignoring {}={} (expected one of {:?})",
+                        var,
+                        value,
+                        supported
+                    ),
+                }
+            }
+        }
+        let saved: Option<usize> = conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = ?1",
+                params![INDEX_DIMENSIONS_META_KEY],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?
+            .and_then(|value| value.parse().ok());
+        match saved {
+            Some(dimensions) if !supported.contains(&dimensions) => Err(StorageError::Config(format!(
+                "vector index holds {}-dimensional vectors but embedding backend {} produces {:?}; \
+                 set VESTIGE_EMBEDDING_DIMS to one of those and backfill embeddings",
+                dimensions,
+                service.model_name(),
+                supported
+            ))),
+            Some(dimensions) => Ok(dimensions),
+            None => Ok(service.dimensions()),
+        }
+    }
+
+    /// Count stored embeddings whose width differs from the backend's output
+    ///
+    /// Such vectors come from another model and are not comparable with new
+    /// query embeddings; `backfill_embeddings` replaces them once dropped.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn count_foreign_embeddings(conn: &Connection, service: &EmbeddingService) -> Result<usize> {
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM node_embeddings WHERE dimensions != ?1",
+            params![service.full_dimensions() as i64],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    /// Bring stored index vectors to `dimensions` and record them as in use
+    ///
+    /// Vectors stored at another size are re-truncated from `embedding`, so
+    /// later loads are a straight copy. Embeddings shorter than `dimensions`
+    /// (256-dim ones when moving up to 768) cannot be widened: they are kept,
+    /// but get no index vector (so they stay out of the index) and their
+    /// nodes are flagged for `backfill_embeddings`.
+    /// Returns (re-truncated, needing re-embedding).
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn sync_index_vectors(&self, dimensions: usize) -> Result<(usize, usize)> {
+        let mut writer = self.lock_writer()?;
+        let tx = writer.transaction()?;
+        let stale: Vec<(String, Vec<u8>)> = tx
+            .prepare("SELECT node_id, embedding FROM node_embeddings WHERE index_dimensions IS NOT ?1")?
+            .query_map(params![dimensions as i64], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+
+        let (mut retruncated, mut needs_reembedding) = (0, 0);
+        for (i, (node_id, bytes)) in stale.iter().enumerate() {
+            match Embedding::from_bytes(bytes).filter(|e| e.dimensions >= dimensions) {
+                Some(source) => {
+                    let vector = Embedding::new(matryoshka_truncate_to(source.vector, dimensions));
+                    tx.execute(
+                        "UPDATE node_embeddings SET index_vector = ?1, index_dimensions = ?2 WHERE node_id = ?3",
+                        params![vector.to_bytes(), dimensions as i64, node_id],
+                    )?;
+                    retruncated += 1;
+                }
+                None => {
+                    tx.execute(
+                        "UPDATE node_embeddings SET index_vector = NULL, index_dimensions = NULL
+                         WHERE node_id = ?1",
+                        params![node_id],
+                    )?;
+                    tx.execute(
+                        "UPDATE knowledge_nodes SET has_embedding = 0 WHERE id = ?1",
+                        params![node_id],
+                    )?;
+                    needs_reembedding += 1;
+                }
+            }
+            if (i + 1) % 1000 == 0 {
+                tracing::info!(done = i + 1, total = stale.len(), dimensions, "Re-truncating embeddings");
+            }
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)",
+            params![INDEX_DIMENSIONS_META_KEY, dimensions.to_string()],
+        )?;
+        tx.commit()?;
+
+        if needs_reembedding > 0 {
+            tracing::warn!(
+                needs_reembedding,
+                dimensions,
+                "Embeddings too short for the index dimensions were left out of it; run an embedding backfill"
+            );
+        }
+        Ok((retruncated, needs_reembedding))
+    }
+
+    /// Switch the vector index to another Matryoshka operating point
+    ///
+    /// `dimensions` must be one the embedding backend can truncate to (the
+    /// `MATRYOSHKA_DIMENSIONS` for the default model). Stored index
+    /// vectors are re-truncated and persisted, the HNSW index is rebuilt at the
+    /// new size and cached query embeddings are discarded. Embeddings stored
+    /// at fewer dimensions than requested need `backfill_embeddings`
+    /// afterwards; the report counts them.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    pub fn retruncate_embeddings(&self, dimensions: usize) -> Result<RetruncateReport> {
+        let started = std::time::Instant::now();
+        let supported = self.embedding_service.supported_dimensions();
+        if !supported.contains(&dimensions) {
+            return Err(StorageError::InvalidInput {
+                field: "index_dimensions",
+                reason: format!("must be one of {:?}, got {}", supported, dimensions),
+            });
+        }
+        let previous_dimensions = self.index_dimensions();
+
+        let (retruncated, needs_reembedding) = self.sync_index_vectors(dimensions)?;
+        self.embedding_service
+            .set_dimensions(dimensions)
+            .map_err(|e| StorageError::Config(e.to_string()))?;
+        let indexed = self.rebuild_vector_index_at(dimensions)?;
+        // Cached query vectors were truncated to the old dimensions
+        self.clear_query_cache()?;
+
+        Ok(RetruncateReport {
+            previous_dimensions,
+            dimensions,
+            retruncated,
+            needs_reembedding,
+            indexed,
+            duration_ms: started.elapsed().as_millis() as u64,
+        })
+    }
+
+    /// Rebuild the HNSW vector index from `node_embeddings`
+    ///
+    /// Discards removed slots and stale entries accumulated through
+    /// ingest/dedup/delete churn. Returns the number of vectors indexed.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    pub fn rebuild_vector_index(&self) -> Result<usize> {
+        self.rebuild_vector_index_at(self.index_dimensions())
+    }
+
+    /// Replace the vector index with a fresh one of `dimensions`
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn rebuild_vector_index_at(&self, dimensions: usize) -> Result<usize> {
+        let vectors = self.load_index_vectors(dimensions)?;
 
         let mut index = self
             .vector_index
             .lock()
-            .map_err(|_| StorageError::Init("Vector index lock poisoned".to_string()))?;
-
-        for (node_id, embedding_bytes) in embeddings {
-            if let Some(embedding) = Embedding::from_bytes(&embedding_bytes) {
-                // Handle Matryoshka migration: old 768-dim → truncate to 256-dim
-                let vector = if embedding.dimensions != EMBEDDING_DIMENSIONS {
-                    matryoshka_truncate(embedding.vector)
-                } else {
-                    embedding.vector
-                };
-                if let Err(e) = index.add(&node_id, &vector) {
-                    tracing::warn!("Failed to load embedding for {}: {}", node_id, e);
-                }
+            .map_err(|_| StorageError::LockPoisoned("Vector index".into()))?;
+
+        let mut config = index.config().clone();
+        config.dimensions = dimensions;
+        let mut fresh = VectorIndex::with_config(config)
+            .map_err(|e| StorageError::VectorIndex(format!("Failed to create vector index: {}", e)))?;
+        fresh
+            .reserve(std::cmp::max(vectors.len(), 16))
+            .map_err(|e| StorageError::VectorIndex(format!("Failed to reserve vector index: {}", e)))?;
+
+        for (node_id, vector) in vectors {
+            if let Err(e) = fresh.add(&node_id, &vector) {
+                tracing::warn!("Failed to index embedding for {}: {}", node_id, e);
             }
         }
 
-        Ok(())
+        let count = fresh.len();
+        *index = fresh;
+        Ok(count)
+    }
+
+    /// Get vector index health (size, capacity, removed slots)
+    #[cfg(feature = "vector-search")]
+    pub fn vector_index_stats(&self) -> Result<VectorIndexStats> {
+        let index = self
+            .vector_index
+            .lock()
+            .map_err(|_| StorageError::LockPoisoned("Vector index".into()))?;
+        Ok(index.stats())
     }
 
     /// Ingest a new memory
+    ///
+    /// With `input.auto_tag` set, confident tag suggestions are added first
+    /// (see `suggest_tags`). Content identical to an existing memory is
+    /// handled per `input.on_duplicate`; use `ingest_with_outcome` to tell
+    /// whether the returned node is new.
     pub fn ingest(&self, input: IngestInput) -> Result<KnowledgeNode> {
-        let now = Utc::now();
-        let id = Uuid::new_v4().to_string();
+        self.ingest_with_outcome(input).map(|outcome| outcome.node)
+    }
 
+    /// `ingest`, also reporting whether an existing duplicate was returned
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            node_type = %input.node_type,
+            content_bytes = input.content.len(),
+            returned_existing = tracing::field::Empty,
+            elapsed_ms = tracing::field::Empty,
+        )
+    )]
+    pub fn ingest_with_outcome(&self, mut input: IngestInput) -> Result<IngestOutcome> {
+        let _timer = SpanTimer::start();
+        self.validate_ingest(&input)?;
+        self.apply_auto_tags(&mut input)?;
+        let id = Uuid::new_v4().to_string();
         let fsrs_state = self.scheduler.lock()
-            .map_err(|_| StorageError::Init("Scheduler lock poisoned".into()))?
+            .map_err(|_| StorageError::LockPoisoned("Scheduler".into()))?
             .new_card();
 
-        // Sentiment boost for stability
-        let sentiment_boost = if input.sentiment_magnitude > 0.0 {
-            1.0 + (input.sentiment_magnitude * 0.5)
-        } else {
-            1.0
-        };
-
-        let tags_json = serde_json::to_string(&input.tags).unwrap_or_else(|_| "[]".to_string());
-        let next_review = now + Duration::days(fsrs_state.scheduled_days as i64);
-        let valid_from_str = input.valid_from.map(|dt| dt.to_rfc3339());
-        let valid_until_str = input.valid_until.map(|dt| dt.to_rfc3339());
-
         {
-            let writer = self.writer.lock()
-                .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-            writer.execute(
-                "INSERT INTO knowledge_nodes (
-                    id, content, node_type, created_at, updated_at, last_accessed,
-                    stability, difficulty, reps, lapses, learning_state,
-                    storage_strength, retrieval_strength, retention_strength,
-                    sentiment_score, sentiment_magnitude, next_review, scheduled_days,
-                    source, tags, valid_from, valid_until, has_embedding, embedding_model
-                ) VALUES (
-                    ?1, ?2, ?3, ?4, ?5, ?6,
-                    ?7, ?8, ?9, ?10, ?11,
-                    ?12, ?13, ?14,
-                    ?15, ?16, ?17, ?18,
-                    ?19, ?20, ?21, ?22, ?23, ?24
-                )",
-                params![
-                    id,
-                    input.content,
-                    input.node_type,
-                    now.to_rfc3339(),
-                    now.to_rfc3339(),
-                    now.to_rfc3339(),
-                    fsrs_state.stability * sentiment_boost,
-                    fsrs_state.difficulty,
-                    fsrs_state.reps,
-                    fsrs_state.lapses,
-                    "new",
-                    1.0,
-                    1.0,
-                    1.0,
-                    input.sentiment_score,
-                    input.sentiment_magnitude,
-                    next_review.to_rfc3339(),
-                    fsrs_state.scheduled_days,
-                    input.source,
-                    tags_json,
-                    valid_from_str,
-                    valid_until_str,
-                    0,
-                    Option::<String>::None,
-                ],
-            )?;
+            let writer = self.lock_writer()?;
+            // Checked under the writer lock so concurrent retries can't both insert
+            if input.on_duplicate != DuplicatePolicy::CreateAnyway
+                && let Some(existing_id) = Self::find_exact_duplicate(&writer, &input)?
+            {
+                drop(writer);
+                if input.on_duplicate == DuplicatePolicy::Error {
+                    return Err(StorageError::Duplicate { existing_id });
+                }
+                let node = self.get_node(&existing_id)?
+                    .ok_or_else(|| Self::vanished(&existing_id))?;
+                tracing::Span::current().record("returned_existing", true);
+                return Ok(IngestOutcome { node, returned_existing: true });
+            }
+            self.insert_node(&writer, &id, &input, &fsrs_state)?;
         }
+        self.note_mutation();
 
         // Generate embedding if available
         #[cfg(all(feature = "embeddings", feature = "vector-search"))]
@@ -313,53 +1013,472 @@ impl Storage {
             tracing::warn!("Failed to generate embedding for {}: {}", id, e);
         }
 
-        self.get_node(&id)?
-            .ok_or_else(|| StorageError::NotFound(id))
+        let node = self.get_node(&id)?
+            .ok_or_else(|| StorageError::NotFound(id))?;
+        tracing::Span::current().record("returned_existing", false);
+        Ok(IngestOutcome { node, returned_existing: false })
     }
 
-    /// Smart ingest with Prediction Error Gating
-    ///
-    /// Uses neuroscience-inspired prediction error to decide whether to:
-    /// - Create a new memory (high prediction error)
-    /// - Update an existing memory (low prediction error)
-    /// - Supersede a demoted/outdated memory (correction)
+    /// `ingest_with_outcome` for several inputs, validated up front
     ///
-    /// This solves the "bad vs good similar memory" problem.
-    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
-    pub fn smart_ingest(
-        &self,
-        input: IngestInput,
-    ) -> Result<SmartIngestResult> {
-        use crate::advanced::prediction_error::{
-            CandidateMemory, GateDecision, PredictionErrorGate, UpdateType,
-        };
+    /// Nothing is written if any input is invalid. Outcomes are returned in
+    /// input order; duplicates within the batch resolve to the first copy
+    /// per `on_duplicate`.
+    pub fn ingest_batch(&self, inputs: Vec<IngestInput>) -> Result<Vec<IngestOutcome>> {
+        for input in &inputs {
+            self.validate_ingest(input)?;
+        }
+        inputs.into_iter().map(|input| self.ingest_with_outcome(input)).collect()
+    }
 
-        // Generate embedding for new content
-        if !self.embedding_service.is_ready() {
-            // Fall back to regular ingest if embeddings not available
-            let node = self.ingest(input)?;
-            return Ok(SmartIngestResult {
-                decision: "create".to_string(),
-                node,
-                superseded_id: None,
-                similarity: None,
-                prediction_error: Some(1.0),
-                reason: "Embeddings not available, falling back to regular ingest".to_string(),
+    /// Existing memory with the same normalized content, node type and namespace
+    fn find_exact_duplicate(conn: &Connection, input: &IngestInput) -> Result<Option<String>> {
+        let normalized = normalize_whitespace(&input.content);
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, content FROM knowledge_nodes
+             WHERE content_hash = ?1 AND node_type = ?2 AND namespace IS ?3
+             ORDER BY created_at",
+        )?;
+        let candidates = stmt
+            .query_map(
+                params![normalized_content_hash(&input.content), input.node_type, input.namespace],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        // The hash is only 64 bits, so confirm the content really matches
+        Ok(candidates
+            .into_iter()
+            .find(|(_, content)| normalize_whitespace(content) == normalized)
+            .map(|(id, _)| id))
+    }
+
+    /// A smart_ingest target that was deleted between matching and updating
+    fn vanished(id: &str) -> StorageError {
+        StorageError::Conflict(format!("Memory {} was deleted while being updated", id))
+    }
+
+    /// Reject input that can't become a memory
+    fn validate_ingest(&self, input: &IngestInput) -> Result<()> {
+        if input.content.trim().is_empty() {
+            return Err(StorageError::InvalidInput {
+                field: "content",
+                reason: "must not be empty".into(),
+            });
+        }
+        let max_bytes = self.max_content_bytes();
+        if input.content.len() > max_bytes {
+            return Err(StorageError::InvalidInput {
+                field: "content",
+                reason: format!(
+                    "is {} bytes, over the {} byte limit; split it into smaller memories",
+                    input.content.len(),
+                    max_bytes
+                ),
+            });
+        }
+        if !input.sentiment_score.is_finite() || !(-1.0..=1.0).contains(&input.sentiment_score) {
+            return Err(StorageError::InvalidInput {
+                field: "sentiment_score",
+                reason: format!("must be between -1.0 and 1.0, got {}", input.sentiment_score),
+            });
+        }
+        if !input.sentiment_magnitude.is_finite() || !(0.0..=1.0).contains(&input.sentiment_magnitude) {
+            return Err(StorageError::InvalidInput {
+                field: "sentiment_magnitude",
+                reason: format!("must be between 0.0 and 1.0, got {}", input.sentiment_magnitude),
+            });
+        }
+        if !self.is_known_node_type(&input.node_type) {
+            return Err(StorageError::InvalidInput {
+                field: "node_type",
+                reason: format!(
+                    "unknown type '{}'; expected one of: {}",
+                    input.node_type,
+                    self.node_types().join(", ")
+                ),
             });
         }
+        Ok(())
+    }
+
+    /// Stability multiplier for emotionally charged content
+    fn sentiment_stability_boost(sentiment_magnitude: f64) -> f64 {
+        if sentiment_magnitude > 0.0 {
+            1.0 + (sentiment_magnitude * 0.5)
+        } else {
+            1.0
+        }
+    }
+
+    /// Insert the row for a new node in the given FSRS state
+    fn insert_node(
+        &self,
+        conn: &Connection,
+        id: &str,
+        input: &IngestInput,
+        fsrs_state: &FSRSState,
+    ) -> Result<()> {
+        let now = self.now();
+
+        let sentiment_boost = Self::sentiment_stability_boost(input.sentiment_magnitude);
+
+        let tags_json = serde_json::to_string(&input.tags).unwrap_or_else(|_| "[]".to_string());
+        let next_review = now + Duration::days(fsrs_state.scheduled_days as i64);
+        let valid_from_str = input.valid_from.map(|dt| dt.to_rfc3339());
+        let valid_until_str = input.valid_until.map(|dt| dt.to_rfc3339());
+
+        conn.execute(
+            "INSERT INTO knowledge_nodes (
+                id, content, node_type, created_at, updated_at, last_accessed,
+                stability, difficulty, reps, lapses, learning_state,
+                storage_strength, retrieval_strength, retention_strength,
+                sentiment_score, sentiment_magnitude, next_review, scheduled_days,
+                source, tags, valid_from, valid_until, has_embedding, embedding_model,
+                namespace, content_hash
+            ) VALUES (
+                ?1, ?2, ?3, ?4, ?5, ?6,
+                ?7, ?8, ?9, ?10, ?11,
+                ?12, ?13, ?14,
+                ?15, ?16, ?17, ?18,
+                ?19, ?20, ?21, ?22, ?23, ?24,
+                ?25, ?26
+            )",
+            params![
+                id,
+                input.content,
+                input.node_type,
+                now.to_rfc3339(),
+                now.to_rfc3339(),
+                now.to_rfc3339(),
+                fsrs_state.stability * sentiment_boost,
+                fsrs_state.difficulty,
+                fsrs_state.reps,
+                fsrs_state.lapses,
+                "new",
+                1.0,
+                1.0,
+                1.0,
+                input.sentiment_score,
+                input.sentiment_magnitude,
+                next_review.to_rfc3339(),
+                fsrs_state.scheduled_days,
+                input.source,
+                tags_json,
+                valid_from_str,
+                valid_until_str,
+                0,
+                Option::<String>::None,
+                input.namespace,
+                normalized_content_hash(&input.content),
+            ],
+        )?;
+        self.metrics.node_ingested();
+        Ok(())
+    }
+
+    /// Replace `old_id` with a new memory in one transaction
+    ///
+    /// The old memory is demoted, the new one starts its revision history
+    /// with the old content, and a `Refinement` edge from new to old records
+    /// `reason`. Nothing is written if the old memory does not exist.
+    pub fn supersede(&self, old_id: &str, mut input: IngestInput, reason: &str) -> Result<KnowledgeNode> {
+        self.validate_ingest(&input)?;
+        self.apply_auto_tags(&mut input)?;
+        let id = Uuid::new_v4().to_string();
+        let fsrs_state = self.scheduler.lock()
+            .map_err(|_| StorageError::LockPoisoned("Scheduler".into()))?
+            .new_card();
+
+        {
+            let mut writer = self.lock_writer()?;
+            let tx = writer.transaction()?;
+            let old_content: String = tx
+                .query_row(
+                    "SELECT content FROM knowledge_nodes WHERE id = ?1",
+                    params![old_id],
+                    |row| row.get(0),
+                )
+                .optional()?
+                .ok_or_else(|| StorageError::NotFound(old_id.to_string()))?;
+            tx.execute(DEMOTE_SQL, params![self.now().to_rfc3339(), old_id, 1.0])?;
+            self.insert_node(&tx, &id, &input, &fsrs_state)?;
+            self.record_revision(&tx, &id, old_id, &old_content, &input.content, SUPERSEDE_SOURCE)?;
+
+            let mut edge = KnowledgeEdge::new(id.clone(), old_id.to_string(), EdgeType::Refinement);
+            edge.created_by = Some(SUPERSEDE_SOURCE.to_string());
+            edge.metadata = Some(serde_json::json!({ "supersede_reason": reason }).to_string());
+            Self::insert_knowledge_edge(&tx, &edge)?;
+            tx.commit()?;
+        }
+        self.note_mutation();
+
+        self.after_demote(old_id, 1.0, Some(reason));
+
+        #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+        if let Err(e) = self.generate_embedding_for_node(&id, &input.content) {
+            tracing::warn!("Failed to generate embedding for {}: {}", id, e);
+        }
+
+        self.get_node(&id)?
+            .ok_or_else(|| StorageError::NotFound(id))
+    }
+
+    /// The supersede history `id` belongs to, newest first: the memories
+    /// that replaced it, itself, then the ones it replaced
+    pub fn get_superseded_chain(&self, id: &str) -> Result<Vec<KnowledgeNode>> {
+        let chain = {
+            let reader = self.lock_reader()?;
+            // The newest replacement of `from` (newer = true) or what it replaced
+            let step = |from: &str, newer: bool| -> Result<Option<String>> {
+                let sql = if newer {
+                    "SELECT source_id FROM knowledge_edges
+                     WHERE target_id = ?1 AND edge_type = 'refinement' AND created_by = ?2
+                     ORDER BY created_at DESC LIMIT 1"
+                } else {
+                    "SELECT target_id FROM knowledge_edges
+                     WHERE source_id = ?1 AND edge_type = 'refinement' AND created_by = ?2
+                     ORDER BY created_at DESC LIMIT 1"
+                };
+                Ok(reader
+                    .query_row(sql, params![from, SUPERSEDE_SOURCE], |row| row.get(0))
+                    .optional()?)
+            };
+
+            let mut chain = vec![id.to_string()];
+            while let Some(next) = step(&chain[0], true)? {
+                if chain.contains(&next) {
+                    break;
+                }
+                chain.insert(0, next);
+            }
+            while let Some(previous) = step(&chain[chain.len() - 1], false)? {
+                if chain.contains(&previous) {
+                    break;
+                }
+                chain.push(previous);
+            }
+            chain
+        };
+
+        let mut nodes = Vec::with_capacity(chain.len());
+        for node_id in &chain {
+            if let Some(node) = self.get_node(node_id)? {
+                nodes.push(node);
+            }
+        }
+        Ok(nodes)
+    }
+
+    /// Use a model (e.g. an NLI cross-encoder) for smart_ingest's
+    /// contradiction check instead of the word-level heuristics
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    pub fn set_contradiction_scorer(&self, scorer: Option<std::sync::Arc<dyn ContradictionScorer>>) -> Result<()> {
+        *self.contradiction_scorer.lock()
+            .map_err(|_| StorageError::LockPoisoned("Contradiction scorer".into()))? = scorer;
+        Ok(())
+    }
+
+    /// Smart ingest with Prediction Error Gating
+    ///
+    /// Uses neuroscience-inspired prediction error to decide whether to:
+    /// - Create a new memory (high prediction error)
+    /// - Update an existing memory (low prediction error)
+    /// - Supersede a demoted/outdated memory (correction)
+    ///
+    /// This solves the "bad vs good similar memory" problem. Similar memories
+    /// the new content probably contradicts are not merged; they are linked
+    /// with `Contradiction` edges and listed in `contradicts`.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            content_bytes = input.content.len(),
+            candidates = tracing::field::Empty,
+            decision = tracing::field::Empty,
+            elapsed_ms = tracing::field::Empty,
+        )
+    )]
+    pub fn smart_ingest(
+        &self,
+        mut input: IngestInput,
+    ) -> Result<SmartIngestResult> {
+        let _timer = SpanTimer::start();
+        self.validate_ingest(&input)?;
+        self.apply_auto_tags(&mut input)?;
+
+        // Generate embedding for new content
+        if !self.embedding_service.is_ready() {
+            // Fall back to regular ingest if embeddings not available
+            return Ok(Self::fallback_ingest_result(self.ingest_with_outcome(input)?));
+        }
+
+        let new_embedding = self
+            .embedding_service
+            .embed(&input.content)
+            .map_err(|e| StorageError::EmbeddingUnavailable(format!("Embedding failed: {}", e)))?;
+        let candidates = self.gate_candidates(&input)?;
+
+        let result = self.gate_and_apply(input, &new_embedding.vector, &candidates)?;
+        let span = tracing::Span::current();
+        span.record("candidates", candidates.len());
+        span.record("decision", result.decision.as_str());
+        Ok(result)
+    }
+
+    /// What `smart_ingest` would do with `input`, without writing anything
+    ///
+    /// Runs the same prediction error gate and reports the decision,
+    /// similarity, target and probable contradictions. For updates `node` is
+    /// the target showing the content it would get; new memories come back
+    /// unsaved with an empty id. Memories may change before the real call, so
+    /// its decision can still differ.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    pub fn smart_ingest_preview(&self, input: &IngestInput) -> Result<SmartIngestResult> {
+        let mut input = input.clone();
+        self.validate_ingest(&input)?;
+        self.apply_auto_tags(&mut input)?;
+
+        if !self.embedding_service.is_ready() {
+            return Ok(Self::fallback_ingest_result(self.preview_ingest(&input)?));
+        }
 
         let new_embedding = self
             .embedding_service
             .embed(&input.content)
-            .map_err(|e| StorageError::Init(format!("Embedding failed: {}", e)))?;
+            .map_err(|e| StorageError::EmbeddingUnavailable(format!("Embedding failed: {}", e)))?;
+        let candidates = self.gate_candidates(&input)?;
+
+        self.gate_preview(&input, &new_embedding.vector, &candidates)
+    }
+
+    /// Run the prediction error gate over candidates and describe its
+    /// decision as `gate_and_apply` would report it, without applying it
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn gate_preview(
+        &self,
+        input: &IngestInput,
+        new_embedding: &[f32],
+        candidates: &[CandidateMemory],
+    ) -> Result<SmartIngestResult> {
+        let plan = self.plan_gate(input, new_embedding, candidates);
+
+        let mut node = match &plan.decision {
+            GateDecision::Update { target_id, .. } => self.get_node(target_id)?
+                .ok_or_else(|| Self::vanished(target_id))?,
+            GateDecision::Supersede { .. } => self.unsaved_node(input)?,
+            GateDecision::Create { .. } | GateDecision::Merge { .. } => {
+                self.preview_ingest(input)?.node
+            }
+        };
+        if let GateDecision::Update { update_type, .. } = &plan.decision {
+            node.content = match update_type {
+                UpdateType::Reinforce => node.content,
+                UpdateType::Merge | UpdateType::Append => {
+                    self.merged_update_content(&node.content, &input.content)
+                }
+                UpdateType::Replace => input.content.clone(),
+                UpdateType::AddContext => Self::added_context_content(&node.content, &input.content),
+            };
+        }
+
+        let mut result = Self::gate_result(&plan.decision, node);
+        if result.decision == "update"
+            && plan.labile_target.as_ref().is_some_and(|l| l.id == result.node.id)
+        {
+            result.reason =
+                "Would update recently retrieved memory during its reconsolidation window".to_string();
+        }
+        for (old_id, _) in plan.conflicts {
+            if old_id != result.node.id && result.superseded_id.as_deref() != Some(old_id.as_str()) {
+                result.contradicts.push(old_id);
+            }
+        }
+        Self::note_contradictions(&mut result);
+        Ok(result)
+    }
+
+    /// Smart ingest result for the plain-ingest fallback used without embeddings
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn fallback_ingest_result(outcome: IngestOutcome) -> SmartIngestResult {
+        let (decision, reason) = if outcome.returned_existing {
+            ("duplicate", "Embeddings not available; content exactly matches an existing memory")
+        } else {
+            ("create", "Embeddings not available, falling back to regular ingest")
+        };
+        SmartIngestResult {
+            decision: decision.to_string(),
+            node: outcome.node,
+            superseded_id: None,
+            contradicts: Vec::new(),
+            reconsolidation: None,
+            similarity: None,
+            prediction_error: Some(if outcome.returned_existing { 0.0 } else { 1.0 }),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// What `ingest_with_outcome` would return for `input`, without inserting
+    ///
+    /// Exact duplicates are handled per `on_duplicate`; anything else comes
+    /// back as an unsaved node.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn preview_ingest(&self, input: &IngestInput) -> Result<IngestOutcome> {
+        if input.on_duplicate != DuplicatePolicy::CreateAnyway {
+            let existing = {
+                let reader = self.lock_reader()?;
+                Self::find_exact_duplicate(&reader, input)?
+            };
+            if let Some(existing_id) = existing {
+                if input.on_duplicate == DuplicatePolicy::Error {
+                    return Err(StorageError::Duplicate { existing_id });
+                }
+                let node = self.get_node(&existing_id)?
+                    .ok_or_else(|| Self::vanished(&existing_id))?;
+                return Ok(IngestOutcome { node, returned_existing: true });
+            }
+        }
+        Ok(IngestOutcome { node: self.unsaved_node(input)?, returned_existing: false })
+    }
+
+    /// The node `insert_node` would write for `input`, with an empty id
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn unsaved_node(&self, input: &IngestInput) -> Result<KnowledgeNode> {
+        let fsrs_state = self.scheduler.lock()
+            .map_err(|_| StorageError::LockPoisoned("Scheduler".into()))?
+            .new_card();
+        let now = self.now();
+        Ok(KnowledgeNode {
+            content: input.content.clone(),
+            node_type: input.node_type.clone(),
+            stability: fsrs_state.stability * Self::sentiment_stability_boost(input.sentiment_magnitude),
+            difficulty: fsrs_state.difficulty,
+            reps: fsrs_state.reps,
+            lapses: fsrs_state.lapses,
+            sentiment_score: input.sentiment_score,
+            sentiment_magnitude: input.sentiment_magnitude,
+            next_review: Some(now + Duration::days(fsrs_state.scheduled_days as i64)),
+            source: input.source.clone(),
+            tags: input.tags.clone(),
+            valid_from: input.valid_from,
+            valid_until: input.valid_until,
+            namespace: input.namespace.clone(),
+            ..KnowledgeNode::default()
+        })
+    }
 
+    /// Same-namespace memories semantically close to `input`, with embeddings
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn gate_candidates(&self, input: &IngestInput) -> Result<Vec<CandidateMemory>> {
         // Find similar memories using semantic search
         let similar = self.semantic_search_raw(&input.content, 10)?;
 
-        // Build candidate memories
+        // Build candidate memories (never merging across namespaces)
         let mut candidates: Vec<CandidateMemory> = Vec::new();
         for (node_id, _similarity) in similar.iter() {
-            if let Some(node) = self.get_node(node_id)? {
+            if let Some(node) = self.get_node(node_id)?
+                && node.namespace == input.namespace
+            {
                 // Get embedding for this node
                 if let Some(emb) = self.get_node_embedding(node_id)? {
                     // Check if this memory was previously demoted (low retrieval strength)
@@ -380,147 +1499,277 @@ impl Storage {
                 }
             }
         }
+        Ok(candidates)
+    }
+
+    /// Run the prediction error gate over candidates, without writing
+    ///
+    /// If the best candidate was retrieved recently enough to still be labile,
+    /// the gate is relaxed toward updating it: an edit made right after recall
+    /// reshapes that memory instead of forking a near-copy.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn plan_gate(
+        &self,
+        input: &IngestInput,
+        new_embedding: &[f32],
+        candidates: &[CandidateMemory],
+    ) -> GatePlan {
+        /// Update threshold while the best match is labile (default gate: 0.75)
+        const LABILE_SIMILARITY_THRESHOLD: f32 = 0.6;
+
+        let labile_target = candidates
+            .iter()
+            .max_by(|a, b| {
+                cosine_similarity(new_embedding, &a.embedding)
+                    .total_cmp(&cosine_similarity(new_embedding, &b.embedding))
+            })
+            .filter(|c| self.is_labile(&c.id))
+            .cloned();
+
+        let mut gate = match labile_target {
+            Some(_) => PredictionErrorGate::with_config(PredictionErrorConfig {
+                similarity_threshold: LABILE_SIMILARITY_THRESHOLD,
+                // Corrections of a labile memory rewrite it rather than supersede it
+                correction_threshold: f32::MAX,
+                auto_supersede_demoted: false,
+                ..Default::default()
+            }),
+            None => PredictionErrorGate::new(),
+        };
+        // Probable contradictions are kept apart from the gate so they are
+        // linked rather than merged. Explicit corrections and edits of a
+        // labile memory still go through the gate and rewrite or supersede.
+        let conflicts = self.find_contradictions(&input.content, new_embedding, candidates, &gate);
+        let gated: Vec<CandidateMemory> = candidates
+            .iter()
+            .filter(|c| {
+                !conflicts.iter().any(|(id, _)| *id == c.id)
+                    || labile_target.as_ref().is_some_and(|l| l.id == c.id)
+            })
+            .cloned()
+            .collect();
+        let decision = gate.evaluate(&input.content, new_embedding, &gated);
 
-        // Evaluate with prediction error gate
-        let mut gate = PredictionErrorGate::new();
-        let decision = gate.evaluate(&input.content, &new_embedding.vector, &candidates);
+        GatePlan { gate, decision, conflicts, labile_target }
+    }
+
+    /// Run the prediction error gate over candidates and apply its decision
+    ///
+    /// An update landing on a labile target is recorded as a reconsolidation
+    /// of that memory.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn gate_and_apply(
+        &self,
+        input: IngestInput,
+        new_embedding: &[f32],
+        candidates: &[CandidateMemory],
+    ) -> Result<SmartIngestResult> {
+        let GatePlan { gate, decision, conflicts, labile_target } =
+            self.plan_gate(&input, new_embedding, candidates);
 
-        match decision {
-            GateDecision::Create { prediction_error, related_memory_ids, reason, .. } => {
-                // Create new memory
+        let mut result = match &decision {
+            GateDecision::Create { .. } | GateDecision::Merge { .. } => {
+                // Merges create a new memory linked to the similar ones for now
                 let node = self.ingest(input)?;
-                Ok(SmartIngestResult {
-                    decision: "create".to_string(),
-                    node,
-                    superseded_id: None,
-                    similarity: None,
-                    prediction_error: Some(prediction_error),
-                    reason: format!("Created new memory: {:?}. Related: {:?}", reason, related_memory_ids),
-                })
+                Self::gate_result(&decision, node)
             }
-            GateDecision::Update { target_id, similarity, update_type, prediction_error } => {
+            GateDecision::Update { target_id, update_type, .. } => {
+                let existing = self.get_node(target_id)?
+                    .ok_or_else(|| Self::vanished(target_id))?;
+                let mut reconsolidation = None;
                 match update_type {
                     UpdateType::Reinforce => {
                         // Just strengthen the existing memory
-                        self.strengthen_on_access(&target_id)?;
-                        let node = self.get_node(&target_id)?
-                            .ok_or_else(|| StorageError::NotFound(target_id.clone()))?;
-                        Ok(SmartIngestResult {
-                            decision: "reinforce".to_string(),
-                            node,
-                            superseded_id: None,
-                            similarity: Some(similarity),
-                            prediction_error: Some(prediction_error),
-                            reason: "Content nearly identical - reinforced existing memory".to_string(),
-                        })
+                        self.strengthen_on_access(target_id)?;
                     }
                     UpdateType::Merge | UpdateType::Append => {
                         // Update the existing memory with merged content
-                        let existing = self.get_node(&target_id)?
-                            .ok_or_else(|| StorageError::NotFound(target_id.clone()))?;
-
-                        let merged_content = format!(
-                            "{}\n\n[Updated {}]\n{}",
-                            existing.content,
-                            chrono::Utc::now().format("%Y-%m-%d"),
-                            input.content
-                        );
-
-                        self.update_node_content(&target_id, &merged_content)?;
-                        self.strengthen_on_access(&target_id)?;
-
-                        let node = self.get_node(&target_id)?
-                            .ok_or_else(|| StorageError::NotFound(target_id.clone()))?;
-
-                        Ok(SmartIngestResult {
-                            decision: "update".to_string(),
-                            node,
-                            superseded_id: None,
-                            similarity: Some(similarity),
-                            prediction_error: Some(prediction_error),
-                            reason: "Merged with existing similar memory".to_string(),
-                        })
+                        let merged_content = self.merged_update_content(&existing.content, &input.content);
+                        self.update_node_content_from(target_id, &merged_content, "smart_ingest:merge")?;
+                        self.strengthen_on_access(target_id)?;
+
+                        reconsolidation = match labile_target {
+                            Some(labile) if labile.id == *target_id => self.reconsolidate_with(
+                                target_id,
+                                Modification::UpdateContent {
+                                    new_content: Some(input.content.clone()),
+                                    is_correction: gate
+                                        .detect_contradiction(&input.content, &labile.content),
+                                },
+                            )?,
+                            _ => None,
+                        };
                     }
                     UpdateType::Replace => {
                         // Replace content entirely
-                        self.update_node_content(&target_id, &input.content)?;
-                        let node = self.get_node(&target_id)?
-                            .ok_or_else(|| StorageError::NotFound(target_id.clone()))?;
-
-                        Ok(SmartIngestResult {
-                            decision: "replace".to_string(),
-                            node,
-                            superseded_id: None,
-                            similarity: Some(similarity),
-                            prediction_error: Some(prediction_error),
-                            reason: "Replaced existing memory with new content".to_string(),
-                        })
+                        self.update_node_content_from(target_id, &input.content, "smart_ingest:replace")?;
                     }
                     UpdateType::AddContext => {
                         // Add as context without modifying main content
-                        let existing = self.get_node(&target_id)?
-                            .ok_or_else(|| StorageError::NotFound(target_id.clone()))?;
-
-                        let merged_content = format!(
-                            "{}\n\n---\nContext: {}",
-                            existing.content,
-                            input.content
-                        );
-
-                        self.update_node_content(&target_id, &merged_content)?;
-                        let node = self.get_node(&target_id)?
-                            .ok_or_else(|| StorageError::NotFound(target_id.clone()))?;
-
-                        Ok(SmartIngestResult {
-                            decision: "add_context".to_string(),
-                            node,
-                            superseded_id: None,
-                            similarity: Some(similarity),
-                            prediction_error: Some(prediction_error),
-                            reason: "Added new content as context to existing memory".to_string(),
-                        })
+                        let merged_content = Self::added_context_content(&existing.content, &input.content);
+                        self.update_node_content_from(target_id, &merged_content, "smart_ingest:add_context")?;
                     }
                 }
-            }
-            GateDecision::Supersede { old_memory_id, similarity, supersede_reason, prediction_error } => {
-                // Demote the old memory and create new
-                self.demote_memory(&old_memory_id)?;
 
-                // Create the new improved memory
-                let node = self.ingest(input)?;
+                let node = self.get_node(target_id)?
+                    .ok_or_else(|| Self::vanished(target_id))?;
+                let mut result = Self::gate_result(&decision, node);
+                if reconsolidation.is_some() {
+                    result.reason =
+                        "Updated recently retrieved memory during its reconsolidation window".to_string();
+                }
+                result.reconsolidation = reconsolidation;
+                result
+            }
+            GateDecision::Supersede { old_memory_id, supersede_reason, .. } => {
+                // Demote the old memory and create the new improved one,
+                // linked to it, in one transaction
+                let node = self
+                    .supersede(old_memory_id, input, &format!("{:?}", supersede_reason))
+                    .map_err(|e| match e {
+                        StorageError::NotFound(_) => Self::vanished(old_memory_id),
+                        e => e,
+                    })?;
+                Self::gate_result(&decision, node)
+            }
+        };
 
-                Ok(SmartIngestResult {
-                    decision: "supersede".to_string(),
-                    node,
-                    superseded_id: Some(old_memory_id),
-                    similarity: Some(similarity),
-                    prediction_error: Some(prediction_error),
-                    reason: format!("New memory supersedes old: {:?}", supersede_reason),
-                })
+        for (old_id, contradiction) in conflicts {
+            if old_id == result.node.id || result.superseded_id.as_deref() == Some(old_id.as_str()) {
+                continue;
             }
-            GateDecision::Merge { memory_ids, avg_similarity, strategy } => {
-                // For now, create new and link to existing
-                let node = self.ingest(input)?;
+            let mut edge = KnowledgeEdge::new(result.node.id.clone(), old_id.clone(), EdgeType::Contradiction);
+            edge.created_by = Some("smart_ingest".to_string());
+            edge.metadata = serde_json::to_string(&contradiction).ok();
+            self.save_knowledge_edge(&edge)?;
+            result.contradicts.push(old_id);
+        }
+        Self::note_contradictions(&mut result);
+        Ok(result)
+    }
 
-                Ok(SmartIngestResult {
-                    decision: "merge".to_string(),
-                    node,
-                    superseded_id: None,
-                    similarity: Some(avg_similarity),
-                    prediction_error: Some(1.0 - avg_similarity),
-                    reason: format!("Created new memory linked to {} similar memories ({:?})", memory_ids.len(), strategy),
-                })
+    /// Decision label, similarity and explanation for a gate decision
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn gate_result(decision: &GateDecision, node: KnowledgeNode) -> SmartIngestResult {
+        let (label, similarity, superseded_id, reason) = match decision {
+            GateDecision::Create { reason, related_memory_ids, .. } => (
+                "create",
+                None,
+                None,
+                format!("Created new memory: {:?}. Related: {:?}", reason, related_memory_ids),
+            ),
+            GateDecision::Update { similarity, update_type, .. } => {
+                let (label, reason) = match update_type {
+                    UpdateType::Reinforce => {
+                        ("reinforce", "Content nearly identical - reinforced existing memory")
+                    }
+                    UpdateType::Merge | UpdateType::Append => ("update", "Merged with existing similar memory"),
+                    UpdateType::Replace => ("replace", "Replaced existing memory with new content"),
+                    UpdateType::AddContext => {
+                        ("add_context", "Added new content as context to existing memory")
+                    }
+                };
+                (label, Some(*similarity), None, reason.to_string())
             }
+            GateDecision::Supersede { old_memory_id, similarity, supersede_reason, .. } => (
+                "supersede",
+                Some(*similarity),
+                Some(old_memory_id.clone()),
+                format!("New memory supersedes old: {:?}", supersede_reason),
+            ),
+            GateDecision::Merge { memory_ids, avg_similarity, strategy } => (
+                "merge",
+                Some(*avg_similarity),
+                None,
+                format!("Created new memory linked to {} similar memories ({:?})", memory_ids.len(), strategy),
+            ),
+        };
+        SmartIngestResult {
+            decision: label.to_string(),
+            node,
+            superseded_id,
+            contradicts: Vec::new(),
+            similarity,
+            prediction_error: Some(decision.prediction_error()),
+            reason,
+            reconsolidation: None,
+        }
+    }
+
+    /// Mention probable contradictions in the result's explanation
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn note_contradictions(result: &mut SmartIngestResult) {
+        if !result.contradicts.is_empty() {
+            result.reason = format!(
+                "{}. Probably contradicts {} existing memor{}",
+                result.reason,
+                result.contradicts.len(),
+                if result.contradicts.len() == 1 { "y" } else { "ies" }
+            );
         }
     }
 
+    /// Content of a memory after smart_ingest merges new content into it
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn merged_update_content(&self, existing: &str, new_content: &str) -> String {
+        format!(
+            "{}\n\n[Updated {}]\n{}",
+            existing,
+            self.now().format("%Y-%m-%d"),
+            new_content
+        )
+    }
+
+    /// Content of a memory after smart_ingest adds new content as context
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn added_context_content(existing: &str, new_content: &str) -> String {
+        format!("{}\n\n---\nContext: {}", existing, new_content)
+    }
+
+    /// Similar candidates the new content probably contradicts
+    ///
+    /// Explicit corrections (which the gate already supersedes) are left out.
+    /// A registered `ContradictionScorer` takes precedence over the word-level
+    /// heuristics when it returns a probability.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn find_contradictions(
+        &self,
+        content: &str,
+        new_embedding: &[f32],
+        candidates: &[CandidateMemory],
+        gate: &PredictionErrorGate,
+    ) -> Vec<(String, Contradiction)> {
+        /// Candidates less similar than this are about something else
+        const CONTRADICTION_SIMILARITY: f32 = 0.6;
+
+        let scorer = self.contradiction_scorer.lock().ok().and_then(|s| s.clone());
+        candidates
+            .iter()
+            .filter(|c| cosine_similarity(new_embedding, &c.embedding) >= CONTRADICTION_SIMILARITY)
+            .filter(|c| !gate.detect_contradiction(content, &c.content))
+            .filter_map(|c| {
+                let model = scorer
+                    .as_ref()
+                    .and_then(|s| s.contradiction_probability(content, &c.content))
+                    .map(|p| {
+                        (p >= 0.5).then(|| Contradiction {
+                            kind: ContradictionKind::Model,
+                            detail: format!("contradiction probability {:.2}", p),
+                        })
+                    });
+                model
+                    .unwrap_or_else(|| detect_contradiction(content, &c.content))
+                    .map(|found| (c.id.clone(), found))
+            })
+            .collect()
+    }
+
     /// Get the embedding vector for a node
     #[cfg(all(feature = "embeddings", feature = "vector-search"))]
     pub fn get_node_embedding(&self, node_id: &str) -> Result<Option<Vec<f32>>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
+        let reader = self.lock_reader()?;
         let mut stmt = reader.prepare(
-            "SELECT embedding FROM node_embeddings WHERE node_id = ?1"
+            "SELECT COALESCE(index_vector, embedding) FROM node_embeddings WHERE node_id = ?1"
         )?;
 
         let embedding_bytes: Option<Vec<u8>> = stmt
@@ -535,10 +1784,9 @@ impl Storage {
     /// Get all embedding vectors for duplicate detection
     #[cfg(all(feature = "embeddings", feature = "vector-search"))]
     pub fn get_all_embeddings(&self) -> Result<Vec<(String, Vec<f32>)>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
+        let reader = self.lock_reader()?;
         let mut stmt = reader
-            .prepare("SELECT node_id, embedding FROM node_embeddings")?;
+            .prepare("SELECT node_id, COALESCE(index_vector, embedding) FROM node_embeddings")?;
 
         let results: Vec<(String, Vec<f32>)> = stmt
             .query_map([], |row| {
@@ -558,83 +1806,413 @@ impl Storage {
 
     /// Update the content of an existing node
     pub fn update_node_content(&self, id: &str, new_content: &str) -> Result<()> {
-        let now = Utc::now();
+        self.update_node_content_from(id, new_content, "update")
+    }
 
-        {
-            let writer = self.writer.lock()
-                .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-            writer.execute(
-                "UPDATE knowledge_nodes SET content = ?1, updated_at = ?2 WHERE id = ?3",
-                params![new_content, now.to_rfc3339(), id],
-            )?;
-        }
+    /// Update the content of an existing node and adjust its scheduling
+    ///
+    /// Rewrites whose new embedding is far from the old one are recorded as a
+    /// `content_rewrite` state transition so they show up in the changelog.
+    pub fn update_node_content_with(
+        &self,
+        id: &str,
+        new_content: &str,
+        mode: ContentUpdateMode,
+    ) -> Result<()> {
+        /// Rewrites less similar than this to the old content are logged
+        #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+        const REWRITE_SIMILARITY: f32 = 0.8;
 
-        // Regenerate embedding for updated content
         #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+        let old_embedding = self.get_node_embedding(id)?;
+
+        self.update_node_content(id, new_content)?;
+        self.reset_schedule(id, mode)?;
+
+        #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+        if let Some(old_embedding) = old_embedding
+            && let Some(new_embedding) = self.get_node_embedding(id)?
         {
-            // Remove old embedding from index
-            if let Ok(mut index) = self.vector_index.lock() {
-                let _ = index.remove(id);
-            }
-            // Generate new embedding
-            if let Err(e) = self.generate_embedding_for_node(id, new_content) {
-                tracing::warn!("Failed to regenerate embedding for {}: {}", id, e);
+            let similarity = cosine_similarity(&old_embedding, &new_embedding);
+            if similarity < REWRITE_SIMILARITY {
+                self.log_content_rewrite(id, similarity, mode)?;
             }
         }
 
         Ok(())
     }
 
-    /// Generate embedding for a node
-    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
-    fn generate_embedding_for_node(&self, node_id: &str, content: &str) -> Result<()> {
-        if !self.embedding_service.is_ready() {
-            return Ok(());
-        }
-
-        let embedding = self
-            .embedding_service
-            .embed(content)
-            .map_err(|e| StorageError::Init(format!("Embedding failed: {}", e)))?;
-
-        let now = Utc::now();
+    /// Apply a `ContentUpdateMode` to a node's FSRS state
+    fn reset_schedule(&self, id: &str, mode: ContentUpdateMode) -> Result<()> {
+        let now = self.now();
+        let writer = self.lock_writer()?;
+        match mode {
+            ContentUpdateMode::Keep => {}
+            ContentUpdateMode::SoftReset(factor) => {
+                let stability: f64 = writer.query_row(
+                    "SELECT stability FROM knowledge_nodes WHERE id = ?1",
+                    params![id],
+                    |row| row.get(0),
+                )?;
+                let stability = (stability * factor.clamp(0.0, 1.0)).max(MIN_STABILITY);
+                let scheduled_days = next_interval(stability, DEFAULT_RETENTION).max(1);
+                writer.execute(
+                    "UPDATE knowledge_nodes SET stability = ?1, last_accessed = ?2,
+                        next_review = ?3, scheduled_days = ?4
+                     WHERE id = ?5",
+                    params![
+                        stability,
+                        now.to_rfc3339(),
+                        (now + Duration::days(scheduled_days as i64)).to_rfc3339(),
+                        scheduled_days,
+                        id,
+                    ],
+                )?;
+            }
+            ContentUpdateMode::FullReset => {
+                let card = self.scheduler.lock()
+                    .map_err(|_| StorageError::LockPoisoned("Scheduler".into()))?
+                    .new_card();
+                writer.execute(
+                    "UPDATE knowledge_nodes SET stability = ?1, difficulty = ?2, reps = ?3,
+                        lapses = ?4, learning_state = 'new', last_accessed = ?5,
+                        next_review = ?6, scheduled_days = ?7
+                     WHERE id = ?8",
+                    params![
+                        card.stability,
+                        card.difficulty,
+                        card.reps,
+                        card.lapses,
+                        now.to_rfc3339(),
+                        (now + Duration::days(card.scheduled_days as i64)).to_rfc3339(),
+                        card.scheduled_days,
+                        id,
+                    ],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Record a substantial content rewrite for the changelog
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn log_content_rewrite(&self, id: &str, similarity: f32, mode: ContentUpdateMode) -> Result<()> {
+        let state = self
+            .get_memory_state(id)?
+            .map(|record| record.state)
+            .unwrap_or_else(|| "active".to_string());
+        let reason_data = serde_json::json!({
+            "similarity": similarity,
+            "mode": format!("{:?}", mode),
+        });
+        let writer = self.lock_writer()?;
+        writer.execute(
+            "INSERT INTO state_transitions (memory_id, from_state, to_state, reason_type, reason_data, timestamp)
+             VALUES (?1, ?2, ?2, 'content_rewrite', ?3, ?4)",
+            params![id, state, reason_data.to_string(), self.now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Revisions kept per node, overridable via VESTIGE_MAX_REVISIONS
+    fn max_revisions_from_env() -> i64 {
+        const DEFAULT_MAX_REVISIONS: i64 = 20;
+        std::env::var("VESTIGE_MAX_REVISIONS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_MAX_REVISIONS)
+    }
+
+    /// Update the content of an existing node, recording the replaced content
+    ///
+    /// `change_source` names what made the change (e.g. `smart_ingest:merge`).
+    /// Only the newest revisions per node are kept (see `max_revisions_from_env`).
+    pub fn update_node_content_from(
+        &self,
+        id: &str,
+        new_content: &str,
+        change_source: &str,
+    ) -> Result<()> {
+        let now = self.now();
+
+        {
+            let mut writer = self.lock_writer()?;
+            let tx = writer.transaction()?;
+            let old_content: Option<String> = tx
+                .query_row(
+                    "SELECT content FROM knowledge_nodes WHERE id = ?1",
+                    params![id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if let Some(old_content) = old_content.filter(|old| old != new_content) {
+                self.record_revision(&tx, id, id, &old_content, new_content, change_source)?;
+            }
+            tx.execute(
+                "UPDATE knowledge_nodes SET content = ?1, content_hash = ?2, updated_at = ?3 WHERE id = ?4",
+                params![new_content, normalized_content_hash(new_content), now.to_rfc3339(), id],
+            )?;
+            tx.commit()?;
+        }
+        self.note_mutation();
+
+        // Regenerate embedding for updated content
+        #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+        {
+            // Remove old embedding from index
+            if let Ok(mut index) = self.vector_index.lock() {
+                let _ = index.remove(id);
+            }
+            // Generate new embedding
+            if let Err(e) = self.generate_embedding_for_node(id, new_content) {
+                tracing::warn!("Failed to regenerate embedding for {}: {}", id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Record `old_content` (and the current embedding of `previous_id`) as a
+    /// revision of `node_id`, pruning revisions past the cap
+    fn record_revision(
+        &self,
+        conn: &Connection,
+        node_id: &str,
+        previous_id: &str,
+        old_content: &str,
+        new_content: &str,
+        change_source: &str,
+    ) -> Result<()> {
+        conn.execute(
+            "INSERT INTO node_revisions
+                (node_id, old_content, new_content_hash, changed_at, change_source, embedding)
+             VALUES (?1, ?2, ?3, ?4, ?5, (SELECT embedding FROM node_embeddings WHERE node_id = ?6))",
+            params![
+                node_id,
+                old_content,
+                content_hash(new_content),
+                self.now().to_rfc3339(),
+                change_source,
+                previous_id,
+            ],
+        )?;
+        conn.execute(
+            "DELETE FROM node_revisions WHERE node_id = ?1 AND id NOT IN (
+                SELECT id FROM node_revisions WHERE node_id = ?1 ORDER BY id DESC LIMIT ?2
+             )",
+            params![node_id, Self::max_revisions_from_env()],
+        )?;
+        Ok(())
+    }
+
+    /// Diff between two revisions of a node (`to = None` compares against
+    /// the current content)
+    ///
+    /// Revision IDs come from `get_node_revisions`; each names the content the
+    /// node held before that change. When both sides have stored embeddings,
+    /// their cosine similarity is included.
+    pub fn diff_revisions(&self, id: &str, from: i64, to: Option<i64>) -> Result<RevisionDiff> {
+        let reader = self.lock_reader()?;
+        let revision = |revision_id: i64| -> Result<(String, Option<Vec<u8>>)> {
+            reader
+                .query_row(
+                    "SELECT old_content, embedding FROM node_revisions WHERE id = ?1 AND node_id = ?2",
+                    params![revision_id, id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?
+                .ok_or_else(|| StorageError::NotFound(format!("revision {} of {}", revision_id, id)))
+        };
+        let (from_content, from_embedding) = revision(from)?;
+        let (to_content, to_embedding) = match to {
+            Some(to) => revision(to)?,
+            None => reader
+                .query_row(
+                    "SELECT n.content, e.embedding FROM knowledge_nodes n
+                     LEFT JOIN node_embeddings e ON e.node_id = n.id
+                     WHERE n.id = ?1",
+                    params![id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?
+                .ok_or_else(|| StorageError::NotFound(id.to_string()))?,
+        };
+
+        #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+        let similarity = from_embedding
+            .as_deref()
+            .and_then(Embedding::from_bytes)
+            .zip(to_embedding.as_deref().and_then(Embedding::from_bytes))
+            .filter(|(a, b)| a.vector.len() == b.vector.len())
+            .map(|(a, b)| cosine_similarity(&a.vector, &b.vector));
+        #[cfg(not(all(feature = "embeddings", feature = "vector-search")))]
+        let similarity = {
+            let _ = (from_embedding, to_embedding);
+            None
+        };
+
+        Ok(RevisionDiff {
+            node_id: id.to_string(),
+            from,
+            to,
+            diff: unified_diff(&from_content, &to_content),
+            similarity,
+        })
+    }
+
+    /// Restore the content a node held before `revision_id`
+    ///
+    /// The revert is itself recorded as a revision, so it can be undone.
+    pub fn revert_node(&self, id: &str, revision_id: i64) -> Result<KnowledgeNode> {
+        let content: String = {
+            let reader = self.lock_reader()?;
+            reader
+                .query_row(
+                    "SELECT old_content FROM node_revisions WHERE id = ?1 AND node_id = ?2",
+                    params![revision_id, id],
+                    |row| row.get(0),
+                )
+                .optional()?
+                .ok_or_else(|| StorageError::NotFound(format!("revision {} of {}", revision_id, id)))?
+        };
+        self.update_node_content_from(id, &content, &format!("revert:{}", revision_id))?;
+        self.get_node(id)?
+            .ok_or_else(|| StorageError::NotFound(id.to_string()))
+    }
+
+    /// Content revisions of a node, newest first, each with a diff to the
+    /// content that replaced it
+    pub fn get_node_revisions(&self, id: &str, limit: i32) -> Result<Vec<NodeRevision>> {
+        let (current, rows) = {
+            let reader = self.lock_reader()?;
+            let current: Option<String> = reader
+                .query_row(
+                    "SELECT content FROM knowledge_nodes WHERE id = ?1",
+                    params![id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let mut stmt = reader.prepare(
+                "SELECT id, old_content, new_content_hash, changed_at, change_source
+                 FROM node_revisions WHERE node_id = ?1
+                 ORDER BY id DESC LIMIT ?2",
+            )?;
+            let rows = stmt
+                .query_map(params![id, limit], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        Self::parse_timestamp(&row.get::<_, String>(3)?, "changed_at")?,
+                        row.get::<_, String>(4)?,
+                    ))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            (current, rows)
+        };
+
+        // Each revision was replaced by the next newer one (or the current
+        // content); the stored hash guards against untracked edits in between
+        let mut newer = current;
+        Ok(rows
+            .into_iter()
+            .map(|(revision_id, old_content, new_content_hash, changed_at, change_source)| {
+                let diff = newer
+                    .as_deref()
+                    .filter(|content| content_hash(content) == new_content_hash)
+                    .map(|content| unified_diff(&old_content, content));
+                newer = Some(old_content.clone());
+                NodeRevision {
+                    id: revision_id,
+                    node_id: id.to_string(),
+                    old_content,
+                    new_content_hash,
+                    changed_at,
+                    change_source,
+                    diff,
+                }
+            })
+            .collect())
+    }
+
+    /// Generate embedding for a node
+    ///
+    /// Content is classified first: source code goes through
+    /// `CodeEmbedding`'s preprocessing with the detected language, prose and
+    /// other content through `AdaptiveEmbedder`. Every strategy embeds with
+    /// the same text model and is truncated to the same index dimensions, so
+    /// code and prose share one vector space and one index, and
+    /// cross-strategy similarity is an ordinary cosine. The strategy and
+    /// language are recorded so a strategy's vectors can be re-embedded on
+    /// their own (`reembed_by_strategy`) if its preprocessing changes.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn generate_embedding_for_node(&self, node_id: &str, content: &str) -> Result<()> {
+        if !self.embedding_service.is_ready() {
+            return Ok(());
+        }
+
+        let content_type = ContentType::detect(content);
+        let language = content_type.language().map(|lang| lang.as_str());
+        let (strategy, text) = match content_type {
+            ContentType::Code(_) => (
+                EmbeddingStrategy::CodeEmbedding,
+                CodeEmbedding::preprocess(content, language),
+            ),
+            // Logs and structured data are normalized before embedding
+            _ => AdaptiveEmbedder::new().prepare_as(content, &content_type),
+        };
+        let embedding = self
+            .embedding_service
+            .embed_full(&text)
+            .map_err(|e| StorageError::EmbeddingUnavailable(format!("Embedding failed: {}", e)))?;
+        // The full vector is kept so the index can later move to more dimensions
+        let index_vector =
+            Embedding::new(matryoshka_truncate_to(embedding.vector.clone(), self.index_dimensions()));
+
+        let now = self.now();
 
         {
-            let writer = self.writer.lock()
-                .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
+            let writer = self.lock_writer()?;
             writer.execute(
-                "INSERT OR REPLACE INTO node_embeddings (node_id, embedding, dimensions, model, created_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                "INSERT OR REPLACE INTO node_embeddings
+                    (node_id, embedding, dimensions, model, created_at, strategy, index_vector,
+                     index_dimensions, language)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
                 params![
                     node_id,
                     embedding.to_bytes(),
-                    EMBEDDING_DIMENSIONS as i32,
-                    "all-MiniLM-L6-v2",
+                    embedding.dimensions as i32,
+                    self.embedding_service.model_name(),
                     now.to_rfc3339(),
+                    strategy.as_str(),
+                    index_vector.to_bytes(),
+                    index_vector.dimensions as i64,
+                    language,
                 ],
             )?;
 
             writer.execute(
-                "UPDATE knowledge_nodes SET has_embedding = 1, embedding_model = 'all-MiniLM-L6-v2' WHERE id = ?1",
-                params![node_id],
+                "UPDATE knowledge_nodes SET has_embedding = 1, embedding_model = ?2 WHERE id = ?1",
+                params![node_id, self.embedding_service.model_name()],
             )?;
         }
 
         let mut index = self
             .vector_index
             .lock()
-            .map_err(|_| StorageError::Init("Vector index lock poisoned".to_string()))?;
+            .map_err(|_| StorageError::LockPoisoned("Vector index".into()))?;
         index
-            .add(node_id, &embedding.vector)
-            .map_err(|e| StorageError::Init(format!("Vector index add failed: {}", e)))?;
+            .add(node_id, &index_vector.vector)
+            .map_err(|e| StorageError::VectorIndex(format!("Vector index add failed: {}", e)))?;
+        self.metrics.embedding_generated();
 
         Ok(())
     }
 
     /// Get a node by ID
     pub fn get_node(&self, id: &str) -> Result<Option<KnowledgeNode>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
+        let reader = self.lock_reader()?;
         let mut stmt = reader
             .prepare("SELECT * FROM knowledge_nodes WHERE id = ?1")?;
 
@@ -727,94 +2305,749 @@ impl Storage {
             times_useful: row.get("times_useful").ok(),
             emotional_valence: row.get("emotional_valence").ok(),
             flashbulb: row.get::<_, Option<bool>>("flashbulb").ok().flatten(),
+            importance: row.get::<_, Option<f64>>("importance").ok().flatten(),
             temporal_level: row.get::<_, Option<String>>("temporal_level").ok().flatten(),
+            namespace: row.get::<_, Option<String>>("namespace").ok().flatten(),
         })
     }
 
     /// Recall memories matching a query
+    ///
+    /// With a namespace set, memories from other namespaces are filtered out,
+    /// and with `exclude_unavailable` so are suppressed memories. Either way
+    /// the search is widened until `limit` results are found or the
+    /// candidates run out.
+    ///
+    /// Results only receive a search impression (see `record_impressions`);
+    /// call `touch` for the ones the caller actually opens.
     pub fn recall(&self, input: RecallInput) -> Result<Vec<KnowledgeNode>> {
-        let nodes = match input.search_mode {
-            SearchMode::Keyword => {
-                self.keyword_search(&input.query, input.limit, input.min_retention)?
+        self.recall_with(input, true)
+    }
+
+    /// `recall` without even the search-impression boost
+    pub fn recall_without_strengthen(&self, input: RecallInput) -> Result<Vec<KnowledgeNode>> {
+        self.recall_with(input, false)
+    }
+
+    fn recall_with(&self, input: RecallInput, impressions: bool) -> Result<Vec<KnowledgeNode>> {
+        let unavailable = if input.exclude_unavailable {
+            self.unavailable_memory_ids()?
+        } else {
+            HashSet::new()
+        };
+        let nodes = if input.namespace.is_none() && unavailable.is_empty() {
+            self.recall_nodes(&input, input.limit)?
+        } else {
+            let namespace = input.namespace.as_deref();
+            let wanted = input.limit.max(0) as usize;
+            let mut pool = input.limit.max(1);
+            loop {
+                // Keyword search applies the namespace in SQL; semantic and
+                // hybrid results are filtered here, so keep widening until
+                // the search itself runs out of candidates
+                let found = self.recall_nodes(&input, pool)?;
+                let exhausted = found.len() < pool as usize || pool == i32::MAX;
+                let mut scoped: Vec<KnowledgeNode> = found
+                    .into_iter()
+                    .filter(|n| namespace.is_none_or(|ns| n.namespace.as_deref() == Some(ns)))
+                    .filter(|n| !unavailable.contains(&n.id))
+                    .collect();
+                if scoped.len() >= wanted || exhausted {
+                    scoped.truncate(wanted);
+                    break scoped;
+                }
+                pool = pool.saturating_mul(4);
+            }
+        };
+
+        // Being listed in results is a weak impression, not an access: a
+        // broad search must not reshape importance the way opening a memory does
+        let ids: Vec<&str> = nodes.iter().map(|n| n.id.as_str()).collect();
+        if impressions {
+            let _ = self.record_impressions(&ids); // Ignore errors, don't fail recall
+        }
+        let _ = self.mark_retrieved(&ids, Some(&input.query));
+
+        if self.retrieval_forgetting_policy().enabled {
+            let _ = self.suppress_competitors(&input, &nodes);
+        }
+
+        Ok(nodes)
+    }
+
+    /// Retrieval-induced forgetting: weaken close competitors of the top result
+    ///
+    /// The next `RIF_COMPETITOR_POOL` candidates that `recall` didn't return
+    /// compete with its top result via `CompetitionManager`. Each loser loses
+    /// retrieval strength in proportion to its similarity, never below the
+    /// policy's floor, and isn't suppressed again until its suppression
+    /// window from an earlier competition has passed.
+    fn suppress_competitors(
+        &self,
+        input: &RecallInput,
+        winners: &[KnowledgeNode],
+    ) -> Result<Option<CompetitionEvent>> {
+        let Some(winner) = winners.first() else {
+            return Ok(None);
+        };
+        let policy = self.retrieval_forgetting_policy();
+        let returned: HashSet<&str> = winners.iter().map(|n| n.id.as_str()).collect();
+        let namespace = input.namespace.as_deref();
+        let competitors: Vec<KnowledgeNode> = self
+            .recall_nodes(input, input.limit.max(1) + RIF_COMPETITOR_POOL)?
+            .into_iter()
+            .filter(|n| !returned.contains(n.id.as_str()))
+            .filter(|n| namespace.is_none_or(|ns| n.namespace.as_deref() == Some(ns)))
+            .collect();
+        if competitors.is_empty() {
+            return Ok(None);
+        }
+
+        let event = {
+            let mut manager = self.competition.lock()
+                .map_err(|_| StorageError::LockPoisoned("Competition manager".into()))?;
+            manager.config = policy.competition.clone();
+            let now = self.now();
+            let still_suppressed: HashSet<&str> = manager
+                .history
+                .iter()
+                .filter(|e| e.timestamp + e.suppression_duration > now)
+                .flat_map(|e| e.loser_ids.iter().map(String::as_str))
+                .collect();
+            let candidates: Vec<CompetitionCandidate> = std::iter::once(winner)
+                .chain(competitors.iter().filter(|n| !still_suppressed.contains(n.id.as_str())))
+                .enumerate()
+                .map(|(rank, node)| CompetitionCandidate {
+                    memory_id: node.id.clone(),
+                    relevance_score: 1.0 / (rank + 1) as f64,
+                    similarity_to_query: self.query_similarity(&input.query, node),
+                })
+                .collect();
+            if manager
+                .run_competition(&candidates, policy.competition.similarity_threshold)
+                .is_none()
+            {
+                return Ok(None);
             }
+            let Some(event) = manager.history.back_mut() else {
+                return Ok(None);
+            };
+            event.query_text = Some(input.query.clone());
+            event.clone()
+        };
+
+        let writer = self.lock_writer()?;
+        for (id, similarity) in event.loser_ids.iter().zip(&event.loser_similarities) {
+            writer.execute(
+                "UPDATE knowledge_nodes
+                 SET retrieval_strength = MAX(MIN(retrieval_strength, ?1), retrieval_strength - ?2)
+                 WHERE id = ?3",
+                params![policy.min_retrieval_strength, policy.suppression * similarity, id],
+            )?;
+        }
+        Ok(Some(event))
+    }
+
+    /// How closely a memory matches a query: embedding cosine when both are
+    /// available, otherwise the fraction of query terms the content contains
+    fn query_similarity(&self, query: &str, node: &KnowledgeNode) -> f64 {
+        #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+        if let (Ok(query_embedding), Ok(Some(embedding))) =
+            (self.get_query_embedding(query), self.get_node_embedding(&node.id))
+            && query_embedding.len() == embedding.len()
+        {
+            return cosine_similarity(&query_embedding, &embedding) as f64;
+        }
+
+        let query_terms = auto_tag::extract_terms(query);
+        if query_terms.is_empty() {
+            return 0.0;
+        }
+        let content_terms: HashSet<String> = auto_tag::extract_terms(&node.content)
+            .into_iter()
+            .map(|(term, _)| term)
+            .collect();
+        let matched = query_terms.iter().filter(|(term, _)| content_terms.contains(term)).count();
+        matched as f64 / query_terms.len() as f64
+    }
+
+    /// Retrieval competitions `recall` has run, oldest first
+    pub fn competition_history(&self) -> Vec<CompetitionEvent> {
+        self.competition
+            .lock()
+            .map(|m| m.history.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Run the search for `recall` with an explicit result limit
+    fn recall_nodes(&self, input: &RecallInput, limit: i32) -> Result<Vec<KnowledgeNode>> {
+        let nodes = match input.search_mode {
+            SearchMode::Keyword => self.keyword_search(
+                &input.query,
+                limit,
+                input.min_retention,
+                input.syntax,
+                input.namespace.as_deref(),
+            )?,
             #[cfg(all(feature = "embeddings", feature = "vector-search"))]
             SearchMode::Semantic => {
-                let results = self.semantic_search(&input.query, input.limit, 0.3)?;
+                let (_, results) = self.semantic_search_with_expansion(
+                    &input.query,
+                    limit,
+                    0.3,
+                    input.query_expansion,
+                )?;
                 results.into_iter().map(|r| r.node).collect()
             }
             #[cfg(all(feature = "embeddings", feature = "vector-search"))]
             SearchMode::Hybrid => {
-                let results = self.hybrid_search(&input.query, input.limit, 0.3, 0.7)?;
+                let config = HybridSearchConfig {
+                    query_expansion: input.query_expansion,
+                    syntax: input.syntax,
+                    ..Default::default()
+                };
+                let results = match self.session_intent() {
+                    Some(detection) => {
+                        let mut results = self.hybrid_search_with_config(
+                            &input.query,
+                            limit.saturating_mul(2),
+                            &config,
+                        )?;
+                        self.apply_intent_boost(&mut results, &detection, &config, limit);
+                        results
+                    }
+                    None => self.hybrid_search_with_config(&input.query, limit, &config)?,
+                };
                 results.into_iter().map(|r| r.node).collect()
             }
             #[cfg(not(all(feature = "embeddings", feature = "vector-search")))]
-            _ => self.keyword_search(&input.query, input.limit, input.min_retention)?,
+            _ => self.keyword_search(
+                &input.query,
+                limit,
+                input.min_retention,
+                input.syntax,
+                input.namespace.as_deref(),
+            )?,
         };
+        Ok(nodes)
+    }
 
-        // Auto-strengthen memories on access (Testing Effect - Roediger & Karpicke 2006)
-        // This implements "use it or lose it" - accessed memories get stronger
-        let ids: Vec<&str> = nodes.iter().map(|n| n.id.as_str()).collect();
-        let _ = self.strengthen_batch_on_access(&ids); // Ignore errors, don't fail recall
+    // ========================================================================
+    // BULK OPERATIONS
+    // ========================================================================
 
-        Ok(nodes)
+    /// Apply one action to many memories in a single transaction
+    ///
+    /// Returns one outcome per distinct ID, in request order; unknown IDs
+    /// are reported as not found rather than failing the batch.
+    pub fn bulk_update(&self, ids: &[String], action: &BulkAction) -> Result<Vec<BulkOutcome>> {
+        let mut seen = HashSet::new();
+        let ids: Vec<&String> = ids.iter().filter(|id| seen.insert(id.as_str())).collect();
+        let now = self.now().to_rfc3339();
+        let tags_json = match action {
+            BulkAction::Retag { tags } => {
+                serde_json::to_string(tags).unwrap_or_else(|_| "[]".to_string())
+            }
+            _ => String::new(),
+        };
+
+        let mut found = Vec::with_capacity(ids.len());
+        {
+            let mut writer = self.lock_writer()?;
+            let tx = writer.transaction()?;
+            for id in &ids {
+                let rows = match action {
+                    BulkAction::Delete => {
+                        tx.execute("DELETE FROM knowledge_nodes WHERE id = ?1", params![id])?
+                    }
+                    BulkAction::Promote => tx.execute(PROMOTE_SQL, params![now, id, 1.0])?,
+                    BulkAction::Demote => tx.execute(DEMOTE_SQL, params![now, id, 1.0])?,
+                    BulkAction::Retag { .. } => tx.execute(
+                        "UPDATE knowledge_nodes SET tags = ?1, updated_at = ?2 WHERE id = ?3",
+                        params![tags_json, now, id],
+                    )?,
+                };
+                found.push(rows > 0);
+            }
+            tx.commit()?;
+        }
+
+        let mut outcomes = Vec::with_capacity(ids.len());
+        for (id, found) in ids.into_iter().zip(found) {
+            if !found {
+                outcomes.push(BulkOutcome::not_found(id));
+                continue;
+            }
+            match action {
+                BulkAction::Delete => {
+                    #[cfg(feature = "vector-search")]
+                    if let Ok(mut index) = self.vector_index.lock() {
+                        let _ = index.remove(id);
+                    }
+                }
+                BulkAction::Promote => self.after_promote(id, 1.0, None),
+                BulkAction::Demote => self.after_demote(id, 1.0, None),
+                BulkAction::Retag { .. } => {}
+            }
+            let retention_strength = match action {
+                BulkAction::Promote | BulkAction::Demote => {
+                    self.get_node(id)?.map(|node| node.retention_strength)
+                }
+                _ => None,
+            };
+            outcomes.push(BulkOutcome {
+                id: id.clone(),
+                ok: true,
+                error: None,
+                retention_strength,
+            });
+        }
+        Ok(outcomes)
     }
 
-    /// Keyword search with FTS5
-    fn keyword_search(
-        &self,
-        query: &str,
-        limit: i32,
-        min_retention: f64,
-    ) -> Result<Vec<KnowledgeNode>> {
-        let sanitized_query = sanitize_fts5_query(query);
+    // ========================================================================
+    // NAMESPACES
+    // ========================================================================
+
+    /// A view of this storage confined to one namespace
+    pub fn with_namespace(&self, namespace: impl Into<String>) -> NamespacedStorage<'_> {
+        NamespacedStorage::new(self, namespace.into())
+    }
 
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
+    /// Namespaces holding at least one memory, alphabetically (the default
+    /// namespace is not listed)
+    pub fn list_namespaces(&self) -> Result<Vec<String>> {
+        let reader = self.lock_reader()?;
         let mut stmt = reader.prepare(
-            "SELECT n.* FROM knowledge_nodes n
-             JOIN knowledge_fts fts ON n.id = fts.id
-             WHERE knowledge_fts MATCH ?1
-             AND n.retention_strength >= ?2
-             ORDER BY n.retention_strength DESC
-             LIMIT ?3",
+            "SELECT DISTINCT namespace FROM knowledge_nodes
+             WHERE namespace IS NOT NULL ORDER BY namespace",
         )?;
+        let namespaces = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(namespaces)
+    }
 
-        let nodes = stmt.query_map(params![sanitized_query, min_retention, limit], |row| {
-            Self::row_to_node(row)
-        })?;
+    // ========================================================================
+    // SESSION INTENT
+    // ========================================================================
 
-        let mut result = Vec::new();
-        for node in nodes {
-            result.push(node?);
+    /// Infer what the user is doing from a sequence of their actions
+    ///
+    /// Actions older than the detector's 30-minute window are ignored; no
+    /// usable actions yields `DetectedIntent::Unknown` with zero confidence.
+    /// With `persist`, the detection is kept as session context and biases
+    /// hybrid `recall` towards suitable memories until the window lapses or
+    /// an Unknown detection replaces it. Without it, nothing is changed.
+    pub fn detect_intent(&self, actions: &[UserAction], persist: bool) -> Result<IntentDetectionResult> {
+        let detector = IntentDetector::new();
+        for action in actions {
+            detector.record_action(action.clone());
         }
-        Ok(result)
+        let detection = detector.detect_intent();
+
+        if persist {
+            let mut session = self.session_intent.lock()
+                .map_err(|_| StorageError::LockPoisoned("Session intent".into()))?;
+            *session = (!matches!(detection.primary_intent, DetectedIntent::Unknown))
+                .then(|| detection.clone());
+        }
+        Ok(detection)
     }
 
-    /// Mark a memory as reviewed
-    pub fn mark_reviewed(&self, id: &str, rating: Rating) -> Result<KnowledgeNode> {
-        let node = self
-            .get_node(id)?
-            .ok_or_else(|| StorageError::NotFound(id.to_string()))?;
+    /// The persisted session intent, if one was detected within the window
+    pub fn session_intent(&self) -> Option<IntentDetectionResult> {
+        let cutoff = self.now() - Duration::minutes(INTENT_WINDOW_MINUTES);
+        self.session_intent
+            .lock()
+            .ok()?
+            .clone()
+            .filter(|d| d.detected_at > cutoff)
+    }
 
-        let learning_state = match node.reps {
-            0 => LearningState::New,
-            _ if node.lapses > 0 && node.reps == node.lapses => LearningState::Relearning,
-            _ => LearningState::Review,
-        };
+    /// Forget the persisted session intent
+    pub fn clear_session_intent(&self) -> Result<()> {
+        *self.session_intent.lock()
+            .map_err(|_| StorageError::LockPoisoned("Session intent".into()))? = None;
+        Ok(())
+    }
 
-        let current_state = FSRSState {
-            difficulty: node.difficulty,
-            stability: node.stability,
-            state: learning_state,
-            reps: node.reps,
-            lapses: node.lapses,
-            last_review: node.last_accessed,
-            scheduled_days: 0,
-        };
+    // ========================================================================
+    // RECONSOLIDATION
+    // ========================================================================
 
-        let scheduler = self.scheduler.lock()
-            .map_err(|_| StorageError::Init("Scheduler lock poisoned".into()))?;
-        let elapsed_days = scheduler.days_since_review(&current_state.last_review);
+    /// Labile window in seconds, overridable via VESTIGE_LABILE_WINDOW_SECS
+    fn labile_window_from_env() -> i64 {
+        const DEFAULT_LABILE_WINDOW_SECS: i64 = 3600;
+        std::env::var("VESTIGE_LABILE_WINDOW_SECS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_LABILE_WINDOW_SECS)
+    }
+
+    /// Open the reconsolidation window for memories returned by a search
+    ///
+    /// While the window is open, a smart_ingest whose best match is one of
+    /// these memories updates it in place instead of creating a new memory.
+    pub fn mark_retrieved(&self, ids: &[&str], query: Option<&str>) -> Result<()> {
+        self.mark_retrieved_at(ids, query, self.now())
+    }
+
+    fn mark_retrieved_at(
+        &self,
+        ids: &[&str],
+        query: Option<&str>,
+        retrieved_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let co_retrieved: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+        for id in ids {
+            let Some(node) = self.get_node(id)? else {
+                continue;
+            };
+            let connection_ids = self
+                .get_connections_for_memory(id)?
+                .into_iter()
+                .map(|c| if c.source_id == *id { c.target_id } else { c.source_id })
+                .collect();
+            let snapshot = MemorySnapshot {
+                content: node.content,
+                tags: node.tags,
+                retention_strength: node.retention_strength,
+                storage_strength: node.storage_strength,
+                retrieval_strength: node.retrieval_strength,
+                connection_ids,
+                captured_at: retrieved_at,
+            };
+            let context = AccessContext {
+                trigger: AccessTrigger::Search,
+                query: query.map(String::from),
+                co_retrieved: co_retrieved.clone(),
+                session_id: None,
+            };
+            self.reconsolidation
+                .lock()
+                .map_err(|_| StorageError::LockPoisoned("Reconsolidation".into()))?
+                .mark_labile_at(id, snapshot, Some(context), retrieved_at);
+        }
+        Ok(())
+    }
+
+    /// Whether a memory is inside its reconsolidation window
+    pub fn is_labile(&self, id: &str) -> bool {
+        self.reconsolidation
+            .lock()
+            .map(|manager| manager.is_labile(id))
+            .unwrap_or(false)
+    }
+
+    /// Time left in a memory's reconsolidation window, if it is labile
+    pub fn labile_remaining(&self, id: &str) -> Option<Duration> {
+        self.reconsolidation
+            .lock()
+            .ok()
+            .and_then(|manager| manager.remaining_labile_time(id))
+    }
+
+    /// Queue a modification on a labile memory; false if its window has closed
+    pub fn apply_reconsolidation(&self, id: &str, modification: Modification) -> bool {
+        self.reconsolidation
+            .lock()
+            .map(|mut manager| manager.apply_modification(id, modification))
+            .unwrap_or(false)
+    }
+
+    /// Apply a final modification and reconsolidate the memory immediately
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn reconsolidate_with(
+        &self,
+        id: &str,
+        modification: Modification,
+    ) -> Result<Option<ReconsolidatedMemory>> {
+        let mut manager = self
+            .reconsolidation
+            .lock()
+            .map_err(|_| StorageError::LockPoisoned("Reconsolidation".into()))?;
+        if !manager.apply_modification(id, modification) {
+            return Ok(None);
+        }
+        Ok(manager.reconsolidate(id))
+    }
+
+    /// Close expired reconsolidation windows with their queued modifications
+    pub fn reconsolidate_expired(&self) -> Result<Vec<ReconsolidatedMemory>> {
+        Ok(self
+            .reconsolidation
+            .lock()
+            .map_err(|_| StorageError::LockPoisoned("Reconsolidation".into()))?
+            .reconsolidate_expired())
+    }
+
+    /// Counts of memories made labile, modified, and reconsolidated
+    pub fn get_reconsolidation_stats(&self) -> Result<ReconsolidationStats> {
+        Ok(self
+            .reconsolidation
+            .lock()
+            .map_err(|_| StorageError::LockPoisoned("Reconsolidation".into()))?
+            .get_stats()
+            .clone())
+    }
+
+    // ========================================================================
+    // SYNAPTIC TAGGING AND CAPTURE
+    // ========================================================================
+
+    /// Tag a weakly encoded memory so a later importance event can capture it
+    ///
+    /// Re-tagging keeps the original tag time. Captures are applied during
+    /// consolidation by `apply_synaptic_captures`.
+    pub fn tag_for_capture(&self, id: &str) -> Result<()> {
+        self.tag_for_capture_at(id, self.now())
+    }
+
+    fn tag_for_capture_at(&self, id: &str, tagged_at: DateTime<Utc>) -> Result<()> {
+        let writer = self.lock_writer()?;
+        let exists: bool = writer.query_row(
+            "SELECT EXISTS(SELECT 1 FROM knowledge_nodes WHERE id = ?1)",
+            params![id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(StorageError::NotFound(id.to_string()));
+        }
+        writer.execute(
+            "INSERT OR IGNORE INTO synaptic_tags (memory_id, tagged_at, initial_strength)
+             VALUES (?1, ?2, 1.0)",
+            params![id, tagged_at.to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Record an importance event for the next consolidation to act on
+    pub fn record_importance_event(&self, event: &ImportanceEvent) -> Result<()> {
+        let writer = self.lock_writer()?;
+        writer.execute(
+            "INSERT INTO importance_events (event_type, memory_id, strength, context, occurred_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                event.event_type.to_string(),
+                event.memory_id,
+                event.strength,
+                event.context,
+                event.timestamp.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get the persisted synaptic tag for a memory
+    pub fn get_synaptic_tag(&self, id: &str) -> Result<Option<SynapticTag>> {
+        let reader = self.lock_reader()?;
+        let row = reader
+            .query_row(
+                "SELECT tagged_at, initial_strength, captured_at, capture_event
+                 FROM synaptic_tags WHERE memory_id = ?1",
+                params![id],
+                |row| {
+                    let captured_at = row
+                        .get::<_, Option<String>>(2)?
+                        .map(|s| Self::parse_timestamp(&s, "captured_at"))
+                        .transpose()?;
+                    Ok((
+                        Self::parse_timestamp(&row.get::<_, String>(0)?, "tagged_at")?,
+                        row.get::<_, f64>(1)?,
+                        captured_at,
+                        row.get::<_, Option<String>>(3)?,
+                    ))
+                },
+            )
+            .optional()?;
+
+        Ok(row.map(|(tagged_at, initial_strength, captured_at, capture_event)| {
+            let mut tag = SynapticTag::with_strength(id, initial_strength);
+            tag.created_at = tagged_at;
+            tag.captured_at = captured_at;
+            tag.captured = tag.captured_at.is_some();
+            tag.capture_event = capture_event;
+            tag
+        }))
+    }
+
+    /// Let pending importance events capture tagged memories
+    ///
+    /// Captured memories gain storage strength in proportion to their
+    /// consolidated importance, and the capture is recorded on the tag.
+    /// Events stay pending until their forward window has passed so that
+    /// memories tagged shortly after an event can still be captured.
+    /// Uncaptured tags older than the tag lifetime are dropped.
+    pub fn apply_synaptic_captures(&self) -> Result<Vec<CapturedMemory>> {
+        let config = SynapticTaggingConfig::for_consolidation();
+        let now = self.now();
+
+        let (tags, events) = {
+            let reader = self.lock_reader()?;
+
+            let mut stmt = reader.prepare(
+                "SELECT memory_id, tagged_at, initial_strength FROM synaptic_tags
+                 WHERE captured_at IS NULL",
+            )?;
+            let tags = stmt
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        Self::parse_timestamp(&row.get::<_, String>(1)?, "tagged_at")?,
+                        row.get::<_, f64>(2)?,
+                    ))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            let mut stmt = reader.prepare(
+                "SELECT id, event_type, memory_id, strength, context, occurred_at
+                 FROM importance_events WHERE processed_at IS NULL
+                 ORDER BY occurred_at ASC",
+            )?;
+            let events = stmt
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                        row.get::<_, f64>(3)?,
+                        row.get::<_, Option<String>>(4)?,
+                        Self::parse_timestamp(&row.get::<_, String>(5)?, "occurred_at")?,
+                    ))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            (tags, events)
+        };
+
+        let mut system = SynapticTaggingSystem::with_config(config.clone()).with_clock(self.clock());
+        for (memory_id, tagged_at, initial_strength) in tags {
+            let mut tag = SynapticTag::with_strength(&memory_id, initial_strength);
+            tag.created_at = tagged_at;
+            system.restore_tag(tag);
+        }
+
+        let mut captured = Vec::new();
+        let mut processed = Vec::new();
+        for (row_id, event_type, memory_id, strength, context, occurred_at) in events {
+            let Some(event_type) = ImportanceEventType::from_name(&event_type) else {
+                processed.push(row_id);
+                continue;
+            };
+            let event = ImportanceEvent {
+                event_type,
+                memory_id,
+                timestamp: occurred_at,
+                strength,
+                context,
+            };
+            let forward_minutes = config.capture_window.forward_hours
+                * event_type.capture_radius_multiplier()
+                * 60.0;
+            if event.timestamp + Duration::minutes(forward_minutes as i64) <= now {
+                processed.push(row_id);
+            }
+            captured.extend(system.trigger_prp(event).captured_memories);
+        }
+
+        let expired_before = now - Duration::minutes((config.tag_lifetime_hours * 60.0) as i64);
+        let mut writer = self.lock_writer()?;
+        let tx = writer.transaction()?;
+        for memory in &captured {
+            tx.execute(
+                "UPDATE knowledge_nodes
+                 SET storage_strength = MIN(10.0, storage_strength * (1.0 + ?1))
+                 WHERE id = ?2",
+                params![memory.consolidated_importance, memory.memory_id],
+            )?;
+            tx.execute(
+                "UPDATE synaptic_tags
+                 SET captured_at = ?1, capture_event = ?2, consolidated_importance = ?3
+                 WHERE memory_id = ?4",
+                params![
+                    memory.captured_at.to_rfc3339(),
+                    memory.capture_event_id,
+                    memory.consolidated_importance,
+                    memory.memory_id,
+                ],
+            )?;
+        }
+        for row_id in processed {
+            tx.execute(
+                "UPDATE importance_events SET processed_at = ?1 WHERE id = ?2",
+                params![now.to_rfc3339(), row_id],
+            )?;
+        }
+        tx.execute(
+            "DELETE FROM synaptic_tags WHERE captured_at IS NULL AND tagged_at < ?1",
+            params![expired_before.to_rfc3339()],
+        )?;
+        tx.commit()?;
+
+        Ok(captured)
+    }
+
+    /// Keyword search with FTS5, optionally limited to one namespace
+    fn keyword_search(
+        &self,
+        query: &str,
+        limit: i32,
+        min_retention: f64,
+        syntax: SearchSyntax,
+        namespace: Option<&str>,
+    ) -> Result<Vec<KnowledgeNode>> {
+        let sanitized_query = sanitize_fts5_query_with(query, syntax);
+
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT n.* FROM knowledge_nodes n
+             JOIN knowledge_fts fts ON n.id = fts.id
+             WHERE knowledge_fts MATCH ?1
+             AND n.retention_strength >= ?2
+             AND (?4 IS NULL OR n.namespace = ?4)
+             ORDER BY n.retention_strength DESC
+             LIMIT ?3",
+        )?;
+
+        let nodes = stmt.query_map(
+            params![sanitized_query, min_retention, limit, namespace],
+            Self::row_to_node,
+        )?;
+
+        let mut result = Vec::new();
+        for node in nodes {
+            result.push(node?);
+        }
+        Ok(result)
+    }
+
+    /// Mark a memory as reviewed
+    pub fn mark_reviewed(&self, id: &str, rating: Rating) -> Result<KnowledgeNode> {
+        let node = self
+            .get_node(id)?
+            .ok_or_else(|| StorageError::NotFound(id.to_string()))?;
+
+        let learning_state = match node.reps {
+            0 => LearningState::New,
+            _ if node.lapses > 0 && node.reps == node.lapses => LearningState::Relearning,
+            _ => LearningState::Review,
+        };
+
+        let current_state = FSRSState {
+            difficulty: node.difficulty,
+            stability: node.stability,
+            state: learning_state,
+            reps: node.reps,
+            lapses: node.lapses,
+            last_review: node.last_accessed,
+            scheduled_days: 0,
+        };
+
+        let scheduler = self.scheduler.lock()
+            .map_err(|_| StorageError::LockPoisoned("Scheduler".into()))?;
+        let elapsed_days = scheduler.days_since_review_at(&current_state.last_review, self.now());
 
         let sentiment_boost = if node.sentiment_magnitude > 0.0 {
             Some(node.sentiment_magnitude)
@@ -826,7 +3059,7 @@ impl Storage {
             .review(&current_state, rating, elapsed_days, sentiment_boost);
         drop(scheduler);
 
-        let now = Utc::now();
+        let now = self.now();
         let next_review = now + Duration::days(result.interval as i64);
 
         let new_storage_strength = if rating != Rating::Again {
@@ -840,8 +3073,7 @@ impl Storage {
             (new_retrieval_strength * 0.7) + ((new_storage_strength / 10.0).min(1.0) * 0.3);
 
         {
-            let writer = self.writer.lock()
-                .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
+            let writer = self.lock_writer()?;
             writer.execute(
                 "UPDATE knowledge_nodes SET
                     stability = ?1,
@@ -874,6 +3106,7 @@ impl Storage {
                 ],
             )?;
         }
+        self.note_mutation();
 
         self.get_node(id)?
             .ok_or_else(|| StorageError::NotFound(id.to_string()))
@@ -882,77 +3115,233 @@ impl Storage {
     /// Passively strengthen a memory when it's accessed (recalled/searched).
     /// Implements the Testing Effect (Roediger & Karpicke 2006) + v1.4.0
     /// content-aware cross-memory reinforcement: semantically similar neighbors
-    /// receive a diminished boost proportional to cosine similarity.
+    /// receive a diminished boost proportional to cosine similarity, applied
+    /// in deferred batches (see `flush_neighbor_reinforcement`).
+    ///
+    /// Boost sizes come from the `ReinforcementConfig`.
     pub fn strengthen_on_access(&self, id: &str) -> Result<()> {
-        let now = Utc::now();
+        self.reinforce_access(id, AccessKind::SearchHit)
+    }
 
-        // Primary boost on the accessed node
-        {
-            let writer = self.writer.lock()
-                .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-            writer.execute(
+    /// Deliberately open a memory: the full testing-effect boost of
+    /// `strengthen_on_access`, logged as an "open" access
+    ///
+    /// Use this for results the caller actually reads, rather than for
+    /// everything a search returned.
+    pub fn touch(&self, id: &str) -> Result<()> {
+        if self.get_node(id)?.is_none() {
+            return Err(StorageError::NotFound(id.to_string()));
+        }
+        self.reinforce_access(id, AccessKind::Open)
+    }
+
+    /// Record that memories were shown in search results without being opened
+    ///
+    /// Each gets `impression_weight` of the access boost. Unlike an access,
+    /// an impression doesn't reset `last_accessed` (so FSRS decay continues),
+    /// isn't logged, and doesn't reinforce neighbors.
+    pub fn record_impressions(&self, ids: &[&str]) -> Result<()> {
+        let config = self.reinforcement_config();
+        if ids.is_empty() || config.impression_weight <= 0.0 {
+            return Ok(());
+        }
+        let mut writer = self.lock_writer()?;
+        let tx = writer.transaction()?;
+        for id in ids {
+            tx.execute(
                 "UPDATE knowledge_nodes SET
-                    last_accessed = ?1,
-                    retrieval_strength = MIN(1.0, retrieval_strength + 0.05),
-                    retention_strength = MIN(1.0, retention_strength + 0.02),
-                    times_retrieved = COALESCE(times_retrieved, 0) + 1,
-                    utility_score = CASE
-                        WHEN COALESCE(times_retrieved, 0) + 1 > 0
-                        THEN CAST(COALESCE(times_useful, 0) AS REAL) / (COALESCE(times_retrieved, 0) + 1)
-                        ELSE 0.0
-                    END
-                WHERE id = ?2",
-                params![now.to_rfc3339(), id],
+                    retrieval_strength = MIN(1.0, retrieval_strength + ?1),
+                    retention_strength = MIN(1.0, retention_strength + ?2)
+                WHERE id = ?3",
+                params![
+                    config.retrieval_boost * config.impression_weight,
+                    config.retention_boost * config.impression_weight,
+                    id
+                ],
             )?;
         }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Apply the access boost to `id` and queue its neighbors, logging a `kind` access
+    fn reinforce_access(&self, id: &str, kind: AccessKind) -> Result<()> {
+        self.reinforce_accesses(&[id], kind)
+    }
+
+    /// Apply the access boost to each distinct id in one statement, log the
+    /// accesses and queue the ids for neighbor reinforcement
+    ///
+    /// The queue is drained here once `neighbor_flush_secs` have passed since
+    /// the last drain; see `flush_neighbor_reinforcement`.
+    fn reinforce_accesses(&self, ids: &[&str], kind: AccessKind) -> Result<()> {
+        let mut ids = ids.to_vec();
+        ids.sort_unstable();
+        ids.dedup();
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let now = self.now();
+        let config = self.reinforcement_config();
+
+        // Primary boost on the accessed nodes, with their access log and
+        // usage events, in one transaction
+        {
+            let mut writer = self.lock_writer()?;
+            let tx = writer.transaction()?;
+            for chunk in ids.chunks(REINFORCEMENT_BATCH_SIZE) {
+                let placeholders = chunk.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+                let sql = format!(
+                    "UPDATE knowledge_nodes SET
+                        last_accessed = ?1,
+                        retrieval_strength = MIN(1.0, retrieval_strength + ?2),
+                        retention_strength = MIN(1.0, retention_strength + ?3),
+                        times_retrieved = COALESCE(times_retrieved, 0) + 1,
+                        utility_score = CASE
+                            WHEN COALESCE(times_retrieved, 0) + 1 > 0
+                            THEN CAST(COALESCE(times_useful, 0) AS REAL) / (COALESCE(times_retrieved, 0) + 1)
+                            ELSE 0.0
+                        END
+                    WHERE id IN ({})",
+                    placeholders
+                );
+                let now_str = now.to_rfc3339();
+                let mut params: Vec<&dyn rusqlite::ToSql> =
+                    vec![&now_str, &config.retrieval_boost, &config.retention_boost];
+                params.extend(chunk.iter().map(|id| id as &dyn rusqlite::ToSql));
+                tx.execute(&sql, params.as_slice())?;
+            }
+
+            // Logged for ACT-R activation and usage-evolved importance
+            let now_str = now.to_rfc3339();
+            {
+                let mut log = tx.prepare(
+                    "INSERT INTO memory_access_log (node_id, access_type, accessed_at)
+                     SELECT id, ?2, ?3 FROM knowledge_nodes WHERE id = ?1",
+                )?;
+                let mut usage = tx.prepare(
+                    "INSERT INTO usage_events (memory_id, was_helpful, context, occurred_at)
+                     SELECT id, 1, 'access', ?2 FROM knowledge_nodes WHERE id = ?1",
+                )?;
+                for id in &ids {
+                    log.execute(params![id, kind.as_str(), now_str])?;
+                    usage.execute(params![id, now_str])?;
+                }
+            }
+            tx.commit()?;
+        }
+
+        // Content-aware cross-memory reinforcement, deferred
+        if config.neighbors {
+            let due = {
+                let mut queue = self.neighbor_queue.lock()
+                    .map_err(|_| StorageError::LockPoisoned("Neighbor queue".into()))?;
+                for id in &ids {
+                    queue.push(id);
+                }
+                queue.is_due(now, Duration::seconds(config.neighbor_flush_secs as i64))
+            };
+            if due && let Err(e) = self.flush_neighbor_reinforcement() {
+                tracing::warn!("Failed to reinforce neighbors: {}", e);
+            }
+        }
+
+        Ok(())
+    }
 
-        // Log access for ACT-R activation computation
-        let _ = self.log_access(id, "search_hit");
+    /// Reinforce the semantic neighbors of every memory accessed since the
+    /// last drain, returning how many neighbors were boosted
+    ///
+    /// Each neighbor with at least `neighbor_min_similarity` gets the
+    /// similarity-scaled neighbor boosts once per queued access, limited to
+    /// `neighbor_hourly_cap` retrieval strength per neighbor per hour.
+    /// Accesses drain on their own every `neighbor_flush_secs`, at the
+    /// start of each consolidation run and when the `Storage` is dropped;
+    /// call this to drain them now. The queue is not persisted, so a crash
+    /// loses up to `neighbor_flush_secs` of queued accesses.
+    pub fn flush_neighbor_reinforcement(&self) -> Result<usize> {
+        let now = self.now();
+        let drained = self.neighbor_queue.lock()
+            .map_err(|_| StorageError::LockPoisoned("Neighbor queue".into()))?
+            .drain(now);
+        if drained.is_empty() {
+            return Ok(0);
+        }
 
-        // Content-aware cross-memory reinforcement: boost semantically similar neighbors
         #[cfg(all(feature = "embeddings", feature = "vector-search"))]
         {
-            if let Ok(Some(embedding)) = self.get_node_embedding(id) {
-                let index = self
+            let config = self.reinforcement_config();
+            // Neighbor → (retrieval boost, retention boost) summed over accesses
+            let mut boosts: HashMap<String, (f64, f64)> = HashMap::new();
+            for (id, accesses) in &drained {
+                let Ok(Some(embedding)) = self.get_node_embedding(id) else {
+                    continue;
+                };
+                // Top 6 similar; one will be the memory itself
+                let neighbors = self
                     .vector_index
                     .lock()
-                    .map_err(|_| StorageError::Init("Vector index lock poisoned".to_string()))?;
+                    .map_err(|_| StorageError::LockPoisoned("Vector index".into()))?
+                    .search(&embedding, 6);
+                let Ok(neighbors) = neighbors else { continue };
+                for (neighbor_id, similarity) in neighbors {
+                    if neighbor_id == *id || (similarity as f64) < config.neighbor_min_similarity {
+                        continue;
+                    }
+                    // Diminished boost, proportional to similarity
+                    let boost = boosts.entry(neighbor_id).or_insert((0.0, 0.0));
+                    boost.0 += config.neighbor_retrieval_boost * similarity as f64 * *accesses as f64;
+                    boost.1 += config.neighbor_retention_boost * similarity as f64 * *accesses as f64;
+                }
+            }
 
-                // Query top-6 similar (one will be self, so we get ~5 neighbors)
-                let neighbors_result = index.search(&embedding, 6);
-                drop(index);
+            let granted: Vec<(String, f64, f64)> = {
+                let mut queue = self.neighbor_queue.lock()
+                    .map_err(|_| StorageError::LockPoisoned("Neighbor queue".into()))?;
+                queue.prune(now);
+                boosts
+                    .into_iter()
+                    .filter_map(|(id, (retrieval, retention))| {
+                        let allowed = queue.grant(&id, retrieval, config.neighbor_hourly_cap, now);
+                        // Retention is held back in the same proportion
+                        let scale = if retrieval > 0.0 { allowed / retrieval } else { 0.0 };
+                        (allowed > 0.0).then_some((id, allowed, retention * scale))
+                    })
+                    .collect()
+            };
 
-                if let Ok(neighbors) = neighbors_result {
-                    let writer = self.writer.lock()
-                        .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-                    for (neighbor_id, similarity) in neighbors {
-                        if neighbor_id == id || similarity < 0.7 {
-                            continue;
-                        }
-                        // Diminished boost: 0.02 * similarity (max ~0.02)
-                        let boost = 0.02 * similarity as f64;
-                        let retention_boost = 0.008 * similarity as f64;
-                        let _ = writer.execute(
-                            "UPDATE knowledge_nodes SET
-                                retrieval_strength = MIN(1.0, retrieval_strength + ?1),
-                                retention_strength = MIN(1.0, retention_strength + ?2)
-                            WHERE id = ?3",
-                            params![boost, retention_boost, neighbor_id],
-                        );
-                    }
+            let mut writer = self.lock_writer()?;
+            let tx = writer.transaction()?;
+            {
+                let mut update = tx.prepare(
+                    "UPDATE knowledge_nodes SET
+                        retrieval_strength = MIN(1.0, retrieval_strength + ?1),
+                        retention_strength = MIN(1.0, retention_strength + ?2)
+                    WHERE id = ?3",
+                )?;
+                for (id, retrieval, retention) in &granted {
+                    update.execute(params![retrieval, retention, id])?;
                 }
             }
+            tx.commit()?;
+            Ok(granted.len())
         }
 
-        Ok(())
+        #[cfg(not(all(feature = "embeddings", feature = "vector-search")))]
+        Ok(0)
+    }
+
+    /// Queued accesses whose neighbors have not been reinforced yet
+    pub fn pending_neighbor_reinforcements(&self) -> usize {
+        self.neighbor_queue.lock().map(|q| q.pending()).unwrap_or(0)
     }
 
-    /// Batch strengthen multiple memories on access
+    /// Strengthen multiple memories on access in one batch
+    ///
+    /// Equivalent to `strengthen_on_access` for each distinct id, but the
+    /// boosts and access log entries are written in a single transaction.
     pub fn strengthen_batch_on_access(&self, ids: &[&str]) -> Result<()> {
-        for id in ids {
-            self.strengthen_on_access(id)?;
-        }
-        Ok(())
+        self.reinforce_accesses(ids, AccessKind::SearchHit)
     }
 
     /// Mark a memory as "useful" — called when a retrieved memory is subsequently
@@ -960,8 +3349,7 @@ impl Storage {
     ///
     /// Increments `times_useful` and recomputes `utility_score = times_useful / times_retrieved`.
     pub fn mark_memory_useful(&self, id: &str) -> Result<()> {
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
+        let writer = self.lock_writer()?;
         writer.execute(
             "UPDATE knowledge_nodes SET
                 times_useful = COALESCE(times_useful, 0) + 1,
@@ -973,84 +3361,403 @@ impl Storage {
             WHERE id = ?1",
             params![id],
         )?;
+        drop(writer);
+        let _ = self.log_access(id, AccessKind::Reference.as_str());
+        let _ = self.record_usage_event(id, true, "useful");
+        Ok(())
+    }
+
+    /// Record a usage event for the next consolidation to fold into the
+    /// memory's evolving importance
+    fn record_usage_event(&self, id: &str, was_helpful: bool, context: &str) -> Result<()> {
+        let writer = self.lock_writer()?;
+        writer.execute(
+            "INSERT INTO usage_events (memory_id, was_helpful, context, occurred_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![id, was_helpful, context, self.now().to_rfc3339()],
+        )?;
         Ok(())
     }
 
     /// Log a memory access event for ACT-R activation computation
     fn log_access(&self, node_id: &str, access_type: &str) -> Result<()> {
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
+        self.log_access_with_details(node_id, access_type, 1.0, None)
+    }
+
+    /// Log a weighted access along with free-text details, e.g. a feedback
+    /// reason
+    fn log_access_with_details(
+        &self,
+        node_id: &str,
+        access_type: &str,
+        weight: f64,
+        details: Option<&str>,
+    ) -> Result<()> {
+        let writer = self.lock_writer()?;
         writer.execute(
-            "INSERT INTO memory_access_log (node_id, access_type, accessed_at)
-             VALUES (?1, ?2, ?3)",
-            params![node_id, access_type, Utc::now().to_rfc3339()],
+            "INSERT INTO memory_access_log (node_id, access_type, accessed_at, weight, details)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![node_id, access_type, self.now().to_rfc3339(), weight, details],
+        )?;
+        Ok(())
+    }
+
+    /// Log an access of `kind` with a weight on top of the kind's own
+    ///
+    /// Only the access log is written, so this feeds ACT-R activation and
+    /// the access history without boosting strengths; use `touch` or
+    /// `strengthen_on_access` for that.
+    pub fn log_access_typed(&self, id: &str, kind: AccessKind, weight: f64) -> Result<()> {
+        if !weight.is_finite() || weight <= 0.0 {
+            return Err(StorageError::InvalidInput {
+                field: "weight",
+                reason: format!("must be a positive number, got {}", weight),
+            });
+        }
+        if self.get_node(id)?.is_none() {
+            return Err(StorageError::NotFound(id.to_string()));
+        }
+        let writer = self.lock_writer()?;
+        writer.execute(
+            "INSERT INTO memory_access_log (node_id, access_type, accessed_at, weight)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![id, kind.as_str(), self.now().to_rfc3339(), weight],
         )?;
         Ok(())
     }
 
+    /// A memory's most recent accesses, newest first
+    ///
+    /// The log keeps 90 days of history (see `prune_access_log`).
+    pub fn get_access_history(&self, id: &str, limit: usize) -> Result<Vec<AccessRecord>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT access_type, weight, details, accessed_at FROM memory_access_log
+             WHERE node_id = ?1
+             ORDER BY accessed_at DESC, id DESC
+             LIMIT ?2",
+        )?;
+        let records = stmt
+            .query_map(params![id, limit as i64], |row| {
+                Ok(AccessRecord {
+                    access_type: row.get(0)?,
+                    weight: row.get(1)?,
+                    details: row.get(2)?,
+                    accessed_at: Self::parse_timestamp(&row.get::<_, String>(3)?, "accessed_at")?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(records)
+    }
+
     /// Promote a memory (thumbs up) - used when a memory led to a good outcome
     /// Significantly boosts retrieval strength so it surfaces more often.
     /// v1.9.0: Also sets waking SWR tag for preferential dream replay.
     pub fn promote_memory(&self, id: &str) -> Result<KnowledgeNode> {
-        let now = Utc::now();
+        self.promote_memory_with(id, &FeedbackOptions::default())
+    }
+
+    /// Promote a memory with graded strength and a logged reason
+    ///
+    /// `strength` scales the default boost; `invalidate` is ignored.
+    pub fn promote_memory_with(&self, id: &str, options: &FeedbackOptions) -> Result<KnowledgeNode> {
+        let strength = options.validated_strength()?;
+        let now = self.now();
 
-        // Strong boost: +0.2 retrieval, +0.1 retention
+        // Strong boost: +0.2 retrieval, +0.1 retention at full strength
         {
-            let writer = self.writer.lock()
-                .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-            writer.execute(
-                "UPDATE knowledge_nodes SET
-                    last_accessed = ?1,
-                    retrieval_strength = MIN(1.0, retrieval_strength + 0.20),
-                    retention_strength = MIN(1.0, retention_strength + 0.10),
-                    stability = stability * 1.5
-                WHERE id = ?2",
-                params![now.to_rfc3339(), id],
-            )?;
+            let writer = self.lock_writer()?;
+            writer.execute(PROMOTE_SQL, params![now.to_rfc3339(), id, strength])?;
         }
+        self.note_mutation();
 
-        let _ = self.log_access(id, "promote");
-
-        // v1.9.0: Set waking SWR tag for preferential dream replay
-        let _ = self.set_waking_tag(id);
+        self.after_promote(id, strength, options.reason());
 
         self.get_node(id)?
             .ok_or_else(|| StorageError::NotFound(id.to_string()))
     }
 
+    /// Side effects of a promotion at `strength`, outside the strength update
+    fn after_promote(&self, id: &str, strength: f64, reason: Option<&str>) {
+        let _ = self.log_access_with_details(id, AccessKind::Promote.as_str(), strength, reason);
+        let _ = self.record_usage_event(id, true, "promote");
+
+        // Promotion is an importance event: weak memories tagged in the
+        // hours around it are captured at the next consolidation
+        let _ = self.record_importance_event(&ImportanceEvent::user_flag(id, Some("promote")));
+
+        // v1.9.0: Set waking SWR tag for preferential dream replay
+        let _ = self.set_waking_tag(id);
+    }
+
     /// Demote a memory (thumbs down) - used when a memory led to a bad outcome
     /// Significantly reduces retrieval strength so better alternatives surface
     /// Does NOT delete - the memory stays for reference but ranks lower
     pub fn demote_memory(&self, id: &str) -> Result<KnowledgeNode> {
-        let now = Utc::now();
+        self.demote_memory_with(id, &FeedbackOptions::default())
+    }
+
+    /// Demote a memory with graded strength and a logged reason
+    ///
+    /// With `invalidate` the memory is also marked no longer valid
+    /// (`valid_until` = now), for explicit corrections; that needs a reason.
+    pub fn demote_memory_with(&self, id: &str, options: &FeedbackOptions) -> Result<KnowledgeNode> {
+        let strength = options.validated_strength()?;
+        if options.invalidate && options.reason().is_none() {
+            return Err(StorageError::InvalidInput {
+                field: "reason",
+                reason: "invalidating a memory needs the correction as a reason".into(),
+            });
+        }
+        let now = self.now().to_rfc3339();
 
-        // Strong penalty: -0.3 retrieval, -0.15 retention, halve stability
+        // Strong penalty: -0.3 retrieval, -0.15 retention, halve stability at full strength
         {
-            let writer = self.writer.lock()
-                .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-            writer.execute(
-                "UPDATE knowledge_nodes SET
-                    last_accessed = ?1,
-                    retrieval_strength = MAX(0.05, retrieval_strength - 0.30),
-                    retention_strength = MAX(0.05, retention_strength - 0.15),
-                    stability = stability * 0.5
-                WHERE id = ?2",
-                params![now.to_rfc3339(), id],
-            )?;
+            let mut writer = self.lock_writer()?;
+            let tx = writer.transaction()?;
+            tx.execute(DEMOTE_SQL, params![now, id, strength])?;
+            if options.invalidate {
+                tx.execute(
+                    "UPDATE knowledge_nodes SET valid_until = ?1, updated_at = ?1 WHERE id = ?2",
+                    params![now, id],
+                )?;
+            }
+            tx.commit()?;
         }
+        self.note_mutation();
 
-        let _ = self.log_access(id, "demote");
+        self.after_demote(id, strength, options.reason());
 
         self.get_node(id)?
             .ok_or_else(|| StorageError::NotFound(id.to_string()))
     }
 
+    /// Side effects of a demotion at `strength`, outside the strength update
+    fn after_demote(&self, id: &str, strength: f64, reason: Option<&str>) {
+        let _ = self.log_access_with_details(id, AccessKind::Demote.as_str(), strength, reason);
+        let _ = self.record_usage_event(id, false, "demote");
+    }
+
+    /// Report how a task informed by `memory_ids` turned out
+    ///
+    /// `magnitude` (0..=1) scales the outcome's reward. Positive outcomes
+    /// strengthen the memories beyond a plain promote and raise their
+    /// importance; negative ones demote them, lower their importance and
+    /// make them due for review. Each outcome is persisted, logged for the
+    /// FSRS optimizer (as an Easy..Again review) and queued for the
+    /// importance tracker. All memories are updated or none are.
+    pub fn record_outcome(
+        &self,
+        memory_ids: &[&str],
+        outcome: OutcomeType,
+        magnitude: f32,
+        note: Option<String>,
+    ) -> Result<Vec<KnowledgeNode>> {
+        if !(0.0..=1.0).contains(&magnitude) {
+            return Err(StorageError::InvalidInput {
+                field: "magnitude",
+                reason: format!("must be between 0.0 and 1.0, got {}", magnitude),
+            });
+        }
+        if memory_ids.is_empty() {
+            return Err(StorageError::InvalidInput {
+                field: "memory_ids",
+                reason: "at least one memory is required".into(),
+            });
+        }
+        let note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        let reward = outcome.reward() * magnitude as f64;
+        let now = self.now().to_rfc3339();
+
+        {
+            let mut writer = self.lock_writer()?;
+            let tx = writer.transaction()?;
+            for id in memory_ids {
+                let updated = if reward > 0.0 {
+                    tx.execute(OUTCOME_REWARD_SQL, params![now, id, reward])?
+                } else if reward < 0.0 {
+                    let updated = tx.execute(DEMOTE_SQL, params![now, id, -reward])?;
+                    tx.execute(OUTCOME_PENALTY_SQL, params![now, id, -reward])?;
+                    updated
+                } else {
+                    tx.execute("UPDATE knowledge_nodes SET last_accessed = ?1 WHERE id = ?2", params![now, id])?
+                };
+                if updated == 0 {
+                    return Err(StorageError::NotFound(id.to_string()));
+                }
+                tx.execute(
+                    "INSERT INTO memory_outcomes (memory_id, outcome, magnitude, note, recorded_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![id, outcome.as_str(), magnitude as f64, note, now],
+                )?;
+            }
+            tx.commit()?;
+        }
+
+        let access_type = format!("outcome_{}", outcome.as_str());
+        let context = format!("outcome:{}", outcome.as_str());
+        let mut nodes = Vec::with_capacity(memory_ids.len());
+        for id in memory_ids {
+            let _ = self.log_access_with_details(id, &access_type, 1.0, note.as_deref());
+            if reward != 0.0 {
+                let _ = self.record_usage_event(id, reward > 0.0, &context);
+            }
+            if reward > 0.0 {
+                let _ = self.record_importance_event(&ImportanceEvent::user_flag(id, Some("outcome")));
+            }
+            nodes.push(self.get_node(id)?.ok_or_else(|| StorageError::NotFound(id.to_string()))?);
+        }
+        Ok(nodes)
+    }
+
+    /// Outcomes reported against a memory, newest first
+    pub fn get_outcomes(&self, memory_id: &str, limit: usize) -> Result<Vec<OutcomeRecord>> {
+        let reader = self.lock_reader()?;
+        let outcomes = reader
+            .prepare(
+                "SELECT memory_id, outcome, magnitude, note, recorded_at FROM memory_outcomes
+                 WHERE memory_id = ?1 ORDER BY recorded_at DESC, id DESC LIMIT ?2",
+            )?
+            .query_map(params![memory_id, limit as i64], |row| {
+                let outcome: String = row.get(1)?;
+                Ok(OutcomeRecord {
+                    memory_id: row.get(0)?,
+                    outcome: outcome.parse().map_err(|e: String| {
+                        rusqlite::Error::FromSqlConversionFailure(
+                            1,
+                            rusqlite::types::Type::Text,
+                            Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+                        )
+                    })?,
+                    magnitude: row.get::<_, f64>(2)? as f32,
+                    note: row.get(3)?,
+                    recorded_at: Self::parse_timestamp(&row.get::<_, String>(4)?, "recorded_at")?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(outcomes)
+    }
+
+    /// Pin a memory so it ranks higher in hybrid search and is exempt from
+    /// decay, GC and compression
+    ///
+    /// `until` sets an expiry, after which consolidation unpins it; `None`
+    /// pins indefinitely. Pinning an already pinned memory replaces its TTL.
+    pub fn pin_memory(&self, id: &str, until: Option<DateTime<Utc>>) -> Result<PinnedMemory> {
+        let node = self.get_node(id)?.ok_or_else(|| StorageError::NotFound(id.to_string()))?;
+        let now = self.now();
+        if until.is_some_and(|until| until <= now) {
+            return Err(StorageError::InvalidInput {
+                field: "until",
+                reason: "pin expiry must be in the future".to_string(),
+            });
+        }
+
+        let writer = self.lock_writer()?;
+        writer.execute(
+            "INSERT OR REPLACE INTO memory_pins (memory_id, pinned_at, pinned_until)
+             VALUES (?1, ?2, ?3)",
+            params![id, now.to_rfc3339(), until.map(|t| t.to_rfc3339())],
+        )?;
+        drop(writer);
+        let _ = self.log_access(id, AccessKind::Pin.as_str());
+
+        Ok(PinnedMemory {
+            node,
+            pinned_at: now,
+            pinned_until: until,
+        })
+    }
+
+    /// Remove a memory's pin; returns whether it was pinned
+    pub fn unpin_memory(&self, id: &str) -> Result<bool> {
+        let writer = self.lock_writer()?;
+        let removed = writer.execute("DELETE FROM memory_pins WHERE memory_id = ?1", params![id])?;
+        Ok(removed > 0)
+    }
+
+    /// Currently pinned memories, most recently pinned first
+    ///
+    /// Pins whose TTL has passed but that consolidation has not yet removed
+    /// are left out.
+    pub fn list_pinned(&self) -> Result<Vec<PinnedMemory>> {
+        let pins = {
+            let reader = self.lock_reader()?;
+            reader
+                .prepare(
+                    "SELECT memory_id, pinned_at, pinned_until FROM memory_pins
+                     WHERE pinned_until IS NULL OR pinned_until > ?1
+                     ORDER BY pinned_at DESC",
+                )?
+                .query_map(params![self.now().to_rfc3339()], |row| {
+                    let pinned_until: Option<String> = row.get(2)?;
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        Self::parse_timestamp(&row.get::<_, String>(1)?, "pinned_at")?,
+                        pinned_until
+                            .map(|t| Self::parse_timestamp(&t, "pinned_until"))
+                            .transpose()?,
+                    ))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?
+        };
+
+        let mut pinned = Vec::with_capacity(pins.len());
+        for (id, pinned_at, pinned_until) in pins {
+            if let Some(node) = self.get_node(&id)? {
+                pinned.push(PinnedMemory {
+                    node,
+                    pinned_at,
+                    pinned_until,
+                });
+            }
+        }
+        Ok(pinned)
+    }
+
+    /// Whether a memory is currently pinned (and so exempt from decay and GC)
+    pub fn is_pinned(&self, id: &str) -> Result<bool> {
+        let reader = self.lock_reader()?;
+        Ok(reader.query_row(
+            "SELECT EXISTS(SELECT 1 FROM memory_pins
+                           WHERE memory_id = ?1 AND (pinned_until IS NULL OR pinned_until > ?2))",
+            params![id, self.now().to_rfc3339()],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// IDs of currently pinned memories
+    ///
+    /// Expired pins are left out even before consolidation removes them.
+    pub fn active_pin_ids(&self) -> Result<HashSet<String>> {
+        let reader = self.lock_reader()?;
+        let ids = reader
+            .prepare(
+                "SELECT memory_id FROM memory_pins
+                 WHERE pinned_until IS NULL OR pinned_until > ?1",
+            )?
+            .query_map(params![self.now().to_rfc3339()], |row| row.get(0))?
+            .collect::<rusqlite::Result<HashSet<String>>>()?;
+        Ok(ids)
+    }
+
+    /// Remove pins whose TTL has passed; returns how many were removed
+    ///
+    /// Runs at the start of every consolidation.
+    pub fn expire_pins(&self) -> Result<usize> {
+        let writer = self.lock_writer()?;
+        let expired = writer.execute(
+            "DELETE FROM memory_pins WHERE pinned_until IS NOT NULL AND pinned_until <= ?1",
+            params![self.now().to_rfc3339()],
+        )?;
+        Ok(expired)
+    }
+
     /// Get memories due for review
     pub fn get_review_queue(&self, limit: i32) -> Result<Vec<KnowledgeNode>> {
-        let now = Utc::now().to_rfc3339();
+        let now = self.now().to_rfc3339();
 
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
+        let reader = self.lock_reader()?;
         let mut stmt = reader.prepare(
             "SELECT * FROM knowledge_nodes
              WHERE next_review <= ?1
@@ -1090,67 +3797,95 @@ impl Storage {
         };
 
         let scheduler = self.scheduler.lock()
-            .map_err(|_| StorageError::Init("Scheduler lock poisoned".into()))?;
-        let elapsed_days = scheduler.days_since_review(&current_state.last_review);
+            .map_err(|_| StorageError::LockPoisoned("Scheduler".into()))?;
+        let elapsed_days = scheduler.days_since_review_at(&current_state.last_review, self.now());
 
         Ok(scheduler.preview_reviews(&current_state, elapsed_days))
     }
 
+    /// Next interval for each rating as a short label ("<1d", "2d", "5d", ...)
+    ///
+    /// Ordered Again, Hard, Good, Easy; see `format_interval`.
+    pub fn preview_intervals(&self, id: &str) -> Result<Vec<(Rating, String)>> {
+        Ok(self
+            .preview_review(id)?
+            .as_intervals()
+            .into_iter()
+            .map(|(rating, interval)| (rating, crate::fsrs::format_interval(interval)))
+            .collect())
+    }
+
     /// Get memory statistics
     pub fn get_stats(&self) -> Result<MemoryStats> {
-        let now = Utc::now().to_rfc3339();
-
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-
-        let total: i64 =
-            reader
-                .query_row("SELECT COUNT(*) FROM knowledge_nodes", [], |row| row.get(0))?;
-
-        let due: i64 = reader.query_row(
-            "SELECT COUNT(*) FROM knowledge_nodes WHERE next_review <= ?1",
-            params![now],
-            |row| row.get(0),
-        )?;
-
-        let avg_retention: f64 = reader.query_row(
-            "SELECT COALESCE(AVG(retention_strength), 0) FROM knowledge_nodes",
-            [],
-            |row| row.get(0),
-        )?;
-
-        let avg_storage: f64 = reader.query_row(
-            "SELECT COALESCE(AVG(storage_strength), 1) FROM knowledge_nodes",
-            [],
-            |row| row.get(0),
-        )?;
-
-        let avg_retrieval: f64 = reader.query_row(
-            "SELECT COALESCE(AVG(retrieval_strength), 1) FROM knowledge_nodes",
-            [],
-            |row| row.get(0),
-        )?;
-
-        let oldest: Option<String> = reader
-            .query_row("SELECT MIN(created_at) FROM knowledge_nodes", [], |row| {
-                row.get(0)
-            })
-            .ok();
+        self.get_stats_in(None)
+    }
 
-        let newest: Option<String> = reader
-            .query_row("SELECT MAX(created_at) FROM knowledge_nodes", [], |row| {
-                row.get(0)
-            })
-            .ok();
+    /// Memory statistics for one namespace, or for all of them with `None`
+    pub(crate) fn get_stats_in(&self, namespace: Option<&str>) -> Result<MemoryStats> {
+        self.get_stats_filtered(StatsFilter {
+            namespace: namespace.map(str::to_string),
+            ..Default::default()
+        })
+    }
 
-        let nodes_with_embeddings: i64 = reader.query_row(
-            "SELECT COUNT(*) FROM knowledge_nodes WHERE has_embedding = 1",
-            [],
-            |row| row.get(0),
+    /// Memory statistics over the nodes matching `filter`
+    ///
+    /// Counts, averages and the due-for-review count cover only the matching
+    /// subset, e.g. one `project:*` tag's collection.
+    pub fn get_stats_filtered(&self, filter: StatsFilter) -> Result<MemoryStats> {
+        let now = self.now().to_rfc3339();
+
+        let reader = self.lock_reader()?;
+
+        let (total, due, avg_retention, avg_storage, avg_retrieval, oldest, newest, nodes_with_embeddings): (
+            i64,
+            i64,
+            f64,
+            f64,
+            f64,
+            Option<String>,
+            Option<String>,
+            i64,
+        ) = reader.query_row(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(next_review <= ?1), 0),
+                    COALESCE(AVG(retention_strength), 0),
+                    COALESCE(AVG(storage_strength), 1),
+                    COALESCE(AVG(retrieval_strength), 1),
+                    MIN(created_at),
+                    MAX(created_at),
+                    COALESCE(SUM(has_embedding = 1), 0)
+             FROM knowledge_nodes
+             WHERE (?2 IS NULL OR namespace = ?2)
+               AND (?3 IS NULL OR EXISTS (
+                   SELECT 1 FROM json_each(knowledge_nodes.tags) tag WHERE lower(tag.value) = lower(?3)
+               ))
+               AND (?4 IS NULL OR node_type = ?4)
+               AND (?5 IS NULL OR scope = ?5)",
+            params![now, filter.namespace, filter.tag, filter.node_type, filter.scope],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                    row.get(6)?,
+                    row.get(7)?,
+                ))
+            },
         )?;
 
+        // Most recently used model; older rows may predate a backend switch
         let embedding_model: Option<String> = if nodes_with_embeddings > 0 {
-            Some("all-MiniLM-L6-v2".to_string())
+            reader
+                .query_row(
+                    "SELECT model FROM node_embeddings ORDER BY created_at DESC LIMIT 1",
+                    [],
+                    |row| row.get(0),
+                )
+                .optional()?
         } else {
             None
         };
@@ -1178,19 +3913,40 @@ impl Storage {
 
     /// Delete a node
     pub fn delete_node(&self, id: &str) -> Result<bool> {
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
+        let writer = self.lock_writer()?;
         let rows = writer
             .execute("DELETE FROM knowledge_nodes WHERE id = ?1", params![id])?;
+        drop(writer);
+        if rows > 0 {
+            self.note_mutation();
+        }
+
+        #[cfg(feature = "vector-search")]
+        if rows > 0 {
+            if let Ok(mut index) = self.vector_index.lock() {
+                let _ = index.remove(id);
+            }
+        }
+
         Ok(rows > 0)
     }
 
     /// Search with full-text search
     pub fn search(&self, query: &str, limit: i32) -> Result<Vec<KnowledgeNode>> {
-        let sanitized_query = sanitize_fts5_query(query);
+        self.search_with_syntax(query, limit, SearchSyntax::Plain)
+    }
 
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
+    /// Full-text search, optionally accepting quoted phrases and prefix terms
+    pub fn search_with_syntax(
+        &self,
+        query: &str,
+        limit: i32,
+        syntax: SearchSyntax,
+    ) -> Result<Vec<KnowledgeNode>> {
+        self.metrics.search_served();
+        let sanitized_query = sanitize_fts5_query_with(query, syntax);
+
+        let reader = self.lock_reader()?;
         let mut stmt = reader.prepare(
             "SELECT n.* FROM knowledge_nodes n
              JOIN knowledge_fts fts ON n.id = fts.id
@@ -1208,17 +3964,132 @@ impl Storage {
         Ok(result)
     }
 
+    /// Full-text search returning each node's raw FTS5 rank
+    ///
+    /// The rank is SQLite's negated BM25 score: more negative is a better
+    /// match, and values are not comparable across queries. Results come
+    /// best first. Hybrid search normalizes these same ranks to [0, 1]
+    /// before fusing them with semantic similarities.
+    pub fn keyword_search_scored(&self, query: &str, limit: i32) -> Result<Vec<(KnowledgeNode, f32)>> {
+        self.metrics.search_served();
+        let sanitized_query = sanitize_fts5_query_with(query, SearchSyntax::Plain);
+
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT n.*, rank FROM knowledge_nodes n
+             JOIN knowledge_fts fts ON n.id = fts.id
+             WHERE knowledge_fts MATCH ?1
+             ORDER BY rank
+             LIMIT ?2",
+        )?;
+
+        let results = stmt.query_map(params![sanitized_query, limit], |row| {
+            Ok((Self::row_to_node(row)?, row.get::<_, f64>("rank")? as f32))
+        })?;
+
+        let mut scored = Vec::new();
+        for result in results {
+            scored.push(result?);
+        }
+        Ok(scored)
+    }
+
+    /// One page of search results plus the total number of matches
+    ///
+    /// With a ready embedding model this is hybrid search with the default
+    /// config, and the total is the size of the fused candidate set: every
+    /// keyword match plus every memory at least `min_semantic_similarity`
+    /// from the query. Every page is cut from that same set, so the total
+    /// does not change with `offset`. Otherwise it is keyword search, and the
+    /// total is an exact `COUNT` of the FTS matches.
+    pub fn search_with_count(
+        &self,
+        query: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<(Vec<SearchResult>, usize)> {
+        #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+        if self.embedding_service.is_ready() {
+            let (_, results, total) =
+                self.hybrid_search_page(query, limit as i32, Some(offset), &HybridSearchConfig::default())?;
+            return Ok((results, total));
+        }
+
+        self.keyword_search_with_count(query, limit, offset)
+    }
+
+    /// Keyword branch of `search_with_count`
+    ///
+    /// Scores are normalized against the best match of the whole result set,
+    /// not the page, so they stay comparable from page to page.
+    fn keyword_search_with_count(
+        &self,
+        query: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<(Vec<SearchResult>, usize)> {
+        self.metrics.search_served();
+        let sanitized_query = sanitize_fts5_query_with(query, SearchSyntax::Plain);
+
+        let reader = self.lock_reader()?;
+        let (total, best_rank): (i64, Option<f64>) = reader.query_row(
+            "SELECT COUNT(*), MIN(rank) FROM knowledge_nodes n
+             JOIN knowledge_fts fts ON n.id = fts.id
+             WHERE knowledge_fts MATCH ?1",
+            params![sanitized_query],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let best_score = best_rank.map_or(0.0, |rank| (-rank).max(0.0)) as f32;
+
+        let mut stmt = reader.prepare(
+            "SELECT n.*, rank FROM knowledge_nodes n
+             JOIN knowledge_fts fts ON n.id = fts.id
+             WHERE knowledge_fts MATCH ?1
+             ORDER BY rank
+             LIMIT ?2 OFFSET ?3",
+        )?;
+        let rows = stmt.query_map(params![sanitized_query, limit as i64, offset as i64], |row| {
+            Ok((Self::row_to_node(row)?, row.get::<_, f64>("rank")? as f32))
+        })?;
+
+        let mut results = Vec::new();
+        for row in rows {
+            let (node, rank) = row?;
+            let score = if best_score > 0.0 { (-rank).max(0.0) / best_score } else { 0.0 };
+            results.push(SearchResult {
+                node,
+                keyword_score: Some(score),
+                semantic_score: None,
+                combined_score: score,
+                match_type: MatchType::Keyword,
+                score_breakdown: None,
+                source_trust: 1.0,
+            });
+        }
+        Ok((results, total as usize))
+    }
+
     /// Get all nodes (paginated)
     pub fn get_all_nodes(&self, limit: i32, offset: i32) -> Result<Vec<KnowledgeNode>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
+        self.get_all_nodes_in(None, limit, offset)
+    }
+
+    /// `get_all_nodes` for one namespace, or all of them with `None`
+    pub(crate) fn get_all_nodes_in(
+        &self,
+        namespace: Option<&str>,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<KnowledgeNode>> {
+        let reader = self.lock_reader()?;
         let mut stmt = reader.prepare(
             "SELECT * FROM knowledge_nodes
+             WHERE ?3 IS NULL OR namespace = ?3
              ORDER BY created_at DESC
              LIMIT ?1 OFFSET ?2",
         )?;
 
-        let nodes = stmt.query_map(params![limit, offset], |row| Self::row_to_node(row))?;
+        let nodes = stmt.query_map(params![limit, offset, namespace], |row| Self::row_to_node(row))?;
 
         let mut result = Vec::new();
         for node in nodes {
@@ -1227,6 +4098,56 @@ impl Storage {
         Ok(result)
     }
 
+    /// Visit every node matching `filter`, returning how many were visited
+    ///
+    /// Nodes are read in pages by keyset pagination on the id, so memory
+    /// stays bounded whatever the database size, and no lock is held while
+    /// `visit` runs: it may use the storage itself. Nodes come in id order,
+    /// not by age.
+    pub fn for_each_node(
+        &self,
+        filter: &NodeFilter,
+        mut visit: impl FnMut(KnowledgeNode) -> Result<()>,
+    ) -> Result<usize> {
+        const PAGE_SIZE: i64 = 500;
+        let created_before = filter.created_before.map(|t| t.to_rfc3339());
+        let mut visited = 0;
+        let mut last_id = String::new();
+
+        loop {
+            let page: Vec<KnowledgeNode> = {
+                let reader = self.lock_reader()?;
+                let mut stmt = reader.prepare_cached(
+                    "SELECT * FROM knowledge_nodes
+                     WHERE id > ?1
+                       AND (?3 IS NULL OR namespace = ?3)
+                       AND (?4 IS NULL OR created_at < ?4)
+                       AND (?5 IS NULL OR retention_strength < ?5)
+                     ORDER BY id
+                     LIMIT ?2",
+                )?;
+                let rows = stmt.query_map(
+                    params![last_id, PAGE_SIZE, filter.namespace, created_before, filter.max_retention],
+                    Self::row_to_node,
+                )?;
+                rows.collect::<rusqlite::Result<_>>()?
+            };
+            let Some(last) = page.last() else {
+                return Ok(visited);
+            };
+            last_id = last.id.clone();
+            let full_page = page.len() as i64 == PAGE_SIZE;
+
+            for node in page {
+                visit(node)?;
+                visited += 1;
+            }
+            if !full_page {
+                return Ok(visited);
+            }
+        }
+    }
+
     /// Get nodes by type and optional tag filter
     ///
     /// This is used for codebase context retrieval where we need to query
@@ -1237,65 +4158,133 @@ impl Storage {
         tag_filter: Option<&str>,
         limit: i32,
     ) -> Result<Vec<KnowledgeNode>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        match tag_filter {
-            Some(tag) => {
-                // Query with tag filter using JSON LIKE search
-                // Tags are stored as JSON array, e.g., '["pattern", "codebase", "codebase:vestige"]'
-                let tag_pattern = format!("%\"{}%", tag);
-                let mut stmt = reader.prepare(
-                    "SELECT * FROM knowledge_nodes
-                     WHERE node_type = ?1
-                     AND tags LIKE ?2
-                     ORDER BY retention_strength DESC, created_at DESC
-                     LIMIT ?3",
-                )?;
-                let rows = stmt.query_map(params![node_type, tag_pattern, limit], |row| {
-                    Self::row_to_node(row)
-                })?;
-                let mut nodes = Vec::new();
-                for node in rows.flatten() {
-                    nodes.push(node);
-                }
-                Ok(nodes)
-            }
-            None => {
-                // Query without tag filter
-                let mut stmt = reader.prepare(
-                    "SELECT * FROM knowledge_nodes
-                     WHERE node_type = ?1
-                     ORDER BY retention_strength DESC, created_at DESC
-                     LIMIT ?2",
-                )?;
-                let rows = stmt.query_map(params![node_type, limit], |row| Self::row_to_node(row))?;
-                let mut nodes = Vec::new();
-                for node in rows.flatten() {
-                    nodes.push(node);
-                }
-                Ok(nodes)
-            }
+        self.get_nodes_by_type_and_tag_in(None, node_type, tag_filter, limit)
+    }
+
+    /// `get_nodes_by_type_and_tag` limited to one namespace, or all of them with `None`
+    pub(crate) fn get_nodes_by_type_and_tag_in(
+        &self,
+        namespace: Option<&str>,
+        node_type: &str,
+        tag_filter: Option<&str>,
+        limit: i32,
+    ) -> Result<Vec<KnowledgeNode>> {
+        // Tags are stored as JSON array, e.g., '["pattern", "codebase", "codebase:vestige"]',
+        // so a tag filter is a JSON LIKE search
+        let tag_pattern = tag_filter.map(|tag| format!("%\"{}%", tag));
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT * FROM knowledge_nodes
+             WHERE node_type = ?1
+             AND (?2 IS NULL OR tags LIKE ?2)
+             AND (?4 IS NULL OR namespace = ?4)
+             ORDER BY retention_strength DESC, created_at DESC
+             LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(params![node_type, tag_pattern, limit, namespace], |row| {
+            Self::row_to_node(row)
+        })?;
+        let mut nodes = Vec::new();
+        for node in rows.flatten() {
+            nodes.push(node);
         }
+        Ok(nodes)
     }
 
-    /// Check if embedding service is ready
-    #[cfg(feature = "embeddings")]
-    pub fn is_embedding_ready(&self) -> bool {
-        self.embedding_service.is_ready()
+    /// Classify a memory as episodic, semantic or procedural
+    pub fn set_memory_system(&self, id: &str, system: MemorySystem) -> Result<()> {
+        let writer = self.lock_writer()?;
+        let updated = writer.execute(
+            "UPDATE knowledge_nodes SET memory_system = ?1 WHERE id = ?2",
+            params![system.to_string(), id],
+        )?;
+        if updated == 0 {
+            return Err(StorageError::NotFound(id.to_string()));
+        }
+        Ok(())
     }
 
-    #[cfg(not(feature = "embeddings"))]
-    pub fn is_embedding_ready(&self) -> bool {
-        false
+    /// Memory system a memory is classified under (semantic unless set)
+    pub fn memory_system(&self, id: &str) -> Result<MemorySystem> {
+        let reader = self.lock_reader()?;
+        let system: Option<String> = reader
+            .query_row(
+                "SELECT memory_system FROM knowledge_nodes WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or_else(|| StorageError::NotFound(id.to_string()))?;
+        Ok(system.and_then(|s| s.parse().ok()).unwrap_or_default())
     }
 
-    /// Initialize the embedding service explicitly
-    /// Call this at startup to catch initialization errors early
-    #[cfg(feature = "embeddings")]
-    pub fn init_embeddings(&self) -> Result<()> {
-        self.embedding_service.init().map_err(|e| {
-            StorageError::Init(format!("Embedding service initialization failed: {}", e))
-        })
+    /// Last commit analyzed for the repository at `repo` (see `GitAnalyzer::analyze_since`)
+    pub fn git_cursor(&self, repo: &Path) -> Result<Option<String>> {
+        let reader = self.lock_reader()?;
+        Ok(reader
+            .query_row(
+                "SELECT last_commit FROM git_cursors WHERE repo_path = ?1",
+                params![Self::git_cursor_key(repo)],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Record `sha` as the last commit analyzed for the repository at `repo`
+    pub fn set_git_cursor(&self, repo: &Path, sha: &str) -> Result<()> {
+        if sha.trim().is_empty() {
+            return Err(StorageError::InvalidInput {
+                field: "sha",
+                reason: "must not be empty".into(),
+            });
+        }
+        let writer = self.lock_writer()?;
+        writer.execute(
+            "INSERT INTO git_cursors (repo_path, last_commit, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(repo_path) DO UPDATE SET last_commit = excluded.last_commit, updated_at = excluded.updated_at",
+            params![Self::git_cursor_key(repo), sha.trim(), self.now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Forget the analysis cursor so the next analysis rescans all history
+    pub fn clear_git_cursor(&self, repo: &Path) -> Result<bool> {
+        let writer = self.lock_writer()?;
+        let removed = writer.execute(
+            "DELETE FROM git_cursors WHERE repo_path = ?1",
+            params![Self::git_cursor_key(repo)],
+        )?;
+        Ok(removed > 0)
+    }
+
+    /// Cursor key: the canonical repository path, so `repo/` and `./repo` agree
+    fn git_cursor_key(repo: &Path) -> String {
+        std::fs::canonicalize(repo)
+            .unwrap_or_else(|_| repo.to_path_buf())
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// Check if embedding service is ready
+    #[cfg(feature = "embeddings")]
+    pub fn is_embedding_ready(&self) -> bool {
+        self.embedding_service.is_ready()
+    }
+
+    #[cfg(not(feature = "embeddings"))]
+    pub fn is_embedding_ready(&self) -> bool {
+        false
+    }
+
+    /// Initialize the embedding service explicitly
+    /// Call this at startup to catch initialization errors early
+    #[cfg(feature = "embeddings")]
+    pub fn init_embeddings(&self) -> Result<()> {
+        self.embedding_service.init().map_err(|e| {
+            StorageError::EmbeddingUnavailable(format!("Embedding service initialization failed: {}", e))
+        })?;
+        // A (re)initialized model may embed differently; start from an empty cache
+        self.clear_query_cache()
     }
 
     #[cfg(not(feature = "embeddings"))]
@@ -1303,33 +4292,123 @@ impl Storage {
         Ok(()) // No-op when embeddings feature is disabled
     }
 
+    /// Query cache budget from `VESTIGE_QUERY_CACHE_BYTES` (default 4 MiB)
+    #[cfg(feature = "embeddings")]
+    fn query_cache_bytes_from_env() -> usize {
+        std::env::var("VESTIGE_QUERY_CACHE_BYTES")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_QUERY_CACHE_BYTES)
+    }
+
+    /// Drop all cached query embeddings
+    ///
+    /// Done automatically by `init_embeddings` and whenever a lookup comes
+    /// from a different model than the cached vectors.
+    #[cfg(feature = "embeddings")]
+    pub fn clear_query_cache(&self) -> Result<()> {
+        self.query_cache.lock()
+            .map_err(|_| StorageError::LockPoisoned("Query cache".into()))?
+            .clear();
+        Ok(())
+    }
+
+    /// Set the query cache budget in bytes, evicting entries that no longer fit
+    ///
+    /// Zero disables caching.
+    #[cfg(feature = "embeddings")]
+    pub fn set_query_cache_capacity(&self, bytes: usize) -> Result<()> {
+        self.query_cache.lock()
+            .map_err(|_| StorageError::LockPoisoned("Query cache".into()))?
+            .set_capacity(bytes);
+        Ok(())
+    }
+
+    /// Query cache occupancy and hit/miss counters
+    #[cfg(feature = "embeddings")]
+    pub fn query_cache_stats(&self) -> Result<QueryCacheStats> {
+        Ok(self.query_cache.lock()
+            .map_err(|_| StorageError::LockPoisoned("Query cache".into()))?
+            .stats())
+    }
+
+    #[cfg(not(feature = "embeddings"))]
+    pub fn query_cache_stats(&self) -> Result<QueryCacheStats> {
+        Ok(QueryCacheStats::default())
+    }
+
+    /// Look up a cached query embedding computed by the active model
+    ///
+    /// `expanded` selects the HyDE centroid for the query rather than the
+    /// embedding of its literal text.
+    #[cfg(feature = "embeddings")]
+    fn cached_query_embedding(&self, query: &str, expanded: bool) -> Result<Option<Vec<f32>>> {
+        let mut cache = self.query_cache.lock()
+            .map_err(|_| StorageError::LockPoisoned("Query cache".into()))?;
+        let cached = cache.get(self.embedding_service.model_name(), query, expanded);
+        if cached.is_some() {
+            self.metrics.query_cache_hit();
+        }
+        tracing::Span::current().record("query_cache_hit", cached.is_some());
+        Ok(cached)
+    }
+
+    /// Store a query embedding computed by the active model
+    #[cfg(feature = "embeddings")]
+    fn cache_query_embedding(&self, query: &str, expanded: bool, vector: Vec<f32>) -> Result<()> {
+        let mut cache = self.query_cache.lock()
+            .map_err(|_| StorageError::LockPoisoned("Query cache".into()))?;
+        cache.put(self.embedding_service.model_name(), query, expanded, vector);
+        Ok(())
+    }
+
     /// Get query embedding from cache or compute it
     #[cfg(feature = "embeddings")]
     fn get_query_embedding(&self, query: &str) -> Result<Vec<f32>> {
         // Check cache first
-        {
-            let mut cache = self.query_cache.lock()
-                .map_err(|_| StorageError::Init("Query cache lock poisoned".to_string()))?;
-            if let Some(cached) = cache.get(query) {
-                return Ok(cached.clone());
-            }
+        if let Some(cached) = self.cached_query_embedding(query, false)? {
+            return Ok(cached);
         }
 
-        // Not in cache, compute embedding
-        let embedding = self.embedding_service.embed(query)
-            .map_err(|e| StorageError::Init(format!("Failed to embed query: {}", e)))?;
+        // Not in cache, compute embedding at the index's dimensions
+        let embedding = self.embedding_service.embed_truncated(query, self.index_dimensions())
+            .map_err(|e| StorageError::EmbeddingUnavailable(format!("Failed to embed query: {}", e)))?;
 
-        // Store in cache
-        {
-            let mut cache = self.query_cache.lock()
-                .map_err(|_| StorageError::Init("Query cache lock poisoned".to_string()))?;
-            cache.put(query.to_string(), embedding.vector.clone());
-        }
+        self.cache_query_embedding(query, false, embedding.vector.clone())?;
 
         Ok(embedding.vector)
     }
 
+    /// Embedding used to search for a planned query
+    ///
+    /// Expanded queries are embedded as the centroid of their variants, cached
+    /// under the query itself so repeat searches skip re-embedding every variant.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn expanded_query_embedding(&self, query: &str, plan: &QueryExpansion) -> Result<Vec<f32>> {
+        if !plan.expanded || plan.variants.len() < 2 {
+            return self.get_query_embedding(query);
+        }
+        if let Some(cached) = self.cached_query_embedding(query, true)? {
+            return Ok(cached);
+        }
+
+        let texts: Vec<&str> = plan.variants.iter().map(String::as_str).collect();
+        let centroid = match self.embedding_service.embed_batch(&texts) {
+            Ok(embeddings) if embeddings.len() > 1 => {
+                let vectors: Vec<Vec<f32>> = embeddings.into_iter().map(|e| e.vector).collect();
+                hyde::centroid_embedding(&vectors)
+            }
+            _ => return self.get_query_embedding(query),
+        };
+
+        self.cache_query_embedding(query, true, centroid.clone())?;
+        Ok(centroid)
+    }
+
     /// Semantic search
+    ///
+    /// Embeds the literal query; see `semantic_search_with_expansion` for
+    /// HyDE-expanded queries.
     #[cfg(all(feature = "embeddings", feature = "vector-search"))]
     pub fn semantic_search(
         &self,
@@ -1337,20 +4416,52 @@ impl Storage {
         limit: i32,
         min_similarity: f32,
     ) -> Result<Vec<SimilarityResult>> {
+        let (_, results) =
+            self.semantic_search_with_expansion(query, limit, min_similarity, QueryExpansionMode::Off)?;
+        Ok(results)
+    }
+
+    /// Semantic search with HyDE query expansion
+    ///
+    /// Returns how the query was embedded (classified intent, whether it was
+    /// expanded, and the variants) alongside the results.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            limit,
+            expanded = tracing::field::Empty,
+            query_cache_hit = tracing::field::Empty,
+            results = tracing::field::Empty,
+            elapsed_ms = tracing::field::Empty,
+        )
+    )]
+    pub fn semantic_search_with_expansion(
+        &self,
+        query: &str,
+        limit: i32,
+        min_similarity: f32,
+        mode: QueryExpansionMode,
+    ) -> Result<(QueryExpansion, Vec<SimilarityResult>)> {
+        let _timer = SpanTimer::start();
+        self.metrics.search_served();
         if !self.embedding_service.is_ready() {
-            return Err(StorageError::Init("Embedding model not ready".to_string()));
+            return Err(StorageError::EmbeddingUnavailable("Embedding model not ready".to_string()));
         }
 
-        let query_embedding = self.get_query_embedding(query)?;
+        let plan = hyde::plan_expansion(query, mode);
+        tracing::Span::current().record("expanded", plan.expanded);
+        let query_embedding = self.expanded_query_embedding(query, &plan)?;
 
         let index = self
             .vector_index
             .lock()
-            .map_err(|_| StorageError::Init("Vector index lock poisoned".to_string()))?;
+            .map_err(|_| StorageError::LockPoisoned("Vector index".into()))?;
 
         let results = index
             .search_with_threshold(&query_embedding, limit as usize, min_similarity)
-            .map_err(|e| StorageError::Init(format!("Vector search failed: {}", e)))?;
+            .map_err(|e| StorageError::VectorIndex(format!("Vector search failed: {}", e)))?;
 
         let mut similarity_results = Vec::with_capacity(results.len());
 
@@ -1360,10 +4471,14 @@ impl Storage {
             }
         }
 
-        Ok(similarity_results)
+        tracing::Span::current().record("results", similarity_results.len());
+        Ok((plan, similarity_results))
     }
 
-    /// Hybrid search
+    /// Hybrid search fusing keyword and semantic scores linearly with the
+    /// given weights
+    ///
+    /// Use `hybrid_search_with_config` for the default reciprocal rank fusion.
     #[cfg(all(feature = "embeddings", feature = "vector-search"))]
     pub fn hybrid_search(
         &self,
@@ -1372,32 +4487,148 @@ impl Storage {
         keyword_weight: f32,
         semantic_weight: f32,
     ) -> Result<Vec<SearchResult>> {
-        let keyword_results = self.keyword_search_with_scores(query, limit * 2)?;
+        let config = HybridSearchConfig {
+            fusion: FusionMethod::Linear {
+                kw_weight: keyword_weight,
+                sem_weight: semantic_weight,
+            },
+            ..Default::default()
+        };
+        self.hybrid_search_with_config(query, limit, &config)
+    }
 
-        let semantic_results = if self.embedding_service.is_ready() {
-            self.semantic_search_raw(query, limit * 2)?
-        } else {
-            vec![]
+    /// Hybrid search driven by a `HybridSearchConfig`
+    ///
+    /// Results are reranked with the config's recency/importance/relevance
+    /// weights, which must sum to ~1.0, plus the source trust weight (see
+    /// `set_source_trust`). With `config.explain` set, each result carries a
+    /// `ScoreBreakdown`.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    pub fn hybrid_search_with_config(
+        &self,
+        query: &str,
+        limit: i32,
+        config: &HybridSearchConfig,
+    ) -> Result<Vec<SearchResult>> {
+        let (_, results) = self.hybrid_search_with_expansion(query, limit, config)?;
+        Ok(results)
+    }
+
+    /// Hybrid search that also reports how the query was embedded
+    ///
+    /// Expansion follows `config.query_expansion`. Without a ready embedding
+    /// model only keyword search runs and nothing is expanded.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    pub fn hybrid_search_with_expansion(
+        &self,
+        query: &str,
+        limit: i32,
+        config: &HybridSearchConfig,
+    ) -> Result<(QueryExpansion, Vec<SearchResult>)> {
+        let (plan, results, _) = self.hybrid_search_page(query, limit, None, config)?;
+        Ok((plan, results))
+    }
+
+    /// One page of hybrid search results, plus the size of the fused
+    /// candidate set the page was cut from
+    ///
+    /// Pages are cut from the fused order and reranked within the page.
+    /// Without an `offset` the candidates are the top `limit *
+    /// source_limit_multiplier` of each branch; with one they are every
+    /// keyword match and every semantic hit of at least
+    /// `min_semantic_similarity`, so the set is the same for every page.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            limit,
+            keyword_hits = tracing::field::Empty,
+            semantic_hits = tracing::field::Empty,
+            expanded = tracing::field::Empty,
+            query_cache_hit = tracing::field::Empty,
+            results = tracing::field::Empty,
+            elapsed_ms = tracing::field::Empty,
+        )
+    )]
+    fn hybrid_search_page(
+        &self,
+        query: &str,
+        limit: i32,
+        offset: Option<usize>,
+        config: &HybridSearchConfig,
+    ) -> Result<(QueryExpansion, Vec<SearchResult>, usize)> {
+        let _timer = SpanTimer::start();
+        self.metrics.search_served();
+        if !config.is_valid() {
+            return Err(StorageError::InvalidInput {
+                field: "config",
+                reason: format!(
+                    "rerank weights ({}, {}, {}) must sum to 1.0, recency decay base {} must be in (0, 1] \
+                     and fusion {:?} needs a positive k or non-negative weights",
+                    config.recency_weight,
+                    config.importance_weight,
+                    config.relevance_weight,
+                    config.recency_decay_base,
+                    config.fusion
+                ),
+            });
+        }
+
+        // SQLite reads a negative LIMIT as no limit
+        let (keyword_limit, semantic_limit) = match offset {
+            Some(_) => {
+                let indexed = self
+                    .vector_index
+                    .lock()
+                    .map_err(|_| StorageError::LockPoisoned("Vector index".into()))?
+                    .len();
+                (-1, indexed as i32)
+            }
+            None => {
+                let source_limit = limit * config.source_limit_multiplier.max(1) as i32;
+                (source_limit, source_limit)
+            }
         };
 
-        let combined = if !semantic_results.is_empty() {
-            linear_combination(&keyword_results, &semantic_results, keyword_weight, semantic_weight)
+        let keyword_results = self.keyword_search_with_scores(
+            query,
+            keyword_limit,
+            config.syntax,
+            config.namespace.as_deref(),
+        )?;
+
+        let (plan, semantic_results) = if self.embedding_service.is_ready() {
+            let plan = hyde::plan_expansion(query, config.query_expansion);
+            let mut results = self.semantic_search_raw_with(
+                query,
+                &plan,
+                semantic_limit,
+                config.namespace.as_deref(),
+            )?;
+            if offset.is_some() {
+                results.retain(|(_, similarity)| *similarity >= config.min_semantic_similarity);
+            }
+            (plan, results)
         } else {
-            keyword_results.clone()
+            (hyde::plan_expansion(query, QueryExpansionMode::Off), vec![])
         };
 
+        let span = tracing::Span::current();
+        span.record("keyword_hits", keyword_results.len());
+        span.record("semantic_hits", semantic_results.len());
+        span.record("expanded", plan.expanded);
+        let combined = config.fusion.fuse(&keyword_results, &semantic_results);
+        let total = combined.len();
+
         let mut results = Vec::with_capacity(limit as usize);
 
-        for (node_id, combined_score) in combined.into_iter().take(limit as usize) {
+        for (node_id, _) in combined.into_iter().skip(offset.unwrap_or(0)).take(limit as usize) {
             if let Some(node) = self.get_node(&node_id)? {
-                let keyword_score = keyword_results
-                    .iter()
-                    .find(|(id, _)| id == &node_id)
-                    .map(|(_, s)| *s);
-                let semantic_score = semantic_results
-                    .iter()
-                    .find(|(id, _)| id == &node_id)
-                    .map(|(_, s)| *s);
+                let keyword_rank = keyword_results.iter().position(|(id, _)| id == &node_id);
+                let keyword_score = keyword_rank.map(|i| keyword_results[i].1);
+                let semantic_rank = semantic_results.iter().position(|(id, _)| id == &node_id);
+                let semantic_score = semantic_rank.map(|i| semantic_results[i].1);
 
                 let match_type = match (keyword_score.is_some(), semantic_score.is_some()) {
                     (true, true) => MatchType::Both,
@@ -1406,46 +4637,61 @@ impl Storage {
                     (false, false) => MatchType::Keyword,
                 };
 
-                let weighted_score = match (keyword_score, semantic_score) {
-                    (Some(kw), Some(sem)) => kw * keyword_weight + sem * semantic_weight,
-                    (Some(kw), None) => kw * keyword_weight,
-                    (None, Some(sem)) => sem * semantic_weight,
-                    (None, None) => combined_score,
+                let relevance =
+                    config.fusion.relevance(keyword_rank, keyword_score, semantic_rank, semantic_score);
+
+                let score_breakdown = if config.explain {
+                    Some(ScoreBreakdown {
+                        fts_rank: self.fts_rank(query, config.syntax, &node_id)?,
+                        keyword_rank: keyword_rank.map(|i| i + 1),
+                        keyword_score,
+                        semantic_rank: semantic_rank.map(|i| i + 1),
+                        semantic_similarity: semantic_score,
+                        hyde_expanded: plan.expanded,
+                        ..Default::default()
+                    })
+                } else {
+                    None
                 };
 
                 results.push(SearchResult {
                     node,
                     keyword_score,
                     semantic_score,
-                    combined_score: weighted_score,
+                    combined_score: relevance,
                     match_type,
+                    score_breakdown,
+                    source_trust: 1.0,
                 });
             }
         }
 
-        // Three-signal reranking (Park et al. Generative Agents 2023)
-        // final_score = 0.2*recency + 0.3*importance + 0.5*relevance
-        let now = Utc::now();
+        let now = self.now();
+        let trusts = self.get_source_trusts()?;
+        let pinned = self.active_pin_ids()?;
         for result in &mut results {
-            let hours_since = (now - result.node.last_accessed).num_seconds() as f64 / 3600.0;
-            let recency = 0.995_f64.powf(hours_since.max(0.0));
-
-            // ACT-R activation as importance signal (pre-computed during consolidation)
-            let activation: f64 = self
-                .reader.lock()
-                .map(|r| r.query_row(
-                    "SELECT COALESCE(activation, 0.0) FROM knowledge_nodes WHERE id = ?1",
-                    params![result.node.id],
-                    |row| row.get(0),
-                ).unwrap_or(0.0))
-                .unwrap_or(0.0);
-            // Normalize ACT-R activation [-2, 5] → [0, 1]
-            let importance = ((activation + 2.0) / 7.0).clamp(0.0, 1.0);
-
-            let relevance = result.combined_score as f64;
-
-            let final_score = 0.2 * recency + 0.3 * importance + 0.5 * relevance;
-            result.combined_score = final_score as f32;
+            let scored = self.rerank_breakdown(
+                &result.node,
+                result.combined_score as f64,
+                config,
+                &trusts,
+                &pinned,
+                now,
+            );
+            result.combined_score = scored.final_score as f32;
+            result.source_trust = scored.trust;
+
+            if let Some(breakdown) = result.score_breakdown.take() {
+                result.score_breakdown = Some(ScoreBreakdown {
+                    fts_rank: breakdown.fts_rank,
+                    keyword_rank: breakdown.keyword_rank,
+                    keyword_score: breakdown.keyword_score,
+                    semantic_rank: breakdown.semantic_rank,
+                    semantic_similarity: breakdown.semantic_similarity,
+                    hyde_expanded: breakdown.hyde_expanded,
+                    ..scored
+                });
+            }
         }
 
         results.sort_by(|a, b| {
@@ -1454,26 +4700,33 @@ impl Storage {
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
-        Ok(results)
+        span.record("results", results.len());
+        Ok((plan, results, total))
     }
 
     /// Keyword search returning scores
     #[cfg(all(feature = "embeddings", feature = "vector-search"))]
-    fn keyword_search_with_scores(&self, query: &str, limit: i32) -> Result<Vec<(String, f32)>> {
-        let sanitized_query = sanitize_fts5_query(query);
+    fn keyword_search_with_scores(
+        &self,
+        query: &str,
+        limit: i32,
+        syntax: SearchSyntax,
+        namespace: Option<&str>,
+    ) -> Result<Vec<(String, f32)>> {
+        let sanitized_query = sanitize_fts5_query_with(query, syntax);
 
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
+        let reader = self.lock_reader()?;
         let mut stmt = reader.prepare(
             "SELECT n.id, rank FROM knowledge_nodes n
              JOIN knowledge_fts fts ON n.id = fts.id
              WHERE knowledge_fts MATCH ?1
+               AND (?3 IS NULL OR n.namespace = ?3)
              ORDER BY rank
              LIMIT ?2",
         )?;
 
         let results: Vec<(String, f32)> = stmt
-            .query_map(params![sanitized_query, limit], |row| {
+            .query_map(params![sanitized_query, limit, namespace], |row| {
                 Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)? as f32))
             })?
             .filter_map(|r| r.ok())
@@ -1495,43 +4748,331 @@ impl Storage {
         }
     }
 
+    /// Three-signal reranking (Park et al. Generative Agents 2023) plus source trust
+    ///
+    /// final_score = w_recency*recency + w_importance*importance + w_relevance*relevance
+    ///             + w_trust*trust, with the weights renormalized to sum to 1
+    /// (defaults 0.2 / 0.3 / 0.5 / 0.1), plus `config.pin_bonus` for pinned
+    /// memories. Only the rerank fields of the returned breakdown are set.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn rerank_breakdown(
+        &self,
+        node: &KnowledgeNode,
+        relevance: f64,
+        config: &HybridSearchConfig,
+        trusts: &[SourceTrust],
+        pinned: &HashSet<String>,
+        now: DateTime<Utc>,
+    ) -> ScoreBreakdown {
+        let weight_sum = config.recency_weight
+            + config.importance_weight
+            + config.relevance_weight
+            + config.trust_weight;
+        let hours_since = (now - node.last_accessed).num_seconds() as f64 / 3600.0;
+        let recency = config.recency_decay_base.powf(hours_since.max(0.0));
+
+        // Persisted composite importance (usage-evolved, pre-computed during
+        // consolidation), falling back to ACT-R activation for unscored nodes
+        let activation: f64 = self
+            .reader.lock()
+            .map(|r| r.query_row(
+                "SELECT COALESCE(activation, 0.0) FROM knowledge_nodes WHERE id = ?1",
+                params![node.id],
+                |row| row.get(0),
+            ).unwrap_or(0.0))
+            .unwrap_or(0.0);
+        // Normalize ACT-R activation [-2, 5] → [0, 1]
+        let importance = node
+            .importance
+            .unwrap_or_else(|| ((activation + 2.0) / 7.0).clamp(0.0, 1.0));
+
+        // Map the trust multiplier [0.5, 1.5] onto [0, 1] like the other signals
+        let trust = SourceTrust::resolve(trusts, node.source.as_deref());
+        let trust_signal = (trust - MIN_SOURCE_TRUST) / (MAX_SOURCE_TRUST - MIN_SOURCE_TRUST);
+
+        let recency_term = config.recency_weight * recency / weight_sum;
+        let importance_term = config.importance_weight * importance / weight_sum;
+        let relevance_term = config.relevance_weight * relevance / weight_sum;
+        let trust_term = config.trust_weight * trust_signal / weight_sum;
+        let pin_bonus = if pinned.contains(&node.id) { config.pin_bonus } else { 0.0 };
+        ScoreBreakdown {
+            relevance,
+            recency,
+            activation,
+            importance,
+            recency_term,
+            importance_term,
+            relevance_term,
+            trust,
+            trust_term,
+            pin_bonus,
+            final_score: recency_term + importance_term + relevance_term + trust_term + pin_bonus,
+            ..Default::default()
+        }
+    }
+
+    /// Raw FTS5 rank of one node for a keyword query, if it matches at all
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn fts_rank(&self, query: &str, syntax: SearchSyntax, node_id: &str) -> Result<Option<f64>> {
+        let sanitized_query = sanitize_fts5_query_with(query, syntax);
+        let reader = self.lock_reader()?;
+        Ok(reader
+            .query_row(
+                "SELECT rank FROM knowledge_nodes n
+                 JOIN knowledge_fts fts ON n.id = fts.id
+                 WHERE knowledge_fts MATCH ?1 AND n.id = ?2",
+                params![sanitized_query, node_id],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Explain how a node scores for a query, whether or not it made the cut
+    ///
+    /// Uses the default `HybridSearchConfig` and candidate pools sized for a
+    /// 10-result search; see `explain_ranking_with_config`.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    pub fn explain_ranking(&self, query: &str, node_id: &str) -> Result<ScoreBreakdown> {
+        self.explain_ranking_with_config(query, node_id, 10, &HybridSearchConfig::default())
+    }
+
+    /// Explain how a node scores for a query under a search config
+    ///
+    /// Computes the same breakdown `hybrid_search_with_config` attaches in
+    /// explain mode for a search returning `limit` results. A node outside the keyword or semantic candidate pools
+    /// has no rank there, but its BM25 score and cosine similarity are still
+    /// computed (and, under linear fusion, counted towards relevance), so the
+    /// result shows what it would have needed to rank.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    pub fn explain_ranking_with_config(
+        &self,
+        query: &str,
+        node_id: &str,
+        limit: i32,
+        config: &HybridSearchConfig,
+    ) -> Result<ScoreBreakdown> {
+        if !config.is_valid() {
+            return Err(StorageError::InvalidInput {
+                field: "config",
+                reason: "invalid hybrid search config".to_string(),
+            });
+        }
+        let node = self
+            .get_node(node_id)?
+            .ok_or_else(|| StorageError::NotFound(node_id.to_string()))?;
+        let source_limit = limit * config.source_limit_multiplier.max(1) as i32;
+
+        let keyword_results = self.keyword_search_with_scores(
+            query,
+            source_limit,
+            config.syntax,
+            config.namespace.as_deref(),
+        )?;
+        let keyword_rank = keyword_results.iter().position(|(id, _)| id == node_id);
+        let fts_rank = self.fts_rank(query, config.syntax, node_id)?;
+        // Normalize against the best match, as keyword_search_with_scores does
+        let keyword_score = match (keyword_rank, fts_rank, keyword_results.first()) {
+            (Some(i), _, _) => Some(keyword_results[i].1),
+            (None, Some(rank), Some((best_id, _))) => {
+                let best = self.fts_rank(query, config.syntax, best_id)?.unwrap_or(rank);
+                Some(if best < 0.0 { (rank / best).clamp(0.0, 1.0) as f32 } else { 0.0 })
+            }
+            _ => None,
+        };
+
+        let (plan, semantic_rank, semantic_similarity) = if self.embedding_service.is_ready() {
+            let plan = hyde::plan_expansion(query, config.query_expansion);
+            let semantic_results = self.semantic_search_raw_with(
+                query,
+                &plan,
+                source_limit,
+                config.namespace.as_deref(),
+            )?;
+            let rank = semantic_results.iter().position(|(id, _)| id == node_id);
+            let similarity = match rank {
+                Some(i) => Some(semantic_results[i].1),
+                None => self.get_node_embedding(node_id)?.map(|embedding| {
+                    self.expanded_query_embedding(query, &plan)
+                        .map(|q| cosine_similarity(&q, &embedding))
+                }).transpose()?,
+            };
+            (plan, rank, similarity)
+        } else {
+            (hyde::plan_expansion(query, QueryExpansionMode::Off), None, None)
+        };
+
+        let relevance = config.fusion.relevance(
+            keyword_rank,
+            keyword_score,
+            semantic_rank,
+            semantic_similarity,
+        );
+        let scored = self.rerank_breakdown(
+            &node,
+            relevance as f64,
+            config,
+            &self.get_source_trusts()?,
+            &self.active_pin_ids()?,
+            self.now(),
+        );
+
+        Ok(ScoreBreakdown {
+            fts_rank,
+            keyword_rank: keyword_rank.map(|i| i + 1),
+            keyword_score,
+            semantic_rank: semantic_rank.map(|i| i + 1),
+            semantic_similarity,
+            hyde_expanded: plan.expanded,
+            ..scored
+        })
+    }
+
+    /// Hybrid search biased by the intent detected in the query
+    ///
+    /// The query is classified (debugging, learning, implementing, reviewing,
+    /// ...) and results whose node type suits that intent are boosted in
+    /// proportion to the detection confidence. Debugging additionally favors
+    /// recent memories; learning favors well-consolidated (high stability)
+    /// ones. Returns the detection alongside the reranked results.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    pub fn hybrid_search_with_intent(
+        &self,
+        query: &str,
+        limit: i32,
+        config: &HybridSearchConfig,
+    ) -> Result<(IntentDetectionResult, Vec<SearchResult>)> {
+        let detection = IntentDetector::classify_query(query);
+        if matches!(detection.primary_intent, DetectedIntent::Unknown) {
+            let results = self.hybrid_search_with_config(query, limit, config)?;
+            return Ok((detection, results));
+        }
+
+        // Over-fetch so boosted memories can move up from just below the cut
+        let mut results = self.hybrid_search_with_config(query, limit * 2, config)?;
+        self.apply_intent_boost(&mut results, &detection, config, limit);
+
+        Ok((detection, results))
+    }
+
+    /// Boost results suited to a detected intent, re-sort and truncate to `limit`
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn apply_intent_boost(
+        &self,
+        results: &mut Vec<SearchResult>,
+        detection: &IntentDetectionResult,
+        config: &HybridSearchConfig,
+        limit: i32,
+    ) {
+        let intent = &detection.primary_intent;
+        let preferred_types = intent.preferred_node_types();
+        let intent_tags = intent.relevant_tags();
+        let now = self.now();
+
+        for result in results.iter_mut() {
+            let node = &result.node;
+            let mut boost = 0.0;
+            if preferred_types.contains(&node.node_type.as_str()) {
+                boost += 0.3;
+            }
+            if node.tags.iter().any(|t| intent_tags.contains(&t.to_lowercase())) {
+                boost += 0.1;
+            }
+            match intent {
+                DetectedIntent::Debugging { .. } => {
+                    let hours_since = (now - node.last_accessed).num_seconds() as f64 / 3600.0;
+                    boost += 0.2 * config.recency_decay_base.powf(hours_since.max(0.0));
+                }
+                DetectedIntent::Learning { .. } if preferred_types.contains(&node.node_type.as_str()) => {
+                    boost += 0.2 * node.stability / (node.stability + 10.0);
+                }
+                _ => {}
+            }
+
+            let intent_boost = result.combined_score as f64 * boost * detection.confidence;
+            result.combined_score = (result.combined_score as f64 + intent_boost) as f32;
+            if let Some(breakdown) = result.score_breakdown.as_mut() {
+                breakdown.intent_boost = intent_boost;
+                breakdown.final_score += intent_boost;
+            }
+        }
+
+        results.sort_by(|a, b| {
+            b.combined_score
+                .partial_cmp(&a.combined_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results.truncate(limit.max(0) as usize);
+    }
+
+    /// Show how a query would be expanded for semantic search, without searching
+    ///
+    /// Returns the classified intent and the variants whose embeddings are
+    /// averaged. Intents that skip HyDE expansion yield just the original query.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    pub fn explain_query_expansion(&self, query: &str) -> (hyde::QueryIntent, Vec<String>) {
+        let plan = hyde::plan_expansion(query, QueryExpansionMode::Auto);
+        (plan.intent, plan.variants)
+    }
+
     /// Semantic search returning scores
     #[cfg(all(feature = "embeddings", feature = "vector-search"))]
     fn semantic_search_raw(&self, query: &str, limit: i32) -> Result<Vec<(String, f32)>> {
+        let plan = hyde::plan_expansion(query, QueryExpansionMode::Auto);
+        self.semantic_search_raw_with(query, &plan, limit, None)
+    }
+
+    /// Semantic search for a query embedded according to an expansion plan
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn semantic_search_raw_with(
+        &self,
+        query: &str,
+        plan: &QueryExpansion,
+        limit: i32,
+        namespace: Option<&str>,
+    ) -> Result<Vec<(String, f32)>> {
         if !self.embedding_service.is_ready() {
             return Ok(vec![]);
         }
 
         // HyDE query expansion: for conceptual queries, embed expanded variants
         // and use the centroid for broader semantic coverage
-        let intent = hyde::classify_intent(query);
-        let query_embedding = match intent {
-            hyde::QueryIntent::Definition
-            | hyde::QueryIntent::HowTo
-            | hyde::QueryIntent::Reasoning
-            | hyde::QueryIntent::Lookup => {
-                let variants = hyde::expand_query(query);
-                let embeddings: Vec<Vec<f32>> = variants
-                    .iter()
-                    .filter_map(|v| self.get_query_embedding(v).ok())
-                    .collect();
-                if embeddings.len() > 1 {
-                    hyde::centroid_embedding(&embeddings)
-                } else {
-                    self.get_query_embedding(query)?
-                }
+        let query_embedding = self.expanded_query_embedding(query, plan)?;
+
+        // Candidates a namespaced search may return, looked up before the
+        // index lock so the vector search only visits them
+        let candidates: Option<HashSet<String>> = match namespace {
+            Some(ns) => {
+                let reader = self.lock_reader()?;
+                let mut stmt = reader.prepare(
+                    "SELECT id FROM knowledge_nodes WHERE namespace = ?1 AND has_embedding = 1",
+                )?;
+                let ids = stmt
+                    .query_map(params![ns], |row| row.get::<_, String>(0))?
+                    .collect::<rusqlite::Result<_>>()?;
+                Some(ids)
             }
-            _ => self.get_query_embedding(query)?,
+            None => None,
         };
 
         let index = self
             .vector_index
             .lock()
-            .map_err(|_| StorageError::Init("Vector index lock poisoned".to_string()))?;
+            .map_err(|_| StorageError::LockPoisoned("Vector index".into()))?;
+        if query_embedding.len() != index.dimensions() {
+            return Err(StorageError::VectorIndex(format!(
+                "Query embedding has {} dimensions but the vector index holds {}",
+                query_embedding.len(),
+                index.dimensions()
+            )));
+        }
 
-        index
-            .search(&query_embedding, limit as usize)
-            .map_err(|e| StorageError::Init(format!("Vector search failed: {}", e)))
+        match candidates {
+            Some(ids) => index.search_filtered(&query_embedding, limit as usize, |id| {
+                ids.contains(id)
+            }),
+            None => index.search(&query_embedding, limit as usize),
+        }
+        .map_err(|e| StorageError::VectorIndex(format!("Vector search failed: {}", e)))
     }
 
     /// Generate embeddings for nodes
@@ -1543,61 +5084,46 @@ impl Storage {
     ) -> Result<EmbeddingResult> {
         if !self.embedding_service.is_ready() {
             self.embedding_service.init().map_err(|e| {
-                StorageError::Init(format!("Failed to init embedding service: {}", e))
+                StorageError::EmbeddingUnavailable(format!("Failed to init embedding service: {}", e))
             })?;
         }
 
+        let Some(ids) = node_ids else {
+            let options = BackfillOptions { force, ..Default::default() };
+            let progress = self.backfill_embeddings(None, &options, None)?;
+            return Ok(EmbeddingResult {
+                successful: progress.embedded as i64,
+                failed: progress.failed as i64,
+                skipped: 0,
+                errors: progress.errors,
+            });
+        };
+
         let mut result = EmbeddingResult::default();
 
         let nodes: Vec<(String, String)> = {
-            let reader = self.reader.lock()
-                .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-            if let Some(ids) = node_ids {
-                let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
-                let query = format!(
-                    "SELECT id, content FROM knowledge_nodes WHERE id IN ({})",
-                    placeholders
-                );
-
-                let mut result_nodes = Vec::new();
-                {
-                    let mut stmt = reader.prepare(&query)?;
-                    let params: Vec<&dyn rusqlite::ToSql> =
-                        ids.iter().map(|s| s as &dyn rusqlite::ToSql).collect();
+            let reader = self.lock_reader()?;
+            let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            let query = format!(
+                "SELECT id, content FROM knowledge_nodes WHERE id IN ({})",
+                placeholders
+            );
 
-                    let rows = stmt.query_map(params.as_slice(), |row| {
-                        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-                    })?;
+            let mut stmt = reader.prepare(&query)?;
+            let params: Vec<&dyn rusqlite::ToSql> =
+                ids.iter().map(|s| s as &dyn rusqlite::ToSql).collect();
 
-                    for r in rows.flatten() {
-                        result_nodes.push(r);
-                    }
-                }
-                result_nodes
-            } else if force {
-                let mut stmt = reader
-                    .prepare("SELECT id, content FROM knowledge_nodes")?;
-                let rows = stmt.query_map([], |row| {
-                    Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-                })?;
-                rows.filter_map(|r| r.ok()).collect()
-            } else {
-                let mut stmt = reader.prepare(
-                    "SELECT id, content FROM knowledge_nodes
-                         WHERE has_embedding = 0 OR has_embedding IS NULL",
-                )?;
-                let rows = stmt.query_map([], |row| {
-                    Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-                })?;
-                rows.filter_map(|r| r.ok()).collect()
-            }
+            let rows = stmt.query_map(params.as_slice(), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+            rows.flatten().collect()
         };
 
         for (id, content) in nodes {
             if !force {
                 let has_emb: i32 = self
                     .reader.lock()
-                    .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?
+                    .map_err(|_| StorageError::LockPoisoned("Reader".into()))?
                     .query_row(
                         "SELECT COALESCE(has_embedding, 0) FROM knowledge_nodes WHERE id = ?1",
                         params![id],
@@ -1623,17 +5149,224 @@ impl Storage {
         Ok(result)
     }
 
-    /// Query memories valid at a specific time
-    pub fn query_at_time(
+    /// Embed nodes in budgeted, resumable slices
+    ///
+    /// Nodes are visited in id order after the cursor in `resume_token` (from
+    /// the start when None), checkpointing the cursor after every batch. The
+    /// slice returns when the job finishes, a budget in `options` runs out, or
+    /// `cancel` is set; pass the returned `resume_token` to continue without
+    /// embedding any node twice.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    pub fn backfill_embeddings(
         &self,
-        point_in_time: DateTime<Utc>,
-        limit: i32,
-    ) -> Result<Vec<KnowledgeNode>> {
-        let timestamp = point_in_time.to_rfc3339();
+        resume_token: Option<&str>,
+        options: &BackfillOptions,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<BackfillProgress> {
+        if !self.embedding_service.is_ready() {
+            self.embedding_service.init().map_err(|e| {
+                StorageError::EmbeddingUnavailable(format!("Failed to init embedding service: {}", e))
+            })?;
+        }
+        self.run_backfill(resume_token, options, cancel, |id, content| {
+            self.generate_embedding_for_node(id, content)
+        })
+    }
 
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let mut stmt = reader.prepare(
+    /// Backfill loop behind `backfill_embeddings`, with the per-node embed step supplied
+    #[cfg_attr(not(all(feature = "embeddings", feature = "vector-search")), allow(dead_code))]
+    fn run_backfill(
+        &self,
+        resume_token: Option<&str>,
+        options: &BackfillOptions,
+        cancel: Option<&AtomicBool>,
+        mut embed: impl FnMut(&str, &str) -> Result<()>,
+    ) -> Result<BackfillProgress> {
+        let started = std::time::Instant::now();
+        let mut progress = match (resume_token, self.embedding_backfill_status()?) {
+            // Continuing the recorded job keeps its running totals
+            (Some(token), Some(saved))
+                if saved.resume_token.as_deref() == Some(token) && saved.force == options.force =>
+            {
+                saved
+            }
+            _ => BackfillProgress {
+                force: options.force,
+                resume_token: resume_token.map(str::to_string),
+                ..Default::default()
+            },
+        };
+        progress.cancelled = false;
+        progress.errors.clear();
+        let mut cursor = progress.resume_token.clone().unwrap_or_default();
+        let mut slice_count = 0usize;
+
+        let out_of_budget = |slice_count: usize| {
+            options.max_nodes.is_some_and(|max| slice_count >= max)
+                || options.time_budget.is_some_and(|budget| started.elapsed() >= budget)
+        };
+        let mut finished = false;
+        'batches: loop {
+            let batch = self.backfill_batch(&cursor, options.force, options.batch_size.max(1))?;
+            if batch.is_empty() {
+                finished = true;
+                break;
+            }
+            for (id, content) in batch {
+                if cancel.is_some_and(|flag| flag.load(Ordering::Relaxed)) {
+                    progress.cancelled = true;
+                    break 'batches;
+                }
+                if out_of_budget(slice_count) {
+                    break 'batches;
+                }
+                match embed(&id, &content) {
+                    Ok(()) => progress.embedded += 1,
+                    Err(e) => {
+                        progress.failed += 1;
+                        progress.errors.push(format!("{}: {}", id, e));
+                    }
+                }
+                progress.processed += 1;
+                slice_count += 1;
+                cursor = id;
+            }
+            progress.resume_token = Some(cursor.clone());
+            progress.updated_at = Some(self.now());
+            self.save_backfill_progress(&progress)?;
+        }
+
+        if finished {
+            progress.resume_token = None;
+            progress.remaining = 0;
+        } else {
+            progress.resume_token = Some(cursor.clone());
+            progress.remaining = self.backfill_remaining(&cursor, options.force)?;
+        }
+        progress.updated_at = Some(self.now());
+        self.save_backfill_progress(&progress)?;
+        Ok(progress)
+    }
+
+    /// Next nodes after `cursor` a backfill should embed, in id order
+    #[cfg_attr(not(all(feature = "embeddings", feature = "vector-search")), allow(dead_code))]
+    fn backfill_batch(&self, cursor: &str, force: bool, limit: usize) -> Result<Vec<(String, String)>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare_cached(
+            "SELECT id, content FROM knowledge_nodes
+             WHERE id > ?1 AND (?2 OR COALESCE(has_embedding, 0) = 0)
+             ORDER BY id LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(params![cursor, force, limit as i64], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    fn backfill_remaining(&self, cursor: &str, force: bool) -> Result<usize> {
+        let reader = self.lock_reader()?;
+        let count: i64 = reader.query_row(
+            "SELECT COUNT(*) FROM knowledge_nodes
+             WHERE id > ?1 AND (?2 OR COALESCE(has_embedding, 0) = 0)",
+            params![cursor, force],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    #[cfg_attr(not(all(feature = "embeddings", feature = "vector-search")), allow(dead_code))]
+    fn save_backfill_progress(&self, progress: &BackfillProgress) -> Result<()> {
+        let json = serde_json::to_string(progress)
+            .map_err(|e| StorageError::Config(format!("Backfill state: {}", e)))?;
+        self.lock_writer()?
+            .execute(
+                "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)",
+                params![BACKFILL_META_KEY, json],
+            )?;
+        Ok(())
+    }
+
+    /// The last recorded embedding backfill job, with `remaining` recounted
+    pub fn embedding_backfill_status(&self) -> Result<Option<BackfillProgress>> {
+        let saved: Option<String> = {
+            let reader = self.lock_reader()?;
+            reader
+                .query_row(
+                    "SELECT value FROM meta WHERE key = ?1",
+                    params![BACKFILL_META_KEY],
+                    |row| row.get(0),
+                )
+                .optional()?
+        };
+        let Some(mut progress) = saved.and_then(|json| serde_json::from_str::<BackfillProgress>(&json).ok())
+        else {
+            return Ok(None);
+        };
+        if let Some(cursor) = progress.resume_token.clone() {
+            progress.remaining = self.backfill_remaining(&cursor, progress.force)?;
+        }
+        Ok(Some(progress))
+    }
+
+    /// Re-embed every node whose stored embedding used `strategy`
+    ///
+    /// Each node gets the strategy its content now selects, so this migrates
+    /// vectors after preprocessing changes (e.g. raw-text embeddings of code
+    /// made before adaptive embedding existed).
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    pub fn reembed_by_strategy(&self, strategy: EmbeddingStrategy) -> Result<EmbeddingResult> {
+        let ids: Vec<String> = {
+            let reader = self.lock_reader()?;
+            let mut stmt = reader.prepare("SELECT node_id FROM node_embeddings WHERE strategy = ?1")?;
+            stmt.query_map(params![strategy.as_str()], |row| row.get(0))?
+                .filter_map(|r| r.ok())
+                .collect()
+        };
+        if ids.is_empty() {
+            return Ok(EmbeddingResult::default());
+        }
+        self.generate_embeddings(Some(&ids), true)
+    }
+
+    /// Strategy a node's stored embedding was made with
+    pub fn get_embedding_strategy(&self, node_id: &str) -> Result<Option<EmbeddingStrategy>> {
+        let reader = self.lock_reader()?;
+        let name: Option<String> = reader
+            .query_row(
+                "SELECT strategy FROM node_embeddings WHERE node_id = ?1",
+                params![node_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(name.and_then(|n| EmbeddingStrategy::from_name(&n)))
+    }
+
+    /// Language detected for a node embedded as code (e.g. `"rust"`)
+    ///
+    /// `None` for prose, for code in an unrecognized language, and for
+    /// nodes without an embedding.
+    pub fn get_embedding_language(&self, node_id: &str) -> Result<Option<String>> {
+        let reader = self.lock_reader()?;
+        let language: Option<Option<String>> = reader
+            .query_row(
+                "SELECT language FROM node_embeddings WHERE node_id = ?1",
+                params![node_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(language.flatten())
+    }
+
+    /// Query memories valid at a specific time
+    pub fn query_at_time(
+        &self,
+        point_in_time: DateTime<Utc>,
+        limit: i32,
+    ) -> Result<Vec<KnowledgeNode>> {
+        let timestamp = point_in_time.to_rfc3339();
+
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
             "SELECT * FROM knowledge_nodes
              WHERE (valid_from IS NULL OR valid_from <= ?1)
              AND (valid_until IS NULL OR valid_until >= ?1)
@@ -1650,6 +5383,55 @@ impl Storage {
         Ok(result)
     }
 
+    /// Hybrid search over what was known at `as_of`
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    pub fn search_as_of(
+        &self,
+        query: &str,
+        as_of: DateTime<Utc>,
+        limit: i32,
+    ) -> Result<Vec<SearchResult>> {
+        self.search_as_of_with_config(query, as_of, limit, &HybridSearchConfig::default())
+    }
+
+    /// As-of hybrid search driven by a `HybridSearchConfig`
+    ///
+    /// Keeps memories that existed at `as_of` (created at or before it) and
+    /// whose validity window contains it, so memories superseded since then
+    /// still appear. Scores are blended 85/15 with recency relative to `as_of`.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    pub fn search_as_of_with_config(
+        &self,
+        query: &str,
+        as_of: DateTime<Utc>,
+        limit: i32,
+        config: &HybridSearchConfig,
+    ) -> Result<Vec<SearchResult>> {
+        // Later knowledge crowds out what was current then, so search wide
+        const AS_OF_POOL: i32 = 200;
+
+        let searcher = TemporalSearcher::new();
+        let mut results: Vec<SearchResult> = self
+            .hybrid_search_with_config(query, AS_OF_POOL.max(limit), config)?
+            .into_iter()
+            .filter(|r| {
+                r.node.created_at <= as_of
+                    && TemporalSearcher::is_valid_at(r.node.valid_from, r.node.valid_until, as_of)
+            })
+            .collect();
+        for result in &mut results {
+            let recency = searcher.recency_boost_at(result.node.created_at, as_of) as f32;
+            result.combined_score *= 0.85 + 0.15 * recency;
+        }
+        results.sort_by(|a, b| {
+            b.combined_score
+                .partial_cmp(&a.combined_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results.truncate(limit.max(0) as usize);
+        Ok(results)
+    }
+
     /// Query memories created/modified in a time range
     pub fn query_time_range(
         &self,
@@ -1657,54 +5439,30 @@ impl Storage {
         end: Option<DateTime<Utc>>,
         limit: i32,
     ) -> Result<Vec<KnowledgeNode>> {
-        let start_str = start.map(|dt| dt.to_rfc3339());
-        let end_str = end.map(|dt| dt.to_rfc3339());
-
-        let (query, params): (&str, Vec<Box<dyn rusqlite::ToSql>>) = match (&start_str, &end_str) {
-            (Some(s), Some(e)) => (
-                "SELECT * FROM knowledge_nodes
-                 WHERE created_at >= ?1 AND created_at <= ?2
-                 ORDER BY created_at DESC
-                 LIMIT ?3",
-                vec![
-                    Box::new(s.clone()) as Box<dyn rusqlite::ToSql>,
-                    Box::new(e.clone()) as Box<dyn rusqlite::ToSql>,
-                    Box::new(limit) as Box<dyn rusqlite::ToSql>,
-                ],
-            ),
-            (Some(s), None) => (
-                "SELECT * FROM knowledge_nodes
-                 WHERE created_at >= ?1
-                 ORDER BY created_at DESC
-                 LIMIT ?2",
-                vec![
-                    Box::new(s.clone()) as Box<dyn rusqlite::ToSql>,
-                    Box::new(limit) as Box<dyn rusqlite::ToSql>,
-                ],
-            ),
-            (None, Some(e)) => (
-                "SELECT * FROM knowledge_nodes
-                 WHERE created_at <= ?1
-                 ORDER BY created_at DESC
-                 LIMIT ?2",
-                vec![
-                    Box::new(e.clone()) as Box<dyn rusqlite::ToSql>,
-                    Box::new(limit) as Box<dyn rusqlite::ToSql>,
-                ],
-            ),
-            (None, None) => (
-                "SELECT * FROM knowledge_nodes
-                 ORDER BY created_at DESC
-                 LIMIT ?1",
-                vec![Box::new(limit) as Box<dyn rusqlite::ToSql>],
-            ),
-        };
+        self.query_time_range_in(None, start, end, limit)
+    }
 
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let mut stmt = reader.prepare(query)?;
-        let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
-        let nodes = stmt.query_map(params_refs.as_slice(), |row| Self::row_to_node(row))?;
+    /// `query_time_range` limited to one namespace, or all of them with `None`
+    pub(crate) fn query_time_range_in(
+        &self,
+        namespace: Option<&str>,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+        limit: i32,
+    ) -> Result<Vec<KnowledgeNode>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT * FROM knowledge_nodes
+             WHERE (?1 IS NULL OR created_at >= ?1)
+               AND (?2 IS NULL OR created_at <= ?2)
+               AND (?3 IS NULL OR namespace = ?3)
+             ORDER BY created_at DESC
+             LIMIT ?4",
+        )?;
+        let nodes = stmt.query_map(
+            params![start.map(|dt| dt.to_rfc3339()), end.map(|dt| dt.to_rfc3339()), namespace, limit],
+            |row| Self::row_to_node(row),
+        )?;
 
         let mut result = Vec::new();
         for node in nodes {
@@ -1718,30 +5476,56 @@ impl Storage {
     /// Uses the real FSRS-6 retrievability formula: R = (1 + factor * t / S)^(-w20)
     /// with personalized w20 from fsrs_config table. Sentiment boost extends
     /// effective stability for emotional memories.
+    /// Pinned memories are skipped, as are memories decayed within the saved
+    /// `ConsolidationConfig::decay_freshness_minutes` and not accessed since.
+    /// Returns how many memories were rewritten.
     pub fn apply_decay(&self) -> Result<i32> {
+        let freshness = Duration::minutes(self.consolidation_config()?.decay_freshness_minutes);
+        self.apply_decay_in(None, freshness)
+    }
+
+    /// `apply_decay` limited to one namespace, or all of them with `None`,
+    /// skipping memories decayed less than `freshness` ago
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(decayed = tracing::field::Empty, elapsed_ms = tracing::field::Empty)
+    )]
+    pub(crate) fn apply_decay_in(&self, namespace: Option<&str>, freshness: Duration) -> Result<i32> {
+        let _timer = SpanTimer::start();
         // Read personalized w20 from config (falls back to default 0.1542)
         let w20 = self.get_fsrs_w20().unwrap_or(DEFAULT_DECAY);
         let sleep = crate::SleepConsolidation::new();
 
         const BATCH_SIZE: i64 = 500;
-        let now = Utc::now();
+        // Strength changes smaller than this are not worth a write
+        const DECAY_EPSILON: f64 = 1e-9;
+        let now = self.now();
+        let now_str = now.to_rfc3339();
+        let fresh_after = (now - freshness).to_rfc3339();
         let mut count = 0i32;
-        let mut offset = 0i64;
+        let mut last_id = String::new();
 
         loop {
-            // Read batch using reader
-            let batch: Vec<(String, String, f64, f64, f64, f64)> = {
-                let reader = self.reader.lock()
-                    .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
+            // Read the next page after `last_id` (keyset pagination on the primary key)
+            type DecayRow = (String, String, f64, f64, f64, f64, f64);
+            let batch: Vec<DecayRow> = {
+                let reader = self.lock_reader()?;
                 reader
                     .prepare(
                         "SELECT id, last_accessed, storage_strength, retrieval_strength,
-                                sentiment_magnitude, stability
+                                retention_strength, sentiment_magnitude, stability
                          FROM knowledge_nodes
+                         WHERE id > ?1
+                           AND (?3 IS NULL OR namespace = ?3)
+                           AND (last_decayed_at IS NULL OR last_decayed_at <= ?5
+                                OR last_accessed > last_decayed_at)
+                           AND id NOT IN (SELECT memory_id FROM memory_pins
+                                          WHERE pinned_until IS NULL OR pinned_until > ?4)
                          ORDER BY id
-                         LIMIT ?1 OFFSET ?2",
+                         LIMIT ?2",
                     )?
-                    .query_map(params![BATCH_SIZE, offset], |row| {
+                    .query_map(params![last_id, BATCH_SIZE, namespace, now_str, fresh_after], |row| {
                         Ok((
                             row.get(0)?,
                             row.get(1)?,
@@ -1749,25 +5533,29 @@ impl Storage {
                             row.get(3)?,
                             row.get(4)?,
                             row.get(5)?,
+                            row.get(6)?,
                         ))
                     })?
                     .filter_map(|r| r.ok())
                     .collect()
             };
 
-            if batch.is_empty() {
+            let Some(last) = batch.last() else {
                 break;
-            }
-
-            let batch_len = batch.len() as i64;
+            };
+            last_id = last.0.clone();
 
             // Write batch using writer transaction
             {
-                let mut writer = self.writer.lock()
-                    .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
+                let mut writer = self.lock_writer()?;
                 let tx = writer.transaction()?;
+                let mut update = tx.prepare(
+                    "UPDATE knowledge_nodes
+                     SET retrieval_strength = ?1, retention_strength = ?2, last_decayed_at = ?3
+                     WHERE id = ?4",
+                )?;
 
-                for (id, last_accessed, storage_strength, _, sentiment_mag, stability) in &batch {
+                for (id, last_accessed, storage_strength, retrieval, retention, sentiment_mag, stability) in &batch {
                     let last = DateTime::parse_from_rfc3339(last_accessed)
                         .map(|dt| dt.with_timezone(&Utc))
                         .unwrap_or(now);
@@ -1775,519 +5563,1064 @@ impl Storage {
                     let days_since = (now - last).num_seconds() as f64 / 86400.0;
 
                     if days_since > 0.0 {
-                        // Sentiment boost: emotional memories decay slower (up to 1.5x stability)
-                        let effective_stability = stability * (1.0 + sentiment_mag * 0.5);
-
-                        // Real FSRS-6 retrievability with personalized w20
-                        let new_retrieval = retrievability_with_decay(
-                            effective_stability, days_since, w20,
-                        );
+                        let new_retrieval =
+                            Self::decayed_retrievability(*stability, *sentiment_mag, days_since, w20);
 
                         // Use SleepConsolidation for retention calculation
                         let new_retention = sleep.calculate_retention(*storage_strength, new_retrieval);
 
-                        tx.execute(
-                            "UPDATE knowledge_nodes SET retrieval_strength = ?1, retention_strength = ?2 WHERE id = ?3",
-                            params![new_retrieval, new_retention, id],
-                        )?;
-
-                        count += 1;
+                        if (new_retrieval - retrieval).abs() > DECAY_EPSILON
+                            || (new_retention - retention).abs() > DECAY_EPSILON
+                        {
+                            update.execute(params![new_retrieval, new_retention, now_str, id])?;
+                            count += 1;
+                        }
                     }
                 }
 
+                drop(update);
                 tx.commit()?;
             }
-            offset += batch_len;
         }
 
+        tracing::Span::current().record("decayed", count);
         Ok(count)
     }
 
+    /// FSRS-6 retrievability after `days_since` days, with the sentiment
+    /// boost to stability that `apply_decay` uses
+    fn decayed_retrievability(stability: f64, sentiment_mag: f64, days_since: f64, w20: f64) -> f64 {
+        // Sentiment boost: emotional memories decay slower (up to 1.5x stability)
+        let effective_stability = stability * (1.0 + sentiment_mag * 0.5);
+
+        // Real FSRS-6 retrievability with personalized w20
+        retrievability_with_decay(effective_stability, days_since, w20)
+    }
+
+    /// Predicted retrievability of a memory at `at`
+    ///
+    /// Decays from `last_accessed` exactly as `apply_decay` would, using the
+    /// personalized w20. Times before the last access return 1.0.
+    pub fn retrievability_at(&self, id: &str, at: DateTime<Utc>) -> Result<f64> {
+        Ok(self.forgetting_curve(id, &[at])?[0])
+    }
+
+    /// Predicted retrievability of a memory at each of `points`, for charting
+    /// its forgetting curve
+    pub fn forgetting_curve(&self, id: &str, points: &[DateTime<Utc>]) -> Result<Vec<f64>> {
+        let node = self
+            .get_node(id)?
+            .ok_or_else(|| StorageError::NotFound(id.to_string()))?;
+        let w20 = self.get_fsrs_w20().unwrap_or(DEFAULT_DECAY);
+
+        Ok(points
+            .iter()
+            .map(|at| {
+                let days_since = (*at - node.last_accessed).num_seconds() as f64 / 86400.0;
+                if days_since > 0.0 {
+                    Self::decayed_retrievability(node.stability, node.sentiment_magnitude, days_since, w20)
+                } else {
+                    1.0
+                }
+            })
+            .collect())
+    }
+
+    /// Current FSRS parameters used by the scheduler
+    pub fn fsrs_parameters(&self) -> Result<FSRSParameters> {
+        let scheduler = self.scheduler.lock()
+            .map_err(|_| StorageError::LockPoisoned("Scheduler".into()))?;
+        Ok(scheduler.params().clone())
+    }
+
+    /// Replace the FSRS parameters, e.g. weights already optimized in Anki
+    ///
+    /// The full weight vector (`w0`..`w20`) and settings are persisted in
+    /// `fsrs_config` so they survive restarts, and the scheduler is reloaded.
+    /// w20 shares its row with the consolidation optimizer, which may refine
+    /// it further once enough access history exists.
+    pub fn set_fsrs_parameters(&self, params: FSRSParameters) -> Result<()> {
+        params.validate().map_err(|reason| StorageError::InvalidInput {
+            field: "fsrs_parameters",
+            reason,
+        })?;
+
+        {
+            let mut writer = self.lock_writer()?;
+            let tx = writer.transaction()?;
+            let now = self.now().to_rfc3339();
+            let settings = [
+                ("desired_retention".to_string(), params.desired_retention),
+                ("max_interval".to_string(), params.max_interval as f64),
+                ("enable_fuzz".to_string(), if params.enable_fuzz { 1.0 } else { 0.0 }),
+            ];
+            let weights = params.weights.iter().enumerate().map(|(i, w)| (format!("w{}", i), *w));
+            for (key, value) in weights.chain(settings) {
+                tx.execute(
+                    "INSERT OR REPLACE INTO fsrs_config (key, value, updated_at) VALUES (?1, ?2, ?3)",
+                    params![key, value, now],
+                )?;
+            }
+            tx.commit()?;
+        }
+
+        self.scheduler.lock()
+            .map_err(|_| StorageError::LockPoisoned("Scheduler".into()))?
+            .set_params(params);
+        Ok(())
+    }
+
+    /// Parameters saved by [`Self::set_fsrs_parameters`], if any
+    ///
+    /// Databases that only carry the optimizer's w20 row keep the defaults.
+    fn load_fsrs_parameters(conn: &Connection) -> Result<Option<FSRSParameters>> {
+        let mut stmt = conn.prepare("SELECT key, value FROM fsrs_config")?;
+        let config: HashMap<String, f64> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+
+        let weights: Option<Vec<f64>> = (0..21).map(|i| config.get(&format!("w{}", i)).copied()).collect();
+        let Some(weights) = weights.filter(|_| config.contains_key("w0")) else {
+            return Ok(None);
+        };
+        let mut params = FSRSParameters::from_weights(&weights).map_err(|e| {
+            StorageError::Config(format!("Invalid FSRS parameters in fsrs_config: {}", e))
+        })?;
+        if let Some(retention) = config.get("desired_retention") {
+            params.desired_retention = *retention;
+        }
+        if let Some(max_interval) = config.get("max_interval") {
+            params.max_interval = *max_interval as i32;
+        }
+        if let Some(fuzz) = config.get("enable_fuzz") {
+            params.enable_fuzz = *fuzz != 0.0;
+        }
+        Ok(Some(params))
+    }
+
     /// Read personalized w20 from fsrs_config table
     fn get_fsrs_w20(&self) -> Result<f64> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
+        let reader = self.lock_reader()?;
         reader
             .query_row(
                 "SELECT value FROM fsrs_config WHERE key = 'w20'",
                 [],
                 |row| row.get(0),
             )
-            .map_err(|e| StorageError::Init(format!("Failed to read w20: {}", e)))
+            .map_err(|e| StorageError::Config(format!("Failed to read w20: {}", e)))
     }
 
-    /// Run full FSRS-6 consolidation cycle (v1.4.0)
+    /// Run the consolidation cycle: every step `consolidation_config` enables,
+    /// in `ConsolidationPipeline` order
     ///
-    /// 7-step automatic consolidation:
-    /// 1. Apply FSRS-6 decay with personalized w20
-    /// 2. Promote emotional memories (synaptic tagging)
-    /// 3. Generate missing embeddings
-    /// 4. Auto-dedup: merge similar memories (episodic → semantic)
-    /// 5. Compute ACT-R base-level activations from access history
-    /// 6. Prune old access log entries (keep 90 days)
-    /// 7. Optimize w20 if enough usage data exists
+    /// Decay, emotional promotion, embedding backfill, dedup, ACT-R
+    /// activations, access log pruning, w20 optimization, dream insights,
+    /// compression, state transitions, synaptic capture, cross-project
+    /// learning, importance evolution, connection and search index
+    /// maintenance, auto-promotion and the retention target GC.
     pub fn run_consolidation(&self) -> Result<ConsolidationResult> {
-        let start = std::time::Instant::now();
+        self.run_consolidation_in(None, &ConsolidationLimits::default(), &mut |_| {})
+    }
 
-        // v1.5.0: Use SleepConsolidation for structured consolidation
-        let sleep = crate::SleepConsolidation::new();
+    /// Run the consolidation cycle, calling `progress` as each step completes
+    pub fn run_consolidation_with_progress(
+        &self,
+        progress: &mut dyn FnMut(ConsolidationProgress),
+    ) -> Result<ConsolidationResult> {
+        self.run_consolidation_in(None, &ConsolidationLimits::default(), progress)
+    }
 
-        // 1. Apply FSRS-6 decay with real formula + personalized w20
-        let decay_applied = self.apply_decay()? as i64;
+    /// Run the consolidation cycle within `limits`, calling `progress` as
+    /// each step completes
+    ///
+    /// Between steps the run stops once `limits.deadline` has passed or
+    /// `limits.cancel` is cancelled; the result's `stopped_early` and
+    /// `steps_not_run` say so. A run stopped early is recorded in the
+    /// history but does not count as a full run for skipping later ones.
+    pub fn run_consolidation_with_limits(
+        &self,
+        limits: &ConsolidationLimits,
+        progress: &mut dyn FnMut(ConsolidationProgress),
+    ) -> Result<ConsolidationResult> {
+        self.run_consolidation_in(None, limits, progress)
+    }
 
-        // 2. Promote emotional memories via SleepConsolidation
-        let mut promoted = 0i64;
-        {
-            let candidates: Vec<(String, f64, f64)> = {
-                let reader = self.reader.lock()
-                    .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-                reader
-                    .prepare(
-                        "SELECT id, sentiment_magnitude, storage_strength
-                         FROM knowledge_nodes
-                         WHERE storage_strength < 10.0"
-                    )?
-                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
-                    .filter_map(|r| r.ok())
-                    .collect()
+    /// Consolidation cycle with decay, promotion, dedup and dream insights
+    /// limited to one namespace, or covering all of them with `None`
+    ///
+    /// Merges and insights never mix namespaces either way. The remaining
+    /// steps maintain shared structures (vector index, access log, w20, FTS)
+    /// and always run database-wide.
+    #[tracing::instrument(
+        level = "debug",
+        skip(self, limits, progress),
+        fields(
+            skipped = tracing::field::Empty,
+            nodes_processed = tracing::field::Empty,
+            step_errors = tracing::field::Empty,
+            elapsed_ms = tracing::field::Empty,
+        )
+    )]
+    pub(crate) fn run_consolidation_in(
+        &self,
+        namespace: Option<&str>,
+        limits: &ConsolidationLimits,
+        progress: &mut dyn FnMut(ConsolidationProgress),
+    ) -> Result<ConsolidationResult> {
+        let _timer = SpanTimer::start();
+        let span = tracing::Span::current();
+        if let Some(reason) = self.consolidation_skip_reason(&self.consolidation_config()?)? {
+            span.record("skipped", reason.as_str());
+            let result = ConsolidationResult {
+                skipped: Some(reason),
+                ..Default::default()
             };
+            self.record_consolidation_run(&result)?;
+            return Ok(result);
+        }
+        let result = self.run_pipeline_in(&ConsolidationPipeline::standard(), namespace, limits, progress)?;
+        span.record("nodes_processed", result.nodes_processed);
+        span.record("step_errors", result.step_errors.len());
+        Ok(result)
+    }
 
-            let writer = self.writer.lock()
-                .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-            for (id, sentiment_mag, storage_strength) in &candidates {
-                if sleep.should_promote(*sentiment_mag, *storage_strength) {
-                    let boosted = sleep.promotion_boost(*storage_strength);
-                    writer.execute(
-                        "UPDATE knowledge_nodes SET storage_strength = ?1 WHERE id = ?2",
-                        params![boosted, id],
-                    )?;
-                    promoted += 1;
-                }
-            }
+    /// Run a custom consolidation pipeline
+    ///
+    /// Standard steps run when the saved `ConsolidationConfig` enables them;
+    /// steps with other names always run. The run is recorded in the
+    /// consolidation history like `run_consolidation`.
+    pub fn run_consolidation_pipeline(&self, pipeline: &ConsolidationPipeline) -> Result<ConsolidationResult> {
+        self.run_pipeline_in(pipeline, None, &ConsolidationLimits::default(), &mut |_| {})
+    }
+
+    fn run_pipeline_in(
+        &self,
+        pipeline: &ConsolidationPipeline,
+        namespace: Option<&str>,
+        limits: &ConsolidationLimits,
+        progress: &mut dyn FnMut(ConsolidationProgress),
+    ) -> Result<ConsolidationResult> {
+        let start = std::time::Instant::now();
+        let config = self.consolidation_config()?;
+        let ctx = StepContext { namespace, config: &config };
+
+        // Queued accesses reinforce their neighbors before decay runs
+        let neighbors_reinforced = self.flush_neighbor_reinforcement().unwrap_or_else(|e| {
+            tracing::warn!("Failed to reinforce neighbors: {}", e);
+            0
+        });
+        // Pins whose TTL has passed are released before anything decays
+        let pins_expired = self.expire_pins().unwrap_or_else(|e| {
+            tracing::warn!("Failed to expire pins: {}", e);
+            0
+        });
+        if pins_expired > 0 {
+            tracing::info!(expired = pins_expired, "Expired memory pins");
+        }
+        let mut result = pipeline.run(self, &ctx, limits, progress);
+        result.neighbors_reinforced = neighbors_reinforced as i64;
+        result.pins_expired = pins_expired as i64;
+        result.duration_ms = start.elapsed().as_millis() as i64;
+        self.metrics.consolidation_run();
+
+        // A complete database-wide run has seen every change so far; changes
+        // made while it ran are counted with it
+        if namespace.is_none() && result.stopped_early.is_none() {
+            self.mutations_since_consolidation.store(0, Ordering::Relaxed);
+            self.persist_mutation_count(0);
         }
+        self.record_consolidation_run(&result)?;
 
-        // 3. Generate missing embeddings
-        #[cfg(all(feature = "embeddings", feature = "vector-search"))]
-        let embeddings_generated = self.generate_missing_embeddings()?;
-        #[cfg(not(all(feature = "embeddings", feature = "vector-search")))]
-        let embeddings_generated = 0i64;
+        Ok(result)
+    }
 
-        // 4. Auto-dedup: merge similar memories (episodic → semantic consolidation)
-        #[cfg(all(feature = "embeddings", feature = "vector-search"))]
-        let duplicates_merged = self.auto_dedup_consolidation().unwrap_or(0);
-        #[cfg(not(all(feature = "embeddings", feature = "vector-search")))]
-        let duplicates_merged = 0i64;
+    /// Run one consolidation step by name, whether or not it is enabled
+    ///
+    /// Uses the saved step parameters; nothing is added to the
+    /// consolidation history.
+    pub fn run_consolidation_step(&self, name: &str) -> Result<StepResult> {
+        self.run_consolidation_step_in(None, name)
+    }
 
-        // 5. Compute ACT-R activations from access history
-        let activations_computed = self.compute_act_r_activations().unwrap_or(0);
+    /// `run_consolidation_step` limited to one namespace, or all with `None`
+    pub(crate) fn run_consolidation_step_in(&self, namespace: Option<&str>, name: &str) -> Result<StepResult> {
+        let pipeline = ConsolidationPipeline::standard();
+        let step = pipeline.step(name).ok_or_else(|| StorageError::InvalidInput {
+            field: "step",
+            reason: format!(
+                "unknown consolidation step '{}'; expected one of {}",
+                name,
+                ConsolidationPipeline::step_names().join(", ")
+            ),
+        })?;
+        let config = self.consolidation_config()?;
+        step.run(self, &StepContext { namespace, config: &config })
+    }
 
-        // 6. Prune old access log entries (keep 90 days)
-        let _ = self.prune_access_log();
+    /// The saved consolidation config (defaults if none was saved), with
+    /// environment overrides applied
+    pub fn consolidation_config(&self) -> Result<ConsolidationConfig> {
+        let saved: Option<String> = {
+            let reader = self.lock_reader()?;
+            reader
+                .query_row(
+                    "SELECT value FROM meta WHERE key = ?1",
+                    params![CONSOLIDATION_CONFIG_META_KEY],
+                    |row| row.get(0),
+                )
+                .optional()?
+        };
+        let config = match saved {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| StorageError::Config(format!("Saved consolidation config: {}", e)))?,
+            None => ConsolidationConfig::default(),
+        };
+        Ok(config.with_env_overrides())
+    }
 
-        // 7. Optimize w20 if enough usage data
-        let w20_optimized = self.optimize_w20_if_ready().unwrap_or(None);
+    /// Validate and save the consolidation config
+    pub fn set_consolidation_config(&self, config: &ConsolidationConfig) -> Result<()> {
+        config.validate()?;
+        let json = serde_json::to_string(config)
+            .map_err(|e| StorageError::Config(format!("Consolidation config: {}", e)))?;
+        self.lock_writer()?
+            .execute(
+                "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)",
+                params![CONSOLIDATION_CONFIG_META_KEY, json],
+            )?;
+        self.note_mutation();
+        Ok(())
+    }
 
-        // ====================================================================
-        // v1.5.0: Extended consolidation steps 8-15
-        // ====================================================================
+    /// Memories ingested, updated, deleted, reviewed or given feedback (and
+    /// retention or consolidation settings changed) since the last run
+    pub fn mutations_since_consolidation(&self) -> u64 {
+        self.mutations_since_consolidation.load(Ordering::Relaxed)
+    }
 
-        // 8. Memory Dreams — synthesize insights (sync path)
-        let mut _insights_generated = 0i64;
-        {
-            let dreamer = crate::advanced::dreams::MemoryDreamer::new();
-            let recent = self.get_all_nodes(100, 0).unwrap_or_default();
-            let dream_memories: Vec<crate::advanced::dreams::DreamMemory> = recent
-                .iter()
-                .map(|n| crate::advanced::dreams::DreamMemory {
-                    id: n.id.clone(),
-                    content: n.content.clone(),
-                    embedding: None,
-                    tags: n.tags.clone(),
-                    created_at: n.created_at,
-                    access_count: n.reps as u32,
-                })
-                .collect();
-            if dream_memories.len() >= 5 {
-                let insights = dreamer.synthesize_insights(&dream_memories);
-                _insights_generated = insights.len() as i64;
-                for insight in &insights {
-                    let record = InsightRecord {
-                        id: Uuid::new_v4().to_string(),
-                        insight: insight.insight.clone(),
-                        source_memories: insight.source_memories.clone(),
-                        confidence: insight.confidence,
-                        novelty_score: insight.novelty_score,
-                        insight_type: format!("{:?}", insight.insight_type),
-                        generated_at: Utc::now(),
-                        tags: vec![],
-                        feedback: None,
-                        applied_count: 0,
-                    };
-                    let _ = self.save_insight(&record);
-                }
-            }
+    fn load_mutation_count(conn: &Connection) -> Result<u64> {
+        let saved: Option<String> = conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = ?1",
+                params![MUTATIONS_META_KEY],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(saved.and_then(|v| v.parse().ok()).unwrap_or(0))
+    }
+
+    /// Count a change for the skip check; call without holding the writer lock
+    fn note_mutation(&self) {
+        let count = self.mutations_since_consolidation.fetch_add(1, Ordering::Relaxed) + 1;
+        if count == 1 || count % MUTATION_PERSIST_EVERY == 0 {
+            self.persist_mutation_count(count);
         }
+    }
 
-        // 9. Memory Compression (old memories → summaries)
-        let mut _memories_compressed = 0i64;
-        {
-            let mut compressor = crate::advanced::compression::MemoryCompressor::new();
-            let all_nodes = self.get_all_nodes(500, 0).unwrap_or_default();
-            let thirty_days_ago = Utc::now() - Duration::days(30);
-            let old_memories: Vec<crate::advanced::compression::MemoryForCompression> = all_nodes
-                .iter()
-                .filter(|n| n.created_at < thirty_days_ago && n.retention_strength < 0.5)
-                .map(|n| crate::advanced::compression::MemoryForCompression {
-                    id: n.id.clone(),
-                    content: n.content.clone(),
-                    tags: n.tags.clone(),
-                    created_at: n.created_at,
-                    last_accessed: Some(n.last_accessed),
-                    embedding: None,
-                })
-                .collect();
-            if old_memories.len() >= 3 {
-                let groups = compressor.find_compressible_groups(&old_memories);
-                for group_ids in groups.iter().take(5) {
-                    // Limit to 5 groups per consolidation
-                    let group: Vec<_> = old_memories
-                        .iter()
-                        .filter(|m| group_ids.contains(&m.id))
-                        .cloned()
-                        .collect();
-                    if let Some(_compressed) = compressor.compress(&group) {
-                        _memories_compressed += group.len() as i64;
-                    }
-                }
-            }
+    fn persist_mutation_count(&self, count: u64) {
+        let Ok(writer) = self.writer.lock() else { return };
+        if let Err(e) = writer.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)",
+            params![MUTATIONS_META_KEY, count.to_string()],
+        ) {
+            tracing::warn!("Failed to persist mutation count: {}", e);
         }
+    }
 
-        // 10. Memory State Transitions (Active→Dormant→Silent→Unavailable)
-        let _state_transitions: i64;
-        {
-            let service = crate::neuroscience::memory_states::StateUpdateService::new();
-            let all_nodes = self.get_all_nodes(500, 0).unwrap_or_default();
-            let mut lifecycles: Vec<crate::neuroscience::memory_states::MemoryLifecycle> = all_nodes
-                .iter()
-                .map(|n| {
-                    let mut lc = crate::neuroscience::memory_states::MemoryLifecycle::new();
-                    lc.last_access = n.last_accessed;
-                    lc.access_count = n.reps as u32;
-                    lc.state = if n.retention_strength > 0.7 {
-                        crate::neuroscience::memory_states::MemoryState::Active
-                    } else if n.retention_strength > 0.3 {
-                        crate::neuroscience::memory_states::MemoryState::Dormant
-                    } else if n.retention_strength > 0.1 {
-                        crate::neuroscience::memory_states::MemoryState::Silent
-                    } else {
-                        crate::neuroscience::memory_states::MemoryState::Unavailable
-                    };
-                    lc
-                })
-                .collect();
-            let batch_result = service.batch_update(&mut lifecycles);
-            _state_transitions = batch_result.total_transitions as i64;
+    /// Why `run_consolidation` would skip the next run, if it would
+    ///
+    /// Runs are skipped while fewer than `skip_below_mutations` changes
+    /// happened since a full run that finished within `max_skip_hours`.
+    fn consolidation_skip_reason(&self, config: &ConsolidationConfig) -> Result<Option<String>> {
+        let mutations = self.mutations_since_consolidation();
+        if config.skip_below_mutations == 0 || mutations >= config.skip_below_mutations {
+            return Ok(None);
         }
-
-        // 11. Synaptic Capture Sweep (retroactive importance)
-        {
-            let mut sts = crate::neuroscience::synaptic_tagging::SynapticTaggingSystem::new();
-            let _ = sts.sweep_for_capture(Utc::now());
-            sts.decay_tags();
+        let Some(last) = self.get_last_consolidation()? else {
+            return Ok(None);
+        };
+        if self.now() - last > Duration::hours(config.max_skip_hours) {
+            return Ok(None);
         }
+        Ok(Some(match mutations {
+            0 => "no changes".to_string(),
+            n => format!("{} changes, below {}", n, config.skip_below_mutations),
+        }))
+    }
 
-        // 12. Cross-Project Learning (detect universal patterns)
-        {
-            let learner = crate::advanced::cross_project::CrossProjectLearner::new();
-            let _patterns = learner.find_universal_patterns();
-        }
+    /// Append `result` to the consolidation history
+    fn record_consolidation_run(&self, result: &ConsolidationResult) -> Result<()> {
+        let writer = self.lock_writer()?;
+        let _ = writer.execute(
+            "INSERT INTO consolidation_history (completed_at, duration_ms, memories_replayed, duplicates_merged, activations_computed, w20_optimized, step_timings, step_errors, skipped_reason, stopped_reason, steps_not_run)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                self.now().to_rfc3339(),
+                result.duration_ms,
+                result.decay_applied,
+                result.duplicates_merged,
+                result.activations_computed,
+                result.w20_optimized,
+                serde_json::to_string(&result.step_timings).unwrap_or_else(|_| "[]".into()),
+                serde_json::to_string(&result.step_errors).unwrap_or_else(|_| "[]".into()),
+                result.skipped,
+                result.stopped_early,
+                serde_json::to_string(&result.steps_not_run).unwrap_or_else(|_| "[]".into()),
+            ],
+        );
+        Ok(())
+    }
 
-        // 13. Hippocampal Index Maintenance
-        {
-            let index = crate::neuroscience::hippocampal_index::HippocampalIndex::new();
-            let _ = index.prune_weak_links();
-        }
+    /// Boost the storage strength of emotional memories (synaptic tagging)
+    pub(crate) fn promote_emotional_in(&self, namespace: Option<&str>) -> Result<i64> {
+        let sleep = crate::SleepConsolidation::new();
+        let candidates: Vec<(String, f64, f64)> = {
+            let reader = self.lock_reader()?;
+            reader
+                .prepare(
+                    "SELECT id, sentiment_magnitude, storage_strength
+                     FROM knowledge_nodes
+                     WHERE storage_strength < 10.0 AND (?1 IS NULL OR namespace = ?1)"
+                )?
+                .query_map(params![namespace], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                .filter_map(|r| r.ok())
+                .collect()
+        };
 
-        // 14. Importance Evolution (decay stale importance)
-        {
-            let tracker = crate::advanced::importance::ImportanceTracker::new();
-            tracker.apply_importance_decay();
+        let mut promoted = 0i64;
+        let writer = self.lock_writer()?;
+        for (id, sentiment_mag, storage_strength) in &candidates {
+            if sleep.should_promote(*sentiment_mag, *storage_strength) {
+                let boosted = sleep.promotion_boost(*storage_strength);
+                writer.execute(
+                    "UPDATE knowledge_nodes SET storage_strength = ?1 WHERE id = ?2",
+                    params![boosted, id],
+                )?;
+                promoted += 1;
+            }
         }
+        Ok(promoted)
+    }
 
-        // 15. Connection Graph Maintenance (decay + prune weak connections)
-        let _connections_pruned = self.prune_weak_connections(0.05).unwrap_or(0) as i64;
-
-        // 16. FTS5 index optimization — merge segments for faster keyword search
-        // 17. Run PRAGMA optimize to refresh query planner statistics
-        {
-            let writer = self.writer.lock()
-                .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-            let _ = writer.execute_batch(
-                "INSERT INTO knowledge_fts(knowledge_fts) VALUES('optimize');"
-            );
-            let _ = writer.execute_batch("PRAGMA optimize;");
-        }
+    /// Merge FTS5 segments and refresh query planner statistics
+    pub(crate) fn optimize_search_index(&self) -> Result<()> {
+        let writer = self.lock_writer()?;
+        let _ = writer.execute_batch(
+            "INSERT INTO knowledge_fts(knowledge_fts) VALUES('optimize');"
+        );
+        let _ = writer.execute_batch("PRAGMA optimize;");
+        Ok(())
+    }
 
-        // ====================================================================
-        // v1.9.0: Autonomic features (18-20)
-        // ====================================================================
+    /// Find clusters of likely duplicate memories without modifying anything
+    ///
+    /// Runs the same greedy clustering over the vector index as
+    /// consolidation's auto-dedup, across every embedded memory, and returns
+    /// each cluster's node IDs, anchor first, so they can be reviewed before
+    /// merging. Clusters never span namespaces.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    pub fn find_duplicate_clusters(&self, threshold: f32) -> Result<Vec<Vec<String>>> {
+        let mut clusters = Vec::new();
+        self.scan_duplicate_clusters(None, None, threshold.clamp(0.0, 1.0), None, &mut |cluster| {
+            clusters.push(cluster);
+            Ok(())
+        })?;
+        Ok(clusters)
+    }
 
-        // 18. Auto-promote memories with 3+ accesses in 24h (frequency-dependent potentiation)
-        let auto_promoted = self.auto_promote_frequent_access().unwrap_or(0);
-        promoted += auto_promoted;
+    /// Greedy duplicate clustering through the vector index
+    ///
+    /// Each embedded memory (only those changed or embedded at or after
+    /// `since`, when given, least recently changed first) that no earlier
+    /// cluster claimed anchors a cluster of its `DEDUP_NEIGHBORS` nearest
+    /// unclaimed neighbors in the same namespace at least `threshold`
+    /// similar to it. `on_cluster` gets each cluster's IDs, anchor first, as
+    /// it is found. Work is bounded by the changed memories rather than the
+    /// whole corpus, and stops once `deadline` passes.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn scan_duplicate_clusters(
+        &self,
+        namespace: Option<&str>,
+        since: Option<&str>,
+        threshold: f32,
+        deadline: Option<std::time::Instant>,
+        on_cluster: &mut dyn FnMut(Vec<String>) -> Result<()>,
+    ) -> Result<DedupScan> {
+        let candidates: Vec<(String, Option<String>, String)> = {
+            let reader = self.lock_reader()?;
+            reader
+                .prepare(
+                    "SELECT n.id, n.namespace, MAX(n.updated_at, e.created_at) AS changed_at
+                     FROM knowledge_nodes n JOIN node_embeddings e ON e.node_id = n.id
+                     WHERE (?1 IS NULL OR n.namespace = ?1)
+                       AND (?2 IS NULL OR n.updated_at >= ?2 OR e.created_at >= ?2)
+                     ORDER BY changed_at, n.id",
+                )?
+                .query_map(params![namespace, since], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                .collect::<rusqlite::Result<_>>()?
+        };
 
-        // 19. Retention Target System — auto-GC if avg retention below target
-        let mut gc_triggered = false;
-        {
-            let retention_target: f64 = std::env::var("VESTIGE_RETENTION_TARGET")
-                .ok()
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(0.8);
-
-            let avg_retention = self.get_avg_retention().unwrap_or(1.0);
-            let total = self.get_stats().map(|s| s.total_nodes).unwrap_or(0);
-            let below_target = self.count_memories_below_retention(0.3).unwrap_or(0);
-
-            if avg_retention < retention_target && below_target > 0 {
-                let gc_count = self.gc_below_retention(0.3, 30).unwrap_or(0);
-                if gc_count > 0 {
-                    gc_triggered = true;
-                    tracing::info!(
-                        avg_retention = avg_retention,
-                        target = retention_target,
-                        gc_count = gc_count,
-                        "Retention target auto-GC: removed {} low-retention memories",
-                        gc_count
-                    );
+        let mut scan = DedupScan::default();
+        let mut claimed: HashSet<String> = HashSet::new();
+        for (id, node_namespace, changed_at) in candidates {
+            if claimed.contains(&id) {
+                continue;
+            }
+            if deadline.is_some_and(|deadline| std::time::Instant::now() >= deadline) {
+                scan.resume_from = Some(changed_at);
+                break;
+            }
+            // Merged away by an earlier cluster's callback
+            let Some(embedding) = self.get_node_embedding(&id)? else {
+                continue;
+            };
+            scan.examined += 1;
+
+            let neighbors = self
+                .vector_index
+                .lock()
+                .map_err(|_| StorageError::LockPoisoned("Vector index".into()))?
+                .search_with_threshold(&embedding, DEDUP_NEIGHBORS + 1, threshold);
+            let Ok(neighbors) = neighbors else { continue };
+
+            let mut cluster = vec![id.clone()];
+            for (neighbor_id, _) in neighbors {
+                if neighbor_id == id || claimed.contains(&neighbor_id) {
+                    continue;
+                }
+                let neighbor_namespace: Option<Option<String>> = self
+                    .lock_reader()?
+                    .query_row(
+                        "SELECT namespace FROM knowledge_nodes WHERE id = ?1",
+                        params![neighbor_id],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                if neighbor_namespace.as_ref() == Some(&node_namespace) {
+                    cluster.push(neighbor_id);
                 }
             }
 
-            // 20. Save retention snapshot for trend tracking
-            let _ = self.save_retention_snapshot(avg_retention, total, below_target, gc_triggered);
-        }
-
-        let duration = start.elapsed().as_millis() as i64;
-
-        // Record consolidation history
-        {
-            let writer = self.writer.lock()
-                .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-            let _ = writer.execute(
-                "INSERT INTO consolidation_history (completed_at, duration_ms, memories_replayed, duplicates_merged, activations_computed, w20_optimized)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-                params![
-                    Utc::now().to_rfc3339(),
-                    duration,
-                    decay_applied,
-                    duplicates_merged,
-                    activations_computed,
-                    w20_optimized,
-                ],
-            );
+            if cluster.len() > 1 {
+                claimed.extend(cluster.iter().cloned());
+                scan.clusters += 1;
+                on_cluster(cluster)?;
+            }
         }
 
-        Ok(ConsolidationResult {
-            nodes_processed: decay_applied,
-            nodes_promoted: promoted,
-            nodes_pruned: 0,
-            decay_applied,
-            duration_ms: duration,
-            embeddings_generated,
-            duplicates_merged,
-            neighbors_reinforced: 0,
-            activations_computed,
-            w20_optimized,
-        })
+        Ok(scan)
     }
 
     /// Auto-deduplicate similar memories during consolidation (episodic → semantic merge)
     ///
-    /// Finds clusters with cosine similarity > 0.85, keeps the strongest node,
-    /// appends unique content from weaker nodes, and deletes duplicates.
+    /// Finds clusters with cosine similarity >= `threshold` around the
+    /// memories changed since the last pass (see `scan_duplicate_clusters`),
+    /// keeps the strongest node, appends unique content from weaker nodes,
+    /// and deletes duplicates. Stops after `budget`; memories not examined
+    /// in time are picked up first by the next pass.
     #[cfg(all(feature = "embeddings", feature = "vector-search"))]
-    fn auto_dedup_consolidation(&self) -> Result<i64> {
-        let all_embeddings = self.get_all_embeddings()?;
-        let n = all_embeddings.len();
-
-        if !(2..=2000).contains(&n) {
-            return Ok(0);
+    pub(crate) fn auto_dedup_consolidation(
+        &self,
+        namespace: Option<&str>,
+        threshold: f32,
+        budget: std::time::Duration,
+    ) -> Result<DedupOutcome> {
+        let cursor_key = match namespace {
+            Some(ns) => format!("{}:{}", DEDUP_CURSOR_META_KEY, ns),
+            None => DEDUP_CURSOR_META_KEY.to_string(),
+        };
+        let since: Option<String> = self
+            .lock_reader()?
+            .query_row("SELECT value FROM meta WHERE key = ?1", params![cursor_key], |row| row.get(0))
+            .optional()?;
+        let started_at = self.now().to_rfc3339();
+        let deadline = std::time::Instant::now() + budget;
+
+        let mut outcome = DedupOutcome::default();
+        let scan = self.scan_duplicate_clusters(
+            namespace,
+            since.as_deref(),
+            threshold,
+            Some(deadline),
+            &mut |cluster| {
+                outcome.merged += self.merge_duplicate_cluster(&cluster)?;
+                outcome.clusters_merged += 1;
+                Ok(())
+            },
+        )?;
+        outcome.clusters_examined = scan.examined;
+        outcome.budget_exhausted = scan.resume_from.is_some();
+        if outcome.budget_exhausted {
+            tracing::info!(
+                examined = scan.examined,
+                budget_ms = budget.as_millis() as u64,
+                "Dedup time budget exhausted; resuming next run"
+            );
         }
 
-        const SIMILARITY_THRESHOLD: f32 = 0.85;
-        let mut merged_count = 0i64;
-        let mut consumed: std::collections::HashSet<String> = std::collections::HashSet::new();
-
-        for i in 0..n {
-            if consumed.contains(&all_embeddings[i].0) {
-                continue;
-            }
-
-            let mut cluster: Vec<(usize, f32)> = Vec::new();
-
-            for j in (i + 1)..n {
-                if consumed.contains(&all_embeddings[j].0) {
-                    continue;
-                }
-                let sim =
-                    crate::embeddings::cosine_similarity(&all_embeddings[i].1, &all_embeddings[j].1);
-                if sim >= SIMILARITY_THRESHOLD {
-                    cluster.push((j, sim));
-                }
-            }
+        // Next pass starts where this one stopped, or at this pass's start
+        let cursor = scan.resume_from.unwrap_or(started_at);
+        self.lock_writer()?.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)",
+            params![cursor_key, cursor],
+        )?;
 
-            if cluster.is_empty() {
-                continue;
-            }
+        Ok(outcome)
+    }
 
-            // Find the strongest node (highest retention_strength)
-            let anchor_id = &all_embeddings[i].0;
-            let reader = self.reader.lock()
-                .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-            let anchor_retention: f64 = reader
+    /// Merge one duplicate cluster into its strongest memory
+    ///
+    /// The memory with the highest retention is kept, unique content from the
+    /// others is appended to it, and the others are deleted. Returns how many
+    /// memories were merged away.
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn merge_duplicate_cluster(&self, cluster: &[String]) -> Result<i64> {
+        let reader = self.lock_reader()?;
+
+        // Find the strongest node (highest retention_strength), the anchor on ties
+        let mut best_id = &cluster[0];
+        let mut best_retention = f64::MIN;
+        for id in cluster {
+            let retention: f64 = reader
                 .query_row(
                     "SELECT retention_strength FROM knowledge_nodes WHERE id = ?1",
-                    params![anchor_id],
+                    params![id],
                     |row| row.get(0),
                 )
                 .unwrap_or(0.0);
-
-            let mut best_idx = i;
-            let mut best_retention = anchor_retention;
-
-            for &(j, _) in &cluster {
-                let dup_id = &all_embeddings[j].0;
-                let dup_retention: f64 = reader
-                    .query_row(
-                        "SELECT retention_strength FROM knowledge_nodes WHERE id = ?1",
-                        params![dup_id],
-                        |row| row.get(0),
-                    )
-                    .unwrap_or(0.0);
-                if dup_retention > best_retention {
-                    best_retention = dup_retention;
-                    best_idx = j;
-                }
+            if retention > best_retention {
+                best_retention = retention;
+                best_id = id;
             }
+        }
 
-            let best_id = all_embeddings[best_idx].0.clone();
+        // Get keeper's content
+        let keeper_content: String = reader
+            .query_row(
+                "SELECT content FROM knowledge_nodes WHERE id = ?1",
+                params![best_id],
+                |row| row.get(0),
+            )
+            .unwrap_or_default();
+
+        // All nodes in the cluster except the keeper
+        let weak_ids: Vec<&String> = cluster.iter().filter(|id| *id != best_id).collect();
 
-            // Get keeper's content
-            let keeper_content: String = reader
+        // Merge unique content from weak nodes
+        let mut merged_content = keeper_content.clone();
+        for weak_id in &weak_ids {
+            let weak_content: String = reader
                 .query_row(
                     "SELECT content FROM knowledge_nodes WHERE id = ?1",
-                    params![best_id],
+                    params![weak_id],
                     |row| row.get(0),
                 )
                 .unwrap_or_default();
 
-            // Collect weak node IDs (all nodes in cluster except the keeper)
-            let mut weak_ids: Vec<String> = Vec::new();
-            if best_idx != i {
-                weak_ids.push(anchor_id.clone());
-            }
-            for &(j, _) in &cluster {
-                if j != best_idx {
-                    weak_ids.push(all_embeddings[j].0.clone());
-                }
+            let weak_trimmed = weak_content.trim();
+            if !merged_content.contains(weak_trimmed) && weak_trimmed.len() > 20 {
+                merged_content.push_str("\n\n[MERGED] ");
+                merged_content.push_str(weak_trimmed);
             }
+        }
 
-            // Merge unique content from weak nodes
-            let mut merged_content = keeper_content.clone();
-            for weak_id in &weak_ids {
-                let weak_content: String = reader
-                    .query_row(
-                        "SELECT content FROM knowledge_nodes WHERE id = ?1",
-                        params![weak_id],
-                        |row| row.get(0),
-                    )
-                    .unwrap_or_default();
+        // Drop reader before taking writer locks in update/delete
+        drop(reader);
 
-                let weak_trimmed = weak_content.trim();
-                if !merged_content.contains(weak_trimmed) && weak_trimmed.len() > 20 {
-                    merged_content.push_str("\n\n[MERGED] ");
-                    merged_content.push_str(weak_trimmed);
-                }
-            }
+        // Update keeper with merged content
+        if merged_content != keeper_content {
+            let _ = self.update_node_content_from(best_id, &merged_content, "consolidation:merge");
+        }
+
+        // Delete weak nodes
+        let mut merged = 0;
+        for weak_id in weak_ids {
+            let _ = self.delete_node(weak_id);
+            merged += 1;
+        }
+        Ok(merged)
+    }
 
-            // Drop reader before taking writer locks in update/delete
-            drop(reader);
+    /// Replay pending usage events into each memory's evolving importance
+    ///
+    /// Loads the persisted scores into an `ImportanceTracker`, applies the
+    /// unprocessed events in order, decays idle scores per the tracker's
+    /// `ImportanceDecayConfig`, and writes the scores back. Every event, and
+    /// every decay that moves a score noticeably, is added to the history.
+    /// Returns the number of events replayed.
+    pub(crate) fn evolve_importance(&self) -> Result<usize> {
+        let tracker = ImportanceTracker::new();
+        let (scores, events) = {
+            let reader = self.lock_reader()?;
+            let scores = reader
+                .prepare(
+                    "SELECT memory_id, base_importance, usage_importance, recency_importance,
+                            connection_importance, final_score, retrieval_count, helpful_count,
+                            last_accessed, calculated_at
+                     FROM importance_scores",
+                )?
+                .query_map([], |row| {
+                    let last_accessed = row
+                        .get::<_, Option<String>>(8)?
+                        .map(|s| Self::parse_timestamp(&s, "last_accessed"))
+                        .transpose()?;
+                    Ok(ImportanceScore {
+                        memory_id: row.get(0)?,
+                        base_importance: row.get(1)?,
+                        usage_importance: row.get(2)?,
+                        recency_importance: row.get(3)?,
+                        connection_importance: row.get(4)?,
+                        final_score: row.get(5)?,
+                        retrieval_count: row.get(6)?,
+                        helpful_count: row.get(7)?,
+                        last_accessed,
+                        calculated_at: Self::parse_timestamp(&row.get::<_, String>(9)?, "calculated_at")?,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            let events = reader
+                .prepare(
+                    "SELECT id, memory_id, was_helpful, context, occurred_at
+                     FROM usage_events WHERE processed_at IS NULL
+                     ORDER BY occurred_at, id",
+                )?
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        UsageEvent {
+                            memory_id: row.get(1)?,
+                            was_helpful: row.get(2)?,
+                            context: row.get(3)?,
+                            timestamp: Self::parse_timestamp(&row.get::<_, String>(4)?, "occurred_at")?,
+                        },
+                    ))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            (scores, events)
+        };
+        if scores.is_empty() && events.is_empty() {
+            return Ok(0);
+        }
+        for score in scores {
+            tracker.restore_score(score);
+        }
 
-            // Update keeper with merged content
-            if merged_content != keeper_content {
-                let _ = self.update_node_content(&best_id, &merged_content);
+        let mut history: Vec<(ImportanceScore, String, DateTime<Utc>)> = Vec::new();
+        for (_, event) in &events {
+            tracker.record_event(event.clone());
+            if let Some(score) = tracker.get_importance(&event.memory_id) {
+                let cause = event.context.clone().unwrap_or_else(|| "usage".to_string());
+                history.push((score, cause, event.timestamp));
             }
+        }
 
-            // Delete weak nodes
-            for weak_id in &weak_ids {
-                let _ = self.delete_node(weak_id);
-                consumed.insert(weak_id.clone());
-                merged_count += 1;
+        let before: HashMap<String, f64> = tracker
+            .get_all_scores()
+            .into_iter()
+            .map(|s| (s.memory_id, s.final_score))
+            .collect();
+        tracker.apply_importance_decay();
+        let now = self.now();
+        let scores = tracker.get_all_scores();
+        for score in &scores {
+            let previous = before.get(&score.memory_id).copied().unwrap_or(score.final_score);
+            if previous - score.final_score >= IMPORTANCE_HISTORY_MIN_DECAY {
+                history.push((score.clone(), "decay".to_string(), now));
             }
-
-            consumed.insert(best_id);
         }
 
-        Ok(merged_count)
+        let mut writer = self.lock_writer()?;
+        let tx = writer.transaction()?;
+        for score in &scores {
+            tx.execute(
+                "INSERT OR REPLACE INTO importance_scores (
+                    memory_id, base_importance, usage_importance, recency_importance,
+                    connection_importance, final_score, retrieval_count, helpful_count,
+                    last_accessed, calculated_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    score.memory_id,
+                    score.base_importance,
+                    score.usage_importance,
+                    score.recency_importance,
+                    score.connection_importance,
+                    score.final_score,
+                    score.retrieval_count,
+                    score.helpful_count,
+                    score.last_accessed.map(|t| t.to_rfc3339()),
+                    score.calculated_at.to_rfc3339(),
+                ],
+            )?;
+        }
+        for (score, cause, recorded_at) in &history {
+            tx.execute(
+                "INSERT INTO importance_history
+                    (memory_id, final_score, usage_importance, recency_importance, cause, recorded_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    score.memory_id,
+                    score.final_score,
+                    score.usage_importance,
+                    score.recency_importance,
+                    cause,
+                    recorded_at.to_rfc3339(),
+                ],
+            )?;
+        }
+        for (id, _) in &events {
+            tx.execute(
+                "UPDATE usage_events SET processed_at = ?1 WHERE id = ?2",
+                params![now.to_rfc3339(), id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(events.len())
     }
 
-    /// Compute ACT-R base-level activation for all nodes from access history.
-    /// B_i = ln(Σ t_j^(-d)) where t_j = days since j-th access, d = 0.5
-    fn compute_act_r_activations(&self) -> Result<i64> {
-        const ACT_R_DECAY: f64 = 0.5;
-        let now = Utc::now();
+    /// How a memory's evolving importance moved, oldest first
+    ///
+    /// Consolidation keeps 90 days of history (see `prune_usage_history`).
+    pub fn get_importance_history(&self, id: &str) -> Result<Vec<ImportanceHistoryPoint>> {
+        let reader = self.lock_reader()?;
+        reader
+            .prepare(
+                "SELECT final_score, usage_importance, recency_importance, cause, recorded_at
+                 FROM importance_history WHERE memory_id = ?1
+                 ORDER BY recorded_at, id",
+            )?
+            .query_map(params![id], |row| {
+                Ok(ImportanceHistoryPoint {
+                    final_score: row.get(0)?,
+                    usage_importance: row.get(1)?,
+                    recency_importance: row.get(2)?,
+                    cause: row.get(3)?,
+                    recorded_at: Self::parse_timestamp(&row.get::<_, String>(4)?, "recorded_at")?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(StorageError::from)
+    }
 
-        let node_ids: Vec<String> = {
-            let reader = self.reader.lock()
-                .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
+    /// Recompute and persist every node's composite importance
+    pub(crate) fn update_importance_scores(&self) -> Result<i64> {
+        let rows: Vec<(String, NodeImportance)> = {
+            let reader = self.lock_reader()?;
             reader
-                .prepare("SELECT DISTINCT node_id FROM memory_access_log")?
-                .query_map([], |row| row.get(0))?
+                .prepare(
+                    "SELECT n.id, n.utility_score, n.activation, n.emotional_valence, n.reps,
+                            s.final_score
+                     FROM knowledge_nodes n
+                     LEFT JOIN importance_scores s ON s.memory_id = n.id",
+                )?
+                .query_map([], |row| {
+                    Ok((
+                        row.get(0)?,
+                        NodeImportance::compute(
+                            row.get(1)?,
+                            row.get(2)?,
+                            row.get(3)?,
+                            row.get(4)?,
+                            row.get(5)?,
+                        ),
+                    ))
+                })?
                 .filter_map(|r| r.ok())
                 .collect()
         };
 
-        if node_ids.is_empty() {
-            return Ok(0);
+        let mut writer = self.lock_writer()?;
+        let tx = writer.transaction()?;
+        for (id, importance) in &rows {
+            tx.execute(
+                "UPDATE knowledge_nodes SET importance = ?1 WHERE id = ?2",
+                params![importance.composite, id],
+            )?;
         }
+        tx.commit()?;
+        Ok(rows.len() as i64)
+    }
 
-        let mut count = 0i64;
-        let mut writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        let tx = writer.transaction()?;
+    /// Most important memories by persisted composite importance
+    ///
+    /// Nodes not yet scored by a consolidation run are excluded.
+    pub fn top_important(&self, limit: i32) -> Result<Vec<KnowledgeNode>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT * FROM knowledge_nodes
+             WHERE importance IS NOT NULL
+             ORDER BY importance DESC, retention_strength DESC
+             LIMIT ?1",
+        )?;
+        let nodes = stmt.query_map(params![limit], Self::row_to_node)?;
+        let mut result = Vec::new();
+        for node in nodes {
+            result.push(node?);
+        }
+        Ok(result)
+    }
+
+    /// Current importance channels for one memory, computed from its live columns
+    pub fn get_importance_breakdown(&self, id: &str) -> Result<Option<NodeImportance>> {
+        let reader = self.lock_reader()?;
+        reader
+            .query_row(
+                "SELECT n.utility_score, n.activation, n.emotional_valence, n.reps, s.final_score
+                 FROM knowledge_nodes n
+                 LEFT JOIN importance_scores s ON s.memory_id = n.id
+                 WHERE n.id = ?1",
+                params![id],
+                |row| {
+                    Ok(NodeImportance::compute(
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get(4)?,
+                    ))
+                },
+            )
+            .optional()
+            .map_err(StorageError::from)
+    }
 
-        for node_id in &node_ids {
-            let timestamps: Vec<String> = tx
+    /// Refresh the ACT-R base-level activation B = ln(Σ w_j·t_j^-d) of every
+    /// memory with access history, returning how many were rewritten
+    ///
+    /// Each access counts with its kind's weight (see `AccessKind::weight`)
+    /// times the weight it was logged with.
+    ///
+    /// Each memory keeps its newest accesses and the decayed sum of the
+    /// older ones in `actr_state`. A run decays that sum in closed form and
+    /// adds only the log rows newer than the last one it counted, whatever
+    /// their `accessed_at`, leaves alone memories whose
+    /// activation has drifted less than `ACT_R_MAX_ERROR` without new
+    /// accesses, and recomputes from the log once the approximation could be
+    /// off by more than that.
+    pub(crate) fn compute_act_r_activations(&self) -> Result<i64> {
+        let now = self.now();
+        let log_start = now - Duration::days(ACCESS_LOG_RETENTION_DAYS);
+
+        let nodes: Vec<(String, Option<ActrState>)> = {
+            let reader = self.lock_reader()?;
+            reader
                 .prepare(
-                    "SELECT accessed_at FROM memory_access_log
-                     WHERE node_id = ?1
-                     ORDER BY accessed_at DESC
-                     LIMIT 500",
+                    "SELECT l.node_id, s.recent_accesses, s.tail_sum, s.tail_count, s.tail_newest,
+                            s.tail_oldest, s.computed_at, s.last_log_id, s.error_bound
+                     FROM (SELECT DISTINCT node_id FROM memory_access_log) l
+                     LEFT JOIN actr_state s ON s.node_id = l.node_id",
                 )?
-                .query_map(params![node_id], |row| row.get(0))?
-                .filter_map(|r| r.ok())
-                .collect();
+                .query_map([], |row| {
+                    let Some(computed_at) = row.get::<_, Option<String>>(6)? else {
+                        return Ok((row.get(0)?, None));
+                    };
+                    let timestamp = |idx: usize, field: &str| -> rusqlite::Result<Option<DateTime<Utc>>> {
+                        row.get::<_, Option<String>>(idx)?
+                            .map(|value| Self::parse_timestamp(&value, field))
+                            .transpose()
+                    };
+                    let recent: Vec<(String, f64)> =
+                        serde_json::from_str(&row.get::<_, String>(1)?).unwrap_or_default();
+                    let state = ActrState {
+                        recent: recent
+                            .iter()
+                            .map(|(at, weight)| Ok((Self::parse_timestamp(at, "recent_accesses")?, *weight)))
+                            .collect::<rusqlite::Result<_>>()?,
+                        tail_sum: row.get(2)?,
+                        tail_count: row.get(3)?,
+                        tail_span: timestamp(4, "tail_newest")?.zip(timestamp(5, "tail_oldest")?),
+                        computed_at: Self::parse_timestamp(&computed_at, "computed_at")?,
+                        last_log_id: row.get(7)?,
+                        error_bound: row.get(8)?,
+                    };
+                    Ok((row.get(0)?, Some(state)))
+                })?
+                .collect::<rusqlite::Result<_>>()?
+        };
 
-            if timestamps.is_empty() {
-                continue;
-            }
+        if nodes.is_empty() {
+            return Ok(0);
+        }
 
-            let mut sum_decay = 0.0_f64;
-            for ts_str in &timestamps {
-                let accessed_at = DateTime::parse_from_rfc3339(ts_str)
+        let mut count = 0i64;
+        let mut writer = self.lock_writer()?;
+        let tx = writer.transaction()?;
+        {
+            let access = |row: &rusqlite::Row| -> rusqlite::Result<LoggedAccess> {
+                let at = DateTime::parse_from_rfc3339(&row.get::<_, String>(1)?)
                     .map(|dt| dt.with_timezone(&Utc))
                     .unwrap_or(now);
-                let days_since = (now - accessed_at).num_seconds() as f64 / 86400.0;
-                let t = days_since.max(0.001);
-                sum_decay += t.powf(-ACT_R_DECAY);
-            }
+                let kind_weight = AccessKind::parse_name(&row.get::<_, String>(2)?).map_or(1.0, AccessKind::weight);
+                Ok(LoggedAccess {
+                    id: row.get(0)?,
+                    at,
+                    weight: kind_weight * row.get::<_, f64>(3)?,
+                })
+            };
+            let mut new_accesses_stmt = tx.prepare(
+                "SELECT id, accessed_at, access_type, weight FROM memory_access_log
+                 WHERE node_id = ?1 AND id > ?2",
+            )?;
+            let mut all_accesses_stmt = tx.prepare(
+                "SELECT id, accessed_at, access_type, weight FROM memory_access_log
+                 WHERE node_id = ?1
+                 ORDER BY accessed_at DESC
+                 LIMIT ?2",
+            )?;
+            let mut update_stmt = tx.prepare("UPDATE knowledge_nodes SET activation = ?1 WHERE id = ?2")?;
+            let mut save_stmt = tx.prepare(
+                "INSERT OR REPLACE INTO actr_state
+                 (node_id, recent_accesses, tail_sum, tail_count, tail_newest, tail_oldest,
+                  computed_at, last_log_id, error_bound)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?;
 
-            let activation = sum_decay.ln();
+            for (node_id, stored) in &nodes {
+                let advanced = match stored {
+                    Some(state) => {
+                        let new_accesses: Vec<LoggedAccess> = new_accesses_stmt
+                            .query_map(params![node_id, state.last_log_id], access)?
+                            .filter_map(|r| r.ok())
+                            .filter(|a| a.weight > 0.0)
+                            .collect();
+                        if new_accesses.is_empty() && state.error_bound + state.max_drift(now) <= ACT_R_MAX_ERROR {
+                            continue;
+                        }
+                        state.advance(&new_accesses, now, log_start)
+                    }
+                    None => None,
+                };
 
-            tx.execute(
-                "UPDATE knowledge_nodes SET activation = ?1 WHERE id = ?2",
-                params![activation, node_id],
-            )?;
-            count += 1;
+                let state = match advanced {
+                    Some(state) => state,
+                    None => {
+                        let accesses: Vec<LoggedAccess> = all_accesses_stmt
+                            .query_map(params![node_id, ACT_R_MAX_ACCESSES], access)?
+                            .filter_map(|r| r.ok())
+                            .filter(|a| a.weight > 0.0)
+                            .collect();
+                        match ActrState::exact(accesses, now) {
+                            Some(state) => state,
+                            None => continue,
+                        }
+                    }
+                };
+
+                let recent: Vec<(String, f64)> =
+                    state.recent.iter().map(|&(at, weight)| (at.to_rfc3339(), weight)).collect();
+                update_stmt.execute(params![state.activation(), node_id])?;
+                save_stmt.execute(params![
+                    node_id,
+                    serde_json::to_string(&recent).unwrap_or_else(|_| "[]".to_string()),
+                    state.tail_sum,
+                    state.tail_count,
+                    state.tail_span.map(|(newest, _)| newest.to_rfc3339()),
+                    state.tail_span.map(|(_, oldest)| oldest.to_rfc3339()),
+                    state.computed_at.to_rfc3339(),
+                    state.last_log_id,
+                    state.error_bound,
+                ])?;
+                count += 1;
+            }
         }
 
         tx.commit()?;
@@ -2295,10 +6628,9 @@ impl Storage {
     }
 
     /// Prune old access log entries (keep last 90 days)
-    fn prune_access_log(&self) -> Result<i64> {
-        let cutoff = (Utc::now() - Duration::days(90)).to_rfc3339();
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
+    pub(crate) fn prune_access_log(&self) -> Result<i64> {
+        let cutoff = (self.now() - Duration::days(ACCESS_LOG_RETENTION_DAYS)).to_rfc3339();
+        let writer = self.lock_writer()?;
         let deleted = writer.execute(
             "DELETE FROM memory_access_log WHERE accessed_at < ?1",
             params![cutoff],
@@ -2306,18 +6638,164 @@ impl Storage {
         Ok(deleted)
     }
 
-    /// Optimize personalized w20 (forgetting curve decay) if enough access data exists.
-    /// Uses FSRSOptimizer golden section search on real retrieval history.
-    fn optimize_w20_if_ready(&self) -> Result<Option<f64>> {
-        use crate::fsrs::{FSRSOptimizer, ReviewLog};
+    /// Prune replayed usage events and importance history older than the
+    /// access log's 90 days, returning (events, history points) removed
+    ///
+    /// Unreplayed events are kept whatever their age so importance never
+    /// misses them.
+    pub(crate) fn prune_usage_history(&self) -> Result<(i64, i64)> {
+        let cutoff = (self.now() - Duration::days(ACCESS_LOG_RETENTION_DAYS)).to_rfc3339();
+        let mut writer = self.lock_writer()?;
+        let tx = writer.transaction()?;
+        let events = tx.execute(
+            "DELETE FROM usage_events WHERE processed_at IS NOT NULL AND occurred_at < ?1",
+            params![cutoff],
+        )? as i64;
+        let history = tx.execute(
+            "DELETE FROM importance_history WHERE recorded_at < ?1",
+            params![cutoff],
+        )? as i64;
+        tx.commit()?;
+        Ok((events, history))
+    }
 
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
+    /// Usage insights from the access log over the last `window`
+    ///
+    /// Windows longer than the log's 90-day retention are cut to it, which
+    /// the result reports via `window_truncated`. Every figure is a SQL
+    /// aggregate, so the log is never loaded into memory.
+    pub fn access_analytics(&self, window: Duration) -> Result<AccessAnalytics> {
+        if window <= Duration::zero() {
+            return Err(StorageError::InvalidInput {
+                field: "window",
+                reason: "must be positive".into(),
+            });
+        }
+        let retention = Duration::days(ACCESS_LOG_RETENTION_DAYS);
+        let window_truncated = window > retention;
+        let window_end = self.now();
+        let window_start = window_end - window.min(retention);
+        let since = window_start.to_rfc3339();
 
-        let access_count: i64 = reader
-            .query_row(
-                "SELECT COUNT(*) FROM memory_access_log",
-                [],
+        let reader = self.lock_reader()?;
+
+        let accesses_by_type: BTreeMap<String, i64> = reader
+            .prepare(
+                "SELECT access_type, COUNT(*) FROM memory_access_log
+                 WHERE accessed_at >= ?1 GROUP BY access_type",
+            )?
+            .query_map(params![since], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        let total_accesses = accesses_by_type.values().sum();
+        let promotes = accesses_by_type.get("promote").copied().unwrap_or(0);
+        let demotes = accesses_by_type.get("demote").copied().unwrap_or(0);
+
+        let top_accessed = reader
+            .prepare(
+                "SELECT l.node_id, substr(n.content, 1, 100), COUNT(*) AS accesses,
+                        MAX(l.accessed_at)
+                 FROM memory_access_log l JOIN knowledge_nodes n ON n.id = l.node_id
+                 WHERE l.accessed_at >= ?1
+                 GROUP BY l.node_id
+                 ORDER BY accesses DESC, l.node_id
+                 LIMIT ?2",
+            )?
+            .query_map(params![since, ACCESS_ANALYTICS_TOP], |row| {
+                Ok(AccessedMemory {
+                    id: row.get(0)?,
+                    preview: row.get(1)?,
+                    accesses: row.get(2)?,
+                    last_accessed: Self::parse_timestamp(&row.get::<_, String>(3)?, "accessed_at")?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let accesses_by_day = reader
+            .prepare(
+                "SELECT substr(accessed_at, 1, 10) AS day, COUNT(*) FROM memory_access_log
+                 WHERE accessed_at >= ?1 GROUP BY day ORDER BY day",
+            )?
+            .query_map(params![since], |row| {
+                Ok(DailyAccessCount { date: row.get(0)?, accesses: row.get(1)? })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let (total_memories, never_accessed): (i64, i64) = reader.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(NOT EXISTS (
+                 SELECT 1 FROM memory_access_log l
+                 WHERE l.node_id = n.id AND l.accessed_at >= ?1
+             )), 0)
+             FROM knowledge_nodes n",
+            params![since],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        let recent_feedback = reader
+            .prepare(
+                "SELECT node_id, access_type, details, accessed_at FROM memory_access_log
+                 WHERE accessed_at >= ?1 AND access_type IN ('promote', 'demote')
+                   AND details IS NOT NULL
+                 ORDER BY accessed_at DESC, id DESC
+                 LIMIT ?2",
+            )?
+            .query_map(params![since, ACCESS_ANALYTICS_TOP], |row| {
+                Ok(FeedbackRecord {
+                    node_id: row.get(0)?,
+                    action: row.get(1)?,
+                    reason: row.get(2)?,
+                    recorded_at: Self::parse_timestamp(&row.get::<_, String>(3)?, "accessed_at")?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        // Memories created inside the window, so their first access is still logged
+        let median_hours_to_first_access: Option<f64> = reader.query_row(
+            "WITH firsts AS (
+                 SELECT (julianday(MIN(l.accessed_at)) - julianday(n.created_at)) * 24.0 AS hours
+                 FROM memory_access_log l JOIN knowledge_nodes n ON n.id = l.node_id
+                 WHERE n.created_at >= ?1 AND l.accessed_at >= ?1
+                 GROUP BY l.node_id
+             )
+             SELECT AVG(hours) FROM (
+                 SELECT hours FROM firsts ORDER BY hours
+                 LIMIT 2 - (SELECT COUNT(*) FROM firsts) % 2
+                 OFFSET (SELECT (COUNT(*) - 1) / 2 FROM firsts)
+             )",
+            params![since],
+            |row| row.get(0),
+        )?;
+
+        Ok(AccessAnalytics {
+            window_start,
+            window_end,
+            window_truncated,
+            total_accesses,
+            top_accessed,
+            accesses_by_day,
+            promote_ratio: (promotes + demotes > 0)
+                .then(|| promotes as f64 / (promotes + demotes) as f64),
+            accesses_by_type,
+            never_accessed_pct: if total_memories > 0 {
+                never_accessed as f64 / total_memories as f64 * 100.0
+            } else {
+                0.0
+            },
+            median_hours_to_first_access,
+            recent_feedback,
+        })
+    }
+
+    /// Optimize personalized w20 (forgetting curve decay) if enough access data exists.
+    /// Uses FSRSOptimizer golden section search on real retrieval history.
+    pub(crate) fn optimize_w20_if_ready(&self) -> Result<Option<f64>> {
+        use crate::fsrs::{FSRSOptimizer, ReviewLog};
+
+        let reader = self.lock_reader()?;
+
+        let access_count: i64 = reader
+            .query_row(
+                "SELECT COUNT(*) FROM memory_access_log",
+                [],
                 |row| row.get(0),
             )
             .unwrap_or(0);
@@ -2352,16 +6830,17 @@ impl Storage {
             if let Some((stability, difficulty, created_at)) = node_state {
                 let ts = DateTime::parse_from_rfc3339(accessed_at)
                     .map(|dt| dt.with_timezone(&Utc))
-                    .unwrap_or_else(|_| Utc::now());
+                    .unwrap_or_else(|_| self.now());
 
                 let created = DateTime::parse_from_rfc3339(&created_at)
                     .map(|dt| dt.with_timezone(&Utc))
                     .unwrap_or(ts);
 
                 let rating = match access_type.as_str() {
-                    "promote" => 4,
-                    "search_hit" => 3,
-                    "demote" => 1,
+                    "promote" | "outcome_very_helpful" => 4,
+                    "search_hit" | "outcome_helpful" => 3,
+                    "outcome_not_helpful" => 2,
+                    "demote" | "outcome_harmful" => 1,
                     _ => 3,
                 };
 
@@ -2387,12 +6866,11 @@ impl Storage {
 
         // Save to config
         {
-            let writer = self.writer.lock()
-                .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
+            let writer = self.lock_writer()?;
             writer.execute(
                 "INSERT OR REPLACE INTO fsrs_config (key, value, updated_at)
                  VALUES ('w20', ?1, ?2)",
-                params![optimized_w20, Utc::now().to_rfc3339()],
+                params![optimized_w20, self.now().to_rfc3339()],
             )?;
         }
 
@@ -2403,7 +6881,7 @@ impl Storage {
 
     /// Generate missing embeddings
     #[cfg(all(feature = "embeddings", feature = "vector-search"))]
-    fn generate_missing_embeddings(&self) -> Result<i64> {
+    pub(crate) fn generate_missing_embeddings(&self) -> Result<i64> {
         if !self.embedding_service.is_ready() {
             if let Err(e) = self.embedding_service.init() {
                 tracing::warn!("Could not initialize embedding model: {}", e);
@@ -2412,8 +6890,7 @@ impl Storage {
         }
 
         let nodes: Vec<(String, String)> = {
-            let reader = self.reader.lock()
-                .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
+            let reader = self.lock_reader()?;
             reader
                 .prepare(
                     "SELECT id, content FROM knowledge_nodes
@@ -2482,6 +6959,13 @@ pub struct InsightRecord {
 
 impl Default for InsightRecord {
     fn default() -> Self {
+        Self::default_at(Utc::now())
+    }
+}
+
+impl InsightRecord {
+    /// An empty record generated at `now`
+    pub fn default_at(now: DateTime<Utc>) -> Self {
         Self {
             id: String::new(),
             insight: String::new(),
@@ -2489,7 +6973,7 @@ impl Default for InsightRecord {
             confidence: 0.0,
             novelty_score: 0.0,
             insight_type: String::new(),
-            generated_at: Utc::now(),
+            generated_at: now,
             tags: Vec::new(),
             feedback: None,
             applied_count: 0,
@@ -2509,1228 +6993,9300 @@ pub struct ConnectionRecord {
     pub activation_count: i32,
 }
 
-/// Memory state record
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct MemoryStateRecord {
-    pub memory_id: String,
-    pub state: String,  // 'active', 'dormant', 'silent', 'unavailable'
-    pub last_access: DateTime<Utc>,
-    pub access_count: i32,
-    pub state_entered_at: DateTime<Utc>,
-    pub suppression_until: Option<DateTime<Utc>>,
-    pub suppressed_by: Vec<String>,
+/// Retention target and automatic GC policy applied during consolidation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetentionPolicy {
+    /// Average retention below which GC runs
+    pub target: f64,
+    /// Memories below this retention are GC candidates
+    pub gc_below: f64,
+    /// Memories younger than this are never collected
+    pub min_age_days: i64,
+    /// When false, consolidation only records retention snapshots
+    pub enabled: bool,
 }
 
-/// State transition record for audit trail
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct StateTransitionRecord {
-    pub id: i64,
-    pub memory_id: String,
-    pub from_state: String,
-    pub to_state: String,
-    pub reason_type: String,
-    pub reason_data: Option<String>,
-    pub timestamp: DateTime<Utc>,
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            target: 0.8,
+            gc_below: 0.3,
+            min_age_days: 30,
+            enabled: true,
+        }
+    }
 }
 
-/// Consolidation history record
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct ConsolidationHistoryRecord {
-    pub id: i64,
-    pub completed_at: DateTime<Utc>,
-    pub duration_ms: i64,
-    pub memories_replayed: i32,
-    pub connections_found: i32,
-    pub connections_strengthened: i32,
-    pub connections_pruned: i32,
-    pub insights_generated: i32,
+impl RetentionPolicy {
+    /// Defaults overridden by VESTIGE_RETENTION_TARGET, VESTIGE_RETENTION_GC_BELOW,
+    /// VESTIGE_RETENTION_GC_MIN_AGE_DAYS and VESTIGE_RETENTION_GC
+    /// (`false`, `0` or `off` disables GC)
+    pub fn from_env() -> Self {
+        let env = |name: &str| std::env::var(name).ok();
+        let defaults = Self::default();
+        Self {
+            target: env("VESTIGE_RETENTION_TARGET")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.target),
+            gc_below: env("VESTIGE_RETENTION_GC_BELOW")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.gc_below),
+            min_age_days: env("VESTIGE_RETENTION_GC_MIN_AGE_DAYS")
+                .and_then(|v| v.parse().ok())
+                .filter(|days| *days >= 0)
+                .unwrap_or(defaults.min_age_days),
+            enabled: env("VESTIGE_RETENTION_GC")
+                .map(|v| !matches!(v.trim().to_lowercase().as_str(), "false" | "0" | "off" | "no"))
+                .unwrap_or(defaults.enabled),
+        }
+    }
 }
 
-/// Dream history record — persists dream metadata for automation triggers
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct DreamHistoryRecord {
-    pub dreamed_at: DateTime<Utc>,
-    pub duration_ms: i64,
-    pub memories_replayed: i32,
-    pub connections_found: i32,
-    pub insights_generated: i32,
-    pub memories_strengthened: i32,
-    pub memories_compressed: i32,
-    // v2.0: 4-Phase dream cycle metrics
-    pub phase_nrem1_ms: Option<i64>,
-    pub phase_nrem3_ms: Option<i64>,
-    pub phase_rem_ms: Option<i64>,
-    pub phase_integration_ms: Option<i64>,
-    pub summaries_generated: Option<i32>,
-    pub emotional_memories_processed: Option<i32>,
-    pub creative_connections_found: Option<i32>,
-}
+/// Days of access log kept by consolidation
+const ACCESS_LOG_RETENTION_DAYS: i64 = 90;
 
-impl Storage {
-    // ========================================================================
-    // INTENTIONS PERSISTENCE
-    // ========================================================================
+/// ACT-R decay rate d in B = ln(Σ w_j·t_j^-d)
+const ACT_R_DECAY: f64 = 0.5;
 
-    /// Save an intention to the database
-    pub fn save_intention(&self, intention: &IntentionRecord) -> Result<()> {
-        let tags_json = serde_json::to_string(&intention.tags).unwrap_or_else(|_| "[]".to_string());
-        let related_json = serde_json::to_string(&intention.related_memories).unwrap_or_else(|_| "[]".to_string());
+/// Most recent accesses that count toward a memory's activation
+const ACT_R_MAX_ACCESSES: i64 = 500;
 
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        writer.execute(
-            "INSERT OR REPLACE INTO intentions (
-                id, content, trigger_type, trigger_data, priority, status,
-                created_at, deadline, fulfilled_at, reminder_count, last_reminded_at,
-                notes, tags, related_memories, snoozed_until, source_type, source_data
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
-            params![
-                intention.id,
-                intention.content,
-                intention.trigger_type,
-                intention.trigger_data,
-                intention.priority,
-                intention.status,
-                intention.created_at.to_rfc3339(),
-                intention.deadline.map(|dt| dt.to_rfc3339()),
-                intention.fulfilled_at.map(|dt| dt.to_rfc3339()),
-                intention.reminder_count,
-                intention.last_reminded_at.map(|dt| dt.to_rfc3339()),
-                intention.notes,
-                tags_json,
-                related_json,
-                intention.snoozed_until.map(|dt| dt.to_rfc3339()),
-                intention.source_type,
-                intention.source_data,
-            ],
-        )?;
-        Ok(())
-    }
+/// Error, in activation units, an incrementally maintained activation may
+/// carry before it is recomputed from the access log (0.005 is about 0.5% of
+/// the decayed access sum)
+const ACT_R_MAX_ERROR: f64 = 0.005;
 
-    /// Get an intention by ID
-    pub fn get_intention(&self, id: &str) -> Result<Option<IntentionRecord>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let mut stmt = reader.prepare(
-            "SELECT * FROM intentions WHERE id = ?1"
-        )?;
+/// Access ages are floored at this many days so fresh accesses stay finite
+const ACT_R_MIN_AGE_DAYS: f64 = 0.001;
 
-        stmt.query_row(params![id], |row| Self::row_to_intention(row))
-            .optional()
-            .map_err(StorageError::from)
+/// Newest accesses of a memory kept exactly in its ACT-R state; older ones
+/// only count through their decayed sum
+const ACT_R_RECENT_ACCESSES: usize = 16;
+
+/// One `memory_access_log` row as ACT-R counts it
+#[derive(Debug, Clone, Copy)]
+struct LoggedAccess {
+    id: i64,
+    at: DateTime<Utc>,
+    /// Kind weight times the logged weight
+    weight: f64,
+}
+
+/// One memory's running ACT-R access sum, as kept in `actr_state`
+#[derive(Debug, Clone)]
+struct ActrState {
+    /// Newest accesses as (time, weight), oldest first
+    recent: Vec<(DateTime<Utc>, f64)>,
+    /// Σ w·t^-d over the older accesses, as of `computed_at`
+    tail_sum: f64,
+    tail_count: i64,
+    /// Newest and oldest of the older accesses, if there are any
+    tail_span: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    computed_at: DateTime<Utc>,
+    /// Newest `memory_access_log` row counted
+    last_log_id: i64,
+    /// Bound on how far the activation may be from the exact one
+    error_bound: f64,
+}
+
+impl ActrState {
+    /// Exact state over `accesses`, or `None` without any
+    fn exact(accesses: Vec<LoggedAccess>, now: DateTime<Utc>) -> Option<Self> {
+        let last_log_id = accesses.iter().map(|a| a.id).max()?;
+        let mut accesses: Vec<(DateTime<Utc>, f64)> = accesses.into_iter().map(|a| (a.at, a.weight)).collect();
+        accesses.sort_by_key(|&(at, _)| at);
+        let split = accesses.len().saturating_sub(ACT_R_RECENT_ACCESSES);
+        let recent = accesses.split_off(split);
+        recent.first()?;
+        Some(Self {
+            recent,
+            tail_sum: accesses.iter().map(|&(at, weight)| weight * actr_term(at, now)).sum(),
+            tail_count: accesses.len() as i64,
+            tail_span: accesses.last().zip(accesses.first()).map(|(&(newest, _), &(oldest, _))| (newest, oldest)),
+            computed_at: now,
+            last_log_id,
+            error_bound: 0.0,
+        })
     }
 
-    /// Get all active intentions
-    pub fn get_active_intentions(&self) -> Result<Vec<IntentionRecord>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let mut stmt = reader.prepare(
-            "SELECT * FROM intentions WHERE status = 'active' ORDER BY priority DESC, created_at ASC"
-        )?;
+    /// Activation B = ln(Σ w·t^-d) as of `computed_at`
+    fn activation(&self) -> f64 {
+        let recent: f64 = self.recent.iter().map(|&(at, weight)| weight * actr_term(at, self.computed_at)).sum();
+        (recent + self.tail_sum).ln()
+    }
 
-        let rows = stmt.query_map([], |row| Self::row_to_intention(row))?;
-        let mut result = Vec::new();
-        for row in rows {
-            result.push(row?);
-        }
-        Ok(result)
+    /// Most the activation can have dropped since `computed_at`: every term
+    /// shrinks by (1 + Δ/t)^-d, the newest access's the most
+    fn max_drift(&self, now: DateTime<Utc>) -> f64 {
+        let newest = self.recent.last().map_or(self.computed_at, |&(at, _)| at);
+        let elapsed = age_days(now - self.computed_at).max(0.0);
+        let age = age_days(self.computed_at - newest).max(ACT_R_MIN_AGE_DAYS);
+        ACT_R_DECAY * (elapsed / age).ln_1p()
     }
 
-    /// Get intentions by status
-    pub fn get_intentions_by_status(&self, status: &str) -> Result<Vec<IntentionRecord>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let mut stmt = reader.prepare(
-            "SELECT * FROM intentions WHERE status = ?1 ORDER BY priority DESC, created_at ASC"
-        )?;
+    /// Decay the state to `now` and add `new_accesses`, or `None` when it has
+    /// to be recomputed from the log instead: its error would exceed
+    /// `ACT_R_MAX_ERROR`, it would count more than `ACT_R_MAX_ACCESSES`, or
+    /// it counts accesses older than the log keeps
+    fn advance(
+        &self,
+        new_accesses: &[LoggedAccess],
+        now: DateTime<Utc>,
+        log_start: DateTime<Utc>,
+    ) -> Option<Self> {
+        let count = self.recent.len() as i64 + self.tail_count + new_accesses.len() as i64;
+        let oldest = self.tail_span.map(|(_, oldest)| oldest).or(self.recent.first().map(|&(at, _)| at))?;
+        if count > ACT_R_MAX_ACCESSES || oldest < log_start {
+            return None;
+        }
 
-        let rows = stmt.query_map(params![status], |row| Self::row_to_intention(row))?;
-        let mut result = Vec::new();
-        for row in rows {
-            result.push(row?);
+        // Each tail term shrinks by (1 + Δ/t)^-d whatever its weight, between the factors of the
+        // newest and the oldest tail access; decay by the midpoint
+        let (least, most) = match self.tail_span {
+            Some((newest, oldest)) => {
+                let elapsed = age_days(now - self.computed_at).max(0.0);
+                let factor = |at: DateTime<Utc>| {
+                    let age = age_days(self.computed_at - at).max(ACT_R_MIN_AGE_DAYS);
+                    (-ACT_R_DECAY * (elapsed / age).ln_1p()).exp()
+                };
+                (factor(newest), factor(oldest))
+            }
+            None => (1.0, 1.0),
+        };
+        let mut tail_sum = self.tail_sum * (least + most) / 2.0;
+        let spread = self.tail_sum * (most - least) / 2.0;
+
+        let mut recent = self.recent.clone();
+        recent.extend(new_accesses.iter().map(|a| (a.at, a.weight)));
+        recent.sort_by_key(|&(at, _)| at);
+        let moved = recent.len().saturating_sub(ACT_R_RECENT_ACCESSES);
+        let mut tail_span = self.tail_span;
+        for (at, weight) in recent.drain(..moved) {
+            tail_sum += weight * actr_term(at, now);
+            tail_span = Some(tail_span.map_or((at, at), |(newest, oldest)| (newest.max(at), oldest.min(at))));
         }
-        Ok(result)
+
+        // ln(1 + x) <= x, against the least the exact sum can be
+        let head: f64 = recent.iter().map(|&(at, weight)| weight * actr_term(at, now)).sum();
+        let error_bound = self.error_bound + spread / (head + tail_sum - spread);
+        if error_bound > ACT_R_MAX_ERROR {
+            return None;
+        }
+        Some(Self {
+            recent,
+            tail_sum,
+            tail_count: self.tail_count + moved as i64,
+            tail_span,
+            computed_at: now,
+            last_log_id: new_accesses.iter().map(|a| a.id).fold(self.last_log_id, i64::max),
+            error_bound,
+        })
     }
+}
 
-    /// Update intention status
-    pub fn update_intention_status(&self, id: &str, status: &str) -> Result<bool> {
-        let now = Utc::now();
-        let fulfilled_at = if status == "fulfilled" { Some(now.to_rfc3339()) } else { None };
+/// A duration in fractional days, at second resolution
+fn age_days(duration: Duration) -> f64 {
+    duration.num_seconds() as f64 / 86400.0
+}
 
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        let rows = writer.execute(
-            "UPDATE intentions SET status = ?1, fulfilled_at = ?2 WHERE id = ?3",
-            params![status, fulfilled_at, id],
-        )?;
-        Ok(rows > 0)
+/// One access's contribution t^-d to the ACT-R sum at `now`
+fn actr_term(accessed_at: DateTime<Utc>, now: DateTime<Utc>) -> f64 {
+    age_days(now - accessed_at).max(ACT_R_MIN_AGE_DAYS).powf(-ACT_R_DECAY)
+}
+
+/// Most accessed memories listed in `AccessAnalytics`
+const ACCESS_ANALYTICS_TOP: i64 = 10;
+
+/// Retrieval-induced forgetting applied by `recall`
+///
+/// Off by default since it changes what later searches find: when enabled,
+/// memories that closely compete with a recall's top result but weren't
+/// returned lose a little retrieval strength.
+#[derive(Debug, Clone)]
+pub struct RetrievalForgettingPolicy {
+    pub enabled: bool,
+    /// Similarity needed to compete, and how long a loser is protected from
+    /// being suppressed again
+    pub competition: CompetitionConfig,
+    /// Retrieval strength removed from a loser, scaled by its similarity
+    pub suppression: f64,
+    /// Suppression never lowers retrieval strength below this
+    pub min_retrieval_strength: f64,
+}
+
+impl Default for RetrievalForgettingPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            competition: CompetitionConfig::default(),
+            suppression: 0.05,
+            min_retrieval_strength: 0.3,
+        }
     }
+}
 
-    /// Delete an intention
-    pub fn delete_intention(&self, id: &str) -> Result<bool> {
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        let rows = writer.execute("DELETE FROM intentions WHERE id = ?1", params![id])?;
-        Ok(rows > 0)
+impl RetrievalForgettingPolicy {
+    /// Defaults, enabled by VESTIGE_RETRIEVAL_FORGETTING (`true`, `1`, `on` or `yes`)
+    pub fn from_env() -> Self {
+        Self {
+            enabled: std::env::var("VESTIGE_RETRIEVAL_FORGETTING")
+                .map(|v| matches!(v.trim().to_lowercase().as_str(), "true" | "1" | "on" | "yes"))
+                .unwrap_or(false),
+            ..Self::default()
+        }
     }
+}
 
-    /// Get overdue intentions
-    pub fn get_overdue_intentions(&self) -> Result<Vec<IntentionRecord>> {
-        let now = Utc::now().to_rfc3339();
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let mut stmt = reader.prepare(
-            "SELECT * FROM intentions WHERE status = 'active' AND deadline IS NOT NULL AND deadline < ?1 ORDER BY deadline ASC"
-        )?;
+/// Candidates beyond a recall's results that compete with its top result
+const RIF_COMPETITOR_POOL: i32 = 10;
 
-        let rows = stmt.query_map(params![now], |row| Self::row_to_intention(row))?;
-        let mut result = Vec::new();
-        for row in rows {
-            result.push(row?);
+/// Accessed ids boosted per UPDATE statement by `strengthen_batch_on_access`
+const REINFORCEMENT_BATCH_SIZE: usize = 500;
+
+/// Boosts applied by `strengthen_on_access` (the testing effect)
+///
+/// These boosts work against FSRS decay: consolidation lowers retrieval and
+/// retention strength from `last_accessed`, while every search hit raises
+/// them again and resets `last_accessed`. Under read-heavy workloads the
+/// default boosts can pin frequently-returned memories (and their neighbors)
+/// near 1.0 so they never decay; lower them, or use `disabled()`, to let
+/// the forgetting curve and explicit reviews drive strength instead.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReinforcementConfig {
+    /// Retrieval strength added to an accessed memory
+    pub retrieval_boost: f64,
+    /// Retention strength added to an accessed memory
+    pub retention_boost: f64,
+    /// Whether semantically similar neighbors are reinforced too
+    pub neighbors: bool,
+    /// Cosine similarity a neighbor needs to be reinforced
+    pub neighbor_min_similarity: f64,
+    /// Retrieval strength added to a neighbor, scaled by its similarity
+    pub neighbor_retrieval_boost: f64,
+    /// Retention strength added to a neighbor, scaled by its similarity
+    pub neighbor_retention_boost: f64,
+    /// Fraction of the access boost a search result gets without being opened
+    pub impression_weight: f64,
+    /// Shortest interval, in seconds, between neighbor reinforcement drains
+    /// (0 reinforces neighbors on every access)
+    pub neighbor_flush_secs: u64,
+    /// Most retrieval strength one memory gains from neighbor reinforcement
+    /// per hour
+    pub neighbor_hourly_cap: f64,
+}
+
+impl Default for ReinforcementConfig {
+    fn default() -> Self {
+        Self {
+            retrieval_boost: 0.05,
+            retention_boost: 0.02,
+            neighbors: true,
+            neighbor_min_similarity: 0.7,
+            neighbor_retrieval_boost: 0.02,
+            neighbor_retention_boost: 0.008,
+            impression_weight: 0.1,
+            neighbor_flush_secs: 30,
+            neighbor_hourly_cap: 0.1,
         }
-        Ok(result)
     }
+}
 
-    /// Snooze an intention
-    pub fn snooze_intention(&self, id: &str, until: DateTime<Utc>) -> Result<bool> {
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        let rows = writer.execute(
-            "UPDATE intentions SET status = 'snoozed', snoozed_until = ?1 WHERE id = ?2",
-            params![until.to_rfc3339(), id],
-        )?;
-        Ok(rows > 0)
+impl ReinforcementConfig {
+    /// No boosts at all: accesses are still logged, but strength only
+    /// changes through decay and reviews
+    pub fn disabled() -> Self {
+        Self {
+            retrieval_boost: 0.0,
+            retention_boost: 0.0,
+            neighbors: false,
+            neighbor_retrieval_boost: 0.0,
+            neighbor_retention_boost: 0.0,
+            impression_weight: 0.0,
+            ..Self::default()
+        }
     }
 
-    fn row_to_intention(row: &rusqlite::Row) -> rusqlite::Result<IntentionRecord> {
-        let tags_json: String = row.get("tags")?;
-        let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
-        let related_json: String = row.get("related_memories")?;
-        let related: Vec<String> = serde_json::from_str(&related_json).unwrap_or_default();
+    /// Defaults overridden by VESTIGE_ACCESS_RETRIEVAL_BOOST,
+    /// VESTIGE_ACCESS_RETENTION_BOOST, VESTIGE_NEIGHBOR_REINFORCEMENT,
+    /// VESTIGE_NEIGHBOR_MIN_SIMILARITY, VESTIGE_NEIGHBOR_BOOST (retrieval;
+    /// the retention boost keeps its default ratio to it) and
+    /// VESTIGE_IMPRESSION_WEIGHT
+    pub fn from_env() -> Self {
+        let env = |name: &str| std::env::var(name).ok();
+        let boost = |name: &str, default: f64| {
+            env(name)
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|b| (0.0..=1.0).contains(b))
+                .unwrap_or(default)
+        };
+        let defaults = Self::default();
+        let neighbor_retrieval_boost =
+            boost("VESTIGE_NEIGHBOR_BOOST", defaults.neighbor_retrieval_boost);
+        Self {
+            retrieval_boost: boost("VESTIGE_ACCESS_RETRIEVAL_BOOST", defaults.retrieval_boost),
+            retention_boost: boost("VESTIGE_ACCESS_RETENTION_BOOST", defaults.retention_boost),
+            neighbors: env("VESTIGE_NEIGHBOR_REINFORCEMENT")
+                .map(|v| !matches!(v.trim().to_lowercase().as_str(), "false" | "0" | "off" | "no"))
+                .unwrap_or(defaults.neighbors),
+            neighbor_min_similarity: boost(
+                "VESTIGE_NEIGHBOR_MIN_SIMILARITY",
+                defaults.neighbor_min_similarity,
+            ),
+            neighbor_retrieval_boost,
+            neighbor_retention_boost: neighbor_retrieval_boost
+                * (defaults.neighbor_retention_boost / defaults.neighbor_retrieval_boost),
+            impression_weight: boost("VESTIGE_IMPRESSION_WEIGHT", defaults.impression_weight),
+            ..defaults
+        }
+    }
+}
 
-        let parse_opt_dt = |s: Option<String>| -> Option<DateTime<Utc>> {
-            s.and_then(|v| DateTime::parse_from_rfc3339(&v).ok().map(|dt| dt.with_timezone(&Utc)))
+/// Most recently accessed memories in a session overview
+const SESSION_RECENT_MEMORIES: i64 = 30;
+
+/// Most memories of the current project in a session overview
+const SESSION_PROJECT_MEMORIES: i64 = 30;
+
+/// Smallest drop in final importance recorded as a decay point in the history
+const IMPORTANCE_HISTORY_MIN_DECAY: f64 = 0.01;
+
+/// Memories replayed by `run_dream_cycle` (70% reserved for waking-tagged ones)
+const DREAM_CYCLE_MEMORIES: usize = 50;
+
+/// Nearest memories consulted when `IngestInput::auto_tag` is set
+const AUTO_TAG_NEIGHBORS: usize = 5;
+
+/// Most keywords weighted when suggesting tags
+const MAX_TAG_TERMS: usize = 16;
+
+/// Lowest source trust multiplier
+pub const MIN_SOURCE_TRUST: f64 = 0.5;
+/// Highest source trust multiplier
+pub const MAX_SOURCE_TRUST: f64 = 1.5;
+
+/// Ranking trust multiplier for memories from matching sources
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SourceTrust {
+    /// Exact source, or a prefix when it ends in `*`
+    pub pattern: String,
+    /// Multiplier in [0.5, 1.5]; 1.0 is neutral
+    pub weight: f64,
+}
+
+impl SourceTrust {
+    /// Whether this pattern applies to `source`
+    pub fn matches(&self, source: &str) -> bool {
+        match self.pattern.strip_suffix('*') {
+            Some(prefix) => source.starts_with(prefix),
+            None => source == self.pattern,
+        }
+    }
+
+    /// Trust for `source`: an exact pattern wins, then the longest prefix,
+    /// then neutral (1.0)
+    pub fn resolve(trusts: &[SourceTrust], source: Option<&str>) -> f64 {
+        let Some(source) = source else {
+            return 1.0;
         };
+        trusts
+            .iter()
+            .filter(|t| t.matches(source))
+            .max_by_key(|t| (!t.pattern.ends_with('*'), t.pattern.len()))
+            .map_or(1.0, |t| t.weight)
+    }
+}
 
-        Ok(IntentionRecord {
+/// Graded feedback for `Storage::promote_memory_with` / `demote_memory_with`
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedbackOptions {
+    /// Fraction (0.0-1.0) of the default boost or penalty; None applies it fully
+    pub strength: Option<f32>,
+    /// Why the memory helped or misled, kept in the access log
+    pub reason: Option<String>,
+    /// Demotion only: also end the memory's validity (needs a reason)
+    #[serde(default)]
+    pub invalidate: bool,
+}
+
+impl FeedbackOptions {
+    /// Strength to scale the default delta by, rejecting values outside 0..=1
+    fn validated_strength(&self) -> Result<f64> {
+        match self.strength {
+            None => Ok(1.0),
+            Some(s) if (0.0..=1.0).contains(&s) => Ok(s as f64),
+            Some(s) => Err(StorageError::InvalidInput {
+                field: "strength",
+                reason: format!("{} is outside 0.0-1.0", s),
+            }),
+        }
+    }
+
+    /// Trimmed, non-empty reason
+    fn reason(&self) -> Option<&str> {
+        self.reason.as_deref().map(str::trim).filter(|r| !r.is_empty())
+    }
+}
+
+/// How a content update treats the node's FSRS scheduling state
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ContentUpdateMode {
+    /// Leave difficulty, stability and the schedule untouched
+    #[default]
+    Keep,
+    /// Multiply stability by the factor (0-1) and reschedule from now
+    SoftReset(f64),
+    /// Schedule the node as if it were a new card
+    FullReset,
+}
+
+/// Subset of memories `Storage::get_stats_filtered` aggregates over
+///
+/// Every set field must match; the default covers the whole database.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsFilter {
+    pub namespace: Option<String>,
+    /// Nodes carrying this tag (case-insensitive)
+    pub tag: Option<String>,
+    pub node_type: Option<String>,
+    /// Memory scope: "session", "user" or "agent"
+    pub scope: Option<String>,
+}
+
+/// Strength and type filter for memory graph traversal
+#[derive(Debug, Clone, Default)]
+pub struct GraphFilter {
+    /// Drop connections weaker than this
+    pub min_strength: f64,
+    /// Only follow these link types (None = all)
+    pub link_types: Option<Vec<String>>,
+    /// Only include memories in this namespace (None = all)
+    pub namespace: Option<String>,
+}
+
+impl GraphFilter {
+    /// Whether a connection passes the filter
+    pub fn accepts(&self, connection: &ConnectionRecord) -> bool {
+        connection.strength >= self.min_strength
+            && self
+                .link_types
+                .as_ref()
+                .is_none_or(|types| types.iter().any(|t| t.eq_ignore_ascii_case(&connection.link_type)))
+    }
+}
+
+/// Which nodes `Storage::for_each_node` visits
+///
+/// Every set field must match; the default visits every node.
+#[derive(Debug, Clone, Default)]
+pub struct NodeFilter {
+    pub namespace: Option<String>,
+    /// Nodes created strictly before this time
+    pub created_before: Option<DateTime<Utc>>,
+    /// Nodes whose retention strength is strictly below this
+    pub max_retention: Option<f64>,
+}
+
+/// Per-node graph metadata not carried on `KnowledgeNode`
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NodeGraphState {
+    /// ACT-R base-level activation, once consolidation has computed it
+    pub activation: Option<f64>,
+    /// Accessibility state: active, dormant, silent or unavailable
+    pub state: String,
+}
+
+/// Persisted reasoning chain between two memories
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ReasoningChainRecord {
+    pub id: String,
+    pub chain: ReasoningChain,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Tool invocation recorded for speculative retrieval
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct UsageRecord {
+    pub tool: String,
+    pub query: Option<String>,
+    pub memory_ids: Vec<String>,
+    pub project: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// What kind of access a `memory_access_log` entry records
+///
+/// Kinds weigh differently in ACT-R activation: reading a memory in full
+/// counts for more than seeing it in a result list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessKind {
+    /// Listed in search results without being opened
+    Impression,
+    /// Returned by a search and strengthened on access
+    SearchHit,
+    /// Opened and read in full
+    Open,
+    /// Referenced by a new memory or a decision
+    Reference,
+    /// Marked helpful by the user
+    Promote,
+    /// Marked unhelpful or superseded; logged for history only, it adds
+    /// nothing to activation
+    Demote,
+    /// Pinned by the user
+    Pin,
+}
+
+impl AccessKind {
+    /// Name stored in `memory_access_log.access_type`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AccessKind::Impression => "impression",
+            AccessKind::SearchHit => "search_hit",
+            AccessKind::Open => "open",
+            AccessKind::Reference => "reference",
+            AccessKind::Promote => "promote",
+            AccessKind::Demote => "demote",
+            AccessKind::Pin => "pin",
+        }
+    }
+
+    /// Kind for a stored `access_type`; other types (such as outcome
+    /// feedback) have none and weigh 1.0
+    pub fn parse_name(name: &str) -> Option<Self> {
+        [
+            AccessKind::Impression,
+            AccessKind::SearchHit,
+            AccessKind::Open,
+            AccessKind::Reference,
+            AccessKind::Promote,
+            AccessKind::Demote,
+            AccessKind::Pin,
+        ]
+        .into_iter()
+        .find(|kind| kind.as_str() == name)
+    }
+
+    /// How much one access of this kind counts toward ACT-R activation,
+    /// relative to a search hit; accesses weighing 0.0 are left out
+    pub fn weight(self) -> f64 {
+        match self {
+            AccessKind::Demote => 0.0,
+            AccessKind::Impression => 0.25,
+            AccessKind::SearchHit | AccessKind::Promote | AccessKind::Pin => 1.0,
+            AccessKind::Reference => 1.5,
+            AccessKind::Open => 2.0,
+        }
+    }
+}
+
+/// One entry of a memory's access history
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessRecord {
+    /// Stored access type: an `AccessKind` name or e.g. `outcome_success`
+    pub access_type: String,
+    /// Weight the access was logged with, on top of its kind's weight
+    pub weight: f64,
+    pub details: Option<String>,
+    pub accessed_at: DateTime<Utc>,
+}
+
+/// Usage insights from the access log (see `Storage::access_analytics`)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessAnalytics {
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    /// The requested window exceeded the log's 90-day retention and was cut to it
+    pub window_truncated: bool,
+    pub total_accesses: i64,
+    /// Most accessed memories, most accesses first
+    pub top_accessed: Vec<AccessedMemory>,
+    /// Days with at least one access, oldest first
+    pub accesses_by_day: Vec<DailyAccessCount>,
+    /// Accesses per type ("search_hit", "promote", "demote", ...)
+    pub accesses_by_type: BTreeMap<String, i64>,
+    /// Promotes / (promotes + demotes); None when there were neither
+    pub promote_ratio: Option<f64>,
+    /// Percentage of all memories with no access in the window
+    pub never_accessed_pct: f64,
+    /// Median hours from creation to first access, over memories created in the window
+    pub median_hours_to_first_access: Option<f64>,
+    /// Latest promotions and demotions that came with a reason, newest first
+    #[serde(default)]
+    pub recent_feedback: Vec<FeedbackRecord>,
+}
+
+/// A promotion or demotion and its reason, from the access log
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedbackRecord {
+    pub node_id: String,
+    /// "promote" or "demote"
+    pub action: String,
+    pub reason: String,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// A task outcome reported against a memory with `Storage::record_outcome`
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutcomeRecord {
+    pub memory_id: String,
+    pub outcome: OutcomeType,
+    /// Scale of the outcome's reward (0..=1)
+    pub magnitude: f32,
+    pub note: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// A memory pinned with `Storage::pin_memory`
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedMemory {
+    pub node: KnowledgeNode,
+    pub pinned_at: DateTime<Utc>,
+    /// When the pin expires; `None` pins indefinitely
+    pub pinned_until: Option<DateTime<Utc>>,
+}
+
+/// One memory's access count in `AccessAnalytics`
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessedMemory {
+    pub id: String,
+    pub preview: String,
+    pub accesses: i64,
+    pub last_accessed: DateTime<Utc>,
+}
+
+/// Accesses on one UTC day (`YYYY-MM-DD`)
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyAccessCount {
+    pub date: String,
+    pub accesses: i64,
+}
+
+/// Projected review load and retention (see `Storage::forecast`)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Forecast {
+    pub generated_at: DateTime<Utc>,
+    /// Forgetting curve decay the projection used
+    pub w20: f64,
+    pub total_memories: i64,
+    /// Today first, then one entry per day ahead
+    pub days: Vec<ForecastDay>,
+}
+
+/// One day of a `Forecast`
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastDay {
+    /// UTC date (YYYY-MM-DD)
+    pub date: String,
+    /// Memories whose next review falls on this day (today includes overdue ones)
+    pub due_count: i64,
+    /// Average retrievability at this time of day if nothing is reviewed
+    pub projected_avg_retention: f64,
+}
+
+/// Limits for one slice of `Storage::backfill_embeddings`
+#[derive(Debug, Clone, Copy)]
+pub struct BackfillOptions {
+    /// Re-embed nodes that already have an embedding
+    pub force: bool,
+    /// Nodes read, and the cursor checkpointed, per batch
+    pub batch_size: usize,
+    /// Stop after this many nodes (None = no limit)
+    pub max_nodes: Option<usize>,
+    /// Stop once this much time has passed (None = no limit)
+    pub time_budget: Option<std::time::Duration>,
+}
+
+impl Default for BackfillOptions {
+    fn default() -> Self {
+        Self {
+            force: false,
+            batch_size: 100,
+            max_nodes: None,
+            time_budget: None,
+        }
+    }
+}
+
+/// State of an embedding backfill job after a slice
+///
+/// Counts accumulate across slices resumed with `resume_token`; `errors`
+/// only covers the latest slice. The job state is persisted in `meta`, so
+/// `embedding_backfill_status` reports it across restarts.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackfillProgress {
+    /// Whether the job re-embeds nodes that already had embeddings
+    pub force: bool,
+    /// Nodes attempted so far
+    pub processed: usize,
+    pub embedded: usize,
+    pub failed: usize,
+    /// Nodes after the cursor still to process
+    pub remaining: usize,
+    /// Pass to the next call to continue; None once the job is complete
+    pub resume_token: Option<String>,
+    /// The latest slice stopped because the cancel flag was set
+    pub cancelled: bool,
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub errors: Vec<String>,
+}
+
+impl BackfillProgress {
+    /// Share of the job done, 0-100
+    pub fn percent_complete(&self) -> f64 {
+        let total = self.processed + self.remaining;
+        if total == 0 || self.resume_token.is_none() {
+            100.0
+        } else {
+            self.processed as f64 / total as f64 * 100.0
+        }
+    }
+}
+
+/// Composite importance of one memory and the channels it blends
+///
+/// Each channel is normalized to 0.0-1.0 before weighting.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeImportance {
+    /// Fraction of retrievals that proved useful
+    pub utility: f64,
+    /// ACT-R base-level activation, normalized from [-2, 5]
+    pub activation: f64,
+    /// Emotional intensity (magnitude of valence)
+    pub emotion: f64,
+    /// Saturating count of successful reviews
+    pub repetition: f64,
+    /// Importance evolved from usage events (0.0 until first used)
+    pub usage: f64,
+    /// Weighted blend of the channels
+    pub composite: f64,
+}
+
+impl NodeImportance {
+    const UTILITY_WEIGHT: f64 = 0.3;
+    const ACTIVATION_WEIGHT: f64 = 0.25;
+    const EMOTION_WEIGHT: f64 = 0.15;
+    const REPETITION_WEIGHT: f64 = 0.15;
+    const USAGE_WEIGHT: f64 = 0.15;
+    /// Reviews at which the repetition channel reaches 0.5
+    const REPETITION_HALF: f64 = 5.0;
+
+    /// Blend raw node columns into a composite importance
+    pub fn compute(
+        utility_score: Option<f64>,
+        activation: Option<f64>,
+        emotional_valence: Option<f64>,
+        reps: i32,
+        usage_importance: Option<f64>,
+    ) -> Self {
+        let utility = utility_score.unwrap_or(0.0).clamp(0.0, 1.0);
+        let activation = activation.map_or(0.0, |a| ((a + 2.0) / 7.0).clamp(0.0, 1.0));
+        let emotion = emotional_valence.unwrap_or(0.0).abs().min(1.0);
+        let reps = reps.max(0) as f64;
+        let repetition = reps / (reps + Self::REPETITION_HALF);
+        let usage = usage_importance.unwrap_or(0.0).clamp(0.0, 1.0);
+        let composite = utility * Self::UTILITY_WEIGHT
+            + activation * Self::ACTIVATION_WEIGHT
+            + emotion * Self::EMOTION_WEIGHT
+            + repetition * Self::REPETITION_WEIGHT
+            + usage * Self::USAGE_WEIGHT;
+        Self {
+            utility,
+            activation,
+            emotion,
+            repetition,
+            usage,
+            composite,
+        }
+    }
+}
+
+/// One point in a memory's evolving importance
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportanceHistoryPoint {
+    pub final_score: f64,
+    pub usage_importance: f64,
+    pub recency_importance: f64,
+    /// What moved the score: the usage event ("access", "promote", "demote",
+    /// "useful") or "decay"
+    pub cause: String,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Action applied by `Storage::bulk_update`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkAction {
+    Delete,
+    Promote,
+    Demote,
+    /// Replace each memory's tags
+    Retag { tags: Vec<String> },
+}
+
+/// Result of a bulk action for one memory
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkOutcome {
+    pub id: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// New retention strength after a promote or demote
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retention_strength: Option<f64>,
+}
+
+impl BulkOutcome {
+    fn not_found(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            ok: false,
+            error: Some("not found".to_string()),
+            retention_strength: None,
+        }
+    }
+}
+
+/// One titled section of a context packet
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextSection {
+    pub title: String,
+    pub lines: Vec<String>,
+}
+
+/// Ready-to-prompt bundle of memories, insights, and due intentions for a query
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextPacket {
+    pub query: String,
+    /// Non-empty sections in prompt order
+    pub sections: Vec<ContextSection>,
+    pub memory_ids: Vec<String>,
+    pub insight_ids: Vec<String>,
+    pub intention_ids: Vec<String>,
+    /// Approximate size of `to_prompt()` (chars / 4)
+    pub estimated_tokens: usize,
+    /// Whether anything was left out to stay within the budget
+    pub truncated: bool,
+}
+
+impl ContextPacket {
+    /// Memories retrieved for the query before budgeting
+    const MEMORY_CANDIDATES: i32 = 10;
+    /// Recent insights scanned for ones built on the included memories
+    const INSIGHT_SCAN: i32 = 200;
+    /// Intentions with a deadline within this many hours are due
+    const DUE_WITHIN_HOURS: i64 = 24;
+
+    /// Render the packet as markdown, one `##` heading per section
+    pub fn to_prompt(&self) -> String {
+        self.sections
+            .iter()
+            .map(|section| format!("## {}\n{}", section.title, section.lines.join("\n")))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+/// Fills context packet sections against a shared character budget
+struct ContextBudget {
+    remaining: usize,
+    truncated: bool,
+}
+
+impl ContextBudget {
+    /// Add a section with as many lines as fit; returns the indices kept
+    fn fill(&mut self, title: &str, lines: &[String], sections: &mut Vec<ContextSection>) -> Vec<usize> {
+        // Heading plus the blank line separating it from the previous section
+        let header = title.chars().count() + 5;
+        let mut kept = Vec::new();
+        let mut section = ContextSection {
+            title: title.to_string(),
+            lines: Vec::new(),
+        };
+        for (i, line) in lines.iter().enumerate() {
+            let cost = line.chars().count() + 1 + if kept.is_empty() { header } else { 0 };
+            if cost > self.remaining {
+                self.truncated = true;
+                continue;
+            }
+            self.remaining -= cost;
+            section.lines.push(line.clone());
+            kept.push(i);
+        }
+        if !section.lines.is_empty() {
+            sections.push(section);
+        }
+        kept
+    }
+}
+
+/// Diff between two contents of a node
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionDiff {
+    pub node_id: String,
+    /// Revision whose content is the old side
+    pub from: i64,
+    /// Revision whose content is the new side; `None` for the current content
+    pub to: Option<i64>,
+    /// Unified diff from `from` to `to`
+    pub diff: String,
+    /// Cosine similarity of the two contents' embeddings, when both are known
+    pub similarity: Option<f32>,
+}
+
+/// Content a node held before one update
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeRevision {
+    pub id: i64,
+    pub node_id: String,
+    pub old_content: String,
+    /// Hash of the content that replaced `old_content`
+    pub new_content_hash: String,
+    pub changed_at: DateTime<Utc>,
+    /// What made the change: `update`, `smart_ingest:merge`, ...
+    pub change_source: String,
+    /// Unified diff to the replacing content; `None` if that content is no
+    /// longer known (pruned or changed outside revision tracking)
+    pub diff: Option<String>,
+}
+
+/// Outcome of one storage diagnostic check, ordered by severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticStatus {
+    Ok,
+    Warn,
+    Error,
+}
+
+/// One storage diagnostic check
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticCheck {
+    pub name: String,
+    pub status: DiagnosticStatus,
+    /// What was measured
+    pub detail: String,
+    /// Suggested fix; `None` when the check passed
+    pub remediation: Option<String>,
+}
+
+impl DiagnosticCheck {
+    fn ok(name: &str, detail: String) -> Self {
+        Self {
+            name: name.to_string(),
+            status: DiagnosticStatus::Ok,
+            detail,
+            remediation: None,
+        }
+    }
+
+    fn failed(name: &str, status: DiagnosticStatus, detail: String, remediation: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            detail,
+            remediation: Some(remediation.to_string()),
+        }
+    }
+}
+
+/// Storage integrity and maintenance report from `Storage::diagnostics`
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsReport {
+    /// Worst status across all checks
+    pub status: DiagnosticStatus,
+    pub generated_at: DateTime<Utc>,
+    pub db_size_bytes: u64,
+    pub wal_size_bytes: u64,
+    pub page_count: i64,
+    pub page_size: i64,
+    pub freelist_count: i64,
+    pub checks: Vec<DiagnosticCheck>,
+}
+
+impl DiagnosticsReport {
+    /// WAL size above which a checkpoint is suggested (matches `journal_size_limit`)
+    pub const WAL_WARN_BYTES: u64 = 64 * 1024 * 1024;
+    /// Consolidation older than this is reported as stale
+    pub const CONSOLIDATION_STALE_HOURS: i64 = 24;
+    /// Dreams older than this are reported as stale, as are backups when
+    /// automatic backups are disabled (otherwise the backup interval applies)
+    pub const MAINTENANCE_STALE_DAYS: i64 = 7;
+
+    /// Look up a check by name
+    pub fn check(&self, name: &str) -> Option<&DiagnosticCheck> {
+        self.checks.iter().find(|c| c.name == name)
+    }
+}
+
+/// Embedding, vector index and lock consistency from `Storage::diagnose`
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    pub node_count: i64,
+    pub embedding_count: i64,
+    /// Vectors in the in-memory index; `None` without vector search
+    pub vector_index_size: Option<usize>,
+    /// Embedding rows whose node no longer exists
+    pub orphaned_embeddings: i64,
+    /// Nodes with no embedding row
+    pub nodes_missing_embeddings: i64,
+    /// Nodes flagged as embedded that have no embedding row
+    pub flagged_without_embedding: i64,
+    /// Stored embeddings with no vector in the index; `None` without vector search
+    pub vectors_missing_from_index: Option<usize>,
+    /// Storage locks left poisoned by a panic
+    pub poisoned_locks: Vec<String>,
+    /// Query embedding cache occupancy and hit rate; `None` without embeddings
+    pub query_cache: Option<QueryCacheStats>,
+    pub schema_version: u32,
+    pub last_consolidation: Option<DateTime<Utc>>,
+    pub last_dream: Option<DateTime<Utc>>,
+    /// Changes since the last consolidation; runs are skipped below the
+    /// configured threshold
+    #[serde(default)]
+    pub mutations_since_consolidation: u64,
+}
+
+impl HealthReport {
+    /// Whether nothing needs `Storage::repair` (or a restart, for poisoned locks)
+    pub fn is_consistent(&self) -> bool {
+        self.orphaned_embeddings == 0
+            && self.flagged_without_embedding == 0
+            && self.vectors_missing_from_index.unwrap_or(0) == 0
+            && self.poisoned_locks.is_empty()
+    }
+}
+
+/// What `Storage::repair` fixed
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairReport {
+    pub orphaned_embeddings_deleted: usize,
+    /// Nodes whose embedded flag was cleared so consolidation regenerates them
+    pub embedding_flags_cleared: usize,
+    pub vectors_readded: usize,
+}
+
+/// Memory state record
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct MemoryStateRecord {
+    pub memory_id: String,
+    pub state: String,  // 'active', 'dormant', 'silent', 'unavailable'
+    pub last_access: DateTime<Utc>,
+    pub access_count: i32,
+    pub state_entered_at: DateTime<Utc>,
+    pub suppression_until: Option<DateTime<Utc>>,
+    pub suppressed_by: Vec<String>,
+}
+
+/// State transition record for audit trail
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StateTransitionRecord {
+    pub id: i64,
+    pub memory_id: String,
+    pub from_state: String,
+    pub to_state: String,
+    pub reason_type: String,
+    pub reason_data: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Consolidation history record
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ConsolidationHistoryRecord {
+    pub id: i64,
+    pub completed_at: DateTime<Utc>,
+    pub duration_ms: i64,
+    pub memories_replayed: i32,
+    pub connections_found: i32,
+    pub connections_strengthened: i32,
+    pub connections_pruned: i32,
+    pub insights_generated: i32,
+    /// Per-step durations of the run (empty for runs before V30)
+    #[serde(default)]
+    pub step_timings: Vec<StepTiming>,
+    /// Steps that failed during the run
+    #[serde(default)]
+    pub step_errors: Vec<StepError>,
+    /// Why the run was skipped; `None` when it ran
+    #[serde(default)]
+    pub skipped_reason: Option<String>,
+    /// Why the run stopped before every step ran; `None` when it completed
+    #[serde(default)]
+    pub stopped_reason: Option<String>,
+    /// Steps a run stopped early left out
+    #[serde(default)]
+    pub steps_not_run: Vec<String>,
+}
+
+/// Dream history record — persists dream metadata for automation triggers
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DreamHistoryRecord {
+    pub dreamed_at: DateTime<Utc>,
+    pub duration_ms: i64,
+    pub memories_replayed: i32,
+    pub connections_found: i32,
+    pub insights_generated: i32,
+    pub memories_strengthened: i32,
+    pub memories_compressed: i32,
+    // v2.0: 4-Phase dream cycle metrics
+    pub phase_nrem1_ms: Option<i64>,
+    pub phase_nrem3_ms: Option<i64>,
+    pub phase_rem_ms: Option<i64>,
+    pub phase_integration_ms: Option<i64>,
+    pub summaries_generated: Option<i32>,
+    pub emotional_memories_processed: Option<i32>,
+    pub creative_connections_found: Option<i32>,
+}
+
+impl Storage {
+    // ========================================================================
+    // INTENTIONS PERSISTENCE
+    // ========================================================================
+
+    /// Save an intention to the database
+    pub fn save_intention(&self, intention: &IntentionRecord) -> Result<()> {
+        self.save_intention_in(intention, None)
+    }
+
+    /// Save an intention into a namespace; `None` keeps the namespace of an
+    /// existing intention with the same ID
+    pub(crate) fn save_intention_in(
+        &self,
+        intention: &IntentionRecord,
+        namespace: Option<&str>,
+    ) -> Result<()> {
+        let tags_json = serde_json::to_string(&intention.tags).unwrap_or_else(|_| "[]".to_string());
+        let related_json = serde_json::to_string(&intention.related_memories).unwrap_or_else(|_| "[]".to_string());
+
+        let writer = self.lock_writer()?;
+        writer.execute(
+            "INSERT OR REPLACE INTO intentions (
+                id, content, trigger_type, trigger_data, priority, status,
+                created_at, deadline, fulfilled_at, reminder_count, last_reminded_at,
+                notes, tags, related_memories, snoozed_until, source_type, source_data,
+                namespace
+            ) VALUES (
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17,
+                COALESCE(?18, (SELECT namespace FROM intentions WHERE id = ?1))
+            )",
+            params![
+                intention.id,
+                intention.content,
+                intention.trigger_type,
+                intention.trigger_data,
+                intention.priority,
+                intention.status,
+                intention.created_at.to_rfc3339(),
+                intention.deadline.map(|dt| dt.to_rfc3339()),
+                intention.fulfilled_at.map(|dt| dt.to_rfc3339()),
+                intention.reminder_count,
+                intention.last_reminded_at.map(|dt| dt.to_rfc3339()),
+                intention.notes,
+                tags_json,
+                related_json,
+                intention.snoozed_until.map(|dt| dt.to_rfc3339()),
+                intention.source_type,
+                intention.source_data,
+                namespace,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get an intention by ID
+    pub fn get_intention(&self, id: &str) -> Result<Option<IntentionRecord>> {
+        self.get_intention_in(None, id)
+    }
+
+    /// `get_intention`, not finding intentions outside `namespace` (if any)
+    pub(crate) fn get_intention_in(
+        &self,
+        namespace: Option<&str>,
+        id: &str,
+    ) -> Result<Option<IntentionRecord>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT * FROM intentions WHERE id = ?1 AND (?2 IS NULL OR namespace = ?2)"
+        )?;
+
+        stmt.query_row(params![id, namespace], |row| Self::row_to_intention(row))
+            .optional()
+            .map_err(StorageError::from)
+    }
+
+    /// Get all active intentions
+    pub fn get_active_intentions(&self) -> Result<Vec<IntentionRecord>> {
+        self.get_active_intentions_in(None)
+    }
+
+    /// Active intentions in one namespace, or all of them with `None`
+    pub(crate) fn get_active_intentions_in(
+        &self,
+        namespace: Option<&str>,
+    ) -> Result<Vec<IntentionRecord>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT * FROM intentions
+             WHERE status = 'active' AND (?1 IS NULL OR namespace = ?1)
+             ORDER BY priority DESC, created_at ASC"
+        )?;
+
+        let rows = stmt.query_map(params![namespace], |row| Self::row_to_intention(row))?;
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    /// Get intentions by status
+    pub fn get_intentions_by_status(&self, status: &str) -> Result<Vec<IntentionRecord>> {
+        self.get_intentions_by_status_in(None, status)
+    }
+
+    /// Intentions with a status in one namespace, or all of them with `None`
+    pub(crate) fn get_intentions_by_status_in(
+        &self,
+        namespace: Option<&str>,
+        status: &str,
+    ) -> Result<Vec<IntentionRecord>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT * FROM intentions
+             WHERE status = ?1 AND (?2 IS NULL OR namespace = ?2)
+             ORDER BY priority DESC, created_at ASC"
+        )?;
+
+        let rows = stmt.query_map(params![status, namespace], |row| Self::row_to_intention(row))?;
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    /// Update intention status
+    pub fn update_intention_status(&self, id: &str, status: &str) -> Result<bool> {
+        let now = self.now();
+        let fulfilled_at = if status == "fulfilled" { Some(now.to_rfc3339()) } else { None };
+
+        let writer = self.lock_writer()?;
+        let rows = writer.execute(
+            "UPDATE intentions SET status = ?1, fulfilled_at = ?2 WHERE id = ?3",
+            params![status, fulfilled_at, id],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// Delete an intention
+    pub fn delete_intention(&self, id: &str) -> Result<bool> {
+        let writer = self.lock_writer()?;
+        let rows = writer.execute("DELETE FROM intentions WHERE id = ?1", params![id])?;
+        Ok(rows > 0)
+    }
+
+    /// Get overdue intentions
+    pub fn get_overdue_intentions(&self) -> Result<Vec<IntentionRecord>> {
+        let now = self.now().to_rfc3339();
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT * FROM intentions WHERE status = 'active' AND deadline IS NOT NULL AND deadline < ?1 ORDER BY deadline ASC"
+        )?;
+
+        let rows = stmt.query_map(params![now], |row| Self::row_to_intention(row))?;
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    /// Snooze an intention
+    pub fn snooze_intention(&self, id: &str, until: DateTime<Utc>) -> Result<bool> {
+        let writer = self.lock_writer()?;
+        let rows = writer.execute(
+            "UPDATE intentions SET status = 'snoozed', snoozed_until = ?1 WHERE id = ?2",
+            params![until.to_rfc3339(), id],
+        )?;
+        Ok(rows > 0)
+    }
+
+    fn row_to_intention(row: &rusqlite::Row) -> rusqlite::Result<IntentionRecord> {
+        let tags_json: String = row.get("tags")?;
+        let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
+        let related_json: String = row.get("related_memories")?;
+        let related: Vec<String> = serde_json::from_str(&related_json).unwrap_or_default();
+
+        let parse_opt_dt = |s: Option<String>| -> Option<DateTime<Utc>> {
+            s.and_then(|v| DateTime::parse_from_rfc3339(&v).ok().map(|dt| dt.with_timezone(&Utc)))
+        };
+
+        Ok(IntentionRecord {
+            id: row.get("id")?,
+            content: row.get("content")?,
+            trigger_type: row.get("trigger_type")?,
+            trigger_data: row.get("trigger_data")?,
+            priority: row.get("priority")?,
+            status: row.get("status")?,
+            created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>("created_at")?)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            deadline: parse_opt_dt(row.get("deadline").ok().flatten()),
+            fulfilled_at: parse_opt_dt(row.get("fulfilled_at").ok().flatten()),
+            reminder_count: row.get("reminder_count").unwrap_or(0),
+            last_reminded_at: parse_opt_dt(row.get("last_reminded_at").ok().flatten()),
+            notes: row.get("notes").ok().flatten(),
+            tags,
+            related_memories: related,
+            snoozed_until: parse_opt_dt(row.get("snoozed_until").ok().flatten()),
+            source_type: row.get("source_type").unwrap_or_else(|_| "api".to_string()),
+            source_data: row.get("source_data").ok().flatten(),
+        })
+    }
+
+    // ========================================================================
+    // INSIGHTS PERSISTENCE
+    // ========================================================================
+
+    /// Save an insight to the database
+    pub fn save_insight(&self, insight: &InsightRecord) -> Result<()> {
+        self.save_insight_in(insight, None)
+    }
+
+    /// Save an insight into a namespace; `None` keeps the namespace of an
+    /// existing insight with the same ID
+    pub(crate) fn save_insight_in(
+        &self,
+        insight: &InsightRecord,
+        namespace: Option<&str>,
+    ) -> Result<()> {
+        let source_json = serde_json::to_string(&insight.source_memories).unwrap_or_else(|_| "[]".to_string());
+        let tags_json = serde_json::to_string(&insight.tags).unwrap_or_else(|_| "[]".to_string());
+
+        let writer = self.lock_writer()?;
+        writer.execute(
+            "INSERT OR REPLACE INTO insights (
+                id, insight, source_memories, confidence, novelty_score, insight_type,
+                generated_at, tags, feedback, applied_count, namespace
+            ) VALUES (
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10,
+                COALESCE(?11, (SELECT namespace FROM insights WHERE id = ?1))
+            )",
+            params![
+                insight.id,
+                insight.insight,
+                source_json,
+                insight.confidence,
+                insight.novelty_score,
+                insight.insight_type,
+                insight.generated_at.to_rfc3339(),
+                tags_json,
+                insight.feedback,
+                insight.applied_count,
+                namespace,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get insights with optional limit
+    pub fn get_insights(&self, limit: i32) -> Result<Vec<InsightRecord>> {
+        self.get_insights_in(None, limit)
+    }
+
+    /// Insights from one namespace, or all of them with `None`
+    pub(crate) fn get_insights_in(
+        &self,
+        namespace: Option<&str>,
+        limit: i32,
+    ) -> Result<Vec<InsightRecord>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT * FROM insights
+             WHERE ?2 IS NULL OR namespace = ?2
+             ORDER BY generated_at DESC LIMIT ?1"
+        )?;
+
+        let rows = stmt.query_map(params![limit, namespace], |row| Self::row_to_insight(row))?;
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    /// Get insights without feedback (pending review)
+    pub fn get_pending_insights(&self) -> Result<Vec<InsightRecord>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT * FROM insights WHERE feedback IS NULL ORDER BY novelty_score DESC"
+        )?;
+
+        let rows = stmt.query_map([], |row| Self::row_to_insight(row))?;
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    /// Mark insight feedback
+    pub fn mark_insight_feedback(&self, id: &str, feedback: &str) -> Result<bool> {
+        let writer = self.lock_writer()?;
+        let rows = writer.execute(
+            "UPDATE insights SET feedback = ?1 WHERE id = ?2",
+            params![feedback, id],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// Clear all insights
+    pub fn clear_insights(&self) -> Result<i32> {
+        let writer = self.lock_writer()?;
+        let count: i32 = writer.query_row("SELECT COUNT(*) FROM insights", [], |row| row.get(0))?;
+        writer.execute("DELETE FROM insights", [])?;
+        Ok(count)
+    }
+
+    fn row_to_insight(row: &rusqlite::Row) -> rusqlite::Result<InsightRecord> {
+        let source_json: String = row.get("source_memories")?;
+        let source_memories: Vec<String> = serde_json::from_str(&source_json).unwrap_or_default();
+        let tags_json: String = row.get("tags")?;
+        let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
+
+        Ok(InsightRecord {
+            id: row.get("id")?,
+            insight: row.get("insight")?,
+            source_memories,
+            confidence: row.get("confidence")?,
+            novelty_score: row.get("novelty_score")?,
+            insight_type: row.get("insight_type")?,
+            generated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>("generated_at")?)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            tags,
+            feedback: row.get("feedback").ok().flatten(),
+            applied_count: row.get("applied_count").unwrap_or(0),
+        })
+    }
+
+    // ========================================================================
+    // CONTEXT PACKETS
+    // ========================================================================
+
+    /// Assemble a token-budgeted context bundle for a query
+    ///
+    /// Gathers the most relevant memories, the insights generated from them,
+    /// and active intentions that are overdue or due within a day. Duplicate
+    /// lines are dropped and sections are filled in that order until the
+    /// budget (approximated as chars / 4) runs out. Included memories are
+    /// strengthened as with `recall`.
+    pub fn build_context_packet(&self, query: &str, budget_tokens: usize) -> Result<ContextPacket> {
+        let mut budget = ContextBudget {
+            remaining: budget_tokens.saturating_mul(4),
+            truncated: false,
+        };
+        let mut sections = Vec::new();
+        let mut seen_lines = HashSet::new();
+
+        let mut memories = self.recall(RecallInput {
+            query: query.to_string(),
+            limit: ContextPacket::MEMORY_CANDIDATES,
+            ..Default::default()
+        })?;
+        let mut seen_ids = HashSet::new();
+        memories.retain(|node| {
+            seen_ids.insert(node.id.clone())
+                && seen_lines.insert(node.content.trim().to_lowercase())
+        });
+        let lines: Vec<String> = memories
+            .iter()
+            .map(|node| format!("- [{}] {}", node.node_type, node.content.trim()))
+            .collect();
+        let memory_ids: Vec<String> = budget
+            .fill("Memories", &lines, &mut sections)
+            .into_iter()
+            .map(|i| memories[i].id.clone())
+            .collect();
+
+        let included: HashSet<&str> = memory_ids.iter().map(String::as_str).collect();
+        let insights: Vec<InsightRecord> = self
+            .get_insights(ContextPacket::INSIGHT_SCAN)?
+            .into_iter()
+            .filter(|insight| {
+                insight.source_memories.iter().any(|id| included.contains(id.as_str()))
+                    && seen_lines.insert(insight.insight.trim().to_lowercase())
+            })
+            .collect();
+        let lines: Vec<String> = insights
+            .iter()
+            .map(|insight| format!("- {}", insight.insight.trim()))
+            .collect();
+        let insight_ids: Vec<String> = budget
+            .fill("Insights", &lines, &mut sections)
+            .into_iter()
+            .map(|i| insights[i].id.clone())
+            .collect();
+
+        let due_before = self.now() + Duration::hours(ContextPacket::DUE_WITHIN_HOURS);
+        let intentions: Vec<IntentionRecord> = self
+            .get_active_intentions()?
+            .into_iter()
+            .filter(|intention| {
+                intention.deadline.is_some_and(|d| d <= due_before)
+                    && seen_lines.insert(intention.content.trim().to_lowercase())
+            })
+            .collect();
+        let lines: Vec<String> = intentions
+            .iter()
+            .map(|intention| {
+                let deadline = intention
+                    .deadline
+                    .map(|d| format!(" (due {})", d.format("%Y-%m-%d %H:%M UTC")))
+                    .unwrap_or_default();
+                format!("- {}{}", intention.content.trim(), deadline)
+            })
+            .collect();
+        let intention_ids: Vec<String> = budget
+            .fill("Due intentions", &lines, &mut sections)
+            .into_iter()
+            .map(|i| intentions[i].id.clone())
+            .collect();
+
+        let mut packet = ContextPacket {
+            query: query.to_string(),
+            sections,
+            memory_ids,
+            insight_ids,
+            intention_ids,
+            estimated_tokens: 0,
+            truncated: budget.truncated,
+        };
+        packet.estimated_tokens = packet.to_prompt().chars().count().div_ceil(4);
+        Ok(packet)
+    }
+
+    /// Summarize the session's working set into topical packets within a token budget
+    ///
+    /// The working set is the most recently accessed memories, the memories
+    /// related to active intentions and, with `project_tag`, the memories
+    /// carrying that tag. Clusters are dropped lowest-importance first to fit
+    /// the budget (see `advanced::session_packet`).
+    pub fn build_session_overview(
+        &self,
+        project_tag: Option<&str>,
+        budget_tokens: usize,
+    ) -> Result<SessionOverview> {
+        self.build_session_overview_in(None, project_tag, budget_tokens)
+    }
+
+    /// `build_session_overview` limited to one namespace, or all of them with `None`
+    pub(crate) fn build_session_overview_in(
+        &self,
+        namespace: Option<&str>,
+        project_tag: Option<&str>,
+        budget_tokens: usize,
+    ) -> Result<SessionOverview> {
+        let mut nodes = {
+            let reader = self.lock_reader()?;
+            let mut nodes = reader
+                .prepare(
+                    "SELECT * FROM knowledge_nodes WHERE (?2 IS NULL OR namespace = ?2)
+                     ORDER BY last_accessed DESC LIMIT ?1",
+                )?
+                .query_map(params![SESSION_RECENT_MEMORIES, namespace], |row| {
+                    Self::row_to_node(row)
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            if let Some(tag) = project_tag {
+                nodes.extend(
+                    reader
+                        .prepare(
+                            "SELECT * FROM knowledge_nodes WHERE tags LIKE ?1
+                               AND (?3 IS NULL OR namespace = ?3)
+                             ORDER BY last_accessed DESC LIMIT ?2",
+                        )?
+                        .query_map(
+                            params![format!("%\"{}\"%", tag), SESSION_PROJECT_MEMORIES, namespace],
+                            |row| Self::row_to_node(row),
+                        )?
+                        .collect::<rusqlite::Result<Vec<_>>>()?,
+                );
+            }
+            nodes
+        };
+        for intention in self.get_active_intentions_in(namespace)? {
+            for id in &intention.related_memories {
+                if let Some(node) = self.get_node(id)?
+                    && (namespace.is_none() || node.namespace.as_deref() == namespace)
+                {
+                    nodes.push(node);
+                }
+            }
+        }
+
+        let mut seen = HashSet::new();
+        nodes.retain(|node| seen.insert(node.id.clone()));
+        let mut items = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+            let embedding = self.get_node_embedding(&node.id)?;
+            #[cfg(not(all(feature = "embeddings", feature = "vector-search")))]
+            let embedding = None;
+            items.push(SessionItem {
+                importance: node.importance.unwrap_or(node.retention_strength),
+                id: node.id,
+                content: node.content,
+                embedding,
+            });
+        }
+        Ok(session_packet::build_overview(&items, budget_tokens))
+    }
+
+    // ========================================================================
+    // MEMORY CONNECTIONS PERSISTENCE (Activation Network)
+    // ========================================================================
+
+    /// Save a memory connection, in the namespace of its source memory
+    pub fn save_connection(&self, connection: &ConnectionRecord) -> Result<()> {
+        let writer = self.lock_writer()?;
+        writer.execute(
+            "INSERT OR REPLACE INTO memory_connections (
+                source_id, target_id, strength, link_type, created_at, last_activated, activation_count,
+                namespace
+            ) VALUES (
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7,
+                (SELECT namespace FROM knowledge_nodes WHERE id = ?1)
+            )",
+            params![
+                connection.source_id,
+                connection.target_id,
+                connection.strength,
+                connection.link_type,
+                connection.created_at.to_rfc3339(),
+                connection.last_activated.to_rfc3339(),
+                connection.activation_count,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get connections for a memory
+    pub fn get_connections_for_memory(&self, memory_id: &str) -> Result<Vec<ConnectionRecord>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT * FROM memory_connections WHERE source_id = ?1 OR target_id = ?1 ORDER BY strength DESC"
+        )?;
+
+        let rows = stmt.query_map(params![memory_id], |row| Self::row_to_connection(row))?;
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    /// Get all connections (for building activation network)
+    pub fn get_all_connections(&self) -> Result<Vec<ConnectionRecord>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT * FROM memory_connections ORDER BY strength DESC"
+        )?;
+
+        let rows = stmt.query_map([], |row| Self::row_to_connection(row))?;
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    /// Strengthen a connection
+    pub fn strengthen_connection(&self, source_id: &str, target_id: &str, boost: f64) -> Result<bool> {
+        let now = self.now().to_rfc3339();
+        let writer = self.lock_writer()?;
+        let rows = writer.execute(
+            "UPDATE memory_connections SET
+                strength = MIN(strength + ?1, 1.0),
+                last_activated = ?2,
+                activation_count = activation_count + 1
+             WHERE source_id = ?3 AND target_id = ?4",
+            params![boost, now, source_id, target_id],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// Apply decay to all connections
+    pub fn apply_connection_decay(&self, decay_factor: f64) -> Result<i32> {
+        let writer = self.lock_writer()?;
+        let rows = writer.execute(
+            "UPDATE memory_connections SET strength = strength * ?1",
+            params![decay_factor],
+        )?;
+        Ok(rows as i32)
+    }
+
+    /// Prune weak connections below threshold
+    pub fn prune_weak_connections(&self, min_strength: f64) -> Result<i32> {
+        let writer = self.lock_writer()?;
+        let rows = writer.execute(
+            "DELETE FROM memory_connections WHERE strength < ?1",
+            params![min_strength],
+        )?;
+        Ok(rows as i32)
+    }
+
+    fn row_to_connection(row: &rusqlite::Row) -> rusqlite::Result<ConnectionRecord> {
+        Ok(ConnectionRecord {
+            source_id: row.get("source_id")?,
+            target_id: row.get("target_id")?,
+            strength: row.get("strength")?,
+            link_type: row.get("link_type")?,
+            created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>("created_at")?)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            last_activated: DateTime::parse_from_rfc3339(&row.get::<_, String>("last_activated")?)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            activation_count: row.get("activation_count").unwrap_or(0),
+        })
+    }
+
+    // ========================================================================
+    // KNOWLEDGE EDGES (Typed temporal graph)
+    // ========================================================================
+
+    /// Save a typed knowledge edge, replacing any edge with the same ID
+    ///
+    /// Both endpoints must exist; a missing one is `NotFound`.
+    pub fn save_knowledge_edge(&self, edge: &KnowledgeEdge) -> Result<()> {
+        let writer = self.lock_writer()?;
+        Self::insert_knowledge_edge(&writer, edge)
+    }
+
+    /// Insert or replace an edge on `conn`, checking both endpoints exist
+    fn insert_knowledge_edge(conn: &Connection, edge: &KnowledgeEdge) -> Result<()> {
+        for id in [&edge.source_id, &edge.target_id] {
+            let exists: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM knowledge_nodes WHERE id = ?1)",
+                params![id],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                return Err(StorageError::NotFound(id.clone()));
+            }
+        }
+        conn.execute(
+            "INSERT OR REPLACE INTO knowledge_edges (
+                id, source_id, target_id, edge_type, weight, valid_from, valid_until,
+                created_at, created_by, confidence, metadata
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                edge.id,
+                edge.source_id,
+                edge.target_id,
+                edge.edge_type.to_string(),
+                edge.weight,
+                edge.valid_from.map(|t| t.to_rfc3339()),
+                edge.valid_until.map(|t| t.to_rfc3339()),
+                edge.created_at.to_rfc3339(),
+                edge.created_by,
+                edge.confidence,
+                edge.metadata,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get a knowledge edge by ID
+    pub fn get_knowledge_edge(&self, id: &str) -> Result<Option<KnowledgeEdge>> {
+        let reader = self.lock_reader()?;
+        let edge = reader
+            .query_row(
+                "SELECT * FROM knowledge_edges WHERE id = ?1",
+                params![id],
+                Self::row_to_knowledge_edge,
+            )
+            .optional()?;
+        Ok(edge)
+    }
+
+    /// Knowledge edges touching a node (either direction), strongest first
+    pub fn get_knowledge_edges(&self, node_id: &str) -> Result<Vec<KnowledgeEdge>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT * FROM knowledge_edges WHERE source_id = ?1 OR target_id = ?1
+             ORDER BY weight DESC, created_at DESC",
+        )?;
+        let edges = stmt
+            .query_map(params![node_id], Self::row_to_knowledge_edge)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(edges)
+    }
+
+    /// Unresolved contradiction edges, newest first
+    pub fn list_contradictions(&self, limit: i32) -> Result<Vec<KnowledgeEdge>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT * FROM knowledge_edges
+             WHERE edge_type = 'contradiction' AND valid_until IS NULL
+             ORDER BY created_at DESC
+             LIMIT ?1",
+        )?;
+        let edges = stmt
+            .query_map(params![limit], Self::row_to_knowledge_edge)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(edges)
+    }
+
+    /// Mark a contradiction edge resolved (it stays as history); false if no
+    /// unresolved contradiction has this ID
+    pub fn resolve_contradiction(&self, id: &str) -> Result<bool> {
+        let writer = self.lock_writer()?;
+        let rows = writer.execute(
+            "UPDATE knowledge_edges SET valid_until = ?1
+             WHERE id = ?2 AND edge_type = 'contradiction' AND valid_until IS NULL",
+            params![self.now().to_rfc3339(), id],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// Delete a knowledge edge; false if it did not exist
+    pub fn delete_knowledge_edge(&self, id: &str) -> Result<bool> {
+        let writer = self.lock_writer()?;
+        let rows = writer.execute("DELETE FROM knowledge_edges WHERE id = ?1", params![id])?;
+        Ok(rows > 0)
+    }
+
+    fn row_to_knowledge_edge(row: &rusqlite::Row) -> rusqlite::Result<KnowledgeEdge> {
+        let edge_type: String = row.get("edge_type")?;
+        let optional_time = |field: &str| -> rusqlite::Result<Option<DateTime<Utc>>> {
+            row.get::<_, Option<String>>(field)?
+                .map(|s| Self::parse_timestamp(&s, field))
+                .transpose()
+        };
+        Ok(KnowledgeEdge {
+            id: row.get("id")?,
+            source_id: row.get("source_id")?,
+            target_id: row.get("target_id")?,
+            edge_type: edge_type.parse().unwrap_or(EdgeType::Custom),
+            weight: row.get::<_, f64>("weight")? as f32,
+            valid_from: optional_time("valid_from")?,
+            valid_until: optional_time("valid_until")?,
+            created_at: Self::parse_timestamp(&row.get::<_, String>("created_at")?, "created_at")?,
+            created_by: row.get("created_by")?,
+            confidence: row.get::<_, f64>("confidence")? as f32,
+            metadata: row.get("metadata")?,
+        })
+    }
+
+    // ========================================================================
+    // MEMORY STATES PERSISTENCE
+    // ========================================================================
+
+    /// Save or update memory state
+    pub fn save_memory_state(&self, state: &MemoryStateRecord) -> Result<()> {
+        let suppressed_json = serde_json::to_string(&state.suppressed_by).unwrap_or_else(|_| "[]".to_string());
+
+        let writer = self.lock_writer()?;
+        writer.execute(
+            "INSERT OR REPLACE INTO memory_states (
+                memory_id, state, last_access, access_count, state_entered_at,
+                suppression_until, suppressed_by
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                state.memory_id,
+                state.state,
+                state.last_access.to_rfc3339(),
+                state.access_count,
+                state.state_entered_at.to_rfc3339(),
+                state.suppression_until.map(|dt| dt.to_rfc3339()),
+                suppressed_json,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get memory state
+    pub fn get_memory_state(&self, memory_id: &str) -> Result<Option<MemoryStateRecord>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT * FROM memory_states WHERE memory_id = ?1"
+        )?;
+
+        stmt.query_row(params![memory_id], |row| Self::row_to_memory_state(row))
+            .optional()
+            .map_err(StorageError::from)
+    }
+
+    /// Get memories by state
+    pub fn get_memories_by_state(&self, state: &str) -> Result<Vec<String>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT memory_id FROM memory_states WHERE state = ?1"
+        )?;
+
+        let rows = stmt.query_map(params![state], |row| row.get::<_, String>(0))?;
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    /// Update memory state
+    pub fn update_memory_state(&self, memory_id: &str, new_state: &str, reason: &str) -> Result<bool> {
+        let now = self.now();
+
+        // Get old state for transition record
+        if let Some(old_record) = self.get_memory_state(memory_id)? {
+            // Record state transition
+            let writer = self.lock_writer()?;
+            writer.execute(
+                "INSERT INTO state_transitions (memory_id, from_state, to_state, reason_type, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![memory_id, old_record.state, new_state, reason, now.to_rfc3339()],
+            )?;
+        }
+
+        let writer = self.lock_writer()?;
+        let rows = writer.execute(
+            "UPDATE memory_states SET state = ?1, state_entered_at = ?2 WHERE memory_id = ?3",
+            params![new_state, now.to_rfc3339(), memory_id],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// Record access to memory (updates state)
+    pub fn record_memory_access(&self, memory_id: &str) -> Result<()> {
+        let now = self.now();
+
+        let writer = self.lock_writer()?;
+
+        // Check if state exists (writer can read too)
+        let exists: bool = writer.query_row(
+            "SELECT EXISTS(SELECT 1 FROM memory_states WHERE memory_id = ?1)",
+            params![memory_id],
+            |row| row.get(0),
+        )?;
+
+        if exists {
+            writer.execute(
+                "UPDATE memory_states SET
+                    last_access = ?1,
+                    access_count = access_count + 1,
+                    state = 'active',
+                    state_entered_at = CASE WHEN state != 'active' THEN ?1 ELSE state_entered_at END
+                 WHERE memory_id = ?2",
+                params![now.to_rfc3339(), memory_id],
+            )?;
+        } else {
+            writer.execute(
+                "INSERT INTO memory_states (memory_id, state, last_access, access_count, state_entered_at)
+                 VALUES (?1, 'active', ?2, 1, ?2)",
+                params![memory_id, now.to_rfc3339()],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Bring a Silent or Unavailable memory back to Active on explicit access
+    ///
+    /// Records the access either way and returns whether the memory was
+    /// reactivated; the transition is logged in `state_transitions`.
+    pub fn reactivate(&self, id: &str) -> Result<bool> {
+        if self.get_node(id)?.is_none() {
+            return Err(StorageError::NotFound(id.to_string()));
+        }
+        let previous = self
+            .get_memory_state(id)?
+            .map(|record| MemoryState::parse_name(&record.state));
+        let reactivated = matches!(previous, Some(MemoryState::Silent | MemoryState::Unavailable));
+        if let Some(from) = previous.filter(|_| reactivated) {
+            self.record_state_transition(id, from, MemoryState::Active, &StateTransitionReason::Access)?;
+        }
+        self.record_memory_access(id)?;
+        Ok(reactivated)
+    }
+
+    /// Advance the lifecycle state of every memory in a namespace (or all
+    /// with `None`), persisting the states and their transitions
+    ///
+    /// Memories without a stored state start from their retention strength.
+    /// Unavailable is reserved for suppression, so even faint memories start
+    /// no lower than Silent. Returns the number of transitions applied.
+    pub(crate) fn update_memory_states_in(&self, namespace: Option<&str>) -> Result<usize> {
+        let service = StateUpdateService::new().with_clock(self.clock());
+        let now = self.now();
+        let mut transitions = 0;
+        let filter = NodeFilter {
+            namespace: namespace.map(str::to_string),
+            ..Default::default()
+        };
+        self.for_each_node(&filter, |node| {
+            let stored = self.get_memory_state(&node.id)?;
+            let mut lifecycle = MemoryLifecycle::new();
+            match &stored {
+                Some(record) => {
+                    lifecycle.state = MemoryState::parse_name(&record.state);
+                    lifecycle.last_access = record.last_access.max(node.last_accessed);
+                    lifecycle.access_count = record.access_count.max(0) as u32;
+                    lifecycle.state_entered_at = record.state_entered_at;
+                    lifecycle.suppression_until = record.suppression_until;
+                    lifecycle.suppressed_by = record.suppressed_by.clone();
+                }
+                None => {
+                    lifecycle.last_access = node.last_accessed;
+                    lifecycle.state_entered_at = now;
+                    lifecycle.access_count = node.reps.max(0) as u32;
+                    lifecycle.state = if node.retention_strength > 0.7 {
+                        MemoryState::Active
+                    } else if node.retention_strength > 0.3 {
+                        MemoryState::Dormant
+                    } else {
+                        MemoryState::Silent
+                    };
+                }
+            }
+
+            // Memories recalled since the last pass are active again unless suppressed
+            let mut applied = Vec::new();
+            let suppressed =
+                lifecycle.state == MemoryState::Unavailable && !lifecycle.is_suppression_expired_at(now);
+            if let Some(record) = &stored
+                && node.last_accessed > record.last_access
+                && lifecycle.state != MemoryState::Active
+                && !suppressed
+            {
+                applied.push(StateTransition::at(
+                    lifecycle.state,
+                    MemoryState::Active,
+                    StateTransitionReason::Access,
+                    now,
+                ));
+                lifecycle.transition_to_at(MemoryState::Active, StateTransitionReason::Access, now);
+            }
+            applied.extend(service.update_lifecycle(&mut lifecycle));
+            if stored.is_some() && applied.is_empty() {
+                return Ok(());
+            }
+            self.save_memory_state(&MemoryStateRecord {
+                memory_id: node.id.clone(),
+                state: lifecycle.state.as_str().to_string(),
+                last_access: lifecycle.last_access,
+                access_count: lifecycle.access_count as i32,
+                state_entered_at: lifecycle.state_entered_at,
+                suppression_until: lifecycle.suppression_until,
+                suppressed_by: lifecycle.suppressed_by.clone(),
+            })?;
+            for transition in &applied {
+                self.record_state_transition(
+                    &node.id,
+                    transition.from_state,
+                    transition.to_state,
+                    &transition.reason,
+                )?;
+            }
+            transitions += applied.len();
+            Ok(())
+        })?;
+        Ok(transitions)
+    }
+
+    /// Append a state change to the `state_transitions` audit trail
+    fn record_state_transition(
+        &self,
+        id: &str,
+        from: MemoryState,
+        to: MemoryState,
+        reason: &StateTransitionReason,
+    ) -> Result<()> {
+        let writer = self.lock_writer()?;
+        writer.execute(
+            "INSERT INTO state_transitions (memory_id, from_state, to_state, reason_type, reason_data, timestamp)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                id,
+                from.as_str(),
+                to.as_str(),
+                reason.as_str(),
+                reason.description(),
+                self.now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// IDs of memories currently blocked from retrieval
+    ///
+    /// Unavailable memories whose suppression has expired are left out, so
+    /// they resurface before the next consolidation resolves them.
+    fn unavailable_memory_ids(&self) -> Result<HashSet<String>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT memory_id FROM memory_states
+             WHERE state = 'unavailable' AND (suppression_until IS NULL OR suppression_until > ?1)",
+        )?;
+        let ids = stmt
+            .query_map(params![self.now().to_rfc3339()], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<HashSet<_>>>()?;
+        Ok(ids)
+    }
+
+    fn row_to_memory_state(row: &rusqlite::Row) -> rusqlite::Result<MemoryStateRecord> {
+        let suppressed_json: String = row.get("suppressed_by")?;
+        let suppressed_by: Vec<String> = serde_json::from_str(&suppressed_json).unwrap_or_default();
+
+        let parse_opt_dt = |s: Option<String>| -> Option<DateTime<Utc>> {
+            s.and_then(|v| DateTime::parse_from_rfc3339(&v).ok().map(|dt| dt.with_timezone(&Utc)))
+        };
+
+        Ok(MemoryStateRecord {
+            memory_id: row.get("memory_id")?,
+            state: row.get("state")?,
+            last_access: DateTime::parse_from_rfc3339(&row.get::<_, String>("last_access")?)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            access_count: row.get("access_count").unwrap_or(1),
+            state_entered_at: DateTime::parse_from_rfc3339(&row.get::<_, String>("state_entered_at")?)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            suppression_until: parse_opt_dt(row.get("suppression_until").ok().flatten()),
+            suppressed_by,
+        })
+    }
+
+    // ========================================================================
+    // CONSOLIDATION HISTORY
+    // ========================================================================
+
+    /// Save consolidation history record
+    pub fn save_consolidation_history(&self, record: &ConsolidationHistoryRecord) -> Result<i64> {
+        let writer = self.lock_writer()?;
+        writer.execute(
+            "INSERT INTO consolidation_history (
+                completed_at, duration_ms, memories_replayed, connections_found,
+                connections_strengthened, connections_pruned, insights_generated,
+                step_timings, step_errors, skipped_reason, stopped_reason, steps_not_run
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                record.completed_at.to_rfc3339(),
+                record.duration_ms,
+                record.memories_replayed,
+                record.connections_found,
+                record.connections_strengthened,
+                record.connections_pruned,
+                record.insights_generated,
+                serde_json::to_string(&record.step_timings).unwrap_or_else(|_| "[]".into()),
+                serde_json::to_string(&record.step_errors).unwrap_or_else(|_| "[]".into()),
+                record.skipped_reason,
+                record.stopped_reason,
+                serde_json::to_string(&record.steps_not_run).unwrap_or_else(|_| "[]".into()),
+            ],
+        )?;
+        Ok(writer.last_insert_rowid())
+    }
+
+    /// Decode a JSON array column, empty when NULL or malformed
+    fn json_column<T: serde::de::DeserializeOwned + Default>(row: &rusqlite::Row, column: &str) -> T {
+        row.get::<_, Option<String>>(column)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Get last consolidation timestamp
+    pub fn get_last_consolidation(&self) -> Result<Option<DateTime<Utc>>> {
+        let reader = self.lock_reader()?;
+        let result: Option<String> = reader.query_row(
+            "SELECT MAX(completed_at) FROM consolidation_history
+             WHERE skipped_reason IS NULL AND stopped_reason IS NULL",
+            [],
+            |row| row.get(0),
+        ).ok().flatten();
+
+        Ok(result.and_then(|s| {
+            DateTime::parse_from_rfc3339(&s).ok().map(|dt| dt.with_timezone(&Utc))
+        }))
+    }
+
+    /// Get consolidation history
+    pub fn get_consolidation_history(&self, limit: i32) -> Result<Vec<ConsolidationHistoryRecord>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT * FROM consolidation_history ORDER BY completed_at DESC LIMIT ?1"
+        )?;
+
+        let rows = stmt.query_map(params![limit], |row| {
+            Ok(ConsolidationHistoryRecord {
+                id: row.get("id")?,
+                completed_at: DateTime::parse_from_rfc3339(&row.get::<_, String>("completed_at")?)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .unwrap_or_else(|_| self.now()),
+                duration_ms: row.get("duration_ms")?,
+                memories_replayed: row.get("memories_replayed").unwrap_or(0),
+                connections_found: row.get("connections_found").unwrap_or(0),
+                connections_strengthened: row.get("connections_strengthened").unwrap_or(0),
+                connections_pruned: row.get("connections_pruned").unwrap_or(0),
+                insights_generated: row.get("insights_generated").unwrap_or(0),
+                step_timings: Self::json_column(row, "step_timings"),
+                step_errors: Self::json_column(row, "step_errors"),
+                skipped_reason: row.get("skipped_reason").unwrap_or(None),
+                stopped_reason: row.get("stopped_reason").unwrap_or(None),
+                steps_not_run: Self::json_column(row, "steps_not_run"),
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    // ========================================================================
+    // DREAM HISTORY PERSISTENCE
+    // ========================================================================
+
+    /// Save a dream history record
+    pub fn save_dream_history(&self, record: &DreamHistoryRecord) -> Result<i64> {
+        let writer = self.lock_writer()?;
+        writer.execute(
+            "INSERT INTO dream_history (
+                dreamed_at, duration_ms, memories_replayed, connections_found,
+                insights_generated, memories_strengthened, memories_compressed,
+                phase_nrem1_ms, phase_nrem3_ms, phase_rem_ms, phase_integration_ms,
+                summaries_generated, emotional_memories_processed, creative_connections_found
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            params![
+                record.dreamed_at.to_rfc3339(),
+                record.duration_ms,
+                record.memories_replayed,
+                record.connections_found,
+                record.insights_generated,
+                record.memories_strengthened,
+                record.memories_compressed,
+                record.phase_nrem1_ms,
+                record.phase_nrem3_ms,
+                record.phase_rem_ms,
+                record.phase_integration_ms,
+                record.summaries_generated,
+                record.emotional_memories_processed,
+                record.creative_connections_found,
+            ],
+        )?;
+        Ok(writer.last_insert_rowid())
+    }
+
+    /// Run the 4-phase dream cycle (NREM1, NREM3, REM, Integration)
+    ///
+    /// Replays waking-tagged memories first, filled up with the most recent
+    /// ones, then saves the validated insights and creative connections,
+    /// records per-phase timings in `dream_history` and clears the waking
+    /// tags of the replayed memories.
+    pub fn run_dream_cycle(&self) -> Result<FourPhaseDreamResult> {
+        self.run_dream_cycle_in(None)
+    }
+
+    /// Dream cycle over one namespace's memories, or all of them with `None`
+    pub(crate) fn run_dream_cycle_in(&self, namespace: Option<&str>) -> Result<FourPhaseDreamResult> {
+        let limit = DREAM_CYCLE_MEMORIES;
+        let tagged_target = limit * 7 / 10;
+        let mut memories: Vec<KnowledgeNode> = self
+            .get_waking_tagged_memories(limit as i32)?
+            .into_iter()
+            .filter(|n| namespace.is_none() || n.namespace.as_deref() == namespace)
+            .take(tagged_target)
+            .collect();
+        let tagged_ids: Vec<String> = memories.iter().map(|n| n.id.clone()).collect();
+        let seen: HashSet<String> = tagged_ids.iter().cloned().collect();
+        for node in self.get_all_nodes_in(namespace, limit as i32, 0)? {
+            if memories.len() >= limit {
+                break;
+            }
+            if !seen.contains(&node.id) {
+                memories.push(node);
+            }
+        }
+
+        let result = DreamEngine::new().run(
+            &memories,
+            &mut EmotionalMemory::new(),
+            &ImportanceSignals::new(),
+            &mut SynapticTaggingSystem::new().with_clock(self.clock()),
+        );
+
+        let now = self.now();
+        for insight in &result.insights {
+            let record = InsightRecord {
+                id: Uuid::new_v4().to_string(),
+                insight: insight.insight.clone(),
+                source_memories: insight.source_memory_ids.clone(),
+                confidence: insight.confidence,
+                novelty_score: insight.novelty,
+                insight_type: insight.insight_type.clone(),
+                generated_at: now,
+                tags: vec![],
+                feedback: None,
+                applied_count: 0,
+            };
+            self.save_insight_in(&record, namespace)?;
+        }
+        for connection in &result.creative_connections {
+            self.save_connection(&ConnectionRecord {
+                source_id: connection.memory_a_id.clone(),
+                target_id: connection.memory_b_id.clone(),
+                strength: connection.confidence,
+                link_type: connection.connection_type.as_link_type().to_string(),
+                created_at: now,
+                last_activated: now,
+                activation_count: 1,
+            })?;
+        }
+
+        let phase_ms = |phase: DreamPhase| {
+            result.phases.iter().find(|p| p.phase == phase).map(|p| p.duration_ms as i64)
+        };
+        self.save_dream_history(&DreamHistoryRecord {
+            dreamed_at: now,
+            duration_ms: result.total_duration_ms as i64,
+            memories_replayed: result.memories_replayed as i32,
+            connections_found: result.creative_connections.len() as i32,
+            insights_generated: result.insights.len() as i32,
+            memories_strengthened: result.memories_strengthened as i32,
+            memories_compressed: 0,
+            phase_nrem1_ms: phase_ms(DreamPhase::Nrem1),
+            phase_nrem3_ms: phase_ms(DreamPhase::Nrem3),
+            phase_rem_ms: phase_ms(DreamPhase::Rem),
+            phase_integration_ms: phase_ms(DreamPhase::Integration),
+            summaries_generated: None,
+            emotional_memories_processed: Some(result.emotional_processed as i32),
+            creative_connections_found: Some(result.creative_connections.len() as i32),
+        })?;
+
+        {
+            let writer = self.lock_writer()?;
+            for id in &tagged_ids {
+                writer.execute(
+                    "UPDATE knowledge_nodes SET waking_tag = FALSE, waking_tag_at = NULL WHERE id = ?1",
+                    params![id],
+                )?;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Get last dream timestamp
+    pub fn get_last_dream(&self) -> Result<Option<DateTime<Utc>>> {
+        let reader = self.lock_reader()?;
+        let result: Option<String> = reader.query_row(
+            "SELECT MAX(dreamed_at) FROM dream_history",
+            [],
+            |row| row.get(0),
+        ).ok().flatten();
+
+        Ok(result.and_then(|s| {
+            DateTime::parse_from_rfc3339(&s).ok().map(|dt| dt.with_timezone(&Utc))
+        }))
+    }
+
+    /// Count memories created since a given timestamp
+    pub fn count_memories_since(&self, since: DateTime<Utc>) -> Result<i64> {
+        let reader = self.lock_reader()?;
+        let count: i64 = reader.query_row(
+            "SELECT COUNT(*) FROM knowledge_nodes WHERE created_at >= ?1",
+            params![since.to_rfc3339()],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    /// Get last backup timestamp by scanning the backups directory.
+    /// Parses `vestige-YYYYMMDD-HHMMSS.db` filenames.
+    pub fn get_last_backup_timestamp() -> Option<DateTime<Utc>> {
+        Self::list_backups().ok()?.first().map(|b| b.created_at)
+    }
+
+    // ========================================================================
+    // CROSS-PROJECT PATTERNS PERSISTENCE
+    // ========================================================================
+
+    /// Learn universal patterns from memories tagged `codebase:<name>` and
+    /// merge them into the stored set. Returns the number of patterns updated.
+    pub(crate) fn learn_cross_project_patterns(&self) -> Result<usize> {
+        use crate::advanced::cross_project::{MemoryForLearning, PatternCategory};
+
+        let memories: Vec<MemoryForLearning> = {
+            let reader = self.lock_reader()?;
+            let mut stmt = reader.prepare(
+                "SELECT id, content, tags FROM knowledge_nodes
+                 WHERE tags LIKE '%\"codebase:%'
+                 ORDER BY created_at DESC
+                 LIMIT 2000",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?;
+
+            rows.flatten()
+                .filter_map(|(id, content, tags_json)| {
+                    let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
+                    let project_name = tags
+                        .iter()
+                        .find_map(|t| t.strip_prefix("codebase:"))?
+                        .to_string();
+                    let category = PatternCategory::infer(&content, &tags);
+                    Some(MemoryForLearning {
+                        id,
+                        content,
+                        project_name,
+                        category: Some(category),
+                    })
+                })
+                .collect()
+        };
+
+        let learner = crate::advanced::cross_project::CrossProjectLearner::new();
+        learner.learn_from_memories(&memories);
+
+        let patterns = learner.get_all_patterns();
+        for pattern in &patterns {
+            self.save_universal_pattern(pattern, &learner.get_supporting_memories(&pattern.id))?;
+        }
+        Ok(patterns.len())
+    }
+
+    /// Save a universal pattern, merging with any stored version
+    ///
+    /// Patterns are deduplicated by id (the learner's category/keyword
+    /// signature). Contributing projects and memories are unioned, `first_seen`
+    /// and the outcome-tracked success rate are kept, confidence grows with the
+    /// number of projects the pattern has been seen in, and `support` counts the
+    /// distinct memories that have evidenced it.
+    pub fn save_universal_pattern(
+        &self,
+        pattern: &UniversalPattern,
+        supporting_memories: &[String],
+    ) -> Result<()> {
+        let writer = self.lock_writer()?;
+
+        let existing = writer
+            .query_row(
+                "SELECT * FROM universal_patterns WHERE id = ?1",
+                params![pattern.id],
+                |row| {
+                    let memories_json: String = row.get("supporting_memories")?;
+                    Ok((
+                        Self::row_to_universal_pattern(row)?,
+                        serde_json::from_str::<Vec<String>>(&memories_json).unwrap_or_default(),
+                    ))
+                },
+            )
+            .optional()?;
+
+        let mut merged = pattern.clone();
+        let mut memories = supporting_memories.to_vec();
+        if let Some((stored, stored_memories)) = existing {
+            for project in stored.projects_seen_in {
+                if !merged.projects_seen_in.contains(&project) {
+                    merged.projects_seen_in.push(project);
+                }
+            }
+            for id in stored_memories {
+                if !memories.contains(&id) {
+                    memories.push(id);
+                }
+            }
+            merged.first_seen = stored.first_seen.min(merged.first_seen);
+            merged.success_rate = stored.success_rate;
+            merged.application_count = stored.application_count;
+        }
+        merged.projects_seen_in.sort();
+        memories.sort();
+        merged.confidence = (0.3 + 0.15 * merged.projects_seen_in.len() as f64).min(0.95);
+
+        let pattern_json = serde_json::to_string(&merged.pattern).unwrap_or_else(|_| "{}".to_string());
+        let projects_json = serde_json::to_string(&merged.projects_seen_in).unwrap_or_else(|_| "[]".to_string());
+        let memories_json = serde_json::to_string(&memories).unwrap_or_else(|_| "[]".to_string());
+
+        writer.execute(
+            "INSERT OR REPLACE INTO universal_patterns (
+                id, name, category, pattern_json, projects_seen_in, supporting_memories,
+                success_rate, applicability, confidence, application_count, first_seen, last_seen,
+                support
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                merged.id,
+                merged.pattern.name,
+                merged.pattern.category.as_str(),
+                pattern_json,
+                projects_json,
+                memories_json,
+                merged.success_rate,
+                merged.applicability,
+                merged.confidence,
+                merged.application_count,
+                merged.first_seen.to_rfc3339(),
+                merged.last_seen.to_rfc3339(),
+                memories.len() as i64,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get stored universal patterns evidenced by at least `min_support`
+    /// distinct memories, most confident first
+    pub fn get_universal_patterns(&self, min_support: u32) -> Result<Vec<UniversalPattern>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT * FROM universal_patterns WHERE support >= ?1
+             ORDER BY confidence DESC, last_seen DESC"
+        )?;
+
+        let rows = stmt.query_map(params![min_support], Self::row_to_universal_pattern)?;
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    /// Find stored cross-project patterns that apply to a project
+    ///
+    /// Patterns the project already contributed to are only returned when one
+    /// of their triggers matches; otherwise they are already known there.
+    pub fn get_applicable_knowledge(&self, project: &ProjectContext) -> Result<Vec<ApplicableKnowledge>> {
+        let supporting: std::collections::HashMap<String, Vec<String>> = {
+            let reader = self.lock_reader()?;
+            let mut stmt = reader.prepare("SELECT id, supporting_memories FROM universal_patterns")?;
+            let rows = stmt.query_map([], |row| {
+                let json: String = row.get(1)?;
+                Ok((row.get::<_, String>(0)?, serde_json::from_str(&json).unwrap_or_default()))
+            })?;
+            rows.flatten().collect()
+        };
+
+        let learner = crate::advanced::cross_project::CrossProjectLearner::new();
+        for pattern in self.get_universal_patterns(0)? {
+            learner.add_pattern(pattern);
+        }
+
+        let mut applicable = learner.detect_applicable(project);
+        for knowledge in &mut applicable {
+            if let Some(ids) = supporting.get(&knowledge.pattern.id) {
+                knowledge.supporting_memories = ids.clone();
+            }
+        }
+        Ok(applicable)
+    }
+
+    /// Cross-project knowledge that applies to a project; shorthand for
+    /// `get_applicable_knowledge`
+    pub fn applicable_to(&self, project: &ProjectContext) -> Result<Vec<ApplicableKnowledge>> {
+        self.get_applicable_knowledge(project)
+    }
+
+    fn row_to_universal_pattern(row: &rusqlite::Row) -> rusqlite::Result<UniversalPattern> {
+        let pattern_json: String = row.get("pattern_json")?;
+        let pattern = serde_json::from_str(&pattern_json).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+        })?;
+        let projects_json: String = row.get("projects_seen_in")?;
+        let parse_time = |s: String| {
+            DateTime::parse_from_rfc3339(&s)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now())
+        };
+
+        Ok(UniversalPattern {
             id: row.get("id")?,
-            content: row.get("content")?,
-            trigger_type: row.get("trigger_type")?,
-            trigger_data: row.get("trigger_data")?,
-            priority: row.get("priority")?,
-            status: row.get("status")?,
-            created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>("created_at")?)
+            pattern,
+            projects_seen_in: serde_json::from_str(&projects_json).unwrap_or_default(),
+            success_rate: row.get("success_rate")?,
+            applicability: row.get("applicability")?,
+            confidence: row.get("confidence")?,
+            first_seen: parse_time(row.get("first_seen")?),
+            last_seen: parse_time(row.get("last_seen")?),
+            application_count: row.get("application_count")?,
+        })
+    }
+
+    // ========================================================================
+    // MEMORY CHAINS (Reasoning)
+    // ========================================================================
+
+    /// Build a reasoning chain between two memories
+    ///
+    /// The graph is loaded outward from `from_id` for up to `max_hops` hops,
+    /// following persisted connections in both directions plus close vector
+    /// neighbors. Returns `None` when `to_id` isn't reachable within the limit.
+    pub fn build_reasoning_chain(
+        &self,
+        from_id: &str,
+        to_id: &str,
+        max_hops: usize,
+    ) -> Result<Option<ReasoningChain>> {
+        for id in [from_id, to_id] {
+            if self.get_node(id)?.is_none() {
+                return Err(StorageError::NotFound(id.to_string()));
+            }
+        }
+
+        Ok(self.chain_graph(from_id, max_hops)?.build_chain(from_id, to_id))
+    }
+
+    /// Build a chain of at most `max_len` connections between two memories
+    ///
+    /// Same as `build_reasoning_chain`: persisted `memory_connections` are
+    /// followed with close vector neighbors as fallback edges, and `None`
+    /// means no path within `max_len` exists. The chain builder searches at
+    /// most 10 hops, so a larger `max_len` behaves like 10.
+    pub fn build_chain(
+        &self,
+        from_id: &str,
+        to_id: &str,
+        max_len: usize,
+    ) -> Result<Option<ReasoningChain>> {
+        self.build_reasoning_chain(from_id, to_id, max_len)
+    }
+
+    /// Load the graph around `from_id` into a chain builder, up to
+    /// `max_hops` hops and `MAX_CHAIN_GRAPH_NODES` memories
+    fn chain_graph(&self, from_id: &str, max_hops: usize) -> Result<MemoryChainBuilder> {
+        let mut nodes: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+        let mut edges: Vec<ChainConnection> = Vec::new();
+        let mut frontier = vec![from_id.to_string()];
+
+        'hops: for _ in 0..max_hops {
+            let mut next = Vec::new();
+            for id in std::mem::take(&mut frontier) {
+                if nodes.contains_key(&id) {
+                    continue;
+                }
+                let Some(node) = self.get_node(&id)? else {
+                    continue;
+                };
+                nodes.insert(id.clone(), node.content.chars().take(100).collect());
+                if nodes.len() >= MAX_CHAIN_GRAPH_NODES {
+                    break 'hops;
+                }
+
+                for record in self.get_connections_for_memory(&id)? {
+                    let other = if record.source_id == id {
+                        record.target_id.clone()
+                    } else {
+                        record.source_id.clone()
+                    };
+                    edges.push(ChainConnection {
+                        from_id: id.clone(),
+                        to_id: other.clone(),
+                        connection_type: ConnectionType::from_link_type(&record.link_type),
+                        strength: record.strength,
+                        created_at: record.created_at,
+                    });
+                    next.push(other);
+                }
+
+                for (neighbor_id, similarity) in self.close_vector_neighbors(&id)? {
+                    edges.push(ChainConnection {
+                        from_id: id.clone(),
+                        to_id: neighbor_id.clone(),
+                        connection_type: ConnectionType::SemanticSimilarity,
+                        strength: similarity as f64,
+                        created_at: self.now(),
+                    });
+                    next.push(neighbor_id);
+                }
+            }
+            frontier = next;
+        }
+
+        // Memories on the last hop still need a node so they can end a path
+        for id in frontier {
+            if nodes.len() >= MAX_CHAIN_GRAPH_NODES || nodes.contains_key(&id) {
+                continue;
+            }
+            if let Some(node) = self.get_node(&id)? {
+                nodes.insert(id, node.content.chars().take(100).collect());
+            }
+        }
+
+        let mut builder = MemoryChainBuilder::new().with_max_depth(max_hops);
+        for (id, content_preview) in nodes {
+            builder.add_memory(MemoryNode {
+                id,
+                content_preview,
+                tags: vec![],
+                connections: vec![],
+            });
+        }
+        for edge in edges {
+            builder.add_connection(edge);
+        }
+        Ok(builder)
+    }
+
+    /// Close vector neighbors of a memory, for graph traversal
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn close_vector_neighbors(&self, id: &str) -> Result<Vec<(String, f32)>> {
+        let Some(embedding) = self.get_node_embedding(id)? else {
+            return Ok(vec![]);
+        };
+        let index = self
+            .vector_index
+            .lock()
+            .map_err(|_| StorageError::LockPoisoned("Vector index".into()))?;
+        Ok(index
+            .search(&embedding, 6)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(neighbor_id, similarity)| neighbor_id != id && *similarity >= 0.7)
+            .collect())
+    }
+
+    #[cfg(not(all(feature = "embeddings", feature = "vector-search")))]
+    fn close_vector_neighbors(&self, _id: &str) -> Result<Vec<(String, f32)>> {
+        Ok(vec![])
+    }
+
+    /// Memories associatively related to one memory, by spreading activation
+    ///
+    /// Activation is seeded at `id` and spreads over persisted connections and
+    /// close vector neighbors for `hops` levels, decaying at each hop. Memories
+    /// reached along several paths accumulate activation, so results are
+    /// ranked by how strongly the whole network points at them.
+    pub fn get_related(&self, id: &str, hops: u32, limit: usize) -> Result<Vec<ActivatedMemory>> {
+        const MAX_RELATED_GRAPH_NODES: usize = 500;
+
+        if self.get_node(id)?.is_none() {
+            return Err(StorageError::NotFound(id.to_string()));
+        }
+        let hops = hops.clamp(1, 5);
+
+        let mut network = ActivationNetwork::with_config(ActivationConfig {
+            max_hops: hops,
+            ..Default::default()
+        });
+        network.add_node(id.to_string());
+
+        let mut seen: HashSet<String> = HashSet::from([id.to_string()]);
+        let mut frontier = vec![id.to_string()];
+        for _ in 0..hops {
+            let mut next = Vec::new();
+            for current in &frontier {
+                let mut neighbors: Vec<(String, LinkType, f64)> = self
+                    .get_connections_for_memory(current)?
+                    .into_iter()
+                    .map(|c| {
+                        let other = if c.source_id == *current { c.target_id } else { c.source_id };
+                        (other, LinkType::from_link_type(&c.link_type), c.strength)
+                    })
+                    .collect();
+                neighbors.extend(
+                    self.close_vector_neighbors(current)?
+                        .into_iter()
+                        .map(|(other, similarity)| (other, LinkType::Semantic, similarity as f64)),
+                );
+
+                for (other, link_type, strength) in neighbors {
+                    if seen.len() >= MAX_RELATED_GRAPH_NODES && !seen.contains(&other) {
+                        continue;
+                    }
+                    // Associations are symmetric: activation may flow either way
+                    network.add_edge(current.clone(), other.clone(), link_type, strength);
+                    network.add_edge(other.clone(), current.clone(), link_type, strength);
+                    if seen.insert(other.clone()) {
+                        next.push(other);
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        // Sum activation arriving over every path; keep the strongest path's details
+        let mut by_memory: HashMap<String, ActivatedMemory> = HashMap::new();
+        for activated in network.activate(id, 1.0) {
+            if activated.memory_id == id {
+                continue;
+            }
+            match by_memory.get_mut(&activated.memory_id) {
+                Some(existing) => existing.activation += activated.activation,
+                None => {
+                    by_memory.insert(activated.memory_id.clone(), activated);
+                }
+            }
+        }
+
+        let mut related: Vec<ActivatedMemory> = by_memory
+            .into_values()
+            .map(|mut m| {
+                m.activation = m.activation.min(1.0);
+                m
+            })
+            .collect();
+        related.sort_by(|a, b| {
+            b.activation
+                .total_cmp(&a.activation)
+                .then_with(|| a.distance.cmp(&b.distance))
+        });
+        related.truncate(limit);
+        Ok(related)
+    }
+
+    /// Save a reasoning chain with its ordered steps, returning the chain ID
+    pub fn save_reasoning_chain(&self, chain: &ReasoningChain) -> Result<String> {
+        let id = Uuid::new_v4().to_string();
+        let mut writer = self.lock_writer()?;
+        let tx = writer.transaction()?;
+
+        tx.execute(
+            "INSERT INTO reasoning_chains (
+                id, from_id, to_id, confidence, total_hops, explanation, created_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                id,
+                chain.from,
+                chain.to,
+                chain.confidence,
+                chain.total_hops as i64,
+                chain.explanation,
+                self.now().to_rfc3339(),
+            ],
+        )?;
+
+        for (index, step) in chain.steps.iter().enumerate() {
+            let connection_type = serde_json::to_string(&step.connection_type)
+                .unwrap_or_else(|_| "\"SemanticSimilarity\"".to_string());
+            tx.execute(
+                "INSERT INTO reasoning_chain_steps (
+                    chain_id, step_index, memory_id, memory_preview,
+                    connection_type, connection_strength, reasoning
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    id,
+                    index as i64,
+                    step.memory_id,
+                    step.memory_preview,
+                    connection_type,
+                    step.connection_strength,
+                    step.reasoning,
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// Get a saved reasoning chain by ID
+    pub fn get_chain(&self, id: &str) -> Result<Option<ReasoningChainRecord>> {
+        let reader = self.lock_reader()?;
+
+        let header = reader
+            .query_row(
+                "SELECT * FROM reasoning_chains WHERE id = ?1",
+                params![id],
+                |row| {
+                    Ok((
+                        row.get::<_, String>("from_id")?,
+                        row.get::<_, String>("to_id")?,
+                        row.get::<_, f64>("confidence")?,
+                        row.get::<_, i64>("total_hops")?,
+                        row.get::<_, String>("explanation")?,
+                        row.get::<_, String>("created_at")?,
+                    ))
+                },
+            )
+            .optional()?;
+        let Some((from, to, confidence, total_hops, explanation, created_at)) = header else {
+            return Ok(None);
+        };
+
+        let mut stmt = reader.prepare(
+            "SELECT * FROM reasoning_chain_steps WHERE chain_id = ?1 ORDER BY step_index"
+        )?;
+        let steps = stmt
+            .query_map(params![id], |row| {
+                let connection_type: String = row.get("connection_type")?;
+                Ok(ChainStep {
+                    memory_id: row.get("memory_id")?,
+                    memory_preview: row.get("memory_preview")?,
+                    connection_type: serde_json::from_str(&connection_type)
+                        .unwrap_or(ConnectionType::Custom(connection_type)),
+                    connection_strength: row.get("connection_strength")?,
+                    reasoning: row.get("reasoning")?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(Some(ReasoningChainRecord {
+            id: id.to_string(),
+            chain: ReasoningChain {
+                from,
+                to,
+                steps,
+                confidence,
+                total_hops: total_hops as usize,
+                explanation,
+            },
+            created_at: DateTime::parse_from_rfc3339(&created_at)
                 .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now()),
-            deadline: parse_opt_dt(row.get("deadline").ok().flatten()),
-            fulfilled_at: parse_opt_dt(row.get("fulfilled_at").ok().flatten()),
-            reminder_count: row.get("reminder_count").unwrap_or(0),
-            last_reminded_at: parse_opt_dt(row.get("last_reminded_at").ok().flatten()),
-            notes: row.get("notes").ok().flatten(),
-            tags,
-            related_memories: related,
-            snoozed_until: parse_opt_dt(row.get("snoozed_until").ok().flatten()),
-            source_type: row.get("source_type").unwrap_or_else(|_| "api".to_string()),
-            source_data: row.get("source_data").ok().flatten(),
+                .unwrap_or_else(|_| self.now()),
+        }))
+    }
+
+    /// Get saved reasoning chains passing through a memory, newest first
+    pub fn get_chains_for_memory(&self, memory_id: &str) -> Result<Vec<ReasoningChainRecord>> {
+        let chain_ids: Vec<String> = {
+            let reader = self.lock_reader()?;
+            let mut stmt = reader.prepare(
+                "SELECT DISTINCT c.id, c.created_at FROM reasoning_chains c
+                 JOIN reasoning_chain_steps s ON s.chain_id = c.id
+                 WHERE s.memory_id = ?1
+                 ORDER BY c.created_at DESC"
+            )?;
+            stmt.query_map(params![memory_id], |row| row.get(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()?
+        };
+
+        let mut chains = Vec::with_capacity(chain_ids.len());
+        for id in chain_ids {
+            if let Some(chain) = self.get_chain(&id)? {
+                chains.push(chain);
+            }
+        }
+        Ok(chains)
+    }
+
+    // ========================================================================
+    // USAGE HISTORY (Speculative Retrieval)
+    // ========================================================================
+
+    /// Record a tool invocation, keeping only the most recent 10,000
+    pub fn record_usage(&self, record: &UsageRecord) -> Result<()> {
+        const MAX_USAGE_HISTORY: i64 = 10_000;
+
+        let writer = self.lock_writer()?;
+        writer.execute(
+            "INSERT INTO usage_history (tool, query, memory_ids, project, hour_of_day, recorded_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                record.tool,
+                record.query,
+                serde_json::to_string(&record.memory_ids).unwrap_or_else(|_| "[]".to_string()),
+                record.project,
+                record.recorded_at.hour(),
+                record.recorded_at.to_rfc3339(),
+            ],
+        )?;
+        let last_id = writer.last_insert_rowid();
+        writer.execute(
+            "DELETE FROM usage_history WHERE id <= ?1",
+            params![last_id - MAX_USAGE_HISTORY],
+        )?;
+        Ok(())
+    }
+
+    /// Get recorded tool invocations, newest first
+    pub fn get_usage_history(&self, limit: i32) -> Result<Vec<UsageRecord>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT * FROM usage_history ORDER BY id DESC LIMIT ?1"
+        )?;
+
+        let rows = stmt.query_map(params![limit], |row| {
+            let memory_ids: String = row.get("memory_ids")?;
+            Ok(UsageRecord {
+                tool: row.get("tool")?,
+                query: row.get("query")?,
+                memory_ids: serde_json::from_str(&memory_ids).unwrap_or_default(),
+                project: row.get("project")?,
+                recorded_at: DateTime::parse_from_rfc3339(&row.get::<_, String>("recorded_at")?)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .unwrap_or_else(|_| self.now()),
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    // ========================================================================
+    // SPECULATIVE PREFETCH (Access-sequence prediction)
+    // ========================================================================
+
+    /// Predict and warm the memories most likely to be accessed next
+    ///
+    /// Mines `memory_access_log` (up to `context.timestamp`) for directed
+    /// transitions between consecutive accesses less than 30 minutes apart,
+    /// then scores each candidate as the recency-weighted probability that
+    /// it follows the context's recent memories (or, if none are given, the
+    /// last logged access). Predicted memories are warmed softly: a small
+    /// retrieval boost, without touching `last_accessed` or the access log,
+    /// so predictions never feed back into the model.
+    pub fn speculative_prefetch(&self, context: PredictionContext, limit: usize) -> Result<Vec<PredictedMemory>> {
+        const LOG_WINDOW: i64 = 5000;
+        const SESSION_GAP_MINUTES: i64 = 30;
+        const SEED_DECAY: f64 = 0.5;
+        const WARM_BOOST: f64 = 0.01;
+
+        let now = context.timestamp.unwrap_or_else(Utc::now);
+        let mut log: Vec<(String, DateTime<Utc>)> = {
+            let reader = self.lock_reader()?;
+            let mut stmt = reader.prepare(
+                "SELECT node_id, accessed_at FROM memory_access_log
+                 WHERE accessed_at <= ?1
+                 ORDER BY accessed_at DESC, id DESC
+                 LIMIT ?2",
+            )?;
+            let rows = stmt.query_map(params![now.to_rfc3339(), LOG_WINDOW], |row| {
+                Ok((row.get::<_, String>(0)?, Self::parse_timestamp(&row.get::<_, String>(1)?, "accessed_at")?))
+            })?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+        log.reverse();
+
+        // transitions[a][b] = times b was accessed right after a
+        let mut transitions: HashMap<&str, HashMap<&str, u32>> = HashMap::new();
+        for pair in log.windows(2) {
+            let ((from, from_at), (to, to_at)) = (&pair[0], &pair[1]);
+            if from != to && *to_at - *from_at <= Duration::minutes(SESSION_GAP_MINUTES) {
+                *transitions.entry(from).or_default().entry(to).or_default() += 1;
+            }
+        }
+
+        // Most recent seed first
+        let mut seeds: Vec<&str> = context.recent_memory_ids.iter().rev().map(String::as_str).collect();
+        if seeds.is_empty() && let Some((last, _)) = log.last() {
+            seeds.push(last);
+        }
+
+        // candidate -> (weighted probability, strongest seed, count, seed total)
+        let mut scores: HashMap<&str, (f64, &str, u32, u32)> = HashMap::new();
+        let mut weight_total = 0.0;
+        for (rank, seed) in seeds.iter().enumerate() {
+            let weight = SEED_DECAY.powi(rank as i32);
+            weight_total += weight;
+            let Some(next) = transitions.get(seed) else { continue };
+            let total: u32 = next.values().sum();
+            for (candidate, count) in next {
+                let entry = scores.entry(candidate).or_insert((0.0, seed, *count, total));
+                let p = *count as f64 / total as f64;
+                if p > entry.2 as f64 / entry.3 as f64 {
+                    *entry = (entry.0, seed, *count, total);
+                }
+                entry.0 += weight * p;
+            }
+        }
+
+        let mut ranked: Vec<(&str, f64, &str, u32, u32)> = scores
+            .into_iter()
+            .filter(|(candidate, _)| !seeds.contains(candidate))
+            .map(|(candidate, (score, seed, count, total))| (candidate, score / weight_total, seed, count, total))
+            .collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then_with(|| a.0.cmp(b.0)));
+
+        let mut predictions = Vec::new();
+        for (id, confidence, seed, count, total) in ranked {
+            if predictions.len() >= limit {
+                break;
+            }
+            let Some(node) = self.get_node(id)? else { continue };
+            predictions.push(PredictedMemory {
+                memory_id: node.id,
+                content_preview: node.content.chars().take(100).collect(),
+                confidence: confidence.min(1.0),
+                reasoning: format!("Accessed right after {} in {} of {} logged transitions", seed, count, total),
+                trigger: PredictionTrigger::CoAccessPattern { related_memory_id: seed.to_string() },
+                predicted_at: now,
+            });
+        }
+
+        {
+            let writer = self.lock_writer()?;
+            for prediction in &predictions {
+                writer.execute(
+                    "UPDATE knowledge_nodes SET retrieval_strength = MIN(1.0, retrieval_strength + ?1)
+                     WHERE id = ?2",
+                    params![WARM_BOOST * prediction.confidence, prediction.memory_id],
+                )?;
+            }
+        }
+
+        Ok(predictions)
+    }
+
+    // ========================================================================
+    // STATE TRANSITIONS (Audit Trail)
+    // ========================================================================
+
+    /// Get state transitions for a memory
+    pub fn get_state_transitions(&self, memory_id: &str, limit: i32) -> Result<Vec<StateTransitionRecord>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT * FROM state_transitions WHERE memory_id = ?1 ORDER BY timestamp DESC LIMIT ?2"
+        )?;
+
+        let rows = stmt.query_map(params![memory_id, limit], |row| {
+            Ok(StateTransitionRecord {
+                id: row.get("id")?,
+                memory_id: row.get("memory_id")?,
+                from_state: row.get("from_state")?,
+                to_state: row.get("to_state")?,
+                reason_type: row.get("reason_type")?,
+                reason_data: row.get("reason_data").ok().flatten(),
+                timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>("timestamp")?)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .unwrap_or_else(|_| self.now()),
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    /// Create a consistent backup using VACUUM INTO
+    pub fn backup_to(&self, path: &std::path::Path) -> Result<()> {
+        let path_str = path.to_str().ok_or_else(|| {
+            StorageError::InvalidInput { field: "path", reason: "not valid UTF-8".to_string() }
+        })?;
+        // Validate path: reject control characters (except tab) for defense-in-depth
+        if path_str.bytes().any(|b| b < 0x20 && b != b'\t') {
+            return Err(StorageError::InvalidInput {
+                field: "path",
+                reason: "contains control characters".to_string(),
+            });
+        }
+        let reader = self.lock_reader()?;
+        if self.in_memory {
+            // VACUUM INTO would write through the memdb VFS, leaving no file
+            let mut dest = Connection::open(path)?;
+            rusqlite::backup::Backup::new(&reader, &mut dest)?.run_to_completion(
+                256,
+                std::time::Duration::ZERO,
+                None,
+            )?;
+            return Ok(());
+        }
+        // VACUUM INTO doesn't support parameterized queries; escape single quotes
+        reader.execute_batch(&format!("VACUUM INTO '{}'", path_str.replace('\'', "''")))?;
+        Ok(())
+    }
+
+    // ========================================================================
+    // BACKUPS
+    // ========================================================================
+
+    /// Backups in the standard backups directory, newest first
+    pub fn list_backups() -> Result<Vec<BackupInfo>> {
+        match default_backup_dir() {
+            Some(dir) => Ok(list_backups_in(&dir)?),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Back up into `dir` as `vestige-YYYYMMDD-HHMMSS.db` and verify the copy
+    ///
+    /// A backup that fails verification is deleted and the error returned.
+    pub fn create_verified_backup(&self, dir: &Path) -> Result<BackupInfo> {
+        std::fs::create_dir_all(dir)?;
+        let created_at = self.now();
+        let path = dir.join(backup_file_name(created_at));
+        if path.exists() {
+            return Err(StorageError::InvalidInput {
+                field: "path",
+                reason: format!("backup {} already exists", path.display()),
+            });
+        }
+
+        self.backup_to(&path)?;
+        if let Err(e) = self.verify_backup(&path) {
+            let _ = std::fs::remove_file(&path);
+            return Err(e);
+        }
+
+        Ok(BackupInfo {
+            size_bytes: std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+            path,
+            // Second precision, matching what listing parses from the name
+            created_at: created_at.with_nanosecond(0).unwrap_or(created_at),
+        })
+    }
+
+    /// Open a backup read-only under the active key and run `PRAGMA integrity_check`
+    pub fn verify_backup(&self, path: &Path) -> Result<()> {
+        let conn = self.open_backup(path)?;
+        let result: String = conn.query_row("PRAGMA integrity_check", [], |row| row.get(0))?;
+        if result != "ok" {
+            return Err(StorageError::InvalidInput {
+                field: "path",
+                reason: format!("backup {} failed integrity check: {}", path.display(), result),
+            });
+        }
+        conn.query_row("SELECT COUNT(*) FROM knowledge_nodes", [], |row| row.get::<_, i64>(0))?;
+        Self::check_schema_supported(&conn)?;
+        Ok(())
+    }
+
+    /// Fail with `SchemaTooNew` if `conn` was migrated past this build's schema
+    fn check_schema_supported(conn: &Connection) -> Result<()> {
+        let found = super::migrations::get_current_version(conn)?;
+        let supported = super::migrations::latest_version();
+        if found > supported {
+            return Err(StorageError::SchemaTooNew {
+                found,
+                supported,
+                written_by: super::migrations::get_written_by(conn)
+                    .unwrap_or_else(|| "unknown".to_string()),
+            });
+        }
+        Ok(())
+    }
+
+    /// Schema version of the open database
+    pub fn schema_version(&self) -> Result<u32> {
+        let reader = self.lock_reader()?;
+        Ok(super::migrations::get_current_version(&reader)?)
+    }
+
+    /// Read-only connection to a backup, keyed like the live database
+    fn open_backup(&self, path: &Path) -> Result<Connection> {
+        if !path.is_file() {
+            return Err(StorageError::NotFound(path.display().to_string()));
+        }
+        let conn = Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        #[cfg(feature = "encryption")]
+        if let Some(key) = self.encryption_key.lock()
+            .map_err(|_| StorageError::LockPoisoned("Encryption key".into()))?
+            .as_deref()
+        {
+            conn.pragma_update(None, "key", key)?;
+        }
+        Ok(conn)
+    }
+
+    /// Delete backups in `dir` outside the rotation policy, returning their paths
+    pub fn rotate_backups(&self, dir: &Path, config: &BackupConfig) -> Result<Vec<PathBuf>> {
+        let pruned = backups_to_prune(&list_backups_in(dir)?, config, self.now());
+        for path in &pruned {
+            std::fs::remove_file(path)?;
+        }
+        Ok(pruned)
+    }
+
+    /// Replace the live database with a verified backup
+    ///
+    /// The backup is copied page by page into the open database, migrated to
+    /// the current schema, and the FTS5 and vector indexes are rebuilt from
+    /// the restored rows. Query caches and labile windows are dropped.
+    pub fn restore_from_backup(&self, path: &Path) -> Result<()> {
+        self.verify_backup(path)?;
+        let source = self.open_backup(path)?;
+
+        {
+            let mut writer = self.lock_writer()?;
+            rusqlite::backup::Backup::new(&source, &mut writer)?
+                .run_to_completion(256, std::time::Duration::ZERO, None)?;
+            super::migrations::apply_migrations(&writer)?;
+            writer.execute("INSERT INTO knowledge_fts(knowledge_fts) VALUES('rebuild')", [])?;
+        }
+
+        #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+        self.rebuild_vector_index()?;
+        #[cfg(feature = "embeddings")]
+        self.clear_query_cache()?;
+        *self.reconsolidation.lock()
+            .map_err(|_| StorageError::LockPoisoned("Reconsolidation".into()))? =
+            ReconsolidationManager::with_window(Self::labile_window_from_env());
+        Ok(())
+    }
+
+    // ========================================================================
+    // ENCRYPTION (SQLCipher)
+    // ========================================================================
+
+    /// Re-encrypt the database under a new SQLCipher key
+    ///
+    /// A verified backup under the old key is written to the standard backups
+    /// directory first and returned. See `rekey_with_backup_dir`.
+    pub fn rekey(&self, new_key: &str) -> Result<BackupInfo> {
+        if cfg!(not(feature = "encryption")) {
+            return Err(StorageError::EncryptionDisabled);
+        }
+        let dir = default_backup_dir()
+            .ok_or_else(|| StorageError::Config("Could not determine backups directory".into()))?;
+        self.rekey_with_backup_dir(new_key, &dir)
+    }
+
+    /// Re-encrypt the database under a new key, backing up into `backup_dir`
+    ///
+    /// Both connections stay locked throughout: the writer runs
+    /// `PRAGMA rekey` and the reader is reopened under the new key, since an
+    /// open SQLCipher connection cannot switch keys. VESTIGE_ENCRYPTION_KEY
+    /// must be updated before the next start. Keys are never logged.
+    #[cfg(feature = "encryption")]
+    fn rekey_with_backup_dir(&self, new_key: &str, backup_dir: &Path) -> Result<BackupInfo> {
+        if new_key.is_empty() {
+            return Err(StorageError::InvalidInput {
+                field: "new_key",
+                reason: "must not be empty".into(),
+            });
+        }
+        let has_key = self.encryption_key.lock()
+            .map_err(|_| StorageError::LockPoisoned("Encryption key".into()))?
+            .is_some();
+        if !has_key {
+            return Err(StorageError::Config(
+                "Database is not encrypted; export it with backup_encrypted_to instead".into(),
+            ));
+        }
+
+        let writer = self.lock_writer()?;
+        let backup = self.create_verified_backup(backup_dir)?;
+        let mut reader = self.lock_reader()?;
+        let path = reader
+            .path()
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from)
+            .ok_or_else(|| StorageError::Config("Cannot rekey an in-memory database".into()))?;
+
+        writer.pragma_update(None, "rekey", new_key)?;
+        let fresh = Connection::open(&path)?;
+        Self::configure_connection(&fresh, Some(new_key))?;
+        *reader = fresh;
+        *self.encryption_key.lock()
+            .map_err(|_| StorageError::LockPoisoned("Encryption key".into()))? =
+            Some(new_key.to_string());
+
+        tracing::info!(backup = %backup.path.display(), "Database re-encrypted under a new key");
+        Ok(backup)
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    fn rekey_with_backup_dir(&self, _new_key: &str, _backup_dir: &Path) -> Result<BackupInfo> {
+        Err(StorageError::EncryptionDisabled)
+    }
+
+    /// Export an encrypted copy under an independent key (e.g. for offsite storage)
+    ///
+    /// Uses ATTACH ... KEY and `sqlcipher_export`; the live database and its
+    /// key are untouched. Refuses to overwrite an existing file.
+    #[cfg(feature = "encryption")]
+    pub fn backup_encrypted_to(&self, path: &Path, key: &str) -> Result<()> {
+        if key.is_empty() {
+            return Err(StorageError::InvalidInput {
+                field: "key",
+                reason: "must not be empty".into(),
+            });
+        }
+        if path.exists() {
+            return Err(StorageError::InvalidInput {
+                field: "path",
+                reason: format!("export target {} already exists", path.display()),
+            });
+        }
+        let path_str = path.to_str().ok_or_else(|| {
+            StorageError::InvalidInput { field: "path", reason: "not valid UTF-8".to_string() }
+        })?;
+
+        let reader = self.lock_reader()?;
+        reader.execute("ATTACH DATABASE ?1 AS vestige_export KEY ?2", params![path_str, key])?;
+        let exported = reader.query_row("SELECT sqlcipher_export('vestige_export')", [], |_| Ok(()));
+        let detached = reader.execute("DETACH DATABASE vestige_export", []);
+        exported?;
+        detached?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    pub fn backup_encrypted_to(&self, _path: &Path, _key: &str) -> Result<()> {
+        Err(StorageError::EncryptionDisabled)
+    }
+
+    // ========================================================================
+    // v1.9.0 AUTONOMIC: Retention Target, Auto-Promote, Waking Tags, Utility
+    // ========================================================================
+
+    /// The retention policy consolidation applies
+    pub fn retention_policy(&self) -> RetentionPolicy {
+        self.retention_policy.lock().map(|p| *p).unwrap_or_default()
+    }
+
+    /// Replace the retention policy (defaults come from the environment)
+    pub fn set_retention_policy(&self, policy: RetentionPolicy) -> Result<()> {
+        *self.retention_policy.lock()
+            .map_err(|_| StorageError::LockPoisoned("Retention policy".into()))? = policy;
+        // A new policy changes what the next run does
+        self.note_mutation();
+        Ok(())
+    }
+
+    /// The retrieval-induced forgetting policy `recall` applies
+    pub fn retrieval_forgetting_policy(&self) -> RetrievalForgettingPolicy {
+        self.retrieval_forgetting.lock().map(|p| p.clone()).unwrap_or_default()
+    }
+
+    /// Replace the retrieval-induced forgetting policy (defaults come from the environment)
+    pub fn set_retrieval_forgetting_policy(&self, policy: RetrievalForgettingPolicy) -> Result<()> {
+        *self.retrieval_forgetting.lock()
+            .map_err(|_| StorageError::LockPoisoned("Retrieval forgetting policy".into()))? = policy;
+        Ok(())
+    }
+
+    /// The boosts `strengthen_on_access` applies
+    pub fn reinforcement_config(&self) -> ReinforcementConfig {
+        self.reinforcement.lock().map(|c| *c).unwrap_or_default()
+    }
+
+    /// Replace the reinforcement boosts (defaults come from the environment)
+    pub fn set_reinforcement_config(&self, config: ReinforcementConfig) -> Result<()> {
+        *self.reinforcement.lock()
+            .map_err(|_| StorageError::LockPoisoned("Reinforcement config".into()))? = config;
+        Ok(())
+    }
+
+    fn max_content_bytes_from_env() -> usize {
+        std::env::var("VESTIGE_MAX_CONTENT_BYTES")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_MAX_CONTENT_BYTES)
+    }
+
+    /// Largest content, in bytes, ingest accepts
+    pub fn max_content_bytes(&self) -> usize {
+        self.max_content_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Change the content size limit (defaults come from the environment)
+    pub fn set_max_content_bytes(&self, max_bytes: usize) -> Result<()> {
+        if max_bytes == 0 {
+            return Err(StorageError::InvalidInput {
+                field: "max_content_bytes",
+                reason: "must be greater than zero".into(),
+            });
+        }
+        self.max_content_bytes.store(max_bytes, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Accept `name` as a node type on ingest
+    ///
+    /// Built-in `NodeType`s are always accepted; this is the escape hatch for
+    /// domain-specific types. Names are stored lowercase and may only contain
+    /// letters, digits, `_` and `-`.
+    pub fn register_node_type(&self, name: &str) -> Result<()> {
+        let name = name.trim().to_lowercase();
+        if name.is_empty()
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(StorageError::InvalidInput {
+                field: "node_type",
+                reason: format!("'{}' is not a valid type name (use letters, digits, _ and -)", name),
+            });
+        }
+        if NodeType::from_name(&name).is_none() {
+            self.custom_node_types.lock()
+                .map_err(|_| StorageError::LockPoisoned("Node types".into()))?
+                .insert(name);
+        }
+        Ok(())
+    }
+
+    /// Whether ingest accepts `name` as a node type
+    pub fn is_known_node_type(&self, name: &str) -> bool {
+        NodeType::from_name(name).is_some()
+            || self.custom_node_types.lock()
+                .map(|types| types.contains(&name.trim().to_lowercase()))
+                .unwrap_or(false)
+    }
+
+    /// Every node type ingest accepts: the built-ins, then registered types
+    pub fn node_types(&self) -> Vec<String> {
+        let mut types: Vec<String> = NodeType::ALL.iter().map(|t| t.as_str().to_string()).collect();
+        if let Ok(custom) = self.custom_node_types.lock() {
+            types.extend(custom.iter().cloned());
+        }
+        types
+    }
+
+    /// Node type to restore a backed-up memory with
+    ///
+    /// Backups can carry types that are no longer built in or were never
+    /// registered here; those come back as `fact` (with a warning) rather
+    /// than failing ingest validation. A missing type is also `fact`.
+    pub fn node_type_for_restore(&self, node_type: Option<&str>) -> String {
+        match node_type {
+            Some(name) if self.is_known_node_type(name) => name.to_string(),
+            Some(name) => {
+                tracing::warn!("Unknown node type '{}' restored as 'fact'", name);
+                NodeType::Fact.as_str().to_string()
+            }
+            None => NodeType::Fact.as_str().to_string(),
+        }
+    }
+
+    /// Set the ranking trust multiplier for a source pattern
+    ///
+    /// A pattern matches a memory's source exactly, or as a prefix when it
+    /// ends in `*` (e.g. `tool:*`). The weight must lie in [0.5, 1.5].
+    pub fn set_source_trust(&self, pattern: &str, weight: f64) -> Result<()> {
+        if pattern.trim().is_empty() {
+            return Err(StorageError::InvalidInput {
+                field: "pattern",
+                reason: "must not be empty".into(),
+            });
+        }
+        if !(MIN_SOURCE_TRUST..=MAX_SOURCE_TRUST).contains(&weight) {
+            return Err(StorageError::InvalidInput {
+                field: "weight",
+                reason: format!("{} must be in [{}, {}]", weight, MIN_SOURCE_TRUST, MAX_SOURCE_TRUST),
+            });
+        }
+        let writer = self.lock_writer()?;
+        writer.execute(
+            "INSERT INTO source_trust (pattern, weight, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(pattern) DO UPDATE SET weight = excluded.weight, updated_at = excluded.updated_at",
+            params![pattern, weight, self.now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Remove a source trust pattern; false if it was not set
+    pub fn remove_source_trust(&self, pattern: &str) -> Result<bool> {
+        let writer = self.lock_writer()?;
+        let rows = writer.execute("DELETE FROM source_trust WHERE pattern = ?1", params![pattern])?;
+        Ok(rows > 0)
+    }
+
+    /// All configured source trust patterns
+    pub fn get_source_trusts(&self) -> Result<Vec<SourceTrust>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare("SELECT pattern, weight FROM source_trust ORDER BY pattern")?;
+        let trusts = stmt
+            .query_map([], |row| {
+                Ok(SourceTrust {
+                    pattern: row.get(0)?,
+                    weight: row.get(1)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(trusts)
+    }
+
+    /// Suggest tags for content from its keywords and the tags of its `k` nearest memories
+    ///
+    /// Keywords are weighted by TF-IDF against the stored memories. Neighbors
+    /// come from vector search when an embedding model is ready and from
+    /// keyword (BM25) search otherwise.
+    pub fn suggest_tags(&self, content: &str, k: usize) -> Result<Vec<TagSuggestion>> {
+        let terms: Vec<(String, usize)> =
+            auto_tag::extract_terms(content).into_iter().take(MAX_TAG_TERMS).collect();
+
+        let (corpus_size, doc_freq, known_tags) = {
+            let reader = self.lock_reader()?;
+            let corpus_size: i64 =
+                reader.query_row("SELECT COUNT(*) FROM knowledge_nodes", [], |row| row.get(0))?;
+            let mut doc_freq = HashMap::with_capacity(terms.len());
+            for (term, _) in &terms {
+                let df: i64 = reader.query_row(
+                    "SELECT COUNT(*) FROM knowledge_fts WHERE knowledge_fts MATCH ?1",
+                    params![format!("\"{}\"", term)],
+                    |row| row.get(0),
+                )?;
+                doc_freq.insert(term.clone(), df as usize);
+            }
+            let mut stmt = reader.prepare(
+                "SELECT DISTINCT lower(tag.value) FROM knowledge_nodes n, json_each(n.tags) tag",
+            )?;
+            let known_tags = stmt
+                .query_map([], |row| row.get::<_, String>(0))?
+                .collect::<rusqlite::Result<HashSet<_>>>()?;
+            (corpus_size as usize, doc_freq, known_tags)
+        };
+        let keyword = auto_tag::keyword_suggestions(&terms, &doc_freq, corpus_size, &known_tags);
+
+        let mut neighbors = Vec::with_capacity(k);
+        for (id, similarity) in self.tag_neighbors(content, &terms, k)? {
+            if let Some(node) = self.get_node(&id)? {
+                neighbors.push((similarity, node.tags));
+            }
+        }
+
+        Ok(auto_tag::merge_suggestions(keyword, auto_tag::neighbor_suggestions(&neighbors)))
+    }
+
+    /// Nearest memories to content with their similarity (0-1)
+    fn tag_neighbors(
+        &self,
+        content: &str,
+        terms: &[(String, usize)],
+        k: usize,
+    ) -> Result<Vec<(String, f32)>> {
+        #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+        if self.embedding_service.is_ready() {
+            let embedding = self.embedding_service.embed(content)
+                .map_err(|e| StorageError::EmbeddingUnavailable(format!("Failed to embed content: {}", e)))?;
+            let index = self.vector_index.lock()
+                .map_err(|_| StorageError::LockPoisoned("Vector index".into()))?;
+            return index
+                .search(&embedding.vector, k)
+                .map_err(|e| StorageError::VectorIndex(format!("Vector search failed: {}", e)));
+        }
+        #[cfg(not(all(feature = "embeddings", feature = "vector-search")))]
+        let _ = content;
+
+        // Any of the content's keywords, scored relative to the best match
+        if terms.is_empty() {
+            return Ok(vec![]);
+        }
+        let query = terms
+            .iter()
+            .map(|(term, _)| format!("\"{}\"", term))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT id, rank FROM knowledge_fts WHERE knowledge_fts MATCH ?1 ORDER BY rank LIMIT ?2",
+        )?;
+        let matches = stmt
+            .query_map(params![query, k as i64], |row| {
+                Ok((row.get::<_, String>(0)?, -row.get::<_, f64>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let best = matches.iter().map(|(_, score)| *score).fold(0.0_f64, f64::max);
+        Ok(matches
+            .into_iter()
+            .map(|(id, score)| (id, if best > 0.0 { (score / best) as f32 } else { 1.0 }))
+            .collect())
+    }
+
+    /// Add confident tag suggestions to an input that asks for them
+    fn apply_auto_tags(&self, input: &mut IngestInput) -> Result<()> {
+        if input.auto_tag {
+            let suggestions = self.suggest_tags(&input.content, AUTO_TAG_NEIGHBORS)?;
+            auto_tag::apply_suggestions(&mut input.tags, &suggestions);
+            input.auto_tag = false;
+        }
+        Ok(())
+    }
+
+    /// Get average retention across all memories
+    pub fn get_avg_retention(&self) -> Result<f64> {
+        let reader = self.lock_reader()?;
+        let avg: f64 = reader.query_row(
+            "SELECT COALESCE(AVG(retention_strength), 0.0) FROM knowledge_nodes",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(avg)
+    }
+
+    /// Get retention distribution in buckets (0-20%, 20-40%, 40-60%, 60-80%, 80-100%)
+    pub fn get_retention_distribution(&self) -> Result<Vec<(String, i64)>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT
+                CASE
+                    WHEN retention_strength < 0.2 THEN '0-20%'
+                    WHEN retention_strength < 0.4 THEN '20-40%'
+                    WHEN retention_strength < 0.6 THEN '40-60%'
+                    WHEN retention_strength < 0.8 THEN '60-80%'
+                    ELSE '80-100%'
+                END as bucket,
+                COUNT(*) as count
+            FROM knowledge_nodes
+            GROUP BY bucket
+            ORDER BY bucket"
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    /// Get retention trend (improving/declining/stable) from retention snapshots
+    pub fn get_retention_trend(&self) -> Result<String> {
+        let reader = self.lock_reader()?;
+
+        let snapshots: Vec<f64> = reader.prepare(
+            "SELECT avg_retention FROM retention_snapshots ORDER BY snapshot_at DESC LIMIT 5"
+        )?.query_map([], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        if snapshots.len() < 3 {
+            return Ok("insufficient_data".to_string());
+        }
+
+        // Compare recent vs older snapshots
+        let recent_avg = snapshots.iter().take(2).sum::<f64>() / 2.0;
+        let older_avg = snapshots.iter().skip(2).sum::<f64>() / (snapshots.len() - 2) as f64;
+
+        let diff = recent_avg - older_avg;
+        Ok(if diff > 0.02 {
+            "improving".to_string()
+        } else if diff < -0.02 {
+            "declining".to_string()
+        } else {
+            "stable".to_string()
+        })
+    }
+
+    /// Forecast review load and retention for today and the next `days` days
+    ///
+    /// Streams every memory once: its next review lands in one day's due
+    /// count and its forgetting curve (personalized w20, sentiment-boosted
+    /// stability, as in `apply_decay`) feeds each day's average, assuming
+    /// no reviews happen in between.
+    pub fn forecast(&self, days: u32) -> Result<Forecast> {
+        let w20 = self.get_fsrs_w20().unwrap_or(DEFAULT_DECAY);
+        let now = self.now();
+        let today = now.date_naive();
+        let horizon = days as usize + 1;
+        let mut due_counts = vec![0i64; horizon];
+        let mut retention_sums = vec![0.0f64; horizon];
+        let mut total = 0i64;
+
+        {
+            let reader = self.lock_reader()?;
+            let mut stmt = reader.prepare(
+                "SELECT last_accessed, next_review, stability, sentiment_magnitude
+                 FROM knowledge_nodes",
+            )?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                let last_accessed: String = row.get(0)?;
+                let next_review: Option<String> = row.get(1)?;
+                let stability: f64 = row.get(2)?;
+                let sentiment_mag: f64 = row.get(3)?;
+                total += 1;
+
+                if let Some(due) = next_review
+                    .as_deref()
+                    .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                {
+                    let offset = (due.with_timezone(&Utc).date_naive() - today).num_days().max(0);
+                    if let Some(count) = due_counts.get_mut(offset as usize) {
+                        *count += 1;
+                    }
+                }
+
+                let last = DateTime::parse_from_rfc3339(&last_accessed)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .unwrap_or(now);
+                let elapsed = (now - last).num_seconds() as f64 / 86400.0;
+                for (day, sum) in retention_sums.iter_mut().enumerate() {
+                    let days_since = elapsed + day as f64;
+                    *sum += if days_since > 0.0 {
+                        Self::decayed_retrievability(stability, sentiment_mag, days_since, w20)
+                    } else {
+                        1.0
+                    };
+                }
+            }
+        }
+
+        let days = (0..horizon)
+            .map(|day| ForecastDay {
+                date: (today + Duration::days(day as i64)).format("%Y-%m-%d").to_string(),
+                due_count: due_counts[day],
+                projected_avg_retention: if total > 0 {
+                    retention_sums[day] / total as f64
+                } else {
+                    0.0
+                },
+            })
+            .collect();
+
+        Ok(Forecast {
+            generated_at: now,
+            w20,
+            total_memories: total,
+            days,
+        })
+    }
+
+    /// Save a retention snapshot (called during consolidation)
+    pub fn save_retention_snapshot(&self, avg_retention: f64, total: i64, below_target: i64, gc_triggered: bool) -> Result<()> {
+        let writer = self.lock_writer()?;
+        writer.execute(
+            "INSERT INTO retention_snapshots (snapshot_at, avg_retention, total_memories, memories_below_target, gc_triggered)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![self.now().to_rfc3339(), avg_retention, total, below_target, gc_triggered],
+        )?;
+        Ok(())
+    }
+
+    /// Count memories below a given retention threshold
+    pub fn count_memories_below_retention(&self, threshold: f64) -> Result<i64> {
+        self.count_memories_below_retention_in(None, threshold)
+    }
+
+    /// `count_memories_below_retention` limited to one namespace, or all of
+    /// them with `None`
+    pub(crate) fn count_memories_below_retention_in(
+        &self,
+        namespace: Option<&str>,
+        threshold: f64,
+    ) -> Result<i64> {
+        let reader = self.lock_reader()?;
+        let count: i64 = reader.query_row(
+            "SELECT COUNT(*) FROM knowledge_nodes
+             WHERE retention_strength < ?1 AND (?2 IS NULL OR namespace = ?2)",
+            params![threshold, namespace],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    /// Auto-GC memories below threshold (used by retention target system)
+    ///
+    /// Pinned memories are kept.
+    pub fn gc_below_retention(&self, threshold: f64, min_age_days: i64) -> Result<i64> {
+        self.gc_below_retention_in(None, threshold, min_age_days)
+    }
+
+    /// `gc_below_retention` limited to one namespace, or all of them with `None`
+    pub(crate) fn gc_below_retention_in(
+        &self,
+        namespace: Option<&str>,
+        threshold: f64,
+        min_age_days: i64,
+    ) -> Result<i64> {
+        let now = self.now();
+        let cutoff = (now - Duration::days(min_age_days)).to_rfc3339();
+        let writer = self.lock_writer()?;
+        let deleted = writer.execute(
+            "DELETE FROM knowledge_nodes WHERE retention_strength < ?1 AND created_at < ?2
+               AND (?4 IS NULL OR namespace = ?4)
+               AND id NOT IN (SELECT memory_id FROM memory_pins
+                              WHERE pinned_until IS NULL OR pinned_until > ?3)",
+            params![threshold, cutoff, now.to_rfc3339(), namespace],
+        )? as i64;
+        Ok(deleted)
+    }
+
+    /// Check for auto-promote candidates: memories accessed 3+ times in last 24h
+    pub fn auto_promote_frequent_access(&self) -> Result<i64> {
+        let twenty_four_hours_ago = (self.now() - Duration::hours(24)).to_rfc3339();
+        let now = self.now().to_rfc3339();
+
+        // Find memories with 3+ accesses in last 24h
+        let candidates: Vec<String> = {
+            let reader = self.lock_reader()?;
+            let mut stmt = reader.prepare(
+                "SELECT node_id, COUNT(*) as access_count
+                 FROM memory_access_log
+                 WHERE accessed_at >= ?1
+                 GROUP BY node_id
+                 HAVING access_count >= 3"
+            )?;
+            stmt.query_map(params![twenty_four_hours_ago], |row| row.get(0))?
+                .filter_map(|r| r.ok())
+                .collect()
+        };
+
+        if candidates.is_empty() {
+            return Ok(0);
+        }
+
+        let writer = self.lock_writer()?;
+        let mut promoted = 0i64;
+        for id in &candidates {
+            let rows = writer.execute(
+                "UPDATE knowledge_nodes SET
+                    retrieval_strength = MIN(1.0, retrieval_strength + 0.10),
+                    retention_strength = MIN(1.0, retention_strength + 0.05),
+                    last_accessed = ?1
+                WHERE id = ?2 AND retrieval_strength < 0.95",
+                params![now, id],
+            )?;
+            if rows > 0 {
+                promoted += 1;
+            }
+        }
+
+        Ok(promoted)
+    }
+
+    /// Persist the emotional evaluation made when a memory was encoded
+    ///
+    /// With `replay` set (flashbulb or high-arousal encoding) the memory also
+    /// gets a waking tag, so the next dream replays it preferentially.
+    pub fn record_emotional_encoding(
+        &self,
+        memory_id: &str,
+        valence: f64,
+        flashbulb: bool,
+        replay: bool,
+    ) -> Result<()> {
+        let writer = self.lock_writer()?;
+        let rows = writer.execute(
+            "UPDATE knowledge_nodes SET emotional_valence = ?1, flashbulb = ?2,
+                waking_tag = CASE WHEN ?3 THEN TRUE ELSE waking_tag END,
+                waking_tag_at = CASE WHEN ?3 THEN ?4 ELSE waking_tag_at END
+             WHERE id = ?5",
+            params![valence, flashbulb, replay, self.now().to_rfc3339(), memory_id],
+        )?;
+        if rows == 0 {
+            return Err(StorageError::NotFound(memory_id.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Set waking tag on a memory (marks it for preferential dream replay)
+    pub fn set_waking_tag(&self, memory_id: &str) -> Result<()> {
+        let writer = self.lock_writer()?;
+        writer.execute(
+            "UPDATE knowledge_nodes SET waking_tag = TRUE, waking_tag_at = ?1 WHERE id = ?2",
+            params![self.now().to_rfc3339(), memory_id],
+        )?;
+        Ok(())
+    }
+
+    /// Clear waking tags (called after dream processes them)
+    pub fn clear_waking_tags(&self) -> Result<i64> {
+        let writer = self.lock_writer()?;
+        let cleared = writer.execute(
+            "UPDATE knowledge_nodes SET waking_tag = FALSE, waking_tag_at = NULL WHERE waking_tag = TRUE",
+            [],
+        )? as i64;
+        Ok(cleared)
+    }
+
+    /// Get waking-tagged memories for preferential dream replay
+    ///
+    /// Flashbulb memories come first, then the most recently tagged.
+    pub fn get_waking_tagged_memories(&self, limit: i32) -> Result<Vec<KnowledgeNode>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT * FROM knowledge_nodes WHERE waking_tag = TRUE
+             ORDER BY COALESCE(flashbulb, FALSE) DESC, waking_tag_at DESC LIMIT ?1"
+        )?;
+        let nodes = stmt.query_map(params![limit], |row| Self::row_to_node(row))?;
+        let mut result = Vec::new();
+        for node in nodes {
+            result.push(node?);
+        }
+        Ok(result)
+    }
+
+    /// Get memories with their connection data for graph visualization
+    pub fn get_memory_subgraph(&self, center_id: &str, depth: u32, max_nodes: usize) -> Result<(Vec<KnowledgeNode>, Vec<ConnectionRecord>)> {
+        self.get_memory_subgraph_filtered(center_id, depth, max_nodes, &GraphFilter::default())
+    }
+
+    /// `get_memory_subgraph` following only connections that pass `filter`
+    pub fn get_memory_subgraph_filtered(
+        &self,
+        center_id: &str,
+        depth: u32,
+        max_nodes: usize,
+        filter: &GraphFilter,
+    ) -> Result<(Vec<KnowledgeNode>, Vec<ConnectionRecord>)> {
+        let members = self.graph_members(filter)?;
+        let mut visited_ids: HashSet<String> = HashSet::new();
+        let mut frontier = vec![center_id.to_string()];
+        visited_ids.insert(center_id.to_string());
+
+        'bfs: for _ in 0..depth {
+            let mut next_frontier = Vec::new();
+            for id in &frontier {
+                for conn in self.get_connections_for_memory(id)? {
+                    if !filter.accepts(&conn) {
+                        continue;
+                    }
+                    let other_id = if conn.source_id == *id { conn.target_id } else { conn.source_id };
+                    if members.as_ref().is_some_and(|m| !m.contains(&other_id)) {
+                        continue;
+                    }
+                    if visited_ids.insert(other_id.clone()) {
+                        next_frontier.push(other_id);
+                        if visited_ids.len() >= max_nodes {
+                            break 'bfs;
+                        }
+                    }
+                }
+            }
+            frontier = next_frontier;
+            if frontier.is_empty() {
+                break;
+            }
+        }
+
+        let mut nodes = Vec::new();
+        for id in &visited_ids {
+            if let Some(node) = self.get_node(id)?
+                && members.as_ref().is_none_or(|m| m.contains(id))
+            {
+                nodes.push(node);
+            }
+        }
+        let edges = self
+            .get_all_connections()?
+            .into_iter()
+            .filter(|c| {
+                filter.accepts(c)
+                    && visited_ids.contains(&c.source_id)
+                    && visited_ids.contains(&c.target_id)
+            })
+            .collect();
+
+        Ok((nodes, edges))
+    }
+
+    /// Ids of the memories a namespaced graph filter admits (None = every memory)
+    fn graph_members(&self, filter: &GraphFilter) -> Result<Option<HashSet<String>>> {
+        let Some(namespace) = &filter.namespace else {
+            return Ok(None);
+        };
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare("SELECT id FROM knowledge_nodes WHERE namespace = ?1")?;
+        let ids = stmt
+            .query_map(params![namespace], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(Some(ids))
+    }
+
+    /// Connected components of the connection graph, largest first
+    ///
+    /// Only connections passing `filter` link nodes; unconnected memories
+    /// are left out. Components are returned whole, so callers should cap
+    /// how many nodes they render.
+    pub fn graph_communities(&self, filter: &GraphFilter) -> Result<Vec<Vec<String>>> {
+        let members = self.graph_members(filter)?;
+        let connections: Vec<ConnectionRecord> = self
+            .get_all_connections()?
+            .into_iter()
+            .filter(|c| {
+                filter.accepts(c)
+                    && members
+                        .as_ref()
+                        .is_none_or(|m| m.contains(&c.source_id) && m.contains(&c.target_id))
+            })
+            .collect();
+
+        // Union-find over node indices
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        let mut ids: Vec<&str> = Vec::new();
+        let mut parent: Vec<usize> = Vec::new();
+        fn find(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+        for conn in &connections {
+            let [a, b] = [conn.source_id.as_str(), conn.target_id.as_str()].map(|id| {
+                *index.entry(id).or_insert_with(|| {
+                    ids.push(id);
+                    parent.push(parent.len());
+                    parent.len() - 1
+                })
+            });
+            let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+            if ra != rb {
+                parent[ra] = rb;
+            }
+        }
+
+        let mut groups: HashMap<usize, Vec<String>> = HashMap::new();
+        for (i, id) in ids.iter().enumerate() {
+            let root = find(&mut parent, i);
+            groups.entry(root).or_default().push(id.to_string());
+        }
+        let mut communities: Vec<Vec<String>> = groups.into_values().collect();
+        for community in &mut communities {
+            community.sort();
+        }
+        communities.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a[0].cmp(&b[0])));
+        Ok(communities)
+    }
+
+    /// Activation and accessibility state for each of `ids` that exists
+    ///
+    /// State comes from `memory_states` when tracked there, otherwise from
+    /// retention with the thresholds consolidation uses.
+    pub fn graph_node_states(&self, ids: &[String]) -> Result<HashMap<String, NodeGraphState>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT n.activation, n.retention_strength, s.state
+             FROM knowledge_nodes n LEFT JOIN memory_states s ON s.memory_id = n.id
+             WHERE n.id = ?1",
+        )?;
+
+        let mut states = HashMap::new();
+        for id in ids {
+            let row = stmt
+                .query_row(params![id], |row| {
+                    Ok((
+                        row.get::<_, Option<f64>>(0)?,
+                        row.get::<_, f64>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                    ))
+                })
+                .optional()?;
+            if let Some((activation, retention, state)) = row {
+                let state = state.unwrap_or_else(|| {
+                    match retention {
+                        r if r > 0.7 => "active",
+                        r if r > 0.3 => "dormant",
+                        r if r > 0.1 => "silent",
+                        _ => "unavailable",
+                    }
+                    .to_string()
+                });
+                states.insert(id.clone(), NodeGraphState { activation, state });
+            }
+        }
+        Ok(states)
+    }
+
+    /// Get recent state transitions across all memories (system-wide changelog)
+    pub fn get_recent_state_transitions(&self, limit: i32) -> Result<Vec<StateTransitionRecord>> {
+        let reader = self.lock_reader()?;
+        let mut stmt = reader.prepare(
+            "SELECT * FROM state_transitions ORDER BY timestamp DESC LIMIT ?1"
+        )?;
+
+        let rows = stmt.query_map(params![limit], |row| {
+            Ok(StateTransitionRecord {
+                id: row.get("id")?,
+                memory_id: row.get("memory_id")?,
+                from_state: row.get("from_state")?,
+                to_state: row.get("to_state")?,
+                reason_type: row.get("reason_type")?,
+                reason_data: row.get("reason_data").ok().flatten(),
+                timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>("timestamp")?)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .unwrap_or_else(|_| self.now()),
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    // ========================================================================
+    // DIAGNOSTICS
+    // ========================================================================
+
+    /// Run storage integrity and maintenance checks
+    ///
+    /// Covers file and WAL size, free pages, FTS5 integrity, embedding rows
+    /// versus nodes, the in-memory vector index, unparsable node timestamps,
+    /// consolidation/dream/backup ages and the query cache hit rate. Each
+    /// failed check carries a suggested remediation.
+    pub fn diagnostics(&self) -> Result<DiagnosticsReport> {
+        let now = self.now();
+        let mut checks = Vec::new();
+
+        let (path, page_count, page_size, freelist_count, total_nodes, embedding_rows, orphaned, missing) = {
+            let reader = self.lock_reader()?;
+            let count = |sql: &str| reader.query_row(sql, [], |row| row.get::<_, i64>(0));
+            (
+                reader.path().filter(|p| !p.is_empty()).map(PathBuf::from),
+                count("PRAGMA page_count")?,
+                count("PRAGMA page_size")?,
+                count("PRAGMA freelist_count")?,
+                count("SELECT COUNT(*) FROM knowledge_nodes")?,
+                count("SELECT COUNT(*) FROM node_embeddings")?,
+                count(
+                    "SELECT COUNT(*) FROM node_embeddings
+                     WHERE node_id NOT IN (SELECT id FROM knowledge_nodes)",
+                )?,
+                count(
+                    "SELECT COUNT(*) FROM knowledge_nodes n WHERE n.has_embedding = 1
+                     AND NOT EXISTS (SELECT 1 FROM node_embeddings e WHERE e.node_id = n.id)",
+                )?,
+            )
+        };
+
+        let file_size = |p: &Path| std::fs::metadata(p).map(|m| m.len()).unwrap_or(0);
+        let db_size_bytes = path.as_deref().map(file_size).unwrap_or(0);
+        let wal_size_bytes = path
+            .map(|p| {
+                let mut wal = p.into_os_string();
+                wal.push("-wal");
+                file_size(Path::new(&wal))
+            })
+            .unwrap_or(0);
+        let mib = |bytes: u64| bytes as f64 / (1024.0 * 1024.0);
+
+        let detail = format!("WAL is {:.1} MiB, database {:.1} MiB", mib(wal_size_bytes), mib(db_size_bytes));
+        checks.push(if wal_size_bytes > DiagnosticsReport::WAL_WARN_BYTES {
+            DiagnosticCheck::failed(
+                "wal_size",
+                DiagnosticStatus::Warn,
+                detail,
+                "Checkpoint the WAL with PRAGMA wal_checkpoint(TRUNCATE); a long-lived reader may be blocking automatic checkpoints",
+            )
+        } else {
+            DiagnosticCheck::ok("wal_size", detail)
+        });
+
+        let detail = format!("{} of {} pages free", freelist_count, page_count);
+        checks.push(if page_count >= 1024 && freelist_count * 4 > page_count {
+            DiagnosticCheck::failed(
+                "free_pages",
+                DiagnosticStatus::Warn,
+                detail,
+                "Run VACUUM (or take a backup, which compacts) to reclaim free pages",
+            )
+        } else {
+            DiagnosticCheck::ok("free_pages", detail)
+        });
+
+        let fts = {
+            let writer = self.lock_writer()?;
+            writer.execute("INSERT INTO knowledge_fts(knowledge_fts) VALUES('integrity-check')", [])
+        };
+        checks.push(match fts {
+            Ok(_) => DiagnosticCheck::ok("fts_integrity", "Full-text index matches knowledge_nodes".to_string()),
+            Err(e) => DiagnosticCheck::failed(
+                "fts_integrity",
+                DiagnosticStatus::Error,
+                format!("FTS5 integrity check failed: {}", e),
+                "Rebuild the full-text index: INSERT INTO knowledge_fts(knowledge_fts) VALUES('rebuild')",
+            ),
+        });
+
+        let detail = format!(
+            "{} embedding rows, {} without a node, {} nodes flagged as embedded without a row",
+            embedding_rows, orphaned, missing
+        );
+        checks.push(if orphaned > 0 || missing > 0 {
+            let mut fixes = Vec::new();
+            if orphaned > 0 {
+                fixes.push("DELETE FROM node_embeddings WHERE node_id NOT IN (SELECT id FROM knowledge_nodes)");
+            }
+            if missing > 0 {
+                fixes.push("UPDATE knowledge_nodes SET has_embedding = 0 WHERE id NOT IN (SELECT node_id FROM node_embeddings), then run consolidate to regenerate them");
+            }
+            DiagnosticCheck::failed("embeddings", DiagnosticStatus::Warn, detail, &fixes.join("; "))
+        } else {
+            DiagnosticCheck::ok("embeddings", detail)
+        });
+
+        #[cfg(feature = "vector-search")]
+        {
+            let indexed = self.vector_index_stats()?.total_vectors as i64;
+            let detail = format!("{} vectors indexed, {} embedding rows", indexed, embedding_rows);
+            checks.push(if indexed != embedding_rows {
+                DiagnosticCheck::failed(
+                    "vector_index",
+                    DiagnosticStatus::Warn,
+                    detail,
+                    "Rebuild the vector index from node_embeddings (restart the server or call Storage::rebuild_vector_index)",
+                )
+            } else {
+                DiagnosticCheck::ok("vector_index", detail)
+            });
+        }
+
+        let bad_timestamps: Vec<String> = {
+            let reader = self.lock_reader()?;
+            let mut stmt = reader.prepare(
+                "SELECT id, created_at, updated_at, last_accessed, next_review, valid_from, valid_until
+                 FROM knowledge_nodes",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                let mut values = Vec::with_capacity(6);
+                for i in 1..=6 {
+                    values.push(row.get::<_, Option<String>>(i)?);
+                }
+                Ok((row.get::<_, String>(0)?, values))
+            })?;
+            rows.flatten()
+                .filter(|(_, values)| {
+                    values.iter().flatten().any(|v| DateTime::parse_from_rfc3339(v).is_err())
+                })
+                .map(|(id, _)| id)
+                .collect()
+        };
+        checks.push(if bad_timestamps.is_empty() {
+            DiagnosticCheck::ok("timestamps", format!("All {} nodes have RFC 3339 timestamps", total_nodes))
+        } else {
+            let sample: Vec<&str> = bad_timestamps.iter().take(5).map(String::as_str).collect();
+            DiagnosticCheck::failed(
+                "timestamps",
+                DiagnosticStatus::Warn,
+                format!("{} nodes have unparsable timestamps (e.g. {})", bad_timestamps.len(), sample.join(", ")),
+                "Rewrite the affected created_at/updated_at/last_accessed/next_review/valid_from/valid_until values as RFC 3339; these nodes fail to load",
+            )
+        });
+
+        let age_check = |name: &str, last: Option<DateTime<Utc>>, stale: Duration, remediation: &str| {
+            match last {
+                _ if total_nodes == 0 => DiagnosticCheck::ok(name, "No memories yet".to_string()),
+                Some(at) if now - at <= stale => {
+                    DiagnosticCheck::ok(name, format!("Last run {}h ago", (now - at).num_hours()))
+                }
+                Some(at) => DiagnosticCheck::failed(
+                    name,
+                    DiagnosticStatus::Warn,
+                    format!("Last run {}h ago", (now - at).num_hours()),
+                    remediation,
+                ),
+                None => DiagnosticCheck::failed(name, DiagnosticStatus::Warn, "Never run".to_string(), remediation),
+            }
+        };
+        checks.push(age_check(
+            "consolidation",
+            self.get_last_consolidation()?,
+            Duration::hours(DiagnosticsReport::CONSOLIDATION_STALE_HOURS),
+            "Run consolidate, or check that the background consolidation task is running",
+        ));
+        checks.push(age_check(
+            "dream",
+            self.get_last_dream()?,
+            Duration::days(DiagnosticsReport::MAINTENANCE_STALE_DAYS),
+            "Run dream to replay recent memories and discover connections",
+        ));
+        // Only verified backups are kept, so the newest one is the last good one
+        let backup_policy = BackupConfig::from_env();
+        checks.push(age_check(
+            "backup",
+            Self::get_last_backup_timestamp(),
+            if backup_policy.enabled() {
+                backup_policy.interval()
+            } else {
+                Duration::days(DiagnosticsReport::MAINTENANCE_STALE_DAYS)
+            },
+            "Run backup to snapshot the database, and check that automatic backups (VESTIGE_BACKUP_INTERVAL_HOURS) are enabled",
+        ));
+
+        #[cfg(feature = "embeddings")]
+        {
+            let stats = self.query_cache_stats()?;
+            checks.push(DiagnosticCheck::ok(
+                "query_cache",
+                format!(
+                    "{} of {} query embedding lookups hit the cache ({:.0}%); {} entries using {} of {} bytes",
+                    stats.hits,
+                    stats.hits + stats.misses,
+                    stats.hit_rate() * 100.0,
+                    stats.entries,
+                    stats.bytes,
+                    stats.capacity_bytes,
+                ),
+            ));
+        }
+        #[cfg(not(feature = "embeddings"))]
+        checks.push(DiagnosticCheck::ok("query_cache", "Embeddings disabled; no query cache".to_string()));
+
+        Ok(DiagnosticsReport {
+            status: checks.iter().map(|c| c.status).max().unwrap_or(DiagnosticStatus::Ok),
+            generated_at: now,
+            db_size_bytes,
+            wal_size_bytes,
+            page_count,
+            page_size,
+            freelist_count,
+            checks,
+        })
+    }
+
+    /// Report whether embeddings, the vector index and the locks are consistent
+    ///
+    /// Unlike `diagnostics`, this still runs with poisoned locks (it only
+    /// reads) and reports which ones are poisoned.
+    pub fn diagnose(&self) -> Result<HealthReport> {
+        let poisoned_locks = self.poisoned_locks();
+        #[cfg(feature = "embeddings")]
+        let query_cache = Some(self.query_cache_stats()?);
+        #[cfg(not(feature = "embeddings"))]
+        let query_cache = None;
+        let (
+            schema_version,
+            node_count,
+            embedding_count,
+            orphaned_embeddings,
+            nodes_missing_embeddings,
+            flagged_without_embedding,
+            embedded_ids,
+        ) = {
+            let reader = self.reader.lock().unwrap_or_else(|e| e.into_inner());
+            let count = |sql: &str| reader.query_row(sql, [], |row| row.get::<_, i64>(0));
+            let embedded_ids: Vec<String> = reader
+                .prepare(
+                    "SELECT e.node_id FROM node_embeddings e
+                     JOIN knowledge_nodes n ON n.id = e.node_id
+                     WHERE COALESCE(n.has_embedding, 0) = 1",
+                )?
+                .query_map([], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+            (
+                super::migrations::get_current_version(&reader)?,
+                count("SELECT COUNT(*) FROM knowledge_nodes")?,
+                count("SELECT COUNT(*) FROM node_embeddings")?,
+                count(
+                    "SELECT COUNT(*) FROM node_embeddings
+                     WHERE node_id NOT IN (SELECT id FROM knowledge_nodes)",
+                )?,
+                count(
+                    "SELECT COUNT(*) FROM knowledge_nodes n
+                     WHERE NOT EXISTS (SELECT 1 FROM node_embeddings e WHERE e.node_id = n.id)",
+                )?,
+                count(
+                    "SELECT COUNT(*) FROM knowledge_nodes n WHERE n.has_embedding = 1
+                     AND NOT EXISTS (SELECT 1 FROM node_embeddings e WHERE e.node_id = n.id)",
+                )?,
+                embedded_ids,
+            )
+        };
+
+        #[cfg(feature = "vector-search")]
+        let (vector_index_size, vectors_missing_from_index) = {
+            let index = self.vector_index.lock().unwrap_or_else(|e| e.into_inner());
+            let missing = embedded_ids.iter().filter(|id| !index.contains(id)).count();
+            (Some(index.len()), Some(missing))
+        };
+        #[cfg(not(feature = "vector-search"))]
+        let (vector_index_size, vectors_missing_from_index) = {
+            let _ = embedded_ids;
+            (None, None)
+        };
+
+        Ok(HealthReport {
+            node_count,
+            embedding_count,
+            vector_index_size,
+            orphaned_embeddings,
+            nodes_missing_embeddings,
+            flagged_without_embedding,
+            vectors_missing_from_index,
+            poisoned_locks,
+            query_cache,
+            schema_version,
+            last_consolidation: self.get_last_consolidation().ok().flatten(),
+            last_dream: self.get_last_dream().ok().flatten(),
+            mutations_since_consolidation: self.mutations_since_consolidation(),
+        })
+    }
+
+    /// Fix what `diagnose` finds: delete orphaned embeddings, clear the
+    /// embedded flag on nodes without an embedding (so consolidation
+    /// regenerates it), and re-add stored embeddings missing from the index
+    pub fn repair(&self) -> Result<RepairReport> {
+        let (orphaned_embeddings_deleted, embedding_flags_cleared) = {
+            let mut writer = self.lock_writer()?;
+            let tx = writer.transaction()?;
+            let deleted = tx.execute(
+                "DELETE FROM node_embeddings WHERE node_id NOT IN (SELECT id FROM knowledge_nodes)",
+                [],
+            )?;
+            let cleared = tx.execute(
+                "UPDATE knowledge_nodes SET has_embedding = 0 WHERE has_embedding = 1
+                 AND NOT EXISTS (SELECT 1 FROM node_embeddings e WHERE e.node_id = knowledge_nodes.id)",
+                [],
+            )?;
+            tx.commit()?;
+            (deleted, cleared)
+        };
+
+        #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+        let vectors_readded = {
+            // Rows without an up-to-date index vector are truncated first
+            let dimensions = self.index_dimensions();
+            self.sync_index_vectors(dimensions)?;
+            let vectors = self.load_index_vectors(dimensions)?;
+            let mut index = self
+                .vector_index
+                .lock()
+                .map_err(|_| StorageError::LockPoisoned("Vector index".into()))?;
+            let mut readded = 0;
+            for (node_id, vector) in vectors {
+                if index.contains(&node_id) {
+                    continue;
+                }
+                match index.add(&node_id, &vector) {
+                    Ok(()) => readded += 1,
+                    Err(e) => tracing::warn!("Failed to re-index embedding for {}: {}", node_id, e),
+                }
+            }
+            readded
+        };
+        #[cfg(not(all(feature = "embeddings", feature = "vector-search")))]
+        let vectors_readded = 0;
+
+        Ok(RepairReport {
+            orphaned_embeddings_deleted,
+            embedding_flags_cleared,
+            vectors_readded,
         })
     }
 
-    // ========================================================================
-    // INSIGHTS PERSISTENCE
-    // ========================================================================
+    /// Names of the storage locks a panicking holder left poisoned
+    fn poisoned_locks(&self) -> Vec<String> {
+        let mut locks = vec![
+            ("writer", self.writer.is_poisoned()),
+            ("reader", self.reader.is_poisoned()),
+            ("scheduler", self.scheduler.is_poisoned()),
+            ("reconsolidation", self.reconsolidation.is_poisoned()),
+            ("session_intent", self.session_intent.is_poisoned()),
+            ("retention_policy", self.retention_policy.is_poisoned()),
+            ("retrieval_forgetting", self.retrieval_forgetting.is_poisoned()),
+            ("competition", self.competition.is_poisoned()),
+            ("reinforcement", self.reinforcement.is_poisoned()),
+        ];
+        #[cfg(feature = "vector-search")]
+        locks.push(("vector_index", self.vector_index.is_poisoned()));
+        #[cfg(feature = "embeddings")]
+        locks.push(("query_cache", self.query_cache.is_poisoned()));
+        locks
+            .into_iter()
+            .filter(|(_, poisoned)| *poisoned)
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+}
+
+impl Drop for Storage {
+    /// Drain the neighbor queue, which lives only in memory, so a clean
+    /// shutdown does not drop accesses waiting for the next flush
+    fn drop(&mut self) {
+        if self.pending_neighbor_reinforcements() > 0
+            && let Err(e) = self.flush_neighbor_reinforcement()
+        {
+            tracing::warn!("Failed to reinforce neighbors on shutdown: {}", e);
+        }
+    }
+}
+
+/// Cap on memories loaded into a reasoning chain builder
+const MAX_CHAIN_GRAPH_NODES: usize = 500;
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use tempfile::tempdir;
+    use std::cell::RefCell;
+
+    fn create_test_storage() -> Storage {
+        Storage::new_in_memory().unwrap()
+    }
+
+    /// Storage embedding with deterministic mock vectors, no model download
+    #[cfg(feature = "embeddings")]
+    fn create_mock_storage() -> Storage {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        Storage::with_embedding_service(Some(db_path), EmbeddingService::mock()).unwrap()
+    }
+
+    #[test]
+    fn test_in_memory_storage_is_private() {
+        let storage = Storage::with_config(StorageConfig {
+            in_memory: true,
+            disable_embeddings: true,
+            ..Default::default()
+        }).unwrap();
+        let node = storage.ingest(IngestInput {
+            content: "Only this instance sees me".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+        // Read back through the reader connection
+        assert_eq!(storage.get_node(&node.id).unwrap().unwrap().content, node.content);
+        assert_eq!(storage.search("instance", 10).unwrap().len(), 1);
+        assert_eq!(Storage::new_in_memory().unwrap().get_stats().unwrap().total_nodes, 0);
+
+        // The reader waits out an open write instead of seeing its uncommitted rows
+        std::thread::scope(|s| {
+            let (started, wait) = std::sync::mpsc::channel();
+            let storage = &storage;
+            s.spawn(move || {
+                let writer = storage.writer.lock().unwrap();
+                writer.execute_batch("BEGIN; DELETE FROM knowledge_nodes;").unwrap();
+                started.send(()).unwrap();
+                std::thread::sleep(std::time::Duration::from_millis(100));
+                writer.execute_batch("ROLLBACK").unwrap();
+            });
+            wait.recv().unwrap();
+            assert_eq!(storage.get_stats().unwrap().total_nodes, 1);
+        });
+
+        assert!(!storage.is_embedding_ready());
+        #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+        {
+            assert!(matches!(
+                storage.semantic_search("instance", 10, 0.0),
+                Err(StorageError::EmbeddingUnavailable(_))
+            ));
+        }
+    }
+
+    /// In-memory storage whose clock only moves when the test advances it
+    fn create_clocked_storage() -> (Storage, Arc<MockClock>) {
+        use chrono::TimeZone;
+        let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2025, 3, 1, 9, 0, 0).unwrap()));
+        let storage = Storage::with_config(StorageConfig {
+            in_memory: true,
+            disable_embeddings: true,
+            clock: Some(clock.clone()),
+            ..Default::default()
+        }).unwrap();
+        (storage, clock)
+    }
+
+    #[test]
+    fn test_back_to_back_decay_skips_fresh_memories() {
+        let (storage, clock) = create_clocked_storage();
+        const NODES: usize = 10_000;
+        {
+            let mut writer = storage.writer.lock().unwrap();
+            let tx = writer.transaction().unwrap();
+            for i in 0..NODES {
+                let last_accessed = clock.now() - Duration::hours(1 + (i % 2000) as i64);
+                tx.execute(
+                    "INSERT INTO knowledge_nodes (id, content, created_at, updated_at, last_accessed,
+                        stability, sentiment_magnitude)
+                     VALUES (?1, ?2, ?3, ?3, ?4, ?5, ?6)",
+                    params![
+                        format!("node-{:05}", i),
+                        format!("Synthetic memory {}", i),
+                        clock.now().to_rfc3339(),
+                        last_accessed.to_rfc3339(),
+                        1.0 + (i % 50) as f64,
+                        (i % 10) as f64 / 10.0,
+                    ],
+                ).unwrap();
+            }
+            tx.commit().unwrap();
+        }
+        let strengths = |storage: &Storage| -> Vec<(String, f64, f64)> {
+            storage.reader.lock().unwrap()
+                .prepare("SELECT id, retrieval_strength, retention_strength FROM knowledge_nodes ORDER BY id")
+                .unwrap()
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+                .unwrap()
+                .collect::<rusqlite::Result<_>>()
+                .unwrap()
+        };
+
+        assert_eq!(storage.apply_decay().unwrap(), NODES as i32);
+        let first = strengths(&storage);
+
+        // Decayed moments ago: the second run writes nothing and changes nothing
+        assert_eq!(storage.apply_decay().unwrap(), 0);
+        assert_eq!(strengths(&storage), first);
+
+        // Without a freshness window, unchanged strengths are still not rewritten
+        let config = ConsolidationConfig { decay_freshness_minutes: 0, ..Default::default() };
+        storage.set_consolidation_config(&config).unwrap();
+        assert_eq!(storage.apply_decay().unwrap(), 0);
+        assert_eq!(strengths(&storage), first);
+        storage.set_consolidation_config(&ConsolidationConfig::default()).unwrap();
+
+        // Within the window only memories accessed since their last decay are redone
+        clock.advance(Duration::minutes(30));
+        {
+            let writer = storage.writer.lock().unwrap();
+            writer.execute(
+                "UPDATE knowledge_nodes SET last_accessed = ?1 WHERE id = 'node-00042'",
+                params![(clock.now() - Duration::minutes(10)).to_rfc3339()],
+            ).unwrap();
+        }
+        assert_eq!(storage.apply_decay().unwrap(), 1);
+
+        // Once the window passes, everything decays again
+        clock.advance(Duration::hours(2));
+        assert_eq!(storage.apply_decay().unwrap(), NODES as i32);
+    }
+
+    #[test]
+    fn test_mock_clock_drives_decay_and_review_scheduling() {
+        let (storage, clock) = create_clocked_storage();
+        let node = storage.ingest(IngestInput {
+            content: "The staging database is rebuilt every Sunday".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(node.created_at, clock.now());
+
+        // Nothing decays while time stands still
+        assert_eq!(storage.apply_decay().unwrap(), 0);
+
+        clock.advance(Duration::days(30));
+        assert_eq!(storage.apply_decay().unwrap(), 1);
+        let decayed = storage.get_node(&node.id).unwrap().unwrap();
+        let expected = retrievability_with_decay(node.stability, 30.0, DEFAULT_DECAY);
+        assert!((decayed.retrieval_strength - expected).abs() < 1e-9);
+        assert!(decayed.retrieval_strength < node.retrieval_strength);
+
+        // Reviews are stamped and scheduled from the clock's time
+        let reviewed = storage.mark_reviewed(&node.id, Rating::Good).unwrap();
+        assert_eq!(reviewed.last_accessed, clock.now());
+        let next_review = reviewed.next_review.unwrap();
+        assert!(next_review > clock.now());
+        assert_eq!((next_review - clock.now()).num_seconds() % 86_400, 0);
+        assert!(storage.get_review_queue(10).unwrap().is_empty());
+
+        clock.set(next_review);
+        let due = storage.get_review_queue(10).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].id, node.id);
+    }
+
+    #[test]
+    fn test_mock_clock_drives_memory_state_thresholds() {
+        use crate::neuroscience::{DEFAULT_ACTIVE_DECAY_HOURS, DEFAULT_DORMANT_DECAY_DAYS};
+
+        let (storage, clock) = create_clocked_storage();
+        let node = storage.ingest(IngestInput {
+            content: "Cache eviction runs on the hour".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+        let state = || storage.get_memory_state(&node.id).unwrap().unwrap().state;
+
+        assert_eq!(storage.update_memory_states_in(None).unwrap(), 0);
+        assert_eq!(state(), "active");
+
+        // Active -> Dormant exactly at the hour threshold
+        clock.advance(Duration::hours(DEFAULT_ACTIVE_DECAY_HOURS) - Duration::minutes(1));
+        assert_eq!(storage.update_memory_states_in(None).unwrap(), 0);
+        clock.advance(Duration::minutes(1));
+        assert_eq!(storage.update_memory_states_in(None).unwrap(), 1);
+        assert_eq!(state(), "dormant");
+
+        // Dormant -> Silent once the day threshold since last access passes
+        clock.set(node.last_accessed + Duration::days(DEFAULT_DORMANT_DECAY_DAYS) - Duration::hours(1));
+        assert_eq!(storage.update_memory_states_in(None).unwrap(), 0);
+        clock.advance(Duration::hours(1));
+        assert_eq!(storage.update_memory_states_in(None).unwrap(), 1);
+        assert_eq!(state(), "silent");
+        let latest = &storage.get_state_transitions(&node.id, 1).unwrap()[0];
+        assert_eq!(latest.reason_type, "time_decay");
+        assert_eq!(latest.timestamp, clock.now());
+    }
+
+    #[test]
+    fn test_storage_creation() {
+        let storage = create_test_storage();
+        let stats = storage.get_stats().unwrap();
+        assert_eq!(stats.total_nodes, 0);
+    }
+
+    #[test]
+    fn test_ingest_and_get() {
+        let storage = create_test_storage();
+
+        let input = IngestInput {
+            content: "Test memory content".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        };
+
+        let node = storage.ingest(input).unwrap();
+        assert!(!node.id.is_empty());
+        assert_eq!(node.content, "Test memory content");
+
+        let retrieved = storage.get_node(&node.id).unwrap();
+        assert!(retrieved.is_some());
+        assert_eq!(retrieved.unwrap().content, "Test memory content");
+    }
+
+    #[test]
+    fn test_search() {
+        let storage = create_test_storage();
+
+        let input = IngestInput {
+            content: "The mitochondria is the powerhouse of the cell".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        };
+
+        storage.ingest(input).unwrap();
+
+        let results = storage.search("mitochondria", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].content.contains("mitochondria"));
+    }
+
+    #[test]
+    fn test_review() {
+        let storage = create_test_storage();
+
+        let input = IngestInput {
+            content: "Test review".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        };
+
+        let node = storage.ingest(input).unwrap();
+        assert_eq!(node.reps, 0);
+
+        let reviewed = storage.mark_reviewed(&node.id, Rating::Good).unwrap();
+        assert_eq!(reviewed.reps, 1);
+    }
+
+    #[test]
+    fn test_delete() {
+        let storage = create_test_storage();
+
+        let input = IngestInput {
+            content: "To be deleted".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        };
+
+        let node = storage.ingest(input).unwrap();
+        assert!(storage.get_node(&node.id).unwrap().is_some());
+
+        let deleted = storage.delete_node(&node.id).unwrap();
+        assert!(deleted);
+        assert!(storage.get_node(&node.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_dream_history_save_and_get_last() {
+        let storage = create_test_storage();
+        let now = Utc::now();
+
+        let record = DreamHistoryRecord {
+            dreamed_at: now,
+            duration_ms: 1500,
+            memories_replayed: 50,
+            connections_found: 12,
+            insights_generated: 3,
+            memories_strengthened: 8,
+            memories_compressed: 2,
+            phase_nrem1_ms: None,
+            phase_nrem3_ms: None,
+            phase_rem_ms: None,
+            phase_integration_ms: None,
+            summaries_generated: None,
+            emotional_memories_processed: None,
+            creative_connections_found: None,
+        };
+
+        let id = storage.save_dream_history(&record).unwrap();
+        assert!(id > 0);
+
+        let last = storage.get_last_dream().unwrap();
+        assert!(last.is_some());
+        // Timestamps should be within 1 second (RFC3339 round-trip)
+        let diff = (last.unwrap() - now).num_seconds().abs();
+        assert!(diff <= 1, "Timestamp mismatch: diff={}s", diff);
+    }
+
+    #[test]
+    fn test_dream_history_empty() {
+        let storage = create_test_storage();
+        let last = storage.get_last_dream().unwrap();
+        assert!(last.is_none());
+    }
+
+    #[test]
+    fn test_run_dream_cycle_persists_phases_and_clears_tags() {
+        let storage = create_test_storage();
+        let mut ids = Vec::new();
+        for i in 0..6 {
+            let content = if i < 4 {
+                format!("Raising the database connection pool size fixed timeout {}", i)
+            } else {
+                format!("Unrelated note about gardening number {}", i)
+            };
+            let node = storage.ingest(IngestInput {
+                content,
+                node_type: "fact".to_string(),
+                tags: vec![format!("topic-{}", i % 2)],
+                ..Default::default()
+            }).unwrap();
+            ids.push(node.id);
+        }
+        storage.set_waking_tag(&ids[0]).unwrap();
+
+        let result = storage.run_dream_cycle().unwrap();
+        assert_eq!(result.phases.len(), 4);
+        assert_eq!(result.memories_replayed, 6);
+        assert!(!result.insights.is_empty());
+        assert_eq!(storage.get_insights(50).unwrap().len(), result.insights.len());
+        let connection = &result.creative_connections[0];
+        assert!(storage
+            .get_connections_for_memory(&connection.memory_a_id)
+            .unwrap()
+            .iter()
+            .any(|c| c.link_type == connection.connection_type.as_link_type()));
+
+        let reader = storage.reader.lock().unwrap();
+        let (replayed, nrem1, integration): (i32, Option<i64>, Option<i64>) = reader
+            .query_row(
+                "SELECT memories_replayed, phase_nrem1_ms, phase_integration_ms FROM dream_history",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        drop(reader);
+        assert_eq!(replayed, 6);
+        assert!(nrem1.is_some() && integration.is_some());
+        assert!(storage.get_waking_tagged_memories(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_memory_states_persisted_and_reactivated() {
+        let storage = create_test_storage();
+        let node = storage.ingest(IngestInput {
+            content: "Lifecycle test memory about cache eviction".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+        let set_last_accessed = |at: DateTime<Utc>| {
+            let writer = storage.writer.lock().unwrap();
+            writer.execute(
+                "UPDATE knowledge_nodes SET last_accessed = ?1 WHERE id = ?2",
+                params![at.to_rfc3339(), node.id],
+            ).unwrap();
+        };
+
+        set_last_accessed(Utc::now() - Duration::days(2));
+        assert_eq!(storage.update_memory_states_in(None).unwrap(), 1);
+        assert_eq!(storage.get_memory_state(&node.id).unwrap().unwrap().state, "dormant");
+        let transitions = storage.get_state_transitions(&node.id, 10).unwrap();
+        assert_eq!(transitions[0].reason_type, "time_decay");
+        // Nothing changed, nothing recorded
+        assert_eq!(storage.update_memory_states_in(None).unwrap(), 0);
+
+        // Recalled since the last pass: active again
+        set_last_accessed(Utc::now());
+        assert_eq!(storage.update_memory_states_in(None).unwrap(), 1);
+        assert_eq!(storage.get_memory_state(&node.id).unwrap().unwrap().state, "active");
+
+        storage.update_memory_state(&node.id, "silent", "manual_override").unwrap();
+        assert!(storage.reactivate(&node.id).unwrap());
+        assert_eq!(storage.get_memory_state(&node.id).unwrap().unwrap().state, "active");
+        let latest = &storage.get_state_transitions(&node.id, 1).unwrap()[0];
+        assert_eq!((latest.from_state.as_str(), latest.reason_type.as_str()), ("silent", "access"));
+        assert!(!storage.reactivate(&node.id).unwrap());
+        assert!(matches!(storage.reactivate("missing"), Err(StorageError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_recall_can_exclude_unavailable_memories() {
+        let storage = create_test_storage();
+        let node = storage.ingest(IngestInput {
+            content: "Suppressed memory about flaky integration tests".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+        let now = Utc::now();
+        storage.save_memory_state(&MemoryStateRecord {
+            memory_id: node.id.clone(),
+            state: "unavailable".to_string(),
+            last_access: now,
+            access_count: 1,
+            state_entered_at: now,
+            suppression_until: Some(now + Duration::hours(12)),
+            suppressed_by: vec!["winner".to_string()],
+        }).unwrap();
+
+        let recall = |exclude_unavailable: bool| {
+            storage.recall(RecallInput {
+                query: "flaky integration tests".to_string(),
+                search_mode: SearchMode::Keyword,
+                exclude_unavailable,
+                ..Default::default()
+            }).unwrap()
+        };
+        assert_eq!(recall(false).len(), 1);
+        assert!(recall(true).is_empty());
+
+        storage.reactivate(&node.id).unwrap();
+        assert_eq!(recall(true).len(), 1);
+    }
+
+    #[test]
+    fn test_recall_suppresses_competitors_when_enabled() {
+        let storage = create_test_storage();
+        let mut ids = Vec::new();
+        for content in [
+            "Postgres connection pool exhausted during the load test",
+            "Postgres connection pool limit raised after the load test",
+            "Postgres connection pool metrics dashboard",
+            "Lunch order preferences for the offsite",
+        ] {
+            ids.push(storage.ingest(IngestInput {
+                content: content.to_string(),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap().id);
+        }
+        let strength = |id: &str| storage.get_node(id).unwrap().unwrap().retrieval_strength;
+        let before: Vec<f64> = ids.iter().map(|id| strength(id)).collect();
+        let recall = || {
+            storage.recall(RecallInput {
+                query: "postgres connection pool".to_string(),
+                limit: 1,
+                search_mode: SearchMode::Keyword,
+                ..Default::default()
+            }).unwrap()
+        };
+
+        // Off by default
+        let winner = recall()[0].id.clone();
+        assert!(storage.competition_history().is_empty());
+
+        storage.set_retrieval_forgetting_policy(RetrievalForgettingPolicy {
+            enabled: true,
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(recall()[0].id, winner);
+        let history = storage.competition_history();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].winner_id, winner);
+        assert_eq!(history[0].loser_ids.len(), 2);
+        assert_eq!(history[0].query_text.as_deref(), Some("postgres connection pool"));
+        let losers = history[0].loser_ids.clone();
+        for (id, before) in ids.iter().zip(&before) {
+            if losers.contains(id) {
+                assert!((strength(id) - (before - 0.05)).abs() < 1e-9);
+            } else if *id != winner {
+                assert_eq!(strength(id), *before);
+            }
+        }
+
+        // Losers inside their suppression window aren't suppressed again
+        let suppressed: Vec<f64> = losers.iter().map(|id| strength(id)).collect();
+        recall();
+        assert_eq!(storage.competition_history().len(), 1);
+        assert_eq!(losers.iter().map(|id| strength(id)).collect::<Vec<_>>(), suppressed);
+
+        // Once the window passes they can lose again, but never below the floor
+        storage.competition.lock().unwrap().history[0].timestamp = Utc::now() - Duration::days(2);
+        {
+            let writer = storage.writer.lock().unwrap();
+            writer.execute(
+                "UPDATE knowledge_nodes SET retrieval_strength = 0.31 WHERE id = ?1",
+                params![losers[0]],
+            ).unwrap();
+        }
+        recall();
+        assert_eq!(storage.competition_history().len(), 2);
+        assert!((strength(&losers[0]) - 0.3).abs() < 1e-9);
+        assert!((strength(&losers[1]) - (suppressed[1] - 0.05)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_disabled_reinforcement_lets_retention_stay_decayed() {
+        let storage = create_test_storage();
+        let node = storage.ingest(IngestInput {
+            content: "Read on every search".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+        let strengths = || {
+            storage.reader.lock().unwrap().query_row(
+                "SELECT retrieval_strength, retention_strength FROM knowledge_nodes WHERE id = ?1",
+                params![node.id],
+                |row| Ok((row.get::<_, f64>(0)?, row.get::<_, f64>(1)?)),
+            ).unwrap()
+        };
+        let decay = || {
+            storage.writer.lock().unwrap().execute(
+                "UPDATE knowledge_nodes SET retrieval_strength = 0.4, retention_strength = 0.5
+                 WHERE id = ?1",
+                params![node.id],
+            ).unwrap();
+        };
+
+        decay();
+        for _ in 0..30 {
+            storage.strengthen_batch_on_access(&[node.id.as_str()]).unwrap();
+        }
+        let (retrieval, retention) = strengths();
+        assert!(retention > 0.5 && retrieval >= 1.0, "default boosts saturate retrieval");
+
+        storage.set_reinforcement_config(ReinforcementConfig::disabled()).unwrap();
+        decay();
+        for _ in 0..30 {
+            storage.strengthen_batch_on_access(&[node.id.as_str()]).unwrap();
+        }
+        assert_eq!(strengths(), (0.4, 0.5));
+        assert_eq!(storage.get_node(&node.id).unwrap().unwrap().times_retrieved, Some(60));
+    }
+
+    #[test]
+    fn test_batch_strengthen_matches_per_id_strengthen() {
+        let sequential = create_test_storage();
+        let batched = create_test_storage();
+        let seed = |storage: &Storage| -> Vec<String> {
+            (0..20)
+                .map(|i| storage.ingest(crate::testing::seed_input(i)).unwrap().id)
+                .collect()
+        };
+        let seq_ids = seed(&sequential);
+        let batch_ids = seed(&batched);
+        let decay = |storage: &Storage| {
+            storage.writer.lock().unwrap().execute_batch(
+                "UPDATE knowledge_nodes SET retrieval_strength = 0.4, retention_strength = 0.5",
+            ).unwrap();
+        };
+        decay(&sequential);
+        decay(&batched);
+
+        for id in &seq_ids {
+            sequential.strengthen_on_access(id).unwrap();
+        }
+        let refs: Vec<&str> = batch_ids.iter().map(String::as_str).collect();
+        batched.strengthen_batch_on_access(&refs).unwrap();
+
+        let summary = |storage: &Storage| {
+            let mut rows: Vec<(String, i64, i64)> = storage.get_all_nodes(100, 0).unwrap()
+                .into_iter()
+                .map(|n| (
+                    n.content,
+                    (n.retrieval_strength * 1e6).round() as i64,
+                    n.times_retrieved.unwrap_or(0) as i64,
+                ))
+                .collect();
+            rows.sort();
+            rows
+        };
+        assert_eq!(summary(&sequential), summary(&batched));
+        let logged = |storage: &Storage| -> i64 {
+            storage.reader.lock().unwrap()
+                .query_row("SELECT COUNT(*) FROM memory_access_log WHERE access_type = 'search_hit'", [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(logged(&batched), 20);
+        assert_eq!(logged(&sequential), logged(&batched));
+
+        // Neighbors are queued rather than reinforced inline: the first
+        // access drained immediately, later ones wait for the interval or
+        // the next consolidation
+        assert_eq!(sequential.pending_neighbor_reinforcements(), 19);
+        batched.strengthen_batch_on_access(&refs[..5]).unwrap();
+        assert_eq!(batched.pending_neighbor_reinforcements(), 5);
+        batched.run_consolidation().unwrap();
+        assert_eq!(batched.pending_neighbor_reinforcements(), 0);
+
+        // Unknown ids are ignored, as by strengthen_on_access
+        batched.strengthen_batch_on_access(&["missing", "missing"]).unwrap();
+    }
+
+    #[test]
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn test_deferred_neighbor_reinforcement_matches_inline_in_aggregate() {
+        let content = [
+            "Tokio runs async tasks on a work-stealing runtime",
+            "The tokio runtime schedules async tasks across worker threads",
+            "Async tasks in tokio are scheduled by a work-stealing runtime",
+            "Postgres vacuum reclaims dead tuples",
+        ];
+        let setup = |config: ReinforcementConfig| {
+            let storage = create_test_storage();
+            storage.set_reinforcement_config(config).unwrap();
+            let ids: Vec<String> = content.iter()
+                .map(|c| storage.ingest(IngestInput {
+                    content: c.to_string(),
+                    node_type: "fact".to_string(),
+                    ..Default::default()
+                }).unwrap().id)
+                .collect();
+            storage.writer.lock().unwrap().execute_batch(
+                "UPDATE knowledge_nodes SET retrieval_strength = 0.2, retention_strength = 0.2",
+            ).unwrap();
+            (storage, ids)
+        };
+        let uncapped = ReinforcementConfig { neighbor_hourly_cap: 1.0, ..Default::default() };
+        let (inline, inline_ids) = setup(ReinforcementConfig { neighbor_flush_secs: 0, ..uncapped });
+        let (deferred, deferred_ids) = setup(ReinforcementConfig { neighbor_flush_secs: 3600, ..uncapped });
+        deferred.flush_neighbor_reinforcement().unwrap();
+
+        // A burst of recalls of the first memory
+        for _ in 0..5 {
+            inline.strengthen_batch_on_access(&[inline_ids[0].as_str()]).unwrap();
+            deferred.strengthen_batch_on_access(&[deferred_ids[0].as_str()]).unwrap();
+        }
+        assert_eq!(deferred.pending_neighbor_reinforcements(), 5);
+        assert!(deferred.flush_neighbor_reinforcement().unwrap() > 0);
+
+        let strength = |storage: &Storage, id: &str| storage.get_node(id).unwrap().unwrap().retrieval_strength;
+        for (a, b) in inline_ids.iter().zip(&deferred_ids) {
+            assert!((strength(&inline, a) - strength(&deferred, b)).abs() < 1e-6);
+        }
+        assert!(strength(&deferred, &deferred_ids[1]) > 0.2);
+        assert_eq!(strength(&deferred, &deferred_ids[3]), 0.2);
+
+        // The hourly cap stops hot memories' neighbors from snowballing
+        let (capped, capped_ids) = setup(ReinforcementConfig { neighbor_hourly_cap: 0.01, ..uncapped });
+        for _ in 0..5 {
+            capped.strengthen_batch_on_access(&[capped_ids[0].as_str()]).unwrap();
+        }
+        capped.flush_neighbor_reinforcement().unwrap();
+        assert!(strength(&capped, &capped_ids[1]) <= 0.21 + 1e-9);
+    }
+
+    #[test]
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn test_dropping_storage_flushes_queued_neighbor_reinforcement() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("neighbors.db");
+        let open = || Storage::with_embedding_service(Some(db_path.clone()), EmbeddingService::mock()).unwrap();
+
+        let storage = open();
+        storage.set_reinforcement_config(ReinforcementConfig {
+            neighbor_flush_secs: 3600,
+            ..Default::default()
+        }).unwrap();
+        let ids: Vec<String> = [
+            "Tokio runs async tasks on a work-stealing runtime",
+            "The tokio runtime schedules async tasks across worker threads",
+        ].iter()
+            .map(|c| storage.ingest(IngestInput {
+                content: c.to_string(),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap().id)
+            .collect();
+        storage.writer.lock().unwrap().execute_batch(
+            "UPDATE knowledge_nodes SET retrieval_strength = 0.2, retention_strength = 0.2",
+        ).unwrap();
+        storage.flush_neighbor_reinforcement().unwrap();
+        storage.strengthen_batch_on_access(&[ids[0].as_str()]).unwrap();
+        assert_eq!(storage.pending_neighbor_reinforcements(), 1);
+        drop(storage);
+
+        let reopened = open();
+        assert!(reopened.get_node(&ids[1]).unwrap().unwrap().retrieval_strength > 0.2);
+    }
+
+    #[test]
+    fn test_recall_impressions_are_weaker_than_touch() {
+        let storage = create_test_storage();
+        let ids: Vec<String> = (0..3)
+            .map(|i| {
+                storage.ingest(IngestInput {
+                    content: format!("Impression target {} about tokio runtimes", i),
+                    node_type: "fact".to_string(),
+                    ..Default::default()
+                }).unwrap().id
+            })
+            .collect();
+        let reset = || {
+            storage.writer.lock().unwrap().execute_batch(
+                "UPDATE knowledge_nodes SET retrieval_strength = 0.5, retention_strength = 0.5,
+                     last_accessed = '2020-01-01T00:00:00+00:00';
+                 DELETE FROM memory_access_log;",
+            ).unwrap();
+        };
+        let state = |id: &str| {
+            let node = storage.get_node(id).unwrap().unwrap();
+            (node.retrieval_strength, node.last_accessed)
+        };
+        let accesses = || {
+            storage.reader.lock().unwrap()
+                .query_row("SELECT COUNT(*) FROM memory_access_log", [], |row| row.get::<_, i64>(0))
+                .unwrap()
+        };
+        let query = || RecallInput {
+            query: "tokio runtimes".to_string(),
+            limit: 10,
+            search_mode: SearchMode::Keyword,
+            ..Default::default()
+        };
+
+        reset();
+        assert_eq!(storage.recall_without_strengthen(query()).unwrap().len(), 3);
+        assert_eq!(state(&ids[0]).0, 0.5);
+
+        assert_eq!(storage.recall(query()).unwrap().len(), 3);
+        let (impressed, last_accessed) = state(&ids[0]);
+        assert!((impressed - 0.505).abs() < 1e-9, "an impression is 10% of an access");
+        assert_eq!(last_accessed.format("%Y").to_string(), "2020", "impressions don't reset decay");
+        assert_eq!(accesses(), 0, "impressions aren't logged as accesses");
+
+        storage.touch(&ids[1]).unwrap();
+        let (opened, last_accessed) = state(&ids[1]);
+        assert!((opened - 0.555).abs() < 1e-9);
+        assert!(last_accessed > Utc::now() - Duration::minutes(1));
+        assert_eq!(accesses(), 1);
+        assert!(matches!(storage.touch("missing"), Err(StorageError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_forecast_counts_due_reviews_and_decays_retention() {
+        let storage = create_test_storage();
+        let ids: Vec<String> = (0..3)
+            .map(|i| {
+                storage.ingest(IngestInput {
+                    content: format!("Forecast memory {}", i),
+                    node_type: "fact".to_string(),
+                    ..Default::default()
+                }).unwrap().id
+            })
+            .collect();
+        let now = Utc::now();
+        {
+            let writer = storage.writer.lock().unwrap();
+            for (id, due_in, idle) in [(&ids[0], 3, 2), (&ids[1], -2, 10), (&ids[2], 40, 0)] {
+                writer.execute(
+                    "UPDATE knowledge_nodes SET next_review = ?1, last_accessed = ?2 WHERE id = ?3",
+                    params![
+                        (now + Duration::days(due_in)).to_rfc3339(),
+                        (now - Duration::days(idle)).to_rfc3339(),
+                        id
+                    ],
+                ).unwrap();
+            }
+        }
+
+        let forecast = storage.forecast(7).unwrap();
+        assert_eq!(forecast.total_memories, 3);
+        assert_eq!(forecast.days.len(), 8);
+        assert_eq!(forecast.days[0].date, now.date_naive().format("%Y-%m-%d").to_string());
+        assert_eq!(forecast.days[0].due_count, 1, "overdue reviews are due today");
+        assert_eq!(forecast.days[3].due_count, 1);
+        assert_eq!(forecast.days.iter().map(|d| d.due_count).sum::<i64>(), 2);
+        for pair in forecast.days.windows(2) {
+            assert!(pair[1].projected_avg_retention <= pair[0].projected_avg_retention);
+        }
+        assert!(forecast.days[7].projected_avg_retention < forecast.days[0].projected_avg_retention);
+        assert!(forecast.days[0].projected_avg_retention <= 1.0);
+    }
+
+    #[test]
+    fn test_access_analytics_aggregates_seeded_log() {
+        let storage = create_test_storage();
+        let now = Utc::now();
+        let mut ids = Vec::new();
+        for content in ["Deploy runbook", "Flaky test notes", "Onboarding checklist", "Never read"] {
+            ids.push(storage.ingest(IngestInput {
+                content: content.to_string(),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap().id);
+        }
+        {
+            let writer = storage.writer.lock().unwrap();
+            writer.execute(
+                "UPDATE knowledge_nodes SET created_at = ?1",
+                params![(now - Duration::days(10)).to_rfc3339()],
+            ).unwrap();
+            let log = |id: &str, access_type: &str, ago: Duration| {
+                writer.execute(
+                    "INSERT INTO memory_access_log (node_id, access_type, accessed_at)
+                     VALUES (?1, ?2, ?3)",
+                    params![id, access_type, (now - ago).to_rfc3339()],
+                ).unwrap();
+            };
+            log(&ids[0], "search_hit", Duration::days(8));
+            log(&ids[0], "search_hit", Duration::days(2));
+            log(&ids[0], "search_hit", Duration::days(1));
+            log(&ids[0], "promote", Duration::days(1));
+            log(&ids[1], "search_hit", Duration::days(9));
+            log(&ids[1], "demote", Duration::days(3));
+            log(&ids[2], "search_hit", Duration::days(4));
+            // Past the log's retention
+            log(&ids[3], "search_hit", Duration::days(120));
+        }
+
+        let month = storage.access_analytics(Duration::days(30)).unwrap();
+        assert!(!month.window_truncated);
+        assert_eq!(month.total_accesses, 7);
+        let top: Vec<(&str, i64)> = month.top_accessed.iter().map(|m| (m.id.as_str(), m.accesses)).collect();
+        assert_eq!(top, vec![(ids[0].as_str(), 4), (ids[1].as_str(), 2), (ids[2].as_str(), 1)]);
+        assert_eq!(month.top_accessed[0].preview, "Deploy runbook");
+        assert_eq!(month.accesses_by_type["search_hit"], 5);
+        assert_eq!(month.promote_ratio, Some(0.5));
+        assert_eq!(month.accesses_by_day.len(), 6);
+        assert_eq!(month.accesses_by_day.last().unwrap().accesses, 2);
+        assert_eq!(month.accesses_by_day.iter().map(|d| d.accesses).sum::<i64>(), 7);
+        assert!((month.never_accessed_pct - 25.0).abs() < 1e-9);
+        // First accesses 48h, 24h and 144h after creation
+        assert!((month.median_hours_to_first_access.unwrap() - 48.0).abs() < 0.01);
+
+        let year = storage.access_analytics(Duration::days(365)).unwrap();
+        assert!(year.window_truncated);
+        assert_eq!(year.total_accesses, 7);
+
+        let recent = storage.access_analytics(Duration::hours(60)).unwrap();
+        assert_eq!(recent.total_accesses, 3);
+        assert!((recent.never_accessed_pct - 75.0).abs() < 1e-9);
+        assert!(recent.median_hours_to_first_access.is_none());
+
+        assert!(storage.access_analytics(Duration::zero()).is_err());
+    }
+
+    #[test]
+    fn test_graded_feedback_scales_deltas_and_logs_reasons() {
+        let storage = create_test_storage();
+        let node = |content: &str| storage.ingest(IngestInput {
+            content: content.to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+        let full = node("Release branches are cut on Tuesdays");
+        let slight = node("Staging mirrors production data weekly");
+        let wrong = node("The API rate limit is 10 requests per second");
+        {
+            let writer = storage.writer.lock().unwrap();
+            writer.execute(
+                "UPDATE knowledge_nodes SET retrieval_strength = 0.5, retention_strength = 0.5, stability = 10.0",
+                [],
+            ).unwrap();
+        }
+
+        let promoted = storage.promote_memory(&full.id).unwrap();
+        assert!((promoted.retrieval_strength - 0.7).abs() < 1e-9);
+        assert!((promoted.stability - 15.0).abs() < 1e-9);
+
+        let slightly = storage.promote_memory_with(&slight.id, &FeedbackOptions {
+            strength: Some(0.25),
+            reason: Some("  slightly helpful  ".to_string()),
+            ..Default::default()
+        }).unwrap();
+        assert!((slightly.retrieval_strength - 0.55).abs() < 1e-6);
+        assert!((slightly.retention_strength - 0.525).abs() < 1e-6);
+        assert!((slightly.stability - 11.25).abs() < 1e-6);
+        assert!(storage.promote_memory_with(&slight.id, &FeedbackOptions {
+            strength: Some(1.5),
+            ..Default::default()
+        }).is_err());
+
+        let correction = FeedbackOptions {
+            reason: Some("Limit was raised to 100 req/s".to_string()),
+            invalidate: true,
+            ..Default::default()
+        };
+        assert!(storage.demote_memory_with(&wrong.id, &FeedbackOptions {
+            reason: None,
+            ..correction.clone()
+        }).is_err());
+        let demoted = storage.demote_memory_with(&wrong.id, &correction).unwrap();
+        assert!((demoted.retrieval_strength - 0.2).abs() < 1e-9);
+        assert!(demoted.valid_until.is_some());
+        assert!(storage.get_node(&full.id).unwrap().unwrap().valid_until.is_none());
+
+        let feedback = storage.access_analytics(Duration::days(1)).unwrap().recent_feedback;
+        let reasons: Vec<(&str, &str, &str)> = feedback
+            .iter()
+            .map(|f| (f.node_id.as_str(), f.action.as_str(), f.reason.as_str()))
+            .collect();
+        assert_eq!(reasons, vec![
+            (wrong.id.as_str(), "demote", "Limit was raised to 100 req/s"),
+            (slight.id.as_str(), "promote", "slightly helpful"),
+        ]);
+
+        // The log records the strength each feedback was given at
+        let logged = |id: &str| storage.get_access_history(id, 1).unwrap()[0].weight;
+        assert_eq!(logged(&slight.id), 0.25);
+        assert_eq!(logged(&full.id), 1.0);
+    }
+
+    #[test]
+    fn test_record_outcome_rewards_penalizes_and_persists() {
+        let storage = create_test_storage();
+        let node = |content: &str| storage.ingest(IngestInput {
+            content: content.to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+        let good = node("Deploys go through the blue/green pipeline");
+        let bad = node("Deploys can skip the migration check");
+        {
+            let writer = storage.writer.lock().unwrap();
+            writer.execute(
+                "UPDATE knowledge_nodes SET retrieval_strength = 0.5, retention_strength = 0.5,
+                    stability = 10.0, next_review = ?1",
+                params![(Utc::now() + Duration::days(7)).to_rfc3339()],
+            ).unwrap();
+        }
+
+        let rewarded = storage
+            .record_outcome(&[&good.id], OutcomeType::VeryHelpful, 1.0, Some("deploy succeeded".into()))
+            .unwrap();
+        // Stronger than a plain promote (+0.2 retrieval, 1.5x stability)
+        assert!((rewarded[0].retrieval_strength - 0.75).abs() < 1e-9);
+        assert!((rewarded[0].stability - 17.5).abs() < 1e-9);
+        assert!((rewarded[0].importance.unwrap() - 0.7).abs() < 1e-9);
+
+        let penalized = storage
+            .record_outcome(&[&bad.id], OutcomeType::Harmful, 0.5, Some("build failed".into()))
+            .unwrap();
+        assert!((penalized[0].retrieval_strength - 0.35).abs() < 1e-9);
+        assert!((penalized[0].importance.unwrap() - 0.4).abs() < 1e-9);
+        assert!(penalized[0].next_review.unwrap() <= Utc::now(), "flagged for review");
+
+        assert!(matches!(
+            storage.record_outcome(&[&good.id], OutcomeType::Helpful, 1.5, None),
+            Err(StorageError::InvalidInput { field: "magnitude", .. })
+        ));
+        // An unknown id rolls back the whole report
+        assert!(matches!(
+            storage.record_outcome(&[&good.id, "missing"], OutcomeType::Helpful, 1.0, None),
+            Err(StorageError::NotFound(_))
+        ));
+
+        let outcomes = storage.get_outcomes(&good.id, 10).unwrap();
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].outcome, OutcomeType::VeryHelpful);
+        assert_eq!(outcomes[0].note.as_deref(), Some("deploy succeeded"));
+        let bad_outcomes = storage.get_outcomes(&bad.id, 10).unwrap();
+        assert_eq!(bad_outcomes[0].outcome, OutcomeType::Harmful);
+        assert!((bad_outcomes[0].magnitude - 0.5).abs() < 1e-6);
+
+        let reader = storage.reader.lock().unwrap();
+        let logged: Vec<String> = reader
+            .prepare("SELECT access_type FROM memory_access_log WHERE access_type LIKE 'outcome_%' ORDER BY id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(logged, vec!["outcome_very_helpful", "outcome_harmful"]);
+        let usage: Vec<bool> = reader
+            .prepare("SELECT was_helpful FROM usage_events WHERE context LIKE 'outcome:%' ORDER BY id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(usage, vec![true, false]);
+    }
+
+    #[test]
+    fn test_pins_exempt_from_decay_and_gc_expire_and_persist() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("pins.db");
+        let storage = Storage::new(Some(db_path.clone())).unwrap();
+        let node = |content: &str| storage.ingest(IngestInput {
+            content: content.to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+        let pinned = node("The on-call rotation handbook lives in the ops wiki");
+        let expiring = node("The release freeze starts next Monday");
+        let loose = node("The staging database was reset last week");
+
+        storage.pin_memory(&pinned.id, None).unwrap();
+        storage.pin_memory(&expiring.id, Some(Utc::now() + Duration::days(1))).unwrap();
+        assert!(matches!(
+            storage.pin_memory(&loose.id, Some(Utc::now() - Duration::hours(1))),
+            Err(StorageError::InvalidInput { field: "until", .. })
+        ));
+        assert!(matches!(storage.pin_memory("missing", None), Err(StorageError::NotFound(_))));
+        assert!(storage.is_pinned(&pinned.id).unwrap());
+        assert!(!storage.is_pinned(&loose.id).unwrap());
+
+        // Age everything and make it GC-eligible
+        {
+            let writer = storage.writer.lock().unwrap();
+            writer.execute(
+                "UPDATE knowledge_nodes SET last_accessed = ?1, created_at = ?1,
+                    retrieval_strength = 0.9, retention_strength = 0.9",
+                params![(Utc::now() - Duration::days(60)).to_rfc3339()],
+            ).unwrap();
+        }
+        assert_eq!(storage.apply_decay().unwrap(), 1);
+        let decayed = storage.get_node(&loose.id).unwrap().unwrap();
+        assert!(decayed.retrieval_strength < 0.9);
+        let held = storage.get_node(&pinned.id).unwrap().unwrap();
+        assert!((held.retrieval_strength - 0.9).abs() < 1e-9);
+
+        {
+            let writer = storage.writer.lock().unwrap();
+            writer.execute("UPDATE knowledge_nodes SET retention_strength = 0.01", []).unwrap();
+        }
+        assert_eq!(storage.gc_below_retention(0.1, 0).unwrap(), 1);
+        assert!(storage.get_node(&loose.id).unwrap().is_none());
+
+        // Pins survive a restart
+        drop(storage);
+        let storage = Storage::new(Some(db_path)).unwrap();
+        let ids: Vec<String> = storage.list_pinned().unwrap().into_iter().map(|p| p.node.id).collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&pinned.id) && ids.contains(&expiring.id));
+
+        // A lapsed TTL drops out of the list at once and is removed by consolidation
+        {
+            let writer = storage.writer.lock().unwrap();
+            writer.execute(
+                "UPDATE memory_pins SET pinned_until = ?1 WHERE memory_id = ?2",
+                params![(Utc::now() - Duration::minutes(1)).to_rfc3339(), expiring.id],
+            ).unwrap();
+        }
+        let listed = storage.list_pinned().unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].node.id, pinned.id);
+        assert!(listed[0].pinned_until.is_none());
+        assert!(!storage.is_pinned(&expiring.id).unwrap());
+        // Even with decay disabled, since expiry runs ahead of the steps
+        let mut config = storage.consolidation_config().unwrap();
+        config.enabled_steps.remove("decay");
+        storage.set_consolidation_config(&config).unwrap();
+        assert_eq!(storage.run_consolidation().unwrap().pins_expired, 1);
+        assert_eq!(storage.expire_pins().unwrap(), 0, "consolidation already expired it");
+        let remaining: i64 = storage.reader.lock().unwrap()
+            .query_row("SELECT COUNT(*) FROM memory_pins", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(remaining, 1);
+
+        assert!(storage.unpin_memory(&pinned.id).unwrap());
+        assert!(!storage.unpin_memory(&pinned.id).unwrap());
+        assert!(storage.list_pinned().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_pin_ttl_expiry_clears_user_starred() {
+        use crate::neuroscience::hippocampal_index::HippocampalIndex;
+
+        let (storage, clock) = create_clocked_storage();
+        let node = |content: &str| storage.ingest(IngestInput {
+            content: content.to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+        let pinned = node("The escalation policy lives in the ops wiki");
+        let expiring = node("The release freeze lasts one week");
+        storage.pin_memory(&pinned.id, None).unwrap();
+        storage.pin_memory(&expiring.id, Some(clock.now() + Duration::days(7))).unwrap();
+
+        // A freshly loaded index picks the stars up from the stored pins
+        let index = HippocampalIndex::new();
+        for memory in [&pinned, &expiring] {
+            index
+                .index_memory(&memory.id, &memory.content, "fact", memory.created_at, None)
+                .unwrap();
+        }
+        assert_eq!(index.sync_user_starred(&storage.active_pin_ids().unwrap()).unwrap(), 2);
+        let starred = |id: &str| {
+            index.get_index(id).unwrap().unwrap().importance_flags.is_user_starred()
+        };
+        assert!(starred(&pinned.id) && starred(&expiring.id));
+
+        // Once the TTL lapses and consolidation expires the pin, the star goes too
+        clock.advance(Duration::days(8));
+        storage.run_consolidation().unwrap();
+        assert_eq!(index.sync_user_starred(&storage.active_pin_ids().unwrap()).unwrap(), 1);
+        assert!(starred(&pinned.id));
+        assert!(!starred(&expiring.id));
+        assert_eq!(index.sync_user_starred(&storage.active_pin_ids().unwrap()).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_count_memories_since() {
+        let storage = create_test_storage();
+        let before = Utc::now() - Duration::seconds(10);
+
+        for i in 0..5 {
+            storage.ingest(IngestInput {
+                content: format!("Count test memory {}", i),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap();
+        }
+
+        let count = storage.count_memories_since(before).unwrap();
+        assert_eq!(count, 5);
+
+        let future = Utc::now() + Duration::hours(1);
+        let count_future = storage.count_memories_since(future).unwrap();
+        assert_eq!(count_future, 0);
+    }
+
+    #[test]
+    fn test_get_last_backup_timestamp_no_panic() {
+        // Static method should not panic even if no backups exist
+        let _ = Storage::get_last_backup_timestamp();
+    }
+
+    #[test]
+    fn test_cross_project_patterns_surface_for_new_project() {
+        let storage = create_test_storage();
+
+        for project in ["alpha", "beta"] {
+            storage.ingest(IngestInput {
+                content: "Prefer builder pattern for config structs".to_string(),
+                node_type: "pattern".to_string(),
+                tags: vec!["pattern".to_string(), format!("codebase:{}", project)],
+                on_duplicate: DuplicatePolicy::CreateAnyway,
+                ..Default::default()
+            }).unwrap();
+        }
+
+        storage.run_consolidation().unwrap();
+        // A second run merges into the stored patterns rather than duplicating them
+        storage.run_consolidation().unwrap();
+
+        let patterns = storage.get_universal_patterns(0).unwrap();
+        let builder = patterns
+            .iter()
+            .find(|p| p.id == "auto-architecture-builder")
+            .expect("builder pattern should be persisted");
+        assert_eq!(builder.projects_seen_in, vec!["alpha", "beta"]);
+        assert_eq!(patterns.iter().filter(|p| p.id == builder.id).count(), 1);
+
+        // Re-running over the same memories does not inflate support; a new
+        // observation does
+        let supported = |min| {
+            storage.get_universal_patterns(min).unwrap()
+                .iter()
+                .any(|p| p.id == "auto-architecture-builder")
+        };
+        assert!(supported(2));
+        assert!(!supported(3));
+        storage.ingest(IngestInput {
+            content: "Use the builder pattern for request options".to_string(),
+            node_type: "pattern".to_string(),
+            tags: vec!["pattern".to_string(), "codebase:beta".to_string()],
+            ..Default::default()
+        }).unwrap();
+        storage.run_consolidation().unwrap();
+        assert!(supported(3));
+
+        let gamma = ProjectContext {
+            name: Some("gamma".to_string()),
+            ..Default::default()
+        };
+        let applicable = storage.get_applicable_knowledge(&gamma).unwrap();
+        let knowledge = applicable
+            .iter()
+            .find(|k| k.pattern.id == "auto-architecture-builder")
+            .expect("builder pattern should apply to a third project");
+        assert_eq!(knowledge.supporting_memories.len(), 3);
+
+        // Projects that contributed the pattern already know it
+        let alpha = ProjectContext {
+            name: Some("alpha".to_string()),
+            ..Default::default()
+        };
+        assert!(storage.get_applicable_knowledge(&alpha).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_universal_patterns_merge_by_signature() {
+        use crate::advanced::cross_project::{CodePattern, PatternCategory};
+
+        let storage = create_test_storage();
+        let now = Utc::now();
+        let observed_in = |project: &str| UniversalPattern {
+            id: "auto-errorhandling-context".to_string(),
+            pattern: CodePattern {
+                name: "Attach context to errors".to_string(),
+                category: PatternCategory::ErrorHandling,
+                description: "Wrap errors with the operation that failed".to_string(),
+                example: None,
+                triggers: vec![],
+                benefits: vec![],
+                considerations: vec![],
+            },
+            projects_seen_in: vec![project.to_string()],
+            success_rate: 0.5,
+            applicability: String::new(),
+            confidence: 0.5,
+            first_seen: now,
+            last_seen: now,
+            application_count: 0,
+        };
+        let support = || {
+            let reader = storage.reader.lock().unwrap();
+            reader
+                .query_row(
+                    "SELECT COUNT(*), MAX(support) FROM universal_patterns",
+                    [],
+                    |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+                )
+                .unwrap()
+        };
+
+        storage
+            .save_universal_pattern(&observed_in("alpha"), &["m1".to_string()])
+            .unwrap();
+        assert_eq!(support(), (1, 1));
+
+        // Re-observing the same signature merges into one row and bumps support,
+        // but only for memories it hasn't counted yet
+        storage
+            .save_universal_pattern(&observed_in("beta"), &["m1".to_string(), "m2".to_string()])
+            .unwrap();
+        assert_eq!(support(), (1, 2));
+        storage
+            .save_universal_pattern(&observed_in("beta"), &["m2".to_string()])
+            .unwrap();
+        assert_eq!(support(), (1, 2));
+        assert_eq!(storage.get_universal_patterns(2).unwrap().len(), 1);
+        assert!(storage.get_universal_patterns(3).unwrap().is_empty());
+
+        // Source projects are kept for provenance
+        let stored = &storage.get_universal_patterns(0).unwrap()[0];
+        assert_eq!(stored.projects_seen_in, vec!["alpha", "beta"]);
+
+        let gamma = ProjectContext {
+            name: Some("gamma".to_string()),
+            ..Default::default()
+        };
+        let applicable = storage.applicable_to(&gamma).unwrap();
+        assert_eq!(applicable.len(), 1);
+        assert_eq!(
+            applicable[0].pattern.projects_seen_in,
+            vec!["alpha", "beta"]
+        );
+        assert_eq!(applicable[0].supporting_memories, vec!["m1", "m2"]);
+        assert!(applicable[0].match_reason.contains("alpha, beta"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn test_hybrid_search_rerank_weights() {
+        let storage = create_test_storage();
+        storage.ingest(IngestInput {
+            content: "Rerank weights control recency versus relevance".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+
+        let relevance_only = HybridSearchConfig {
+            recency_weight: 0.0,
+            importance_weight: 0.0,
+            relevance_weight: 1.0,
+            trust_weight: 0.0,
+            explain: true,
+            ..Default::default()
+        };
+        let results = storage.hybrid_search_with_config("rerank", 10, &relevance_only).unwrap();
+        assert_eq!(results.len(), 1);
+        let breakdown = results[0].score_breakdown.as_ref().unwrap();
+        assert_eq!(breakdown.recency_term, 0.0);
+        assert_eq!(breakdown.importance_term, 0.0);
+        assert!((breakdown.final_score - breakdown.relevance).abs() < 1e-9);
+
+        let invalid = HybridSearchConfig {
+            relevance_weight: 0.9,
+            ..Default::default()
+        };
+        assert!(storage.hybrid_search_with_config("rerank", 10, &invalid).is_err());
+    }
+
+    #[test]
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn test_explain_ranking_covers_misses() {
+        let storage = create_test_storage();
+        let mut ids = Vec::new();
+        for content in [
+            "Deploys run through the deploy pipeline after deploy review",
+            "The deploy pipeline needs a green build",
+            "Friday deploy freeze applies to the deploy pipeline",
+            "Lunch is at noon",
+        ] {
+            let node = storage.ingest(IngestInput {
+                content: content.to_string(),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap();
+            ids.push(node.id);
+        }
+
+        let config = HybridSearchConfig { explain: true, ..Default::default() };
+        let results = storage.hybrid_search_with_config("deploy", 1, &config).unwrap();
+        let top = results[0].score_breakdown.as_ref().unwrap();
+        assert!(top.fts_rank.unwrap() < 0.0);
+        assert_eq!(top.keyword_rank, Some(1));
+        let explained = storage
+            .explain_ranking_with_config("deploy", &results[0].node.id, 1, &config)
+            .unwrap();
+        assert!((explained.final_score - top.final_score).abs() < 1e-6);
+
+        // Every match outside the top result still gets a keyword score
+        for id in &ids[..3] {
+            let explained = storage.explain_ranking_with_config("deploy", id, 1, &config).unwrap();
+            assert!(explained.fts_rank.is_some());
+            assert!(explained.keyword_score.unwrap() > 0.0);
+            assert!(explained.final_score <= top.final_score + 1e-6);
+        }
+        let unrelated = storage.explain_ranking("deploy", &ids[3]).unwrap();
+        assert_eq!(unrelated.fts_rank, None);
+        assert_eq!(unrelated.keyword_rank, None);
+
+        let json = serde_json::to_value(&unrelated).unwrap();
+        assert!(json.get("hydeExpanded").is_some());
+        assert!(matches!(
+            storage.explain_ranking("deploy", "missing"),
+            Err(StorageError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn test_explain_query_expansion() {
+        use crate::search::QueryIntent;
+
+        let storage = create_test_storage();
+
+        let (intent, variants) = storage.explain_query_expansion("how to rotate api keys");
+        assert_eq!(intent, QueryIntent::HowTo);
+        assert!(variants.len() > 1);
+        assert_eq!(variants[0], "how to rotate api keys");
+
+        // Technical queries are embedded as-is
+        let (intent, variants) = storage.explain_query_expansion("Storage::ingest");
+        assert_eq!(intent, QueryIntent::Technical);
+        assert_eq!(variants, vec!["Storage::ingest".to_string()]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn test_query_expansion_modes() {
+        let storage = create_test_storage();
+        storage.ingest(IngestInput {
+            content: "how to rotate api keys without downtime".to_string(),
+            node_type: "procedure".to_string(),
+            ..Default::default()
+        }).unwrap();
+        let query = "how to rotate api keys";
+
+        let auto = HybridSearchConfig::default();
+        let off = HybridSearchConfig {
+            query_expansion: QueryExpansionMode::Off,
+            ..Default::default()
+        };
+        let (plan, results) = storage.hybrid_search_with_expansion(query, 5, &off).unwrap();
+        assert_eq!(plan.intent, hyde::QueryIntent::HowTo);
+        assert!(!plan.expanded);
+        assert_eq!(plan.variants, vec![query.to_string()]);
+        assert_eq!(results.len(), 1);
+
+        let (plan, _) = storage.hybrid_search_with_expansion(query, 5, &auto).unwrap();
+        if !storage.is_embedding_ready() {
+            // Keyword-only search never expands
+            assert!(!plan.expanded);
+            return; // Needs the local embedding model
+        }
+        assert!(plan.expanded);
+        assert!(storage.cached_query_embedding(query, true).unwrap().is_some());
+
+        // Off is the pre-HyDE literal-query search: the raw query embedding
+        // looked up in the index, with no planning or expansion involved
+        let raw = storage
+            .embedding_service
+            .embed_truncated(query, storage.index_dimensions())
+            .unwrap()
+            .vector;
+        let literal: Vec<(String, f32)> = storage
+            .vector_index
+            .lock()
+            .unwrap()
+            .search_with_threshold(&raw, 5, 0.0)
+            .unwrap();
+        let (_, unexpanded) = storage
+            .semantic_search_with_expansion(query, 5, 0.0, QueryExpansionMode::Off)
+            .unwrap();
+        let unexpanded: Vec<(String, f32)> =
+            unexpanded.into_iter().map(|r| (r.node.id, r.similarity)).collect();
+        assert_eq!(literal, unexpanded);
+    }
+
+    #[test]
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn test_hybrid_search_with_intent_boosts_bug_fixes() {
+        let storage = create_test_storage();
+        let concept = storage.ingest(IngestInput {
+            content: "Why is scheduler panicking? Explains why is scheduler panicking: a panic unwinds the thread".to_string(),
+            node_type: "concept".to_string(),
+            ..Default::default()
+        }).unwrap();
+        let bug_fix = storage.ingest(IngestInput {
+            content: "Fixed the long standing report of why is scheduler panicking when the work queue drains empty during startup".to_string(),
+            node_type: "bug_fix".to_string(),
+            ..Default::default()
+        }).unwrap();
+
+        let config = HybridSearchConfig::default();
+        let query = "why is scheduler panicking";
+
+        // Without intent bias the concept ranks first on keyword relevance
+        // (keyword search matches the query as a phrase)
+        let plain = storage.hybrid_search_with_config(query, 10, &config).unwrap();
+        assert_eq!(plain.len(), 2);
+        let plain_order: Vec<_> = plain.iter().map(|r| r.node.id.clone()).collect();
+
+        let (detection, results) = storage.hybrid_search_with_intent(query, 10, &config).unwrap();
+        assert_eq!(detection.primary_intent.kind(), "debugging");
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].node.id, bug_fix.id);
+        assert_eq!(results[1].node.id, concept.id);
+        assert_eq!(plain_order[0], concept.id, "boost should change the ordering");
+
+        // The boost is its own term in the breakdown, and the terms add up
+        let explain = HybridSearchConfig { explain: true, ..config };
+        let (_, results) = storage.hybrid_search_with_intent(query, 10, &explain).unwrap();
+        let breakdown = results[0].score_breakdown.as_ref().unwrap();
+        assert!(breakdown.intent_boost > 0.0);
+        let terms = breakdown.recency_term
+            + breakdown.importance_term
+            + breakdown.relevance_term
+            + breakdown.trust_term
+            + breakdown.pin_bonus
+            + breakdown.intent_boost;
+        assert!((breakdown.final_score - terms).abs() < 1e-9);
+        assert!((breakdown.final_score - results[0].combined_score as f64).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_reasoning_chain_persistence() {
+        let storage = create_test_storage();
+        let ids: Vec<String> = ["Cache misses slow the API", "The cache is evicted on deploy", "Deploys happen hourly"]
+            .iter()
+            .map(|content| storage.ingest(IngestInput {
+                content: content.to_string(),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap().id)
+            .collect();
+        let now = Utc::now();
+        for (source, target, link_type) in [(&ids[1], &ids[0], "causal"), (&ids[2], &ids[1], "causal")] {
+            storage.save_connection(&ConnectionRecord {
+                source_id: source.clone(),
+                target_id: target.clone(),
+                strength: 0.8,
+                link_type: link_type.to_string(),
+                created_at: now,
+                last_activated: now,
+                activation_count: 0,
+            }).unwrap();
+        }
+
+        // Connections are followed in both directions, but only within the hop limit
+        assert!(storage.build_reasoning_chain(&ids[0], &ids[2], 1).unwrap().is_none());
+        let chain = storage.build_reasoning_chain(&ids[0], &ids[2], 3).unwrap().unwrap();
+        assert_eq!(chain.path_ids(), ids);
+
+        let chain_id = storage.save_reasoning_chain(&chain).unwrap();
+        let saved = storage.get_chain(&chain_id).unwrap().unwrap();
+        assert_eq!(saved.chain.path_ids(), ids);
+        assert_eq!(saved.chain.steps[0].connection_type, ConnectionType::Causal);
+        assert_eq!(storage.get_chains_for_memory(&ids[1]).unwrap().len(), 1);
+        assert!(storage.get_chain("missing").unwrap().is_none());
+        assert!(storage.build_reasoning_chain(&ids[0], "missing", 3).is_err());
+    }
+
+    #[test]
+    fn test_build_chain_found_missing_and_too_long() {
+        let storage = create_test_storage();
+        let ingest = |content: &str| {
+            storage
+                .ingest(IngestInput {
+                    content: content.to_string(),
+                    node_type: "fact".to_string(),
+                    ..Default::default()
+                })
+                .unwrap()
+                .id
+        };
+        let ids: Vec<String> = [
+            "Slow queries time out the checkout",
+            "The orders table lost its index",
+            "A migration dropped the index",
+            "Migrations run without review",
+        ]
+        .iter()
+        .map(|content| ingest(content))
+        .collect();
+        let island = ingest("The office plants need watering");
+        let now = Utc::now();
+        for pair in ids.windows(2) {
+            storage
+                .save_connection(&ConnectionRecord {
+                    source_id: pair[1].clone(),
+                    target_id: pair[0].clone(),
+                    strength: 0.8,
+                    link_type: "causal".to_string(),
+                    created_at: now,
+                    last_activated: now,
+                    activation_count: 0,
+                })
+                .unwrap();
+        }
 
-    /// Save an insight to the database
-    pub fn save_insight(&self, insight: &InsightRecord) -> Result<()> {
-        let source_json = serde_json::to_string(&insight.source_memories).unwrap_or_else(|_| "[]".to_string());
-        let tags_json = serde_json::to_string(&insight.tags).unwrap_or_else(|_| "[]".to_string());
+        // Found: three connections, walked against their direction
+        let chain = storage.build_chain(&ids[0], &ids[3], 3).unwrap().unwrap();
+        assert_eq!(chain.path_ids(), ids);
+        assert!(
+            chain
+                .steps
+                .iter()
+                .take(3)
+                .all(|step| step.connection_type == ConnectionType::Causal)
+        );
+
+        // Too long: the only path needs more than `max_len` connections
+        assert!(storage.build_chain(&ids[0], &ids[3], 2).unwrap().is_none());
+        assert_eq!(
+            storage
+                .build_chain(&ids[0], &ids[2], 2)
+                .unwrap()
+                .unwrap()
+                .path_ids(),
+            ids[..3]
+        );
+
+        // Not found: nothing links the island, and unknown memories are errors
+        assert!(storage.build_chain(&ids[0], &island, 5).unwrap().is_none());
+        assert!(matches!(
+            storage.build_chain(&ids[0], "missing", 3),
+            Err(StorageError::NotFound(_))
+        ));
+    }
 
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        writer.execute(
-            "INSERT OR REPLACE INTO insights (
-                id, insight, source_memories, confidence, novelty_score, insight_type,
-                generated_at, tags, feedback, applied_count
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-            params![
-                insight.id,
-                insight.insight,
-                source_json,
-                insight.confidence,
-                insight.novelty_score,
-                insight.insight_type,
-                insight.generated_at.to_rfc3339(),
-                tags_json,
-                insight.feedback,
-                insight.applied_count,
-            ],
-        )?;
-        Ok(())
+    #[test]
+    fn test_reasoning_chain_graph_stays_under_node_cap() {
+        let storage = create_test_storage();
+        let ingest = |content: String| {
+            storage.ingest(IngestInput {
+                content,
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap().id
+        };
+        let link = |source: &str, target: &str| {
+            let now = Utc::now();
+            storage.save_connection(&ConnectionRecord {
+                source_id: source.to_string(),
+                target_id: target.to_string(),
+                strength: 0.8,
+                link_type: "semantic".to_string(),
+                created_at: now,
+                last_activated: now,
+                activation_count: 0,
+            }).unwrap();
+        };
+
+        // A hub with more leaves than the cap, each leading on to a tip of
+        // its own, so every hop past the cap still has new memories to load
+        let hub = ingest("Hub memory".to_string());
+        for i in 0..MAX_CHAIN_GRAPH_NODES + 100 {
+            let leaf = ingest(format!("Leaf memory number {}", i));
+            let tip = ingest(format!("Tip memory number {}", i));
+            link(&hub, &leaf);
+            link(&leaf, &tip);
+        }
+
+        let builder = storage.chain_graph(&hub, 4).unwrap();
+        assert_eq!(builder.memory_count(), MAX_CHAIN_GRAPH_NODES);
     }
 
-    /// Get insights with optional limit
-    pub fn get_insights(&self, limit: i32) -> Result<Vec<InsightRecord>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let mut stmt = reader.prepare(
-            "SELECT * FROM insights ORDER BY generated_at DESC LIMIT ?1"
-        )?;
+    #[test]
+    fn test_get_stats_filtered_by_tag_type_and_scope() {
+        let storage = create_test_storage();
+        let mut ids = Vec::new();
+        for (i, (project, node_type)) in [("a", "fact"), ("a", "decision"), ("b", "fact")].iter().enumerate() {
+            let node = storage.ingest(IngestInput {
+                content: format!("Collection note {} for project {}", i, project),
+                node_type: node_type.to_string(),
+                tags: vec![format!("project:{}", project)],
+                ..Default::default()
+            }).unwrap();
+            ids.push(node.id);
+        }
+        {
+            let writer = storage.writer.lock().unwrap();
+            writer.execute(
+                "UPDATE knowledge_nodes SET next_review = ?1",
+                params![(Utc::now() + Duration::days(1)).to_rfc3339()],
+            ).unwrap();
+            writer.execute(
+                "UPDATE knowledge_nodes SET next_review = ?1, retention_strength = 0.2, scope = 'agent' WHERE id = ?2",
+                params![(Utc::now() - Duration::days(1)).to_rfc3339(), ids[1]],
+            ).unwrap();
+        }
 
-        let rows = stmt.query_map(params![limit], |row| Self::row_to_insight(row))?;
-        let mut result = Vec::new();
-        for row in rows {
-            result.push(row?);
+        let stats = |filter: StatsFilter| storage.get_stats_filtered(filter).unwrap();
+        let project_a = stats(StatsFilter { tag: Some("PROJECT:A".to_string()), ..Default::default() });
+        assert_eq!(project_a.total_nodes, 2);
+        assert_eq!(project_a.nodes_due_for_review, 1);
+        assert!(project_a.average_retention < stats(StatsFilter::default()).average_retention);
+
+        let project_b = stats(StatsFilter { tag: Some("project:b".to_string()), ..Default::default() });
+        assert_eq!((project_b.total_nodes, project_b.nodes_due_for_review), (1, 0));
+
+        let facts = stats(StatsFilter { node_type: Some("fact".to_string()), ..Default::default() });
+        assert_eq!(facts.total_nodes, 2);
+        let agent = stats(StatsFilter { scope: Some("agent".to_string()), ..Default::default() });
+        assert_eq!(agent.total_nodes, 1);
+
+        let none = stats(StatsFilter {
+            tag: Some("project:b".to_string()),
+            node_type: Some("decision".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(none.total_nodes, 0);
+        assert!(none.oldest_memory.is_none());
+        assert_eq!(stats(StatsFilter::default()).total_nodes, storage.get_stats().unwrap().total_nodes);
+    }
+
+    #[cfg(feature = "embeddings")]
+    #[test]
+    fn test_query_cache_keyed_by_model() {
+        let storage = create_test_storage();
+        let query = "stale vectors";
+
+        // A vector cached by a previous model is never served to the active one
+        storage.query_cache.lock().unwrap().put("old-model", query, false, vec![1.0, 0.0]);
+        assert!(storage.cached_query_embedding(query, false).unwrap().is_none());
+        assert_eq!(storage.query_cache_stats().unwrap().entries, 0);
+
+        storage.cache_query_embedding(query, false, vec![0.0, 1.0]).unwrap();
+        assert_eq!(storage.cached_query_embedding(query, false).unwrap(), Some(vec![0.0, 1.0]));
+
+        // An expanded (HyDE centroid) vector is cached separately from the literal one
+        assert!(storage.cached_query_embedding(query, true).unwrap().is_none());
+        storage.cache_query_embedding(query, true, vec![0.6, 0.8]).unwrap();
+        assert_eq!(storage.cached_query_embedding(query, true).unwrap(), Some(vec![0.6, 0.8]));
+        assert_eq!(storage.cached_query_embedding(query, false).unwrap(), Some(vec![0.0, 1.0]));
+
+        // Lookups are normalized: case and whitespace differences share an entry
+        assert_eq!(storage.cached_query_embedding("  STALE\tvectors ", false).unwrap(), Some(vec![0.0, 1.0]));
+
+        storage.clear_query_cache().unwrap();
+        assert!(storage.cached_query_embedding(query, false).unwrap().is_none());
+        assert_eq!(storage.query_cache_stats().unwrap().entries, 0);
+    }
+
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    #[test]
+    fn test_retruncate_embeddings_preserves_recall() {
+        use crate::embeddings::MODEL_DIMENSIONS;
+
+        // Labelled set: 8 topics with 4 memories each. As with a Matryoshka
+        // model, the leading dimensions carry most of the signal
+        let storage = create_test_storage();
+        let mut seed = 7u64;
+        let mut noise = move || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) as f32 / (1u64 << 31) as f32 - 0.5
+        };
+        let weight = |i: usize| 1.0 / (1.0 + i as f32 / 64.0);
+        let topics: Vec<Vec<f32>> = (0..8)
+            .map(|_| (0..MODEL_DIMENSIONS).map(|i| noise() * weight(i)).collect())
+            .collect();
+        let mut near = |topic: &[f32]| -> Vec<f32> {
+            topic.iter().enumerate().map(|(i, x)| x + 0.5 * noise() * weight(i)).collect()
+        };
+
+        let mut labels = HashMap::new();
+        for (t, topic) in topics.iter().enumerate() {
+            for m in 0..4 {
+                let node = storage.ingest(IngestInput {
+                    content: format!("Topic {t} memory {m}"),
+                    node_type: "fact".to_string(),
+                    ..Default::default()
+                }).unwrap();
+                storage.writer.lock().unwrap().execute(
+                    "INSERT OR REPLACE INTO node_embeddings (node_id, embedding, dimensions, model, created_at)
+                     VALUES (?1, ?2, ?3, 'test', ?4)",
+                    params![
+                        node.id,
+                        Embedding::new(near(topic)).to_bytes(),
+                        MODEL_DIMENSIONS as i32,
+                        Utc::now().to_rfc3339()
+                    ],
+                ).unwrap();
+                labels.insert(node.id, t);
+            }
         }
-        Ok(result)
+        let queries: Vec<(usize, Vec<f32>)> =
+            topics.iter().enumerate().map(|(t, topic)| (t, near(topic))).collect();
+
+        let precision_at_4 = |dims: usize| {
+            let index = storage.vector_index.lock().unwrap();
+            assert_eq!(index.dimensions(), dims);
+            let hits: usize = queries
+                .iter()
+                .map(|(t, query)| {
+                    index
+                        .search(&matryoshka_truncate_to(query.clone(), dims), 4)
+                        .unwrap()
+                        .iter()
+                        .filter(|(id, _)| labels[id] == *t)
+                        .count()
+                })
+                .sum();
+            hits as f64 / (queries.len() * 4) as f64
+        };
+
+        let report = storage.retruncate_embeddings(768).unwrap();
+        assert_eq!(report.previous_dimensions, crate::embeddings::EMBEDDING_DIMENSIONS);
+        assert_eq!((report.retruncated, report.needs_reembedding, report.indexed), (32, 0, 32));
+        let full = precision_at_4(768);
+
+        let report = storage.retruncate_embeddings(256).unwrap();
+        assert_eq!((report.dimensions, report.indexed), (256, 32));
+        let truncated = precision_at_4(256);
+        assert!(full >= 0.9, "768 dims: {full}");
+        assert!(truncated >= full - 0.1, "256 dims: {truncated}, 768 dims: {full}");
+
+        // Truncated vectors are persisted, so the next open loads them as-is
+        let persisted: i64 = storage.reader.lock().unwrap().query_row(
+            "SELECT COUNT(*) FROM node_embeddings WHERE index_dimensions = 256",
+            [],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(persisted, 32);
+        assert!(matches!(
+            storage.retruncate_embeddings(300),
+            Err(StorageError::InvalidInput { .. })
+        ));
     }
 
-    /// Get insights without feedback (pending review)
-    pub fn get_pending_insights(&self) -> Result<Vec<InsightRecord>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let mut stmt = reader.prepare(
-            "SELECT * FROM insights WHERE feedback IS NULL ORDER BY novelty_score DESC"
-        )?;
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    #[test]
+    fn test_retruncate_keeps_short_embeddings_out_of_the_index() {
+        let storage = create_test_storage();
+        let node = storage.ingest(IngestInput {
+            content: "Embedded at 256 dimensions only".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+        {
+            let writer = storage.writer.lock().unwrap();
+            writer.execute(
+                "INSERT OR REPLACE INTO node_embeddings (node_id, embedding, dimensions, model, created_at)
+                 VALUES (?1, ?2, 256, 'test', ?3)",
+                params![node.id, Embedding::new(vec![0.1; 256]).to_bytes(), Utc::now().to_rfc3339()],
+            ).unwrap();
+            writer.execute("UPDATE knowledge_nodes SET has_embedding = 1 WHERE id = ?1", params![node.id]).unwrap();
+        }
 
-        let rows = stmt.query_map([], |row| Self::row_to_insight(row))?;
-        let mut result = Vec::new();
-        for row in rows {
-            result.push(row?);
+        let report = storage.retruncate_embeddings(768).unwrap();
+        assert_eq!((report.needs_reembedding, report.indexed), (1, 0));
+        assert!(!storage.vector_index.lock().unwrap().contains(&node.id));
+        let (rows, flagged): (i64, i64) = storage.reader.lock().unwrap().query_row(
+            "SELECT (SELECT COUNT(*) FROM node_embeddings WHERE node_id = ?1 AND index_vector IS NULL),
+                    (SELECT has_embedding FROM knowledge_nodes WHERE id = ?1)",
+            params![node.id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).unwrap();
+        assert_eq!((rows, flagged), (1, 0), "the row stays, flagged for re-embedding");
+        assert_eq!(storage.diagnose().unwrap().vectors_missing_from_index, Some(0));
+    }
+
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    #[test]
+    fn test_find_duplicate_clusters_is_read_only() {
+        let storage = create_test_storage();
+        let dims = storage.index_dimensions();
+        let basis = |weights: &[(usize, f32)]| {
+            let mut vector = vec![0.0; dims];
+            for &(i, w) in weights {
+                vector[i] = w;
+            }
+            vector
+        };
+        let vectors = [basis(&[(0, 1.0)]), basis(&[(0, 0.99), (1, 0.1)]), basis(&[(1, 1.0)])];
+        let mut ids = Vec::new();
+        for (i, vector) in vectors.iter().enumerate() {
+            let node = storage.ingest(IngestInput {
+                content: format!("Duplicate candidate {i}"),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap();
+            let bytes = Embedding::new(vector.clone()).to_bytes();
+            storage.writer.lock().unwrap().execute(
+                "INSERT OR REPLACE INTO node_embeddings
+                    (node_id, embedding, dimensions, model, created_at, index_vector, index_dimensions)
+                 VALUES (?1, ?2, ?3, 'test', ?4, ?2, ?3)",
+                params![node.id, bytes, dims as i64, Utc::now().to_rfc3339()],
+            ).unwrap();
+            ids.push(node.id);
         }
-        Ok(result)
+        storage.rebuild_vector_index().unwrap();
+
+        let clusters = storage.find_duplicate_clusters(0.85).unwrap();
+        assert_eq!(clusters, vec![vec![ids[0].clone(), ids[1].clone()]]);
+        for id in &ids {
+            assert!(storage.get_node(id).unwrap().is_some(), "nothing should be merged");
+        }
+        assert!(storage.find_duplicate_clusters(1.0).unwrap().is_empty());
     }
 
-    /// Mark insight feedback
-    pub fn mark_insight_feedback(&self, id: &str, feedback: &str) -> Result<bool> {
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        let rows = writer.execute(
-            "UPDATE insights SET feedback = ?1 WHERE id = ?2",
-            params![feedback, id],
-        )?;
-        Ok(rows > 0)
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    #[test]
+    fn test_auto_dedup_scales_through_vector_index() {
+        const PAIRS: usize = 5_000;
+        let storage = create_mock_storage();
+        let dims = storage.index_dimensions();
+        let mut seed = 11u64;
+        let mut noise = move || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) as f32 / (1u64 << 31) as f32 - 0.5
+        };
+
+        // Seeded near-duplicate pairs; the second copy of each is stronger
+        let now = Utc::now().to_rfc3339();
+        {
+            let mut writer = storage.writer.lock().unwrap();
+            let tx = writer.transaction().unwrap();
+            for p in 0..PAIRS {
+                let base: Vec<f32> = (0..dims).map(|_| noise()).collect();
+                let near: Vec<f32> = base.iter().map(|x| x + 0.02 * noise()).collect();
+                for (copy, vector) in [base, near].into_iter().enumerate() {
+                    let id = format!("pair-{:04}-{}", p, copy);
+                    tx.execute(
+                        "INSERT INTO knowledge_nodes (id, content, created_at, updated_at, last_accessed,
+                            retention_strength)
+                         VALUES (?1, ?2, ?3, ?3, ?3, ?4)",
+                        params![id, format!("Pair {p} copy {copy}"), now, 0.5 + copy as f64 * 0.1],
+                    ).unwrap();
+                    let bytes = Embedding::new(vector).to_bytes();
+                    tx.execute(
+                        "INSERT INTO node_embeddings
+                            (node_id, embedding, dimensions, model, created_at, index_vector, index_dimensions)
+                         VALUES (?1, ?2, ?3, 'test', ?4, ?2, ?3)",
+                        params![id, bytes, dims as i64, now],
+                    ).unwrap();
+                }
+            }
+            tx.commit().unwrap();
+        }
+        assert_eq!(storage.rebuild_vector_index().unwrap(), 2 * PAIRS);
+
+        // Out of time at once: nothing examined, and the next pass starts over
+        let starved = storage.auto_dedup_consolidation(None, 0.85, std::time::Duration::ZERO).unwrap();
+        assert!(starved.budget_exhausted);
+        assert_eq!((starved.clusters_examined, starved.merged), (0, 0));
+
+        let budget = std::time::Duration::from_secs(120);
+        let started = std::time::Instant::now();
+        let outcome = storage.auto_dedup_consolidation(None, 0.85, budget).unwrap();
+        assert!(started.elapsed() < budget);
+        assert!(!outcome.budget_exhausted);
+        assert_eq!((outcome.clusters_merged, outcome.merged), (PAIRS as i64, PAIRS as i64));
+        assert_eq!(outcome.clusters_examined, PAIRS as i64, "each pair is found from its first copy");
+        for p in 0..PAIRS {
+            assert!(storage.get_node(&format!("pair-{:04}-0", p)).unwrap().is_none());
+            assert!(storage.get_node(&format!("pair-{:04}-1", p)).unwrap().is_some());
+        }
+
+        // Only memories changed since the last pass are examined again
+        let again = storage.auto_dedup_consolidation(None, 0.85, budget).unwrap();
+        assert_eq!(again, DedupOutcome::default());
+    }
+
+    #[test]
+    fn test_usage_history_roundtrip() {
+        let storage = create_test_storage();
+        for query in ["first", "second"] {
+            storage.record_usage(&UsageRecord {
+                tool: "search".to_string(),
+                query: Some(query.to_string()),
+                memory_ids: vec!["mem-1".to_string()],
+                project: Some("vestige".to_string()),
+                recorded_at: Utc::now(),
+            }).unwrap();
+        }
+
+        let history = storage.get_usage_history(10).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].query.as_deref(), Some("second"));
+        assert_eq!(history[0].memory_ids, vec!["mem-1"]);
+        assert_eq!(history[1].project.as_deref(), Some("vestige"));
+    }
+
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    #[test]
+    fn test_labile_window_updates_instead_of_forking() {
+        use crate::advanced::prediction_error::CandidateMemory;
+
+        let storage = create_test_storage();
+        let ingest = |content: &str| {
+            storage.ingest(IngestInput {
+                content: content.to_string(),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap()
+        };
+        let candidate = |node: &KnowledgeNode| CandidateMemory {
+            id: node.id.clone(),
+            content: node.content.clone(),
+            embedding: vec![1.0, 0.0, 0.0],
+            retrieval_strength: node.retrieval_strength,
+            retention_strength: node.retention_strength,
+            tags: node.tags.clone(),
+            source: node.source.clone(),
+            was_demoted: false,
+            was_promoted: false,
+        };
+        let correction = |content: &str| IngestInput {
+            content: content.to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        };
+        // ~0.68 cosine: below the default update threshold
+        let correction_embedding = [0.68, 0.733, 0.0];
+
+        // Retrieved five minutes ago: the correction rewrites the memory in place
+        let staging = ingest("The staging database runs on port 5432");
+        storage
+            .mark_retrieved_at(&[&staging.id], Some("staging port"), Utc::now() - Duration::minutes(5))
+            .unwrap();
+        let result = storage
+            .gate_and_apply(
+                correction("Correction: the staging database runs on port 6543"),
+                &correction_embedding,
+                &[candidate(&staging)],
+            )
+            .unwrap();
+        assert_eq!(result.decision, "update");
+        assert_eq!(result.node.id, staging.id);
+        assert!(result.node.content.contains("6543"));
+        let reconsolidated = result.reconsolidation.unwrap();
+        assert!(reconsolidated.change_summary.content_updated);
+        assert_eq!(reconsolidated.applied_modifications.len(), 1);
+        assert_eq!(storage.get_reconsolidation_stats().unwrap().total_modified, 1);
+
+        // Retrieved two hours ago: the window has closed, so a new memory is made
+        let analytics = ingest("The analytics database runs on port 5433");
+        storage
+            .mark_retrieved_at(&[&analytics.id], None, Utc::now() - Duration::hours(2))
+            .unwrap();
+        let result = storage
+            .gate_and_apply(
+                correction("Correction: the analytics database runs on port 6544"),
+                &correction_embedding,
+                &[candidate(&analytics)],
+            )
+            .unwrap();
+        assert_eq!(result.decision, "create");
+        assert_ne!(result.node.id, analytics.id);
+        assert!(result.reconsolidation.is_none());
+        assert_eq!(storage.get_node(&analytics.id).unwrap().unwrap().content, analytics.content);
+    }
+
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    #[test]
+    fn test_gate_preview_matches_apply_without_writing() {
+        let storage = create_test_storage();
+        let staging = storage.ingest(IngestInput {
+            content: "The staging database runs on port 5432".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+        let candidate = CandidateMemory {
+            id: staging.id.clone(),
+            content: staging.content.clone(),
+            embedding: vec![1.0, 0.0, 0.0],
+            retrieval_strength: staging.retrieval_strength,
+            retention_strength: staging.retention_strength,
+            tags: staging.tags.clone(),
+            source: staging.source.clone(),
+            was_demoted: false,
+            was_promoted: false,
+        };
+        let correction = IngestInput {
+            content: "Correction: the staging database runs on port 6543".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        };
+        let embedding = [0.68, 0.733, 0.0];
+
+        // Not labile: a new memory would be created, returned unsaved
+        let preview = storage
+            .gate_preview(&correction, &embedding, std::slice::from_ref(&candidate))
+            .unwrap();
+        assert_eq!(preview.decision, "create");
+        assert!(preview.node.id.is_empty());
+        assert_eq!(preview.node.content, correction.content);
+        assert_eq!(storage.get_stats().unwrap().total_nodes, 1);
+
+        // Labile: the preview shows the rewrite but leaves the memory alone
+        storage
+            .mark_retrieved_at(&[&staging.id], None, Utc::now() - Duration::minutes(5))
+            .unwrap();
+        let preview = storage
+            .gate_preview(&correction, &embedding, std::slice::from_ref(&candidate))
+            .unwrap();
+        assert_eq!(preview.decision, "update");
+        assert_eq!(preview.node.id, staging.id);
+        assert!(preview.node.content.contains("6543"));
+        assert!(preview.reason.contains("reconsolidation window"));
+        assert_eq!(storage.get_node(&staging.id).unwrap().unwrap().content, staging.content);
+        assert_eq!(storage.get_reconsolidation_stats().unwrap().total_modified, 0);
+
+        let applied = storage.gate_and_apply(correction, &embedding, &[candidate]).unwrap();
+        assert_eq!(applied.decision, preview.decision);
+        assert_eq!(applied.similarity, preview.similarity);
+        assert_eq!(applied.node.content, preview.node.content);
+    }
+
+    #[test]
+    fn test_incremental_act_r_matches_full_recompute() {
+        let (storage, clock) = create_clocked_storage();
+        let ids: Vec<String> = (0..30)
+            .map(|i| {
+                storage.ingest(IngestInput {
+                    content: format!("Access pattern memory {}", i),
+                    node_type: "fact".to_string(),
+                    ..Default::default()
+                }).unwrap().id
+            })
+            .collect();
+
+        // Deterministic synthetic histories: bursts, steady use and long gaps
+        let mut seed = 0x2545_f491_u64;
+        let mut next = move |bound: u64| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) % bound
+        };
+        for _ in 0..60 {
+            for id in &ids {
+                if next(4) == 0 {
+                    for _ in 0..=next(3) {
+                        storage.log_access(id, "search_hit").unwrap();
+                    }
+                }
+            }
+            clock.advance(Duration::minutes(10 + next(600) as i64));
+            storage.compute_act_r_activations().unwrap();
+        }
+
+        let activations = |storage: &Storage| -> HashMap<String, f64> {
+            let reader = storage.lock_reader().unwrap();
+            reader
+                .prepare("SELECT id, activation FROM knowledge_nodes WHERE activation IS NOT NULL")
+                .unwrap()
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                .unwrap()
+                .collect::<rusqlite::Result<_>>()
+                .unwrap()
+        };
+        let incremental = activations(&storage);
+        let max_error: f64 = storage
+            .lock_reader()
+            .unwrap()
+            .query_row("SELECT MAX(error_bound) FROM actr_state", [], |row| row.get(0))
+            .unwrap();
+        assert!(max_error > 0.0, "no activation was maintained incrementally");
+
+        storage.lock_writer().unwrap().execute("DELETE FROM actr_state", []).unwrap();
+        storage.compute_act_r_activations().unwrap();
+        let full = activations(&storage);
+
+        assert_eq!(incremental.len(), ids.len());
+        for (id, exact) in &full {
+            // Activation is ln of the decayed access sum; compare the sums
+            let ratio = (incremental[id] - exact).exp();
+            assert!((ratio - 1.0).abs() < 0.01, "{}: {} vs {}", id, incremental[id], exact);
+        }
+    }
+
+    #[test]
+    fn test_act_r_rerun_touches_only_newly_accessed_memories() {
+        let (storage, clock) = create_clocked_storage();
+        let ids: Vec<String> = (0..20)
+            .map(|i| {
+                let id = storage.ingest(IngestInput {
+                    content: format!("Quiet memory {}", i),
+                    node_type: "fact".to_string(),
+                    ..Default::default()
+                }).unwrap().id;
+                storage.log_access(&id, "search_hit").unwrap();
+                storage.log_access(&id, "promote").unwrap();
+                id
+            })
+            .collect();
+
+        clock.advance(Duration::days(10));
+        assert_eq!(
+            storage.run_consolidation_step("actr").unwrap(),
+            StepResult::Actr { activations_computed: 20 }
+        );
+
+        clock.advance(Duration::minutes(1));
+        assert_eq!(
+            storage.run_consolidation_step("actr").unwrap(),
+            StepResult::Actr { activations_computed: 0 }
+        );
+
+        for id in &ids[..3] {
+            storage.log_access(id, "search_hit").unwrap();
+        }
+        clock.advance(Duration::minutes(1));
+        let activation = |id: &str| -> f64 {
+            storage
+                .lock_reader()
+                .unwrap()
+                .query_row("SELECT activation FROM knowledge_nodes WHERE id = ?1", params![id], |row| row.get(0))
+                .unwrap()
+        };
+        let before = activation(&ids[0]);
+        assert_eq!(
+            storage.run_consolidation_step("actr").unwrap(),
+            StepResult::Actr { activations_computed: 3 }
+        );
+        assert!(activation(&ids[0]) > before);
+
+        // A row logged after the last run counts even when it is stamped
+        // before that run, e.g. one written late or restored from a backup
+        let before = activation(&ids[3]);
+        storage.lock_writer().unwrap().execute(
+            "INSERT INTO memory_access_log (node_id, access_type, accessed_at) VALUES (?1, 'search_hit', ?2)",
+            params![ids[3], (storage.now() - Duration::hours(1)).to_rfc3339()],
+        ).unwrap();
+        assert_eq!(
+            storage.run_consolidation_step("actr").unwrap(),
+            StepResult::Actr { activations_computed: 1 }
+        );
+        assert!(activation(&ids[3]) > before);
+    }
+
+    #[test]
+    fn test_typed_accesses_weight_activation_and_fill_history() {
+        let (storage, clock) = create_clocked_storage();
+        let ingest = |content: &str| {
+            storage.ingest(IngestInput {
+                content: content.to_string(),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap().id
+        };
+        let opened = ingest("Opened and read in full");
+        let glimpsed = ingest("Only seen in a result list");
+        let boosted = ingest("Seen once, logged with extra weight");
+
+        storage.log_access_typed(&opened, AccessKind::Open, 1.0).unwrap();
+        storage.log_access_typed(&glimpsed, AccessKind::Impression, 1.0).unwrap();
+        storage.log_access_typed(&boosted, AccessKind::Impression, 12.0).unwrap();
+        clock.advance(Duration::hours(1));
+        storage.log_access_typed(&opened, AccessKind::SearchHit, 0.5).unwrap();
+
+        assert!(matches!(
+            storage.log_access_typed(&opened, AccessKind::Open, 0.0),
+            Err(StorageError::InvalidInput { field: "weight", .. })
+        ));
+        assert!(matches!(
+            storage.log_access_typed(&opened, AccessKind::Open, f64::NAN),
+            Err(StorageError::InvalidInput { field: "weight", .. })
+        ));
+        assert!(matches!(
+            storage.log_access_typed("missing", AccessKind::Open, 1.0),
+            Err(StorageError::NotFound(_))
+        ));
+
+        clock.advance(Duration::days(1));
+        storage.run_consolidation_step("actr").unwrap();
+        let activation = |id: &str| -> f64 {
+            storage
+                .lock_reader()
+                .unwrap()
+                .query_row("SELECT activation FROM knowledge_nodes WHERE id = ?1", params![id], |row| row.get(0))
+                .unwrap()
+        };
+        assert!(activation(&opened) > activation(&glimpsed));
+        // An impression weighs a quarter of a search hit, so at weight 12 it
+        // outweighs an open (2.0) plus a half-weight hit
+        assert!(activation(&boosted) > activation(&opened));
+
+        let history = storage.get_access_history(&opened, 10).unwrap();
+        assert_eq!(
+            history.iter().map(|r| (r.access_type.as_str(), r.weight)).collect::<Vec<_>>(),
+            vec![("search_hit", 0.5), ("open", 1.0)]
+        );
+        assert!(history[0].accessed_at > history[1].accessed_at);
+        assert_eq!(storage.get_access_history(&opened, 1).unwrap().len(), 1);
+        assert!(storage.get_access_history("missing", 10).unwrap().is_empty());
+        assert_eq!(AccessKind::parse_name("open"), Some(AccessKind::Open));
+        assert_eq!(AccessKind::parse_name("outcome_helpful"), None);
+    }
+
+    #[test]
+    fn test_demotion_does_not_raise_activation() {
+        let storage = create_test_storage();
+        let ingest = |content: &str| {
+            storage.ingest(IngestInput {
+                content: content.to_string(),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap().id
+        };
+        let demoted = ingest("Retrieved once, then demoted");
+        let untouched = ingest("Only ever demoted");
+        let activation = |id: &str| -> Option<f64> {
+            storage.reader.lock().unwrap()
+                .query_row("SELECT activation FROM knowledge_nodes WHERE id = ?1", params![id], |row| row.get(0))
+                .unwrap()
+        };
+
+        storage.log_access_typed(&demoted, AccessKind::SearchHit, 1.0).unwrap();
+        storage.compute_act_r_activations().unwrap();
+        let before = activation(&demoted).unwrap();
+        let untouched_before = activation(&untouched);
+
+        storage.demote_memory(&demoted).unwrap();
+        storage.demote_memory(&untouched).unwrap();
+        storage.compute_act_r_activations().unwrap();
+
+        assert!(activation(&demoted).unwrap() <= before);
+        assert_eq!(activation(&untouched), untouched_before);
+        assert_eq!(storage.get_access_history(&untouched, 10).unwrap()[0].access_type, "demote");
+    }
+
+    #[test]
+    fn test_get_related_accumulates_activation() {
+        let storage = create_test_storage();
+        let ids: Vec<String> = ["Seed", "Reached twice", "Bridge", "Strong dead end", "Two hops out"]
+            .iter()
+            .map(|content| storage.ingest(IngestInput {
+                content: content.to_string(),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap().id)
+            .collect();
+        let now = Utc::now();
+        for (source, target, strength) in [(0, 1, 0.6), (0, 2, 0.6), (2, 1, 0.6), (0, 3, 0.8), (3, 4, 0.8)] {
+            storage.save_connection(&ConnectionRecord {
+                source_id: ids[source].clone(),
+                target_id: ids[target].clone(),
+                strength,
+                link_type: "semantic".to_string(),
+                created_at: now,
+                last_activated: now,
+                activation_count: 0,
+            }).unwrap();
+        }
+
+        // One hop: the strongest direct link wins
+        let related = storage.get_related(&ids[0], 1, 10).unwrap();
+        assert_eq!(related.len(), 3);
+        assert_eq!(related[0].memory_id, ids[3]);
+
+        // Two hops: memories reached along two weaker paths outrank it
+        let related = storage.get_related(&ids[0], 2, 10).unwrap();
+        let order: Vec<&str> = related.iter().map(|m| m.memory_id.as_str()).collect();
+        assert!(order[..2].contains(&ids[1].as_str()) && order[..2].contains(&ids[2].as_str()));
+        assert_eq!(order[2], ids[3]);
+        let far = related.iter().find(|m| m.memory_id == ids[4]).unwrap();
+        assert_eq!(far.distance, 2);
+        assert!(related.iter().all(|m| m.memory_id != ids[0]));
+        assert_eq!(storage.get_related(&ids[0], 2, 2).unwrap().len(), 2);
+        assert!(storage.get_related("missing", 2, 10).is_err());
+    }
+
+    #[test]
+    fn test_embedding_strategy_defaults_for_existing_rows() {
+        let storage = create_test_storage();
+        let node = storage.ingest(IngestInput {
+            content: "Embedded before strategies were recorded".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+        assert!(storage.get_embedding_strategy(&node.id).unwrap().is_none());
+
+        storage.writer.lock().unwrap().execute(
+            "INSERT OR REPLACE INTO node_embeddings (node_id, embedding, dimensions, model, created_at)
+             VALUES (?1, x'00', 1, 'test', ?2)",
+            params![node.id, Utc::now().to_rfc3339()],
+        ).unwrap();
+        assert_eq!(
+            storage.get_embedding_strategy(&node.id).unwrap(),
+            Some(EmbeddingStrategy::SentenceTransformer)
+        );
+    }
+
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    #[test]
+    #[ignore = "needs the local embedding model"]
+    fn test_code_is_embedded_normalized_and_reembeddable() {
+        let storage = create_test_storage();
+        assert!(storage.is_embedding_ready(), "local embedding model failed to load");
+
+        let code = "// Copyright Example Corp and contributors, all rights reserved\n\
+                    // Licensed under the Apache License Version 2.0\n\
+                    use std::time::Duration;\n\
+                    use crate::client::{send_request, Error};\n\
+                    fn retry_request(attempts: u32) -> Result<(), Error> {\n\
+                    let delay = Duration::from_secs(2u64.pow(attempts));\n\
+                    std::thread::sleep(delay);\n\
+                    tracing::warn!(\"retrying request\");\n\
+                    send_request()?;\n\
+                    Ok(()) }";
+        let node = storage.ingest(IngestInput {
+            content: code.to_string(),
+            node_type: "code".to_string(),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(
+            storage.get_embedding_strategy(&node.id).unwrap(),
+            Some(EmbeddingStrategy::CodeEmbedding)
+        );
+        assert_eq!(storage.get_embedding_language(&node.id).unwrap().as_deref(), Some("rust"));
+
+        // Prose in the same store uses the text strategy and the same index
+        let prose = storage.ingest(IngestInput {
+            content: "Requests are retried with exponential backoff between attempts".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(
+            storage.get_embedding_strategy(&prose.id).unwrap(),
+            Some(EmbeddingStrategy::SentenceTransformer)
+        );
+        assert!(storage.get_embedding_language(&prose.id).unwrap().is_none());
+        assert_eq!(
+            storage.get_node_embedding(&prose.id).unwrap().unwrap().len(),
+            storage.get_node_embedding(&node.id).unwrap().unwrap().len()
+        );
+
+        // The comment-free embedding sits closer to a code query than raw text
+        let query = storage.get_query_embedding("retry request with exponential backoff delay").unwrap();
+        let normalized = storage.get_node_embedding(&node.id).unwrap().unwrap();
+        let raw = storage.embedding_service.embed(code).unwrap().vector;
+        assert!(
+            crate::embeddings::cosine_similarity(&query, &normalized)
+                > crate::embeddings::cosine_similarity(&query, &raw)
+        );
+
+        // Rows recorded under another strategy can be migrated in bulk
+        storage.writer.lock().unwrap().execute(
+            "UPDATE node_embeddings SET strategy = 'sentence_transformer' WHERE node_id = ?1",
+            params![node.id],
+        ).unwrap();
+        let result = storage.reembed_by_strategy(EmbeddingStrategy::SentenceTransformer).unwrap();
+        assert_eq!(result.successful, 1);
+        assert_eq!(
+            storage.get_embedding_strategy(&node.id).unwrap(),
+            Some(EmbeddingStrategy::CodeEmbedding)
+        );
     }
 
-    /// Clear all insights
-    pub fn clear_insights(&self) -> Result<i32> {
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        let count: i32 = writer.query_row("SELECT COUNT(*) FROM insights", [], |row| row.get(0))?;
-        writer.execute("DELETE FROM insights", [])?;
-        Ok(count)
+    #[test]
+    fn test_importance_persisted_and_ranked() {
+        let storage = create_test_storage();
+        let mut ids = Vec::new();
+        for content in ["useful deploy runbook", "emotional outage memory", "unremarkable note"] {
+            ids.push(storage.ingest(IngestInput {
+                content: content.to_string(),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap().id);
+        }
+        {
+            let writer = storage.writer.lock().unwrap();
+            writer.execute(
+                "UPDATE knowledge_nodes SET utility_score = 0.9, activation = 3.0, reps = 8 WHERE id = ?1",
+                params![ids[0]],
+            ).unwrap();
+            writer.execute(
+                "UPDATE knowledge_nodes SET emotional_valence = -0.9, reps = 1 WHERE id = ?1",
+                params![ids[1]],
+            ).unwrap();
+        }
+
+        // Nothing is ranked until consolidation scores it
+        assert!(storage.top_important(10).unwrap().is_empty());
+
+        assert_eq!(storage.update_importance_scores().unwrap(), 3);
+        let top = storage.top_important(10).unwrap();
+        let order: Vec<&str> = top.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(order, vec![ids[0].as_str(), ids[1].as_str(), ids[2].as_str()]);
+        assert_eq!(storage.top_important(1).unwrap().len(), 1);
+
+        let breakdown = storage.get_importance_breakdown(&ids[0]).unwrap().unwrap();
+        assert!((top[0].importance.unwrap() - breakdown.composite).abs() < 1e-9);
+        assert!((breakdown.utility - 0.9).abs() < 1e-9);
+        assert!((breakdown.activation - 5.0 / 7.0).abs() < 1e-9);
+        let emotional = storage.get_importance_breakdown(&ids[1]).unwrap().unwrap();
+        assert!((emotional.emotion - 0.9).abs() < 1e-9);
+        assert!(storage.get_importance_breakdown("missing").unwrap().is_none());
     }
 
-    fn row_to_insight(row: &rusqlite::Row) -> rusqlite::Result<InsightRecord> {
-        let source_json: String = row.get("source_memories")?;
-        let source_memories: Vec<String> = serde_json::from_str(&source_json).unwrap_or_default();
-        let tags_json: String = row.get("tags")?;
-        let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
+    #[test]
+    fn test_usage_log_step_prunes_replayed_history_past_retention() {
+        let (storage, clock) = create_clocked_storage();
+        let id = storage.ingest(IngestInput {
+            content: "Deploys freeze on Fridays".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap().id;
+        storage.promote_memory(&id).unwrap();
+        storage.promote_memory(&id).unwrap();
+        assert_eq!(storage.evolve_importance().unwrap(), 2);
+        storage.demote_memory(&id).unwrap();
+
+        clock.advance(Duration::days(ACCESS_LOG_RETENTION_DAYS + 1));
+        let result = storage.run_consolidation_step("usage_log").unwrap();
+        let StepResult::UsageLog { events_pruned, history_pruned } = result else {
+            panic!("unexpected step result: {result:?}");
+        };
+        assert_eq!(events_pruned, 2);
+        assert!(history_pruned >= 2);
+        assert!(storage.get_importance_history(&id).unwrap().is_empty());
 
-        Ok(InsightRecord {
-            id: row.get("id")?,
-            insight: row.get("insight")?,
-            source_memories,
-            confidence: row.get("confidence")?,
-            novelty_score: row.get("novelty_score")?,
-            insight_type: row.get("insight_type")?,
-            generated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>("generated_at")?)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now()),
-            tags,
-            feedback: row.get("feedback").ok().flatten(),
-            applied_count: row.get("applied_count").unwrap_or(0),
-        })
+        // The demotion was never replayed, so it survives the pruning
+        assert_eq!(storage.evolve_importance().unwrap(), 1);
     }
 
-    // ========================================================================
-    // MEMORY CONNECTIONS PERSISTENCE (Activation Network)
-    // ========================================================================
+    #[test]
+    fn test_step_errors_are_reported_not_swallowed() {
+        let storage = create_test_storage();
+        let _ = std::thread::scope(|s| {
+            s.spawn(|| {
+                let _guard = storage.reader.lock().unwrap();
+                panic!("poison the reader lock");
+            }).join()
+        });
 
-    /// Save a memory connection
-    pub fn save_connection(&self, connection: &ConnectionRecord) -> Result<()> {
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        writer.execute(
-            "INSERT OR REPLACE INTO memory_connections (
-                source_id, target_id, strength, link_type, created_at, last_activated, activation_count
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![
-                connection.source_id,
-                connection.target_id,
-                connection.strength,
-                connection.link_type,
-                connection.created_at.to_rfc3339(),
-                connection.last_activated.to_rfc3339(),
-                connection.activation_count,
-            ],
-        )?;
-        Ok(())
+        let config = ConsolidationConfig::default();
+        let ctx = StepContext { namespace: None, config: &config };
+        let result = ConsolidationPipeline::standard().run(
+            &storage,
+            &ctx,
+            &ConsolidationLimits::default(),
+            &mut |_| {},
+        );
+
+        // Compression scans through the reader, so it fails loudly...
+        let compression = result
+            .step_errors
+            .iter()
+            .find(|e| e.step == "compression")
+            .expect("compression failure reported");
+        assert!(compression.error.contains("Reader lock poisoned"));
+        assert!(!result.steps.contains_key("compression"));
+        // ...and the writer-only steps after it still run
+        assert_eq!(result.steps.get("fts_optimize"), Some(&StepResult::FtsOptimize));
     }
 
-    /// Get connections for a memory
-    pub fn get_connections_for_memory(&self, memory_id: &str) -> Result<Vec<ConnectionRecord>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let mut stmt = reader.prepare(
-            "SELECT * FROM memory_connections WHERE source_id = ?1 OR target_id = ?1 ORDER BY strength DESC"
-        )?;
-
-        let rows = stmt.query_map(params![memory_id], |row| Self::row_to_connection(row))?;
-        let mut result = Vec::new();
-        for row in rows {
-            result.push(row?);
+    #[test]
+    fn test_importance_rises_with_promotion_then_decays_when_idle() {
+        let storage = create_test_storage();
+        let id = storage.ingest(IngestInput {
+            content: "Release checklist lives in the ops wiki".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap().id;
+
+        storage.promote_memory(&id).unwrap();
+        storage.promote_memory(&id).unwrap();
+        assert_eq!(storage.evolve_importance().unwrap(), 2);
+        storage.update_importance_scores().unwrap();
+
+        let history = storage.get_importance_history(&id).unwrap();
+        assert_eq!(history.len(), 2);
+        assert!(history.iter().all(|p| p.cause == "promote"));
+        assert!(history[1].usage_importance > history[0].usage_importance);
+        assert!(history[1].final_score > history[0].final_score);
+        let active = storage.get_importance_breakdown(&id).unwrap().unwrap();
+        assert!((active.usage - history[1].final_score).abs() < 1e-9);
+        let persisted = storage.get_node(&id).unwrap().unwrap().importance.unwrap();
+        assert!((persisted - active.composite).abs() < 1e-9);
+
+        // Events are consumed: replaying again changes nothing
+        assert_eq!(storage.evolve_importance().unwrap(), 0);
+        assert_eq!(storage.get_importance_history(&id).unwrap().len(), 2);
+
+        // 60 idle days
+        {
+            let writer = storage.writer.lock().unwrap();
+            let idle_since = (Utc::now() - Duration::days(60)).to_rfc3339();
+            writer.execute(
+                "UPDATE importance_scores SET last_accessed = ?1, calculated_at = ?1 WHERE memory_id = ?2",
+                params![idle_since, id],
+            ).unwrap();
         }
-        Ok(result)
+        storage.evolve_importance().unwrap();
+        storage.update_importance_scores().unwrap();
+
+        let history = storage.get_importance_history(&id).unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[2].cause, "decay");
+        assert!(history[2].final_score < history[1].final_score);
+        assert!(history[2].usage_importance < history[0].usage_importance);
+        let idle = storage.get_importance_breakdown(&id).unwrap().unwrap();
+        assert!(idle.usage < active.usage);
+        assert!(storage.get_node(&id).unwrap().unwrap().importance.unwrap() < persisted);
     }
 
-    /// Get all connections (for building activation network)
-    pub fn get_all_connections(&self) -> Result<Vec<ConnectionRecord>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let mut stmt = reader.prepare(
-            "SELECT * FROM memory_connections ORDER BY strength DESC"
-        )?;
+    #[test]
+    fn test_metrics_snapshot_counts_operations() {
+        let storage = create_test_storage();
+        assert_eq!(storage.metrics_snapshot(), MetricsSnapshot::default());
 
-        let rows = stmt.query_map([], |row| Self::row_to_connection(row))?;
-        let mut result = Vec::new();
-        for row in rows {
-            result.push(row?);
+        for i in 0..3 {
+            storage.ingest(IngestInput {
+                content: format!("Metrics memory {} about the scheduler", i),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap();
         }
-        Ok(result)
+        // A returned duplicate writes no node
+        storage.ingest(IngestInput {
+            content: "Metrics memory 0 about the scheduler".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+        storage.search("scheduler", 10).unwrap();
+        storage.keyword_search_scored("scheduler", 10).unwrap();
+        storage.run_consolidation().unwrap();
+        // Nothing changed since, so this run is skipped and not counted
+        assert!(storage.run_consolidation().unwrap().skipped.is_some());
+
+        let snapshot = storage.metrics_snapshot();
+        assert_eq!(snapshot.nodes_ingested, 3);
+        assert_eq!(snapshot.searches_served, 2);
+        assert_eq!(snapshot.consolidation_runs, 1);
+        assert_eq!(snapshot.slow_lock_waits, 0);
+
+        let json = serde_json::to_value(snapshot).unwrap();
+        assert_eq!(json["searchesServed"], 2);
     }
 
-    /// Strengthen a connection
-    pub fn strengthen_connection(&self, source_id: &str, target_id: &str, boost: f64) -> Result<bool> {
-        let now = Utc::now().to_rfc3339();
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        let rows = writer.execute(
-            "UPDATE memory_connections SET
-                strength = MIN(strength + ?1, 1.0),
-                last_activated = ?2,
-                activation_count = activation_count + 1
-             WHERE source_id = ?3 AND target_id = ?4",
-            params![boost, now, source_id, target_id],
-        )?;
-        Ok(rows > 0)
-    }
+    #[test]
+    fn test_keyword_search_scored_returns_raw_rank() {
+        let storage = create_test_storage();
+        for content in [
+            "Retry the flaky retry loop with retry backoff",
+            "One retry is enough",
+            "Nothing relevant here",
+        ] {
+            storage.ingest(IngestInput {
+                content: content.to_string(),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap();
+        }
 
-    /// Apply decay to all connections
-    pub fn apply_connection_decay(&self, decay_factor: f64) -> Result<i32> {
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        let rows = writer.execute(
-            "UPDATE memory_connections SET strength = strength * ?1",
-            params![decay_factor],
-        )?;
-        Ok(rows as i32)
+        let scored = storage.keyword_search_scored("retry", 10).unwrap();
+        assert_eq!(scored.len(), 2);
+        assert!(scored[0].0.content.starts_with("Retry the flaky"));
+        // Raw BM25 ranks: negative, best first, not normalized
+        assert!(scored.iter().all(|(_, rank)| *rank < 0.0));
+        assert!(scored[0].1 < scored[1].1);
+        let plain: Vec<_> = storage.search("retry", 10).unwrap().into_iter().map(|n| n.id).collect();
+        assert_eq!(scored.iter().map(|(n, _)| n.id.clone()).collect::<Vec<_>>(), plain);
     }
 
-    /// Prune weak connections below threshold
-    pub fn prune_weak_connections(&self, min_strength: f64) -> Result<i32> {
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        let rows = writer.execute(
-            "DELETE FROM memory_connections WHERE strength < ?1",
-            params![min_strength],
-        )?;
-        Ok(rows as i32)
-    }
+    #[test]
+    fn test_search_with_count_reports_total_across_pages() {
+        let storage = create_test_storage();
+        for i in 0..25 {
+            storage.ingest(IngestInput {
+                content: format!("Deploy checklist item {} for the release", i),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap();
+        }
+        storage.ingest(IngestInput {
+            content: "Unrelated note about lunch".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+
+        let (first, total) = storage.search_with_count("deploy", 10, 0).unwrap();
+        assert_eq!(total, 25);
+        assert_eq!(first.len(), 10);
+        assert!(first.iter().all(|r| r.match_type == MatchType::Keyword));
+        assert!(first.iter().all(|r| r.combined_score > 0.0 && r.combined_score <= 1.0));
+
+        let (last, total) = storage.search_with_count("deploy", 10, 20).unwrap();
+        assert_eq!(total, 25);
+        assert_eq!(last.len(), 5);
+
+        let mut seen = HashSet::new();
+        for offset in [0, 10, 20] {
+            let (page, _) = storage.search_with_count("deploy", 10, offset).unwrap();
+            seen.extend(page.into_iter().map(|r| r.node.id));
+        }
+        assert_eq!(seen.len(), 25);
 
-    fn row_to_connection(row: &rusqlite::Row) -> rusqlite::Result<ConnectionRecord> {
-        Ok(ConnectionRecord {
-            source_id: row.get("source_id")?,
-            target_id: row.get("target_id")?,
-            strength: row.get("strength")?,
-            link_type: row.get("link_type")?,
-            created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>("created_at")?)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now()),
-            last_activated: DateTime::parse_from_rfc3339(&row.get::<_, String>("last_activated")?)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now()),
-            activation_count: row.get("activation_count").unwrap_or(0),
-        })
+        let (none, total) = storage.search_with_count("kubernetes", 10, 0).unwrap();
+        assert!(none.is_empty());
+        assert_eq!(total, 0);
     }
 
-    // ========================================================================
-    // MEMORY STATES PERSISTENCE
-    // ========================================================================
-
-    /// Save or update memory state
-    pub fn save_memory_state(&self, state: &MemoryStateRecord) -> Result<()> {
-        let suppressed_json = serde_json::to_string(&state.suppressed_by).unwrap_or_else(|_| "[]".to_string());
+    #[test]
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn test_hybrid_search_with_count_total_does_not_grow_with_offset() {
+        let storage = create_mock_storage();
+        for i in 0..25 {
+            storage.ingest(IngestInput {
+                content: format!("Deploy checklist item {} for the release", i),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap();
+        }
 
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        writer.execute(
-            "INSERT OR REPLACE INTO memory_states (
-                memory_id, state, last_access, access_count, state_entered_at,
-                suppression_until, suppressed_by
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![
-                state.memory_id,
-                state.state,
-                state.last_access.to_rfc3339(),
-                state.access_count,
-                state.state_entered_at.to_rfc3339(),
-                state.suppression_until.map(|dt| dt.to_rfc3339()),
-                suppressed_json,
-            ],
-        )?;
-        Ok(())
+        let (_, total) = storage.search_with_count("deploy", 5, 0).unwrap();
+        assert!(total >= 25);
+        let mut seen = HashSet::new();
+        for offset in (0..total + 5).step_by(5) {
+            let (page, page_total) = storage.search_with_count("deploy", 5, offset).unwrap();
+            assert_eq!(page_total, total);
+            assert_eq!(page.len(), total.saturating_sub(offset).min(5));
+            seen.extend(page.into_iter().map(|r| r.node.id));
+        }
+        assert_eq!(seen.len(), total);
     }
 
-    /// Get memory state
-    pub fn get_memory_state(&self, memory_id: &str) -> Result<Option<MemoryStateRecord>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let mut stmt = reader.prepare(
-            "SELECT * FROM memory_states WHERE memory_id = ?1"
-        )?;
+    #[test]
+    fn test_advanced_syntax_phrases_prefixes_and_hostile_input() {
+        let storage = create_test_storage();
+        let mut ids = Vec::new();
+        for content in [
+            "connection pool exhausted under load",
+            "the pool connection was exhausted",
+            "nightly consolidation finished",
+        ] {
+            ids.push(storage.ingest(IngestInput {
+                content: content.to_string(),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap().id);
+        }
 
-        stmt.query_row(params![memory_id], |row| Self::row_to_memory_state(row))
-            .optional()
-            .map_err(StorageError::from)
+        let phrase = storage
+            .search_with_syntax("\"connection pool exhausted\"", 10, SearchSyntax::Advanced)
+            .unwrap();
+        assert_eq!(phrase.iter().map(|n| n.id.as_str()).collect::<Vec<_>>(), vec![ids[0].as_str()]);
+
+        // Unquoted words all match, in any order
+        assert_eq!(
+            storage.search_with_syntax("exhausted pool", 10, SearchSyntax::Advanced).unwrap().len(),
+            2
+        );
+
+        let prefix = storage.search_with_syntax("consolid*", 10, SearchSyntax::Advanced).unwrap();
+        assert_eq!(prefix.len(), 1);
+        assert_eq!(prefix[0].id, ids[2]);
+        // Plain syntax treats the asterisk as noise, not a wildcard
+        assert!(storage.search("consol*", 10).unwrap().is_empty());
+
+        let search_ids = |query: &str| -> Vec<String> {
+            storage
+                .search_with_syntax(query, 10, SearchSyntax::Advanced)
+                .unwrap()
+                .into_iter()
+                .map(|n| n.id)
+                .collect()
+        };
+        assert_eq!(search_ids("pool -load"), vec![ids[1].clone()]);
+        assert_eq!(search_ids("pool NOT \"under load\""), vec![ids[1].clone()]);
+        assert_eq!(search_ids("exhausted pool -\"was exhausted\" -nightl*"), vec![ids[0].clone()]);
+        assert!(search_ids("-pool").is_empty());
+
+        let hostile = [
+            "\"unbalanced",
+            "pool\" exhausted\" \"",
+            "OR OR",
+            "AND",
+            "NOT NOT pool",
+            "content:secret",
+            "{id content}: pool",
+            "NEAR(pool exhausted, 2)",
+            "pool) OR (1=1",
+            "^pool -exhausted",
+            "-pool -",
+            "pool NOT",
+            "NOT -\"pool\" -(exhausted",
+            "pool -content:secret -\"\"",
+            "*",
+            "\"\"\"\"\"",
+            "a* b** c***",
+            "\u{0}\u{7f} ü* 日本語*",
+        ];
+        for query in hostile {
+            for syntax in [SearchSyntax::Plain, SearchSyntax::Advanced] {
+                assert!(
+                    storage.search_with_syntax(query, 10, syntax).is_ok(),
+                    "{query:?} with {syntax:?}"
+                );
+                let input = RecallInput {
+                    query: query.to_string(),
+                    search_mode: SearchMode::Keyword,
+                    syntax,
+                    ..Default::default()
+                };
+                assert!(storage.recall(input).is_ok(), "{query:?} recall with {syntax:?}");
+            }
+        }
     }
 
-    /// Get memories by state
-    pub fn get_memories_by_state(&self, state: &str) -> Result<Vec<String>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let mut stmt = reader.prepare(
-            "SELECT memory_id FROM memory_states WHERE state = ?1"
-        )?;
-
-        let rows = stmt.query_map(params![state], |row| row.get::<_, String>(0))?;
-        let mut result = Vec::new();
-        for row in rows {
-            result.push(row?);
+    #[test]
+    fn test_synaptic_capture_within_nine_hour_window() {
+        let storage = create_test_storage();
+        let mut ids = Vec::new();
+        for content in [
+            "Sam mentioned vacation plans for next month",
+            "Chatted about the weather over lunch",
+            "Sam is leaving the company",
+        ] {
+            ids.push(storage.ingest(IngestInput {
+                content: content.to_string(),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap().id);
         }
-        Ok(result)
+        let now = Utc::now();
+        storage.tag_for_capture_at(&ids[0], now - Duration::minutes(510)).unwrap();
+        storage.tag_for_capture_at(&ids[1], now - Duration::hours(10)).unwrap();
+        assert!(matches!(
+            storage.tag_for_capture("missing"),
+            Err(StorageError::NotFound(_))
+        ));
+        let before = storage.get_node(&ids[0]).unwrap().unwrap().storage_strength;
+
+        // Promoting the departure news is the importance event
+        storage.promote_memory(&ids[2]).unwrap();
+        let captured = storage.apply_synaptic_captures().unwrap();
+        let captured_ids: Vec<&str> = captured.iter().map(|c| c.memory_id.as_str()).collect();
+        assert_eq!(captured_ids, vec![ids[0].as_str()]);
+
+        let after = storage.get_node(&ids[0]).unwrap().unwrap().storage_strength;
+        assert!(after > before, "captured memory should be strengthened");
+        let tag = storage.get_synaptic_tag(&ids[0]).unwrap().unwrap();
+        assert!(tag.captured && tag.capture_event.is_some());
+        let missed = storage.get_synaptic_tag(&ids[1]).unwrap().unwrap();
+        assert!(!missed.captured, "a 10-hour-old tag is outside the window");
+
+        // Captures are applied once
+        assert!(storage.apply_synaptic_captures().unwrap().is_empty());
+        assert!((storage.get_node(&ids[0]).unwrap().unwrap().storage_strength - after).abs() < 1e-9);
     }
 
-    /// Update memory state
-    pub fn update_memory_state(&self, memory_id: &str, new_state: &str, reason: &str) -> Result<bool> {
+    #[test]
+    fn test_context_packet_sections_and_budget() {
+        let storage = create_test_storage();
+        let mut ids = Vec::new();
+        for content in [
+            "Deploys to staging run through the canary pipeline",
+            "The canary pipeline blocks on failing smoke tests",
+            "Lunch order preferences for the offsite",
+        ] {
+            ids.push(storage.ingest(IngestInput {
+                content: content.to_string(),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap().id);
+        }
+        storage.save_insight(&InsightRecord {
+            id: "insight-1".to_string(),
+            insight: "Smoke test flakiness delays every canary deploy".to_string(),
+            source_memories: vec![ids[0].clone(), ids[1].clone()],
+            ..InsightRecord::default_at(storage.now())
+        }).unwrap();
         let now = Utc::now();
-
-        // Get old state for transition record
-        if let Some(old_record) = self.get_memory_state(memory_id)? {
-            // Record state transition
-            let writer = self.writer.lock()
-                .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-            writer.execute(
-                "INSERT INTO state_transitions (memory_id, from_state, to_state, reason_type, timestamp)
-                 VALUES (?1, ?2, ?3, ?4, ?5)",
-                params![memory_id, old_record.state, new_state, reason, now.to_rfc3339()],
-            )?;
+        for (id, deadline) in [("due", Some(now - Duration::hours(1))), ("later", Some(now + Duration::days(7))), ("open", None)] {
+            storage.save_intention(&IntentionRecord {
+                id: id.to_string(),
+                content: format!("Follow up on the {id} canary rollout"),
+                trigger_type: "time".to_string(),
+                trigger_data: "{}".to_string(),
+                priority: 2,
+                status: "active".to_string(),
+                created_at: now,
+                deadline,
+                fulfilled_at: None,
+                reminder_count: 0,
+                last_reminded_at: None,
+                notes: None,
+                tags: vec![],
+                related_memories: vec![],
+                snoozed_until: None,
+                source_type: "api".to_string(),
+                source_data: None,
+            }).unwrap();
         }
 
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        let rows = writer.execute(
-            "UPDATE memory_states SET state = ?1, state_entered_at = ?2 WHERE memory_id = ?3",
-            params![new_state, now.to_rfc3339(), memory_id],
-        )?;
-        Ok(rows > 0)
+        let packet = storage.build_context_packet("canary pipeline", 1000).unwrap();
+        assert!(!packet.truncated);
+        assert!(packet.memory_ids.contains(&ids[0]) && packet.memory_ids.contains(&ids[1]));
+        assert!(!packet.memory_ids.contains(&ids[2]));
+        assert_eq!(packet.insight_ids, vec!["insight-1".to_string()]);
+        assert_eq!(packet.intention_ids, vec!["due".to_string()]);
+        let titles: Vec<&str> = packet.sections.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, vec!["Memories", "Insights", "Due intentions"]);
+        assert!(packet.to_prompt().starts_with("## Memories\n- [fact] "));
+
+        let small = storage.build_context_packet("canary pipeline", 20).unwrap();
+        assert!(small.truncated);
+        assert!(small.estimated_tokens <= 20);
+        assert!(small.memory_ids.len() < packet.memory_ids.len());
     }
 
-    /// Record access to memory (updates state)
-    pub fn record_memory_access(&self, memory_id: &str) -> Result<()> {
+    #[test]
+    fn test_session_overview_includes_intention_memories_within_budget() {
+        let storage = create_test_storage();
+        let planned = storage.ingest(IngestInput {
+            content: "Migrate the billing service to the new queue".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap().id;
+        {
+            let writer = storage.writer.lock().unwrap();
+            writer.execute(
+                "UPDATE knowledge_nodes SET last_accessed = ?1 WHERE id = ?2",
+                params![(Utc::now() - Duration::days(90)).to_rfc3339(), planned],
+            ).unwrap();
+        }
+        for i in 0..SESSION_RECENT_MEMORIES {
+            storage.ingest(IngestInput {
+                content: format!("Filler memory number {}", i),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap();
+        }
         let now = Utc::now();
+        storage.save_intention(&IntentionRecord {
+            id: "billing".to_string(),
+            content: "Finish the billing migration".to_string(),
+            trigger_type: "time".to_string(),
+            trigger_data: "{}".to_string(),
+            priority: 2,
+            status: "active".to_string(),
+            created_at: now,
+            deadline: None,
+            fulfilled_at: None,
+            reminder_count: 0,
+            last_reminded_at: None,
+            notes: None,
+            tags: vec![],
+            related_memories: vec![planned.clone()],
+            snoozed_until: None,
+            source_type: "api".to_string(),
+            source_data: None,
+        }).unwrap();
+
+        let overview = storage.build_session_overview(None, 1000).unwrap();
+        assert_eq!(overview.packets.len(), 2);
+        assert!(overview.packets.iter().any(|p| p.memory_ids == vec![planned.clone()]));
+        let fillers = overview.packets.iter().find(|p| p.memory_ids.len() > 1).unwrap();
+        assert_eq!(fillers.memory_ids.len(), SESSION_RECENT_MEMORIES as usize);
+
+        let tight = storage.build_session_overview(None, overview.packets[0].estimated_tokens()).unwrap();
+        assert_eq!(tight.packets.len(), 1);
+        assert_eq!(tight.dropped_clusters, 1);
+        assert!(tight.estimated_tokens <= tight.budget_tokens);
+    }
 
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-
-        // Check if state exists (writer can read too)
-        let exists: bool = writer.query_row(
-            "SELECT EXISTS(SELECT 1 FROM memory_states WHERE memory_id = ?1)",
-            params![memory_id],
-            |row| row.get(0),
-        )?;
+    #[test]
+    #[cfg(feature = "embeddings")]
+    fn test_search_as_of_includes_superseded_fact() {
+        let storage = create_test_storage();
+        let old = storage.ingest(IngestInput {
+            content: "The API rate limit is 100 requests per minute".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap().id;
+        let new = storage.ingest(IngestInput {
+            content: "The API rate limit is 500 requests per minute".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap().id;
 
-        if exists {
+        // The old fact was recorded three months ago and superseded last month
+        let now = Utc::now();
+        let last_month = (now - Duration::days(30)).to_rfc3339();
+        {
+            let writer = storage.writer.lock().unwrap();
             writer.execute(
-                "UPDATE memory_states SET
-                    last_access = ?1,
-                    access_count = access_count + 1,
-                    state = 'active',
-                    state_entered_at = CASE WHEN state != 'active' THEN ?1 ELSE state_entered_at END
-                 WHERE memory_id = ?2",
-                params![now.to_rfc3339(), memory_id],
-            )?;
-        } else {
+                "UPDATE knowledge_nodes SET created_at = ?1, valid_until = ?2 WHERE id = ?3",
+                params![(now - Duration::days(90)).to_rfc3339(), last_month, old],
+            ).unwrap();
             writer.execute(
-                "INSERT INTO memory_states (memory_id, state, last_access, access_count, state_entered_at)
-                 VALUES (?1, 'active', ?2, 1, ?2)",
-                params![memory_id, now.to_rfc3339()],
-            )?;
+                "UPDATE knowledge_nodes SET created_at = ?1, valid_from = ?1 WHERE id = ?2",
+                params![last_month, new],
+            ).unwrap();
         }
-        Ok(())
-    }
-
-    fn row_to_memory_state(row: &rusqlite::Row) -> rusqlite::Result<MemoryStateRecord> {
-        let suppressed_json: String = row.get("suppressed_by")?;
-        let suppressed_by: Vec<String> = serde_json::from_str(&suppressed_json).unwrap_or_default();
 
-        let parse_opt_dt = |s: Option<String>| -> Option<DateTime<Utc>> {
-            s.and_then(|v| DateTime::parse_from_rfc3339(&v).ok().map(|dt| dt.with_timezone(&Utc)))
+        let ids = |results: Vec<SearchResult>| -> Vec<String> {
+            results.into_iter().map(|r| r.node.id).collect()
         };
-
-        Ok(MemoryStateRecord {
-            memory_id: row.get("memory_id")?,
-            state: row.get("state")?,
-            last_access: DateTime::parse_from_rfc3339(&row.get::<_, String>("last_access")?)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now()),
-            access_count: row.get("access_count").unwrap_or(1),
-            state_entered_at: DateTime::parse_from_rfc3339(&row.get::<_, String>("state_entered_at")?)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now()),
-            suppression_until: parse_opt_dt(row.get("suppression_until").ok().flatten()),
-            suppressed_by,
-        })
+        let then = storage.search_as_of("API rate limit", now - Duration::days(60), 10).unwrap();
+        assert_eq!(ids(then), vec![old.clone()]);
+        let current = storage.search_as_of("API rate limit", now, 10).unwrap();
+        assert_eq!(ids(current), vec![new]);
+        assert!(storage.search_as_of("API rate limit", now - Duration::days(120), 10).unwrap().is_empty());
     }
 
-    // ========================================================================
-    // CONSOLIDATION HISTORY
-    // ========================================================================
+    #[test]
+    fn test_node_revisions_record_diffs_and_prune() {
+        let storage = create_test_storage();
+        let id = storage.ingest(IngestInput {
+            content: "Deploy on Fridays\nRun the smoke tests".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap().id;
+        assert!(storage.get_node_revisions(&id, 10).unwrap().is_empty());
+
+        storage.update_node_content(&id, "Never deploy on Fridays\nRun the smoke tests").unwrap();
+        storage
+            .update_node_content_from(&id, "Never deploy on Fridays\nRun the smoke tests\nPage on-call", "smart_ingest:merge")
+            .unwrap();
+        // Unchanged content is not a revision
+        storage.update_node_content(&id, "Never deploy on Fridays\nRun the smoke tests\nPage on-call").unwrap();
+
+        let revisions = storage.get_node_revisions(&id, 10).unwrap();
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(revisions[0].change_source, "smart_ingest:merge");
+        assert_eq!(revisions[0].diff.as_deref(), Some("@@ -1,2 +1,3 @@\n Never deploy on Fridays\n Run the smoke tests\n+Page on-call\n"));
+        assert_eq!(revisions[1].change_source, "update");
+        assert_eq!(revisions[1].old_content, "Deploy on Fridays\nRun the smoke tests");
+        assert!(revisions[1].diff.as_deref().unwrap().contains("-Deploy on Fridays\n+Never deploy on Fridays\n"));
+
+        // Oldest revisions are pruned past the cap
+        for i in 0..25 {
+            storage.update_node_content(&id, &format!("version {i}")).unwrap();
+        }
+        let revisions = storage.get_node_revisions(&id, 100).unwrap();
+        assert_eq!(revisions.len(), 20);
+        assert_eq!(revisions.last().unwrap().old_content, "version 4");
+        assert!(revisions.iter().all(|r| r.diff.is_some()));
 
-    /// Save consolidation history record
-    pub fn save_consolidation_history(&self, record: &ConsolidationHistoryRecord) -> Result<i64> {
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        writer.execute(
-            "INSERT INTO consolidation_history (
-                completed_at, duration_ms, memories_replayed, connections_found,
-                connections_strengthened, connections_pruned, insights_generated
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![
-                record.completed_at.to_rfc3339(),
-                record.duration_ms,
-                record.memories_replayed,
-                record.connections_found,
-                record.connections_strengthened,
-                record.connections_pruned,
-                record.insights_generated,
-            ],
-        )?;
-        Ok(writer.last_insert_rowid())
+        storage.delete_node(&id).unwrap();
+        assert!(storage.get_node_revisions(&id, 100).unwrap().is_empty());
     }
 
-    /// Get last consolidation timestamp
-    pub fn get_last_consolidation(&self) -> Result<Option<DateTime<Utc>>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let result: Option<String> = reader.query_row(
-            "SELECT MAX(completed_at) FROM consolidation_history",
-            [],
-            |row| row.get(0),
-        ).ok().flatten();
+    #[test]
+    fn test_diagnostics_flags_missing_embedding_row() {
+        let storage = create_test_storage();
+        let node = storage.ingest(IngestInput {
+            content: "Diagnostics should notice a missing embedding".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+        {
+            let writer = storage.writer.lock().unwrap();
+            writer.execute(
+                "INSERT OR REPLACE INTO node_embeddings (node_id, embedding, dimensions, model, created_at)
+                 VALUES (?1, ?2, 3, 'test', ?3)",
+                params![node.id, vec![0u8; 12], Utc::now().to_rfc3339()],
+            ).unwrap();
+            writer.execute(
+                "UPDATE knowledge_nodes SET has_embedding = 1 WHERE id = ?1",
+                params![node.id],
+            ).unwrap();
+        }
 
-        Ok(result.and_then(|s| {
-            DateTime::parse_from_rfc3339(&s).ok().map(|dt| dt.with_timezone(&Utc))
-        }))
+        let report = storage.diagnostics().unwrap();
+        assert_eq!(report.check("embeddings").unwrap().status, DiagnosticStatus::Ok);
+        assert_eq!(report.check("fts_integrity").unwrap().status, DiagnosticStatus::Ok);
+        assert_eq!(report.check("timestamps").unwrap().status, DiagnosticStatus::Ok);
+        assert!(report.page_count > 0);
+
+        storage.writer.lock().unwrap()
+            .execute("DELETE FROM node_embeddings WHERE node_id = ?1", params![node.id])
+            .unwrap();
+        let report = storage.diagnostics().unwrap();
+        let check = report.check("embeddings").unwrap();
+        assert_eq!(check.status, DiagnosticStatus::Warn);
+        assert!(check.remediation.as_deref().unwrap().contains("consolidate"));
+        assert!(report.status >= DiagnosticStatus::Warn);
+
+        storage.writer.lock().unwrap()
+            .execute("UPDATE knowledge_nodes SET updated_at = 'yesterday' WHERE id = ?1", params![node.id])
+            .unwrap();
+        let report = storage.diagnostics().unwrap();
+        assert_eq!(report.check("timestamps").unwrap().status, DiagnosticStatus::Warn);
     }
 
-    /// Get consolidation history
-    pub fn get_consolidation_history(&self, limit: i32) -> Result<Vec<ConsolidationHistoryRecord>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let mut stmt = reader.prepare(
-            "SELECT * FROM consolidation_history ORDER BY completed_at DESC LIMIT ?1"
-        )?;
-
-        let rows = stmt.query_map(params![limit], |row| {
-            Ok(ConsolidationHistoryRecord {
-                id: row.get("id")?,
-                completed_at: DateTime::parse_from_rfc3339(&row.get::<_, String>("completed_at")?)
-                    .map(|dt| dt.with_timezone(&Utc))
-                    .unwrap_or_else(|_| Utc::now()),
-                duration_ms: row.get("duration_ms")?,
-                memories_replayed: row.get("memories_replayed").unwrap_or(0),
-                connections_found: row.get("connections_found").unwrap_or(0),
-                connections_strengthened: row.get("connections_strengthened").unwrap_or(0),
-                connections_pruned: row.get("connections_pruned").unwrap_or(0),
-                insights_generated: row.get("insights_generated").unwrap_or(0),
-            })
-        })?;
+    #[test]
+    fn test_diagnose_and_repair_embedding_inconsistencies() {
+        let storage = create_test_storage();
+        let node = storage.ingest(IngestInput {
+            content: "Repair should clear a stale embedding flag".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+        let healthy = storage.diagnose().unwrap();
+        assert_eq!(healthy.node_count, 1);
+        assert!(healthy.is_consistent());
+        assert!(healthy.schema_version > 0);
 
-        let mut result = Vec::new();
-        for row in rows {
-            result.push(row?);
+        {
+            let writer = storage.writer.lock().unwrap();
+            writer.execute_batch("PRAGMA foreign_keys = OFF").unwrap();
+            writer.execute(
+                "INSERT INTO node_embeddings (node_id, embedding, dimensions, model, created_at)
+                 VALUES ('ghost', ?1, 3, 'test', ?2)",
+                params![vec![0u8; 12], Utc::now().to_rfc3339()],
+            ).unwrap();
+            writer.execute_batch("PRAGMA foreign_keys = ON").unwrap();
+            writer.execute(
+                "UPDATE knowledge_nodes SET has_embedding = 1 WHERE id = ?1",
+                params![node.id],
+            ).unwrap();
         }
-        Ok(result)
-    }
-
-    // ========================================================================
-    // DREAM HISTORY PERSISTENCE
-    // ========================================================================
+        let _ = std::thread::scope(|s| {
+            s.spawn(|| {
+                let _guard = storage.session_intent.lock().unwrap();
+                panic!("poison the session intent lock");
+            }).join()
+        });
 
-    /// Save a dream history record
-    pub fn save_dream_history(&self, record: &DreamHistoryRecord) -> Result<i64> {
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        writer.execute(
-            "INSERT INTO dream_history (
-                dreamed_at, duration_ms, memories_replayed, connections_found,
-                insights_generated, memories_strengthened, memories_compressed,
-                phase_nrem1_ms, phase_nrem3_ms, phase_rem_ms, phase_integration_ms,
-                summaries_generated, emotional_memories_processed, creative_connections_found
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
-            params![
-                record.dreamed_at.to_rfc3339(),
-                record.duration_ms,
-                record.memories_replayed,
-                record.connections_found,
-                record.insights_generated,
-                record.memories_strengthened,
-                record.memories_compressed,
-                record.phase_nrem1_ms,
-                record.phase_nrem3_ms,
-                record.phase_rem_ms,
-                record.phase_integration_ms,
-                record.summaries_generated,
-                record.emotional_memories_processed,
-                record.creative_connections_found,
-            ],
-        )?;
-        Ok(writer.last_insert_rowid())
+        let report = storage.diagnose().unwrap();
+        assert_eq!(report.embedding_count, 1);
+        assert_eq!(report.orphaned_embeddings, 1);
+        assert_eq!(report.nodes_missing_embeddings, 1);
+        assert_eq!(report.flagged_without_embedding, 1);
+        assert_eq!(report.poisoned_locks, vec!["session_intent".to_string()]);
+        assert!(!report.is_consistent());
+
+        let repaired = storage.repair().unwrap();
+        assert_eq!(repaired.orphaned_embeddings_deleted, 1);
+        assert_eq!(repaired.embedding_flags_cleared, 1);
+
+        storage.session_intent.clear_poison();
+        let report = storage.diagnose().unwrap();
+        assert_eq!(report.orphaned_embeddings, 0);
+        assert_eq!(report.flagged_without_embedding, 0);
+        assert!(report.is_consistent());
     }
 
-    /// Get last dream timestamp
-    pub fn get_last_dream(&self) -> Result<Option<DateTime<Utc>>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let result: Option<String> = reader.query_row(
-            "SELECT MAX(dreamed_at) FROM dream_history",
-            [],
-            |row| row.get(0),
-        ).ok().flatten();
+    #[test]
+    fn test_detect_intent_persists_only_on_request() {
+        let storage = create_test_storage();
 
-        Ok(result.and_then(|s| {
-            DateTime::parse_from_rfc3339(&s).ok().map(|dt| dt.with_timezone(&Utc))
-        }))
+        let neutral = storage.detect_intent(&[], true).unwrap();
+        assert!(matches!(neutral.primary_intent, DetectedIntent::Unknown));
+        assert_eq!(neutral.confidence, 0.0);
+        assert!(storage.session_intent().is_none());
+
+        let actions = vec![
+            UserAction::file_opened("src/storage/sqlite.rs"),
+            UserAction::error("database is locked"),
+            UserAction::error("database is locked again"),
+            UserAction::search("fix database locked error"),
+        ];
+        let detection = storage.detect_intent(&actions, false).unwrap();
+        assert_eq!(detection.primary_intent.kind(), "debugging");
+        assert!(storage.session_intent().is_none());
+
+        storage.detect_intent(&actions, true).unwrap();
+        assert_eq!(storage.session_intent().unwrap().primary_intent.kind(), "debugging");
+
+        // A neutral detection replaces the context
+        storage.detect_intent(&[], true).unwrap();
+        assert!(storage.session_intent().is_none());
     }
 
-    /// Count memories created since a given timestamp
-    pub fn count_memories_since(&self, since: DateTime<Utc>) -> Result<i64> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let count: i64 = reader.query_row(
-            "SELECT COUNT(*) FROM knowledge_nodes WHERE created_at >= ?1",
-            params![since.to_rfc3339()],
-            |row| row.get(0),
-        )?;
-        Ok(count)
-    }
+    #[test]
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn test_session_intent_biases_recall() {
+        let storage = create_test_storage();
+        storage.ingest(IngestInput {
+            content: "Database locking: SQLite database locking uses a database-wide lock".to_string(),
+            node_type: "concept".to_string(),
+            ..Default::default()
+        }).unwrap();
+        let fix = storage.ingest(IngestInput {
+            content: "Fixed database locking errors by setting busy_timeout".to_string(),
+            node_type: "bug_fix".to_string(),
+            ..Default::default()
+        }).unwrap();
 
-    /// Get last backup timestamp by scanning the backups directory.
-    /// Parses `vestige-YYYYMMDD-HHMMSS.db` filenames.
-    pub fn get_last_backup_timestamp() -> Option<DateTime<Utc>> {
-        let proj_dirs = directories::ProjectDirs::from("com", "vestige", "core")?;
-        let backup_dir = proj_dirs.data_dir().parent()?.join("backups");
+        let recall = || {
+            storage.recall(RecallInput {
+                query: "database locking".to_string(),
+                limit: 1,
+                ..Default::default()
+            }).unwrap()
+        };
+        assert_ne!(recall()[0].id, fix.id);
 
-        if !backup_dir.exists() {
-            return None;
-        }
+        storage.detect_intent(&[
+            UserAction::error("database is locked"),
+            UserAction::error("database is locked again"),
+            UserAction::search("fix database locked error"),
+        ], true).unwrap();
+        assert_eq!(recall()[0].id, fix.id);
 
-        let mut latest: Option<DateTime<Utc>> = None;
+        storage.clear_session_intent().unwrap();
+        assert_ne!(recall()[0].id, fix.id);
+    }
 
-        if let Ok(entries) = std::fs::read_dir(&backup_dir) {
-            for entry in entries.flatten() {
-                let name = entry.file_name();
-                let name_str = name.to_string_lossy();
-                // Parse vestige-YYYYMMDD-HHMMSS.db
-                if let Some(ts_part) = name_str.strip_prefix("vestige-").and_then(|s| s.strip_suffix(".db")) {
-                    if let Ok(naive) = chrono::NaiveDateTime::parse_from_str(ts_part, "%Y%m%d-%H%M%S") {
-                        let dt = naive.and_utc();
-                        if latest.as_ref().is_none_or(|l| dt > *l) {
-                            latest = Some(dt);
-                        }
-                    }
-                }
-            }
+    #[test]
+    fn test_backup_rotation_follows_storage_clock() {
+        let (storage, clock) = create_clocked_storage();
+        let dir = tempfile::tempdir().unwrap();
+        let backup = storage.create_verified_backup(dir.path()).unwrap();
+        for days in [3, 10, 40] {
+            let name = backup_file_name(storage.now() - Duration::days(days));
+            std::fs::copy(&backup.path, dir.path().join(name)).unwrap();
         }
+        let config = BackupConfig { interval_hours: 24, keep_recent: 1, keep_weeks: 2 };
 
-        latest
+        // Ages are measured from the storage clock, not the wall clock
+        assert_eq!(storage.rotate_backups(dir.path(), &config).unwrap().len(), 2);
+        clock.advance(Duration::days(20));
+        assert_eq!(storage.rotate_backups(dir.path(), &config).unwrap().len(), 1);
+        assert_eq!(list_backups_in(dir.path()).unwrap()[0].created_at, backup.created_at);
     }
 
-    // ========================================================================
-    // STATE TRANSITIONS (Audit Trail)
-    // ========================================================================
+    #[test]
+    fn test_verified_backup_rotation_and_restore() {
+        let storage = create_test_storage();
+        let kept = storage.ingest(IngestInput {
+            content: "Backed up before the accident".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let backup = storage.create_verified_backup(dir.path()).unwrap();
+        assert!(backup.size_bytes > 0);
+        let listed = list_backups_in(dir.path()).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].created_at, backup.created_at);
+
+        // Older backups outside the policy are rotated away
+        for days in [3, 10, 40] {
+            let name = backup_file_name(Utc::now() - Duration::days(days));
+            std::fs::copy(&backup.path, dir.path().join(name)).unwrap();
+        }
+        let config = BackupConfig { interval_hours: 24, keep_recent: 1, keep_weeks: 2 };
+        let pruned = storage.rotate_backups(dir.path(), &config).unwrap();
+        assert_eq!(pruned.len(), 2);
+        assert_eq!(list_backups_in(dir.path()).unwrap().len(), 2);
+
+        // A corrupt file fails verification
+        let garbage = dir.path().join("garbage.db");
+        std::fs::write(&garbage, b"not a database").unwrap();
+        assert!(storage.verify_backup(&garbage).is_err());
+        assert!(storage.restore_from_backup(&garbage).is_err());
+
+        storage.delete_node(&kept.id).unwrap();
+        let added = storage.ingest(IngestInput {
+            content: "Written after the backup".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+
+        storage.restore_from_backup(&backup.path).unwrap();
+        assert!(storage.get_node(&kept.id).unwrap().is_some());
+        assert!(storage.get_node(&added.id).unwrap().is_none());
+        let found = storage.keyword_search("accident", 10, 0.0, SearchSyntax::Plain, None).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(storage.diagnostics().unwrap().check("fts_integrity").unwrap().status, DiagnosticStatus::Ok);
+    }
 
-    /// Get state transitions for a memory
-    pub fn get_state_transitions(&self, memory_id: &str, limit: i32) -> Result<Vec<StateTransitionRecord>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let mut stmt = reader.prepare(
-            "SELECT * FROM state_transitions WHERE memory_id = ?1 ORDER BY timestamp DESC LIMIT ?2"
-        )?;
+    #[test]
+    fn test_error_variants_identify_failure_kinds() {
+        let storage = create_test_storage();
+        let empty = storage.ingest(IngestInput {
+            content: "   ".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        });
+        assert!(matches!(empty, Err(StorageError::InvalidInput { field: "content", .. })));
+        assert_eq!(storage.get_stats().unwrap().total_nodes, 0);
 
-        let rows = stmt.query_map(params![memory_id, limit], |row| {
-            Ok(StateTransitionRecord {
-                id: row.get("id")?,
-                memory_id: row.get("memory_id")?,
-                from_state: row.get("from_state")?,
-                to_state: row.get("to_state")?,
-                reason_type: row.get("reason_type")?,
-                reason_data: row.get("reason_data").ok().flatten(),
-                timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>("timestamp")?)
-                    .map(|dt| dt.with_timezone(&Utc))
-                    .unwrap_or_else(|_| Utc::now()),
-            })
-        })?;
+        let backup = storage.backup_to(Path::new("/tmp/bad\nname.db"));
+        assert!(matches!(backup, Err(StorageError::InvalidInput { field: "path", .. })));
 
-        let mut result = Vec::new();
-        for row in rows {
-            result.push(row?);
+        #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+        if !storage.is_embedding_ready() {
+            assert!(matches!(
+                storage.semantic_search("anything", 5, 0.5),
+                Err(StorageError::EmbeddingUnavailable(_))
+            ));
         }
-        Ok(result)
+
+        let lock_error = StorageError::LockPoisoned("Writer".into());
+        assert_eq!(lock_error.to_string(), "Writer lock poisoned");
     }
 
-    /// Create a consistent backup using VACUUM INTO
-    pub fn backup_to(&self, path: &std::path::Path) -> Result<()> {
-        let path_str = path.to_str().ok_or_else(|| {
-            StorageError::Init("Invalid backup path encoding".to_string())
-        })?;
-        // Validate path: reject control characters (except tab) for defense-in-depth
-        if path_str.bytes().any(|b| b < 0x20 && b != b'\t') {
-            return Err(StorageError::Init("Backup path contains invalid characters".to_string()));
+    #[test]
+    fn test_ingest_validation_rejects_bad_input() {
+        let storage = create_test_storage();
+        let input = |content: &str, node_type: &str| IngestInput {
+            content: content.to_string(),
+            node_type: node_type.to_string(),
+            ..Default::default()
+        };
+        let field = |result: Result<KnowledgeNode>| match result {
+            Err(StorageError::InvalidInput { field, .. }) => field,
+            other => panic!("expected InvalidInput, got {:?}", other.map(|n| n.id)),
+        };
+
+        assert_eq!(field(storage.ingest(input("", "fact"))), "content");
+
+        storage.set_max_content_bytes(16).unwrap();
+        assert_eq!(field(storage.ingest(input(&"x".repeat(17), "fact"))), "content");
+        assert!(storage.ingest(input(&"x".repeat(16), "fact")).is_ok());
+        storage.set_max_content_bytes(DEFAULT_MAX_CONTENT_BYTES).unwrap();
+        assert!(storage.set_max_content_bytes(0).is_err());
+
+        let mut sentiment = input("Sentiment out of range", "fact");
+        sentiment.sentiment_score = 1.5;
+        assert_eq!(field(storage.ingest(sentiment.clone())), "sentiment_score");
+        sentiment.sentiment_score = f64::NAN;
+        assert_eq!(field(storage.ingest(sentiment.clone())), "sentiment_score");
+        sentiment.sentiment_score = 0.5;
+        sentiment.sentiment_magnitude = -0.1;
+        assert_eq!(field(storage.ingest(sentiment)), "sentiment_magnitude");
+
+        match storage.ingest(input("Some gossip", "rumor")) {
+            Err(StorageError::InvalidInput { field: "node_type", reason }) => {
+                assert!(reason.contains("rumor"));
+                assert!(reason.contains("insight"), "lists the accepted types: {}", reason);
+            }
+            other => panic!("expected node_type rejection, got {:?}", other.map(|n| n.id)),
         }
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        // VACUUM INTO doesn't support parameterized queries; escape single quotes
-        reader.execute_batch(&format!("VACUUM INTO '{}'", path_str.replace('\'', "''")))?;
-        Ok(())
+        assert!(matches!(
+            storage.supersede("missing", input("Some gossip", "rumor"), "gossip"),
+            Err(StorageError::InvalidInput { field: "node_type", .. })
+        ));
+        assert_eq!(storage.get_stats().unwrap().total_nodes, 1);
     }
 
-    // ========================================================================
-    // v1.9.0 AUTONOMIC: Retention Target, Auto-Promote, Waking Tags, Utility
-    // ========================================================================
+    #[test]
+    fn test_register_node_type_allows_custom_types() {
+        let storage = create_test_storage();
+        assert!(storage.ingest(IngestInput {
+            content: "Built-in types parse case-insensitively".to_string(),
+            node_type: "Insight".to_string(),
+            ..Default::default()
+        }).is_ok());
+        assert!(storage.is_known_node_type("pattern"), "types the bundled tools use are pre-registered");
+        assert!(!storage.is_known_node_type("recipe"));
+
+        assert!(storage.register_node_type("").is_err());
+        assert!(storage.register_node_type("two words").is_err());
+        storage.register_node_type("Recipe").unwrap();
+        assert!(storage.is_known_node_type("recipe"));
+        assert!(storage.node_types().contains(&"recipe".to_string()));
+
+        let node = storage.ingest(IngestInput {
+            content: "Bake at 180C for 25 minutes".to_string(),
+            node_type: "recipe".to_string(),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(node.node_type, "recipe");
+
+        // Registering a built-in is a no-op
+        storage.register_node_type("fact").unwrap();
+        assert_eq!(storage.node_types().iter().filter(|t| *t == "fact").count(), 1);
 
-    /// Get average retention across all memories
-    pub fn get_avg_retention(&self) -> Result<f64> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let avg: f64 = reader.query_row(
-            "SELECT COALESCE(AVG(retention_strength), 0.0) FROM knowledge_nodes",
-            [],
-            |row| row.get(0),
-        )?;
-        Ok(avg)
+        // Restores map types this database doesn't know to `fact`
+        assert_eq!(storage.node_type_for_restore(Some("recipe")), "recipe");
+        assert_eq!(storage.node_type_for_restore(Some("legacy_note")), "fact");
+        assert_eq!(storage.node_type_for_restore(None), "fact");
     }
 
-    /// Get retention distribution in buckets (0-20%, 20-40%, 40-60%, 60-80%, 80-100%)
-    pub fn get_retention_distribution(&self) -> Result<Vec<(String, i64)>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let mut stmt = reader.prepare(
-            "SELECT
-                CASE
-                    WHEN retention_strength < 0.2 THEN '0-20%'
-                    WHEN retention_strength < 0.4 THEN '20-40%'
-                    WHEN retention_strength < 0.6 THEN '40-60%'
-                    WHEN retention_strength < 0.8 THEN '60-80%'
-                    ELSE '80-100%'
-                END as bucket,
-                COUNT(*) as count
-            FROM knowledge_nodes
-            GROUP BY bucket
-            ORDER BY bucket"
-        )?;
-
-        let rows = stmt.query_map([], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
-        })?;
+    #[test]
+    fn test_ingest_returns_existing_exact_duplicate() {
+        let storage = create_test_storage();
+        let input = |content: &str| IngestInput {
+            content: content.to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        };
 
-        let mut result = Vec::new();
-        for row in rows {
-            result.push(row?);
+        let first = storage.ingest_with_outcome(input("The deploy key rotates monthly")).unwrap();
+        assert!(!first.returned_existing);
+        let retry = storage.ingest_with_outcome(input("  The deploy key\nrotates monthly ")).unwrap();
+        assert!(retry.returned_existing);
+        assert_eq!(retry.node.id, first.node.id);
+        assert_eq!(storage.ingest(input("The deploy key rotates monthly")).unwrap().id, first.node.id);
+        assert_eq!(storage.get_stats().unwrap().total_nodes, 1);
+
+        // Different type or namespace is not a duplicate
+        let mut concept = input("The deploy key rotates monthly");
+        concept.node_type = "concept".to_string();
+        assert_ne!(storage.ingest(concept).unwrap().id, first.node.id);
+        let mut namespaced = input("The deploy key rotates monthly");
+        namespaced.namespace = Some("ops".to_string());
+        assert_ne!(storage.ingest(namespaced).unwrap().id, first.node.id);
+
+        let mut strict = input("The deploy key rotates monthly");
+        strict.on_duplicate = DuplicatePolicy::Error;
+        match storage.ingest(strict) {
+            Err(StorageError::Duplicate { existing_id }) => assert_eq!(existing_id, first.node.id),
+            other => panic!("expected Duplicate, got {:?}", other.map(|n| n.id)),
         }
-        Ok(result)
+        let mut anyway = input("The deploy key rotates monthly");
+        anyway.on_duplicate = DuplicatePolicy::CreateAnyway;
+        assert_ne!(storage.ingest(anyway).unwrap().id, first.node.id);
+        assert_eq!(storage.get_stats().unwrap().total_nodes, 4);
+
+        // Edited content is matched by its new hash
+        storage.update_node_content(&first.node.id, "The deploy key rotates weekly").unwrap();
+        let edited = storage.ingest_with_outcome(input("The deploy key rotates weekly")).unwrap();
+        assert!(edited.returned_existing);
+        assert_eq!(edited.node.id, first.node.id);
     }
 
-    /// Get retention trend (improving/declining/stable) from retention snapshots
-    pub fn get_retention_trend(&self) -> Result<String> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
+    #[test]
+    fn test_ingest_batch_validates_before_writing() {
+        let storage = create_test_storage();
+        let input = |content: &str| IngestInput {
+            content: content.to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        };
 
-        let snapshots: Vec<f64> = reader.prepare(
-            "SELECT avg_retention FROM retention_snapshots ORDER BY snapshot_at DESC LIMIT 5"
-        )?.query_map([], |row| row.get(0))?
-            .filter_map(|r| r.ok())
-            .collect();
+        let rejected = storage.ingest_batch(vec![input("Valid first"), input("")]);
+        assert!(matches!(rejected, Err(StorageError::InvalidInput { field: "content", .. })));
+        assert_eq!(storage.get_stats().unwrap().total_nodes, 0);
+
+        let outcomes = storage
+            .ingest_batch(vec![input("Alpha"), input("Beta"), input("Alpha")])
+            .unwrap();
+        assert_eq!(outcomes.len(), 3);
+        assert!(!outcomes[0].returned_existing && !outcomes[1].returned_existing);
+        assert!(outcomes[2].returned_existing);
+        assert_eq!(outcomes[2].node.id, outcomes[0].node.id);
+        assert_eq!(storage.get_stats().unwrap().total_nodes, 2);
+    }
 
-        if snapshots.len() < 3 {
-            return Ok("insufficient_data".to_string());
+    #[test]
+    fn test_preview_intervals_are_labelled_per_rating() {
+        let storage = create_test_storage();
+        let node = storage.ingest(IngestInput {
+            content: "Preview intervals for a review UI".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+
+        let preview = storage.preview_review(&node.id).unwrap();
+        let intervals = storage.preview_intervals(&node.id).unwrap();
+        assert_eq!(intervals.len(), 4);
+        assert_eq!(intervals[0].0, Rating::Again);
+        assert_eq!(intervals[3].0, Rating::Easy);
+        assert_eq!(intervals[2].1, format!("{}d", preview.good.interval));
+        let again = match preview.again.interval {
+            0 => "<1d".to_string(),
+            days => format!("{}d", days),
+        };
+        assert_eq!(intervals[0].1, again);
+        assert!(matches!(storage.preview_intervals("missing"), Err(StorageError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_set_fsrs_parameters_persists_across_reopen() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("fsrs.db");
+
+        let mut params = FSRSParameters::default();
+        params.weights[0] = 0.4;
+        params.weights[20] = 0.25;
+        params.desired_retention = 0.85;
+        params.enable_fuzz = false;
+        {
+            let storage = Storage::new(Some(db_path.clone())).unwrap();
+            assert_eq!(storage.fsrs_parameters().unwrap().weights, crate::fsrs::FSRS6_WEIGHTS);
+            storage.set_fsrs_parameters(params.clone()).unwrap();
+            assert_eq!(storage.get_fsrs_w20().unwrap(), 0.25);
+
+            let mut bad = params.clone();
+            bad.weights[5] = f64::NAN;
+            assert!(matches!(
+                storage.set_fsrs_parameters(bad),
+                Err(StorageError::InvalidInput { .. })
+            ));
         }
 
-        // Compare recent vs older snapshots
-        let recent_avg = snapshots.iter().take(2).sum::<f64>() / 2.0;
-        let older_avg = snapshots.iter().skip(2).sum::<f64>() / (snapshots.len() - 2) as f64;
+        let storage = Storage::new(Some(db_path)).unwrap();
+        let loaded = storage.fsrs_parameters().unwrap();
+        assert_eq!(loaded.weights, params.weights);
+        assert_eq!(loaded.desired_retention, 0.85);
+        assert!(!loaded.enable_fuzz);
+    }
 
-        let diff = recent_avg - older_avg;
-        Ok(if diff > 0.02 {
-            "improving".to_string()
-        } else if diff < -0.02 {
-            "declining".to_string()
-        } else {
-            "stable".to_string()
-        })
+    /// Embedder standing in for fastembed: slow, and records every node it embeds
+    fn mock_embedder<'a>(
+        storage: &'a Storage,
+        embedded: &'a RefCell<Vec<String>>,
+    ) -> impl FnMut(&str, &str) -> Result<()> + 'a {
+        move |id, _content| {
+            std::thread::sleep(std::time::Duration::from_millis(2));
+            storage.writer.lock().unwrap().execute(
+                "UPDATE knowledge_nodes SET has_embedding = 1 WHERE id = ?1",
+                params![id],
+            )?;
+            embedded.borrow_mut().push(id.to_string());
+            Ok(())
+        }
     }
 
-    /// Save a retention snapshot (called during consolidation)
-    pub fn save_retention_snapshot(&self, avg_retention: f64, total: i64, below_target: i64, gc_triggered: bool) -> Result<()> {
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        writer.execute(
-            "INSERT INTO retention_snapshots (snapshot_at, avg_retention, total_memories, memories_below_target, gc_triggered)
-             VALUES (?1, ?2, ?3, ?4, ?5)",
-            params![Utc::now().to_rfc3339(), avg_retention, total, below_target, gc_triggered],
-        )?;
-        Ok(())
+    #[test]
+    fn test_backfill_resumes_where_it_left_off() {
+        let storage = create_test_storage();
+        for i in 0..25 {
+            storage.ingest(IngestInput {
+                content: format!("Backfill candidate number {}", i),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap();
+        }
+        assert_eq!(storage.embedding_backfill_status().unwrap(), None);
+
+        let embedded = RefCell::new(Vec::new());
+        let options = BackfillOptions { batch_size: 4, max_nodes: Some(10), ..Default::default() };
+        let first = storage
+            .run_backfill(None, &options, None, mock_embedder(&storage, &embedded))
+            .unwrap();
+        assert_eq!((first.processed, first.remaining), (10, 15));
+        assert!((first.percent_complete() - 40.0).abs() < 1e-9);
+        let token = first.resume_token.clone().unwrap();
+        assert_eq!(storage.embedding_backfill_status().unwrap().unwrap().resume_token, Some(token.clone()));
+
+        // A slice that is cancelled up front processes nothing and keeps the cursor
+        let cancel = AtomicBool::new(true);
+        let cancelled = storage
+            .run_backfill(Some(&token), &options, Some(&cancel), mock_embedder(&storage, &embedded))
+            .unwrap();
+        assert!(cancelled.cancelled);
+        assert_eq!(cancelled.resume_token, Some(token.clone()));
+        assert_eq!(cancelled.processed, 10);
+
+        // A time budget stops the slow embedder early
+        let timed = BackfillOptions {
+            batch_size: 4,
+            time_budget: Some(std::time::Duration::from_millis(1)),
+            ..Default::default()
+        };
+        let short = storage
+            .run_backfill(Some(&token), &timed, None, mock_embedder(&storage, &embedded))
+            .unwrap();
+        assert!(short.processed > 10 && short.processed < 25);
+
+        let rest = storage
+            .run_backfill(short.resume_token.as_deref(), &BackfillOptions::default(), None, mock_embedder(&storage, &embedded))
+            .unwrap();
+        assert_eq!(rest.resume_token, None);
+        assert_eq!((rest.processed, rest.embedded, rest.remaining), (25, 25, 0));
+        assert_eq!(rest.percent_complete(), 100.0);
+
+        let mut ids = embedded.into_inner();
+        assert_eq!(ids.len(), 25);
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 25, "no node embedded twice");
+        assert_eq!(storage.get_stats().unwrap().nodes_with_embeddings, 25);
     }
 
-    /// Count memories below a given retention threshold
-    pub fn count_memories_below_retention(&self, threshold: f64) -> Result<i64> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let count: i64 = reader.query_row(
-            "SELECT COUNT(*) FROM knowledge_nodes WHERE retention_strength < ?1",
-            params![threshold],
-            |row| row.get(0),
-        )?;
-        Ok(count)
+    #[test]
+    fn test_schema_version_refuses_newer_databases() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("versioned.db");
+        let storage = Storage::new(Some(db_path.clone())).unwrap();
+        let latest = super::super::migrations::latest_version();
+        assert_eq!(storage.schema_version().unwrap(), latest);
+        let backup = storage.create_verified_backup(&dir.path().join("backups")).unwrap();
+        drop(storage);
+
+        // Reopening at the same version is a no-op
+        let storage = Storage::new(Some(db_path.clone())).unwrap();
+        assert_eq!(storage.schema_version().unwrap(), latest);
+        drop(storage);
+
+        let future = |path: &Path| {
+            let conn = Connection::open(path).unwrap();
+            conn.execute(
+                "UPDATE meta SET value = ?1 WHERE key = 'schema_version'",
+                params![(latest + 1).to_string()],
+            ).unwrap();
+            conn.execute("UPDATE meta SET value = '99.0.0' WHERE key = 'crate_version'", []).unwrap();
+        };
+        future(&db_path);
+        match Storage::new(Some(db_path.clone())) {
+            Err(StorageError::SchemaTooNew { found, supported, written_by }) => {
+                assert_eq!(found, latest + 1);
+                assert_eq!(supported, latest);
+                assert_eq!(written_by, "99.0.0");
+            }
+            other => panic!("expected SchemaTooNew, got {:?}", other.map(|_| ())),
+        }
+
+        // A backup from a newer release is refused before it overwrites anything
+        let storage = create_test_storage();
+        future(&backup.path);
+        assert!(matches!(
+            storage.restore_from_backup(&backup.path),
+            Err(StorageError::SchemaTooNew { .. })
+        ));
     }
 
-    /// Auto-GC memories below threshold (used by retention target system)
-    pub fn gc_below_retention(&self, threshold: f64, min_age_days: i64) -> Result<i64> {
-        let cutoff = (Utc::now() - Duration::days(min_age_days)).to_rfc3339();
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        let deleted = writer.execute(
-            "DELETE FROM knowledge_nodes WHERE retention_strength < ?1 AND created_at < ?2",
-            params![threshold, cutoff],
-        )? as i64;
-        Ok(deleted)
+    #[test]
+    #[cfg(not(feature = "encryption"))]
+    fn test_encryption_ops_need_feature() {
+        let storage = create_test_storage();
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(storage.rekey("new-key"), Err(StorageError::EncryptionDisabled)));
+        assert!(matches!(
+            storage.backup_encrypted_to(&dir.path().join("export.db"), "offsite-key"),
+            Err(StorageError::EncryptionDisabled)
+        ));
+        assert!(!dir.path().join("export.db").exists());
     }
 
-    /// Check for auto-promote candidates: memories accessed 3+ times in last 24h
-    pub fn auto_promote_frequent_access(&self) -> Result<i64> {
-        let twenty_four_hours_ago = (Utc::now() - Duration::hours(24)).to_rfc3339();
-        let now = Utc::now().to_rfc3339();
+    #[test]
+    #[cfg(feature = "encryption")]
+    fn test_rekey_and_encrypted_export() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("encrypted.db");
+        let storage = Storage::new_with_key(Some(db_path.clone()), "old-key").unwrap();
+        let node = storage.ingest(IngestInput {
+            content: "Secret that must survive rekeying".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
 
-        // Find memories with 3+ accesses in last 24h
-        let candidates: Vec<String> = {
-            let reader = self.reader.lock()
-                .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-            let mut stmt = reader.prepare(
-                "SELECT node_id, COUNT(*) as access_count
-                 FROM memory_access_log
-                 WHERE accessed_at >= ?1
-                 GROUP BY node_id
-                 HAVING access_count >= 3"
-            )?;
-            stmt.query_map(params![twenty_four_hours_ago], |row| row.get(0))?
-                .filter_map(|r| r.ok())
-                .collect()
+        let opens_with = |path: &Path, key: &str| {
+            let conn = Connection::open(path).unwrap();
+            conn.pragma_update(None, "key", key).unwrap();
+            conn.query_row("SELECT COUNT(*) FROM knowledge_nodes", [], |row| row.get::<_, i64>(0))
+                .is_ok()
         };
 
-        if candidates.is_empty() {
-            return Ok(0);
-        }
+        let backup_dir = dir.path().join("backups");
+        let backup = storage.rekey_with_backup_dir("new-key", &backup_dir).unwrap();
+        assert!(opens_with(&backup.path, "old-key"));
+        assert!(!opens_with(&db_path, "old-key"));
+        assert!(opens_with(&db_path, "new-key"));
+        // Both connections keep working under the new key
+        assert!(storage.get_node(&node.id).unwrap().is_some());
+        storage.ingest(IngestInput {
+            content: "Written after rekeying".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
 
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        let mut promoted = 0i64;
-        for id in &candidates {
-            let rows = writer.execute(
-                "UPDATE knowledge_nodes SET
-                    retrieval_strength = MIN(1.0, retrieval_strength + 0.10),
-                    retention_strength = MIN(1.0, retention_strength + 0.05),
-                    last_accessed = ?1
-                WHERE id = ?2 AND retrieval_strength < 0.95",
-                params![now, id],
-            )?;
-            if rows > 0 {
-                promoted += 1;
+        let export = dir.path().join("offsite.db");
+        storage.backup_encrypted_to(&export, "offsite-key").unwrap();
+        assert!(opens_with(&export, "offsite-key"));
+        assert!(!opens_with(&export, "new-key"));
+        assert!(storage.backup_encrypted_to(&export, "offsite-key").is_err());
+    }
+
+    #[test]
+    fn test_speculative_prefetch_follows_access_sequence() {
+        let storage = create_test_storage();
+        let ids: Vec<String> = ["alpha", "beta", "gamma", "delta"]
+            .iter()
+            .map(|name| {
+                storage.ingest(IngestInput {
+                    content: format!("Prefetch memory {}", name),
+                    node_type: "fact".to_string(),
+                    ..Default::default()
+                }).unwrap().id
+            })
+            .collect();
+        let (a, b, c, d) = (&ids[0], &ids[1], &ids[2], &ids[3]);
+
+        // Three sessions a -> b -> c and one a -> d, an hour apart
+        let start = Utc::now() - Duration::days(1);
+        let sessions = [[a, b, c], [a, b, c], [a, b, c], [a, d, c]];
+        {
+            let writer = storage.writer.lock().unwrap();
+            for (s, session) in sessions.iter().enumerate() {
+                for (i, id) in session.iter().enumerate() {
+                    let at = start + Duration::hours(s as i64) + Duration::minutes(i as i64);
+                    writer.execute(
+                        "INSERT INTO memory_access_log (node_id, access_type, accessed_at)
+                         VALUES (?1, 'search_hit', ?2)",
+                        params![id, at.to_rfc3339()],
+                    ).unwrap();
+                }
             }
         }
+        let log_size = || -> i64 {
+            storage.reader.lock().unwrap()
+                .query_row("SELECT COUNT(*) FROM memory_access_log", [], |r| r.get(0))
+                .unwrap()
+        };
+        let logged = log_size();
+        storage.writer.lock().unwrap()
+            .execute("UPDATE knowledge_nodes SET retrieval_strength = 0.5", [])
+            .unwrap();
+
+        let context = PredictionContext {
+            recent_memory_ids: vec![a.clone()],
+            ..PredictionContext::new()
+        };
+        let predictions = storage.speculative_prefetch(context, 5).unwrap();
+        let found: Vec<(&str, f64)> = predictions.iter().map(|p| (p.memory_id.as_str(), p.confidence)).collect();
+        assert_eq!(found, vec![(b.as_str(), 0.75), (d.as_str(), 0.25)]);
+        assert_eq!(predictions[0].content_preview, "Prefetch memory beta");
+        assert!(matches!(
+            &predictions[0].trigger,
+            PredictionTrigger::CoAccessPattern { related_memory_id } if related_memory_id == a
+        ));
 
-        Ok(promoted)
+        // Warmed softly: a boost scaled by confidence, and nothing added to the access log
+        let strength = |id: &str| storage.get_node(id).unwrap().unwrap().retrieval_strength;
+        assert!((strength(b) - (0.5 + 0.01 * 0.75)).abs() < 1e-9);
+        assert!((strength(d) - (0.5 + 0.01 * 0.25)).abs() < 1e-9);
+        assert_eq!(strength(c), 0.5);
+        assert_eq!(log_size(), logged);
+
+        // Without recent memories the last logged access (c) seeds the
+        // prediction; sessions are an hour apart so nothing follows it
+        assert!(storage.speculative_prefetch(PredictionContext::new(), 5).unwrap().is_empty());
+
+        // Limit applies, and the sequence cannot be seen from before it happened
+        let context = PredictionContext {
+            recent_memory_ids: vec![a.clone()],
+            ..PredictionContext::new()
+        };
+        assert_eq!(storage.speculative_prefetch(context, 1).unwrap().len(), 1);
+        let context = PredictionContext {
+            recent_memory_ids: vec![a.clone()],
+            timestamp: Some(start - Duration::hours(1)),
+            ..Default::default()
+        };
+        assert!(storage.speculative_prefetch(context, 5).unwrap().is_empty());
+
+        // An unreadable log row fails the prediction instead of vanishing from it
+        storage.writer.lock().unwrap().execute(
+            "INSERT INTO memory_access_log (node_id, access_type, accessed_at)
+             VALUES (?1, 'search_hit', '2000-01-01 garbled')",
+            params![a],
+        ).unwrap();
+        let context = PredictionContext {
+            recent_memory_ids: vec![a.clone()],
+            ..PredictionContext::new()
+        };
+        assert!(storage.speculative_prefetch(context, 5).is_err());
     }
 
-    /// Set waking tag on a memory (marks it for preferential dream replay)
-    pub fn set_waking_tag(&self, memory_id: &str) -> Result<()> {
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        writer.execute(
-            "UPDATE knowledge_nodes SET waking_tag = TRUE, waking_tag_at = ?1 WHERE id = ?2",
-            params![Utc::now().to_rfc3339(), memory_id],
-        )?;
-        Ok(())
+    #[test]
+    fn test_knowledge_edge_round_trip() {
+        let storage = create_test_storage();
+        let ingest = |content: &str| storage.ingest(IngestInput {
+            content: content.to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap().id;
+        let cause = ingest("The cache was never invalidated");
+        let effect = ingest("Users saw stale dashboards");
+
+        let mut edge = KnowledgeEdge::new(cause.clone(), effect.clone(), EdgeType::PartOf);
+        edge.weight = 0.8;
+        edge.created_by = Some("user".to_string());
+        storage.save_knowledge_edge(&edge).unwrap();
+
+        let loaded = storage.get_knowledge_edge(&edge.id).unwrap().unwrap();
+        assert_eq!(loaded.edge_type, EdgeType::PartOf);
+        assert_eq!(loaded.valid_from, edge.valid_from);
+        assert!(loaded.valid_until.is_none());
+        assert_eq!(loaded.created_by.as_deref(), Some("user"));
+        assert_eq!(storage.get_knowledge_edges(&effect).unwrap().len(), 1);
+        assert_eq!(storage.get_knowledge_edges(&cause).unwrap()[0].id, edge.id);
+
+        let dangling = KnowledgeEdge::new(cause.clone(), "missing".to_string(), EdgeType::Causal);
+        assert!(matches!(
+            storage.save_knowledge_edge(&dangling),
+            Err(StorageError::NotFound(id)) if id == "missing"
+        ));
+
+        assert!(storage.delete_knowledge_edge(&edge.id).unwrap());
+        assert!(!storage.delete_knowledge_edge(&edge.id).unwrap());
+        assert!(storage.get_knowledge_edges(&cause).unwrap().is_empty());
     }
 
-    /// Clear waking tags (called after dream processes them)
-    pub fn clear_waking_tags(&self) -> Result<i64> {
-        let writer = self.writer.lock()
-            .map_err(|_| StorageError::Init("Writer lock poisoned".into()))?;
-        let cleared = writer.execute(
-            "UPDATE knowledge_nodes SET waking_tag = FALSE, waking_tag_at = NULL WHERE waking_tag = TRUE",
-            [],
-        )? as i64;
-        Ok(cleared)
+    #[test]
+    fn test_namespaces_isolate_memories() {
+        let storage = create_test_storage();
+        let acme = storage.with_namespace("acme");
+        let globex = storage.with_namespace("globex");
+        let mut globex_ids = Vec::new();
+        for i in 0..4 {
+            acme.ingest(IngestInput {
+                content: format!("Quarterly roadmap review {} for the acme account", i),
+                ..Default::default()
+            }).unwrap();
+            globex_ids.push(globex.ingest(IngestInput {
+                content: format!("Quarterly roadmap review {} for the globex account", i),
+                ..Default::default()
+            }).unwrap().id);
+        }
+        storage.ingest(IngestInput {
+            content: "Quarterly roadmap review template".to_string(),
+            ..Default::default()
+        }).unwrap();
+
+        // Other namespaces rank just as well, so leakage would show up here
+        for search_mode in [SearchMode::Keyword, SearchMode::Hybrid] {
+            let hits = acme.recall(RecallInput {
+                query: "quarterly roadmap review".to_string(),
+                limit: 3,
+                search_mode,
+                ..Default::default()
+            }).unwrap();
+            assert_eq!(hits.len(), 3, "{search_mode:?}");
+            assert!(
+                hits.iter().all(|n| n.namespace.as_deref() == Some("acme")),
+                "{search_mode:?} recall leaked across namespaces"
+            );
+        }
+        let everything = storage.recall(RecallInput {
+            query: "quarterly roadmap review".to_string(),
+            limit: 20,
+            search_mode: SearchMode::Keyword,
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(everything.len(), 9);
+
+        assert!(acme.get_node(&globex_ids[0]).unwrap().is_none());
+        assert!(globex.get_node(&globex_ids[0]).unwrap().is_some());
+        assert_eq!(acme.get_all_nodes(100, 0).unwrap().len(), 4);
+        assert_eq!(acme.get_stats().unwrap().total_nodes, 4);
+        assert_eq!(storage.get_stats().unwrap().total_nodes, 9);
+        assert_eq!(storage.list_namespaces().unwrap(), vec!["acme", "globex"]);
+
+        acme.save_insight(&InsightRecord {
+            id: "acme-insight".to_string(),
+            insight: "Roadmap reviews slip every quarter".to_string(),
+            ..InsightRecord::default_at(storage.now())
+        }).unwrap();
+        // Saving through the global API keeps the insight's namespace
+        storage.mark_insight_feedback("acme-insight", "useful").unwrap();
+        storage.save_insight(&acme.get_insights(10).unwrap()[0]).unwrap();
+        assert_eq!(acme.get_insights(10).unwrap().len(), 1);
+        assert!(globex.get_insights(10).unwrap().is_empty());
+
+        acme.run_consolidation().unwrap();
+        assert_eq!(globex.get_all_nodes(100, 0).unwrap().len(), 4);
     }
 
-    /// Get waking-tagged memories for preferential dream replay
-    pub fn get_waking_tagged_memories(&self, limit: i32) -> Result<Vec<KnowledgeNode>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let mut stmt = reader.prepare(
-            "SELECT * FROM knowledge_nodes WHERE waking_tag = TRUE ORDER BY waking_tag_at DESC LIMIT ?1"
-        )?;
-        let nodes = stmt.query_map(params![limit], |row| Self::row_to_node(row))?;
-        let mut result = Vec::new();
-        for node in nodes {
-            result.push(node?);
+    #[test]
+    fn test_namespaced_retention_gc_spares_other_namespaces() {
+        let (storage, clock) = create_clocked_storage();
+        let acme = storage.with_namespace("acme");
+        let globex = storage.with_namespace("globex");
+        for i in 0..3 {
+            acme.ingest(IngestInput {
+                content: format!("Faded acme note {i}"),
+                ..Default::default()
+            })
+            .unwrap();
+            globex
+                .ingest(IngestInput {
+                    content: format!("Faded globex note {i}"),
+                    ..Default::default()
+                })
+                .unwrap();
         }
-        Ok(result)
+        storage
+            .lock_writer()
+            .unwrap()
+            .execute("UPDATE knowledge_nodes SET retention_strength = 0.01", [])
+            .unwrap();
+        storage
+            .set_retention_policy(RetentionPolicy {
+                target: 0.5,
+                gc_below: 0.1,
+                min_age_days: 0,
+                enabled: true,
+            })
+            .unwrap();
+        clock.advance(Duration::seconds(1));
+
+        let result = acme.run_consolidation_step("retention_gc").unwrap();
+        assert!(matches!(result, StepResult::RetentionGc { below_target: 3, collected: 3, .. }));
+        assert!(acme.get_all_nodes(10, 0).unwrap().is_empty());
+        assert_eq!(globex.get_all_nodes(10, 0).unwrap().len(), 3);
     }
 
-    /// Get memories with their connection data for graph visualization
-    pub fn get_memory_subgraph(&self, center_id: &str, depth: u32, max_nodes: usize) -> Result<(Vec<KnowledgeNode>, Vec<ConnectionRecord>)> {
-        let mut visited_ids: std::collections::HashSet<String> = std::collections::HashSet::new();
-        let mut frontier = vec![center_id.to_string()];
-        visited_ids.insert(center_id.to_string());
+    #[test]
+    fn test_namespaced_recall_sees_past_foreign_matches() {
+        let storage = create_test_storage();
+        let acme = storage.with_namespace("acme");
+        let globex = storage.with_namespace("globex");
+        for i in 0..1100 {
+            globex
+                .ingest(IngestInput {
+                    content: format!("Incident postmortem {i} for the globex outage"),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+        for i in 0..3 {
+            acme.ingest(IngestInput {
+                content: format!("Incident postmortem {i} for the acme outage"),
+                ..Default::default()
+            })
+            .unwrap();
+        }
+        // Every foreign match outranks the acme ones
+        storage
+            .lock_writer()
+            .unwrap()
+            .execute(
+                "UPDATE knowledge_nodes SET retention_strength = 0.5 WHERE namespace = 'acme'",
+                [],
+            )
+            .unwrap();
 
-        // BFS to discover connected nodes up to depth
-        for _ in 0..depth {
-            let mut next_frontier = Vec::new();
-            for id in &frontier {
-                let connections = self.get_connections_for_memory(id)?;
-                for conn in &connections {
-                    let other_id = if conn.source_id == *id { &conn.target_id } else { &conn.source_id };
-                    if visited_ids.insert(other_id.clone()) {
-                        next_frontier.push(other_id.clone());
-                        if visited_ids.len() >= max_nodes {
-                            break;
-                        }
-                    }
-                }
-                if visited_ids.len() >= max_nodes {
-                    break;
-                }
-            }
-            frontier = next_frontier;
-            if frontier.is_empty() || visited_ids.len() >= max_nodes {
-                break;
+        let hits = acme
+            .recall(RecallInput {
+                query: "incident postmortem".to_string(),
+                limit: 2,
+                search_mode: SearchMode::Keyword,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|n| n.namespace.as_deref() == Some("acme")));
+    }
+
+    #[test]
+    fn test_bulk_update_reports_per_id_outcomes() {
+        let storage = create_test_storage();
+        let ingest = |content: &str| storage.ingest(IngestInput {
+            content: content.to_string(),
+            node_type: "fact".to_string(),
+            tags: vec!["old".to_string()],
+            ..Default::default()
+        }).unwrap().id;
+        let a = ingest("Bulk memory alpha");
+        let b = ingest("Bulk memory beta");
+        let c = ingest("Bulk memory gamma");
+        let before = storage.get_node(&a).unwrap().unwrap().retention_strength;
+
+        let ids = vec![a.clone(), "missing".to_string(), b.clone(), a.clone()];
+        let outcomes = storage.bulk_update(&ids, &BulkAction::Demote).unwrap();
+        let summary: Vec<(&str, bool)> = outcomes.iter().map(|o| (o.id.as_str(), o.ok)).collect();
+        assert_eq!(summary, vec![(a.as_str(), true), ("missing", false), (b.as_str(), true)]);
+        assert!(outcomes[0].retention_strength.unwrap() < before);
+
+        let retag = BulkAction::Retag { tags: vec!["bulk".to_string()] };
+        storage.bulk_update(&[b.clone(), c.clone()], &retag).unwrap();
+        assert_eq!(storage.get_node(&c).unwrap().unwrap().tags, vec!["bulk".to_string()]);
+        assert_eq!(storage.get_node(&a).unwrap().unwrap().tags, vec!["old".to_string()]);
+
+        let outcomes = storage.bulk_update(&[a.clone(), c.clone()], &BulkAction::Delete).unwrap();
+        assert!(outcomes.iter().all(|o| o.ok && o.retention_strength.is_none()));
+        assert!(storage.get_node(&a).unwrap().is_none());
+        assert!(storage.get_node(&b).unwrap().is_some());
+        assert_eq!(storage.get_all_nodes(10, 0).unwrap().len(), 1);
+    }
+
+    /// Fixed corpus for the consolidation golden test: ages, sentiment,
+    /// strengths and recent accesses chosen so most steps have work to do
+    fn seed_consolidation_corpus(storage: &Storage) -> Vec<String> {
+        let now = Utc::now();
+        let mut ids = Vec::new();
+        for i in 0..12i64 {
+            let node = storage.ingest(IngestInput {
+                content: format!("Seed memory {} about subsystem {} and its failure modes", i, i % 4),
+                node_type: if i % 2 == 0 { "fact" } else { "event" }.to_string(),
+                tags: vec![format!("subsystem-{}", i % 4)],
+                sentiment_score: if i % 3 == 0 { -0.8 } else { 0.1 },
+                sentiment_magnitude: if i % 3 == 0 { 0.9 } else { 0.1 },
+                on_duplicate: DuplicatePolicy::CreateAnyway,
+                ..Default::default()
+            }).unwrap();
+            {
+                let writer = storage.writer.lock().unwrap();
+                writer.execute(
+                    "UPDATE knowledge_nodes SET created_at = ?1, last_accessed = ?2,
+                        storage_strength = ?3, retrieval_strength = ?4, retention_strength = ?4,
+                        stability = ?5
+                     WHERE id = ?6",
+                    params![
+                        (now - Duration::days(60)).to_rfc3339(),
+                        (now - Duration::days(1 + i * 5)).to_rfc3339(),
+                        1.0 + (i % 5) as f64,
+                        if i >= 6 { 0.05 } else { 0.9 },
+                        if i >= 6 { 0.5 } else { 10.0 + i as f64 },
+                        node.id,
+                    ],
+                ).unwrap();
             }
+            ids.push(node.id);
         }
-
-        // Fetch nodes
-        let mut nodes = Vec::new();
-        for id in &visited_ids {
-            if let Some(node) = self.get_node(id)? {
-                nodes.push(node);
+        for id in &ids[..2] {
+            for _ in 0..3 {
+                storage.log_access(id, "search_hit").unwrap();
             }
         }
+        ids
+    }
 
-        // Fetch edges between visited nodes
-        let all_connections = self.get_all_connections()?;
-        let edges: Vec<ConnectionRecord> = all_connections
-            .into_iter()
-            .filter(|c| visited_ids.contains(&c.source_id) && visited_ids.contains(&c.target_id))
+    /// Counts from the pre-pipeline monolithic consolidation on the seed
+    /// corpus; the default pipeline must reproduce them exactly
+    #[test]
+    fn test_consolidation_golden_counts() {
+        let storage = create_test_storage();
+        let ids = seed_consolidation_corpus(&storage);
+        storage.set_retention_policy(RetentionPolicy { gc_below: 0.43, ..Default::default() }).unwrap();
+
+        let result = storage.run_consolidation().unwrap();
+        assert_eq!(result.nodes_processed, 12);
+        assert_eq!(result.decay_applied, 12);
+        assert_eq!(result.nodes_promoted, 5);
+        assert_eq!(result.embeddings_generated, 0);
+        assert_eq!(result.duplicates_merged, 0);
+        assert_eq!(result.activations_computed, 2);
+        assert_eq!(result.w20_optimized, None);
+        assert_eq!(result.steps.len(), ConsolidationPipeline::step_names().len());
+
+        // Emotional promotion boosted every third memory's storage strength
+        let strengths: Vec<f64> = ids[..10]
+            .iter()
+            .map(|id| storage.get_node(id).unwrap().unwrap().storage_strength)
             .collect();
+        assert_eq!(strengths, vec![1.5, 2.0, 3.0, 6.0, 5.0, 1.0, 3.0, 3.0, 4.0, 7.5]);
 
-        Ok((nodes, edges))
+        // The retention target collected the two weakest memories
+        assert_eq!(storage.get_all_nodes(100, 0).unwrap().len(), 10);
+        assert!(storage.get_node(&ids[10]).unwrap().is_none());
+        assert!(storage.get_node(&ids[11]).unwrap().is_none());
+        let (below, gc_triggered): (i64, bool) = storage.reader.lock().unwrap()
+            .query_row(
+                "SELECT memories_below_target, gc_triggered FROM retention_snapshots",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((below, gc_triggered), (2, true));
     }
 
-    /// Get recent state transitions across all memories (system-wide changelog)
-    pub fn get_recent_state_transitions(&self, limit: i32) -> Result<Vec<StateTransitionRecord>> {
-        let reader = self.reader.lock()
-            .map_err(|_| StorageError::Init("Reader lock poisoned".into()))?;
-        let mut stmt = reader.prepare(
-            "SELECT * FROM state_transitions ORDER BY timestamp DESC LIMIT ?1"
-        )?;
+    #[test]
+    fn test_consolidation_reports_each_step() {
+        let storage = create_test_storage();
+        storage.ingest(IngestInput {
+            content: "Consolidation progress memory".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
 
-        let rows = stmt.query_map(params![limit], |row| {
-            Ok(StateTransitionRecord {
-                id: row.get("id")?,
-                memory_id: row.get("memory_id")?,
-                from_state: row.get("from_state")?,
-                to_state: row.get("to_state")?,
-                reason_type: row.get("reason_type")?,
-                reason_data: row.get("reason_data").ok().flatten(),
-                timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>("timestamp")?)
-                    .map(|dt| dt.with_timezone(&Utc))
-                    .unwrap_or_else(|_| Utc::now()),
-            })
-        })?;
+        let mut reports = Vec::new();
+        storage
+            .run_consolidation_with_progress(&mut |p| reports.push(p))
+            .unwrap();
 
-        let mut result = Vec::new();
-        for row in rows {
-            result.push(row?);
+        assert_eq!(reports.len(), 19);
+        for (i, report) in reports.iter().enumerate() {
+            assert_eq!(report.step, i + 1);
+            assert_eq!(report.total, 19);
         }
-        Ok(result)
+        assert_eq!(reports[0].label, "FSRS decay");
     }
-}
 
-// ============================================================================
-// TESTS
-// ============================================================================
+    #[test]
+    fn test_consolidation_config_disables_steps_and_persists() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("config.db");
+        let storage = Storage::new(Some(db_path.clone())).unwrap();
+        let ids = seed_consolidation_corpus(&storage);
+        storage.set_retention_policy(RetentionPolicy { gc_below: 0.43, ..Default::default() }).unwrap();
+
+        let mut config = storage.consolidation_config().unwrap();
+        assert_eq!(config, ConsolidationConfig::default());
+        config.enabled_steps.remove("promote_emotional");
+        config.enabled_steps.remove("retention_gc");
+        storage.set_consolidation_config(&config).unwrap();
+        config.enabled_steps.insert("defrag".to_string());
+        assert!(matches!(
+            storage.set_consolidation_config(&config),
+            Err(StorageError::InvalidInput { field: "enabled_steps", .. })
+        ));
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::tempdir;
+        drop(storage);
+        let storage = Storage::new(Some(db_path)).unwrap();
+        storage.set_retention_policy(RetentionPolicy { gc_below: 0.43, ..Default::default() }).unwrap();
+        let mut reports = Vec::new();
+        let result = storage.run_consolidation_with_progress(&mut |p| reports.push(p)).unwrap();
+        assert_eq!(reports.len(), 17);
+        assert!(!result.steps.contains_key("promote_emotional"));
+        assert!(!result.steps.contains_key("retention_gc"));
+        // Only auto-promotion promoted anything, and nothing was collected
+        assert_eq!(result.nodes_promoted, 1);
+        assert_eq!(storage.get_node(&ids[3]).unwrap().unwrap().storage_strength, 4.0);
+        assert_eq!(storage.get_all_nodes(100, 0).unwrap().len(), 12);
+
+        // A disabled step still runs on demand
+        let promoted = storage.run_consolidation_step("promote_emotional").unwrap();
+        assert_eq!(promoted, StepResult::PromoteEmotional { promoted: 4 });
+        assert_eq!(storage.get_node(&ids[3]).unwrap().unwrap().storage_strength, 6.0);
+        assert!(matches!(
+            storage.run_consolidation_step("defrag"),
+            Err(StorageError::InvalidInput { field: "step", .. })
+        ));
+    }
 
-    fn create_test_storage() -> Storage {
+    #[test]
+    fn test_compression_visits_memories_beyond_the_newest_slice() {
+        let (storage, clock) = create_clocked_storage();
+        let mut old_ids = Vec::new();
+        for content in [
+            "Rolled back the deploy after the health check failed",
+            "Deploy rollback needed a manual cache flush",
+            "Second rollback of the week traced to a bad migration",
+        ] {
+            let node = storage.ingest(IngestInput {
+                content: content.to_string(),
+                node_type: "fact".to_string(),
+                tags: vec!["deploy".to_string(), "rollback".to_string()],
+                ..Default::default()
+            }).unwrap();
+            old_ids.push(node.id);
+        }
+        {
+            let writer = storage.lock_writer().unwrap();
+            writer.execute("UPDATE knowledge_nodes SET retention_strength = 0.2", []).unwrap();
+        }
+
+        // A thousand newer memories push the old ones out of any head slice
+        clock.advance(Duration::days(40));
+        for i in 0..1000 {
+            storage.ingest(IngestInput {
+                content: format!("Fresh note number {}", i),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap();
+        }
+        let newest: HashSet<String> =
+            storage.get_all_nodes(500, 0).unwrap().into_iter().map(|n| n.id).collect();
+        assert!(old_ids.iter().all(|id| !newest.contains(id)));
+
+        let mut visited = Vec::new();
+        let filter = NodeFilter {
+            created_before: Some(storage.now() - Duration::days(30)),
+            max_retention: Some(0.5),
+            ..Default::default()
+        };
+        let count = storage.for_each_node(&filter, |node| {
+            visited.push(node.id);
+            Ok(())
+        }).unwrap();
+        assert_eq!(count, 3);
+        visited.sort();
+        old_ids.sort();
+        assert_eq!(visited, old_ids);
+
+        let compressed = storage.run_consolidation_step("compression").unwrap();
+        assert_eq!(compressed, StepResult::Compression { memories_compressed: 3 });
+    }
+
+    #[test]
+    fn test_consolidation_skips_when_nothing_changed() {
         let dir = tempdir().unwrap();
-        let db_path = dir.path().join("test.db");
-        Storage::new(Some(db_path)).unwrap()
+        let db_path = dir.path().join("skip.db");
+        let storage = Storage::new(Some(db_path.clone())).unwrap();
+        seed_consolidation_corpus(&storage);
+        assert!(storage.mutations_since_consolidation() > 0);
+
+        let first = storage.run_consolidation().unwrap();
+        assert!(first.skipped.is_none());
+        assert!(first.decay_applied > 0);
+        assert_eq!(storage.mutations_since_consolidation(), 0);
+        let last_run = storage.get_last_consolidation().unwrap().unwrap();
+
+        // No changes: only the fast path runs, and it is recorded as skipped
+        let second = storage.run_consolidation().unwrap();
+        assert_eq!(second.skipped.as_deref(), Some("no changes"));
+        assert!(second.steps.is_empty() && second.step_timings.is_empty());
+        assert_eq!(second.decay_applied, 0);
+        let history = storage.get_consolidation_history(10).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].skipped_reason.as_deref(), Some("no changes"));
+        assert!(history[1].skipped_reason.is_none());
+        assert_eq!(storage.get_last_consolidation().unwrap().unwrap(), last_run);
+
+        // The dirty count survives a restart and forces the next run
+        storage.ingest(IngestInput {
+            content: "A change after consolidation".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+        drop(storage);
+        let storage = Storage::new(Some(db_path)).unwrap();
+        assert_eq!(storage.diagnose().unwrap().mutations_since_consolidation, 1);
+        assert!(storage.run_consolidation().unwrap().skipped.is_none());
+
+        // Skipping stops once the last full run is older than max_skip_hours
+        let config = ConsolidationConfig { max_skip_hours: 0, ..Default::default() };
+        storage.set_consolidation_config(&config).unwrap();
+        storage.run_consolidation().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        assert!(storage.run_consolidation().unwrap().skipped.is_none());
     }
 
     #[test]
-    fn test_storage_creation() {
+    fn test_forgetting_curve_decreases_over_time() {
         let storage = create_test_storage();
-        let stats = storage.get_stats().unwrap();
-        assert_eq!(stats.total_nodes, 0);
+        let node = storage.ingest(IngestInput {
+            content: "Forgetting curve memory".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+
+        let base = node.last_accessed;
+        let points: Vec<_> = [-1, 1, 7, 30, 365].iter().map(|d| base + Duration::days(*d)).collect();
+        let curve = storage.forgetting_curve(&node.id, &points).unwrap();
+
+        assert_eq!(curve[0], 1.0);
+        assert!(curve.windows(2).all(|w| w[1] < w[0]));
+        assert!(curve.iter().all(|r| (0.0..=1.0).contains(r)));
+        assert_eq!(storage.retrievability_at(&node.id, points[2]).unwrap(), curve[2]);
+        assert!(matches!(
+            storage.retrievability_at("missing", base),
+            Err(StorageError::NotFound(_))
+        ));
     }
 
     #[test]
-    fn test_ingest_and_get() {
+    fn test_graph_filters_and_communities() {
         let storage = create_test_storage();
+        let ids: Vec<String> = (0..5)
+            .map(|i| storage.ingest(IngestInput {
+                content: format!("Graph node {i}"),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap().id)
+            .collect();
+        let now = Utc::now();
+        // 0 -causal- 1 -semantic(weak)- 2, and a separate 3 -semantic- 4 pair
+        for (a, b, strength, link_type) in [(0, 1, 0.9, "causal"), (1, 2, 0.2, "semantic"), (3, 4, 0.7, "semantic")] {
+            storage.save_connection(&ConnectionRecord {
+                source_id: ids[a].clone(),
+                target_id: ids[b].clone(),
+                strength,
+                link_type: link_type.to_string(),
+                created_at: now,
+                last_activated: now,
+                activation_count: 0,
+            }).unwrap();
+        }
 
-        let input = IngestInput {
-            content: "Test memory content".to_string(),
+        let (nodes, edges) = storage.get_memory_subgraph(&ids[0], 3, 50).unwrap();
+        assert_eq!((nodes.len(), edges.len()), (3, 2));
+
+        let strong = GraphFilter { min_strength: 0.5, ..Default::default() };
+        let (nodes, edges) = storage.get_memory_subgraph_filtered(&ids[0], 3, 50, &strong).unwrap();
+        assert_eq!((nodes.len(), edges.len()), (2, 1));
+
+        let semantic = GraphFilter { link_types: Some(vec!["Semantic".to_string()]), ..Default::default() };
+        let (nodes, _) = storage.get_memory_subgraph_filtered(&ids[0], 3, 50, &semantic).unwrap();
+        assert_eq!(nodes.len(), 1);
+
+        // Memories outside the namespace are neither shown nor walked through
+        storage.lock_writer().unwrap().execute(
+            "UPDATE knowledge_nodes SET namespace = 'acme' WHERE id IN (?1, ?2, ?3)",
+            params![ids[0], ids[2], ids[3]],
+        ).unwrap();
+        let acme = GraphFilter { namespace: Some("acme".to_string()), ..Default::default() };
+        let (nodes, edges) = storage.get_memory_subgraph_filtered(&ids[0], 3, 50, &acme).unwrap();
+        assert_eq!((nodes.len(), edges.len()), (1, 0));
+        assert!(storage.graph_communities(&acme).unwrap().is_empty());
+
+        let communities = storage.graph_communities(&GraphFilter::default()).unwrap();
+        assert_eq!(communities.iter().map(Vec::len).collect::<Vec<_>>(), vec![3, 2]);
+        assert_eq!(storage.graph_communities(&strong).unwrap().len(), 2);
+
+        let states = storage.graph_node_states(&[ids[0].clone(), "missing".to_string()]).unwrap();
+        assert_eq!(states.len(), 1);
+        assert_eq!(states[&ids[0]].state, "active");
+    }
+
+    #[test]
+    fn test_retention_gc_respects_disabled_policy() {
+        let storage = create_test_storage();
+        let node = storage.ingest(IngestInput {
+            content: "Long-forgotten trivia".to_string(),
             node_type: "fact".to_string(),
             ..Default::default()
-        };
-
-        let node = storage.ingest(input).unwrap();
-        assert!(!node.id.is_empty());
-        assert_eq!(node.content, "Test memory content");
+        }).unwrap();
+        let long_ago = (Utc::now() - Duration::days(400)).to_rfc3339();
+        storage.writer.lock().unwrap().execute(
+            "UPDATE knowledge_nodes SET created_at = ?1, last_accessed = ?1, stability = 0.1,
+                storage_strength = 0.01, retention_strength = 0.01 WHERE id = ?2",
+            params![long_ago, node.id],
+        ).unwrap();
+
+        storage.set_retention_policy(RetentionPolicy { enabled: false, ..Default::default() }).unwrap();
+        storage.run_consolidation().unwrap();
+        assert!(storage.get_node(&node.id).unwrap().is_some());
 
-        let retrieved = storage.get_node(&node.id).unwrap();
-        assert!(retrieved.is_some());
-        assert_eq!(retrieved.unwrap().content, "Test memory content");
+        storage.set_retention_policy(RetentionPolicy::default()).unwrap();
+        storage.run_consolidation().unwrap();
+        assert!(storage.get_node(&node.id).unwrap().is_none());
     }
 
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
     #[test]
-    fn test_search() {
+    fn test_smart_ingest_flags_value_flip_contradiction() {
         let storage = create_test_storage();
-
-        let input = IngestInput {
-            content: "The mitochondria is the powerhouse of the cell".to_string(),
+        let old = storage.ingest(IngestInput {
+            content: "The request timeout is 30s".to_string(),
             node_type: "fact".to_string(),
             ..Default::default()
+        }).unwrap();
+        let candidate = CandidateMemory {
+            id: old.id.clone(),
+            content: old.content.clone(),
+            embedding: vec![1.0, 0.0, 0.0],
+            retrieval_strength: old.retrieval_strength,
+            retention_strength: old.retention_strength,
+            tags: old.tags.clone(),
+            source: old.source.clone(),
+            was_demoted: false,
+            was_promoted: false,
         };
 
-        storage.ingest(input).unwrap();
-
-        let results = storage.search("mitochondria", 10).unwrap();
-        assert_eq!(results.len(), 1);
-        assert!(results[0].content.contains("mitochondria"));
+        // ~0.95 cosine: the gate alone would merge the two
+        let result = storage
+            .gate_and_apply(
+                IngestInput {
+                    content: "The request timeout is 5s".to_string(),
+                    node_type: "fact".to_string(),
+                    ..Default::default()
+                },
+                &[0.95, 0.31, 0.0],
+                &[candidate],
+            )
+            .unwrap();
+        assert_eq!(result.decision, "create");
+        assert_ne!(result.node.id, old.id);
+        assert_eq!(result.contradicts, vec![old.id.clone()]);
+        assert_eq!(storage.get_node(&old.id).unwrap().unwrap().content, old.content);
+
+        let contradictions = storage.list_contradictions(10).unwrap();
+        assert_eq!(contradictions.len(), 1);
+        assert_eq!(contradictions[0].source_id, result.node.id);
+        assert_eq!(contradictions[0].target_id, old.id);
+        assert!(contradictions[0].metadata.as_deref().unwrap().contains("30s -> 5s"));
+
+        assert!(storage.resolve_contradiction(&contradictions[0].id).unwrap());
+        assert!(storage.list_contradictions(10).unwrap().is_empty());
+        assert!(!storage.resolve_contradiction(&contradictions[0].id).unwrap());
     }
 
     #[test]
-    fn test_review() {
+    fn test_update_node_content_with_modes() {
         let storage = create_test_storage();
-
-        let input = IngestInput {
-            content: "Test review".to_string(),
+        let node = storage.ingest(IngestInput {
+            content: "Deploys run on Fridays".to_string(),
             node_type: "fact".to_string(),
             ..Default::default()
-        };
+        }).unwrap();
+        storage.mark_reviewed(&node.id, Rating::Good).unwrap();
+        let reviewed = storage.mark_reviewed(&node.id, Rating::Good).unwrap();
 
-        let node = storage.ingest(input).unwrap();
-        assert_eq!(node.reps, 0);
+        storage.update_node_content_with(&node.id, "Deploys run on Thursdays", ContentUpdateMode::Keep).unwrap();
+        let kept = storage.get_node(&node.id).unwrap().unwrap();
+        assert_eq!(kept.content, "Deploys run on Thursdays");
+        assert_eq!(kept.stability, reviewed.stability);
+        assert_eq!(kept.reps, reviewed.reps);
+        assert!(kept.updated_at >= reviewed.updated_at);
+
+        storage
+            .update_node_content_with(&node.id, "Deploys run daily", ContentUpdateMode::SoftReset(0.5))
+            .unwrap();
+        let softened = storage.get_node(&node.id).unwrap().unwrap();
+        assert!((softened.stability - reviewed.stability * 0.5).abs() < 1e-9);
+        assert_eq!(softened.difficulty, reviewed.difficulty);
+
+        storage
+            .update_node_content_with(&node.id, "Deploys are continuous", ContentUpdateMode::FullReset)
+            .unwrap();
+        let reset = storage.get_node(&node.id).unwrap().unwrap();
+        assert_eq!(reset.reps, 0);
+        assert_eq!(reset.lapses, 0);
+        assert_eq!(reset.stability, FSRSState::default().stability);
+    }
 
-        let reviewed = storage.mark_reviewed(&node.id, Rating::Good).unwrap();
-        assert_eq!(reviewed.reps, 1);
+    #[test]
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn test_source_trust_ranks_trusted_source_first() {
+        let storage = create_test_storage();
+        let ingest = |source: &str| storage.ingest(IngestInput {
+            content: "Release builds strip debug symbols".to_string(),
+            node_type: "fact".to_string(),
+            source: Some(source.to_string()),
+            ..Default::default()
+        }).unwrap().id;
+        let curated = ingest("notes:curated");
+        let captured = ingest("tool:bash");
+
+        storage.set_source_trust("notes:curated", 1.4).unwrap();
+        storage.set_source_trust("tool:*", 0.6).unwrap();
+        assert!(storage.set_source_trust("tool:*", 2.0).is_err());
+        assert_eq!(storage.get_source_trusts().unwrap().len(), 2);
+
+        let results = storage.hybrid_search("debug symbols", 10, 0.3, 0.7).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].node.id, curated);
+        assert_eq!(results[0].source_trust, 1.4);
+        assert_eq!(results[1].node.id, captured);
+        assert_eq!(results[1].source_trust, 0.6);
+
+        let trusts = storage.get_source_trusts().unwrap();
+        assert_eq!(SourceTrust::resolve(&trusts, Some("tool:bash")), 0.6);
+        assert_eq!(SourceTrust::resolve(&trusts, Some("notes:curated/sub")), 1.0);
+        assert_eq!(SourceTrust::resolve(&trusts, None), 1.0);
+        assert!(storage.remove_source_trust("tool:*").unwrap());
     }
 
     #[test]
-    fn test_delete() {
+    fn test_diff_and_revert_revisions() {
         let storage = create_test_storage();
+        let id = storage.ingest(IngestInput {
+            content: "Cache TTL is 60s".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap().id;
+        storage.update_node_content(&id, "Cache TTL is 300s").unwrap();
+        storage.update_node_content(&id, "Cache TTL is 300s\nFlush on deploy").unwrap();
+
+        let revisions = storage.get_node_revisions(&id, 10).unwrap();
+        let (second, first) = (revisions[0].id, revisions[1].id);
+        let diff = storage.diff_revisions(&id, first, Some(second)).unwrap();
+        assert!(diff.diff.contains("-Cache TTL is 60s\n+Cache TTL is 300s\n"));
+        let to_current = storage.diff_revisions(&id, first, None).unwrap();
+        assert!(to_current.diff.contains("+Flush on deploy"));
+        assert!(matches!(storage.diff_revisions(&id, 9999, None), Err(StorageError::NotFound(_))));
+
+        let reverted = storage.revert_node(&id, first).unwrap();
+        assert_eq!(reverted.content, "Cache TTL is 60s");
+        let revisions = storage.get_node_revisions(&id, 10).unwrap();
+        assert_eq!(revisions[0].change_source, format!("revert:{first}"));
+        assert_eq!(revisions[0].old_content, "Cache TTL is 300s\nFlush on deploy");
+        assert!(matches!(storage.revert_node("missing", first), Err(StorageError::NotFound(_))));
+    }
 
-        let input = IngestInput {
-            content: "To be deleted".to_string(),
+    #[test]
+    fn test_supersede_is_atomic_and_chained() {
+        let storage = create_test_storage();
+        let input = |content: &str| IngestInput {
+            content: content.to_string(),
             node_type: "fact".to_string(),
             ..Default::default()
         };
+        let first = storage.ingest(input("Staging runs on port 8080")).unwrap();
+        let second = storage
+            .supersede(&first.id, input("Staging runs on port 9090"), "Correction")
+            .unwrap();
+        let third = storage
+            .supersede(&second.id, input("Staging runs on port 9443"), "Updated")
+            .unwrap();
+
+        let demoted = storage.get_node(&first.id).unwrap().unwrap();
+        assert!(demoted.retrieval_strength < first.retrieval_strength);
+        let edges = storage.get_knowledge_edges(&second.id).unwrap();
+        let edge = edges.iter().find(|e| e.target_id == first.id).unwrap();
+        assert_eq!(edge.edge_type, EdgeType::Refinement);
+        assert!(edge.metadata.as_deref().unwrap().contains("Correction"));
+        let demotion = &storage.get_access_history(&first.id, 1).unwrap()[0];
+        assert_eq!(demotion.access_type, AccessKind::Demote.as_str());
+        assert_eq!(demotion.details.as_deref(), Some("Correction"));
+        let revisions = storage.get_node_revisions(&second.id, 10).unwrap();
+        assert_eq!(revisions[0].old_content, "Staging runs on port 8080");
+
+        let ids = |chain: Vec<KnowledgeNode>| chain.into_iter().map(|n| n.id).collect::<Vec<_>>();
+        let expected = vec![third.id.clone(), second.id.clone(), first.id.clone()];
+        assert_eq!(ids(storage.get_superseded_chain(&first.id).unwrap()), expected);
+        assert_eq!(ids(storage.get_superseded_chain(&second.id).unwrap()), expected);
+
+        // A missing target writes nothing
+        let before = storage.get_stats().unwrap().total_nodes;
+        assert!(matches!(
+            storage.supersede("missing", input("Orphan"), "Correction"),
+            Err(StorageError::NotFound(_))
+        ));
+        assert_eq!(storage.get_stats().unwrap().total_nodes, before);
+    }
 
-        let node = storage.ingest(input).unwrap();
-        assert!(storage.get_node(&node.id).unwrap().is_some());
-
-        let deleted = storage.delete_node(&node.id).unwrap();
-        assert!(deleted);
-        assert!(storage.get_node(&node.id).unwrap().is_none());
+    #[test]
+    fn test_emotional_encoding_tags_flashbulbs_for_replay() {
+        let storage = create_test_storage();
+        let ingest = |content: &str| {
+            storage.ingest(IngestInput {
+                content: content.to_string(),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap().id
+        };
+        let outage = ingest("Production is down and customer data is at risk");
+        let promoted = ingest("Prefer small pull requests");
+        let calm = ingest("The readme mentions the license");
+
+        storage.record_emotional_encoding(&outage, -0.8, true, true).unwrap();
+        storage.record_emotional_encoding(&calm, 0.1, false, false).unwrap();
+        storage.set_waking_tag(&promoted).unwrap();
+
+        // Flashbulb memories replay ahead of more recently tagged ones
+        let tagged: Vec<String> = storage
+            .get_waking_tagged_memories(10)
+            .unwrap()
+            .into_iter()
+            .map(|n| n.id)
+            .collect();
+        assert_eq!(tagged, vec![outage.clone(), promoted]);
+
+        let node = storage.get_node(&outage).unwrap().unwrap();
+        assert_eq!(node.emotional_valence, Some(-0.8));
+        assert_eq!(node.flashbulb, Some(true));
+        assert!(matches!(
+            storage.record_emotional_encoding("missing", 0.0, false, true),
+            Err(StorageError::NotFound(_))
+        ));
     }
 
     #[test]
-    fn test_dream_history_save_and_get_last() {
+    fn test_async_ingest_inherits_neighbor_tags() {
         let storage = create_test_storage();
-        let now = Utc::now();
+        let tagged = |content: &str, tags: &[&str]| IngestInput {
+            content: content.to_string(),
+            node_type: "fact".to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        };
+        storage.ingest(tagged("Rust async functions return futures that do nothing until awaited", &["rust", "async"])).unwrap();
+        storage.ingest(tagged("Tokio runs async tasks on a work-stealing runtime", &["async", "tokio"])).unwrap();
+        storage.ingest(tagged("Holding a mutex guard across an await point blocks other async tasks", &["async", "intent:Debugging"])).unwrap();
+        storage.ingest(tagged("Postgres vacuum reclaims dead tuples", &["postgres"])).unwrap();
+
+        let content = "Cancelling async tasks in Rust: dropping a future stops it at the next await";
+        let suggestions = storage.suggest_tags(content, 5).unwrap();
+        let async_tag = suggestions.iter().find(|s| s.tag == "async").unwrap();
+        assert!(async_tag.confidence >= auto_tag::AUTO_TAG_THRESHOLD);
+        assert!(suggestions.iter().all(|s| s.tag != "postgres" && !s.tag.starts_with("intent:")));
+
+        let node = storage.ingest(IngestInput {
+            content: content.to_string(),
+            node_type: "fact".to_string(),
+            auto_tag: true,
+            ..Default::default()
+        }).unwrap();
+        assert!(node.tags.contains(&"async".to_string()));
+
+        // Suggestions are only applied when asked for
+        let plain = storage.ingest(IngestInput {
+            on_duplicate: DuplicatePolicy::CreateAnyway,
+            ..tagged(content, &[])
+        }).unwrap();
+        assert!(plain.tags.is_empty());
+    }
 
-        let record = DreamHistoryRecord {
-            dreamed_at: now,
-            duration_ms: 1500,
-            memories_replayed: 50,
-            connections_found: 12,
-            insights_generated: 3,
-            memories_strengthened: 8,
-            memories_compressed: 2,
-            phase_nrem1_ms: None,
-            phase_nrem3_ms: None,
-            phase_rem_ms: None,
-            phase_integration_ms: None,
-            summaries_generated: None,
-            emotional_memories_processed: None,
-            creative_connections_found: None,
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    #[test]
+    fn test_mock_embeddings_drive_smart_ingest_and_dedup() {
+        let storage = create_mock_storage();
+        assert!(storage.is_embedding_ready());
+        let fact = |content: &str| IngestInput {
+            content: content.to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
         };
 
-        let id = storage.save_dream_history(&record).unwrap();
-        assert!(id > 0);
+        let first = storage.smart_ingest(fact("The deploy pipeline runs integration tests before release")).unwrap();
+        assert_eq!(first.decision, "create");
+        assert_eq!(
+            storage.get_node_embedding(&first.node.id).unwrap().unwrap().len(),
+            storage.index_dimensions()
+        );
+
+        let again = storage.smart_ingest(fact("The deploy pipeline runs integration tests before release")).unwrap();
+        assert_ne!(again.decision, "create");
+        assert_eq!(again.node.id, first.node.id);
+        assert!(again.similarity.unwrap() > 0.99);
+
+        let unrelated = storage.smart_ingest(fact("Grandma's lasagna recipe needs fresh basil")).unwrap();
+        assert_eq!(unrelated.decision, "create");
+
+        storage.ingest(IngestInput {
+            on_duplicate: DuplicatePolicy::CreateAnyway,
+            ..fact("The deploy pipeline runs integration tests before each release")
+        }).unwrap();
+        let clusters = storage.find_duplicate_clusters(0.9).unwrap();
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].len(), 2);
+        assert!(!clusters[0].contains(&unrelated.node.id));
+    }
 
-        let last = storage.get_last_dream().unwrap();
-        assert!(last.is_some());
-        // Timestamps should be within 1 second (RFC3339 round-trip)
-        let diff = (last.unwrap() - now).num_seconds().abs();
-        assert!(diff <= 1, "Timestamp mismatch: diff={}s", diff);
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    #[test]
+    fn test_mock_embeddings_drive_hybrid_search() {
+        let storage = create_mock_storage();
+        let target = storage.ingest(IngestInput {
+            content: "Connection pool exhaustion caused the checkout outage".to_string(),
+            node_type: "event".to_string(),
+            ..Default::default()
+        }).unwrap();
+        storage.ingest(IngestInput {
+            content: "The office plants get watered on Mondays".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+
+        let semantic = storage.semantic_search("checkout outage pool exhaustion", 5, 0.3).unwrap();
+        assert_eq!(semantic.len(), 1);
+        assert_eq!(semantic[0].node.id, target.id);
+
+        let results = storage.hybrid_search("checkout outage", 5, 0.5, 0.5).unwrap();
+        assert_eq!(results[0].node.id, target.id);
+        assert!(results[0].semantic_score.is_some());
+    }
+
+    /// Backend with a fixed, non-Matryoshka output width
+    #[cfg(feature = "embeddings")]
+    struct FixedWidthBackend {
+        dimensions: usize,
+        returned: usize,
+    }
+
+    #[cfg(feature = "embeddings")]
+    impl EmbeddingBackend for FixedWidthBackend {
+        fn embed(&self, text: &str) -> std::result::Result<Vec<f32>, crate::embeddings::EmbeddingError> {
+            let mut vector = vec![1.0; self.returned];
+            vector[0] = text.len() as f32;
+            Ok(vector)
+        }
+
+        fn dimensions(&self) -> usize {
+            self.dimensions
+        }
+
+        fn model_name(&self) -> &str {
+            "test/fixed-width"
+        }
+
+        fn is_ready(&self) -> bool {
+            true
+        }
     }
 
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
     #[test]
-    fn test_dream_history_empty() {
-        let storage = create_test_storage();
-        let last = storage.get_last_dream().unwrap();
-        assert!(last.is_none());
+    fn test_custom_backend_records_model_and_detects_dimension_mismatch() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let backend = FixedWidthBackend { dimensions: 8, returned: 8 };
+        let storage = Storage::with_embedding_backend(Some(db_path.clone()), Box::new(backend)).unwrap();
+        assert_eq!(storage.index_dimensions(), 8);
+
+        let node = storage.ingest(IngestInput {
+            content: "Custom backends embed at their own width".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(storage.get_node_embedding(&node.id).unwrap().unwrap().len(), 8);
+        assert_eq!(storage.get_stats().unwrap().embedding_model.as_deref(), Some("test/fixed-width"));
+        drop(storage);
+
+        // The index was built at 8 dimensions, which the default model cannot produce
+        let reopened = Storage::with_embedding_service(Some(db_path), EmbeddingService::mock());
+        assert!(matches!(reopened, Err(StorageError::Config(_))));
+
+        // A backend returning vectors of another width than it declares is rejected
+        let liar = EmbeddingService::with_backend(Box::new(FixedWidthBackend { dimensions: 8, returned: 4 }));
+        assert!(liar.embed("mismatch").is_err());
     }
 
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
     #[test]
-    fn test_count_memories_since() {
-        let storage = create_test_storage();
-        let before = Utc::now() - Duration::seconds(10);
+    fn test_repeated_outcomes_reorder_hybrid_ranking() {
+        let storage = create_mock_storage();
+        let fact = |content: &str| storage.ingest(IngestInput {
+            content: content.to_string(),
+            node_type: "fact".to_string(),
+            on_duplicate: DuplicatePolicy::CreateAnyway,
+            ..Default::default()
+        }).unwrap();
+        let failing = fact("Restart the payment worker to clear stuck deploy jobs");
+        let working = fact("Restart the payment worker queue to clear stuck deploy jobs");
 
-        for i in 0..5 {
-            storage.ingest(IngestInput {
-                content: format!("Count test memory {}", i),
-                node_type: "fact".to_string(),
-                ..Default::default()
-            }).unwrap();
+        for _ in 0..3 {
+            storage.record_outcome(&[&failing.id], OutcomeType::NotHelpful, 1.0, None).unwrap();
+        }
+        for _ in 0..3 {
+            storage.record_outcome(&[&working.id], OutcomeType::Helpful, 1.0, None).unwrap();
         }
 
-        let count = storage.count_memories_since(before).unwrap();
-        assert_eq!(count, 5);
-
-        let future = Utc::now() + Duration::hours(1);
-        let count_future = storage.count_memories_since(future).unwrap();
-        assert_eq!(count_future, 0);
+        let results = storage.hybrid_search("restart payment worker stuck deploy", 5, 0.5, 0.5).unwrap();
+        let rank = |id: &str| results.iter().position(|r| r.node.id == id).unwrap();
+        assert!(rank(&working.id) < rank(&failing.id));
     }
 
     #[test]
-    fn test_get_last_backup_timestamp_no_panic() {
-        // Static method should not panic even if no backups exist
-        let _ = Storage::get_last_backup_timestamp();
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    fn test_pinned_low_retention_outranks_unpinned() {
+        let storage = create_mock_storage();
+        let fact = |content: &str| storage.ingest(IngestInput {
+            content: content.to_string(),
+            node_type: "fact".to_string(),
+            on_duplicate: DuplicatePolicy::CreateAnyway,
+            ..Default::default()
+        }).unwrap();
+        let pinned = fact("Rotate the signing keys before the quarterly audit");
+        let strong = fact("Rotate the signing keys before the quarterly security audit");
+        {
+            let writer = storage.writer.lock().unwrap();
+            writer.execute(
+                "UPDATE knowledge_nodes SET retention_strength = 0.1, retrieval_strength = 0.1,
+                    importance = 0.1, last_accessed = ?2
+                 WHERE id = ?1",
+                params![pinned.id, (Utc::now() - Duration::days(30)).to_rfc3339()],
+            ).unwrap();
+            writer.execute(
+                "UPDATE knowledge_nodes SET retention_strength = 1.0, retrieval_strength = 1.0,
+                    importance = 1.0
+                 WHERE id = ?1",
+                params![strong.id],
+            ).unwrap();
+        }
+        let query = "rotate signing keys quarterly audit";
+        let rank = |id: &str| {
+            let results = storage.hybrid_search(query, 5, 0.5, 0.5).unwrap();
+            results.iter().position(|r| r.node.id == id).unwrap()
+        };
+        assert!(rank(&strong.id) < rank(&pinned.id));
+
+        storage.pin_memory(&pinned.id, None).unwrap();
+        assert!(rank(&pinned.id) < rank(&strong.id));
+        let breakdown = storage.explain_ranking(query, &pinned.id).unwrap();
+        assert!((breakdown.pin_bonus - crate::search::DEFAULT_PIN_BONUS).abs() < 1e-9);
+        assert_eq!(storage.explain_ranking(query, &strong.id).unwrap().pin_bonus, 0.0);
+
+        storage.unpin_memory(&pinned.id).unwrap();
+        assert!(rank(&strong.id) < rank(&pinned.id));
     }
 }
@@ -0,0 +1,121 @@
+//! Test fixtures for code that uses `Storage`
+//!
+//! `seeded_storage` opens a private in-memory database with embeddings
+//! disabled and fills it with deterministic memories, so tests get a
+//! realistic store in milliseconds without a temp directory or the model:
+//!
+//! ```
+//! let storage = vestige_core::testing::seeded_storage(20).unwrap();
+//! assert_eq!(storage.get_stats().unwrap().total_nodes, 20);
+//! ```
+//!
+//! Memory `i` always has the same content, node type, tags and review
+//! history (see `seed_input` and `seed_ratings`); only timestamps and IDs
+//! differ between runs.
+
+use crate::fsrs::Rating;
+use crate::memory::{DuplicatePolicy, IngestInput, KnowledgeNode};
+use crate::storage::{Result, Storage, StorageConfig};
+
+/// Topics seeded memories cycle through
+const TOPICS: &[&str] = &["database", "deploy", "auth", "cache", "billing", "search"];
+
+/// Facts about a topic seeded memories cycle through
+const FACTS: &[&str] = &[
+    "runs on a dedicated host",
+    "was migrated last quarter",
+    "pages the on-call engineer when it fails",
+    "is configured through environment variables",
+    "has a known flaky integration test",
+];
+
+/// Node types seeded memories cycle through
+const NODE_TYPES: &[&str] = &["fact", "concept", "event", "procedure"];
+
+/// Review ratings applied in turn to seeded memories
+const RATINGS: [Rating; 4] = [Rating::Good, Rating::Easy, Rating::Hard, Rating::Again];
+
+/// In-memory storage without embeddings, holding `n_nodes` seeded memories
+pub fn seeded_storage(n_nodes: usize) -> Result<Storage> {
+    let storage = Storage::with_config(StorageConfig {
+        in_memory: true,
+        disable_embeddings: true,
+        ..Default::default()
+    })?;
+    seed(&storage, n_nodes)?;
+    Ok(storage)
+}
+
+/// Ingest seeded memories `0..n_nodes` into `storage` and review each one
+/// per `seed_ratings`, returning them in order
+pub fn seed(storage: &Storage, n_nodes: usize) -> Result<Vec<KnowledgeNode>> {
+    (0..n_nodes)
+        .map(|i| {
+            let node = storage.ingest(seed_input(i))?;
+            seed_ratings(i)
+                .into_iter()
+                .try_fold(node, |node, rating| storage.mark_reviewed(&node.id, rating))
+        })
+        .collect()
+}
+
+/// Input for seeded memory `i`
+///
+/// Tagged `seed`, `topic:<topic>` and `group:<i % 3>`; content is unique
+/// per index.
+pub fn seed_input(i: usize) -> IngestInput {
+    let topic = TOPICS[i % TOPICS.len()];
+    IngestInput {
+        content: format!(
+            "Seed memory {}: the {} service {}",
+            i,
+            topic,
+            FACTS[i % FACTS.len()]
+        ),
+        node_type: NODE_TYPES[i % NODE_TYPES.len()].to_string(),
+        source: Some("seed".to_string()),
+        tags: vec![
+            "seed".to_string(),
+            format!("topic:{}", topic),
+            format!("group:{}", i % 3),
+        ],
+        on_duplicate: DuplicatePolicy::CreateAnyway,
+        ..Default::default()
+    }
+}
+
+/// Reviews seeded memory `i` receives: `i % 4` ratings, cycling through
+/// Good, Easy, Hard and Again from an offset of `i`
+pub fn seed_ratings(i: usize) -> Vec<Rating> {
+    (0..i % 4).map(|r| RATINGS[(i + r) % RATINGS.len()]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_storage_is_deterministic() {
+        let first = seeded_storage(12).unwrap();
+        let second = seeded_storage(12).unwrap();
+        assert_eq!(first.get_stats().unwrap().total_nodes, 12);
+
+        let summary = |storage: &Storage| {
+            let mut nodes = storage.get_all_nodes(100, 0).unwrap();
+            nodes.sort_by(|a, b| a.content.cmp(&b.content));
+            nodes
+                .into_iter()
+                .map(|n| (n.content, n.node_type, n.tags, n.reps, n.lapses, n.stability.to_bits()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(summary(&first), summary(&second));
+
+        let nodes = first.get_all_nodes(100, 0).unwrap();
+        assert!(nodes.iter().any(|n| n.reps == 3));
+        assert!(nodes.iter().any(|n| n.lapses > 0));
+        assert_eq!(
+            first.get_nodes_by_type_and_tag("fact", Some("topic:database"), 100).unwrap().len(),
+            1
+        );
+    }
+}
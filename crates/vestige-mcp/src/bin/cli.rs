@@ -467,13 +467,16 @@ fn run_restore(backup_path: PathBuf) -> anyhow::Result<()> {
     for (i, memory) in memories.into_iter().enumerate() {
         let input = IngestInput {
             content: memory.content.clone(),
-            node_type: memory.node_type.unwrap_or_else(|| "fact".to_string()),
+            node_type: storage.node_type_for_restore(memory.node_type.as_deref()),
             source: memory.source,
             sentiment_score: 0.0,
             sentiment_magnitude: 0.0,
             tags: memory.tags.unwrap_or_default(),
             valid_from: None,
             valid_until: None,
+            namespace: None,
+            auto_tag: false,
+            on_duplicate: Default::default(),
         };
 
         match storage.ingest(input) {
@@ -730,12 +733,21 @@ fn run_gc(
 
     let storage = Storage::new(None)?;
     let all_nodes = fetch_all_nodes(&storage)?;
+    let pinned: std::collections::HashSet<String> = storage
+        .list_pinned()?
+        .into_iter()
+        .map(|pin| pin.node.id)
+        .collect();
     let now = Utc::now();
 
     // Find candidates for deletion
     let candidates: Vec<&vestige_core::KnowledgeNode> = all_nodes
         .iter()
         .filter(|node| {
+            // Pinned memories are never collected
+            if pinned.contains(&node.id) {
+                return false;
+            }
             // Must be below retention threshold
             if node.retention_strength >= min_retention {
                 return false;
@@ -890,6 +902,9 @@ fn run_ingest(
         tags: tag_list,
         valid_from: None,
         valid_until: None,
+        namespace: None,
+        auto_tag: false,
+        on_duplicate: Default::default(),
     };
 
     let storage = Storage::new(None)?;
@@ -11,13 +11,15 @@ use vestige_core::{
     ImportanceSignals, NoveltySignal, ArousalSignal, RewardSignal, AttentionSignal,
     EmotionalMemory,
     // Advanced modules
-    ImportanceTracker, ReconsolidationManager, IntentDetector, ActivityTracker,
+    ImportanceTracker, IntentDetector, ActivityTracker,
     MemoryDreamer, MemoryChainBuilder, MemoryCompressor, CrossProjectLearner,
     AdaptiveEmbedder, SpeculativeRetriever, ConsolidationScheduler,
     // Search modules
     Reranker, RerankerConfig,
 };
 use vestige_core::search::TemporalSearcher;
+use vestige_core::Storage;
+use crate::speculative::WarmCache;
 use vestige_core::neuroscience::predictive_retrieval::PredictiveMemory;
 use vestige_core::neuroscience::prospective_memory::{ProspectiveMemory, IntentionParser};
 
@@ -47,7 +49,6 @@ pub struct CognitiveEngine {
 
     // -- Advanced --
     pub importance_tracker: ImportanceTracker,
-    pub reconsolidation: ReconsolidationManager,
     pub intent_detector: IntentDetector,
     pub activity_tracker: ActivityTracker,
     pub dreamer: MemoryDreamer,
@@ -56,6 +57,7 @@ pub struct CognitiveEngine {
     pub cross_project: CrossProjectLearner,
     pub adaptive_embedder: AdaptiveEmbedder,
     pub speculative_retriever: SpeculativeRetriever,
+    pub warm_cache: WarmCache,
     pub consolidation_scheduler: ConsolidationScheduler,
 
     // -- Search --
@@ -93,7 +95,6 @@ impl CognitiveEngine {
 
             // Advanced
             importance_tracker: ImportanceTracker::new(),
-            reconsolidation: ReconsolidationManager::new(),
             intent_detector: IntentDetector::new(),
             activity_tracker: ActivityTracker::new(),
             dreamer: MemoryDreamer::new(),
@@ -102,6 +103,7 @@ impl CognitiveEngine {
             cross_project: CrossProjectLearner::new(),
             adaptive_embedder: AdaptiveEmbedder::new(),
             speculative_retriever: SpeculativeRetriever::new(),
+            warm_cache: WarmCache::new(),
             consolidation_scheduler: ConsolidationScheduler::new(),
 
             // Search
@@ -109,4 +111,22 @@ impl CognitiveEngine {
             temporal_searcher: TemporalSearcher::new(),
         }
     }
+
+    /// Match the hippocampal index's user-starred flags to the stored pins.
+    ///
+    /// Called at startup and after each consolidation, which expires pins
+    /// whose TTL has passed.
+    pub fn sync_pins(&self, storage: &Storage) {
+        let synced = storage
+            .active_pin_ids()
+            .map_err(|e| e.to_string())
+            .and_then(|pinned| {
+                self.hippocampal_index
+                    .sync_user_starred(&pinned)
+                    .map_err(|e| e.to_string())
+            });
+        if let Err(e) = synced {
+            tracing::warn!("Failed to sync pinned memories: {}", e);
+        }
+    }
 }
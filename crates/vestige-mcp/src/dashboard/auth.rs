@@ -0,0 +1,189 @@
+//! Optional bearer-token auth for the dashboard API and WebSocket.
+//!
+//! Off unless `VESTIGE_DASHBOARD_TOKEN` is set. When on, `/api` routes need
+//! `Authorization: Bearer <token>` and `/ws` accepts `?token=<token>` instead
+//! (browsers cannot set headers on WebSocket upgrades).
+//!
+//! The HTML pages themselves stay public but only hand the token to a browser
+//! that already knows it: open the dashboard once as `/dashboard?token=<token>`
+//! (or `/?token=<token>` for the legacy page) and the response sets a
+//! `vestige_token` cookie, so later page loads get the token in a
+//! `vestige-token` meta tag without the query parameter.
+
+use std::sync::Arc;
+
+use axum::extract::{Query, Request, State};
+use axum::http::{header, HeaderMap, StatusCode, Uri};
+use axum::middleware::Next;
+use axum::response::{Html, IntoResponse, Response};
+use serde::Deserialize;
+
+/// Environment variable holding the dashboard token
+pub const TOKEN_ENV: &str = "VESTIGE_DASHBOARD_TOKEN";
+
+/// Cookie a `?token=` page load sets so later page loads can drop the query
+pub const TOKEN_COOKIE: &str = "vestige_token";
+
+/// The configured dashboard token, if auth is enabled
+#[derive(Clone, Default)]
+pub struct DashboardToken(Option<Arc<str>>);
+
+#[derive(Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+impl DashboardToken {
+    pub fn new(token: Option<String>) -> Self {
+        Self(token.filter(|t| !t.is_empty()).map(Arc::from))
+    }
+
+    /// Token from `VESTIGE_DASHBOARD_TOKEN` (empty = auth disabled)
+    pub fn from_env() -> Self {
+        Self::new(std::env::var(TOKEN_ENV).ok())
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Whether the `Authorization: Bearer` header carries the token
+    /// (always true when auth is disabled)
+    pub fn accepts_header(&self, headers: &HeaderMap) -> bool {
+        let Some(expected) = &self.0 else {
+            return true;
+        };
+        headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .is_some_and(|provided| token_matches(expected, provided))
+    }
+
+    /// Whether the `?token=` query parameter carries the token
+    /// (always true when auth is disabled)
+    pub fn accepts_query(&self, uri: &Uri) -> bool {
+        let Some(expected) = &self.0 else {
+            return true;
+        };
+        Query::<TokenQuery>::try_from_uri(uri)
+            .ok()
+            .and_then(|Query(q)| q.token)
+            .is_some_and(|provided| token_matches(expected, &provided))
+    }
+
+    /// Whether the `vestige_token` cookie carries the token
+    /// (always true when auth is disabled)
+    pub fn accepts_cookie(&self, headers: &HeaderMap) -> bool {
+        let Some(expected) = &self.0 else {
+            return true;
+        };
+        headers
+            .get_all(header::COOKIE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(';'))
+            .filter_map(|pair| pair.trim().split_once('='))
+            .any(|(name, provided)| name == TOKEN_COOKIE && token_matches(expected, provided))
+    }
+
+    /// The token, for handing to a client that already proved it knows it
+    pub(crate) fn value(&self) -> Option<&str> {
+        self.0.as_deref()
+    }
+
+    /// Serve an HTML page, handing the token to a client that proved it
+    /// knows it (header, `?token=` or cookie)
+    ///
+    /// The token goes into a `vestige-token` meta tag the page's scripts
+    /// send on API and WebSocket calls; a `?token=` load also sets the
+    /// cookie so later loads can drop the query.
+    pub fn serve_page(&self, html: &str, headers: &HeaderMap, uri: &Uri) -> Response {
+        let Some(value) = self.value() else {
+            return Html(html.to_string()).into_response();
+        };
+        let from_query = self.accepts_query(uri);
+        if !(from_query || self.accepts_header(headers) || self.accepts_cookie(headers)) {
+            return Html(html.to_string()).into_response();
+        }
+
+        let escaped = value
+            .replace('&', "&amp;")
+            .replace('"', "&quot;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;");
+        let meta = format!(
+            "<meta name=\"vestige-token\" content=\"{}\" />\n</head>",
+            escaped
+        );
+        let mut response = Html(html.replacen("</head>", &meta, 1)).into_response();
+        if from_query
+            && let Ok(cookie) = format!(
+                "{}={}; Path=/; HttpOnly; SameSite=Strict",
+                TOKEN_COOKIE, value
+            )
+            .parse()
+        {
+            response.headers_mut().insert(header::SET_COOKIE, cookie);
+        }
+        response
+    }
+}
+
+/// Constant-time token comparison
+pub fn token_matches(expected: &str, provided: &str) -> bool {
+    expected.len() == provided.len()
+        && expected
+            .bytes()
+            .zip(provided.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// Middleware rejecting `/api` and `/ws` requests without the token with 401
+pub async fn require_token(
+    State(token): State<DashboardToken>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let authorized = token.accepts_header(request.headers())
+        || (request.uri().path() == "/ws" && token.accepts_query(request.uri()));
+    if authorized {
+        next.run(request).await
+    } else {
+        (StatusCode::UNAUTHORIZED, [(header::WWW_AUTHENTICATE, "Bearer")]).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_checks() {
+        let token = DashboardToken::new(Some("s3cret".to_string()));
+        let mut headers = HeaderMap::new();
+        assert!(!token.accepts_header(&headers));
+        headers.insert(header::AUTHORIZATION, "Bearer nope".parse().unwrap());
+        assert!(!token.accepts_header(&headers));
+        headers.insert(header::AUTHORIZATION, "Bearer s3cret".parse().unwrap());
+        assert!(token.accepts_header(&headers));
+
+        assert!(token.accepts_query(&"/ws?token=s3cret".parse().unwrap()));
+        assert!(!token.accepts_query(&"/ws?token=s3cre".parse().unwrap()));
+        assert!(!token.accepts_query(&"/ws".parse().unwrap()));
+
+        let mut cookies = HeaderMap::new();
+        cookies.insert(
+            header::COOKIE,
+            "theme=dark; vestige_token=s3cret".parse().unwrap(),
+        );
+        assert!(token.accepts_cookie(&cookies));
+        cookies.insert(header::COOKIE, "vestige_token=s3cre".parse().unwrap());
+        assert!(!token.accepts_cookie(&cookies));
+
+        let disabled = DashboardToken::new(Some(String::new()));
+        assert!(!disabled.is_enabled());
+        assert!(disabled.accepts_header(&HeaderMap::new()));
+    }
+}
@@ -38,6 +38,23 @@ pub enum VestigeEvent {
         new_retention: f64,
         timestamp: DateTime<Utc>,
     },
+    /// One event for a whole bulk operation; `ids` lists the memories changed
+    MemoriesBulkUpdated {
+        action: String,
+        ids: Vec<String>,
+        failed: usize,
+        timestamp: DateTime<Utc>,
+    },
+
+    /// A spaced-repetition review; `due_count` is the remaining due total
+    MemoryReviewed {
+        id: String,
+        rating: String,
+        next_review: Option<DateTime<Utc>>,
+        was_due: bool,
+        due_count: i64,
+        timestamp: DateTime<Utc>,
+    },
 
     // -- Search --
     SearchPerformed {
@@ -71,6 +88,13 @@ pub enum VestigeEvent {
     ConsolidationStarted {
         timestamp: DateTime<Utc>,
     },
+    /// Sent as each consolidation step finishes (`step` is 1-based)
+    ConsolidationProgress {
+        step: usize,
+        total: usize,
+        label: String,
+        timestamp: DateTime<Utc>,
+    },
     ConsolidationCompleted {
         nodes_processed: usize,
         decay_applied: usize,
@@ -96,6 +120,15 @@ pub enum VestigeEvent {
         timestamp: DateTime<Utc>,
     },
 
+    EdgeCreated {
+        id: String,
+        source_id: String,
+        target_id: String,
+        edge_type: String,
+        weight: f64,
+        timestamp: DateTime<Utc>,
+    },
+
     // -- Spreading activation --
     ActivationSpread {
         source_id: String,
@@ -2,19 +2,27 @@
 //!
 //! v2.0: Adds cognitive operation endpoints (dream, explore, predict, importance, consolidation)
 
+use std::collections::{HashMap, HashSet};
+
 use axum::extract::{Path, Query, State};
-use axum::http::StatusCode;
-use axum::response::{Html, Json};
+use axum::http::{HeaderMap, StatusCode, Uri};
+use axum::response::{Json, Response};
+use axum::Extension;
 use chrono::{Duration, Utc};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use super::auth::DashboardToken;
 use super::events::VestigeEvent;
 use super::state::AppState;
 
 /// Serve the dashboard HTML
-pub async fn serve_dashboard() -> Html<&'static str> {
-    Html(include_str!("../dashboard.html"))
+pub async fn serve_dashboard(
+    Extension(token): Extension<DashboardToken>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> Response {
+    token.serve_page(include_str!("../dashboard.html"), &headers, &uri)
 }
 
 #[derive(Debug, Deserialize)]
@@ -125,6 +133,10 @@ pub async fn get_memory(
         .get_node(&id)
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .ok_or(StatusCode::NOT_FOUND)?;
+    let labile_remaining = state.storage.labile_remaining(&id);
+    let pinned = state.storage
+        .is_pinned(&id)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     Ok(Json(serde_json::json!({
         "id": node.id,
@@ -144,6 +156,376 @@ pub async fn get_memory(
         "reviewCount": node.reps,
         "validFrom": node.valid_from.map(|dt| dt.to_rfc3339()),
         "validUntil": node.valid_until.map(|dt| dt.to_rfc3339()),
+        "labile": labile_remaining.is_some(),
+        "labileSecondsRemaining": labile_remaining.map(|d| d.num_seconds()),
+        "importance": node.importance,
+        "pinned": pinned,
+    })))
+}
+
+/// Explain a memory's importance: persisted composite, live channels, how the
+/// usage-evolved score moved, and the 4-channel signal breakdown when the
+/// cognitive engine is available
+pub async fn memory_importance(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, StatusCode> {
+    let node = state.storage
+        .get_node(&id)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let breakdown = state.storage
+        .get_importance_breakdown(&id)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let history = state.storage
+        .get_importance_history(&id)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let signals = match state.cognitive {
+        Some(ref cognitive) => {
+            let context = vestige_core::ImportanceContext::current();
+            let cog = cognitive.lock().await;
+            let score = cog.importance_signals.explain_memory(&node.content, &id, &context);
+            drop(cog);
+            Some(serde_json::json!({
+                "composite": score.composite,
+                "dominantSignal": score.dominant_signal(),
+                "summary": score.summary(),
+                "explanation": score.explain(),
+                "channels": score,
+            }))
+        }
+        None => None,
+    };
+
+    Ok(Json(serde_json::json!({
+        "id": node.id,
+        "importance": node.importance,
+        "breakdown": breakdown,
+        "history": history,
+        "signals": signals,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RevisionsParams {
+    pub limit: Option<i32>,
+}
+
+/// Content revisions of a memory, newest first, with unified diffs
+pub async fn memory_revisions(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(params): Query<RevisionsParams>,
+) -> Result<Json<Value>, StatusCode> {
+    state.storage
+        .get_node(&id)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let revisions = state.storage
+        .get_node_revisions(&id, limit)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(serde_json::json!({
+        "id": id,
+        "total": revisions.len(),
+        "revisions": revisions,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RevisionDiffParams {
+    pub from: i64,
+    /// Omit to compare against the current content
+    pub to: Option<i64>,
+}
+
+/// Diff between two revisions of a memory
+pub async fn memory_revision_diff(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(params): Query<RevisionDiffParams>,
+) -> Result<Json<vestige_core::RevisionDiff>, StatusCode> {
+    match state.storage.diff_revisions(&id, params.from, params.to) {
+        Ok(diff) => Ok(Json(diff)),
+        Err(vestige_core::StorageError::NotFound(_)) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Restore the content a memory held before a revision
+pub async fn revert_memory(
+    State(state): State<AppState>,
+    Path((id, revision)): Path<(String, i64)>,
+) -> Result<Json<Value>, StatusCode> {
+    let node = match state.storage.revert_node(&id, revision) {
+        Ok(node) => node,
+        Err(vestige_core::StorageError::NotFound(_)) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    state.emit(VestigeEvent::MemoryUpdated {
+        id: node.id.clone(),
+        content_preview: content_preview(&node.content),
+        field: "content".to_string(),
+        timestamp: Utc::now(),
+    });
+
+    Ok(Json(serde_json::json!({
+        "reverted": true,
+        "revision": revision,
+        "memory": node,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TopImportantParams {
+    pub limit: Option<i32>,
+}
+
+/// Most important memories by persisted composite importance
+pub async fn top_important(
+    State(state): State<AppState>,
+    Query(params): Query<TopImportantParams>,
+) -> Result<Json<Value>, StatusCode> {
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let nodes = state.storage
+        .top_important(limit)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let memories: Vec<Value> = nodes
+        .iter()
+        .map(|n| {
+            serde_json::json!({
+                "id": n.id,
+                "content": n.content.chars().take(120).collect::<String>(),
+                "nodeType": n.node_type,
+                "importance": n.importance,
+                "retentionStrength": n.retention_strength,
+            })
+        })
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "memories": memories,
+        "total": memories.len(),
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RelatedParams {
+    pub hops: Option<u32>,
+    pub limit: Option<usize>,
+}
+
+/// Get memories associatively related to one memory (spreading activation)
+pub async fn related_memories(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(params): Query<RelatedParams>,
+) -> Result<Json<Value>, StatusCode> {
+    let hops = params.hops.unwrap_or(2).clamp(1, 5);
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let related = state.storage.get_related(&id, hops, limit).map_err(|e| match e {
+        vestige_core::StorageError::NotFound(_) => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    })?;
+
+    let memories: Vec<Value> = related
+        .iter()
+        .filter_map(|m| {
+            let node = state.storage.get_node(&m.memory_id).ok().flatten()?;
+            Some(serde_json::json!({
+                "id": node.id,
+                "content": node.content.chars().take(120).collect::<String>(),
+                "nodeType": node.node_type,
+                "activation": m.activation,
+                "distance": m.distance,
+                "path": m.path,
+                "linkType": m.link_type,
+            }))
+        })
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "id": id,
+        "hops": hops,
+        "related": memories,
+        "total": memories.len(),
+    })))
+}
+
+/// Node types accepted from the dashboard: `vestige_core::NodeType` names plus
+/// the types the MCP ingest tools document
+type ApiError = (StatusCode, Json<Value>);
+
+fn api_error(status: StatusCode, message: impl Into<String>) -> ApiError {
+    (status, Json(serde_json::json!({ "error": message.into() })))
+}
+
+fn content_preview(content: &str) -> String {
+    content.chars().take(100).collect()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateMemoryRequest {
+    pub content: String,
+    pub node_type: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub source: Option<String>,
+    /// Emotional valence, -1.0 (negative) to 1.0 (positive)
+    pub sentiment: Option<f64>,
+}
+
+/// Create a memory, merging into a similar one when embeddings are ready
+pub async fn create_memory(
+    State(state): State<AppState>,
+    Json(req): Json<CreateMemoryRequest>,
+) -> Result<Json<Value>, ApiError> {
+    if req.content.trim().is_empty() {
+        return Err(api_error(StatusCode::UNPROCESSABLE_ENTITY, "content cannot be empty"));
+    }
+    let node_type = req.node_type.unwrap_or_else(|| "fact".to_string()).to_lowercase();
+    if !state.storage.is_known_node_type(&node_type) {
+        return Err(api_error(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!(
+                "unknown node_type '{}'; expected one of: {}",
+                node_type,
+                state.storage.node_types().join(", ")
+            ),
+        ));
+    }
+    let sentiment = req.sentiment.unwrap_or(0.0);
+    if !(-1.0..=1.0).contains(&sentiment) {
+        return Err(api_error(StatusCode::UNPROCESSABLE_ENTITY, "sentiment must be between -1.0 and 1.0"));
+    }
+
+    let input = vestige_core::IngestInput {
+        content: req.content,
+        node_type,
+        source: req.source,
+        sentiment_score: sentiment,
+        sentiment_magnitude: sentiment.abs(),
+        tags: req.tags.unwrap_or_default(),
+        ..Default::default()
+    };
+    let storage_err = |e: vestige_core::StorageError| match e {
+        vestige_core::StorageError::InvalidInput { .. } => {
+            api_error(StatusCode::UNPROCESSABLE_ENTITY, e.to_string())
+        }
+        e => api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    };
+
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    let result = if state.storage.is_embedding_ready() {
+        state.storage.smart_ingest(input).map_err(storage_err)?
+    } else {
+        plain_ingest(&state, input).map_err(storage_err)?
+    };
+    #[cfg(not(all(feature = "embeddings", feature = "vector-search")))]
+    let result = plain_ingest(&state, input).map_err(storage_err)?;
+
+    let node = &result.node;
+    if result.decision == "create" {
+        state.emit(VestigeEvent::MemoryCreated {
+            id: node.id.clone(),
+            content_preview: content_preview(&node.content),
+            node_type: node.node_type.clone(),
+            tags: node.tags.clone(),
+            timestamp: Utc::now(),
+        });
+    } else {
+        state.emit(VestigeEvent::MemoryUpdated {
+            id: node.id.clone(),
+            content_preview: content_preview(&node.content),
+            field: result.decision.clone(),
+            timestamp: Utc::now(),
+        });
+    }
+
+    Ok(Json(serde_json::json!({
+        "created": result.decision == "create",
+        "decision": result.decision,
+        "reason": result.reason,
+        "similarity": result.similarity,
+        "supersededId": result.superseded_id,
+        "contradicts": result.contradicts,
+        "memory": result.node,
+    })))
+}
+
+/// Ingest without similarity gating, shaped like a smart ingest "create"
+fn plain_ingest(
+    state: &AppState,
+    input: vestige_core::IngestInput,
+) -> vestige_core::Result<vestige_core::SmartIngestResult> {
+    let node = state.storage.ingest(input)?;
+    Ok(vestige_core::SmartIngestResult {
+        decision: "create".to_string(),
+        node,
+        superseded_id: None,
+        contradicts: Vec::new(),
+        similarity: None,
+        prediction_error: None,
+        reason: "Embeddings not ready - created without similarity check".to_string(),
+        reconsolidation: None,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateMemoryRequest {
+    pub content: Option<String>,
+    /// Replaces the memory's tags
+    pub tags: Option<Vec<String>>,
+}
+
+/// Edit a memory's content and/or tags in place (FSRS state is preserved)
+pub async fn update_memory(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<UpdateMemoryRequest>,
+) -> Result<Json<Value>, ApiError> {
+    if req.content.is_none() && req.tags.is_none() {
+        return Err(api_error(StatusCode::UNPROCESSABLE_ENTITY, "provide content and/or tags"));
+    }
+    if req.content.as_ref().is_some_and(|c| c.trim().is_empty()) {
+        return Err(api_error(StatusCode::UNPROCESSABLE_ENTITY, "content cannot be empty"));
+    }
+    let internal = |e: vestige_core::StorageError| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+    if state.storage.get_node(&id).map_err(internal)?.is_none() {
+        return Err(api_error(StatusCode::NOT_FOUND, format!("memory not found: {}", id)));
+    }
+
+    let mut fields = Vec::new();
+    if let Some(content) = &req.content {
+        state.storage
+            .update_node_content_from(&id, content, "dashboard:edit")
+            .map_err(internal)?;
+        fields.push("content");
+    }
+    if let Some(tags) = req.tags {
+        state.storage
+            .bulk_update(std::slice::from_ref(&id), &vestige_core::BulkAction::Retag { tags })
+            .map_err(internal)?;
+        fields.push("tags");
+    }
+
+    let node = state.storage
+        .get_node(&id)
+        .map_err(internal)?
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, format!("memory not found: {}", id)))?;
+    state.emit(VestigeEvent::MemoryUpdated {
+        id: node.id.clone(),
+        content_preview: content_preview(&node.content),
+        field: fields.join(","),
+        timestamp: Utc::now(),
+    });
+
+    Ok(Json(serde_json::json!({
+        "updated": true,
+        "fields": fields,
+        "memory": node,
     })))
 }
 
@@ -167,14 +549,26 @@ pub async fn delete_memory(
     }
 }
 
-/// Promote a memory
+/// Status for a failed promote, demote or pin
+fn feedback_error(e: vestige_core::StorageError) -> ApiError {
+    let status = match e {
+        vestige_core::StorageError::InvalidInput { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+        vestige_core::StorageError::NotFound(_) => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    api_error(status, e.to_string())
+}
+
+/// Promote a memory, optionally with a JSON body of `strength` (0-1) and `reason`
 pub async fn promote_memory(
     State(state): State<AppState>,
     Path(id): Path<String>,
-) -> Result<Json<Value>, StatusCode> {
+    body: Option<Json<vestige_core::FeedbackOptions>>,
+) -> Result<Json<Value>, ApiError> {
+    let options = body.map(|Json(options)| options).unwrap_or_default();
     let node = state.storage
-        .promote_memory(&id)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .promote_memory_with(&id, &options)
+        .map_err(feedback_error)?;
 
     state.emit(VestigeEvent::MemoryPromoted {
         id: node.id.clone(),
@@ -186,17 +580,21 @@ pub async fn promote_memory(
         "promoted": true,
         "id": node.id,
         "retentionStrength": node.retention_strength,
+        "reason": options.reason,
     })))
 }
 
-/// Demote a memory
+/// Demote a memory, optionally with a JSON body of `strength` (0-1), `reason`
+/// and `invalidate` (end the memory's validity; needs a reason)
 pub async fn demote_memory(
     State(state): State<AppState>,
     Path(id): Path<String>,
-) -> Result<Json<Value>, StatusCode> {
+    body: Option<Json<vestige_core::FeedbackOptions>>,
+) -> Result<Json<Value>, ApiError> {
+    let options = body.map(|Json(options)| options).unwrap_or_default();
     let node = state.storage
-        .demote_memory(&id)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .demote_memory_with(&id, &options)
+        .map_err(feedback_error)?;
 
     state.emit(VestigeEvent::MemoryDemoted {
         id: node.id.clone(),
@@ -208,15 +606,245 @@ pub async fn demote_memory(
         "demoted": true,
         "id": node.id,
         "retentionStrength": node.retention_strength,
+        "reason": options.reason,
+        "validUntil": node.valid_until,
+    })))
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PinRequest {
+    /// When the pin expires (RFC 3339); omit to pin indefinitely
+    pub until: Option<chrono::DateTime<Utc>>,
+}
+
+/// Pin a memory, optionally with a JSON body of `until`
+pub async fn pin_memory(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    body: Option<Json<PinRequest>>,
+) -> Result<Json<Value>, ApiError> {
+    let request = body.map(|Json(request)| request).unwrap_or_default();
+    let pin = state.storage
+        .pin_memory(&id, request.until)
+        .map_err(feedback_error)?;
+    if let Some(ref cognitive) = state.cognitive {
+        let _ = cognitive.lock().await.hippocampal_index.set_user_starred(&id, true);
+    }
+
+    Ok(Json(serde_json::json!({
+        "pinned": true,
+        "id": pin.node.id,
+        "pinnedAt": pin.pinned_at,
+        "pinnedUntil": pin.pinned_until,
     })))
 }
 
-/// Get system stats
+/// Unpin a memory
+pub async fn unpin_memory(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let unpinned = state.storage
+        .unpin_memory(&id)
+        .map_err(feedback_error)?;
+    if !unpinned {
+        return Err(api_error(StatusCode::NOT_FOUND, format!("memory {} is not pinned", id)));
+    }
+    if let Some(ref cognitive) = state.cognitive {
+        let _ = cognitive.lock().await.hippocampal_index.set_user_starred(&id, false);
+    }
+    Ok(Json(serde_json::json!({ "unpinned": true, "id": id })))
+}
+
+/// Currently pinned memories
+pub async fn list_pins(
+    State(state): State<AppState>,
+) -> Result<Json<Value>, ApiError> {
+    let pins = state.storage
+        .list_pinned()
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let pins: Vec<Value> = pins
+        .into_iter()
+        .map(|pin| serde_json::json!({
+            "id": pin.node.id,
+            "content": pin.node.content,
+            "nodeType": pin.node.node_type,
+            "retentionStrength": pin.node.retention_strength,
+            "pinnedAt": pin.pinned_at,
+            "pinnedUntil": pin.pinned_until,
+        }))
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "total": pins.len(),
+        "pins": pins,
+    })))
+}
+
+/// Most memories one bulk request may touch
+const MAX_BULK_IDS: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct BulkRequest {
+    pub ids: Vec<String>,
+    pub action: String, // "delete", "promote", "demote", "retag"
+    pub tags: Option<Vec<String>>,
+}
+
+/// Apply one action to many memories in a single transaction
+///
+/// Broadcasts one aggregated event rather than one per memory.
+pub async fn bulk_memories(
+    State(state): State<AppState>,
+    Json(req): Json<BulkRequest>,
+) -> Result<Json<Value>, StatusCode> {
+    if req.ids.is_empty() || req.ids.len() > MAX_BULK_IDS {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let action = match req.action.as_str() {
+        "delete" => vestige_core::BulkAction::Delete,
+        "promote" => vestige_core::BulkAction::Promote,
+        "demote" => vestige_core::BulkAction::Demote,
+        "retag" => vestige_core::BulkAction::Retag {
+            tags: req.tags.ok_or(StatusCode::BAD_REQUEST)?,
+        },
+        _ => return Err(StatusCode::BAD_REQUEST),
+    };
+
+    let outcomes = state.storage
+        .bulk_update(&req.ids, &action)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let succeeded: Vec<String> = outcomes.iter().filter(|o| o.ok).map(|o| o.id.clone()).collect();
+    let failed = outcomes.len() - succeeded.len();
+
+    if !succeeded.is_empty() {
+        state.emit(VestigeEvent::MemoriesBulkUpdated {
+            action: req.action.clone(),
+            ids: succeeded.clone(),
+            failed,
+            timestamp: Utc::now(),
+        });
+    }
+
+    Ok(Json(serde_json::json!({
+        "action": req.action,
+        "succeeded": succeeded.len(),
+        "failed": failed,
+        "results": outcomes,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewQueueParams {
+    pub limit: Option<i32>,
+}
+
+/// Interval (days) each rating would schedule, for "Again: 1d / Good: 6d" buttons
+fn interval_preview(preview: &vestige_core::PreviewResults) -> Value {
+    serde_json::json!({
+        "again": preview.again.interval,
+        "hard": preview.hard.interval,
+        "good": preview.good.interval,
+        "easy": preview.easy.interval,
+    })
+}
+
+/// Memories due for review, each with its per-rating interval preview
+pub async fn review_queue(
+    State(state): State<AppState>,
+    Query(params): Query<ReviewQueueParams>,
+) -> Result<Json<Value>, StatusCode> {
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let nodes = state.storage
+        .get_review_queue(limit)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let mut items = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        let preview = state.storage
+            .preview_review(&node.id)
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        items.push(serde_json::json!({
+            "retrievability": preview.good.retrievability,
+            "intervals": interval_preview(&preview),
+            "memory": node,
+        }));
+    }
+
+    Ok(Json(serde_json::json!({
+        "total": items.len(),
+        "queue": items,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewRequest {
+    pub rating: String,
+}
+
+/// Parse a rating name ("again", "hard", "good", "easy") or its 1-4 value
+fn parse_rating(value: &str) -> Option<vestige_core::Rating> {
+    use vestige_core::Rating;
+    match value.trim().to_ascii_lowercase().as_str() {
+        "again" => Some(Rating::Again),
+        "hard" => Some(Rating::Hard),
+        "good" => Some(Rating::Good),
+        "easy" => Some(Rating::Easy),
+        other => other.parse().ok().and_then(Rating::from_i32),
+    }
+}
+
+/// Record a review; non-due memories may be reviewed early but are flagged
+pub async fn review_memory(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<ReviewRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let Some(rating) = parse_rating(&req.rating) else {
+        return Err(api_error(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("invalid rating '{}' (expected again, hard, good or easy)", req.rating),
+        ));
+    };
+    let internal = |e: vestige_core::StorageError| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+    let was_due = state.storage
+        .get_node(&id)
+        .map_err(internal)?
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, format!("memory not found: {}", id)))?
+        .is_due();
+
+    let node = state.storage.mark_reviewed(&id, rating).map_err(internal)?;
+    let due_count = state.storage
+        .get_stats()
+        .map(|s| s.nodes_due_for_review)
+        .unwrap_or(0);
+
+    state.emit(VestigeEvent::MemoryReviewed {
+        id: node.id.clone(),
+        rating: format!("{:?}", rating).to_lowercase(),
+        next_review: node.next_review,
+        was_due,
+        due_count,
+        timestamp: Utc::now(),
+    });
+
+    Ok(Json(serde_json::json!({
+        "reviewed": true,
+        "rating": format!("{:?}", rating).to_lowercase(),
+        "early": !was_due,
+        "nextReview": node.next_review,
+        "dueCount": due_count,
+        "memory": node,
+    })))
+}
+
+/// Get system stats, optionally scoped by `tag`, `nodeType`, `scope` or `namespace`
 pub async fn get_stats(
     State(state): State<AppState>,
+    Query(filter): Query<vestige_core::StatsFilter>,
 ) -> Result<Json<Value>, StatusCode> {
     let stats = state.storage
-        .get_stats()
+        .get_stats_filtered(filter)
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     let embedding_coverage = if stats.total_nodes > 0 {
@@ -236,6 +864,7 @@ pub async fn get_stats(
         "embeddingModel": stats.embedding_model,
         "oldestMemory": stats.oldest_memory.map(|dt| dt.to_rfc3339()),
         "newestMemory": stats.newest_memory.map(|dt| dt.to_rfc3339()),
+        "metrics": state.storage.metrics_snapshot(),
     })))
 }
 
@@ -316,41 +945,243 @@ pub async fn health_check(
         "healthy"
     };
 
+    let backfill = state.storage.embedding_backfill_status().ok().flatten().map(|progress| {
+        serde_json::json!({
+            "done": progress.resume_token.is_none(),
+            "percentComplete": progress.percent_complete(),
+            "processed": progress.processed,
+            "remaining": progress.remaining,
+            "failed": progress.failed,
+            "resumeToken": progress.resume_token,
+            "updatedAt": progress.updated_at,
+        })
+    });
+
     Ok(Json(serde_json::json!({
         "status": status,
         "totalMemories": stats.total_nodes,
         "averageRetention": stats.average_retention,
+        "embeddingBackfill": backfill,
         "version": env!("CARGO_PKG_VERSION"),
     })))
 }
 
+/// Storage diagnostics: integrity checks and maintenance ages with remediations
+pub async fn deep_health_check(
+    State(state): State<AppState>,
+) -> Result<Json<Value>, StatusCode> {
+    let report = state.storage
+        .diagnostics()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(serde_json::to_value(report).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?))
+}
+
 // ============================================================================
 // MEMORY GRAPH
 // ============================================================================
 
-/// Serve the memory graph visualization HTML
-pub async fn serve_graph() -> Html<&'static str> {
-    Html(include_str!("../graph.html"))
+/// Serve the memory graph visualization HTML
+pub async fn serve_graph(
+    Extension(token): Extension<DashboardToken>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> Response {
+    token.serve_page(include_str!("../graph.html"), &headers, &uri)
+}
+
+/// Hard cap on nodes in a centered subgraph
+const MAX_GRAPH_NODES: usize = 200;
+/// Hard cap on nodes in whole-graph summary mode
+const MAX_SUMMARY_NODES: usize = 500;
+/// Hard cap on edges in any graph response
+const MAX_GRAPH_EDGES: usize = 2000;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct GraphParams {
+    pub query: Option<String>,
+    /// Center memory ID (`center_id` is accepted as an alias)
+    #[serde(alias = "center_id")]
+    pub center: Option<String>,
+    pub depth: Option<u32>,
+    pub max_nodes: Option<usize>,
+    /// Drop links weaker than this
+    pub min_strength: Option<f64>,
+    /// Comma-separated link/edge types to keep, e.g. `semantic,causal`
+    pub edge_types: Option<String>,
+    /// `summary` returns the whole graph grouped into communities
+    pub mode: Option<String>,
+}
+
+/// A memory in the graph payload
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphNode {
+    pub id: String,
+    pub label: String,
+    #[serde(rename = "type")]
+    pub node_type: String,
+    pub retention: f64,
+    pub importance: Option<f64>,
+    pub activation: Option<f64>,
+    /// Accessibility state: active, dormant, silent or unavailable
+    pub state: String,
+    pub tags: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub is_center: bool,
+    /// Index into `communities` (summary mode only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub community: Option<usize>,
+}
+
+/// A link between two memories in the graph payload
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    pub weight: f64,
+    /// Link type (semantic, causal, contradiction, ...)
+    #[serde(rename = "type")]
+    pub edge_type: String,
+    /// `connection` for activation-network links, `edge` for typed knowledge edges
+    pub kind: &'static str,
+}
+
+/// A connected component in summary mode
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphCommunity {
+    pub id: usize,
+    /// Total memories in the component, including any left out by the cap
+    pub size: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphResponse {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    #[serde(rename = "center_id", skip_serializing_if = "Option::is_none")]
+    pub center_id: Option<String>,
+    pub depth: u32,
+    pub node_count: usize,
+    pub edge_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub communities: Option<Vec<GraphCommunity>>,
+    /// Whether caps dropped nodes or edges
+    pub truncated: bool,
+}
+
+fn graph_filter(params: &GraphParams) -> vestige_core::GraphFilter {
+    vestige_core::GraphFilter {
+        min_strength: params.min_strength.unwrap_or(0.0).clamp(0.0, 1.0),
+        link_types: params.edge_types.as_ref().map(|types| {
+            types
+                .split(',')
+                .map(|t| t.trim().to_lowercase())
+                .filter(|t| !t.is_empty())
+                .collect()
+        }),
+        ..Default::default()
+    }
 }
 
-#[derive(Debug, Deserialize)]
-pub struct GraphParams {
-    pub query: Option<String>,
-    pub center_id: Option<String>,
-    pub depth: Option<u32>,
-    pub max_nodes: Option<usize>,
+/// Graph nodes plus activation-network and typed knowledge edges among them
+fn build_graph(
+    state: &AppState,
+    nodes: Vec<vestige_core::KnowledgeNode>,
+    connections: Vec<vestige_core::ConnectionRecord>,
+    filter: &vestige_core::GraphFilter,
+    center_id: Option<&str>,
+    communities: &HashMap<String, usize>,
+) -> Result<(Vec<GraphNode>, Vec<GraphEdge>, bool), StatusCode> {
+    let ids: Vec<String> = nodes.iter().map(|n| n.id.clone()).collect();
+    let id_set: HashSet<&str> = ids.iter().map(String::as_str).collect();
+    let node_states = state.storage
+        .graph_node_states(&ids)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let mut edges: Vec<GraphEdge> = connections
+        .into_iter()
+        .map(|c| GraphEdge {
+            source: c.source_id,
+            target: c.target_id,
+            weight: c.strength,
+            edge_type: c.link_type,
+            kind: "connection",
+        })
+        .collect();
+    let mut seen_edges = HashSet::new();
+    for id in &ids {
+        let knowledge_edges = state.storage
+            .get_knowledge_edges(id)
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        for e in knowledge_edges {
+            let edge_type = e.edge_type.to_string();
+            let keep = f64::from(e.weight) >= filter.min_strength
+                && filter.link_types.as_ref().is_none_or(|types| types.contains(&edge_type))
+                && id_set.contains(e.source_id.as_str())
+                && id_set.contains(e.target_id.as_str());
+            if keep && seen_edges.insert(e.id) {
+                edges.push(GraphEdge {
+                    source: e.source_id,
+                    target: e.target_id,
+                    weight: f64::from(e.weight),
+                    edge_type,
+                    kind: "edge",
+                });
+            }
+        }
+    }
+    let truncated = edges.len() > MAX_GRAPH_EDGES;
+    edges.truncate(MAX_GRAPH_EDGES);
+
+    let nodes = nodes
+        .into_iter()
+        .map(|n| {
+            let label = if n.content.chars().count() > 80 {
+                format!("{}...", n.content.chars().take(77).collect::<String>())
+            } else {
+                n.content.clone()
+            };
+            let node_state = node_states.get(&n.id);
+            GraphNode {
+                label,
+                node_type: n.node_type,
+                retention: n.retention_strength,
+                importance: n.importance,
+                activation: node_state.and_then(|s| s.activation),
+                state: node_state.map(|s| s.state.clone()).unwrap_or_default(),
+                tags: n.tags,
+                created_at: n.created_at.to_rfc3339(),
+                updated_at: n.updated_at.to_rfc3339(),
+                is_center: center_id == Some(n.id.as_str()),
+                community: communities.get(&n.id).copied(),
+                id: n.id,
+            }
+        })
+        .collect();
+
+    Ok((nodes, edges, truncated))
 }
 
-/// Get memory graph data (nodes + edges with layout positions)
+/// Get memory graph data: a subgraph around a center memory, or with
+/// `mode=summary` the whole connection graph grouped into communities
 pub async fn get_graph(
     State(state): State<AppState>,
     Query(params): Query<GraphParams>,
-) -> Result<Json<Value>, StatusCode> {
+) -> Result<Json<GraphResponse>, StatusCode> {
+    let filter = graph_filter(&params);
+    if params.mode.as_deref() == Some("summary") {
+        return graph_summary(&state, &params, &filter).map(Json);
+    }
+
     let depth = params.depth.unwrap_or(2).clamp(1, 3);
-    let max_nodes = params.max_nodes.unwrap_or(50).clamp(1, 200);
+    let max_nodes = params.max_nodes.unwrap_or(50).clamp(1, MAX_GRAPH_NODES);
 
     // Determine center node
-    let center_id = if let Some(ref id) = params.center_id {
+    let center_id = if let Some(ref id) = params.center {
         id.clone()
     } else if let Some(ref query) = params.query {
         let results = state.storage
@@ -369,57 +1200,236 @@ pub async fn get_graph(
             .ok_or(StatusCode::NOT_FOUND)?
     };
 
-    // Get subgraph
-    let (nodes, edges) = state.storage
-        .get_memory_subgraph(&center_id, depth, max_nodes)
+    let (nodes, connections) = state.storage
+        .get_memory_subgraph_filtered(&center_id, depth, max_nodes, &filter)
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
     if nodes.is_empty() {
         return Err(StatusCode::NOT_FOUND);
     }
 
-    // Build nodes JSON with timestamps for recency calculation
-    let nodes_json: Vec<Value> = nodes.iter()
-        .map(|n| {
-            let label = if n.content.chars().count() > 80 {
-                format!("{}...", n.content.chars().take(77).collect::<String>())
-            } else {
-                n.content.clone()
-            };
-            serde_json::json!({
-                "id": n.id,
-                "label": label,
-                "type": n.node_type,
-                "retention": n.retention_strength,
-                "tags": n.tags,
-                "createdAt": n.created_at.to_rfc3339(),
-                "updatedAt": n.updated_at.to_rfc3339(),
-                "isCenter": n.id == center_id,
-            })
+    let (nodes, edges, truncated) =
+        build_graph(&state, nodes, connections, &filter, Some(&center_id), &HashMap::new())?;
+    Ok(Json(GraphResponse {
+        node_count: nodes.len(),
+        edge_count: edges.len(),
+        nodes,
+        edges,
+        center_id: Some(center_id),
+        depth,
+        communities: None,
+        truncated,
+    }))
+}
+
+/// Whole-graph summary: connected components, largest first, capped
+fn graph_summary(
+    state: &AppState,
+    params: &GraphParams,
+    filter: &vestige_core::GraphFilter,
+) -> Result<GraphResponse, StatusCode> {
+    let max_nodes = params.max_nodes.unwrap_or(MAX_SUMMARY_NODES).clamp(1, MAX_SUMMARY_NODES);
+    let components = state.storage
+        .graph_communities(filter)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let mut membership = HashMap::new();
+    let mut communities = Vec::new();
+    let mut truncated = false;
+    for (index, component) in components.iter().enumerate() {
+        communities.push(GraphCommunity { id: index, size: component.len() });
+        for id in component {
+            if membership.len() >= max_nodes {
+                truncated = true;
+                break;
+            }
+            membership.insert(id.clone(), index);
+        }
+    }
+
+    let mut nodes = Vec::with_capacity(membership.len());
+    for id in membership.keys() {
+        if let Some(node) = state.storage.get_node(id).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)? {
+            nodes.push(node);
+        }
+    }
+    nodes.sort_by_key(|n| membership[&n.id]);
+    let connections = state.storage
+        .get_all_connections()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .into_iter()
+        .filter(|c| {
+            filter.accepts(c)
+                && membership.contains_key(&c.source_id)
+                && membership.contains_key(&c.target_id)
         })
         .collect();
 
-    let edges_json: Vec<Value> = edges.iter()
-        .map(|e| {
-            serde_json::json!({
-                "source": e.source_id,
-                "target": e.target_id,
-                "weight": e.strength,
-                "type": e.link_type,
-            })
+    let (nodes, edges, edges_truncated) = build_graph(state, nodes, connections, filter, None, &membership)?;
+    Ok(GraphResponse {
+        node_count: nodes.len(),
+        edge_count: edges.len(),
+        nodes,
+        edges,
+        center_id: None,
+        depth: 0,
+        communities: Some(communities),
+        truncated: truncated || edges_truncated,
+    })
+}
+
+// ============================================================================
+// KNOWLEDGE EDGES (typed relationships)
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct EdgeListParams {
+    pub node: String,
+}
+
+/// Typed knowledge edges touching a memory
+pub async fn list_edges(
+    State(state): State<AppState>,
+    Query(params): Query<EdgeListParams>,
+) -> Result<Json<Value>, StatusCode> {
+    state.storage
+        .get_node(&params.node)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let edges = state.storage
+        .get_knowledge_edges(&params.node)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(serde_json::json!({
+        "node": params.node,
+        "total": edges.len(),
+        "edges": edges,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateEdgeRequest {
+    pub source_id: String,
+    pub target_id: String,
+    pub edge_type: String, // "causal", "contradiction", "refinement", ...
+    pub weight: Option<f32>,
+    pub confidence: Option<f32>,
+    pub metadata: Option<String>,
+}
+
+/// Create a typed knowledge edge between two memories
+pub async fn create_edge(
+    State(state): State<AppState>,
+    Json(req): Json<CreateEdgeRequest>,
+) -> Result<Json<Value>, StatusCode> {
+    if req.source_id == req.target_id {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let edge_type: vestige_core::EdgeType = req.edge_type
+        .parse()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let mut edge = vestige_core::KnowledgeEdge::new(req.source_id, req.target_id, edge_type);
+    edge.weight = req.weight.unwrap_or(1.0).clamp(0.0, 1.0);
+    edge.confidence = req.confidence.unwrap_or(1.0).clamp(0.0, 1.0);
+    edge.created_by = Some("user".to_string());
+    edge.metadata = req.metadata;
+    state.storage.save_knowledge_edge(&edge).map_err(|e| match e {
+        vestige_core::StorageError::NotFound(_) => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    })?;
+
+    state.emit(VestigeEvent::EdgeCreated {
+        id: edge.id.clone(),
+        source_id: edge.source_id.clone(),
+        target_id: edge.target_id.clone(),
+        edge_type: edge.edge_type.to_string(),
+        weight: edge.weight as f64,
+        timestamp: edge.created_at,
+    });
+
+    Ok(Json(serde_json::json!({
+        "created": true,
+        "edge": edge,
+    })))
+}
+
+/// Delete a knowledge edge by ID
+pub async fn delete_edge(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, StatusCode> {
+    let deleted = state.storage
+        .delete_knowledge_edge(&id)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if deleted {
+        Ok(Json(serde_json::json!({ "deleted": true, "id": id })))
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ContradictionParams {
+    pub limit: Option<i32>,
+}
+
+/// Unresolved contradiction edges with both sides' content
+pub async fn list_contradictions(
+    State(state): State<AppState>,
+    Query(params): Query<ContradictionParams>,
+) -> Result<Json<Value>, StatusCode> {
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let edges = state.storage
+        .list_contradictions(limit)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let side = |id: &str| -> Result<Value, StatusCode> {
+        let node = state.storage
+            .get_node(id)
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        Ok(serde_json::json!({
+            "id": id,
+            "content": node.map(|n| content_preview(&n.content)),
+        }))
+    };
+    let contradictions = edges
+        .iter()
+        .map(|edge| {
+            Ok(serde_json::json!({
+                "id": edge.id,
+                "newer": side(&edge.source_id)?,
+                "older": side(&edge.target_id)?,
+                "confidence": edge.confidence,
+                "metadata": edge.metadata.as_deref()
+                    .and_then(|m| serde_json::from_str::<Value>(m).ok()),
+                "createdAt": edge.created_at.to_rfc3339(),
+            }))
         })
-        .collect();
+        .collect::<Result<Vec<_>, StatusCode>>()?;
 
     Ok(Json(serde_json::json!({
-        "nodes": nodes_json,
-        "edges": edges_json,
-        "center_id": center_id,
-        "depth": depth,
-        "nodeCount": nodes.len(),
-        "edgeCount": edges.len(),
+        "total": contradictions.len(),
+        "contradictions": contradictions,
     })))
 }
 
+/// Mark a contradiction resolved without deleting the edge
+pub async fn resolve_contradiction(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, StatusCode> {
+    let resolved = state.storage
+        .resolve_contradiction(&id)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if resolved {
+        Ok(Json(serde_json::json!({ "resolved": true, "id": id })))
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
 // ============================================================================
 // SEARCH (dedicated endpoint)
 // ============================================================================
@@ -429,20 +1439,51 @@ pub struct SearchParams {
     pub q: String,
     pub limit: Option<i32>,
     pub min_retention: Option<f64>,
+    /// Ranking preset: balanced (default), recent or established
+    pub ranking: Option<String>,
+    /// Attach a per-result score breakdown
+    pub explain: Option<bool>,
+}
+
+/// Dashboard search settings for an optional ranking preset
+fn search_config(
+    ranking: Option<&str>,
+    explain: bool,
+) -> Result<(Option<vestige_core::RankingProfile>, vestige_core::HybridSearchConfig), ApiError> {
+    let profile = ranking
+        .map(|name| {
+            vestige_core::RankingProfile::preset(name).ok_or_else(|| {
+                api_error(
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    "ranking must be balanced, recent or established",
+                )
+            })
+        })
+        .transpose()?;
+    let mut config = vestige_core::HybridSearchConfig {
+        explain,
+        ..Default::default()
+    };
+    if let Some(profile) = profile {
+        config = config.with_profile(profile);
+    }
+    Ok((profile, config))
 }
 
 /// Search memories with hybrid search
 pub async fn search_memories(
     State(state): State<AppState>,
     Query(params): Query<SearchParams>,
-) -> Result<Json<Value>, StatusCode> {
+) -> Result<Json<Value>, ApiError> {
     let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let (profile, config) =
+        search_config(params.ranking.as_deref(), params.explain.unwrap_or(false))?;
     let start = std::time::Instant::now();
 
     let results = state
         .storage
-        .hybrid_search(&params.q, limit, 0.3, 0.7)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .hybrid_search_with_config(&params.q, limit, &config)
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
     let duration_ms = start.elapsed().as_millis() as u64;
 
@@ -473,134 +1514,141 @@ pub async fn search_memories(
                 "retentionStrength": r.node.retention_strength,
                 "combinedScore": r.combined_score,
                 "createdAt": r.node.created_at.to_rfc3339(),
+                "scoreBreakdown": r.score_breakdown,
             })
         })
         .collect();
 
     Ok(Json(serde_json::json!({
         "query": params.q,
+        "ranking": profile,
         "total": formatted.len(),
         "durationMs": duration_ms,
         "results": formatted,
     })))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ExplainParams {
+    pub q: String,
+    pub id: String,
+    pub limit: Option<i32>,
+    /// Ranking preset: balanced (default), recent or established
+    pub ranking: Option<String>,
+}
+
+/// Score breakdown for one memory, whether or not the search returned it
+pub async fn explain_ranking(
+    State(state): State<AppState>,
+    Query(params): Query<ExplainParams>,
+) -> Result<Json<Value>, ApiError> {
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let (profile, config) = search_config(params.ranking.as_deref(), true)?;
+
+    let breakdown = state
+        .storage
+        .explain_ranking_with_config(&params.q, &params.id, limit, &config)
+        .map_err(|e| match e {
+            vestige_core::StorageError::NotFound(_) => {
+                api_error(StatusCode::NOT_FOUND, format!("memory {} not found", params.id))
+            }
+            e => api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        })?;
+
+    Ok(Json(serde_json::json!({
+        "query": params.q,
+        "id": params.id,
+        "ranking": profile,
+        "scoreBreakdown": breakdown,
+    })))
+}
+
 // ============================================================================
 // COGNITIVE OPERATIONS (v2.0)
 // ============================================================================
 
-/// Trigger a dream cycle via CognitiveEngine
+/// Run the 4-phase dream cycle (NREM1, NREM3, REM, Integration)
 pub async fn trigger_dream(
     State(state): State<AppState>,
 ) -> Result<Json<Value>, StatusCode> {
-    let cognitive = state.cognitive.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
-    let start = std::time::Instant::now();
-    let memory_count: usize = 50;
-
-    // Load memories for dreaming
-    let all_nodes = state
+    let memory_count = state
         .storage
-        .get_all_nodes(memory_count as i32, 0)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .get_all_nodes(50, 0)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .len();
 
-    if all_nodes.len() < 5 {
+    if memory_count < 5 {
         return Ok(Json(serde_json::json!({
             "status": "insufficient_memories",
-            "message": format!("Need at least 5 memories. Current: {}", all_nodes.len()),
+            "message": format!("Need at least 5 memories. Current: {}", memory_count),
         })));
     }
 
     // Emit start event
     state.emit(VestigeEvent::DreamStarted {
-        memory_count: all_nodes.len(),
+        memory_count,
         timestamp: Utc::now(),
     });
 
-    // Build dream memories
-    let dream_memories: Vec<vestige_core::DreamMemory> = all_nodes
-        .iter()
-        .map(|n| vestige_core::DreamMemory {
-            id: n.id.clone(),
-            content: n.content.clone(),
-            embedding: state.storage.get_node_embedding(&n.id).ok().flatten(),
-            tags: n.tags.clone(),
-            created_at: n.created_at,
-            access_count: n.reps as u32,
-        })
-        .collect();
+    let result = state
+        .storage
+        .run_dream_cycle()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    // Run dream through CognitiveEngine
-    let cog = cognitive.lock().await;
-    let pre_dream_count = cog.dreamer.get_connections().len();
-    let dream_result = cog.dreamer.dream(&dream_memories).await;
-    let insights = cog.dreamer.synthesize_insights(&dream_memories);
-    let all_connections = cog.dreamer.get_connections();
-    drop(cog);
-
-    // Persist new connections
-    let new_connections = &all_connections[pre_dream_count..];
-    let mut connections_persisted = 0u64;
     let now = Utc::now();
-    for conn in new_connections {
-        let link_type = match conn.connection_type {
-            vestige_core::DiscoveredConnectionType::Semantic => "semantic",
-            vestige_core::DiscoveredConnectionType::SharedConcept => "shared_concepts",
-            vestige_core::DiscoveredConnectionType::Temporal => "temporal",
-            vestige_core::DiscoveredConnectionType::Complementary => "complementary",
-            vestige_core::DiscoveredConnectionType::CausalChain => "causal",
-        };
-        let record = vestige_core::ConnectionRecord {
-            source_id: conn.from_id.clone(),
-            target_id: conn.to_id.clone(),
-            strength: conn.similarity,
-            link_type: link_type.to_string(),
-            created_at: now,
-            last_activated: now,
-            activation_count: 1,
-        };
-        if state.storage.save_connection(&record).is_ok() {
-            connections_persisted += 1;
-        }
-
-        // Emit connection events
+    for (i, phase) in result.phases.iter().enumerate() {
+        state.emit(VestigeEvent::DreamProgress {
+            phase: phase.phase.to_string(),
+            memory_id: None,
+            progress_pct: (i + 1) as f64 / result.phases.len() as f64 * 100.0,
+            timestamp: now,
+        });
+    }
+    for conn in &result.creative_connections {
         state.emit(VestigeEvent::ConnectionDiscovered {
-            source_id: conn.from_id.clone(),
-            target_id: conn.to_id.clone(),
-            connection_type: link_type.to_string(),
-            weight: conn.similarity,
+            source_id: conn.memory_a_id.clone(),
+            target_id: conn.memory_b_id.clone(),
+            connection_type: conn.connection_type.as_link_type().to_string(),
+            weight: conn.confidence,
             timestamp: now,
         });
     }
 
-    let duration_ms = start.elapsed().as_millis() as u64;
-
     // Emit completion event
     state.emit(VestigeEvent::DreamCompleted {
-        memories_replayed: dream_memories.len(),
-        connections_found: connections_persisted as usize,
-        insights_generated: insights.len(),
-        duration_ms,
+        memories_replayed: result.memories_replayed,
+        connections_found: result.creative_connections.len(),
+        insights_generated: result.insights.len(),
+        duration_ms: result.total_duration_ms,
         timestamp: Utc::now(),
     });
 
     Ok(Json(serde_json::json!({
         "status": "dreamed",
-        "memoriesReplayed": dream_memories.len(),
-        "connectionsPersisted": connections_persisted,
-        "insights": insights.iter().map(|i| serde_json::json!({
-            "type": format!("{:?}", i.insight_type),
+        "memoriesReplayed": result.memories_replayed,
+        "connectionsPersisted": result.creative_connections.len(),
+        "insights": result.insights.iter().map(|i| serde_json::json!({
+            "type": i.insight_type,
             "insight": i.insight,
-            "sourceMemories": i.source_memories,
+            "sourceMemories": i.source_memory_ids,
             "confidence": i.confidence,
-            "noveltyScore": i.novelty_score,
+            "noveltyScore": i.novelty,
+        })).collect::<Vec<Value>>(),
+        "phases": result.phases.iter().map(|p| serde_json::json!({
+            "phase": p.phase.to_string(),
+            "durationMs": p.duration_ms,
+            "memoriesProcessed": p.memories_processed,
+            "actions": p.actions,
         })).collect::<Vec<Value>>(),
         "stats": {
-            "newConnectionsFound": dream_result.new_connections_found,
-            "connectionsPersisted": connections_persisted,
-            "memoriesStrengthened": dream_result.memories_strengthened,
-            "memoriesCompressed": dream_result.memories_compressed,
-            "insightsGenerated": dream_result.insights_generated.len(),
-            "durationMs": duration_ms,
+            "newConnectionsFound": result.creative_connections.len(),
+            "connectionsPersisted": result.creative_connections.len(),
+            "memoriesStrengthened": result.memories_strengthened,
+            "memoriesCompressed": 0,
+            "memoriesDownscaled": result.memories_downscaled,
+            "emotionalProcessed": result.emotional_processed,
+            "insightsGenerated": result.insights.len(),
+            "durationMs": result.total_duration_ms,
         }
     })))
 }
@@ -790,9 +1838,21 @@ pub async fn score_importance(
     }
 }
 
-/// Trigger consolidation
+/// Longest a dashboard-triggered consolidation runs unless `deadlineSecs` says otherwise
+const DASHBOARD_CONSOLIDATION_DEADLINE_SECS: u64 = 60;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsolidateParams {
+    /// Stop starting steps after this many seconds
+    pub deadline_secs: Option<u64>,
+}
+
+/// Trigger consolidation, bounded by `deadlineSecs` (default 60) and
+/// stopped on shutdown
 pub async fn trigger_consolidation(
     State(state): State<AppState>,
+    Query(params): Query<ConsolidateParams>,
 ) -> Result<Json<Value>, StatusCode> {
     state.emit(VestigeEvent::ConsolidationStarted {
         timestamp: Utc::now(),
@@ -800,10 +1860,26 @@ pub async fn trigger_consolidation(
 
     let start = std::time::Instant::now();
 
+    let limits = vestige_core::ConsolidationLimits {
+        deadline: Some(std::time::Duration::from_secs(
+            params.deadline_secs.unwrap_or(DASHBOARD_CONSOLIDATION_DEADLINE_SECS),
+        )),
+        cancel: Some(state.shutdown.clone()),
+    };
     let result = state
         .storage
-        .run_consolidation()
+        .run_consolidation_with_limits(&limits, &mut |p| {
+            state.emit(VestigeEvent::ConsolidationProgress {
+                step: p.step,
+                total: p.total,
+                label: p.label.to_string(),
+                timestamp: Utc::now(),
+            });
+        })
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if let Some(ref cognitive) = state.cognitive {
+        cognitive.lock().await.sync_pins(&state.storage);
+    }
 
     let duration_ms = start.elapsed().as_millis() as u64;
 
@@ -820,11 +1896,117 @@ pub async fn trigger_consolidation(
         "decayApplied": result.decay_applied,
         "embeddingsGenerated": result.embeddings_generated,
         "duplicatesMerged": result.duplicates_merged,
+        "dedupClustersExamined": result.dedup_clusters_examined,
+        "dedupClustersMerged": result.dedup_clusters_merged,
         "activationsComputed": result.activations_computed,
         "durationMs": duration_ms,
+        "steps": result.steps,
+        "stepTimings": result.step_timings,
+        "stepErrors": result.step_errors,
+        "skipped": result.skipped,
+        "stoppedEarly": result.stopped_early,
+        "stepsNotRun": result.steps_not_run,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DuplicateParams {
+    pub threshold: Option<f32>,
+}
+
+/// Get clusters of likely duplicate memories for review (read-only)
+pub async fn duplicate_clusters(
+    State(state): State<AppState>,
+    Query(params): Query<DuplicateParams>,
+) -> Result<Json<Value>, StatusCode> {
+    let threshold = params.threshold.unwrap_or(0.85).clamp(0.5, 1.0);
+    let clusters = state
+        .storage
+        .find_duplicate_clusters(threshold)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let clusters: Vec<Value> = clusters
+        .iter()
+        .map(|ids| {
+            let memories: Vec<Value> = ids
+                .iter()
+                .filter_map(|id| state.storage.get_node(id).ok().flatten())
+                .map(|node| {
+                    serde_json::json!({
+                        "id": node.id,
+                        "content": node.content.chars().take(120).collect::<String>(),
+                        "nodeType": node.node_type,
+                        "retention": node.retention_strength,
+                    })
+                })
+                .collect();
+            serde_json::json!({ "memories": memories })
+        })
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "threshold": threshold,
+        "clusters": clusters,
+        "total": clusters.len(),
     })))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SessionOverviewParams {
+    pub budget: Option<usize>,
+    pub codebase: Option<String>,
+}
+
+/// Get the working set summarized into topical packets (session overview panel)
+pub async fn session_overview(
+    State(state): State<AppState>,
+    Query(params): Query<SessionOverviewParams>,
+) -> Result<Json<Value>, StatusCode> {
+    let budget = params.budget.unwrap_or(500).clamp(50, 5000);
+    let project_tag = params.codebase.map(|cb| format!("codebase:{}", cb));
+    let overview = state
+        .storage
+        .build_session_overview(project_tag.as_deref(), budget)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(serde_json::json!(overview)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccessAnalyticsParams {
+    pub days: Option<i64>,
+}
+
+/// Usage insights from the access log (cut to its 90-day retention)
+pub async fn access_analytics(
+    State(state): State<AppState>,
+    Query(params): Query<AccessAnalyticsParams>,
+) -> Result<Json<Value>, StatusCode> {
+    let days = params.days.unwrap_or(30).max(1);
+    let analytics = state
+        .storage
+        .access_analytics(Duration::days(days))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(serde_json::json!(analytics)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ForecastParams {
+    pub days: Option<u32>,
+}
+
+/// Daily due reviews and projected retention (for the forecast chart)
+pub async fn forecast(
+    State(state): State<AppState>,
+    Query(params): Query<ForecastParams>,
+) -> Result<Json<Value>, StatusCode> {
+    let days = params.days.unwrap_or(30).clamp(1, 365);
+    let forecast = state
+        .storage
+        .forecast(days)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(serde_json::json!(forecast)))
+}
+
 /// Get retention distribution (for histogram visualization)
 pub async fn retention_distribution(
     State(state): State<AppState>,
@@ -913,3 +2095,191 @@ pub async fn list_intentions(
         "filter": status_filter,
     })))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+    use vestige_core::Storage;
+
+    fn test_state() -> (AppState, TempDir) {
+        let dir = TempDir::new().unwrap();
+        let storage = Storage::new(Some(dir.path().join("test.db"))).unwrap();
+        (AppState::new(Arc::new(storage), None), dir)
+    }
+
+    fn create_request(content: &str, node_type: Option<&str>) -> CreateMemoryRequest {
+        CreateMemoryRequest {
+            content: content.to_string(),
+            node_type: node_type.map(String::from),
+            tags: Some(vec!["dashboard".to_string()]),
+            source: None,
+            sentiment: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_memory_validation_errors() {
+        let (state, _dir) = test_state();
+
+        let (status, Json(body)) = create_memory(State(state.clone()), Json(create_request("  ", None)))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(body["error"].as_str().unwrap().contains("content"));
+
+        let (status, Json(body)) =
+            create_memory(State(state.clone()), Json(create_request("Valid content", Some("gossip"))))
+                .await
+                .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(body["error"].as_str().unwrap().contains("gossip"));
+
+        assert_eq!(state.storage.get_stats().unwrap().total_nodes, 0);
+    }
+
+    #[tokio::test]
+    async fn test_create_and_update_memory_broadcast_events() {
+        let (state, _dir) = test_state();
+        let mut events = state.subscribe();
+
+        let Json(created) = create_memory(
+            State(state.clone()),
+            Json(create_request("Dashboard-created memory", Some("Decision"))),
+        )
+        .await
+        .unwrap();
+        assert_eq!(created["decision"], "create");
+        assert_eq!(created["memory"]["nodeType"], "decision");
+        let id = created["memory"]["id"].as_str().unwrap().to_string();
+        assert!(matches!(events.try_recv().unwrap(), VestigeEvent::MemoryCreated { .. }));
+
+        let edit = UpdateMemoryRequest {
+            content: Some("Dashboard-edited memory".to_string()),
+            tags: Some(vec!["edited".to_string()]),
+        };
+        let Json(updated) = update_memory(State(state.clone()), Path(id.clone()), Json(edit))
+            .await
+            .unwrap();
+        assert_eq!(updated["memory"]["content"], "Dashboard-edited memory");
+        assert_eq!(updated["memory"]["tags"], serde_json::json!(["edited"]));
+        match events.try_recv().unwrap() {
+            VestigeEvent::MemoryUpdated { field, .. } => assert_eq!(field, "content,tags"),
+            other => panic!("unexpected event {:?}", other),
+        }
+        assert_eq!(state.storage.get_node_revisions(&id, 10).unwrap().len(), 1);
+
+        let empty = UpdateMemoryRequest { content: Some(String::new()), tags: None };
+        let (status, _) = update_memory(State(state.clone()), Path(id), Json(empty)).await.unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let missing = UpdateMemoryRequest { content: None, tags: Some(vec![]) };
+        let (status, _) = update_memory(State(state), Path("missing".to_string()), Json(missing))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_review_queue_and_review() {
+        let (state, _dir) = test_state();
+        let mut events = state.subscribe();
+        let node = state.storage
+            .ingest(vestige_core::IngestInput {
+                content: "Spaced repetition from the dashboard".to_string(),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let Json(queue) = review_queue(State(state.clone()), Query(ReviewQueueParams { limit: None }))
+            .await
+            .unwrap();
+        let intervals = &queue["queue"][0]["intervals"];
+        assert_eq!(queue["queue"][0]["memory"]["id"], node.id.as_str());
+        assert!(intervals["easy"].as_i64().unwrap() >= intervals["again"].as_i64().unwrap());
+
+        let (status, _) = review_memory(
+            State(state.clone()),
+            Path(node.id.clone()),
+            Json(ReviewRequest { rating: "superb".to_string() }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let Json(first) = review_memory(
+            State(state.clone()),
+            Path(node.id.clone()),
+            Json(ReviewRequest { rating: "good".to_string() }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(first["rating"], "good");
+        assert!(first["nextReview"].is_string());
+        assert!(matches!(events.try_recv().unwrap(), VestigeEvent::MemoryReviewed { .. }));
+
+        // Reviewing again straight away is allowed, but flagged as early
+        let Json(second) = review_memory(
+            State(state.clone()),
+            Path(node.id.clone()),
+            Json(ReviewRequest { rating: "Easy".to_string() }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(second["early"], true);
+    }
+
+    #[tokio::test]
+    async fn test_graph_filters_and_summary() {
+        let (state, _dir) = test_state();
+        let ids: Vec<String> = (0..4)
+            .map(|i| {
+                state.storage
+                    .ingest(vestige_core::IngestInput {
+                        content: format!("Graph memory {i}"),
+                        node_type: "concept".to_string(),
+                        ..Default::default()
+                    })
+                    .unwrap()
+                    .id
+            })
+            .collect();
+        let now = Utc::now();
+        for (a, b, strength, link_type) in [(0, 1, 0.9, "causal"), (1, 2, 0.3, "semantic")] {
+            state.storage
+                .save_connection(&vestige_core::ConnectionRecord {
+                    source_id: ids[a].clone(),
+                    target_id: ids[b].clone(),
+                    strength,
+                    link_type: link_type.to_string(),
+                    created_at: now,
+                    last_activated: now,
+                    activation_count: 0,
+                })
+                .unwrap();
+        }
+
+        let params = GraphParams {
+            center: Some(ids[0].clone()),
+            min_strength: Some(0.5),
+            ..Default::default()
+        };
+        let Json(graph) = get_graph(State(state.clone()), Query(params)).await.unwrap();
+        assert_eq!(graph.node_count, 2);
+        assert_eq!(graph.edges[0].edge_type, "causal");
+        assert!(graph.nodes.iter().all(|n| n.state == "active"));
+        let body = serde_json::to_value(&graph).unwrap();
+        assert_eq!(body["center_id"], ids[0].as_str());
+        assert!(body["nodes"][0]["isCenter"].is_boolean());
+
+        let params = GraphParams { mode: Some("summary".to_string()), ..Default::default() };
+        let Json(summary) = get_graph(State(state), Query(params)).await.unwrap();
+        let communities = summary.communities.unwrap();
+        assert_eq!(communities.len(), 1);
+        assert_eq!(communities[0].size, 3);
+        assert_eq!(summary.node_count, 3);
+        assert!(summary.nodes.iter().all(|n| n.community == Some(0)));
+    }
+}
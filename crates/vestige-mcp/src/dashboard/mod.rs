@@ -5,14 +5,15 @@
 //!
 //! v2.0: WebSocket real-time events, CognitiveEngine access, new API endpoints.
 
+pub mod auth;
 pub mod events;
 pub mod handlers;
 pub mod state;
 pub mod static_files;
 pub mod websocket;
 
-use axum::routing::{delete, get, post};
-use axum::Router;
+use axum::routing::{delete, get, post, put};
+use axum::{middleware, Extension, Router};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -35,18 +36,31 @@ pub fn build_router(
     build_router_inner(state, port)
 }
 
-/// Build the axum router sharing an external event broadcast channel.
+/// Build the axum router sharing an external event broadcast channel and
+/// shutdown token.
 pub fn build_router_with_event_tx(
     storage: Arc<Storage>,
     cognitive: Option<Arc<Mutex<CognitiveEngine>>>,
     event_tx: tokio::sync::broadcast::Sender<events::VestigeEvent>,
+    shutdown: vestige_core::CancellationToken,
     port: u16,
 ) -> (Router, AppState) {
-    let state = AppState::with_event_tx(storage, cognitive, event_tx);
+    let state = AppState::with_event_tx(storage, cognitive, event_tx, shutdown);
     build_router_inner(state, port)
 }
 
 fn build_router_inner(state: AppState, port: u16) -> (Router, AppState) {
+    build_router_with_token(state, port, auth::DashboardToken::from_env())
+}
+
+fn build_router_with_token(
+    state: AppState,
+    port: u16,
+    token: auth::DashboardToken,
+) -> (Router, AppState) {
+    if token.is_enabled() {
+        info!("Dashboard token auth enabled ({})", auth::TOKEN_ENV);
+    }
 
     let origins = vec![
         format!("http://127.0.0.1:{}", port)
@@ -69,6 +83,7 @@ fn build_router_inner(state: AppState, port: u16) -> (Router, AppState) {
         .allow_methods([
             axum::http::Method::GET,
             axum::http::Method::POST,
+            axum::http::Method::PUT,
             axum::http::Method::DELETE,
             axum::http::Method::OPTIONS,
         ])
@@ -84,39 +99,67 @@ fn build_router_inner(state: AppState, port: u16) -> (Router, AppState) {
         ),
     );
 
-    let router = Router::new()
-        // SvelteKit Dashboard v2.0 (embedded static build)
-        .route("/dashboard", get(static_files::serve_dashboard_spa))
-        .route("/dashboard/{*path}", get(static_files::serve_dashboard_asset))
-        // Legacy embedded HTML (keep for backward compat)
-        .route("/", get(handlers::serve_dashboard))
-        .route("/graph", get(handlers::serve_graph))
+    // API and WebSocket routes, behind the optional token
+    let api = Router::new()
         // WebSocket for real-time events
         .route("/ws", get(websocket::ws_handler))
         // Memory CRUD
-        .route("/api/memories", get(handlers::list_memories))
+        .route("/api/memories", get(handlers::list_memories).post(handlers::create_memory))
+        .route("/api/memories/bulk", post(handlers::bulk_memories))
         .route("/api/memories/{id}", get(handlers::get_memory))
+        .route("/api/memories/{id}", put(handlers::update_memory))
         .route("/api/memories/{id}", delete(handlers::delete_memory))
         .route("/api/memories/{id}/promote", post(handlers::promote_memory))
         .route("/api/memories/{id}/demote", post(handlers::demote_memory))
+        .route("/api/memories/{id}/pin", post(handlers::pin_memory).delete(handlers::unpin_memory))
+        .route("/api/memories/{id}/related", get(handlers::related_memories))
+        .route("/api/memories/{id}/importance", get(handlers::memory_importance))
+        .route("/api/memories/{id}/revisions", get(handlers::memory_revisions))
+        .route("/api/memories/{id}/revisions/diff", get(handlers::memory_revision_diff))
+        .route("/api/memories/{id}/revisions/{revision}/revert", post(handlers::revert_memory))
+        .route("/api/pins", get(handlers::list_pins))
         // Search
+        .route("/api/review/queue", get(handlers::review_queue))
+        .route("/api/review/{id}", post(handlers::review_memory))
         .route("/api/search", get(handlers::search_memories))
+        .route("/api/search/explain", get(handlers::explain_ranking))
         // Stats & health
         .route("/api/stats", get(handlers::get_stats))
         .route("/api/health", get(handlers::health_check))
+        .route("/api/health/deep", get(handlers::deep_health_check))
         // Timeline
         .route("/api/timeline", get(handlers::get_timeline))
         // Graph
         .route("/api/graph", get(handlers::get_graph))
+        .route("/api/edges", get(handlers::list_edges).post(handlers::create_edge))
+        .route("/api/edges/{id}", delete(handlers::delete_edge))
+        .route("/api/contradictions", get(handlers::list_contradictions))
+        .route("/api/contradictions/{id}/resolve", post(handlers::resolve_contradiction))
         // Cognitive operations (v2.0)
         .route("/api/dream", post(handlers::trigger_dream))
         .route("/api/explore", post(handlers::explore_connections))
         .route("/api/predict", post(handlers::predict_memories))
         .route("/api/importance", post(handlers::score_importance))
+        .route("/api/importance/top", get(handlers::top_important))
         .route("/api/consolidate", post(handlers::trigger_consolidation))
         .route("/api/retention-distribution", get(handlers::retention_distribution))
+        .route("/api/duplicates", get(handlers::duplicate_clusters))
+        .route("/api/session/overview", get(handlers::session_overview))
+        .route("/api/analytics/access", get(handlers::access_analytics))
+        .route("/api/forecast", get(handlers::forecast))
         // Intentions (v2.0)
         .route("/api/intentions", get(handlers::list_intentions))
+        .route_layer(middleware::from_fn_with_state(token.clone(), auth::require_token));
+
+    let router = Router::new()
+        // SvelteKit Dashboard v2.0 (embedded static build)
+        .route("/dashboard", get(static_files::serve_dashboard_spa))
+        .route("/dashboard/{*path}", get(static_files::serve_dashboard_asset))
+        // Legacy embedded HTML (keep for backward compat)
+        .route("/", get(handlers::serve_dashboard))
+        .route("/graph", get(handlers::serve_graph))
+        .merge(api)
+        .layer(Extension(token))
         .layer(
             ServiceBuilder::new()
                 .concurrency_limit(50)
@@ -163,14 +206,16 @@ pub async fn start_background(
     start_background_inner(app, state, port).await
 }
 
-/// Start the dashboard sharing an external event broadcast channel.
+/// Start the dashboard sharing an external event broadcast channel and
+/// shutdown token.
 pub async fn start_background_with_event_tx(
     storage: Arc<Storage>,
     cognitive: Option<Arc<Mutex<CognitiveEngine>>>,
     event_tx: tokio::sync::broadcast::Sender<events::VestigeEvent>,
+    shutdown: vestige_core::CancellationToken,
     port: u16,
 ) -> Result<AppState, Box<dyn std::error::Error>> {
-    let (app, state) = build_router_with_event_tx(storage, cognitive, event_tx, port);
+    let (app, state) = build_router_with_event_tx(storage, cognitive, event_tx, shutdown, port);
     start_background_inner(app, state, port).await
 }
 
@@ -207,3 +252,78 @@ async fn start_background_inner(
 
     Ok(state)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{Body, to_bytes};
+    use axum::http::{Request, StatusCode, header};
+    use tempfile::TempDir;
+    use tower::Service;
+
+    async fn get(
+        router: &mut Router,
+        uri: &str,
+        headers: &[(header::HeaderName, &str)],
+    ) -> axum::response::Response {
+        let mut request = Request::get(uri);
+        for (name, value) in headers {
+            request = request.header(name, *value);
+        }
+        router
+            .call(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_legacy_page_works_with_token_auth() {
+        let dir = TempDir::new().unwrap();
+        let storage = Arc::new(Storage::new(Some(dir.path().join("test.db"))).unwrap());
+        let token = auth::DashboardToken::new(Some("s3cret".to_string()));
+        let (mut router, _state) =
+            build_router_with_token(AppState::new(storage, None), 3927, token);
+
+        // Without the token the page loads but gets no token, and the API refuses
+        let page = get(&mut router, "/", &[]).await;
+        assert_eq!(page.status(), StatusCode::OK);
+        let body = to_bytes(page.into_body(), usize::MAX).await.unwrap();
+        assert!(!String::from_utf8_lossy(&body).contains("vestige-token"));
+        assert_eq!(
+            get(&mut router, "/api/health", &[]).await.status(),
+            StatusCode::UNAUTHORIZED
+        );
+
+        // `?token=` hands the page the token and sets the bootstrap cookie
+        let page = get(&mut router, "/?token=s3cret", &[]).await;
+        let cookie = page.headers()[header::SET_COOKIE]
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert!(cookie.starts_with("vestige_token=s3cret;"));
+        let body = to_bytes(page.into_body(), usize::MAX).await.unwrap();
+        assert!(
+            String::from_utf8_lossy(&body)
+                .contains(r#"<meta name="vestige-token" content="s3cret" />"#)
+        );
+
+        // The cookie alone is enough for later loads of either legacy page
+        let cookie = cookie.split(';').next().unwrap();
+        let graph = get(&mut router, "/graph", &[(header::COOKIE, cookie)]).await;
+        assert!(graph.headers().get(header::SET_COOKIE).is_none());
+        let body = to_bytes(graph.into_body(), usize::MAX).await.unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("vestige-token"));
+
+        // ...and the page's apiFetch header gets through
+        let health = get(
+            &mut router,
+            "/api/health",
+            &[(header::AUTHORIZATION, "Bearer s3cret")],
+        )
+        .await;
+        assert_eq!(health.status(), StatusCode::OK);
+        let wrong = get(&mut router, "/", &[(header::COOKIE, "vestige_token=nope")]).await;
+        let body = to_bytes(wrong.into_body(), usize::MAX).await.unwrap();
+        assert!(!String::from_utf8_lossy(&body).contains("vestige-token"));
+    }
+}
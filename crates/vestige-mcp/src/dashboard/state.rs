@@ -3,7 +3,7 @@
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::{broadcast, Mutex};
-use vestige_core::Storage;
+use vestige_core::{CancellationToken, Storage};
 
 use crate::cognitive::CognitiveEngine;
 use super::events::VestigeEvent;
@@ -18,6 +18,8 @@ pub struct AppState {
     pub cognitive: Option<Arc<Mutex<CognitiveEngine>>>,
     pub event_tx: broadcast::Sender<VestigeEvent>,
     pub start_time: Instant,
+    /// Cancelled when the server shuts down, stopping consolidation it started
+    pub shutdown: CancellationToken,
 }
 
 impl AppState {
@@ -32,6 +34,7 @@ impl AppState {
             cognitive,
             event_tx,
             start_time: Instant::now(),
+            shutdown: CancellationToken::new(),
         }
     }
 
@@ -45,12 +48,14 @@ impl AppState {
         storage: Arc<Storage>,
         cognitive: Option<Arc<Mutex<CognitiveEngine>>>,
         event_tx: broadcast::Sender<VestigeEvent>,
+        shutdown: CancellationToken,
     ) -> Self {
         Self {
             storage,
             cognitive,
             event_tx,
             start_time: Instant::now(),
+            shutdown,
         }
     }
 
@@ -4,29 +4,46 @@
 //! using `include_dir!`. This serves it at `/dashboard/` prefix.
 
 use axum::extract::Path;
-use axum::http::{header, StatusCode};
-use axum::response::{Html, IntoResponse, Response};
+use axum::http::{header, HeaderMap, StatusCode, Uri};
+use axum::Extension;
+use axum::response::{IntoResponse, Response};
 use include_dir::{include_dir, Dir};
 
+use super::auth::DashboardToken;
+
 /// Embed the entire SvelteKit build output into the binary.
 /// Build with: cd apps/dashboard && pnpm build
 /// The build output goes to apps/dashboard/build/
 static DASHBOARD_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/../../apps/dashboard/build");
 
+/// The SPA's index.html, with the auth token handed over as in
+/// [`DashboardToken::serve_page`]
+fn index_html(token: &DashboardToken, headers: &HeaderMap, uri: &Uri) -> Option<Response> {
+    let html =
+        String::from_utf8_lossy(DASHBOARD_DIR.get_file("index.html")?.contents()).to_string();
+    Some(token.serve_page(&html, headers, uri))
+}
+
 /// Serve the SvelteKit dashboard index
-pub async fn serve_dashboard_spa() -> impl IntoResponse {
-    match DASHBOARD_DIR.get_file("index.html") {
-        Some(file) => Html(
-            String::from_utf8_lossy(file.contents()).to_string(),
-        )
-        .into_response(),
+pub async fn serve_dashboard_spa(
+    Extension(token): Extension<DashboardToken>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> impl IntoResponse {
+    match index_html(&token, &headers, &uri) {
+        Some(page) => page,
         None => (StatusCode::NOT_FOUND, "Dashboard not built. Run: cd apps/dashboard && pnpm build")
             .into_response(),
     }
 }
 
 /// Serve static assets from the embedded SvelteKit build
-pub async fn serve_dashboard_asset(Path(path): Path<String>) -> Response {
+pub async fn serve_dashboard_asset(
+    Path(path): Path<String>,
+    Extension(token): Extension<DashboardToken>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> Response {
     // Try exact path
     if let Some(file) = DASHBOARD_DIR.get_file(&path) {
         let mime = mime_guess::from_path(&path)
@@ -53,13 +70,8 @@ pub async fn serve_dashboard_asset(Path(path): Path<String>) -> Response {
     }
 
     // SPA fallback: serve index.html for client-side routing
-    match DASHBOARD_DIR.get_file("index.html") {
-        Some(file) => (
-            StatusCode::OK,
-            [(header::CONTENT_TYPE, "text/html".to_string())],
-            file.contents().to_vec(),
-        )
-            .into_response(),
+    match index_html(&token, &headers, &uri) {
+        Some(page) => page,
         None => (StatusCode::NOT_FOUND, "Not found").into_response(),
     }
 }
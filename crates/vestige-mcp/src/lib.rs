@@ -4,3 +4,4 @@
 
 pub mod cognitive;
 pub mod dashboard;
+pub mod speculative;
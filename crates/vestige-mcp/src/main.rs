@@ -29,12 +29,14 @@
 
 // cognitive is exported from lib.rs for dashboard access
 use vestige_mcp::cognitive;
+use vestige_mcp::speculative;
 mod protocol;
 mod resources;
 mod server;
 mod tools;
 
 use std::io;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -42,17 +44,71 @@ use tracing::{error, info, warn, Level};
 use tracing_subscriber::EnvFilter;
 
 // Use vestige-core for the cognitive science engine
-use vestige_core::Storage;
+use vestige_core::{FSRSParameters, Storage};
 
+use crate::protocol::http::HttpTransport;
 use crate::protocol::stdio::StdioTransport;
 use crate::server::McpServer;
 
-/// Parse command-line arguments and return the optional data directory path.
-/// Returns `None` for the path if no `--data-dir` was specified.
+/// Which transport the MCP server speaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Transport {
+    Stdio,
+    Http,
+}
+
+/// Parsed command-line arguments
+struct CliArgs {
+    /// `None` if no `--data-dir` was specified
+    data_dir: Option<PathBuf>,
+    /// `None` if no `--namespace` was specified
+    namespace: Option<String>,
+    transport: Transport,
+    /// HTTP listen address (`--listen`, http transport only)
+    listen: SocketAddr,
+    /// FSRS parameters file to apply at startup (`--fsrs-params`)
+    fsrs_params: Option<PathBuf>,
+}
+
+/// Value of a `--flag <VALUE>` or `--flag=VALUE` argument; exits if it is missing
+fn flag_value(flag: &str, placeholder: &str, value: Option<&str>) -> String {
+    match value {
+        Some(v) if !v.trim().is_empty() => v.to_string(),
+        _ => {
+            eprintln!("error: {} requires a {} argument", flag, placeholder.to_lowercase());
+            eprintln!("Usage: vestige-mcp {} <{}>", flag, placeholder);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn parse_transport(value: &str) -> Transport {
+    match value {
+        "stdio" => Transport::Stdio,
+        "http" => Transport::Http,
+        other => {
+            eprintln!("error: unknown transport '{}' (expected stdio or http)", other);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn parse_listen(value: &str) -> SocketAddr {
+    value.parse().unwrap_or_else(|_| {
+        eprintln!("error: invalid --listen address '{}' (expected HOST:PORT, e.g. 127.0.0.1:3928)", value);
+        std::process::exit(1);
+    })
+}
+
+/// Parse command-line arguments.
 /// Exits the process if `--help` or `--version` is requested.
-fn parse_args() -> Option<PathBuf> {
+fn parse_args() -> CliArgs {
     let args: Vec<String> = std::env::args().collect();
     let mut data_dir: Option<PathBuf> = None;
+    let mut namespace: Option<String> = None;
+    let mut transport = Transport::Stdio;
+    let mut listen: Option<SocketAddr> = None;
+    let mut fsrs_params: Option<PathBuf> = None;
     let mut i = 1;
 
     while i < args.len() {
@@ -69,13 +125,21 @@ fn parse_args() -> Option<PathBuf> {
                 println!("    -h, --help              Print help information");
                 println!("    -V, --version           Print version information");
                 println!("    --data-dir <PATH>       Custom data directory");
+                println!("    --namespace <NAME>      Default namespace for search, ingest, consolidate and status");
+                println!("    --transport <KIND>      stdio (default) or http (MCP streamable HTTP)");
+                println!("    --listen <HOST:PORT>    HTTP listen address (default: {})", protocol::http::DEFAULT_LISTEN);
+                println!("    --fsrs-params <PATH>    Load FSRS-6 weights from a JSON file (e.g. exported from Anki)");
                 println!();
                 println!("ENVIRONMENT:");
                 println!("    RUST_LOG               Log level filter (e.g., debug, info, warn, error)");
+                println!("    {}      Bearer token required by the http transport", protocol::http::AUTH_TOKEN_ENV);
                 println!();
                 println!("EXAMPLES:");
                 println!("    vestige-mcp");
                 println!("    vestige-mcp --data-dir /custom/path");
+                println!("    vestige-mcp --namespace work");
+                println!("    vestige-mcp --transport http --listen 127.0.0.1:3928");
+                println!("    vestige-mcp --fsrs-params ~/fsrs-weights.json");
                 println!("    RUST_LOG=debug vestige-mcp");
                 std::process::exit(0);
             }
@@ -102,6 +166,45 @@ fn parse_args() -> Option<PathBuf> {
                 }
                 data_dir = Some(PathBuf::from(path));
             }
+            "--namespace" => {
+                i += 1;
+                if i >= args.len() || args[i].trim().is_empty() {
+                    eprintln!("error: --namespace requires a name argument");
+                    eprintln!("Usage: vestige-mcp --namespace <NAME>");
+                    std::process::exit(1);
+                }
+                namespace = Some(args[i].clone());
+            }
+            arg if arg.starts_with("--namespace=") => {
+                let name = arg.strip_prefix("--namespace=").unwrap_or("");
+                if name.trim().is_empty() {
+                    eprintln!("error: --namespace requires a name argument");
+                    eprintln!("Usage: vestige-mcp --namespace <NAME>");
+                    std::process::exit(1);
+                }
+                namespace = Some(name.to_string());
+            }
+            "--transport" => {
+                i += 1;
+                transport = parse_transport(&flag_value("--transport", "KIND", args.get(i).map(String::as_str)));
+            }
+            arg if arg.starts_with("--transport=") => {
+                transport = parse_transport(&flag_value("--transport", "KIND", arg.strip_prefix("--transport=")));
+            }
+            "--listen" => {
+                i += 1;
+                listen = Some(parse_listen(&flag_value("--listen", "HOST:PORT", args.get(i).map(String::as_str))));
+            }
+            arg if arg.starts_with("--listen=") => {
+                listen = Some(parse_listen(&flag_value("--listen", "HOST:PORT", arg.strip_prefix("--listen="))));
+            }
+            "--fsrs-params" => {
+                i += 1;
+                fsrs_params = Some(PathBuf::from(flag_value("--fsrs-params", "PATH", args.get(i).map(String::as_str))));
+            }
+            arg if arg.starts_with("--fsrs-params=") => {
+                fsrs_params = Some(PathBuf::from(flag_value("--fsrs-params", "PATH", arg.strip_prefix("--fsrs-params="))));
+            }
             arg => {
                 eprintln!("error: unknown argument '{}'", arg);
                 eprintln!("Usage: vestige-mcp [OPTIONS]");
@@ -112,13 +215,24 @@ fn parse_args() -> Option<PathBuf> {
         i += 1;
     }
 
-    data_dir
+    if listen.is_some() && transport != Transport::Http {
+        eprintln!("error: --listen requires --transport http");
+        std::process::exit(1);
+    }
+
+    CliArgs {
+        data_dir,
+        namespace,
+        transport,
+        listen: listen.unwrap_or_else(|| parse_listen(protocol::http::DEFAULT_LISTEN)),
+        fsrs_params,
+    }
 }
 
 #[tokio::main]
 async fn main() {
     // Parse CLI arguments first (before logging init, so --help/--version work cleanly)
-    let data_dir = parse_args();
+    let CliArgs { data_dir, namespace, transport, listen, fsrs_params } = parse_args();
 
     // Initialize logging to stderr (stdout is for JSON-RPC)
     tracing_subscriber::fmt()
@@ -150,6 +264,21 @@ async fn main() {
                 }
             }
 
+            // Apply user-supplied FSRS weights; they persist, so later starts
+            // without the flag keep using them
+            if let Some(path) = &fsrs_params {
+                let applied = FSRSParameters::from_json_file(path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|params| s.set_fsrs_parameters(params).map_err(|e| e.to_string()));
+                match applied {
+                    Ok(()) => info!("Loaded FSRS parameters from {}", path.display()),
+                    Err(e) => {
+                        error!("Failed to load FSRS parameters from {}: {}", path.display(), e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+
             Arc::new(s)
         }
         Err(e) => {
@@ -158,11 +287,31 @@ async fn main() {
         }
     };
 
+    // Cancelled once the transport stops, so a consolidation in progress
+    // stops between steps instead of holding up process exit
+    let shutdown = vestige_core::CancellationToken::new();
+
+    // Create cognitive engine (stateful neuroscience modules)
+    let cognitive = Arc::new(Mutex::new(cognitive::CognitiveEngine::new()));
+    info!("CognitiveEngine initialized (28 modules)");
+
+    // Warm the speculative retriever with persisted usage history
+    let replayed = speculative::replay_usage_history(&storage, &*cognitive.lock().await);
+    info!("Replayed {} usage events into speculative retriever", replayed);
+
+    // Restore user-starred flags from the stored pins
+    cognitive.lock().await.sync_pins(&storage);
+
     // Spawn periodic auto-consolidation so FSRS-6 decay scores stay fresh.
     // Runs on startup (if needed) and then every N hours (default: 6).
     // Configurable via VESTIGE_CONSOLIDATION_INTERVAL_HOURS env var.
     {
         let storage_clone = storage.clone();
+        let cognitive_clone = Arc::clone(&cognitive);
+        let limits = vestige_core::ConsolidationLimits {
+            cancel: Some(shutdown.clone()),
+            ..Default::default()
+        };
         tokio::spawn(async move {
             let interval_hours: u64 = std::env::var("VESTIGE_CONSOLIDATION_INTERVAL_HOURS")
                 .ok()
@@ -198,7 +347,13 @@ async fn main() {
                 };
 
                 if should_run {
-                    match storage_clone.run_consolidation() {
+                    match storage_clone.run_consolidation_with_limits(&limits, &mut |_| {}) {
+                        Ok(result) if result.skipped.is_some() => {
+                            info!(
+                                reason = result.skipped.as_deref().unwrap_or_default(),
+                                "Skipped auto-consolidation"
+                            );
+                        }
                         Ok(result) => {
                             info!(
                                 nodes_processed = result.nodes_processed,
@@ -209,6 +364,7 @@ async fn main() {
                                 duration_ms = result.duration_ms,
                                 "Periodic auto-consolidation complete"
                             );
+                            cognitive_clone.lock().await.sync_pins(&storage_clone);
                         }
                         Err(e) => {
                             warn!("Periodic auto-consolidation failed: {}", e);
@@ -222,9 +378,52 @@ async fn main() {
         });
     }
 
-    // Create cognitive engine (stateful neuroscience modules)
-    let cognitive = Arc::new(Mutex::new(cognitive::CognitiveEngine::new()));
-    info!("CognitiveEngine initialized (28 modules)");
+    // Spawn automatic backups into the standard backups directory, verified
+    // and rotated per BackupConfig (VESTIGE_BACKUP_INTERVAL_HOURS, default 24;
+    // 0 disables; VESTIGE_BACKUP_KEEP / VESTIGE_BACKUP_KEEP_WEEKS for rotation).
+    {
+        let storage_clone = storage.clone();
+        let config = vestige_core::BackupConfig::from_env();
+        match vestige_core::storage::default_backup_dir() {
+            Some(backup_dir) if config.enabled() => {
+                tokio::spawn(async move {
+                    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+
+                    // A failed attempt waits out the interval too, rather than retrying at once
+                    let mut last_attempt = None;
+                    loop {
+                        let last = Storage::get_last_backup_timestamp().max(last_attempt);
+                        let wait = config.until_next(last, storage_clone.clock().now());
+                        if wait > chrono::Duration::zero() {
+                            tokio::time::sleep(wait.to_std().unwrap_or_default()).await;
+                            continue;
+                        }
+
+                        last_attempt = Some(storage_clone.clock().now());
+                        match storage_clone.create_verified_backup(&backup_dir) {
+                            Ok(backup) => {
+                                info!(
+                                    path = %backup.path.display(),
+                                    size_bytes = backup.size_bytes,
+                                    "Automatic backup complete"
+                                );
+                                match storage_clone.rotate_backups(&backup_dir, &config) {
+                                    Ok(pruned) if !pruned.is_empty() => {
+                                        info!(pruned = pruned.len(), "Rotated old backups");
+                                    }
+                                    Ok(_) => {}
+                                    Err(e) => warn!("Backup rotation failed: {}", e),
+                                }
+                            }
+                            Err(e) => warn!("Automatic backup failed: {}", e),
+                        }
+                    }
+                });
+            }
+            Some(_) => info!("Automatic backups disabled (VESTIGE_BACKUP_INTERVAL_HOURS=0)"),
+            None => warn!("Could not determine backups directory; automatic backups disabled"),
+        }
+    }
 
     // Create shared event broadcast channel for dashboard <-> MCP tool events
     let (event_tx, _) = tokio::sync::broadcast::channel::<vestige_mcp::dashboard::events::VestigeEvent>(1024);
@@ -238,11 +437,13 @@ async fn main() {
         let dashboard_storage = Arc::clone(&storage);
         let dashboard_cognitive = Arc::clone(&cognitive);
         let dashboard_event_tx = event_tx.clone();
+        let dashboard_shutdown = shutdown.clone();
         tokio::spawn(async move {
             match vestige_mcp::dashboard::start_background_with_event_tx(
                 dashboard_storage,
                 Some(dashboard_cognitive),
                 dashboard_event_tx,
+                dashboard_shutdown,
                 dashboard_port,
             ).await {
                 Ok(_state) => {
@@ -267,16 +468,44 @@ async fn main() {
         });
     }
 
-    // Create MCP server with shared event channel for dashboard broadcasts
-    let server = McpServer::new_with_events(storage, cognitive, event_tx);
+    if let Some(ref ns) = namespace {
+        info!("Default namespace: {}", ns);
+    }
+
+    let result = match transport {
+        Transport::Stdio => {
+            // Create MCP server with shared event channel for dashboard broadcasts
+            let server = McpServer::new_with_events(storage, cognitive, event_tx)
+                .with_namespace(namespace)
+                .with_shutdown(shutdown.clone());
 
-    // Create stdio transport
-    let transport = StdioTransport::new();
+            info!("Starting MCP server on stdio...");
+            StdioTransport::new().run(server).await
+        }
+        Transport::Http => {
+            // One McpServer per HTTP session, all sharing storage, cognition and events
+            let server_shutdown = shutdown.clone();
+            let factory: protocol::http::ServerFactory = Arc::new(move || {
+                McpServer::new_with_events(Arc::clone(&storage), Arc::clone(&cognitive), event_tx.clone())
+                    .with_namespace(namespace.clone())
+                    .with_shutdown(server_shutdown.clone())
+            });
+            let token = std::env::var(protocol::http::AUTH_TOKEN_ENV).ok();
+            if token.is_none() && !listen.ip().is_loopback() {
+                warn!(
+                    "Listening on {} without {} — any client that can reach it has full memory access",
+                    listen,
+                    protocol::http::AUTH_TOKEN_ENV
+                );
+            }
+            HttpTransport::new(listen).with_auth_token(token).run(factory).await
+        }
+    };
 
-    info!("Starting MCP server on stdio...");
+    shutdown.cancel();
 
     // Run the server
-    if let Err(e) = transport.run(server).await {
+    if let Err(e) = result {
         error!("Server error: {}", e);
         std::process::exit(1);
     }
@@ -0,0 +1,459 @@
+//! Streamable HTTP Transport for MCP
+//!
+//! Serves the MCP streamable-HTTP transport on a single `/mcp` endpoint:
+//! - POST carries one JSON-RPC message; requests get a JSON response,
+//!   notifications get 202 Accepted
+//! - GET opens an SSE stream for server-initiated messages (heartbeat pings)
+//! - DELETE ends the session
+//!
+//! `initialize` starts a session, returned in the `Mcp-Session-Id` header.
+//! Each session has its own `McpServer` (and so its own initialization state
+//! and request IDs); all sessions share one Storage and CognitiveEngine.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, Mutex, RwLock};
+use tracing::{debug, info, warn};
+
+use super::types::{JsonRpcError, JsonRpcRequest, JsonRpcResponse};
+use crate::server::McpServer;
+use vestige_mcp::dashboard::auth::token_matches;
+
+/// Session header defined by the streamable-HTTP transport
+pub const SESSION_HEADER: &str = "mcp-session-id";
+
+/// Environment variable holding the bearer token clients must present
+pub const AUTH_TOKEN_ENV: &str = "VESTIGE_MCP_TOKEN";
+
+/// Default listen address for `--transport http`
+pub const DEFAULT_LISTEN: &str = "127.0.0.1:3928";
+
+/// Heartbeat interval on SSE streams, matching the stdio transport
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Open sessions beyond this evict the least recently used one
+const MAX_SESSIONS: usize = 256;
+
+/// Builds the `McpServer` for each new session
+pub type ServerFactory = Arc<dyn Fn() -> McpServer + Send + Sync>;
+
+struct Session {
+    server: Mutex<McpServer>,
+    last_used: std::sync::Mutex<Instant>,
+    /// Feeds the session's SSE stream while one is open; dropped with the
+    /// session, which ends the stream
+    stream: Mutex<Option<mpsc::Sender<String>>>,
+}
+
+impl Session {
+    fn touch(&self) {
+        if let Ok(mut last_used) = self.last_used.lock() {
+            *last_used = Instant::now();
+        }
+    }
+
+    fn idle_since(&self) -> Instant {
+        self.last_used.lock().map(|t| *t).unwrap_or_else(|_| Instant::now())
+    }
+}
+
+#[derive(Clone)]
+struct HttpState {
+    factory: ServerFactory,
+    sessions: Arc<RwLock<HashMap<String, Arc<Session>>>>,
+    auth_token: Option<Arc<str>>,
+}
+
+impl HttpState {
+    async fn session(&self, headers: &HeaderMap) -> Result<Arc<Session>, (StatusCode, &'static str)> {
+        let id = headers
+            .get(SESSION_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .ok_or((StatusCode::BAD_REQUEST, "Missing Mcp-Session-Id header"))?;
+        let session = self.sessions.read().await.get(id).cloned();
+        let session = session.ok_or((StatusCode::NOT_FOUND, "Unknown or expired session"))?;
+        session.touch();
+        Ok(session)
+    }
+
+    /// Response rejecting the request, if it lacks the bearer token (when one
+    /// is configured) or is a browser request from a non-local origin (when not)
+    fn rejection(&self, headers: &HeaderMap) -> Option<Response> {
+        match self.check_auth(headers) {
+            Ok(()) => None,
+            Err(StatusCode::UNAUTHORIZED) => Some(
+                (StatusCode::UNAUTHORIZED, [(header::WWW_AUTHENTICATE, "Bearer")]).into_response(),
+            ),
+            Err(status) => Some(status.into_response()),
+        }
+    }
+
+    fn check_auth(&self, headers: &HeaderMap) -> Result<(), StatusCode> {
+        match &self.auth_token {
+            Some(expected) => {
+                let provided = headers
+                    .get(header::AUTHORIZATION)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.strip_prefix("Bearer "))
+                    .unwrap_or("");
+                if token_matches(expected, provided) {
+                    Ok(())
+                } else {
+                    Err(StatusCode::UNAUTHORIZED)
+                }
+            }
+            None if origin_is_local(headers) => Ok(()),
+            None => Err(StatusCode::FORBIDDEN),
+        }
+    }
+}
+
+/// True without an Origin header (non-browser clients) or for a localhost origin,
+/// guarding against DNS rebinding
+fn origin_is_local(headers: &HeaderMap) -> bool {
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return true;
+    };
+    let Ok(origin) = origin.to_str() else {
+        return false;
+    };
+    let host = origin.split_once("://").map_or(origin, |(_, rest)| rest);
+    host.starts_with("[::1]")
+        || matches!(host.split([':', '/']).next(), Some("localhost" | "127.0.0.1"))
+}
+
+/// Streamable HTTP transport for MCP server
+pub struct HttpTransport {
+    addr: SocketAddr,
+    auth_token: Option<String>,
+}
+
+impl HttpTransport {
+    pub fn new(addr: SocketAddr) -> Self {
+        Self {
+            addr,
+            auth_token: None,
+        }
+    }
+
+    /// Require `Authorization: Bearer <token>` on every request
+    pub fn with_auth_token(mut self, token: Option<String>) -> Self {
+        self.auth_token = token.filter(|t| !t.is_empty());
+        self
+    }
+
+    /// Bind the listen address and serve until the listener fails
+    pub async fn run(self, factory: ServerFactory) -> Result<(), io::Error> {
+        let listener = TcpListener::bind(self.addr).await?;
+        info!(
+            "Starting MCP server on http://{}/mcp{}",
+            listener.local_addr()?,
+            if self.auth_token.is_some() { " (bearer auth)" } else { "" }
+        );
+        self.serve(listener, factory).await
+    }
+
+    /// Serve on an already bound listener
+    pub async fn serve(self, listener: TcpListener, factory: ServerFactory) -> Result<(), io::Error> {
+        axum::serve(listener, self.router(factory)).await
+    }
+
+    fn router(self, factory: ServerFactory) -> Router {
+        let state = HttpState {
+            factory,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            auth_token: self.auth_token.map(Arc::from),
+        };
+        Router::new()
+            .route("/mcp", post(handle_post).get(handle_stream).delete(handle_delete))
+            .with_state(state)
+    }
+}
+
+/// POST /mcp — one JSON-RPC message
+async fn handle_post(State(state): State<HttpState>, headers: HeaderMap, body: Bytes) -> Response {
+    if let Some(response) = state.rejection(&headers) {
+        return response;
+    }
+
+    let request: JsonRpcRequest = match serde_json::from_slice(&body) {
+        Ok(r) => r,
+        Err(e) => {
+            warn!("Failed to parse request: {}", e);
+            let error = JsonRpcResponse::error(None, JsonRpcError::parse_error());
+            return (StatusCode::BAD_REQUEST, Json(error)).into_response();
+        }
+    };
+    debug!("Received over HTTP: {}", request.method);
+    let is_notification = request.id.is_none();
+
+    if request.method == "initialize" {
+        let mut server = (state.factory)();
+        let response = server.handle_request(request).await;
+        if let Some(response) = response.as_ref().filter(|r| r.error.is_some()) {
+            return (StatusCode::OK, Json(response.clone())).into_response();
+        }
+        let id = uuid::Uuid::new_v4().to_string();
+        let session = Arc::new(Session {
+            server: Mutex::new(server),
+            last_used: std::sync::Mutex::new(Instant::now()),
+            stream: Mutex::new(None),
+        });
+
+        let mut sessions = state.sessions.write().await;
+        if sessions.len() >= MAX_SESSIONS
+            && let Some(oldest) = sessions
+                .iter()
+                .min_by_key(|(_, s)| s.idle_since())
+                .map(|(id, _)| id.clone())
+        {
+            info!("Evicting idle MCP session {}", oldest);
+            sessions.remove(&oldest);
+        }
+        sessions.insert(id.clone(), session);
+        drop(sessions);
+        info!("MCP HTTP session {} started", id);
+
+        return match response {
+            Some(response) => (StatusCode::OK, [(SESSION_HEADER, id)], Json(response)).into_response(),
+            None => (StatusCode::ACCEPTED, [(SESSION_HEADER, id)]).into_response(),
+        };
+    }
+
+    let session = match state.session(&headers).await {
+        Ok(session) => session,
+        Err(rejection) => return rejection.into_response(),
+    };
+    let response = session.server.lock().await.handle_request(request).await;
+    match response {
+        Some(response) if !is_notification => (StatusCode::OK, Json(response)).into_response(),
+        _ => StatusCode::ACCEPTED.into_response(),
+    }
+}
+
+/// GET /mcp — SSE stream of server-initiated messages for a session
+async fn handle_stream(State(state): State<HttpState>, headers: HeaderMap) -> Response {
+    if let Some(response) = state.rejection(&headers) {
+        return response;
+    }
+    let session = match state.session(&headers).await {
+        Ok(session) => session,
+        Err(rejection) => return rejection.into_response(),
+    };
+
+    let (tx, rx) = mpsc::channel::<String>(32);
+    // A newer stream replaces an older one, which then ends
+    *session.stream.lock().await = Some(tx);
+    drop(session);
+
+    let heartbeat = tokio::time::interval_at(
+        tokio::time::Instant::now() + HEARTBEAT_INTERVAL,
+        HEARTBEAT_INTERVAL,
+    );
+    let stream = futures_util::stream::unfold((rx, heartbeat), |(mut rx, mut heartbeat)| async move {
+        let message = tokio::select! {
+            message = rx.recv() => message?,
+            _ = heartbeat.tick() => "{\"jsonrpc\":\"2.0\",\"method\":\"notifications/ping\"}".to_string(),
+        };
+        let event = Event::default().event("message").data(message);
+        Some((Ok::<_, Infallible>(event), (rx, heartbeat)))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default()).into_response()
+}
+
+/// DELETE /mcp — end a session
+async fn handle_delete(State(state): State<HttpState>, headers: HeaderMap) -> Response {
+    if let Some(response) = state.rejection(&headers) {
+        return response;
+    }
+    let Some(id) = headers.get(SESSION_HEADER).and_then(|v| v.to_str().ok()) else {
+        return (StatusCode::BAD_REQUEST, "Missing Mcp-Session-Id header").into_response();
+    };
+    match state.sessions.write().await.remove(id) {
+        Some(_) => {
+            info!("MCP HTTP session {} ended", id);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cognitive::CognitiveEngine;
+    use tempfile::TempDir;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use vestige_core::Storage;
+
+    struct HttpResponse {
+        status: u16,
+        headers: HashMap<String, String>,
+        body: String,
+    }
+
+    impl HttpResponse {
+        fn json(&self) -> serde_json::Value {
+            serde_json::from_str(&self.body).unwrap()
+        }
+    }
+
+    /// Minimal HTTP/1.1 client: one request per connection
+    async fn send(
+        addr: SocketAddr,
+        method: &str,
+        headers: &[(&str, &str)],
+        body: Option<serde_json::Value>,
+    ) -> HttpResponse {
+        let body = body.map(|b| b.to_string()).unwrap_or_default();
+        let mut request = format!(
+            "{method} /mcp HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\n\
+             Content-Type: application/json\r\nAccept: application/json, text/event-stream\r\n\
+             Content-Length: {}\r\n",
+            body.len()
+        );
+        for (name, value) in headers {
+            request.push_str(&format!("{name}: {value}\r\n"));
+        }
+        request.push_str("\r\n");
+        request.push_str(&body);
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut raw = String::new();
+        stream.read_to_string(&mut raw).await.unwrap();
+
+        let (head, body) = raw.split_once("\r\n\r\n").unwrap();
+        let mut lines = head.lines();
+        let status = lines.next().unwrap().split(' ').nth(1).unwrap().parse().unwrap();
+        let headers = lines
+            .filter_map(|l| l.split_once(": "))
+            .map(|(k, v)| (k.to_ascii_lowercase(), v.to_string()))
+            .collect();
+        HttpResponse {
+            status,
+            headers,
+            body: body.to_string(),
+        }
+    }
+
+    fn rpc(id: u64, method: &str, params: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+    }
+
+    fn init_params() -> serde_json::Value {
+        serde_json::json!({
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "test", "version": "0.0.0" }
+        })
+    }
+
+    async fn start_server(token: Option<&str>) -> (SocketAddr, Arc<Storage>, TempDir) {
+        let dir = TempDir::new().unwrap();
+        let storage = Arc::new(Storage::new(Some(dir.path().join("test.db"))).unwrap());
+        let cognitive = Arc::new(Mutex::new(CognitiveEngine::new()));
+        let factory_storage = Arc::clone(&storage);
+        let factory: ServerFactory = Arc::new(move || {
+            McpServer::new(Arc::clone(&factory_storage), Arc::clone(&cognitive))
+        });
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let transport = HttpTransport::new(addr).with_auth_token(token.map(String::from));
+        tokio::spawn(transport.serve(listener, factory));
+        (addr, storage, dir)
+    }
+
+    #[tokio::test]
+    async fn test_http_session_ingests_into_shared_storage() {
+        let (addr, storage, _dir) = start_server(None).await;
+
+        let init = send(addr, "POST", &[], Some(rpc(1, "initialize", init_params()))).await;
+        assert_eq!(init.status, 200);
+        assert_eq!(init.json()["result"]["serverInfo"]["name"], "vestige");
+        let session = init.headers[SESSION_HEADER].clone();
+        let with_session = [(SESSION_HEADER, session.as_str())];
+
+        let initialized = serde_json::json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert_eq!(send(addr, "POST", &with_session, Some(initialized)).await.status, 202);
+
+        let tools = send(addr, "POST", &with_session, Some(rpc(2, "tools/list", serde_json::json!({})))).await;
+        assert_eq!(tools.json()["id"], 2);
+        assert!(!tools.json()["result"]["tools"].as_array().unwrap().is_empty());
+
+        let ingest = rpc(3, "tools/call", serde_json::json!({
+            "name": "smart_ingest",
+            "arguments": { "content": "HTTP clients share one Vestige instance", "forceCreate": true }
+        }));
+        let ingested = send(addr, "POST", &with_session, Some(ingest)).await;
+        assert_eq!(ingested.status, 200);
+        assert!(ingested.json()["error"].is_null());
+
+        let nodes = storage.get_all_nodes(10, 0).unwrap();
+        assert!(nodes.iter().any(|n| n.content == "HTTP clients share one Vestige instance"));
+
+        // Sessions are independent: a second client must initialize on its own
+        let other = send(addr, "POST", &[], Some(rpc(1, "initialize", init_params()))).await;
+        let other_session = other.headers[SESSION_HEADER].clone();
+        assert_ne!(other_session, session);
+        let listed = send(
+            addr,
+            "POST",
+            &[(SESSION_HEADER, other_session.as_str())],
+            Some(rpc(2, "tools/list", serde_json::json!({}))),
+        )
+        .await;
+        assert_eq!(listed.json()["id"], 2);
+
+        assert_eq!(send(addr, "POST", &[], Some(rpc(4, "ping", serde_json::json!({})))).await.status, 400);
+        assert_eq!(send(addr, "DELETE", &with_session, None).await.status, 204);
+        let expired = send(addr, "POST", &with_session, Some(rpc(5, "ping", serde_json::json!({})))).await;
+        assert_eq!(expired.status, 404);
+    }
+
+    #[tokio::test]
+    async fn test_http_requires_bearer_token() {
+        let (addr, _storage, _dir) = start_server(Some("s3cret")).await;
+        let init = || Some(rpc(1, "initialize", init_params()));
+
+        let missing = send(addr, "POST", &[], init()).await;
+        assert_eq!(missing.status, 401);
+        let wrong = send(addr, "POST", &[("Authorization", "Bearer nope")], init()).await;
+        assert_eq!(wrong.status, 401);
+        let ok = send(addr, "POST", &[("Authorization", "Bearer s3cret")], init()).await;
+        assert_eq!(ok.status, 200);
+    }
+
+    #[test]
+    fn test_origin_check() {
+        let with_origin = |origin: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::ORIGIN, origin.parse().unwrap());
+            origin_is_local(&headers)
+        };
+        assert!(origin_is_local(&HeaderMap::new()));
+        assert!(with_origin("http://localhost:5173"));
+        assert!(with_origin("http://127.0.0.1"));
+        assert!(with_origin("http://[::1]:3928"));
+        assert!(!with_origin("https://evil.example"));
+        assert!(!with_origin("http://localhost.evil.example"));
+    }
+}
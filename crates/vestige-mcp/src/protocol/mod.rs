@@ -1,7 +1,8 @@
 //! MCP Protocol Implementation
 //!
-//! JSON-RPC 2.0 over stdio for the Model Context Protocol.
+//! JSON-RPC 2.0 over stdio or streamable HTTP for the Model Context Protocol.
 
+pub mod http;
 pub mod messages;
 pub mod stdio;
 pub mod types;
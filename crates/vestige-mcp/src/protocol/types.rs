@@ -4,6 +4,7 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use vestige_core::StorageError;
 
 /// MCP Protocol Version
 pub const MCP_VERSION: &str = "2025-11-25";
@@ -77,6 +78,13 @@ pub enum ErrorCode {
     RequestTimeout = -32001,
     ResourceNotFound = -32002,
     ServerNotInitialized = -32003,
+
+    // Storage errors, by StorageError kind
+    EmbeddingUnavailable = -32010,
+    VectorIndexError = -32011,
+    LockPoisoned = -32012,
+    Conflict = -32013,
+    ConfigError = -32014,
 }
 
 impl From<ErrorCode> for i32 {
@@ -141,6 +149,41 @@ impl std::fmt::Display for JsonRpcError {
 
 impl std::error::Error for JsonRpcError {}
 
+/// Distinct codes per storage failure kind, so clients can tell a retryable
+/// conflict or a not-yet-loaded model from bad arguments
+impl From<&StorageError> for JsonRpcError {
+    fn from(err: &StorageError) -> Self {
+        let (code, kind, retryable) = match err {
+            StorageError::NotFound(_) => (ErrorCode::ResourceNotFound, "not_found", false),
+            StorageError::InvalidInput { .. } | StorageError::InvalidTimestamp(_) => {
+                (ErrorCode::InvalidParams, "invalid_input", false)
+            }
+            StorageError::EmbeddingUnavailable(_) => {
+                (ErrorCode::EmbeddingUnavailable, "embedding_unavailable", true)
+            }
+            StorageError::VectorIndex(_) => (ErrorCode::VectorIndexError, "vector_index", false),
+            StorageError::LockPoisoned(_) => (ErrorCode::LockPoisoned, "lock_poisoned", false),
+            StorageError::Conflict(_) => (ErrorCode::Conflict, "conflict", true),
+            StorageError::Duplicate { .. } => (ErrorCode::Conflict, "duplicate", false),
+            StorageError::Config(_) | StorageError::EncryptionDisabled => {
+                (ErrorCode::ConfigError, "config", false)
+            }
+            _ => (ErrorCode::InternalError, "internal", false),
+        };
+        let mut data = serde_json::json!({ "kind": kind, "retryable": retryable });
+        match err {
+            StorageError::InvalidInput { field, .. } => data["field"] = Value::from(*field),
+            StorageError::Duplicate { existing_id } => data["existingId"] = Value::from(existing_id.as_str()),
+            _ => {}
+        }
+        Self {
+            code: code.into(),
+            message: err.to_string(),
+            data: Some(data),
+        }
+    }
+}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -199,4 +242,27 @@ mod tests {
         assert!(response.error.is_some());
         assert_eq!(response.error.unwrap().code, -32601);
     }
+
+    #[test]
+    fn test_storage_errors_map_to_distinct_codes() {
+        let invalid = JsonRpcError::from(&StorageError::InvalidInput {
+            field: "content",
+            reason: "must not be empty".into(),
+        });
+        assert_eq!(invalid.code, -32602);
+        assert_eq!(invalid.data.as_ref().unwrap()["field"], "content");
+
+        let lock = JsonRpcError::from(&StorageError::LockPoisoned("Writer".into()));
+        assert_eq!(lock.code, -32012);
+        assert_eq!(lock.data.as_ref().unwrap()["kind"], "lock_poisoned");
+        assert_eq!(lock.data.as_ref().unwrap()["retryable"], false);
+
+        let embedding = JsonRpcError::from(&StorageError::EmbeddingUnavailable("loading".into()));
+        assert_eq!(embedding.code, -32010);
+        assert_ne!(JsonRpcError::from(&StorageError::Conflict("race".into())).code, lock.code);
+
+        let duplicate = JsonRpcError::from(&StorageError::Duplicate { existing_id: "abc".into() });
+        assert_eq!(duplicate.data.as_ref().unwrap()["existingId"], "abc");
+        assert_eq!(duplicate.data.as_ref().unwrap()["retryable"], false);
+    }
 }
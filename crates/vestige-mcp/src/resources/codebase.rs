@@ -4,7 +4,8 @@
 
 use std::sync::Arc;
 
-use vestige_core::{RecallInput, SearchMode, Storage};
+use vestige_core::codebase::tags::parse_codebase_tag;
+use vestige_core::{QueryExpansionMode, RecallInput, SearchMode, SearchSyntax, Storage};
 
 /// Read a codebase:// resource
 pub async fn read(storage: &Arc<Storage>, uri: &str) -> Result<String, String> {
@@ -52,7 +53,11 @@ async fn read_structure(storage: &Arc<Storage>) -> Result<String, String> {
             limit: 100,
             min_retention: 0.0,
             search_mode: SearchMode::Keyword,
+            syntax: SearchSyntax::Plain,
+            query_expansion: QueryExpansionMode::Auto,
             valid_at: None,
+            namespace: None,
+            exclude_unavailable: false,
         };
 
         for node in storage.recall(input).unwrap_or_default() {
@@ -69,7 +74,7 @@ async fn read_structure(storage: &Arc<Storage>) -> Result<String, String> {
 
     for node in &nodes {
         for tag in &node.tags {
-            if let Some(codebase) = tag.strip_prefix("codebase:") {
+            if let Some(codebase) = parse_codebase_tag(tag) {
                 codebases.insert(codebase.to_string());
             }
         }
@@ -102,7 +107,11 @@ async fn read_patterns(storage: &Arc<Storage>, query: Option<&str>) -> Result<St
         limit: 50,
         min_retention: 0.0,
         search_mode: SearchMode::Keyword,
+        syntax: SearchSyntax::Plain,
+        query_expansion: QueryExpansionMode::Auto,
         valid_at: None,
+        namespace: None,
+        exclude_unavailable: false,
     };
 
     let nodes = storage.recall(input).unwrap_or_default();
@@ -144,7 +153,11 @@ async fn read_decisions(storage: &Arc<Storage>, query: Option<&str>) -> Result<S
         limit: 50,
         min_retention: 0.0,
         search_mode: SearchMode::Keyword,
+        syntax: SearchSyntax::Plain,
+        query_expansion: QueryExpansionMode::Auto,
         valid_at: None,
+        namespace: None,
+        exclude_unavailable: false,
     };
 
     let nodes = storage.recall(input).unwrap_or_default();
@@ -6,6 +6,8 @@ use std::sync::Arc;
 
 use vestige_core::Storage;
 
+use crate::tools::storage_error;
+
 /// Read a memory:// resource
 pub async fn read(storage: &Arc<Storage>, uri: &str) -> Result<String, String> {
     let path = uri.strip_prefix("memory://").unwrap_or("");
@@ -50,7 +52,7 @@ fn parse_query_param(query: Option<&str>, key: &str, default: i32) -> i32 {
 }
 
 async fn read_stats(storage: &Arc<Storage>) -> Result<String, String> {
-    let stats = storage.get_stats().map_err(|e| e.to_string())?;
+    let stats = storage.get_stats().map_err(storage_error)?;
 
     let embedding_coverage = if stats.total_nodes > 0 {
         (stats.nodes_with_embeddings as f64 / stats.total_nodes as f64) * 100.0
@@ -87,7 +89,7 @@ async fn read_stats(storage: &Arc<Storage>) -> Result<String, String> {
 }
 
 async fn read_recent(storage: &Arc<Storage>, limit: i32) -> Result<String, String> {
-    let nodes = storage.get_all_nodes(limit, 0).map_err(|e| e.to_string())?;
+    let nodes = storage.get_all_nodes(limit, 0).map_err(storage_error)?;
 
     let items: Vec<serde_json::Value> = nodes
         .iter()
@@ -117,7 +119,7 @@ async fn read_recent(storage: &Arc<Storage>, limit: i32) -> Result<String, Strin
 
 async fn read_decaying(storage: &Arc<Storage>) -> Result<String, String> {
     // Get nodes with low retention (below 0.5)
-    let all_nodes = storage.get_all_nodes(100, 0).map_err(|e| e.to_string())?;
+    let all_nodes = storage.get_all_nodes(100, 0).map_err(storage_error)?;
 
     let mut decaying: Vec<_> = all_nodes
         .into_iter()
@@ -172,7 +174,7 @@ async fn read_decaying(storage: &Arc<Storage>) -> Result<String, String> {
 }
 
 async fn read_due(storage: &Arc<Storage>) -> Result<String, String> {
-    let nodes = storage.get_review_queue(20).map_err(|e| e.to_string())?;
+    let nodes = storage.get_review_queue(20).map_err(storage_error)?;
 
     let items: Vec<serde_json::Value> = nodes
         .iter()
@@ -203,7 +205,7 @@ async fn read_due(storage: &Arc<Storage>) -> Result<String, String> {
 }
 
 async fn read_intentions(storage: &Arc<Storage>) -> Result<String, String> {
-    let intentions = storage.get_active_intentions().map_err(|e| e.to_string())?;
+    let intentions = storage.get_active_intentions().map_err(storage_error)?;
     let now = chrono::Utc::now();
 
     let items: Vec<serde_json::Value> = intentions
@@ -241,7 +243,7 @@ async fn read_intentions(storage: &Arc<Storage>) -> Result<String, String> {
 }
 
 async fn read_triggered_intentions(storage: &Arc<Storage>) -> Result<String, String> {
-    let overdue = storage.get_overdue_intentions().map_err(|e| e.to_string())?;
+    let overdue = storage.get_overdue_intentions().map_err(storage_error)?;
     let now = chrono::Utc::now();
 
     let items: Vec<serde_json::Value> = overdue
@@ -286,7 +288,7 @@ async fn read_triggered_intentions(storage: &Arc<Storage>) -> Result<String, Str
 }
 
 async fn read_insights(storage: &Arc<Storage>) -> Result<String, String> {
-    let insights = storage.get_insights(50).map_err(|e| e.to_string())?;
+    let insights = storage.get_insights(50).map_err(storage_error)?;
 
     let pending: Vec<_> = insights.iter().filter(|i| i.feedback.is_none()).collect();
     let accepted: Vec<_> = insights.iter().filter(|i| i.feedback.as_deref() == Some("accepted")).collect();
@@ -319,8 +321,8 @@ async fn read_insights(storage: &Arc<Storage>) -> Result<String, String> {
 }
 
 async fn read_consolidation_log(storage: &Arc<Storage>) -> Result<String, String> {
-    let history = storage.get_consolidation_history(20).map_err(|e| e.to_string())?;
-    let last_run = storage.get_last_consolidation().map_err(|e| e.to_string())?;
+    let history = storage.get_consolidation_history(20).map_err(storage_error)?;
+    let last_run = storage.get_last_consolidation().map_err(storage_error)?;
 
     let items: Vec<serde_json::Value> = history
         .iter()
@@ -334,6 +336,9 @@ async fn read_consolidation_log(storage: &Arc<Storage>) -> Result<String, String
                 "connectionsStrengthened": h.connections_strengthened,
                 "connectionsPruned": h.connections_pruned,
                 "insightsGenerated": h.insights_generated,
+                "stepTimings": h.step_timings,
+                "stepErrors": h.step_errors,
+                "skippedReason": h.skipped_reason,
             })
         })
         .collect();
@@ -11,6 +11,7 @@ use tokio::sync::{broadcast, Mutex};
 use tracing::{debug, info, warn};
 
 use crate::cognitive::CognitiveEngine;
+use crate::speculative;
 use vestige_mcp::dashboard::events::VestigeEvent;
 use crate::protocol::messages::{
     CallToolRequest, CallToolResult, InitializeRequest, InitializeResult,
@@ -20,7 +21,18 @@ use crate::protocol::messages::{
 use crate::protocol::types::{JsonRpcError, JsonRpcRequest, JsonRpcResponse, MCP_VERSION};
 use crate::resources;
 use crate::tools;
-use vestige_core::Storage;
+use vestige_core::{CancellationToken, ConsolidationLimits, Storage};
+
+/// Tools that take an optional `namespace` argument, including deprecated aliases
+const NAMESPACED_TOOLS: &[&str] = &[
+    "search", "memory", "codebase", "intention", "smart_ingest", "ingest", "session_checkpoint",
+    "promote_memory", "demote_memory", "health_check", "stats", "system_status", "recall",
+    "semantic_search", "hybrid_search", "get_knowledge", "delete_knowledge", "get_memory_state",
+    "remember_pattern", "remember_decision", "get_codebase_context", "set_intention",
+    "check_intentions", "complete_intention", "snooze_intention", "list_intentions",
+    "memory_timeline", "consolidate", "export", "explore_connections", "session_context",
+    "memory_graph",
+];
 
 /// MCP Server implementation
 pub struct McpServer {
@@ -31,6 +43,10 @@ pub struct McpServer {
     tool_call_count: AtomicU64,
     /// Optional event broadcast channel for dashboard real-time updates.
     event_tx: Option<broadcast::Sender<VestigeEvent>>,
+    /// Default namespace for tools that accept one (None = all memories).
+    namespace: Option<String>,
+    /// Cancelled on shutdown to stop inline consolidation between steps.
+    shutdown: CancellationToken,
 }
 
 impl McpServer {
@@ -42,6 +58,8 @@ impl McpServer {
             initialized: false,
             tool_call_count: AtomicU64::new(0),
             event_tx: None,
+            namespace: None,
+            shutdown: CancellationToken::new(),
         }
     }
 
@@ -57,9 +75,23 @@ impl McpServer {
             initialized: false,
             tool_call_count: AtomicU64::new(0),
             event_tx: Some(event_tx),
+            namespace: None,
+            shutdown: CancellationToken::new(),
         }
     }
 
+    /// Scope namespace-aware tools to `namespace` unless a call names its own.
+    pub fn with_namespace(mut self, namespace: Option<String>) -> Self {
+        self.namespace = namespace;
+        self
+    }
+
+    /// Stop inline consolidation when `shutdown` is cancelled.
+    pub fn with_shutdown(mut self, shutdown: CancellationToken) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
     /// Emit an event to the dashboard (no-op if no event channel).
     fn emit(&self, event: VestigeEvent) {
         if let Some(ref tx) = self.event_tx {
@@ -166,17 +198,17 @@ impl McpServer {
             // ================================================================
             ToolDescription {
                 name: "search".to_string(),
-                description: Some("Unified search tool. Uses hybrid search (keyword + semantic + convex combination fusion) internally. Auto-strengthens memories on access (Testing Effect).".to_string()),
+                description: Some("Unified search tool. Uses hybrid search (keyword + semantic + convex combination fusion) internally. Results get a weak Testing Effect impression; memory(get) applies the full access boost.".to_string()),
                 input_schema: tools::search_unified::schema(),
             },
             ToolDescription {
                 name: "memory".to_string(),
-                description: Some("Unified memory management tool. Actions: 'get' (retrieve full node), 'delete' (remove memory), 'state' (get accessibility state), 'promote' (thumbs up — increases retrieval strength), 'demote' (thumbs down — decreases retrieval strength, does NOT delete), 'edit' (update content in-place, preserves FSRS state).".to_string()),
+                description: Some("Unified memory management tool. Actions: 'get' (retrieve full node), 'delete' (remove memory), 'state' (get accessibility state), 'promote' (thumbs up — increases retrieval strength), 'demote' (thumbs down — decreases retrieval strength, does NOT delete), 'edit' (update content in-place, preserves FSRS state), 'outcome' (report how a task informed by the memory turned out, e.g. success/failure — rewards or penalizes it), 'pin' (keep ranked high and exempt from decay/GC, optionally until a TTL), 'unpin'.".to_string()),
                 input_schema: tools::memory_unified::schema(),
             },
             ToolDescription {
                 name: "codebase".to_string(),
-                description: Some("Unified codebase tool. Actions: 'remember_pattern' (store code pattern), 'remember_decision' (store architectural decision), 'get_context' (retrieve patterns and decisions).".to_string()),
+                description: Some("Unified codebase tool. Actions: 'remember_pattern' (store code pattern), 'remember_decision' (store architectural decision), 'get_context' (retrieve patterns and decisions), 'learn_patterns' (scan a repository and store its recurring patterns).".to_string()),
                 input_schema: tools::codebase_unified::schema(),
             },
             ToolDescription {
@@ -210,12 +242,12 @@ impl McpServer {
             // ================================================================
             ToolDescription {
                 name: "system_status".to_string(),
-                description: Some("Combined system health and statistics. Returns status (healthy/degraded/critical/empty), full stats, FSRS preview, cognitive module health, state distribution, warnings, and recommendations.".to_string()),
+                description: Some("Combined system health and statistics. Returns status (healthy/degraded/critical/empty), full stats, FSRS preview, cognitive module health, state distribution, warnings, and recommendations. action='forecast' instead projects due reviews and average retention per day for the next N days. action='backfill' embeds memories missing embeddings in time-budgeted slices, reporting percent complete and a resumeToken for the next slice. action='retruncate' rebuilds the vector index at another Matryoshka dimension (256/384/512/768).".to_string()),
                 input_schema: tools::maintenance::system_status_schema(),
             },
             ToolDescription {
                 name: "consolidate".to_string(),
-                description: Some("Run FSRS-6 memory consolidation cycle. Applies decay, generates embeddings, and performs maintenance. Use when memories seem stale.".to_string()),
+                description: Some("Run FSRS-6 memory consolidation cycle. Applies decay, generates embeddings, and performs maintenance. Use when memories seem stale. Pass 'step' to run a single step (e.g. dedup) or 'config' to enable/disable steps and tune their parameters.".to_string()),
                 input_schema: tools::maintenance::consolidate_schema(),
             },
             ToolDescription {
@@ -233,6 +265,11 @@ impl McpServer {
                 description: Some("Garbage collect stale memories below retention threshold. Defaults to dry_run=true for safety.".to_string()),
                 input_schema: tools::maintenance::gc_schema(),
             },
+            ToolDescription {
+                name: "source_trust".to_string(),
+                description: Some("Weight search ranking by memory source. Set a trust multiplier (0.5-1.5) for an exact source or a 'prefix*' pattern, remove one, or list the configured patterns.".to_string()),
+                input_schema: tools::maintenance::source_trust_schema(),
+            },
             // ================================================================
             // AUTO-SAVE & DEDUP TOOLS (v1.3+)
             // ================================================================
@@ -256,7 +293,7 @@ impl McpServer {
             },
             ToolDescription {
                 name: "explore_connections".to_string(),
-                description: Some("Graph exploration tool for memory connections. Actions: 'chain' (build reasoning path between memories), 'associations' (find related memories via spreading activation + hippocampal index), 'bridges' (find connecting memories between two nodes).".to_string()),
+                description: Some("Graph exploration tool for memory connections. Actions: 'chain' (build and save a reasoning path between memories over stored connections and vector neighbors), 'associations' (find related memories via spreading activation + hippocampal index), 'bridges' (find connecting memories between two nodes).".to_string()),
                 input_schema: tools::explore::schema(),
             },
             ToolDescription {
@@ -285,7 +322,7 @@ impl McpServer {
             // ================================================================
             ToolDescription {
                 name: "memory_health".to_string(),
-                description: Some("Retention dashboard. Returns avg retention, retention distribution (buckets: 0-20%, 20-40%, etc.), trend (improving/declining/stable), and recommendation. Lightweight alternative to full system_status focused on memory quality.".to_string()),
+                description: Some("Retention dashboard. Returns avg retention, retention distribution (buckets: 0-20%, 20-40%, etc.), trend (improving/declining/stable), recommendation, and storage diagnostics (FTS/embedding/vector index integrity, WAL size, maintenance ages) with per-check ok/warn/error status and remediation. Lightweight alternative to full system_status focused on memory quality.".to_string()),
                 input_schema: tools::health::schema(),
             },
             ToolDescription {
@@ -304,19 +341,29 @@ impl McpServer {
         &self,
         params: Option<serde_json::Value>,
     ) -> Result<serde_json::Value, JsonRpcError> {
-        let request: CallToolRequest = match params {
+        let mut request: CallToolRequest = match params {
             Some(p) => serde_json::from_value(p).map_err(|e| JsonRpcError::invalid_params(&e.to_string()))?,
             None => return Err(JsonRpcError::invalid_params("Missing tool call parameters")),
         };
 
+        // Apply the server's default namespace to namespace-aware tools
+        if let Some(ref ns) = self.namespace
+            && NAMESPACED_TOOLS.contains(&request.name.as_str())
+        {
+            let args = request.arguments.get_or_insert_with(|| serde_json::json!({}));
+            if let Some(obj) = args.as_object_mut() {
+                obj.entry("namespace").or_insert_with(|| serde_json::json!(ns));
+            }
+        }
+
         // Record activity on every tool call (non-blocking)
         if let Ok(mut cog) = self.cognitive.try_lock() {
             cog.activity_tracker.record_activity();
             cog.consolidation_scheduler.record_activity();
         }
 
-        // Save args for event emission (tool dispatch consumes request.arguments)
-        let saved_args = if self.event_tx.is_some() { request.arguments.clone() } else { None };
+        // Save args for event emission and usage history (tool dispatch consumes request.arguments)
+        let saved_args = request.arguments.clone();
 
         let result = match request.name.as_str() {
             // ================================================================
@@ -417,7 +464,8 @@ impl McpServer {
                         let id = args.get("id").cloned().unwrap_or(serde_json::Value::Null);
                         Some(serde_json::json!({
                             "action": "get",
-                            "id": id
+                            "id": id,
+                            "namespace": args.get("namespace"),
                         }))
                     }
                     None => None,
@@ -431,7 +479,8 @@ impl McpServer {
                         let id = args.get("id").cloned().unwrap_or(serde_json::Value::Null);
                         Some(serde_json::json!({
                             "action": "delete",
-                            "id": id
+                            "id": id,
+                            "namespace": args.get("namespace"),
                         }))
                     }
                     None => None,
@@ -445,7 +494,8 @@ impl McpServer {
                         let id = args.get("memory_id").cloned().unwrap_or(serde_json::Value::Null);
                         Some(serde_json::json!({
                             "action": "state",
-                            "id": id
+                            "id": id,
+                            "namespace": args.get("namespace"),
                         }))
                     }
                     None => None,
@@ -538,7 +588,8 @@ impl McpServer {
                         Some(serde_json::json!({
                             "action": "update",
                             "id": id,
-                            "status": "complete"
+                            "status": "complete",
+                            "namespace": args.get("namespace"),
                         }))
                     }
                     None => None,
@@ -555,7 +606,8 @@ impl McpServer {
                             "action": "update",
                             "id": id,
                             "status": "snooze",
-                            "snooze_minutes": minutes
+                            "snooze_minutes": minutes,
+                            "namespace": args.get("namespace"),
                         }))
                     }
                     None => None,
@@ -608,11 +660,15 @@ impl McpServer {
                 self.emit(VestigeEvent::ConsolidationStarted {
                     timestamp: chrono::Utc::now(),
                 });
-                tools::maintenance::execute_consolidate(&self.storage, request.arguments).await
+                let result =
+                    tools::maintenance::execute_consolidate(&self.storage, request.arguments).await;
+                self.cognitive.lock().await.sync_pins(&self.storage);
+                result
             }
             "backup" => tools::maintenance::execute_backup(&self.storage, request.arguments).await,
             "export" => tools::maintenance::execute_export(&self.storage, request.arguments).await,
             "gc" => tools::maintenance::execute_gc(&self.storage, request.arguments).await,
+            "source_trust" => tools::maintenance::execute_source_trust(&self.storage, request.arguments).await,
 
             // ================================================================
             // AUTO-SAVE & DEDUP TOOLS (v1.3+)
@@ -642,7 +698,7 @@ impl McpServer {
             // ================================================================
             // AUTONOMIC TOOLS (v1.9+)
             // ================================================================
-            "memory_health" => tools::health::execute(&self.storage, request.arguments).await,
+            "memory_health" => tools::health::execute(&self.storage, &self.cognitive, request.arguments).await,
             "memory_graph" => tools::graph::execute(&self.storage, request.arguments).await,
 
             name => {
@@ -659,6 +715,10 @@ impl McpServer {
         // ================================================================
         if let Ok(ref content) = result {
             self.emit_tool_event(&request.name, &saved_args, content);
+
+            // Feed speculative retrieval (query, returned memories, time, project)
+            let cog = self.cognitive.lock().await;
+            speculative::record_tool_usage(&self.storage, &cog, &request.name, saved_args.as_ref(), content);
         }
 
         let response = match result {
@@ -673,10 +733,15 @@ impl McpServer {
                 serde_json::to_value(call_result).map_err(|e| JsonRpcError::internal_error(&e.to_string()))
             }
             Err(e) => {
+                // Storage failures arrive as serialized JsonRpcErrors (see tools::storage_error)
+                let error = match serde_json::from_str::<JsonRpcError>(&e) {
+                    Ok(rpc) => serde_json::json!({ "error": rpc.message, "code": rpc.code, "data": rpc.data }),
+                    Err(_) => serde_json::json!({ "error": e }),
+                };
                 let call_result = CallToolResult {
                     content: vec![crate::protocol::messages::ToolResultContent {
                         content_type: "text".to_string(),
-                        text: serde_json::json!({ "error": e }).to_string(),
+                        text: error.to_string(),
                     }],
                     is_error: Some(true),
                 };
@@ -694,13 +759,19 @@ impl McpServer {
         if should_consolidate {
             let storage_clone = Arc::clone(&self.storage);
             let cognitive_clone = Arc::clone(&self.cognitive);
+            let limits = ConsolidationLimits { cancel: Some(self.shutdown.clone()), ..Default::default() };
             tokio::spawn(async move {
                 // Expire labile reconsolidation windows
-                if let Ok(mut cog) = cognitive_clone.try_lock() {
-                    let _expired = cog.reconsolidation.reconsolidate_expired();
-                }
+                let _expired = storage_clone.reconsolidate_expired();
 
-                match storage_clone.run_consolidation() {
+                match storage_clone.run_consolidation_with_limits(&limits, &mut |_| {}) {
+                    Ok(result) if result.skipped.is_some() => {
+                        tracing::debug!(
+                            tool_calls = count,
+                            reason = result.skipped.as_deref().unwrap_or_default(),
+                            "Inline consolidation skipped"
+                        );
+                    }
                     Ok(result) => {
                         tracing::info!(
                             tool_calls = count,
@@ -710,6 +781,7 @@ impl McpServer {
                             duration_ms = result.duration_ms,
                             "Inline consolidation triggered (scheduler)"
                         );
+                        cognitive_clone.lock().await.sync_pins(&storage_clone);
                     }
                     Err(e) => {
                         tracing::warn!("Inline consolidation failed: {}", e);
@@ -831,7 +903,8 @@ impl McpServer {
                 };
                 serde_json::to_value(result).map_err(|e| JsonRpcError::internal_error(&e.to_string()))
             }
-            Err(e) => Err(JsonRpcError::internal_error(&e)),
+            Err(e) => Err(serde_json::from_str::<JsonRpcError>(&e)
+                .unwrap_or_else(|_| JsonRpcError::internal_error(&e))),
         }
     }
 
@@ -1183,8 +1256,8 @@ mod tests {
         let result = response.result.unwrap();
         let tools = result["tools"].as_array().unwrap();
 
-        // v1.9: 21 tools (4 unified + 1 core + 2 temporal + 5 maintenance + 2 auto-save + 3 cognitive + 1 restore + 1 session_context + 2 autonomic)
-        assert_eq!(tools.len(), 21, "Expected exactly 21 tools in v1.9+");
+        // v1.9: 22 tools (4 unified + 1 core + 2 temporal + 6 maintenance + 2 auto-save + 3 cognitive + 1 restore + 1 session_context + 2 autonomic)
+        assert_eq!(tools.len(), 22, "Expected exactly 22 tools in v1.9+");
 
         let tool_names: Vec<&str> = tools
             .iter()
@@ -1218,6 +1291,7 @@ mod tests {
         assert!(tool_names.contains(&"backup"));
         assert!(tool_names.contains(&"export"));
         assert!(tool_names.contains(&"gc"));
+        assert!(tool_names.contains(&"source_trust"));
 
         // Auto-save & dedup tools (v1.3)
         assert!(tool_names.contains(&"importance_score"));
@@ -1400,4 +1474,68 @@ mod tests {
         assert!(response.error.is_some());
         assert_eq!(response.error.unwrap().code, -32602);
     }
+
+    #[tokio::test]
+    async fn test_tools_call_applies_default_namespace() {
+        let (server, _dir) = test_server().await;
+        let mut server = server.with_namespace(Some("work".to_string()));
+
+        let init_request = make_request("initialize", None);
+        server.handle_request(init_request).await;
+
+        for (content, namespace) in [
+            ("Deploys go out on Tuesdays", None),
+            ("Garden shed gets painted on Tuesdays", Some("personal")),
+        ] {
+            let mut arguments = serde_json::json!({ "content": content, "forceCreate": true });
+            if let Some(ns) = namespace {
+                arguments["namespace"] = serde_json::json!(ns);
+            }
+            let request = make_request("tools/call", Some(serde_json::json!({
+                "name": "smart_ingest",
+                "arguments": arguments
+            })));
+            let response = server.handle_request(request).await.unwrap();
+            assert!(response.error.is_none());
+        }
+
+        let nodes = server.storage.get_all_nodes(10, 0).unwrap();
+        let namespace_of = |content: &str| {
+            nodes.iter().find(|n| n.content == content).and_then(|n| n.namespace.clone())
+        };
+        assert_eq!(namespace_of("Deploys go out on Tuesdays").as_deref(), Some("work"));
+        assert_eq!(namespace_of("Garden shed gets painted on Tuesdays").as_deref(), Some("personal"));
+    }
+
+    #[tokio::test]
+    async fn test_default_namespace_guards_memory_tools() {
+        let (server, _dir) = test_server().await;
+        let mut server = server.with_namespace(Some("work".to_string()));
+        server.handle_request(make_request("initialize", None)).await;
+
+        let personal = server
+            .storage
+            .with_namespace("personal")
+            .ingest(vestige_core::IngestInput {
+                content: "Garden shed gets painted on Tuesdays".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        for (name, arguments) in [
+            ("memory", serde_json::json!({ "action": "get", "id": personal.id })),
+            ("memory", serde_json::json!({ "action": "delete", "id": personal.id })),
+            ("get_knowledge", serde_json::json!({ "id": personal.id })),
+            ("delete_knowledge", serde_json::json!({ "id": personal.id })),
+        ] {
+            let request = make_request("tools/call", Some(serde_json::json!({
+                "name": name,
+                "arguments": arguments
+            })));
+            let result = server.handle_request(request).await.unwrap().result.unwrap();
+            assert_eq!(result["isError"], true, "{name} leaked across namespaces");
+            assert!(result["content"][0]["text"].as_str().unwrap().contains("not in namespace 'work'"));
+        }
+        assert!(server.storage.get_node(&personal.id).unwrap().is_some());
+    }
 }
@@ -0,0 +1,262 @@
+//! Speculative retrieval wiring — usage history, predictions, and a warm cache.
+//!
+//! Every tool invocation is recorded (query text, returned memories, time of
+//! day, project) into persisted usage history and the `SpeculativeRetriever`.
+//! `session_context` asks the retriever what will be needed next and pre-runs
+//! the searches that usually lead there; the results sit in a small
+//! `WarmCache` so the next matching `search` skips hybrid search entirely.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+
+use crate::cognitive::CognitiveEngine;
+use vestige_core::codebase::GitAnalyzer;
+use vestige_core::{SearchResult, Storage, UsageRecord};
+
+/// Maximum number of queries held in the warm cache
+const WARM_CACHE_CAPACITY: usize = 16;
+
+/// How long a warm entry stays valid
+const WARM_CACHE_TTL_MINUTES: i64 = 10;
+
+/// Usage events replayed into the retriever at startup
+const USAGE_REPLAY_LIMIT: i32 = 10_000;
+
+/// Pre-fetched search results for one query
+struct WarmEntry {
+    results: Vec<SearchResult>,
+    cached_at: DateTime<Utc>,
+    /// Insertion order, for eviction
+    seq: u64,
+}
+
+/// Hit/miss counters for the warm cache
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WarmCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+impl WarmCacheStats {
+    /// Fraction of eligible searches served from the cache
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+/// Small in-process cache of speculatively pre-fetched search results
+#[derive(Default)]
+pub struct WarmCache {
+    entries: HashMap<String, WarmEntry>,
+    next_seq: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl WarmCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn key(query: &str) -> String {
+        query
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+    }
+
+    /// Whether a fresh entry exists for this query (does not count as a lookup)
+    pub fn contains(&self, query: &str) -> bool {
+        self.entries
+            .get(&Self::key(query))
+            .is_some_and(|e| Utc::now() - e.cached_at < Duration::minutes(WARM_CACHE_TTL_MINUTES))
+    }
+
+    /// Store pre-fetched results, evicting the oldest entry when full
+    pub fn insert(&mut self, query: &str, results: Vec<SearchResult>) {
+        let key = Self::key(query);
+        if !self.entries.contains_key(&key)
+            && self.entries.len() >= WARM_CACHE_CAPACITY
+            && let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.seq)
+                .map(|(k, _)| k.clone())
+        {
+            self.entries.remove(&oldest);
+        }
+        self.next_seq += 1;
+        self.entries.insert(
+            key,
+            WarmEntry {
+                results,
+                cached_at: Utc::now(),
+                seq: self.next_seq,
+            },
+        );
+    }
+
+    /// Take cached results for a query, counting the hit or miss
+    ///
+    /// Entries are single-use: searching strengthens memories and changes
+    /// their scores, so a served entry would be stale for the next search.
+    pub fn take(&mut self, query: &str) -> Option<Vec<SearchResult>> {
+        let fresh = self
+            .entries
+            .remove(&Self::key(query))
+            .filter(|e| Utc::now() - e.cached_at < Duration::minutes(WARM_CACHE_TTL_MINUTES));
+        match fresh {
+            Some(entry) => {
+                self.hits += 1;
+                Some(entry.results)
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn stats(&self) -> WarmCacheStats {
+        WarmCacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.entries.len(),
+        }
+    }
+}
+
+/// Name of the git repository the server runs in, used as the default project tag
+pub fn default_project() -> Option<String> {
+    static PROJECT: OnceLock<Option<String>> = OnceLock::new();
+    PROJECT
+        .get_or_init(|| {
+            let cwd = std::env::current_dir().ok()?;
+            let git = GitAnalyzer::discover(&cwd).ok()?;
+            git.repo_path()
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .clone()
+}
+
+/// Project tag for a tool call: explicit `codebase` args, else the current repo
+pub fn project_for_args(args: Option<&Value>) -> Option<String> {
+    args.and_then(|a| {
+        a.get("codebase")
+            .or_else(|| a.get("context").and_then(|c| c.get("codebase")))
+            .and_then(|v| v.as_str())
+            .map(String::from)
+    })
+    .or_else(default_project)
+}
+
+/// Record a completed tool invocation into usage history and the retriever
+///
+/// Invocations with neither a query nor returned memories teach nothing and
+/// are skipped.
+pub fn record_tool_usage(
+    storage: &Storage,
+    cog: &CognitiveEngine,
+    tool: &str,
+    args: Option<&Value>,
+    result: &Value,
+) {
+    let query = args
+        .and_then(|a| a.get("query"))
+        .and_then(|q| q.as_str())
+        .map(String::from);
+
+    let mut memory_ids: Vec<String> = result
+        .get("results")
+        .and_then(|r| r.as_array())
+        .map(|results| {
+            results
+                .iter()
+                .filter_map(|r| r.get("id").and_then(|id| id.as_str()).map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Some(id) = result
+        .get("nodeId")
+        .or_else(|| result.get("id"))
+        .and_then(|id| id.as_str())
+    {
+        memory_ids.push(id.to_string());
+    }
+
+    if query.is_none() && memory_ids.is_empty() {
+        return;
+    }
+
+    let record = UsageRecord {
+        tool: tool.to_string(),
+        query,
+        memory_ids,
+        project: project_for_args(args),
+        recorded_at: Utc::now(),
+    };
+    if let Err(e) = storage.record_usage(&record) {
+        tracing::warn!("Failed to record tool usage: {}", e);
+    }
+    feed_retriever(cog, &record);
+}
+
+/// Replay persisted usage history into the retriever (oldest first)
+pub fn replay_usage_history(storage: &Storage, cog: &CognitiveEngine) -> usize {
+    let history = storage.get_usage_history(USAGE_REPLAY_LIMIT).unwrap_or_default();
+    for record in history.iter().rev() {
+        feed_retriever(cog, record);
+    }
+    history.len()
+}
+
+fn feed_retriever(cog: &CognitiveEngine, record: &UsageRecord) {
+    for memory_id in &record.memory_ids {
+        cog.speculative_retriever.record_access_at(
+            memory_id,
+            record.query.as_deref(),
+            record.project.as_deref(),
+            record.recorded_at,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warm_cache_hit_and_miss() {
+        let mut cache = WarmCache::new();
+        cache.insert("Standup  Notes", vec![]);
+        assert!(cache.contains("standup notes"));
+
+        assert!(cache.take("standup notes").is_some());
+        assert!(cache.take("standup notes").is_none(), "entries are single-use");
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+        assert!((stats.hit_rate() - 0.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_warm_cache_evicts_oldest() {
+        let mut cache = WarmCache::new();
+        for i in 0..=WARM_CACHE_CAPACITY {
+            cache.insert(&format!("query {i}"), vec![]);
+        }
+        assert_eq!(cache.stats().entries, WARM_CACHE_CAPACITY);
+        assert!(!cache.contains("query 0"));
+    }
+}
@@ -1,7 +1,8 @@
 //! Memory Changelog Tool
 //!
 //! View audit trail of memory changes.
-//! Per-memory mode: state transitions for a single memory.
+//! Per-memory mode: state transitions and content revisions (with diffs)
+//! for a single memory.
 //! System-wide mode: consolidations + recent state transitions.
 
 use chrono::{DateTime, Utc};
@@ -13,6 +14,8 @@ use uuid::Uuid;
 
 use vestige_core::Storage;
 
+use super::storage_error;
+
 /// Input schema for memory_changelog tool
 pub fn schema() -> Value {
     serde_json::json!({
@@ -92,13 +95,33 @@ fn execute_per_memory(
     // Get the memory for context
     let node = storage
         .get_node(memory_id)
-        .map_err(|e| e.to_string())?
+        .map_err(storage_error)?
         .ok_or_else(|| format!("Memory '{}' not found.", memory_id))?;
 
     // Get state transitions
     let transitions = storage
         .get_state_transitions(memory_id, limit)
-        .map_err(|e| e.to_string())?;
+        .map_err(storage_error)?;
+
+    // Content revisions, newest first, with a diff to what replaced each
+    let revisions = storage
+        .get_node_revisions(memory_id, limit)
+        .map_err(storage_error)?;
+    let formatted_revisions: Vec<Value> = revisions
+        .iter()
+        .map(|r| {
+            let mut revision = serde_json::json!({
+                "changedAt": r.changed_at.to_rfc3339(),
+                "changeSource": r.change_source,
+                "diff": r.diff,
+            });
+            // Without a diff, the replaced content is the only record of the change
+            if r.diff.is_none() {
+                revision["oldContent"] = serde_json::json!(r.old_content);
+            }
+            revision
+        })
+        .collect();
 
     let formatted_transitions: Vec<Value> = transitions
         .iter()
@@ -122,6 +145,8 @@ fn execute_per_memory(
         "currentRetention": node.retention_strength,
         "totalTransitions": formatted_transitions.len(),
         "transitions": formatted_transitions,
+        "totalRevisions": formatted_revisions.len(),
+        "revisions": formatted_revisions,
     }))
 }
 
@@ -133,12 +158,12 @@ fn execute_system_wide(
     // Get consolidation history
     let consolidations = storage
         .get_consolidation_history(limit)
-        .map_err(|e| e.to_string())?;
+        .map_err(storage_error)?;
 
     // Get recent state transitions across all memories
     let transitions = storage
         .get_recent_state_transitions(limit)
-        .map_err(|e| e.to_string())?;
+        .map_err(storage_error)?;
 
     // Build unified event list
     let mut events: Vec<(DateTime<Utc>, Value)> = Vec::new();
@@ -212,6 +237,9 @@ mod tests {
                 tags: vec![],
                 valid_from: None,
                 valid_until: None,
+                namespace: None,
+                auto_tag: false,
+                on_duplicate: Default::default(),
             })
             .unwrap();
         node.id
@@ -310,4 +338,24 @@ mod tests {
         assert_eq!(value["totalTransitions"], 0);
         assert!(value["transitions"].as_array().unwrap().is_empty());
     }
+
+    #[tokio::test]
+    async fn test_changelog_per_memory_revisions_with_diff() {
+        let (storage, _dir) = test_storage().await;
+        let id = ingest_test_memory(&storage).await;
+        storage
+            .update_node_content(&id, "Changelog test memory, revised")
+            .unwrap();
+
+        let args = serde_json::json!({ "memory_id": id });
+        let value = execute(&storage, Some(args)).await.unwrap();
+        assert_eq!(value["totalRevisions"], 1);
+        let revision = &value["revisions"][0];
+        assert_eq!(revision["changeSource"], "update");
+        assert_eq!(
+            revision["diff"],
+            "@@ -1,1 +1,1 @@\n-Changelog test memory\n+Changelog test memory, revised\n"
+        );
+        assert!(revision.get("oldContent").is_none());
+    }
 }
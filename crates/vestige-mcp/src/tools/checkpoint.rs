@@ -106,6 +106,9 @@ pub async fn execute(
             tags: item.tags.unwrap_or_default(),
             valid_from: None,
             valid_until: None,
+            namespace: None,
+            auto_tag: false,
+            on_duplicate: Default::default(),
         };
 
         #[cfg(all(feature = "embeddings", feature = "vector-search"))]
@@ -8,6 +8,7 @@ use serde_json::Value;
 use std::sync::Arc;
 
 
+use vestige_core::codebase::tags::{codebase_tag, CODEBASE_TAG};
 use vestige_core::{IngestInput, Storage};
 
 /// Input schema for remember_pattern tool
@@ -140,9 +141,9 @@ pub async fn execute_pattern(
     }
 
     // Build tags
-    let mut tags = vec!["pattern".to_string(), "codebase".to_string()];
+    let mut tags = vec!["pattern".to_string(), CODEBASE_TAG.to_string()];
     if let Some(ref codebase) = args.codebase {
-        tags.push(format!("codebase:{}", codebase));
+        tags.push(codebase_tag(codebase));
     }
 
     let input = IngestInput {
@@ -154,6 +155,9 @@ pub async fn execute_pattern(
         tags,
         valid_from: None,
         valid_until: None,
+        namespace: None,
+        auto_tag: false,
+        on_duplicate: Default::default(),
     };
 
     let node = storage.ingest(input).map_err(|e| e.to_string())?;
@@ -206,9 +210,9 @@ pub async fn execute_decision(
     }
 
     // Build tags
-    let mut tags = vec!["decision".to_string(), "architecture".to_string(), "codebase".to_string()];
+    let mut tags = vec!["decision".to_string(), "architecture".to_string(), CODEBASE_TAG.to_string()];
     if let Some(ref codebase) = args.codebase {
-        tags.push(format!("codebase:{}", codebase));
+        tags.push(codebase_tag(codebase));
     }
 
     let input = IngestInput {
@@ -220,6 +224,9 @@ pub async fn execute_decision(
         tags,
         valid_from: None,
         valid_until: None,
+        namespace: None,
+        auto_tag: false,
+        on_duplicate: Default::default(),
     };
 
     let node = storage.ingest(input).map_err(|e| e.to_string())?;
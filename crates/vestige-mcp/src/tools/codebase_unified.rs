@@ -5,10 +5,17 @@
 
 use serde::Deserialize;
 use serde_json::Value;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::cognitive::CognitiveEngine;
+use super::storage_error;
+use vestige_core::codebase::tags::{
+    self, commit_tag, file_tag, provenance_tags, COMMIT_PREFIX, DECISION_NODE_TYPE, FILE_PREFIX,
+    PATTERN_NODE_TYPE,
+};
+use vestige_core::codebase::{CodebaseMemory, CodebaseNode, GitAnalyzer, PatternAnalysisConfig};
 use vestige_core::{IngestInput, Storage};
 
 /// Input schema for the unified codebase tool
@@ -18,8 +25,8 @@ pub fn schema() -> Value {
         "properties": {
             "action": {
                 "type": "string",
-                "enum": ["remember_pattern", "remember_decision", "get_context"],
-                "description": "Action to perform: 'remember_pattern' stores a code pattern, 'remember_decision' stores an architectural decision, 'get_context' retrieves patterns and decisions for a codebase"
+                "enum": ["remember_pattern", "remember_decision", "get_context", "learn_patterns"],
+                "description": "Action to perform: 'remember_pattern' stores a code pattern, 'remember_decision' stores an architectural decision, 'get_context' retrieves patterns and decisions for a codebase, 'learn_patterns' scans a repository and stores its recurring patterns"
             },
             // remember_pattern fields
             "name": {
@@ -54,11 +61,28 @@ pub fn schema() -> Value {
                 "type": "string",
                 "description": "Codebase/project identifier (e.g., 'vestige-tauri')"
             },
+            "commit": {
+                "type": "string",
+                "description": "Git commit SHA this relates to. When recording, defaults to HEAD if the server runs inside a git repo; for get_context, filters to memories linked to this commit"
+            },
             // get_context fields
+            "file": {
+                "type": "string",
+                "description": "Only return patterns and decisions that touch this file (for get_context)"
+            },
             "limit": {
                 "type": "integer",
-                "description": "Maximum items per category (default: 10, for get_context)",
+                "description": "Maximum items per category (default: 10, for get_context), or number of patterns to keep (for learn_patterns)",
                 "default": 10
+            },
+            // learn_patterns fields
+            "path": {
+                "type": "string",
+                "description": "Root of the git repository to scan (for learn_patterns, defaults to the server's working directory)"
+            },
+            "namespace": {
+                "type": "string",
+                "description": "Namespace to store new memories in and to read context from (default: no namespace when recording, all namespaces when reading)"
             }
         },
         "required": ["action"]
@@ -79,8 +103,75 @@ struct CodebaseArgs {
     // Shared fields
     files: Option<Vec<String>>,
     codebase: Option<String>,
+    commit: Option<String>,
     // Context fields
+    file: Option<String>,
     limit: Option<i32>,
+    // Learn fields
+    path: Option<String>,
+    namespace: Option<String>,
+}
+
+impl CodebaseArgs {
+    /// The namespace the action is confined to, if any
+    fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref().filter(|ns| !ns.trim().is_empty())
+    }
+
+    /// Patterns or decisions, from the action's namespace if it has one
+    fn nodes_by_type_and_tag(
+        &self,
+        storage: &Storage,
+        node_type: &str,
+        tag_filter: Option<&str>,
+        limit: i32,
+    ) -> vestige_core::Result<Vec<vestige_core::KnowledgeNode>> {
+        match self.namespace() {
+            Some(ns) => storage
+                .with_namespace(ns)
+                .get_nodes_by_type_and_tag(node_type, tag_filter, limit),
+            None => storage.get_nodes_by_type_and_tag(node_type, tag_filter, limit),
+        }
+    }
+}
+
+/// Resolve commit and files for a new memory from the server's git repo (if any)
+///
+/// Explicit files win. An explicit commit is resolved to its full SHA and
+/// contributes the files it touched; otherwise HEAD and the files being worked
+/// on are used.
+fn resolve_provenance(args: &CodebaseArgs) -> (Option<String>, Vec<String>) {
+    let files = args.files.clone().unwrap_or_default();
+    let git = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| GitAnalyzer::discover(&cwd).ok())
+        .and_then(|git| match &args.commit {
+            Some(commit) => git.commit_provenance(commit).ok(),
+            None => git.head_provenance().ok(),
+        });
+
+    match git {
+        Some((sha, changed)) => {
+            let files = if files.is_empty() {
+                changed.iter().map(|p| p.to_string_lossy().to_string()).collect()
+            } else {
+                files
+            };
+            (Some(sha), files)
+        }
+        None => (args.commit.clone(), files),
+    }
+}
+
+/// Whether a node matches the file/commit filters of a get_context call
+fn matches_provenance(tags: &[String], file: Option<&str>, commit: Option<&str>) -> bool {
+    let file_ok = file.is_none_or(|f| tags.iter().any(|t| t.strip_prefix(FILE_PREFIX) == Some(f)));
+    let commit_ok = commit.is_none_or(|c| {
+        tags.iter()
+            .filter_map(|t| t.strip_prefix(COMMIT_PREFIX))
+            .any(|sha| sha.starts_with(c) || c.starts_with(sha))
+    });
+    file_ok && commit_ok
 }
 
 /// Execute the unified codebase tool
@@ -98,8 +189,9 @@ pub async fn execute(
         "remember_pattern" => execute_remember_pattern(storage, cognitive, &args).await,
         "remember_decision" => execute_remember_decision(storage, cognitive, &args).await,
         "get_context" => execute_get_context(storage, cognitive, &args).await,
+        "learn_patterns" => execute_learn_patterns(storage, &args).await,
         _ => Err(format!(
-            "Invalid action '{}'. Must be one of: remember_pattern, remember_decision, get_context",
+            "Invalid action '{}'. Must be one of: remember_pattern, remember_decision, get_context, learn_patterns",
             args.action
         )),
     }
@@ -137,23 +229,28 @@ async fn execute_remember_pattern(
     }
 
     // Build tags
-    let mut tags = vec!["pattern".to_string(), "codebase".to_string()];
-    if let Some(ref codebase) = args.codebase {
-        tags.push(format!("codebase:{}", codebase));
-    }
+    let mut tags = vec![PATTERN_NODE_TYPE.to_string()];
+    tags.extend(provenance_tags(
+        args.codebase.as_deref(),
+        args.commit.as_deref(),
+        args.files.as_deref().unwrap_or_default(),
+    ));
 
     let input = IngestInput {
         content,
-        node_type: "pattern".to_string(),
+        node_type: PATTERN_NODE_TYPE.to_string(),
         source: args.codebase.clone(),
         sentiment_score: 0.0,
         sentiment_magnitude: 0.0,
         tags,
         valid_from: None,
         valid_until: None,
+        namespace: args.namespace().map(str::to_string),
+        auto_tag: false,
+        on_duplicate: Default::default(),
     };
 
-    let node = storage.ingest(input).map_err(|e| e.to_string())?;
+    let node = storage.ingest(input).map_err(storage_error)?;
     let node_id = node.id.clone();
 
     // ====================================================================
@@ -218,37 +315,42 @@ async fn execute_remember_decision(
         }
     }
 
-    if let Some(ref files) = args.files
-        && !files.is_empty()
-    {
+    let (commit, files) = resolve_provenance(args);
+
+    if !files.is_empty() {
         content.push_str("\n\n## Affected Files:\n");
-        for f in files {
+        for f in &files {
             content.push_str(&format!("- {}\n", f));
         }
     }
 
-    // Build tags
-    let mut tags = vec![
-        "decision".to_string(),
-        "architecture".to_string(),
-        "codebase".to_string(),
-    ];
-    if let Some(ref codebase) = args.codebase {
-        tags.push(format!("codebase:{}", codebase));
+    if let Some(ref sha) = commit {
+        content.push_str(&format!("\n\nCommit: {}", sha));
     }
 
+    // Build tags
+    let mut tags = vec![DECISION_NODE_TYPE.to_string(), "architecture".to_string()];
+    tags.extend(provenance_tags(
+        args.codebase.as_deref(),
+        commit.as_deref(),
+        &files,
+    ));
+
     let input = IngestInput {
         content,
-        node_type: "decision".to_string(),
+        node_type: DECISION_NODE_TYPE.to_string(),
         source: args.codebase.clone(),
         sentiment_score: 0.0,
         sentiment_magnitude: 0.0,
         tags,
         valid_from: None,
         valid_until: None,
+        namespace: args.namespace().map(str::to_string),
+        auto_tag: false,
+        on_duplicate: Default::default(),
     };
 
-    let node = storage.ingest(input).map_err(|e| e.to_string())?;
+    let node = storage.ingest(input).map_err(storage_error)?;
     let node_id = node.id.clone();
 
     // ====================================================================
@@ -272,6 +374,8 @@ async fn execute_remember_decision(
         "action": "remember_decision",
         "success": true,
         "nodeId": node_id,
+        "commit": commit,
+        "files": files,
         "message": "Architectural decision remembered successfully",
     }))
 }
@@ -284,21 +388,35 @@ async fn execute_get_context(
 ) -> Result<Value, String> {
     let limit = args.limit.unwrap_or(10).clamp(1, 50);
 
-    // Build tag filter for codebase
-    let tag_filter = args
-        .codebase
-        .as_ref()
-        .map(|cb| format!("codebase:{}", cb));
-
-    // Query patterns by node_type and tag
-    let patterns = storage
-        .get_nodes_by_type_and_tag("pattern", tag_filter.as_deref(), limit)
-        .unwrap_or_default();
+    // Build tag filter: a file or commit is more selective than the codebase
+    let file = args.file.as_deref();
+    let commit = args.commit.as_deref();
+    let codebase_tag = args.codebase.as_deref().map(tags::codebase_tag);
+    let tag_filter = match (file, commit) {
+        (Some(f), _) => Some(file_tag(f)),
+        (None, Some(c)) => Some(commit_tag(c)),
+        (None, None) => codebase_tag.clone(),
+    };
+    let filtered = file.is_some() || commit.is_some();
+    // Over-fetch when post-filtering so exact matches aren't crowded out by the LIKE prefix match
+    let fetch_limit = if filtered { limit * 5 } else { limit };
+
+    let select = |node_type: &str| -> Vec<vestige_core::KnowledgeNode> {
+        args.nodes_by_type_and_tag(storage, node_type, tag_filter.as_deref(), fetch_limit)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|n| {
+                !filtered
+                    || (matches_provenance(&n.tags, file, commit)
+                        && codebase_tag.as_ref().is_none_or(|t| n.tags.contains(t)))
+            })
+            .take(limit as usize)
+            .collect()
+    };
 
-    // Query decisions by node_type and tag
-    let decisions = storage
-        .get_nodes_by_type_and_tag("decision", tag_filter.as_deref(), limit)
-        .unwrap_or_default();
+    // Query patterns and decisions by node_type and tag
+    let patterns = select(PATTERN_NODE_TYPE);
+    let decisions = select(DECISION_NODE_TYPE);
 
     let formatted_patterns: Vec<Value> = patterns
         .iter()
@@ -353,6 +471,8 @@ async fn execute_get_context(
     Ok(serde_json::json!({
         "action": "get_context",
         "codebase": args.codebase,
+        "file": args.file,
+        "commit": args.commit,
         "patterns": {
             "count": formatted_patterns.len(),
             "items": formatted_patterns,
@@ -365,6 +485,174 @@ async fn execute_get_context(
     }))
 }
 
+/// Scan a repository for recurring code patterns and store the top ones
+///
+/// Each learned pattern is keyed by a `pattern-id:<id>` tag, so re-running
+/// refreshes the existing memory instead of creating a duplicate.
+async fn execute_learn_patterns(
+    storage: &Arc<Storage>,
+    args: &CodebaseArgs,
+) -> Result<Value, String> {
+    let root = match args.path {
+        Some(ref p) => PathBuf::from(p),
+        None => std::env::current_dir().map_err(|e| e.to_string())?,
+    };
+    let codebase = args.codebase.clone().unwrap_or_else(|| {
+        root.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "default".to_string())
+    });
+    let config = PatternAnalysisConfig {
+        codebase: Some(codebase.clone()),
+        top_n: args.limit.unwrap_or(10).clamp(1, 50) as usize,
+        ..Default::default()
+    };
+
+    // CodebaseMemory takes blocking locks, so keep it off the async runtime
+    let scan_root = root.clone();
+    let (result, patterns) = tokio::task::spawn_blocking(move || {
+        let memory = CodebaseMemory::new(scan_root.clone()).map_err(|e| e.to_string())?;
+        let result = memory
+            .analyze_patterns(&scan_root, &config)
+            .map_err(|e| e.to_string())?;
+        let patterns = memory
+            .get_nodes_by_type("code_pattern")
+            .map_err(|e| e.to_string())?;
+        Ok::<_, String>((result, patterns))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let codebase_tag = tags::codebase_tag(&codebase);
+    let mut learned = Vec::new();
+    let (mut created, mut updated) = (0usize, 0usize);
+
+    for node in patterns {
+        let CodebaseNode::CodePattern(pattern) = node else {
+            continue;
+        };
+        let files: Vec<String> = pattern
+            .example_files
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+
+        let mut content = format!(
+            "# Code Pattern: {}\n\n{}\n\nWhen to use: {}\n\nSeen in {} files.",
+            pattern.name, pattern.description, pattern.when_to_use, pattern.usage_count
+        );
+        if !files.is_empty() {
+            content.push_str("\n\n## Files:\n");
+            for f in &files {
+                content.push_str(&format!("- {}\n", f));
+            }
+        }
+
+        let id_tag = format!("pattern-id:{}", pattern.id);
+        let mut tags = vec![PATTERN_NODE_TYPE.to_string()];
+        tags.extend(provenance_tags(Some(&codebase), None, &files));
+        tags.push(id_tag.clone());
+
+        let existing = args
+            .nodes_by_type_and_tag(storage, PATTERN_NODE_TYPE, Some(&id_tag), 50)
+            .map_err(storage_error)?
+            .into_iter()
+            .find(|n| n.tags.contains(&id_tag) && n.tags.contains(&codebase_tag));
+
+        let (node_id, decision) = match existing {
+            Some(node) => {
+                storage
+                    .update_node_content_from(&node.id, &content, "codebase:learn_patterns")
+                    .map_err(storage_error)?;
+                // File tags follow the latest scan; other tags on the memory are kept
+                let mut refreshed = tags;
+                for tag in node.tags {
+                    if !tag.starts_with(FILE_PREFIX) && !refreshed.contains(&tag) {
+                        refreshed.push(tag);
+                    }
+                }
+                storage
+                    .bulk_update(
+                        std::slice::from_ref(&node.id),
+                        &vestige_core::BulkAction::Retag { tags: refreshed },
+                    )
+                    .map_err(storage_error)?;
+                updated += 1;
+                (node.id, "update".to_string())
+            }
+            None => {
+                let input = IngestInput {
+                    content,
+                    node_type: PATTERN_NODE_TYPE.to_string(),
+                    source: Some(codebase.clone()),
+                    sentiment_score: 0.0,
+                    sentiment_magnitude: 0.0,
+                    tags,
+                    valid_from: None,
+                    valid_until: None,
+                    namespace: args.namespace().map(str::to_string),
+                    auto_tag: false,
+                    // Deduplicated by pattern-id and codebase tags above
+                    on_duplicate: vestige_core::DuplicatePolicy::CreateAnyway,
+                };
+                let (node_id, decision) = ingest_learned_pattern(storage, input)?;
+                if decision == "create" {
+                    created += 1;
+                } else {
+                    updated += 1;
+                }
+                (node_id, decision)
+            }
+        };
+
+        learned.push(serde_json::json!({
+            "nodeId": node_id,
+            "patternId": pattern.id,
+            "name": pattern.name,
+            "occurrences": pattern.usage_count,
+            "files": files,
+            "decision": decision,
+        }));
+    }
+
+    Ok(serde_json::json!({
+        "action": "learn_patterns",
+        "success": true,
+        "codebase": codebase,
+        "path": root.to_string_lossy(),
+        "patternsDetected": result.patterns_detected,
+        "created": created,
+        "updated": updated,
+        "durationMs": result.duration_ms,
+        "patterns": learned,
+    }))
+}
+
+/// Store a newly learned pattern, preferring smart_ingest so near-identical
+/// memories are merged rather than duplicated
+fn ingest_learned_pattern(
+    storage: &Arc<Storage>,
+    input: IngestInput,
+) -> Result<(String, String), String> {
+    #[cfg(all(feature = "embeddings", feature = "vector-search"))]
+    {
+        let fallback_input = input.clone();
+        match storage.smart_ingest(input) {
+            Ok(result) => Ok((result.node.id, result.decision)),
+            Err(_) => {
+                let node = storage.ingest(fallback_input).map_err(storage_error)?;
+                Ok((node.id, "create".to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(all(feature = "embeddings", feature = "vector-search")))]
+    {
+        let node = storage.ingest(input).map_err(storage_error)?;
+        Ok((node.id, "create".to_string()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -389,6 +677,10 @@ mod tests {
             .as_array()
             .unwrap()
             .contains(&serde_json::json!("get_context")));
+        assert!(action_enum
+            .as_array()
+            .unwrap()
+            .contains(&serde_json::json!("learn_patterns")));
     }
 
     // === INTEGRATION TESTS ===
@@ -571,6 +863,127 @@ mod tests {
         assert!(value["patterns"]["count"].as_u64().unwrap() >= 1);
     }
 
+    #[tokio::test]
+    async fn test_get_context_filters_by_file_and_commit() {
+        let (storage, _dir) = test_storage().await;
+        let cog = test_cognitive();
+        for (decision, file, commit) in [
+            ("Use WAL mode", "src/storage/sqlite.rs", "abc12345"),
+            ("Split reader and writer", "src/storage/sqlite.rs.orig", "def67890"),
+            ("Use axum", "src/dashboard/mod.rs", "abc12345"),
+        ] {
+            let args = serde_json::json!({
+                "action": "remember_decision",
+                "decision": decision,
+                "rationale": "Because",
+                "files": [file],
+                "commit": commit,
+                "codebase": "vestige"
+            });
+            execute(&storage, &cog, Some(args)).await.unwrap();
+        }
+
+        let by_file = execute(&storage, &cog, Some(serde_json::json!({
+            "action": "get_context",
+            "file": "src/storage/sqlite.rs"
+        })))
+        .await
+        .unwrap();
+        assert_eq!(by_file["decisions"]["count"], 1);
+        assert!(by_file["decisions"]["items"][0]["content"]
+            .as_str()
+            .unwrap()
+            .contains("Use WAL mode"));
+
+        let by_commit = execute(&storage, &cog, Some(serde_json::json!({
+            "action": "get_context",
+            "commit": "abc1",
+            "codebase": "vestige"
+        })))
+        .await
+        .unwrap();
+        assert_eq!(by_commit["decisions"]["count"], 2);
+    }
+
+    #[test]
+    fn test_matches_provenance() {
+        let tags = provenance_tags(None, Some("abc12345"), &["src/lib.rs".to_string()]);
+        assert!(matches_provenance(&tags, Some("src/lib.rs"), None));
+        assert!(!matches_provenance(&tags, Some("src/lib"), None));
+        assert!(matches_provenance(&tags, None, Some("abc1")));
+        assert!(!matches_provenance(&tags, Some("src/lib.rs"), Some("ffff")));
+    }
+
+    /// Two Rust files sharing the thiserror error style, in a fresh git repo
+    fn sample_repo() -> tempfile::TempDir {
+        let dir = tempfile::TempDir::new().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+        let src = dir.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        for name in ["storage", "network"] {
+            std::fs::write(
+                src.join(format!("{}.rs", name)),
+                format!(
+                    "//! Custom error types\nuse thiserror::Error;\n\n#[derive(Debug, Error)]\npub enum {}Error {{\n    #[error(\"failed\")]\n    Failed,\n}}\n",
+                    name
+                ),
+            )
+            .unwrap();
+        }
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_learn_patterns_updates_on_rerun() {
+        let (storage, _dir) = test_storage().await;
+        let repo = sample_repo();
+        let args = serde_json::json!({
+            "action": "learn_patterns",
+            "path": repo.path().to_string_lossy(),
+            "codebase": "sample"
+        });
+
+        let first = execute(&storage, &test_cognitive(), Some(args.clone()))
+            .await
+            .unwrap();
+        assert_eq!(first["action"], "learn_patterns");
+        assert!(first["created"].as_u64().unwrap() >= 1);
+        assert_eq!(first["updated"], 0);
+        let learned = first["patterns"].as_array().unwrap();
+        let error_pattern = learned
+            .iter()
+            .find(|p| p["patternId"] == "builtin-rust-error-handling")
+            .expect("error handling pattern should be learned");
+        assert_eq!(error_pattern["occurrences"], 2);
+
+        // The pattern moves: one file drops out and another picks it up
+        let src = repo.path().join("src");
+        std::fs::rename(src.join("network.rs"), src.join("cache.rs")).unwrap();
+        let second = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        assert_eq!(second["created"], 0);
+        assert_eq!(second["updated"], first["created"]);
+
+        let stored = storage
+            .get_nodes_by_type_and_tag("pattern", Some("pattern-id:builtin-rust-error-handling"), 10)
+            .unwrap();
+        assert_eq!(stored.len(), 1);
+        assert!(stored[0].tags.contains(&"file:src/storage.rs".to_string()));
+        assert!(stored[0].tags.contains(&"file:src/cache.rs".to_string()));
+        assert!(!stored[0].tags.contains(&"file:src/network.rs".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_learn_patterns_requires_git_repo() {
+        let (storage, _dir) = test_storage().await;
+        let not_a_repo = tempfile::TempDir::new().unwrap();
+        let args = serde_json::json!({
+            "action": "learn_patterns",
+            "path": not_a_repo.path().to_string_lossy()
+        });
+        let result = execute(&storage, &test_cognitive(), Some(args)).await;
+        assert!(result.is_err());
+    }
+
     #[tokio::test]
     async fn test_get_context_no_codebase() {
         let (storage, _dir) = test_storage().await;
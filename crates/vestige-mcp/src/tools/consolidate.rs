@@ -17,6 +17,15 @@ pub fn schema() -> Value {
 
 pub async fn execute(storage: &Arc<Storage>) -> Result<Value, String> {
     let result = storage.run_consolidation().map_err(|e| e.to_string())?;
+    let message = match &result.skipped {
+        Some(reason) => format!("Consolidation skipped: {}", reason),
+        None => format!(
+            "Consolidation complete: {} nodes processed, {} embeddings generated, {}ms",
+            result.nodes_processed,
+            result.embeddings_generated,
+            result.duration_ms
+        ),
+    };
 
     Ok(serde_json::json!({
         "success": true,
@@ -26,11 +35,7 @@ pub async fn execute(storage: &Arc<Storage>) -> Result<Value, String> {
         "decayApplied": result.decay_applied,
         "embeddingsGenerated": result.embeddings_generated,
         "durationMs": result.duration_ms,
-        "message": format!(
-            "Consolidation complete: {} nodes processed, {} embeddings generated, {}ms",
-            result.nodes_processed,
-            result.embeddings_generated,
-            result.duration_ms
-        ),
+        "skipped": result.skipped,
+        "message": message,
     }))
 }
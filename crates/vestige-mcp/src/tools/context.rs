@@ -8,7 +8,7 @@ use serde_json::Value;
 use std::sync::Arc;
 
 
-use vestige_core::{RecallInput, SearchMode, Storage};
+use vestige_core::{QueryExpansionMode, RecallInput, SearchMode, SearchSyntax, Storage};
 
 /// Input schema for match_context tool
 pub fn schema() -> Value {
@@ -81,7 +81,11 @@ pub async fn execute(
         limit: limit * 2, // Get more, then filter
         min_retention: 0.0,
         search_mode: SearchMode::Hybrid,
+        syntax: SearchSyntax::Plain,
+        query_expansion: QueryExpansionMode::Auto,
         valid_at: None,
+        namespace: None,
+        exclude_unavailable: false,
     };
     let candidates = storage.recall(recall_input)
         .map_err(|e| e.to_string())?;
@@ -6,7 +6,7 @@ use tokio::sync::Mutex;
 
 use chrono::Utc;
 use crate::cognitive::CognitiveEngine;
-use vestige_core::{DreamHistoryRecord, Storage};
+use vestige_core::Storage;
 
 pub fn schema() -> serde_json::Value {
     serde_json::json!({
@@ -87,12 +87,20 @@ pub async fn execute(
         }
     }).collect();
 
-    let cog = cognitive.lock().await;
+    let mut guard = cognitive.lock().await;
+    let cog = &mut *guard;
     let pre_dream_count = cog.dreamer.get_connections().len();
     let dream_result = cog.dreamer.dream(&dream_memories).await;
     let insights = cog.dreamer.synthesize_insights(&dream_memories);
     let all_connections = cog.dreamer.get_connections();
-    drop(cog);
+    drop(guard);
+
+    // Four-phase cycle (flashbulb memories lead replay and REM emotional
+    // processing), run outside the cognitive lock; it persists its insights,
+    // creative connections and dream history itself
+    let phased = storage
+        .run_dream_cycle()
+        .map_err(|e| format!("Dream cycle failed: {}", e))?;
 
     // v1.9.0: Persist only NEW connections from this dream (skip accumulated ones)
     let new_connections = &all_connections[pre_dream_count..];
@@ -129,29 +137,6 @@ pub async fn execute(
         }
     }
 
-    // Persist dream history (non-fatal on failure — dream still happened)
-    {
-        let record = DreamHistoryRecord {
-            dreamed_at: Utc::now(),
-            duration_ms: dream_result.duration_ms as i64,
-            memories_replayed: dream_memories.len() as i32,
-            connections_found: dream_result.new_connections_found as i32,
-            insights_generated: dream_result.insights_generated.len() as i32,
-            memories_strengthened: dream_result.memories_strengthened as i32,
-            memories_compressed: dream_result.memories_compressed as i32,
-            phase_nrem1_ms: None,
-            phase_nrem3_ms: None,
-            phase_rem_ms: None,
-            phase_integration_ms: None,
-            summaries_generated: None,
-            emotional_memories_processed: None,
-            creative_connections_found: None,
-        };
-        if let Err(e) = storage.save_dream_history(&record) {
-            tracing::warn!("Failed to persist dream history: {}", e);
-        }
-    }
-
     // v1.9.0: Clear waking tags after dream processes them
     let tags_cleared = storage.clear_waking_tags().unwrap_or(0);
 
@@ -160,6 +145,7 @@ pub async fn execute(
         "memoriesReplayed": dream_memories.len(),
         "wakingTagsProcessed": tagged_target,
         "wakingTagsCleared": tags_cleared,
+        "emotionalMemoriesProcessed": phased.emotional_processed,
         "insights": insights.iter().map(|i| serde_json::json!({
             "insight_type": format!("{:?}", i.insight_type),
             "insight": i.insight,
@@ -206,6 +192,9 @@ mod tests {
                 tags: vec!["dream-test".to_string()],
                 valid_from: None,
                 valid_until: None,
+                namespace: None,
+                auto_tag: false,
+                on_duplicate: Default::default(),
             })
             .unwrap();
         }
@@ -309,4 +298,17 @@ mod tests {
             assert!(last.is_some(), "Dream should have been persisted to database");
         }
     }
+
+    #[tokio::test]
+    async fn test_dream_processes_flashbulb_memories() {
+        let (storage, _dir) = test_storage().await;
+        ingest_n_memories(&storage, 6).await;
+        let flash = storage.get_all_nodes(1, 0).unwrap().remove(0);
+        storage.record_emotional_encoding(&flash.id, -0.7, true, true).unwrap();
+
+        let value = execute(&storage, &test_cognitive(), None).await.unwrap();
+        assert_eq!(value["status"], "dreamed");
+        assert_eq!(value["wakingTagsProcessed"], 1);
+        assert_eq!(value["emotionalMemoriesProcessed"], 1);
+    }
 }
@@ -1,11 +1,16 @@
 //! Explore connections tool — Graph exploration, chain building, bridge discovery.
 //! v1.5.0: Wires MemoryChainBuilder + ActivationNetwork + HippocampalIndex.
+//! Chains are built over the persisted connection graph and saved to storage.
 
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::cognitive::CognitiveEngine;
-use vestige_core::Storage;
+use super::{check_namespace, in_namespace, storage_error};
+use vestige_core::{Storage, StorageError};
+
+/// Default hop limit when building a chain
+const DEFAULT_MAX_HOPS: usize = 5;
 
 pub fn schema() -> serde_json::Value {
     serde_json::json!({
@@ -28,6 +33,17 @@ pub fn schema() -> serde_json::Value {
                 "type": "integer",
                 "description": "Maximum results (default: 10)",
                 "default": 10
+            },
+            "max_hops": {
+                "type": "integer",
+                "description": "Maximum hops for 'chain' (default: 5)",
+                "default": 5,
+                "minimum": 1,
+                "maximum": 10
+            },
+            "namespace": {
+                "type": "string",
+                "description": "Only explore memories in this namespace; ids from other namespaces are rejected (default: all namespaces)"
             }
         },
         "required": ["action", "from"]
@@ -35,7 +51,7 @@ pub fn schema() -> serde_json::Value {
 }
 
 pub async fn execute(
-    _storage: &Arc<Storage>,
+    storage: &Arc<Storage>,
     cognitive: &Arc<Mutex<CognitiveEngine>>,
     args: Option<serde_json::Value>,
 ) -> Result<serde_json::Value, String> {
@@ -44,40 +60,28 @@ pub async fn execute(
     let from = args.get("from").and_then(|v| v.as_str()).ok_or("Missing 'from'")?;
     let to = args.get("to").and_then(|v| v.as_str());
     let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
+    let namespace = args
+        .get("namespace")
+        .and_then(|v| v.as_str())
+        .filter(|ns| !ns.trim().is_empty());
+    check_namespace(storage, from, namespace)?;
+    if let Some(to_id) = to {
+        check_namespace(storage, to_id, namespace)?;
+    }
+
+    if action == "chain" {
+        let to_id = to.ok_or("'to' is required for chain action")?;
+        let max_hops = args
+            .get("max_hops")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_MAX_HOPS as u64)
+            .clamp(1, 10) as usize;
+        return execute_chain(storage, from, to_id, max_hops, namespace);
+    }
 
     let cog = cognitive.lock().await;
 
     match action {
-        "chain" => {
-            let to_id = to.ok_or("'to' is required for chain action")?;
-            match cog.chain_builder.build_chain(from, to_id) {
-                Some(chain) => {
-                    Ok(serde_json::json!({
-                        "action": "chain",
-                        "from": from,
-                        "to": to_id,
-                        "steps": chain.steps.iter().map(|s| serde_json::json!({
-                            "memory_id": s.memory_id,
-                            "memory_preview": s.memory_preview,
-                            "connection_type": format!("{:?}", s.connection_type),
-                            "connection_strength": s.connection_strength,
-                            "reasoning": s.reasoning,
-                        })).collect::<Vec<_>>(),
-                        "confidence": chain.confidence,
-                        "total_hops": chain.total_hops,
-                    }))
-                }
-                None => {
-                    Ok(serde_json::json!({
-                        "action": "chain",
-                        "from": from,
-                        "to": to_id,
-                        "steps": [],
-                        "message": "No chain found between these memories"
-                    }))
-                }
-            }
-        }
         "associations" => {
             let activation_assocs = cog.activation_network.get_associations(from);
             let hippocampal_assocs = cog.hippocampal_index.get_associations(from, 2)
@@ -85,6 +89,15 @@ pub async fn execute(
 
             let mut all_associations: Vec<serde_json::Value> = Vec::new();
 
+            let activation_assocs: Vec<_> = activation_assocs
+                .into_iter()
+                .filter(|a| in_namespace(storage, &a.memory_id, namespace))
+                .collect();
+            let hippocampal_assocs: Vec<_> = hippocampal_assocs
+                .into_iter()
+                .filter(|m| in_namespace(storage, &m.index.memory_id, namespace))
+                .collect();
+
             for assoc in activation_assocs.iter().take(limit) {
                 all_associations.push(serde_json::json!({
                     "memory_id": assoc.memory_id,
@@ -114,7 +127,11 @@ pub async fn execute(
         "bridges" => {
             let to_id = to.ok_or("'to' is required for bridges action")?;
             let bridges = cog.chain_builder.find_bridge_memories(from, to_id);
-            let limited: Vec<_> = bridges.iter().take(limit).collect();
+            let limited: Vec<_> = bridges
+                .iter()
+                .filter(|id| in_namespace(storage, id, namespace))
+                .take(limit)
+                .collect();
             Ok(serde_json::json!({
                 "action": "bridges",
                 "from": from,
@@ -127,6 +144,74 @@ pub async fn execute(
     }
 }
 
+/// Build a reasoning chain from storage, save it, and describe each hop
+fn execute_chain(
+    storage: &Storage,
+    from: &str,
+    to_id: &str,
+    max_hops: usize,
+    namespace: Option<&str>,
+) -> Result<serde_json::Value, String> {
+    let no_path = |reason: String| {
+        serde_json::json!({
+            "action": "chain",
+            "from": from,
+            "to": to_id,
+            "found": false,
+            "maxHops": max_hops,
+            "reason": reason,
+            "steps": [],
+        })
+    };
+
+    let chain = match storage.build_reasoning_chain(from, to_id, max_hops) {
+        Ok(Some(chain)) => chain,
+        Ok(None) => {
+            return Ok(no_path(format!("No path found within max_hops ({})", max_hops)));
+        }
+        Err(StorageError::NotFound(id)) => {
+            return Ok(no_path(format!("Memory not found: {}", id)));
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+    if !chain.steps.iter().all(|step| in_namespace(storage, &step.memory_id, namespace)) {
+        return Ok(no_path("The only path leaves the namespace".to_string()));
+    }
+    let chain_id = storage.save_reasoning_chain(&chain).map_err(storage_error)?;
+
+    // Each step's connection leads to the following step
+    let hops: Vec<_> = chain
+        .steps
+        .windows(2)
+        .map(|pair| serde_json::json!({
+            "from": pair[0].memory_id,
+            "to": pair[1].memory_id,
+            "connection_type": format!("{:?}", pair[0].connection_type),
+            "connection_strength": pair[0].connection_strength,
+            "reason": pair[1].reasoning,
+        }))
+        .collect();
+
+    Ok(serde_json::json!({
+        "action": "chain",
+        "chainId": chain_id,
+        "from": from,
+        "to": to_id,
+        "found": true,
+        "steps": chain.steps.iter().map(|s| serde_json::json!({
+            "memory_id": s.memory_id,
+            "memory_preview": s.memory_preview,
+            "connection_type": format!("{:?}", s.connection_type),
+            "connection_strength": s.connection_strength,
+            "reasoning": s.reasoning,
+        })).collect::<Vec<_>>(),
+        "hops": hops,
+        "confidence": chain.confidence,
+        "total_hops": chain.total_hops,
+        "explanation": chain.explanation,
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -274,4 +359,42 @@ mod tests {
         let result = execute(&storage, &test_cognitive(), Some(args)).await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_chain_is_built_from_stored_connections_and_saved() {
+        let (storage, _dir) = test_storage().await;
+        let ids: Vec<String> = ["Slow queries", "Missing index", "New migration"]
+            .iter()
+            .map(|content| storage.ingest(vestige_core::IngestInput {
+                content: content.to_string(),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            }).unwrap().id)
+            .collect();
+        let now = chrono::Utc::now();
+        for pair in ids.windows(2) {
+            storage.save_connection(&vestige_core::ConnectionRecord {
+                source_id: pair[0].clone(),
+                target_id: pair[1].clone(),
+                strength: 0.9,
+                link_type: "causal".to_string(),
+                created_at: now,
+                last_activated: now,
+                activation_count: 0,
+            }).unwrap();
+        }
+
+        let args = serde_json::json!({ "action": "chain", "from": ids[0], "to": ids[2] });
+        let value = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        assert_eq!(value["found"], true);
+        assert_eq!(value["hops"].as_array().unwrap().len(), 2);
+        assert_eq!(value["hops"][0]["connection_type"], "Causal");
+        let chain_id = value["chainId"].as_str().unwrap();
+        assert!(storage.get_chain(chain_id).unwrap().is_some());
+
+        let args = serde_json::json!({ "action": "chain", "from": ids[0], "to": ids[2], "max_hops": 1 });
+        let value = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        assert_eq!(value["found"], false);
+        assert_eq!(value["reason"], "No path found within max_hops (1)");
+    }
 }
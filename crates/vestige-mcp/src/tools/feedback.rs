@@ -15,7 +15,7 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::cognitive::CognitiveEngine;
-use vestige_core::{Modification, OutcomeType, Storage};
+use vestige_core::{FeedbackOptions, Modification, OutcomeType, Storage};
 
 /// Input schema for promote_memory tool
 pub fn promote_schema() -> Value {
@@ -78,12 +78,15 @@ pub async fn execute_promote(
     let before = storage.get_node(&args.id).map_err(|e| e.to_string())?
         .ok_or_else(|| format!("Node not found: {}", args.id))?;
 
-    let node = storage.promote_memory(&args.id).map_err(|e| e.to_string())?;
+    let options = FeedbackOptions { reason: args.reason.clone(), ..Default::default() };
+    let node = storage
+        .promote_memory_with(&args.id, &options)
+        .map_err(|e| e.to_string())?;
 
     // ====================================================================
     // COGNITIVE FEEDBACK PIPELINE (promote)
     // ====================================================================
-    if let Ok(mut cog) = cognitive.try_lock() {
+    if let Ok(cog) = cognitive.try_lock() {
         // 5A. Reward signal — record positive outcome
         cog.reward_signal.record_outcome(&args.id, OutcomeType::Helpful);
 
@@ -91,8 +94,8 @@ pub async fn execute_promote(
         cog.importance_tracker.on_retrieved(&args.id, true);
 
         // 5C. Reconsolidation — boost retrieval if memory is labile
-        if cog.reconsolidation.is_labile(&args.id) {
-            cog.reconsolidation.apply_modification(
+        if storage.is_labile(&args.id) {
+            storage.apply_reconsolidation(
                 &args.id,
                 Modification::StrengthenConnection {
                     target_memory_id: args.id.clone(),
@@ -148,12 +151,15 @@ pub async fn execute_demote(
     let before = storage.get_node(&args.id).map_err(|e| e.to_string())?
         .ok_or_else(|| format!("Node not found: {}", args.id))?;
 
-    let node = storage.demote_memory(&args.id).map_err(|e| e.to_string())?;
+    let options = FeedbackOptions { reason: args.reason.clone(), ..Default::default() };
+    let node = storage
+        .demote_memory_with(&args.id, &options)
+        .map_err(|e| e.to_string())?;
 
     // ====================================================================
     // COGNITIVE FEEDBACK PIPELINE (demote)
     // ====================================================================
-    if let Ok(mut cog) = cognitive.try_lock() {
+    if let Ok(cog) = cognitive.try_lock() {
         // 5A. Reward signal — record negative outcome
         cog.reward_signal.record_outcome(&args.id, OutcomeType::NotHelpful);
 
@@ -161,8 +167,8 @@ pub async fn execute_demote(
         cog.importance_tracker.on_retrieved(&args.id, false);
 
         // 5C. Reconsolidation — weaken if memory is labile
-        if cog.reconsolidation.is_labile(&args.id) {
-            cog.reconsolidation.apply_modification(
+        if storage.is_labile(&args.id) {
+            storage.apply_reconsolidation(
                 &args.id,
                 Modification::AddContext {
                     context: "User reported this memory was wrong/unhelpful".to_string(),
@@ -306,6 +312,9 @@ mod tests {
                 tags: vec![],
                 valid_from: None,
                 valid_until: None,
+                namespace: None,
+                auto_tag: false,
+                on_duplicate: Default::default(),
             })
             .unwrap();
         node.id
@@ -546,6 +555,9 @@ mod tests {
                 tags: vec![],
                 valid_from: None,
                 valid_until: None,
+                namespace: None,
+                auto_tag: false,
+                on_duplicate: Default::default(),
             })
             .unwrap();
         let node_id = node.id.clone();
@@ -2,7 +2,9 @@
 //! v1.9.0: Computes Fruchterman-Reingold layout server-side.
 
 use std::sync::Arc;
-use vestige_core::Storage;
+use vestige_core::{GraphFilter, RecallInput, SearchMode, Storage};
+
+use super::check_namespace;
 
 pub fn schema() -> serde_json::Value {
     serde_json::json!({
@@ -28,6 +30,10 @@ pub fn schema() -> serde_json::Value {
                 "description": "Maximum number of nodes to include (default: 50)",
                 "default": 50,
                 "maximum": 200
+            },
+            "namespace": {
+                "type": "string",
+                "description": "Only include memories in this namespace; a center_id from another namespace is rejected (default: all namespaces)"
             }
         }
     })
@@ -137,27 +143,48 @@ pub async fn execute(
         .unwrap_or(50)
         .min(200) as usize;
 
+    let namespace = args.as_ref()
+        .and_then(|a| a.get("namespace"))
+        .and_then(|v| v.as_str())
+        .filter(|ns| !ns.trim().is_empty());
+
     // Determine center node
     let center_id = if let Some(id) = args.as_ref().and_then(|a| a.get("center_id")).and_then(|v| v.as_str()) {
+        check_namespace(storage, id, namespace)?;
         id.to_string()
     } else if let Some(query) = args.as_ref().and_then(|a| a.get("query")).and_then(|v| v.as_str()) {
         // Search for center node
-        let results = storage.search(query, 1)
-            .map_err(|e| format!("Search failed: {}", e))?;
+        let results = match namespace {
+            Some(ns) => storage.with_namespace(ns).recall_without_strengthen(RecallInput {
+                query: query.to_string(),
+                limit: 1,
+                search_mode: SearchMode::Keyword,
+                ..Default::default()
+            }),
+            None => storage.search(query, 1),
+        }
+        .map_err(|e| format!("Search failed: {}", e))?;
         results.first()
             .map(|n| n.id.clone())
             .ok_or_else(|| "No memories found matching query".to_string())?
     } else {
         // Default: use the most recent memory
-        let recent = storage.get_all_nodes(1, 0)
-            .map_err(|e| format!("Failed to get recent node: {}", e))?;
+        let recent = match namespace {
+            Some(ns) => storage.with_namespace(ns).get_all_nodes(1, 0),
+            None => storage.get_all_nodes(1, 0),
+        }
+        .map_err(|e| format!("Failed to get recent node: {}", e))?;
         recent.first()
             .map(|n| n.id.clone())
             .ok_or_else(|| "No memories in database".to_string())?
     };
 
     // Get subgraph
-    let (nodes, edges) = storage.get_memory_subgraph(&center_id, depth, max_nodes)
+    let filter = GraphFilter {
+        namespace: namespace.map(str::to_string),
+        ..Default::default()
+    };
+    let (nodes, edges) = storage.get_memory_subgraph_filtered(&center_id, depth, max_nodes, &filter)
         .map_err(|e| format!("Failed to get subgraph: {}", e))?;
 
     if nodes.is_empty() || !nodes.iter().any(|n| n.id == center_id) {
@@ -302,6 +329,9 @@ mod tests {
             tags: vec!["test".to_string()],
             valid_from: None,
             valid_until: None,
+            namespace: None,
+            auto_tag: false,
+            on_duplicate: Default::default(),
         }).unwrap();
 
         let args = serde_json::json!({ "center_id": node.id });
@@ -327,6 +357,9 @@ mod tests {
             tags: vec!["science".to_string()],
             valid_from: None,
             valid_until: None,
+            namespace: None,
+            auto_tag: false,
+            on_duplicate: Default::default(),
         }).unwrap();
 
         let args = serde_json::json!({ "query": "quantum" });
@@ -348,6 +381,9 @@ mod tests {
             tags: vec![],
             valid_from: None,
             valid_until: None,
+            namespace: None,
+            auto_tag: false,
+            on_duplicate: Default::default(),
         }).unwrap();
 
         let args = serde_json::json!({ "center_id": node.id });
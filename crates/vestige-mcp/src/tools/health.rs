@@ -2,19 +2,40 @@
 //! v1.9.0: Lightweight alternative to full system_status focused on memory health.
 
 use std::sync::Arc;
+use tokio::sync::Mutex;
 use vestige_core::Storage;
 
+use crate::cognitive::CognitiveEngine;
+
 pub fn schema() -> serde_json::Value {
     serde_json::json!({
         "type": "object",
-        "properties": {}
+        "properties": {
+            "repair": {
+                "type": "boolean",
+                "description": "Delete orphaned embeddings and re-index missing vectors before reporting (default: false)",
+                "default": false
+            }
+        }
     })
 }
 
 pub async fn execute(
     storage: &Arc<Storage>,
-    _args: Option<serde_json::Value>,
+    cognitive: &Arc<Mutex<CognitiveEngine>>,
+    args: Option<serde_json::Value>,
 ) -> Result<serde_json::Value, String> {
+    let repair = args
+        .as_ref()
+        .and_then(|a| a.get("repair"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let repaired = if repair {
+        Some(storage.repair().map_err(|e| format!("Failed to repair storage: {}", e))?)
+    } else {
+        None
+    };
+
     // Average retention
     let avg_retention = storage.get_avg_retention()
         .map_err(|e| format!("Failed to get avg retention: {}", e))?;
@@ -39,10 +60,7 @@ pub async fn execute(
     let below_50 = storage.count_memories_below_retention(0.5).unwrap_or(0);
 
     // Retention target
-    let retention_target: f64 = std::env::var("VESTIGE_RETENTION_TARGET")
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .unwrap_or(0.8);
+    let retention_target = storage.retention_policy().target;
 
     let meets_target = avg_retention >= retention_target;
 
@@ -57,6 +75,15 @@ pub async fn execute(
         "Poor memory health. Urgent: run consolidation, then GC stale memories below 0.3."
     };
 
+    // Speculative warm cache effectiveness
+    let cache = cognitive.lock().await.warm_cache.stats();
+
+    // Storage integrity and maintenance checks
+    let diagnostics = storage.diagnostics()
+        .map_err(|e| format!("Failed to run storage diagnostics: {}", e))?;
+    let consistency = storage.diagnose()
+        .map_err(|e| format!("Failed to diagnose storage: {}", e))?;
+
     Ok(serde_json::json!({
         "avgRetention": format!("{:.1}%", avg_retention * 100.0),
         "avgRetentionRaw": avg_retention,
@@ -68,6 +95,17 @@ pub async fn execute(
         "memoriesBelow30pct": below_30,
         "memoriesBelow50pct": below_50,
         "recommendation": recommendation,
+        "speculativeCache": {
+            "hits": cache.hits,
+            "misses": cache.misses,
+            "hitRate": cache.hit_rate(),
+            "entries": cache.entries,
+        },
+        "diagnostics": diagnostics,
+        "consistency": consistency,
+        "consistent": consistency.is_consistent(),
+        "repaired": repaired,
+        "metrics": storage.metrics_snapshot(),
     }))
 }
 
@@ -76,6 +114,10 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    fn test_cognitive() -> Arc<Mutex<CognitiveEngine>> {
+        Arc::new(Mutex::new(CognitiveEngine::new()))
+    }
+
     async fn test_storage() -> (Arc<Storage>, TempDir) {
         let dir = TempDir::new().unwrap();
         let storage = Storage::new(Some(dir.path().join("test.db"))).unwrap();
@@ -91,12 +133,30 @@ mod tests {
     #[tokio::test]
     async fn test_health_empty_database() {
         let (storage, _dir) = test_storage().await;
-        let result = execute(&storage, None).await;
+        let result = execute(&storage, &test_cognitive(), None).await;
         assert!(result.is_ok());
         let value = result.unwrap();
         assert_eq!(value["totalMemories"], 0);
         assert!(value["avgRetention"].is_string());
         assert!(value["recommendation"].is_string());
+        assert_eq!(value["speculativeCache"]["hitRate"], 0.0);
+        assert_eq!(value["diagnostics"]["status"], "ok");
+        assert!(value["diagnostics"]["checks"].as_array().unwrap().iter()
+            .any(|c| c["name"] == "fts_integrity" && c["status"] == "ok"));
+        assert_eq!(value["consistent"], true);
+        assert_eq!(value["consistency"]["orphanedEmbeddings"], 0);
+        assert!(value["consistency"]["poisonedLocks"].as_array().unwrap().is_empty());
+        assert_eq!(value["consistency"]["mutationsSinceConsolidation"], 0);
+        assert!(value["repaired"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_health_repair_reports_fixes() {
+        let (storage, _dir) = test_storage().await;
+        let args = serde_json::json!({ "repair": true });
+        let value = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        assert_eq!(value["repaired"]["orphanedEmbeddingsDeleted"], 0);
+        assert_eq!(value["consistent"], true);
     }
 
     #[tokio::test]
@@ -113,15 +173,19 @@ mod tests {
                 tags: vec!["test".to_string()],
                 valid_from: None,
                 valid_until: None,
+                namespace: None,
+                auto_tag: false,
+                on_duplicate: Default::default(),
             }).unwrap();
         }
 
-        let result = execute(&storage, None).await;
+        let result = execute(&storage, &test_cognitive(), None).await;
         assert!(result.is_ok());
         let value = result.unwrap();
         assert_eq!(value["totalMemories"], 5);
         assert!(value["distribution"].is_array());
         assert!(value["meetsTarget"].is_boolean());
+        assert_eq!(value["metrics"]["nodesIngested"], 5);
     }
 
     #[tokio::test]
@@ -136,9 +200,12 @@ mod tests {
             tags: vec![],
             valid_from: None,
             valid_until: None,
+            namespace: None,
+            auto_tag: false,
+            on_duplicate: Default::default(),
         }).unwrap();
 
-        let result = execute(&storage, None).await.unwrap();
+        let result = execute(&storage, &test_cognitive(), None).await.unwrap();
         let dist = result["distribution"].as_array().unwrap();
         // Should have at least one bucket with data
         assert!(!dist.is_empty());
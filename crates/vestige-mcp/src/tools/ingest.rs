@@ -14,7 +14,8 @@ use tokio::sync::Mutex;
 
 use crate::cognitive::CognitiveEngine;
 use vestige_core::{
-    ContentType, ImportanceContext, ImportanceEvent, ImportanceEventType, IngestInput, Storage,
+    ContentType, DuplicatePolicy, ImportanceContext, ImportanceEvent, ImportanceEventType, IngestInput,
+    IngestOutcome, Storage,
 };
 
 /// Input schema for ingest tool
@@ -28,7 +29,7 @@ pub fn schema() -> Value {
             },
             "node_type": {
                 "type": "string",
-                "description": "Type of knowledge: fact, concept, event, person, place, note, pattern, decision",
+                "description": "Type of knowledge: fact, concept, procedure, event, relationship, quote, code, question, insight, person, place, note, pattern, decision, bug_fix",
                 "default": "fact"
             },
             "tags": {
@@ -39,6 +40,12 @@ pub fn schema() -> Value {
             "source": {
                 "type": "string",
                 "description": "Source or reference for this knowledge"
+            },
+            "on_duplicate": {
+                "type": "string",
+                "enum": ["returnExisting", "createAnyway", "error"],
+                "description": "What to do when the content exactly matches an existing memory of the same type: return that memory (default, safe for retries), create another one anyway, or fail",
+                "default": "returnExisting"
             }
         },
         "required": ["content"]
@@ -52,6 +59,8 @@ struct IngestArgs {
     node_type: Option<String>,
     tags: Option<Vec<String>>,
     source: Option<String>,
+    #[serde(alias = "on_duplicate")]
+    on_duplicate: Option<DuplicatePolicy>,
 }
 
 pub async fn execute(
@@ -69,8 +78,12 @@ pub async fn execute(
         return Err("Content cannot be empty".to_string());
     }
 
-    if args.content.len() > 1_000_000 {
-        return Err("Content too large (max 1MB)".to_string());
+    if args.content.len() > storage.max_content_bytes() {
+        return Err(format!(
+            "Content too large ({} bytes, max {}). Split it into several smaller memories.",
+            args.content.len(),
+            storage.max_content_bytes()
+        ));
     }
 
     // ====================================================================
@@ -118,6 +131,9 @@ pub async fn execute(
         tags,
         valid_from: None,
         valid_until: None,
+        namespace: None,
+        auto_tag: false,
+        on_duplicate: args.on_duplicate.unwrap_or_default(),
     };
 
     // ====================================================================
@@ -125,9 +141,11 @@ pub async fn execute(
     // ====================================================================
 
     // Route through smart_ingest when embeddings are available to prevent duplicates.
-    // Falls back to raw ingest only when embeddings aren't ready.
+    // Falls back to plain ingest when embeddings aren't ready, and uses it
+    // directly when the caller picked another duplicate policy, so exact
+    // duplicates are always handled per `on_duplicate`.
     #[cfg(all(feature = "embeddings", feature = "vector-search"))]
-    {
+    if input.on_duplicate == DuplicatePolicy::ReturnExisting {
         let fallback_input = input.clone();
         match storage.smart_ingest(input) {
             Ok(result) => {
@@ -136,9 +154,9 @@ pub async fn execute(
                 let node_type = result.node.node_type.clone();
                 let has_embedding = result.node.has_embedding.unwrap_or(false);
 
-                run_post_ingest(cognitive, &node_id, &node_content, &node_type, importance_composite);
+                run_post_ingest(storage, cognitive, &node_id, &node_content, &node_type, importance_composite);
 
-                Ok(serde_json::json!({
+                return Ok(serde_json::json!({
                     "success": true,
                     "nodeId": node_id,
                     "decision": result.decision,
@@ -148,61 +166,79 @@ pub async fn execute(
                     "reason": result.reason,
                     "isNovel": is_novel,
                     "embeddingStrategy": embedding_strategy,
-                }))
+                }));
             }
             Err(_) => {
-                let node = storage.ingest(fallback_input).map_err(|e| e.to_string())?;
-                let node_id = node.id.clone();
-                let node_content = node.content.clone();
-                let node_type = node.node_type.clone();
-                let has_embedding = node.has_embedding.unwrap_or(false);
-
-                run_post_ingest(cognitive, &node_id, &node_content, &node_type, importance_composite);
-
-                Ok(serde_json::json!({
-                    "success": true,
-                    "nodeId": node_id,
-                    "decision": "create",
-                    "message": format!("Knowledge ingested successfully. Node ID: {}", node_id),
-                    "hasEmbedding": has_embedding,
-                    "isNovel": is_novel,
-                    "embeddingStrategy": embedding_strategy,
-                }))
+                let outcome = storage.ingest_with_outcome(fallback_input).map_err(|e| e.to_string())?;
+                return Ok(outcome_response(
+                    storage,
+                    cognitive,
+                    outcome,
+                    importance_composite,
+                    is_novel,
+                    &embedding_strategy,
+                ));
             }
         }
     }
 
-    // Fallback for builds without embedding features
-    #[cfg(not(all(feature = "embeddings", feature = "vector-search")))]
-    {
-        let node = storage.ingest(input).map_err(|e| e.to_string())?;
-        let node_id = node.id.clone();
-        let node_content = node.content.clone();
-        let node_type = node.node_type.clone();
-        let has_embedding = node.has_embedding.unwrap_or(false);
-
-        run_post_ingest(cognitive, &node_id, &node_content, &node_type, importance_composite);
-
-        Ok(serde_json::json!({
-            "success": true,
-            "nodeId": node_id,
-            "decision": "create",
-            "message": format!("Knowledge ingested successfully. Node ID: {}", node_id),
-            "hasEmbedding": has_embedding,
-            "isNovel": is_novel,
-            "embeddingStrategy": embedding_strategy,
-        }))
-    }
+    let outcome = storage.ingest_with_outcome(input).map_err(|e| e.to_string())?;
+    Ok(outcome_response(storage, cognitive, outcome, importance_composite, is_novel, &embedding_strategy))
+}
+
+/// Response for a plain ingest, running the post-ingest effects unless an
+/// existing memory was returned
+fn outcome_response(
+    storage: &Storage,
+    cognitive: &Arc<Mutex<CognitiveEngine>>,
+    outcome: IngestOutcome,
+    importance_composite: f64,
+    is_novel: bool,
+    embedding_strategy: &str,
+) -> Value {
+    let node = outcome.node;
+    let has_embedding = node.has_embedding.unwrap_or(false);
+    let (decision, message) = if outcome.returned_existing {
+        ("duplicate", format!("Content already stored. Returned existing node: {}", node.id))
+    } else {
+        run_post_ingest(storage, cognitive, &node.id, &node.content, &node.node_type, importance_composite);
+        ("create", format!("Knowledge ingested successfully. Node ID: {}", node.id))
+    };
+
+    serde_json::json!({
+        "success": true,
+        "nodeId": node.id,
+        "decision": decision,
+        "returnedExisting": outcome.returned_existing,
+        "message": message,
+        "hasEmbedding": has_embedding,
+        "isNovel": is_novel,
+        "embeddingStrategy": embedding_strategy,
+    })
 }
 
 /// Cognitive post-ingest side effects: synaptic tagging, novelty update, hippocampal indexing.
 fn run_post_ingest(
+    storage: &Storage,
     cognitive: &Arc<Mutex<CognitiveEngine>>,
     node_id: &str,
     content: &str,
     node_type: &str,
     importance_composite: f64,
 ) {
+    // Persisted tags and events are captured at the next consolidation,
+    // so they survive restarts and don't depend on the cognitive lock
+    if importance_composite > 0.7 {
+        let event = ImportanceEvent::for_memory(node_id, ImportanceEventType::NoveltySpike);
+        if let Err(e) = storage.record_importance_event(&event) {
+            tracing::warn!("Failed to record importance event: {}", e);
+        }
+    } else if importance_composite > 0.3
+        && let Err(e) = storage.tag_for_capture(node_id)
+    {
+        tracing::warn!("Failed to tag memory for capture: {}", e);
+    }
+
     if let Ok(mut cog) = cognitive.try_lock() {
         // Synaptic tagging for retroactive capture
         if importance_composite > 0.3 {
@@ -297,8 +333,7 @@ mod tests {
     #[tokio::test]
     async fn test_ingest_large_content_fails() {
         let (storage, _dir) = test_storage().await;
-        // Create content larger than 1MB
-        let large_content = "x".repeat(1_000_001);
+        let large_content = "x".repeat(storage.max_content_bytes() + 1);
         let args = serde_json::json!({ "content": large_content });
         let result = execute(&storage, &test_cognitive(), Some(args)).await;
         assert!(result.is_err());
@@ -306,10 +341,9 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_ingest_exactly_1mb_succeeds() {
+    async fn test_ingest_at_size_limit_succeeds() {
         let (storage, _dir) = test_storage().await;
-        // Create content exactly 1MB
-        let exact_content = "x".repeat(1_000_000);
+        let exact_content = "x".repeat(storage.max_content_bytes());
         let args = serde_json::json!({ "content": exact_content });
         let result = execute(&storage, &test_cognitive(), Some(args)).await;
         assert!(result.is_ok());
@@ -412,6 +446,30 @@ mod tests {
         assert_eq!(node.node_type, "fact");
     }
 
+    #[tokio::test]
+    async fn test_ingest_duplicate_policies() {
+        let (storage, _dir) = test_storage().await;
+        let cognitive = test_cognitive();
+        let ingest = |on_duplicate: Option<&str>| {
+            let mut args = serde_json::json!({ "content": "Retries must not duplicate memories." });
+            if let Some(policy) = on_duplicate {
+                args["on_duplicate"] = serde_json::json!(policy);
+            }
+            execute(&storage, &cognitive, Some(args))
+        };
+
+        let first = ingest(None).await.unwrap();
+        let retry = ingest(None).await.unwrap();
+        assert_eq!(retry["nodeId"], first["nodeId"]);
+
+        let err = ingest(Some("error")).await.unwrap_err();
+        assert!(err.contains("Duplicate"), "{err}");
+
+        let forced = ingest(Some("createAnyway")).await.unwrap();
+        assert_ne!(forced["nodeId"], first["nodeId"]);
+        assert_eq!(forced["returnedExisting"], false);
+    }
+
     // ========================================================================
     // SCHEMA TESTS
     // ========================================================================
@@ -430,5 +488,6 @@ mod tests {
         assert!(schema_value["properties"]["node_type"].is_object());
         assert!(schema_value["properties"]["tags"].is_object());
         assert!(schema_value["properties"]["source"].is_object());
+        assert_eq!(schema_value["properties"]["on_duplicate"]["default"], "returnExisting");
     }
 }
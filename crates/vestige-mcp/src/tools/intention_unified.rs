@@ -15,6 +15,7 @@ use tokio::sync::Mutex;
 use uuid::Uuid;
 
 use crate::cognitive::CognitiveEngine;
+use super::storage_error;
 use vestige_core::IntentionRecord;
 use vestige_core::Storage;
 use vestige_core::neuroscience::ProspectiveContext;
@@ -136,6 +137,10 @@ pub fn schema() -> Value {
                 "type": "integer",
                 "default": 20,
                 "description": "[list] Maximum number to return"
+            },
+            "namespace": {
+                "type": "string",
+                "description": "Only create, check, update and list intentions in this namespace (default: all namespaces)"
             }
         },
         "required": ["action"]
@@ -191,6 +196,14 @@ struct UnifiedIntentionArgs {
     #[serde(alias = "filterStatus")]
     filter_status: Option<String>,
     limit: Option<i32>,
+    namespace: Option<String>,
+}
+
+impl UnifiedIntentionArgs {
+    /// The namespace the action is confined to, if any
+    fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref().filter(|ns| !ns.trim().is_empty())
+    }
 }
 
 // ============================================================================
@@ -382,7 +395,11 @@ async fn execute_set(
         source_data: None,
     };
 
-    storage.save_intention(&record).map_err(|e| e.to_string())?;
+    match args.namespace() {
+        Some(ns) => storage.with_namespace(ns).save_intention(&record),
+        None => storage.save_intention(&record),
+    }
+    .map_err(storage_error)?;
 
     Ok(serde_json::json!({
         "success": true,
@@ -426,7 +443,7 @@ async fn execute_check(
 
 
     // Get active intentions
-    let intentions = storage.get_active_intentions().map_err(|e| e.to_string())?;
+    let intentions = active_intentions(storage, args)?;
 
     let mut triggered = Vec::new();
     let mut pending = Vec::new();
@@ -529,11 +546,21 @@ async fn execute_update(
         .as_ref()
         .ok_or("Missing 'status' for update action")?;
 
+    if let Some(ns) = args.namespace()
+        && storage
+            .with_namespace(ns)
+            .get_intention(intention_id)
+            .map_err(storage_error)?
+            .is_none()
+    {
+        return Err(format!("Intention not found in namespace '{}': {}", ns, intention_id));
+    }
+
     match status.as_str() {
         "complete" => {
             let updated = storage
                 .update_intention_status(intention_id, "fulfilled")
-                .map_err(|e| e.to_string())?;
+                .map_err(storage_error)?;
 
             if updated {
                 Ok(serde_json::json!({
@@ -553,7 +580,7 @@ async fn execute_update(
 
             let updated = storage
                 .snooze_intention(intention_id, snooze_until)
-                .map_err(|e| e.to_string())?;
+                .map_err(storage_error)?;
 
             if updated {
                 Ok(serde_json::json!({
@@ -571,7 +598,7 @@ async fn execute_update(
         "cancel" => {
             let updated = storage
                 .update_intention_status(intention_id, "cancelled")
-                .map_err(|e| e.to_string())?;
+                .map_err(storage_error)?;
 
             if updated {
                 Ok(serde_json::json!({
@@ -592,6 +619,18 @@ async fn execute_update(
     }
 }
 
+/// Active intentions in the action's namespace, or all of them
+fn active_intentions(
+    storage: &Storage,
+    args: &UnifiedIntentionArgs,
+) -> Result<Vec<IntentionRecord>, String> {
+    match args.namespace() {
+        Some(ns) => storage.with_namespace(ns).get_active_intentions(),
+        None => storage.get_active_intentions(),
+    }
+    .map_err(storage_error)
+}
+
 /// Execute "list" action - list intentions with optional filtering
 async fn execute_list(
     storage: &Arc<Storage>,
@@ -599,32 +638,25 @@ async fn execute_list(
 ) -> Result<Value, String> {
     let filter_status = args.filter_status.as_deref().unwrap_or("active");
 
+    let by_status = |status: &str| {
+        match args.namespace() {
+            Some(ns) => storage.with_namespace(ns).get_intentions_by_status(status),
+            None => storage.get_intentions_by_status(status),
+        }
+        .map_err(storage_error)
+    };
     let intentions = if filter_status == "all" {
         // Get all by combining different statuses
-        let mut all = storage.get_active_intentions().map_err(|e| e.to_string())?;
-        all.extend(
-            storage
-                .get_intentions_by_status("fulfilled")
-                .map_err(|e| e.to_string())?,
-        );
-        all.extend(
-            storage
-                .get_intentions_by_status("cancelled")
-                .map_err(|e| e.to_string())?,
-        );
-        all.extend(
-            storage
-                .get_intentions_by_status("snoozed")
-                .map_err(|e| e.to_string())?,
-        );
+        let mut all = active_intentions(storage, args)?;
+        all.extend(by_status("fulfilled")?);
+        all.extend(by_status("cancelled")?);
+        all.extend(by_status("snoozed")?);
         all
     } else if filter_status == "active" {
         // Use get_active_intentions for proper priority ordering
-        storage.get_active_intentions().map_err(|e| e.to_string())?
+        active_intentions(storage, args)?
     } else {
-        storage
-            .get_intentions_by_status(filter_status)
-            .map_err(|e| e.to_string())?
+        by_status(filter_status)?
     };
 
     let limit = args.limit.unwrap_or(20) as usize;
@@ -1099,6 +1131,41 @@ mod tests {
         assert_eq!(value["total"], 2);
     }
 
+    #[tokio::test]
+    async fn test_namespace_scopes_intentions() {
+        let (storage, _dir) = test_storage().await;
+        let shared_id = create_test_intention(&storage, "Shared task").await;
+        let args = serde_json::json!({
+            "action": "set",
+            "description": "Acme task",
+            "namespace": "acme"
+        });
+        execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+
+        let list = |namespace: Option<&str>| {
+            let mut args = serde_json::json!({ "action": "list" });
+            if let Some(ns) = namespace {
+                args["namespace"] = serde_json::json!(ns);
+            }
+            let storage = storage.clone();
+            async move { execute(&storage, &test_cognitive(), Some(args)).await.unwrap() }
+        };
+        let acme = list(Some("acme")).await;
+        assert_eq!(acme["total"], 1);
+        assert_eq!(acme["intentions"][0]["description"], "Acme task");
+        assert_eq!(list(None).await["total"], 2);
+
+        let args = serde_json::json!({
+            "action": "update",
+            "id": shared_id,
+            "status": "complete",
+            "namespace": "acme"
+        });
+        let err = execute(&storage, &test_cognitive(), Some(args)).await.unwrap_err();
+        assert!(err.contains("not found in namespace 'acme'"));
+        assert_eq!(list(None).await["total"], 2);
+    }
+
     #[tokio::test]
     async fn test_list_action_filter_by_status() {
         let (storage, _dir) = test_storage().await;
@@ -1,7 +1,7 @@
 //! Maintenance MCP Tools
 //!
 //! Exposes CLI-only operations as MCP tools so Claude can trigger them automatically:
-//! system_status, consolidate, backup, export, gc.
+//! system_status, consolidate, backup, export, gc, source_trust.
 
 use chrono::{NaiveDate, Utc};
 use serde::Deserialize;
@@ -10,8 +10,11 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::cognitive::CognitiveEngine;
+use super::storage_error;
 use vestige_core::advanced::compression::MemoryForCompression;
-use vestige_core::{FSRSScheduler, MemoryLifecycle, MemoryState, Storage};
+use vestige_core::{
+    ConsolidationConfig, ConsolidationPipeline, FSRSScheduler, MemoryLifecycle, MemoryState, Storage,
+};
 
 // ============================================================================
 // SCHEMAS
@@ -29,7 +32,26 @@ pub fn health_check_schema() -> Value {
 pub fn consolidate_schema() -> Value {
     serde_json::json!({
         "type": "object",
-        "properties": {}
+        "properties": {
+            "namespace": {
+                "type": "string",
+                "description": "Only consolidate memories in this namespace (default: all memories)"
+            },
+            "step": {
+                "type": "string",
+                "enum": ConsolidationPipeline::step_names(),
+                "description": "Run only this step (even if disabled) instead of the full cycle, e.g. 'dedup'"
+            },
+            "config": {
+                "type": "object",
+                "description": "Save consolidation settings before running; omitted fields keep their current value. Keys: enabledSteps (array of step names), dedupThreshold, dreamSampleSize, dreamMinMemories, compressionMinAgeDays, compressionMaxRetention, compressionMaxGroups, connectionPruneThreshold, skipBelowMutations, maxSkipHours, decayFreshnessMinutes, dedupTimeBudgetSecs"
+            },
+            "dryRun": {
+                "type": "boolean",
+                "description": "With 'config': save the settings and return them without consolidating",
+                "default": false
+            }
+        }
     })
 }
 
@@ -38,7 +60,12 @@ pub fn consolidate_schema() -> Value {
 pub fn stats_schema() -> Value {
     serde_json::json!({
         "type": "object",
-        "properties": {}
+        "properties": {
+            "namespace": {
+                "type": "string",
+                "description": "Only report on memories in this namespace (default: all memories)"
+            }
+        }
     })
 }
 
@@ -71,6 +98,10 @@ pub fn export_schema() -> Value {
             "path": {
                 "type": "string",
                 "description": "Custom filename (not path). File is saved in ~/.vestige/exports/. Default: memories-{timestamp}.{format}"
+            },
+            "namespace": {
+                "type": "string",
+                "description": "Only export memories in this namespace (default: all memories)"
             }
         }
     })
@@ -101,11 +132,73 @@ pub fn gc_schema() -> Value {
     })
 }
 
+pub fn source_trust_schema() -> Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "pattern": {
+                "type": "string",
+                "description": "Memory source to weight: an exact source, or a prefix ending in '*' (e.g. 'tool:*'). Omit to list the configured patterns."
+            },
+            "weight": {
+                "type": "number",
+                "description": "Ranking multiplier for matching memories (1.0 = neutral)",
+                "minimum": 0.5,
+                "maximum": 1.5
+            },
+            "remove": {
+                "type": "boolean",
+                "description": "Remove the pattern instead of setting it",
+                "default": false
+            }
+        }
+    })
+}
+
 /// Combined system status schema (replaces health_check + stats in v1.7.0)
 pub fn system_status_schema() -> Value {
     serde_json::json!({
         "type": "object",
-        "properties": {}
+        "properties": {
+            "namespace": {
+                "type": "string",
+                "description": "Only report on memories in this namespace (default: all memories)"
+            },
+            "action": {
+                "type": "string",
+                "enum": ["status", "forecast", "backfill", "retruncate"],
+                "description": "'status' (default) reports system health; 'forecast' projects daily review load and average retention assuming no reviews; 'backfill' embeds memories missing embeddings for up to budgetSecs, returning a resumeToken to continue; 'retruncate' rebuilds the vector index at another Matryoshka dimension",
+                "default": "status"
+            },
+            "resumeToken": {
+                "type": "string",
+                "description": "Token from a previous 'backfill' call to continue where it stopped"
+            },
+            "force": {
+                "type": "boolean",
+                "description": "Re-embed memories that already have embeddings (action 'backfill' only)",
+                "default": false
+            },
+            "budgetSecs": {
+                "type": "integer",
+                "description": "Seconds to spend on this backfill slice (default: 20, max: 120)",
+                "default": 20,
+                "minimum": 1,
+                "maximum": 120
+            },
+            "dimensions": {
+                "type": "integer",
+                "description": "Vector index dimensions for action 'retruncate': 256, 384, 512 or 768. Moving up needs a 'backfill' afterwards for memories embedded at fewer dimensions",
+                "enum": [256, 384, 512, 768]
+            },
+            "days": {
+                "type": "integer",
+                "description": "Days ahead to forecast (action 'forecast' only, default: 7, max: 365)",
+                "default": 7,
+                "minimum": 1,
+                "maximum": 365
+            }
+        }
     })
 }
 
@@ -113,6 +206,17 @@ pub fn system_status_schema() -> Value {
 // EXECUTE FUNCTIONS
 // ============================================================================
 
+/// Days of access log summarized in system_status analytics
+const ANALYTICS_WINDOW_DAYS: i64 = 30;
+
+/// Default and maximum horizon of the system_status forecast action
+const DEFAULT_FORECAST_DAYS: u64 = 7;
+const MAX_FORECAST_DAYS: u64 = 365;
+
+/// Default and maximum seconds one system_status backfill slice may run
+const DEFAULT_BACKFILL_SECS: u64 = 20;
+const MAX_BACKFILL_SECS: u64 = 120;
+
 /// Combined system status tool (merges health_check + stats, v1.7.0)
 ///
 /// Returns system health status, full statistics, FSRS preview,
@@ -120,9 +224,36 @@ pub fn system_status_schema() -> Value {
 pub async fn execute_system_status(
     storage: &Arc<Storage>,
     cognitive: &Arc<Mutex<CognitiveEngine>>,
-    _args: Option<Value>,
+    args: Option<Value>,
 ) -> Result<Value, String> {
-    let stats = storage.get_stats().map_err(|e| e.to_string())?;
+    let action = args.as_ref().and_then(|a| a.get("action")).and_then(Value::as_str);
+    match action {
+        None | Some("status") => {}
+        Some("forecast") => {
+            let days = args
+                .as_ref()
+                .and_then(|a| a.get("days"))
+                .and_then(Value::as_u64)
+                .unwrap_or(DEFAULT_FORECAST_DAYS)
+                .clamp(1, MAX_FORECAST_DAYS);
+            return execute_forecast(storage, days as u32);
+        }
+        Some("backfill") => return execute_backfill(storage, args.as_ref()),
+        Some("retruncate") => return execute_retruncate(storage, args.as_ref()),
+        Some(other) => {
+            return Err(format!(
+                "Unknown action '{}': expected 'status', 'forecast', 'backfill' or 'retruncate'",
+                other
+            ));
+        }
+    }
+
+    let namespace = parse_namespace(args)?;
+    let stats = match &namespace {
+        Some(ns) => storage.with_namespace(ns.as_str()).get_stats(),
+        None => storage.get_stats(),
+    }
+    .map_err(storage_error)?;
 
     // === Health assessment ===
     let status = if stats.total_nodes == 0 {
@@ -175,7 +306,11 @@ pub async fn execute_system_status(
     }
 
     // === State distribution ===
-    let nodes = storage.get_all_nodes(500, 0).map_err(|e| e.to_string())?;
+    let nodes = match &namespace {
+        Some(ns) => storage.with_namespace(ns.as_str()).get_all_nodes(500, 0),
+        None => storage.get_all_nodes(500, 0),
+    }
+    .map_err(storage_error)?;
     let total = nodes.len();
     let (active, dormant, silent, unavailable) = if total > 0 {
         let mut a = 0usize;
@@ -253,6 +388,10 @@ pub async fn execute_system_status(
     };
     let last_backup = Storage::get_last_backup_timestamp();
 
+    // === Usage analytics from the access log ===
+    let analytics = storage
+        .access_analytics(chrono::Duration::days(ANALYTICS_WINDOW_DAYS))
+        .ok();
 
     Ok(serde_json::json!({
         "tool": "system_status",
@@ -291,16 +430,102 @@ pub async fn execute_system_status(
             "lastBackupTimestamp": last_backup.map(|dt| dt.to_rfc3339()),
             "lastConsolidationTimestamp": last_consolidation.map(|dt| dt.to_rfc3339()),
         },
+        // Usage analytics (last 30 days of the access log)
+        "analytics": analytics,
+    }))
+}
+
+/// Forecast action of system_status: due reviews and projected retention per day
+fn execute_forecast(storage: &Arc<Storage>, days: u32) -> Result<Value, String> {
+    let forecast = storage.forecast(days).map_err(storage_error)?;
+    let total_due: i64 = forecast.days.iter().map(|d| d.due_count).sum();
+    Ok(serde_json::json!({
+        "tool": "system_status",
+        "action": "forecast",
+        "totalDue": total_due,
+        "forecast": forecast,
+    }))
+}
+
+/// Backfill action of system_status: one time-budgeted slice of embedding generation
+#[cfg(all(feature = "embeddings", feature = "vector-search"))]
+fn execute_backfill(storage: &Arc<Storage>, args: Option<&Value>) -> Result<Value, String> {
+    let arg = |key: &str| args.and_then(|a| a.get(key));
+    let budget_secs = arg("budgetSecs")
+        .and_then(Value::as_u64)
+        .unwrap_or(DEFAULT_BACKFILL_SECS)
+        .clamp(1, MAX_BACKFILL_SECS);
+    let options = vestige_core::BackfillOptions {
+        force: arg("force").and_then(Value::as_bool).unwrap_or(false),
+        time_budget: Some(std::time::Duration::from_secs(budget_secs)),
+        ..Default::default()
+    };
+    let resume_token = arg("resumeToken").and_then(Value::as_str);
+    let progress = storage
+        .backfill_embeddings(resume_token, &options, None)
+        .map_err(storage_error)?;
+    let percent = progress.percent_complete();
+    let message = match &progress.resume_token {
+        None => format!("Backfill complete: {} memories embedded", progress.embedded),
+        Some(_) => format!(
+            "{:.1}% complete, {} remaining; call again with resumeToken to continue",
+            percent, progress.remaining
+        ),
+    };
+    Ok(serde_json::json!({
+        "tool": "system_status",
+        "action": "backfill",
+        "done": progress.resume_token.is_none(),
+        "percentComplete": (percent * 10.0).round() / 10.0,
+        "resumeToken": progress.resume_token,
+        "message": message,
+        "progress": progress,
     }))
 }
 
+#[cfg(not(all(feature = "embeddings", feature = "vector-search")))]
+fn execute_backfill(_storage: &Arc<Storage>, _args: Option<&Value>) -> Result<Value, String> {
+    Err("Embedding backfill requires the embeddings and vector-search features".to_string())
+}
+
+/// Retruncate action of system_status: move the vector index to another dimension
+#[cfg(all(feature = "embeddings", feature = "vector-search"))]
+fn execute_retruncate(storage: &Arc<Storage>, args: Option<&Value>) -> Result<Value, String> {
+    let dimensions = args
+        .and_then(|a| a.get("dimensions"))
+        .and_then(Value::as_u64)
+        .ok_or("Missing 'dimensions' (256, 384, 512 or 768) for action 'retruncate'")?;
+    let report = storage
+        .retruncate_embeddings(dimensions as usize)
+        .map_err(storage_error)?;
+    let message = if report.needs_reembedding > 0 {
+        format!(
+            "Index rebuilt at {} dimensions; {} memories need re-embedding, run action 'backfill'",
+            report.dimensions, report.needs_reembedding
+        )
+    } else {
+        format!("Index rebuilt at {} dimensions with {} vectors", report.dimensions, report.indexed)
+    };
+    Ok(serde_json::json!({
+        "tool": "system_status",
+        "action": "retruncate",
+        "message": message,
+        "report": report,
+    }))
+}
+
+#[cfg(not(all(feature = "embeddings", feature = "vector-search")))]
+fn execute_retruncate(_storage: &Arc<Storage>, _args: Option<&Value>) -> Result<Value, String> {
+    Err("Changing index dimensions requires the embeddings and vector-search features".to_string())
+}
+
 /// Health check tool — deprecated in v1.7, use execute_system_status() instead
 #[allow(dead_code)]
 pub async fn execute_health_check(
     storage: &Arc<Storage>,
     _args: Option<Value>,
 ) -> Result<Value, String> {
-    let stats = storage.get_stats().map_err(|e| e.to_string())?;
+    let stats = storage.get_stats().map_err(storage_error)?;
 
     let status = if stats.total_nodes == 0 {
         "empty"
@@ -364,24 +589,124 @@ pub async fn execute_health_check(
     }))
 }
 
-/// Consolidate tool
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConsolidateArgs {
+    namespace: Option<String>,
+    step: Option<String>,
+    config: Option<serde_json::Map<String, Value>>,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+/// Consolidate tool: the full cycle, one step, or a config update
 pub async fn execute_consolidate(
     storage: &Arc<Storage>,
-    _args: Option<Value>,
+    args: Option<Value>,
 ) -> Result<Value, String> {
-    let result = storage.run_consolidation().map_err(|e| e.to_string())?;
+    let args: ConsolidateArgs = match args {
+        Some(v) => serde_json::from_value(v).map_err(|e| format!("Invalid arguments: {}", e))?,
+        None => ConsolidateArgs::default(),
+    };
+    let namespace = args.namespace.filter(|ns| !ns.trim().is_empty());
+
+    if let Some(changes) = args.config {
+        // Overlay the given keys on the current settings
+        let current = storage.consolidation_config().map_err(storage_error)?;
+        let mut merged = serde_json::to_value(current).map_err(|e| e.to_string())?;
+        if let Value::Object(fields) = &mut merged {
+            fields.extend(changes);
+        }
+        let config: ConsolidationConfig =
+            serde_json::from_value(merged).map_err(|e| format!("Invalid config: {}", e))?;
+        storage.set_consolidation_config(&config).map_err(storage_error)?;
+        if args.dry_run {
+            return Ok(serde_json::json!({
+                "tool": "consolidate",
+                "config": storage.consolidation_config().map_err(storage_error)?,
+            }));
+        }
+    }
+
+    if let Some(step) = args.step {
+        let result = match &namespace {
+            Some(ns) => storage.with_namespace(ns.as_str()).run_consolidation_step(&step),
+            None => storage.run_consolidation_step(&step),
+        }
+        .map_err(storage_error)?;
+        return Ok(serde_json::json!({
+            "tool": "consolidate",
+            "namespace": namespace,
+            "step": step,
+            "result": result,
+        }));
+    }
+
+    let result = match &namespace {
+        Some(ns) => storage.with_namespace(ns.as_str()).run_consolidation(),
+        None => storage.run_consolidation(),
+    }
+    .map_err(storage_error)?;
 
     Ok(serde_json::json!({
         "tool": "consolidate",
+        "namespace": namespace,
         "nodesProcessed": result.nodes_processed,
         "nodesPromoted": result.nodes_promoted,
         "nodesPruned": result.nodes_pruned,
         "decayApplied": result.decay_applied,
         "embeddingsGenerated": result.embeddings_generated,
         "duplicatesMerged": result.duplicates_merged,
+        "dedupClustersExamined": result.dedup_clusters_examined,
+        "dedupClustersMerged": result.dedup_clusters_merged,
         "activationsComputed": result.activations_computed,
         "w20Optimized": result.w20_optimized,
         "durationMs": result.duration_ms,
+        "steps": result.steps,
+        "stepTimings": result.step_timings,
+        "stepErrors": result.step_errors,
+        "skipped": result.skipped,
+    }))
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct SourceTrustArgs {
+    pattern: Option<String>,
+    weight: Option<f64>,
+    remove: Option<bool>,
+}
+
+/// Source trust tool: list, set or remove per-source ranking multipliers
+pub async fn execute_source_trust(
+    storage: &Arc<Storage>,
+    args: Option<Value>,
+) -> Result<Value, String> {
+    let args: SourceTrustArgs = match args {
+        Some(v) => serde_json::from_value(v).map_err(|e| format!("Invalid arguments: {}", e))?,
+        None => SourceTrustArgs::default(),
+    };
+
+    let action = match (&args.pattern, args.weight, args.remove.unwrap_or(false)) {
+        (Some(pattern), _, true) => {
+            if !storage.remove_source_trust(pattern).map_err(storage_error)? {
+                return Err(format!("No source trust set for '{}'", pattern));
+            }
+            "removed"
+        }
+        (Some(pattern), Some(weight), false) => {
+            storage.set_source_trust(pattern, weight).map_err(storage_error)?;
+            "set"
+        }
+        (Some(_), None, false) => return Err("'weight' is required to set a source trust".to_string()),
+        (None, _, _) => "list",
+    };
+
+    let trusts = storage.get_source_trusts().map_err(storage_error)?;
+    Ok(serde_json::json!({
+        "tool": "source_trust",
+        "action": action,
+        "pattern": args.pattern,
+        "trusts": trusts,
     }))
 }
 
@@ -390,12 +715,21 @@ pub async fn execute_consolidate(
 pub async fn execute_stats(
     storage: &Arc<Storage>,
     cognitive: &Arc<Mutex<CognitiveEngine>>,
-    _args: Option<Value>,
+    args: Option<Value>,
 ) -> Result<Value, String> {
-    let stats = storage.get_stats().map_err(|e| e.to_string())?;
+    let namespace = parse_namespace(args)?;
+    let stats = match &namespace {
+        Some(ns) => storage.with_namespace(ns.as_str()).get_stats(),
+        None => storage.get_stats(),
+    }
+    .map_err(storage_error)?;
 
     // Compute state distribution from a sample of nodes
-    let nodes = storage.get_all_nodes(500, 0).map_err(|e| e.to_string())?;
+    let nodes = match &namespace {
+        Some(ns) => storage.with_namespace(ns.as_str()).get_all_nodes(500, 0),
+        None => storage.get_all_nodes(500, 0),
+    }
+    .map_err(storage_error)?;
     let total = nodes.len();
     let (active, dormant, silent, unavailable) = if total > 0 {
         let mut a = 0usize;
@@ -570,37 +904,36 @@ pub async fn execute_backup(
     storage: &Arc<Storage>,
     _args: Option<Value>,
 ) -> Result<Value, String> {
-    // Determine backup path
-    let vestige_dir = directories::ProjectDirs::from("com", "vestige", "core")
+    let backup_dir = vestige_core::storage::default_backup_dir()
         .ok_or("Could not determine data directory")?;
-    let backup_dir = vestige_dir.data_dir().parent()
-        .unwrap_or(vestige_dir.data_dir())
-        .join("backups");
-
-    std::fs::create_dir_all(&backup_dir)
-        .map_err(|e| format!("Failed to create backup directory: {}", e))?;
 
-    let timestamp = Utc::now().format("%Y%m%d-%H%M%S");
-    let backup_path = backup_dir.join(format!("vestige-{}.db", timestamp));
-
-    // Use VACUUM INTO for a consistent backup (handles WAL properly)
-    {
-        storage.backup_to(&backup_path)
-            .map_err(|e| format!("Failed to create backup: {}", e))?;
-    }
-
-    let file_size = std::fs::metadata(&backup_path)
-        .map(|m| m.len())
-        .unwrap_or(0);
+    // VACUUM INTO for a consistent backup (handles WAL properly), then verified
+    let backup = storage.create_verified_backup(&backup_dir)
+        .map_err(|e| format!("Failed to create backup: {}", e))?;
 
     Ok(serde_json::json!({
         "tool": "backup",
-        "path": backup_path.display().to_string(),
-        "sizeBytes": file_size,
+        "path": backup.path.display().to_string(),
+        "sizeBytes": backup.size_bytes,
+        "verified": true,
         "timestamp": Utc::now().to_rfc3339(),
     }))
 }
 
+#[derive(Debug, Default, Deserialize)]
+struct NamespaceArgs {
+    namespace: Option<String>,
+}
+
+/// Optional `namespace` argument of system_status
+fn parse_namespace(args: Option<Value>) -> Result<Option<String>, String> {
+    let args: NamespaceArgs = match args {
+        Some(v) => serde_json::from_value(v).map_err(|e| format!("Invalid arguments: {}", e))?,
+        None => NamespaceArgs::default(),
+    };
+    Ok(args.namespace.filter(|ns| !ns.trim().is_empty()))
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ExportArgs {
@@ -608,6 +941,7 @@ struct ExportArgs {
     tags: Option<Vec<String>>,
     since: Option<String>,
     path: Option<String>,
+    namespace: Option<String>,
 }
 
 /// Export tool
@@ -622,6 +956,7 @@ pub async fn execute_export(
             tags: None,
             since: None,
             path: None,
+            namespace: None,
         },
     };
 
@@ -641,6 +976,7 @@ pub async fn execute_export(
     };
 
     let tag_filter: Vec<String> = args.tags.unwrap_or_default();
+    let namespace = args.namespace.filter(|ns| !ns.trim().is_empty());
 
     // Fetch all nodes (capped at 100K to prevent OOM)
     let mut all_nodes = Vec::new();
@@ -648,7 +984,11 @@ pub async fn execute_export(
     let max_nodes = 100_000;
     let mut offset = 0;
     loop {
-        let batch = storage.get_all_nodes(page_size, offset).map_err(|e| e.to_string())?;
+        let batch = match &namespace {
+            Some(ns) => storage.with_namespace(ns.as_str()).get_all_nodes(page_size, offset),
+            None => storage.get_all_nodes(page_size, offset),
+        }
+        .map_err(storage_error)?;
         let batch_len = batch.len();
         all_nodes.extend(batch);
         if batch_len < page_size as usize || all_nodes.len() >= max_nodes {
@@ -771,7 +1111,7 @@ pub async fn execute_gc(
     let max_nodes = 100_000;
     let mut offset = 0;
     loop {
-        let batch = storage.get_all_nodes(page_size, offset).map_err(|e| e.to_string())?;
+        let batch = storage.get_all_nodes(page_size, offset).map_err(storage_error)?;
         let batch_len = batch.len();
         all_nodes.extend(batch);
         if batch_len < page_size as usize || all_nodes.len() >= max_nodes {
@@ -912,6 +1252,9 @@ mod tests {
                 tags: vec![],
                 valid_from: None,
                 valid_until: None,
+                namespace: None,
+                auto_tag: false,
+                on_duplicate: Default::default(),
             }).unwrap();
         }
         let result = execute_system_status(&storage, &test_cognitive(), None).await;
@@ -920,6 +1263,63 @@ mod tests {
         assert_eq!(value["totalMemories"], 1);
         assert!(value["stateDistribution"].is_object());
         assert!(value["embeddingCoverage"].is_string());
+        assert_eq!(value["analytics"]["windowTruncated"], false);
+        assert!(value["analytics"]["topAccessed"].is_array());
+    }
+
+    #[tokio::test]
+    async fn test_consolidate_runs_single_step_and_saves_config() {
+        let (storage, _dir) = test_storage().await;
+        storage.ingest(vestige_core::IngestInput {
+            content: "Memory to consolidate".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+
+        let args = serde_json::json!({ "step": "decay" });
+        let value = execute_consolidate(&storage, Some(args)).await.unwrap();
+        assert_eq!(value["result"]["step"], "decay");
+        let args = serde_json::json!({ "step": "defrag" });
+        assert!(execute_consolidate(&storage, Some(args)).await.is_err());
+
+        let args = serde_json::json!({
+            "config": { "enabledSteps": ["decay", "dedup"], "dedupThreshold": 0.9 },
+            "dryRun": true
+        });
+        let value = execute_consolidate(&storage, Some(args)).await.unwrap();
+        assert_eq!(value["config"]["enabledSteps"], serde_json::json!(["decay", "dedup"]));
+        // Keys left out keep their previous value
+        assert_eq!(value["config"]["compressionMaxGroups"], 5);
+
+        let value = execute_consolidate(&storage, None).await.unwrap();
+        assert_eq!(value["steps"].as_object().unwrap().len(), 2);
+        assert_eq!(value["stepTimings"][0]["step"], "decay");
+        assert_eq!(value["stepTimings"][1]["succeeded"], true);
+        assert_eq!(value["stepErrors"], serde_json::json!([]));
+        assert!(value["skipped"].is_null());
+
+        // Nothing changed since, so the next run is skipped
+        let value = execute_consolidate(&storage, None).await.unwrap();
+        assert_eq!(value["skipped"], "no changes");
+        assert_eq!(value["steps"], serde_json::json!({}));
+    }
+
+    #[tokio::test]
+    async fn test_system_status_forecast_action() {
+        let (storage, _dir) = test_storage().await;
+        storage.ingest(vestige_core::IngestInput {
+            content: "Memory to forecast".to_string(),
+            node_type: "fact".to_string(),
+            ..Default::default()
+        }).unwrap();
+        let args = serde_json::json!({ "action": "forecast", "days": 3 });
+        let value = execute_system_status(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        assert_eq!(value["action"], "forecast");
+        assert_eq!(value["forecast"]["totalMemories"], 1);
+        assert_eq!(value["forecast"]["days"].as_array().unwrap().len(), 4);
+
+        let args = serde_json::json!({ "action": "bogus" });
+        assert!(execute_system_status(&storage, &test_cognitive(), Some(args)).await.is_err());
     }
 
     #[tokio::test]
@@ -961,6 +1361,9 @@ mod tests {
                     tags: vec![],
                     valid_from: None,
                     valid_until: None,
+                    namespace: None,
+                    auto_tag: false,
+                    on_duplicate: Default::default(),
                 }).unwrap();
             }
         }
@@ -972,4 +1375,24 @@ mod tests {
         assert_eq!(triggers["savesSinceLastDream"], 3);
         assert!(triggers["lastDreamTimestamp"].is_null());
     }
+
+    #[tokio::test]
+    async fn test_source_trust_set_list_remove() {
+        let (storage, _dir) = test_storage().await;
+
+        let args = serde_json::json!({ "pattern": "notes:*", "weight": 1.3 });
+        let result = execute_source_trust(&storage, Some(args)).await.unwrap();
+        assert_eq!(result["action"], "set");
+        assert_eq!(result["trusts"][0]["weight"], 1.3);
+
+        let invalid = serde_json::json!({ "pattern": "notes:*", "weight": 3.0 });
+        assert!(execute_source_trust(&storage, Some(invalid)).await.is_err());
+
+        let listed = execute_source_trust(&storage, None).await.unwrap();
+        assert_eq!(listed["trusts"].as_array().unwrap().len(), 1);
+
+        let remove = serde_json::json!({ "pattern": "notes:*", "remove": true });
+        let result = execute_source_trust(&storage, Some(remove)).await.unwrap();
+        assert!(result["trusts"].as_array().unwrap().is_empty());
+    }
 }
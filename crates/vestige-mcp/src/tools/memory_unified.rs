@@ -9,7 +9,8 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::cognitive::CognitiveEngine;
-use vestige_core::{MemoryState, Modification, OutcomeType, Storage};
+use super::{check_namespace, storage_error};
+use vestige_core::{FeedbackOptions, MemoryState, Modification, OutcomeType, Storage};
 
 // Accessibility thresholds based on retention strength
 const ACCESSIBILITY_ACTIVE: f64 = 0.7;
@@ -43,8 +44,8 @@ pub fn schema() -> Value {
         "properties": {
             "action": {
                 "type": "string",
-                "enum": ["get", "delete", "state", "promote", "demote", "edit"],
-                "description": "Action to perform: 'get' retrieves full memory node, 'delete' removes memory, 'state' returns accessibility state, 'promote' increases retrieval strength (thumbs up), 'demote' decreases retrieval strength (thumbs down), 'edit' updates content in-place (preserves FSRS state)"
+                "enum": ["get", "delete", "state", "promote", "demote", "edit", "outcome", "pin", "unpin"],
+                "description": "Action to perform: 'get' retrieves full memory node, 'delete' removes memory, 'state' returns accessibility state, 'promote' increases retrieval strength (thumbs up), 'demote' decreases retrieval strength (thumbs down), 'edit' updates content in-place (preserves FSRS state), 'outcome' reports how a task informed by the memory turned out (e.g. a successful deploy or a failed build), 'pin' keeps the memory ranked high and exempt from decay and garbage collection, 'unpin' removes a pin"
             },
             "id": {
                 "type": "string",
@@ -52,11 +53,44 @@ pub fn schema() -> Value {
             },
             "reason": {
                 "type": "string",
-                "description": "Why this memory is being promoted/demoted (optional, for logging). Only used with promote/demote actions."
+                "description": "Why this memory is being promoted/demoted, or a note on the outcome (optional, kept in the access log). Only used with promote/demote/outcome actions."
+            },
+            "outcome": {
+                "type": "string",
+                "enum": ["very_helpful", "helpful", "neutral", "not_helpful", "harmful", "success", "failure"],
+                "description": "Outcome only: how the task turned out. 'success' = helpful, 'failure' = not_helpful. Positive outcomes strengthen the memory more than promote; negative ones demote it and flag it for review."
+            },
+            "ids": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "Outcome only: further memories that informed the same task, credited alongside 'id'"
+            },
+            "strength": {
+                "type": "number",
+                "minimum": 0.0,
+                "maximum": 1.0,
+                "description": "How strongly to promote/demote, or the outcome's magnitude, scaling the default change (default 1.0). Use ~0.25 for 'slightly helpful', 1.0 for 'catastrophically wrong'."
+            },
+            "invalidate": {
+                "type": "boolean",
+                "description": "Demote only: mark the memory as no longer valid (sets valid_until). Requires a reason describing the correction."
+            },
+            "until": {
+                "type": "string",
+                "description": "Pin only: RFC 3339 timestamp when the pin expires. Omit (with ttlDays) to pin indefinitely."
+            },
+            "ttlDays": {
+                "type": "number",
+                "exclusiveMinimum": 0,
+                "description": "Pin only: expire the pin after this many days. Alternative to 'until'."
             },
             "content": {
                 "type": "string",
                 "description": "New content for edit action. Replaces existing content, regenerates embedding, preserves FSRS state."
+            },
+            "namespace": {
+                "type": "string",
+                "description": "Only act on memories in this namespace; ids from other namespaces are rejected (default: all namespaces)"
             }
         },
         "required": ["action", "id"]
@@ -70,6 +104,14 @@ struct MemoryArgs {
     id: String,
     reason: Option<String>,
     content: Option<String>,
+    strength: Option<f32>,
+    #[serde(default)]
+    invalidate: bool,
+    outcome: Option<String>,
+    ids: Option<Vec<String>>,
+    until: Option<String>,
+    ttl_days: Option<f64>,
+    namespace: Option<String>,
 }
 
 /// Execute the unified memory tool
@@ -85,24 +127,74 @@ pub async fn execute(
 
     // Validate UUID format
     uuid::Uuid::parse_str(&args.id).map_err(|_| "Invalid memory ID format".to_string())?;
+    let namespace = args.namespace.as_deref().filter(|ns| !ns.trim().is_empty());
+    check_namespace(storage, &args.id, namespace)?;
 
     match args.action.as_str() {
         "get" => execute_get(storage, &args.id).await,
         "delete" => execute_delete(storage, &args.id).await,
         "state" => execute_state(storage, &args.id).await,
-        "promote" => execute_promote(storage, cognitive, &args.id, args.reason).await,
-        "demote" => execute_demote(storage, cognitive, &args.id, args.reason).await,
+        "promote" | "demote" => {
+            let options = FeedbackOptions {
+                strength: args.strength,
+                reason: args.reason,
+                invalidate: args.invalidate,
+            };
+            if args.action == "promote" {
+                execute_promote(storage, cognitive, &args.id, options).await
+            } else {
+                execute_demote(storage, cognitive, &args.id, options).await
+            }
+        }
         "edit" => execute_edit(storage, &args.id, args.content).await,
+        "outcome" => {
+            let outcome: OutcomeType = args
+                .outcome
+                .as_deref()
+                .ok_or("Missing 'outcome' field. Required for outcome action.")?
+                .parse()?;
+            let mut ids = vec![args.id];
+            for id in args.ids.unwrap_or_default() {
+                uuid::Uuid::parse_str(&id).map_err(|_| "Invalid memory ID format".to_string())?;
+                check_namespace(storage, &id, namespace)?;
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+            execute_outcome(storage, cognitive, &ids, outcome, args.strength.unwrap_or(1.0), args.reason).await
+        }
+        "pin" => {
+            let until = match (args.until.as_deref(), args.ttl_days) {
+                (Some(_), Some(_)) => return Err("Use either 'until' or 'ttlDays', not both".to_string()),
+                (Some(until), None) => Some(
+                    chrono::DateTime::parse_from_rfc3339(until)
+                        .map_err(|e| format!("Invalid 'until' timestamp: {}", e))?
+                        .with_timezone(&chrono::Utc),
+                ),
+                (None, Some(days)) if days > 0.0 && days.is_finite() => {
+                    Some(chrono::Utc::now() + chrono::Duration::seconds((days * 86400.0) as i64))
+                }
+                (None, Some(_)) => return Err("'ttlDays' must be a positive number".to_string()),
+                (None, None) => None,
+            };
+            execute_pin(storage, cognitive, &args.id, until, namespace).await
+        }
+        "unpin" => execute_unpin(storage, cognitive, &args.id, namespace).await,
         _ => Err(format!(
-            "Invalid action '{}'. Must be one of: get, delete, state, promote, demote, edit",
+            "Invalid action '{}'. Must be one of: get, delete, state, promote, demote, edit, outcome, pin, unpin",
             args.action
         )),
     }
 }
 
 /// Get full memory node with all metadata
+///
+/// Fetching a memory by id is a deliberate open, so it gets the full access boost.
 async fn execute_get(storage: &Arc<Storage>, id: &str) -> Result<Value, String> {
-    let node = storage.get_node(id).map_err(|e| e.to_string())?;
+    let node = storage.get_node(id).map_err(storage_error)?;
+    if node.is_some() {
+        let _ = storage.touch(id);
+    }
 
     match node {
         Some(n) => Ok(serde_json::json!({
@@ -142,7 +234,7 @@ async fn execute_get(storage: &Arc<Storage>, id: &str) -> Result<Value, String>
 
 /// Delete a memory and return success status
 async fn execute_delete(storage: &Arc<Storage>, id: &str) -> Result<Value, String> {
-    let deleted = storage.delete_node(id).map_err(|e| e.to_string())?;
+    let deleted = storage.delete_node(id).map_err(storage_error)?;
 
     Ok(serde_json::json!({
         "action": "delete",
@@ -203,20 +295,21 @@ async fn execute_promote(
     storage: &Arc<Storage>,
     cognitive: &Arc<Mutex<CognitiveEngine>>,
     id: &str,
-    reason: Option<String>,
+    options: FeedbackOptions,
 ) -> Result<Value, String> {
 
-    let before = storage.get_node(id).map_err(|e| e.to_string())?
+    let before = storage.get_node(id).map_err(storage_error)?
         .ok_or_else(|| format!("Node not found: {}", id))?;
 
-    let node = storage.promote_memory(id).map_err(|e| e.to_string())?;
+    let node = storage.promote_memory_with(id, &options).map_err(storage_error)?;
+    let strength = options.strength.unwrap_or(1.0) as f64;
 
     // Cognitive feedback pipeline
-    if let Ok(mut cog) = cognitive.try_lock() {
+    if let Ok(cog) = cognitive.try_lock() {
         cog.reward_signal.record_outcome(id, OutcomeType::Helpful);
         cog.importance_tracker.on_retrieved(id, true);
-        if cog.reconsolidation.is_labile(id) {
-            cog.reconsolidation.apply_modification(
+        if storage.is_labile(id) {
+            storage.apply_reconsolidation(
                 id,
                 Modification::StrengthenConnection {
                     target_memory_id: id.to_string(),
@@ -230,22 +323,23 @@ async fn execute_promote(
         "success": true,
         "action": "promoted",
         "nodeId": node.id,
-        "reason": reason,
+        "reason": options.reason,
+        "strength": strength,
         "changes": {
             "retrievalStrength": {
                 "before": before.retrieval_strength,
                 "after": node.retrieval_strength,
-                "delta": "+0.20"
+                "delta": format!("+{:.2}", 0.20 * strength)
             },
             "retentionStrength": {
                 "before": before.retention_strength,
                 "after": node.retention_strength,
-                "delta": "+0.10"
+                "delta": format!("+{:.2}", 0.10 * strength)
             },
             "stability": {
                 "before": before.stability,
                 "after": node.stability,
-                "multiplier": "1.5x"
+                "multiplier": format!("{}x", ((1.0 + 0.5 * strength) * 1000.0).round() / 1000.0)
             }
         },
         "message": format!("Memory promoted. It will now surface more often in searches. Retrieval: {:.2} -> {:.2}",
@@ -258,20 +352,21 @@ async fn execute_demote(
     storage: &Arc<Storage>,
     cognitive: &Arc<Mutex<CognitiveEngine>>,
     id: &str,
-    reason: Option<String>,
+    options: FeedbackOptions,
 ) -> Result<Value, String> {
 
-    let before = storage.get_node(id).map_err(|e| e.to_string())?
+    let before = storage.get_node(id).map_err(storage_error)?
         .ok_or_else(|| format!("Node not found: {}", id))?;
 
-    let node = storage.demote_memory(id).map_err(|e| e.to_string())?;
+    let node = storage.demote_memory_with(id, &options).map_err(storage_error)?;
+    let strength = options.strength.unwrap_or(1.0) as f64;
 
     // Cognitive feedback pipeline
-    if let Ok(mut cog) = cognitive.try_lock() {
+    if let Ok(cog) = cognitive.try_lock() {
         cog.reward_signal.record_outcome(id, OutcomeType::NotHelpful);
         cog.importance_tracker.on_retrieved(id, false);
-        if cog.reconsolidation.is_labile(id) {
-            cog.reconsolidation.apply_modification(
+        if storage.is_labile(id) {
+            storage.apply_reconsolidation(
                 id,
                 Modification::AddContext {
                     context: "User reported this memory was wrong/unhelpful".to_string(),
@@ -284,22 +379,25 @@ async fn execute_demote(
         "success": true,
         "action": "demoted",
         "nodeId": node.id,
-        "reason": reason,
+        "reason": options.reason,
+        "strength": strength,
+        "invalidated": options.invalidate,
+        "validUntil": node.valid_until,
         "changes": {
             "retrievalStrength": {
                 "before": before.retrieval_strength,
                 "after": node.retrieval_strength,
-                "delta": "-0.30"
+                "delta": format!("-{:.2}", 0.30 * strength)
             },
             "retentionStrength": {
                 "before": before.retention_strength,
                 "after": node.retention_strength,
-                "delta": "-0.15"
+                "delta": format!("-{:.2}", 0.15 * strength)
             },
             "stability": {
                 "before": before.stability,
                 "after": node.stability,
-                "multiplier": "0.5x"
+                "multiplier": format!("{}x", ((1.0 - 0.5 * strength) * 1000.0).round() / 1000.0)
             }
         },
         "message": format!("Memory demoted. Better alternatives will now surface instead. Retrieval: {:.2} -> {:.2}",
@@ -308,6 +406,118 @@ async fn execute_demote(
     }))
 }
 
+/// Report a task outcome against the memories that informed it
+async fn execute_outcome(
+    storage: &Arc<Storage>,
+    cognitive: &Arc<Mutex<CognitiveEngine>>,
+    ids: &[String],
+    outcome: OutcomeType,
+    magnitude: f32,
+    note: Option<String>,
+) -> Result<Value, String> {
+    let id_refs: Vec<&str> = ids.iter().map(String::as_str).collect();
+    let nodes = storage
+        .record_outcome(&id_refs, outcome.clone(), magnitude, note.clone())
+        .map_err(storage_error)?;
+    let reward = outcome.reward() * magnitude as f64;
+
+    // Cognitive feedback pipeline
+    if let Ok(cog) = cognitive.try_lock() {
+        for id in ids {
+            cog.reward_signal.record_outcome(id, outcome.clone());
+            if reward != 0.0 {
+                cog.importance_tracker.on_retrieved(id, reward > 0.0);
+            }
+        }
+    }
+
+    let memories: Vec<Value> = nodes
+        .iter()
+        .map(|n| serde_json::json!({
+            "nodeId": n.id,
+            "retrievalStrength": n.retrieval_strength,
+            "retentionStrength": n.retention_strength,
+            "stability": n.stability,
+            "importance": n.importance,
+            "nextReview": n.next_review.map(|d| d.to_rfc3339()),
+        }))
+        .collect();
+
+    Ok(serde_json::json!({
+        "success": true,
+        "action": "outcome",
+        "outcome": outcome.as_str(),
+        "magnitude": magnitude,
+        "reward": reward,
+        "note": note,
+        "memories": memories,
+        "message": if reward > 0.0 {
+            format!("Outcome recorded. {} memor{} strengthened.", nodes.len(), if nodes.len() == 1 { "y" } else { "ies" })
+        } else if reward < 0.0 {
+            format!("Outcome recorded. {} memor{} demoted and flagged for review.", nodes.len(), if nodes.len() == 1 { "y" } else { "ies" })
+        } else {
+            "Outcome recorded with no strength change.".to_string()
+        },
+    }))
+}
+
+/// Pin a memory until `until`, or indefinitely
+async fn execute_pin(
+    storage: &Arc<Storage>,
+    cognitive: &Arc<Mutex<CognitiveEngine>>,
+    id: &str,
+    until: Option<chrono::DateTime<chrono::Utc>>,
+    namespace: Option<&str>,
+) -> Result<Value, String> {
+    let pin = storage.pin_memory(id, until).map_err(storage_error)?;
+    let _ = cognitive.lock().await.hippocampal_index.set_user_starred(id, true);
+    Ok(serde_json::json!({
+        "success": true,
+        "action": "pin",
+        "nodeId": pin.node.id,
+        "pinnedAt": pin.pinned_at.to_rfc3339(),
+        "pinnedUntil": pin.pinned_until.map(|d| d.to_rfc3339()),
+        "pinned": pinned_summary(storage, namespace)?,
+        "message": match pin.pinned_until {
+            Some(until) => format!("Memory pinned until {}.", until.to_rfc3339()),
+            None => "Memory pinned indefinitely.".to_string(),
+        },
+    }))
+}
+
+/// Remove a memory's pin
+async fn execute_unpin(
+    storage: &Arc<Storage>,
+    cognitive: &Arc<Mutex<CognitiveEngine>>,
+    id: &str,
+    namespace: Option<&str>,
+) -> Result<Value, String> {
+    let unpinned = storage.unpin_memory(id).map_err(storage_error)?;
+    let _ = cognitive.lock().await.hippocampal_index.set_user_starred(id, false);
+    Ok(serde_json::json!({
+        "success": unpinned,
+        "action": "unpin",
+        "nodeId": id,
+        "pinned": pinned_summary(storage, namespace)?,
+        "message": if unpinned { "Memory unpinned." } else { "Memory was not pinned." },
+    }))
+}
+
+/// Currently pinned memories in the namespace (or all), for pin/unpin responses
+fn pinned_summary(storage: &Storage, namespace: Option<&str>) -> Result<Vec<Value>, String> {
+    Ok(storage
+        .list_pinned()
+        .map_err(storage_error)?
+        .into_iter()
+        .filter(|pin| namespace.is_none() || pin.node.namespace.as_deref() == namespace)
+        .map(|pin| serde_json::json!({
+            "nodeId": pin.node.id,
+            "content": pin.node.content.chars().take(100).collect::<String>(),
+            "pinnedUntil": pin.pinned_until.map(|d| d.to_rfc3339()),
+        }))
+        .collect())
+}
+
 /// Edit a memory's content in-place — preserves FSRS state, regenerates embedding
 async fn execute_edit(
     storage: &Arc<Storage>,
@@ -323,13 +533,13 @@ async fn execute_edit(
     // Get existing node to capture old content
     let old_node = storage
         .get_node(id)
-        .map_err(|e| e.to_string())?
+        .map_err(storage_error)?
         .ok_or_else(|| format!("Memory not found: {}", id))?;
 
     // Update content (regenerates embedding, syncs FTS5)
     storage
-        .update_node_content(id, &new_content)
-        .map_err(|e| e.to_string())?;
+        .update_node_content_from(id, &new_content, "memory:edit")
+        .map_err(storage_error)?;
 
     // Truncate previews for response (char-safe to avoid UTF-8 panics)
     let old_preview = if old_node.content.chars().count() > 200 {
@@ -389,9 +599,12 @@ mod tests {
         assert!(schema["properties"]["id"].is_object());
         assert!(schema["properties"]["reason"].is_object());
         assert_eq!(schema["required"], serde_json::json!(["action", "id"]));
-        // Verify all 6 actions are in enum
+        // Verify all 9 actions are in enum
         let actions = schema["properties"]["action"]["enum"].as_array().unwrap();
-        assert_eq!(actions.len(), 6);
+        assert_eq!(actions.len(), 9);
+        assert!(actions.contains(&serde_json::json!("pin")));
+        assert!(actions.contains(&serde_json::json!("unpin")));
+        assert!(actions.contains(&serde_json::json!("outcome")));
         assert!(actions.contains(&serde_json::json!("edit")));
         assert!(actions.contains(&serde_json::json!("promote")));
         assert!(actions.contains(&serde_json::json!("demote")));
@@ -420,6 +633,9 @@ mod tests {
                 tags: vec!["test-tag".to_string()],
                 valid_from: None,
                 valid_until: None,
+                namespace: None,
+                auto_tag: false,
+                on_duplicate: Default::default(),
             })
             .unwrap();
         node.id
@@ -675,6 +891,83 @@ mod tests {
         assert_eq!(value["changes"]["stability"]["multiplier"], "0.5x");
     }
 
+    #[tokio::test]
+    async fn test_graded_demote_with_invalidation() {
+        let (storage, _dir) = test_storage().await;
+        let id = ingest_memory(&storage).await;
+        let args = serde_json::json!({ "action": "demote", "id": id, "strength": 0.5, "invalidate": true });
+        let result = execute(&storage, &test_cognitive(), Some(args)).await;
+        assert!(result.unwrap_err().contains("reason"));
+
+        let args = serde_json::json!({
+            "action": "demote",
+            "id": id,
+            "strength": 0.5,
+            "invalidate": true,
+            "reason": "Superseded by the new retry policy"
+        });
+        let value = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        assert_eq!(value["changes"]["retrievalStrength"]["delta"], "-0.15");
+        assert_eq!(value["changes"]["stability"]["multiplier"], "0.75x");
+        assert_eq!(value["invalidated"], true);
+        assert!(value["validUntil"].is_string());
+
+        let args = serde_json::json!({ "action": "promote", "id": id, "strength": 2.0 });
+        assert!(execute(&storage, &test_cognitive(), Some(args)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_outcome_action_credits_all_ids() {
+        let (storage, _dir) = test_storage().await;
+        let id = ingest_memory(&storage).await;
+        let other = storage
+            .ingest(vestige_core::IngestInput {
+                content: "Feature flags are cleaned up after each release".to_string(),
+                node_type: "fact".to_string(),
+                ..Default::default()
+            })
+            .unwrap()
+            .id;
+        let args = serde_json::json!({
+            "action": "outcome",
+            "id": id,
+            "ids": [other],
+            "outcome": "failure",
+            "reason": "Build broke on the stale flag"
+        });
+        let value = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        assert_eq!(value["outcome"], "not_helpful");
+        assert_eq!(value["memories"].as_array().unwrap().len(), 2);
+        assert_eq!(storage.get_outcomes(&other, 5).unwrap().len(), 1);
+
+        let args = serde_json::json!({ "action": "outcome", "id": id, "outcome": "great" });
+        assert!(execute(&storage, &test_cognitive(), Some(args)).await.is_err());
+        let args = serde_json::json!({ "action": "outcome", "id": id });
+        assert!(execute(&storage, &test_cognitive(), Some(args)).await.unwrap_err().contains("outcome"));
+    }
+
+    #[tokio::test]
+    async fn test_pin_and_unpin_actions() {
+        let (storage, _dir) = test_storage().await;
+        let id = ingest_memory(&storage).await;
+
+        let args = serde_json::json!({ "action": "pin", "id": id, "ttlDays": 2 });
+        let value = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        assert_eq!(value["action"], "pin");
+        assert!(value["pinnedUntil"].is_string());
+        assert_eq!(value["pinned"].as_array().unwrap().len(), 1);
+
+        let args = serde_json::json!({ "action": "pin", "id": id, "until": "2001-01-01T00:00:00Z" });
+        assert!(execute(&storage, &test_cognitive(), Some(args)).await.is_err());
+        let args = serde_json::json!({ "action": "pin", "id": id, "ttlDays": -1 });
+        assert!(execute(&storage, &test_cognitive(), Some(args)).await.is_err());
+
+        let args = serde_json::json!({ "action": "unpin", "id": id });
+        let value = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        assert_eq!(value["success"], true);
+        assert!(value["pinned"].as_array().unwrap().is_empty());
+    }
+
     // ========================================================================
     // EDIT TESTS (v1.9.2)
     // ========================================================================
@@ -777,4 +1070,34 @@ mod tests {
         let value = result.unwrap();
         assert_eq!(value["success"], true);
     }
+
+    #[tokio::test]
+    async fn test_namespace_rejects_foreign_ids() {
+        let (storage, _dir) = test_storage().await;
+        let id = ingest_memory(&storage).await;
+        let acme = storage
+            .with_namespace("acme")
+            .ingest(vestige_core::IngestInput {
+                content: "Acme-only memory".to_string(),
+                ..Default::default()
+            })
+            .unwrap()
+            .id;
+
+        for action in ["get", "delete", "edit"] {
+            let args = serde_json::json!({
+                "action": action,
+                "id": id,
+                "content": "Rewritten",
+                "namespace": "acme"
+            });
+            let err = execute(&storage, &test_cognitive(), Some(args)).await.unwrap_err();
+            assert!(err.contains("not in namespace 'acme'"), "{action}: {err}");
+        }
+        assert!(storage.get_node(&id).unwrap().is_some());
+
+        let args = serde_json::json!({ "action": "get", "id": acme, "namespace": "acme" });
+        let value = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        assert_eq!(value["found"], true);
+    }
 }
@@ -67,3 +67,38 @@ pub mod search;
 pub mod stats;
 #[allow(dead_code)]
 pub mod tagging;
+
+/// Tool error for a storage failure, carrying its JSON-RPC code and kind
+///
+/// The server unpacks these into `code`/`data` fields of the error result.
+pub(crate) fn storage_error(err: vestige_core::StorageError) -> String {
+    let rpc = crate::protocol::types::JsonRpcError::from(&err);
+    serde_json::to_string(&rpc).unwrap_or_else(|_| err.to_string())
+}
+
+/// Reject a memory id that lives in a namespace other than `namespace`
+///
+/// Unscoped calls and unknown ids pass; actions report missing memories themselves.
+pub(crate) fn check_namespace(
+    storage: &vestige_core::Storage,
+    id: &str,
+    namespace: Option<&str>,
+) -> Result<(), String> {
+    let Some(ns) = namespace else {
+        return Ok(());
+    };
+    match storage.get_node(id).map_err(storage_error)? {
+        Some(node) if node.namespace.as_deref() != Some(ns) => {
+            Err(format!("Memory {} is not in namespace '{}'", id, ns))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Whether a memory belongs to `namespace`; every memory does for unscoped calls
+pub(crate) fn in_namespace(storage: &vestige_core::Storage, id: &str, namespace: Option<&str>) -> bool {
+    match namespace {
+        Some(ns) => matches!(storage.with_namespace(ns).get_node(id), Ok(Some(_))),
+        None => true,
+    }
+}
@@ -1,5 +1,6 @@
 //! Predict tool — Proactive memory prediction ("what will you need next?").
 //! v1.5.0: Wires PredictiveMemory + SpeculativeRetriever.
+//! Also prefetches likely-next memories mined from the persisted access log.
 
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -21,7 +22,12 @@ pub fn schema() -> serde_json::Value {
                         "type": "array",
                         "items": { "type": "string" }
                     },
-                    "codebase": { "type": "string" }
+                    "codebase": { "type": "string" },
+                    "recent_memory_ids": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Memories just accessed, most recent last; seeds access-sequence prefetch"
+                    }
                 }
             }
         }
@@ -29,7 +35,7 @@ pub fn schema() -> serde_json::Value {
 }
 
 pub async fn execute(
-    _storage: &Arc<Storage>,
+    storage: &Arc<Storage>,
     cognitive: &Arc<Mutex<CognitiveEngine>>,
     args: Option<serde_json::Value>,
 ) -> Result<serde_json::Value, String> {
@@ -84,7 +90,11 @@ pub async fn execute(
             .unwrap_or_default(),
         recent_edits: Vec::new(),
         recent_queries: Vec::new(),
-        recent_memory_ids: Vec::new(),
+        recent_memory_ids: context
+            .and_then(|c| c.get("recent_memory_ids"))
+            .and_then(|v| v.as_array())
+            .map(|ids| ids.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+            .unwrap_or_default(),
         project_path: context
             .and_then(|c| c.get("codebase"))
             .and_then(|v| v.as_str())
@@ -92,6 +102,10 @@ pub async fn execute(
         timestamp: Some(chrono::Utc::now()),
     };
     let speculative = cog.speculative_retriever.predict_needed(&speculative_context);
+    drop(cog);
+
+    let prefetched = storage.speculative_prefetch(speculative_context, 5)
+        .map_err(|e| format!("Speculative prefetch failed: {}", e))?;
 
     Ok(serde_json::json!({
         "predictions": predictions.iter().map(|p| serde_json::json!({
@@ -112,6 +126,12 @@ pub async fn execute(
             "confidence": p.confidence,
             "trigger": format!("{:?}", p.trigger),
         })).collect::<Vec<_>>(),
+        "prefetched": prefetched.iter().map(|p| serde_json::json!({
+            "memory_id": p.memory_id,
+            "content_preview": p.content_preview,
+            "confidence": p.confidence,
+            "reasoning": p.reasoning,
+        })).collect::<Vec<_>>(),
         "top_interests": top_interests,
         "prediction_accuracy": accuracy,
     }))
@@ -141,6 +161,7 @@ mod tests {
         assert!(s["properties"]["context"]["properties"]["current_file"].is_object());
         assert!(s["properties"]["context"]["properties"]["current_topics"].is_object());
         assert!(s["properties"]["context"]["properties"]["codebase"].is_object());
+        assert!(s["properties"]["context"]["properties"]["recent_memory_ids"].is_object());
     }
 
     #[tokio::test]
@@ -152,6 +173,7 @@ mod tests {
         assert!(value["predictions"].is_array());
         assert!(value["suggestions"].is_array());
         assert!(value["speculative"].is_array());
+        assert!(value["prefetched"].is_array());
         assert!(value["prediction_accuracy"].is_number());
     }
 
@@ -6,7 +6,7 @@ use serde::Deserialize;
 use serde_json::Value;
 use std::sync::Arc;
 
-use vestige_core::{RecallInput, SearchMode, Storage};
+use vestige_core::{QueryExpansionMode, RecallInput, SearchMode, SearchSyntax, Storage};
 
 /// Input schema for recall tool
 pub fn schema() -> Value {
@@ -62,7 +62,11 @@ pub async fn execute(
         limit: args.limit.unwrap_or(10).clamp(1, 100),
         min_retention: args.min_retention.unwrap_or(0.0).clamp(0.0, 1.0),
         search_mode: SearchMode::Hybrid,
+        syntax: SearchSyntax::Plain,
+        query_expansion: QueryExpansionMode::Auto,
         valid_at: None,
+        namespace: None,
+        exclude_unavailable: false,
     };
 
     let nodes = storage.recall(input).map_err(|e| e.to_string())?;
@@ -122,6 +126,9 @@ mod tests {
             tags: vec![],
             valid_from: None,
             valid_until: None,
+            namespace: None,
+            auto_tag: false,
+            on_duplicate: Default::default(),
         };
         let node = storage.ingest(input).unwrap();
         node.id
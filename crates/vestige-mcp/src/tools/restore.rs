@@ -108,13 +108,16 @@ pub async fn execute(
     for memory in &memories {
         let input = IngestInput {
             content: memory.content.clone(),
-            node_type: memory.node_type.clone().unwrap_or_else(|| "fact".to_string()),
+            node_type: storage.node_type_for_restore(memory.node_type.as_deref()),
             source: memory.source.clone(),
             sentiment_score: 0.0,
             sentiment_magnitude: 0.0,
             tags: memory.tags.clone().unwrap_or_default(),
             valid_from: None,
             valid_until: None,
+            namespace: None,
+            auto_tag: false,
+            on_duplicate: Default::default(),
         };
 
         match storage.ingest(input) {
@@ -118,6 +118,9 @@ mod tests {
             tags: vec![],
             valid_from: None,
             valid_until: None,
+            namespace: None,
+            auto_tag: false,
+            on_duplicate: Default::default(),
         };
         let node = storage.ingest(input).unwrap();
         node.id
@@ -2,7 +2,8 @@
 //!
 //! Merges recall, semantic_search, and hybrid_search into a single `search` tool.
 //! Always uses hybrid search internally (keyword + semantic + RRF fusion).
-//! Implements Testing Effect (Roediger & Karpicke 2006) by auto-strengthening memories on access.
+//! Implements Testing Effect (Roediger & Karpicke 2006): results get a weak search impression;
+//! memories opened via memory(get) get the full access boost.
 //!
 //! v1.5.0: Enhanced 7-stage cognitive pipeline:
 //!   1. Reranker (over-fetch 3x, rerank down)
@@ -20,9 +21,11 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::cognitive::CognitiveEngine;
+use super::storage_error;
+use vestige_core::search::{parse_time_expression, TemporalSearcher};
 use vestige_core::{
-    CompetitionCandidate, EncodingContext, MemoryLifecycle, MemorySnapshot, MemoryState, Storage,
-    TopicalContext,
+    CompetitionCandidate, DetectedIntent, EncodingContext, MemoryLifecycle, MemoryState,
+    SearchSyntax, Storage, TopicalContext,
 };
 
 /// Input schema for unified search tool
@@ -71,6 +74,51 @@ pub fn schema() -> Value {
                 "description": "Max tokens for response. Server truncates content to fit budget. Use memory(action='get') for full content of specific IDs.",
                 "minimum": 100,
                 "maximum": 10000
+            },
+            "explain": {
+                "type": "boolean",
+                "description": "Include a per-result scoreBreakdown (raw FTS rank, keyword score, semantic similarity, HyDE expansion, recency, ACT-R activation, source trust, weighted terms) for debugging ranking",
+                "default": false
+            },
+            "explain_ids": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "Memory IDs to explain even if they did not make the cut: each gets the same score breakdown as explain, plus whether it was returned"
+            },
+            "detect_intent": {
+                "type": "boolean",
+                "description": "Detect the query's intent (debugging, learning, implementing, ...) and boost memory types that suit it. The detected intent is reported in the response.",
+                "default": false
+            },
+            "syntax": {
+                "type": "string",
+                "enum": ["plain", "advanced"],
+                "description": "Keyword query syntax. 'plain' (default) matches the query as one literal phrase. 'advanced' keeps \"quoted phrases\" exact, treats words ending in * (at least 3 characters before it) as prefixes, excludes terms or phrases preceded by - or NOT, and requires all other words to match.",
+                "default": "plain"
+            },
+            "as_of": {
+                "type": "string",
+                "description": "Search what was known at this time: an ISO 8601 date/datetime or a relative phrase like '3 months ago' or 'last week'. Only memories created by then and valid then are returned, including ones superseded since."
+            },
+            "namespace": {
+                "type": "string",
+                "description": "Only return memories from this namespace (default: all namespaces)"
+            },
+            "ranking": {
+                "description": "How to trade recency against established knowledge: 'balanced' (default), 'recent' (what changed lately) or 'established' (well-consolidated memories, ignoring recency). Or an object with recency_weight, importance_weight and relevance_weight (summing to 1.0) and recency_half_life_hours.",
+                "oneOf": [
+                    { "type": "string", "enum": ["balanced", "recent", "established"] },
+                    {
+                        "type": "object",
+                        "properties": {
+                            "recency_weight": { "type": "number", "minimum": 0.0, "maximum": 1.0 },
+                            "importance_weight": { "type": "number", "minimum": 0.0, "maximum": 1.0 },
+                            "relevance_weight": { "type": "number", "minimum": 0.0, "maximum": 1.0 },
+                            "recency_half_life_hours": { "type": "number", "exclusiveMinimum": 0.0 }
+                        },
+                        "required": ["recency_weight", "importance_weight", "relevance_weight", "recency_half_life_hours"]
+                    }
+                ]
             }
         },
         "required": ["query"]
@@ -89,20 +137,88 @@ struct SearchArgs {
     context_topics: Option<Vec<String>>,
     #[serde(alias = "token_budget")]
     token_budget: Option<i32>,
+    explain: Option<bool>,
+    #[serde(alias = "explain_ids")]
+    explain_ids: Option<Vec<String>>,
+    #[serde(alias = "detect_intent")]
+    detect_intent: Option<bool>,
+    syntax: Option<SearchSyntax>,
+    #[serde(alias = "as_of")]
+    as_of: Option<String>,
+    namespace: Option<String>,
+    ranking: Option<Value>,
+}
+
+/// Largest stage 1 over-fetch, also used when pre-fetching into the warm cache
+pub(crate) const MAX_OVERFETCH: i32 = 100;
+
+/// Hybrid search settings for stage 1
+pub(crate) fn stage_one_config(explain: bool) -> vestige_core::HybridSearchConfig {
+    // Reciprocal rank fusion, so BM25 and cosine scales cannot swamp each other
+    vestige_core::HybridSearchConfig {
+        explain,
+        ..Default::default()
+    }
+}
+
+/// Ranking profile from a preset name or an explicit weights object
+pub(crate) fn parse_ranking(value: &Value) -> Result<vestige_core::RankingProfile, String> {
+    let profile = match value {
+        Value::String(name) => vestige_core::RankingProfile::preset(name).ok_or_else(|| {
+            format!(
+                "Invalid ranking '{}'. Must be 'balanced', 'recent', or 'established'.",
+                name
+            )
+        })?,
+        _ => serde_json::from_value(value.clone())
+            .map_err(|e| format!("Invalid ranking: {}", e))?,
+    };
+    if !profile.is_valid() {
+        return Err(
+            "Invalid ranking: weights must be non-negative and sum to 1.0, and recency_half_life_hours must be positive"
+                .to_string(),
+        );
+    }
+    Ok(profile)
+}
+
+/// Stage 3 boost for one node: recency times validity. Profiles that ignore
+/// recency (e.g. `established`) only get the validity part.
+fn temporal_factor(
+    searcher: &TemporalSearcher,
+    node: &vestige_core::KnowledgeNode,
+    as_of: Option<chrono::DateTime<Utc>>,
+    ignore_recency: bool,
+) -> f64 {
+    let recency = match as_of {
+        _ if ignore_recency => 1.0,
+        Some(at) => searcher.recency_boost_at(node.created_at, at),
+        None => searcher.recency_boost(node.created_at),
+    };
+    recency * searcher.validity_boost(node.valid_from, node.valid_until, as_of)
+}
+
+/// Run stage 1 ahead of time for a query the user is predicted to search
+pub(crate) fn prefetch(
+    storage: &Storage,
+    query: &str,
+) -> vestige_core::Result<Vec<vestige_core::SearchResult>> {
+    storage.hybrid_search_with_config(query, MAX_OVERFETCH, &stage_one_config(false))
 }
 
 /// Execute unified search with 7-stage cognitive pipeline.
 ///
 /// Pipeline:
-///   1. Hybrid search (keyword + semantic + RRF) with 3x over-fetch
-///   2. Reranker (BM25-like rescoring, trim to limit)
+///   1. Hybrid search (keyword + semantic + RRF) with 3x over-fetch, biased
+///      toward memory types that suit the query intent when `detect_intent` is set
+///   2. Reranker (BM25-like rescoring blended into the combined score, trim to limit)
 ///   3. Temporal boosting (recency + validity windows)
 ///   4. Memory state accessibility filtering (Active/Dormant/Silent/Unavailable)
 ///   5. Context matching (topic overlap boosting)
 ///   6. Spreading activation (find associated memories)
 ///   7. Side effects: predictive memory recording + reconsolidation labile marking
 ///
-/// Also applies Testing Effect (Roediger & Karpicke 2006) by auto-strengthening on access.
+/// Also records a weak Testing Effect impression (Roediger & Karpicke 2006) on each result.
 pub async fn execute(
     storage: &Arc<Storage>,
     cognitive: &Arc<Mutex<CognitiveEngine>>,
@@ -134,19 +250,83 @@ pub async fn execute(
     let limit = args.limit.unwrap_or(10).clamp(1, 100);
     let min_retention = args.min_retention.unwrap_or(0.0).clamp(0.0, 1.0);
     let min_similarity = args.min_similarity.unwrap_or(0.5).clamp(0.0, 1.0);
-
-    // Favor semantic search — research shows 0.3/0.7 outperforms equal weights
-    let keyword_weight = 0.3_f32;
-    let semantic_weight = 0.7_f32;
+    let as_of = match args.as_of.as_deref() {
+        Some(s) => Some(parse_time_expression(s, Utc::now()).ok_or_else(|| {
+            format!(
+                "Invalid as_of '{}'. Use an ISO 8601 date (YYYY-MM-DD) or a relative phrase like '3 months ago'.",
+                s
+            )
+        })?),
+        None => None,
+    };
 
     // ====================================================================
     // STAGE 1: Hybrid search with 3x over-fetch for reranking pool
     // ====================================================================
-    let overfetch_limit = (limit * 3).min(100); // Cap at 100 to avoid excessive DB load
+    let overfetch_limit = (limit * 3).min(MAX_OVERFETCH); // Cap to avoid excessive DB load
+    let detect_intent = args.detect_intent.unwrap_or(false);
+    let ranking = args.ranking.as_ref().map(parse_ranking).transpose()?;
+    let mut config = stage_one_config(args.explain.unwrap_or(false));
+    config.syntax = args.syntax.unwrap_or_default();
+    config.namespace = args.namespace.filter(|ns| !ns.trim().is_empty());
+    if let Some(profile) = ranking {
+        config = config.with_profile(profile);
+    }
 
-    let results = storage
-        .hybrid_search(&args.query, overfetch_limit, keyword_weight, semantic_weight)
-        .map_err(|e| e.to_string())?;
+    // Results pre-fetched speculatively by session_context stand in for the search
+    // (prefetches use plain syntax, the default ranking, current knowledge, no
+    // intent bias and every namespace, so advanced, re-ranked, as-of, intent and
+    // namespaced queries always search)
+    let warm = if !detect_intent
+        && !config.explain
+        && config.syntax == SearchSyntax::Plain
+        && config.namespace.is_none()
+        && ranking.is_none()
+        && as_of.is_none()
+    {
+        cognitive.lock().await.warm_cache.take(&args.query)
+    } else {
+        None
+    };
+    let served_from_warm_cache = warm.is_some();
+
+    let (detection, results) = if let Some(mut results) = warm {
+        results.truncate(overfetch_limit as usize);
+        // Refresh nodes so edits and deletions since the prefetch are respected
+        results.retain_mut(|r| match storage.get_node(&r.node.id) {
+            Ok(Some(node)) => {
+                r.node = node;
+                true
+            }
+            _ => false,
+        });
+        (None, results)
+    } else if let Some(as_of) = as_of {
+        let results = storage
+            .search_as_of_with_config(&args.query, as_of, overfetch_limit, &config)
+            .map_err(storage_error)?;
+        (None, results)
+    } else if detect_intent {
+        let (detection, results) = storage
+            .hybrid_search_with_intent(&args.query, overfetch_limit, &config)
+            .map_err(storage_error)?;
+        (Some(detection), results)
+    } else {
+        let results = storage
+            .hybrid_search_with_config(&args.query, overfetch_limit, &config)
+            .map_err(storage_error)?;
+        (None, results)
+    };
+    let intent = detection
+        .filter(|d| !matches!(d.primary_intent, DetectedIntent::Unknown))
+        .map(|d| {
+            serde_json::json!({
+                "type": d.primary_intent.kind(),
+                "description": format!("interpreted as {} query", d.primary_intent.kind().replace('_', " ")),
+                "confidence": d.confidence,
+                "evidence": d.evidence,
+            })
+        });
 
     // Filter by min_retention and min_similarity first (cheap filters)
     let mut filtered_results: Vec<_> = results
@@ -173,9 +353,27 @@ pub async fn execute(
             .map(|r| (r.clone(), r.node.content.clone()))
             .collect();
 
-        if let Ok(reranked) = cog.reranker.rerank(&args.query, candidates, Some(limit as usize)) {
-            // Replace filtered_results with reranked items (preserves original SearchResult)
-            filtered_results = reranked.into_iter().map(|rr| rr.item).collect();
+        let pool = candidates.len();
+        if let Ok(reranked) = cog.reranker.rerank(&args.query, candidates, Some(pool)) {
+            // Blend the reranker's relevance into the stage-1 combined score rather
+            // than replacing it, so the ranking profile still shapes the order
+            let max_score = reranked.iter().map(|rr| rr.score).fold(0.0_f32, f32::max);
+            filtered_results = reranked
+                .into_iter()
+                .map(|rr| {
+                    let mut result = rr.item;
+                    if max_score > 0.0 {
+                        result.combined_score *= 0.5 + 0.5 * (rr.score.max(0.0) / max_score);
+                    }
+                    result
+                })
+                .collect();
+            filtered_results.sort_by(|a, b| {
+                b.combined_score
+                    .partial_cmp(&a.combined_score)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+            filtered_results.truncate(limit as usize);
         } else {
             // Reranker failed — fall back to original order, just truncate
             filtered_results.truncate(limit as usize);
@@ -188,18 +386,13 @@ pub async fn execute(
     // ====================================================================
     // STAGE 3: Temporal boosting (recency + validity windows)
     // ====================================================================
+    let ignore_recency = ranking.is_some_and(|profile| profile.recency_weight == 0.0);
     if let Ok(cog) = cognitive.try_lock() {
         for result in &mut filtered_results {
-            let recency = cog.temporal_searcher.recency_boost(result.node.created_at);
-            let validity = cog.temporal_searcher.validity_boost(
-                result.node.valid_from,
-                result.node.valid_until,
-                None,
-            );
             // Blend: 85% relevance + 15% temporal signal
-            let temporal_factor = recency * validity;
+            let factor = temporal_factor(&cog.temporal_searcher, &result.node, as_of, ignore_recency);
             result.combined_score =
-                result.combined_score * 0.85 + (result.combined_score * temporal_factor as f32) * 0.15;
+                result.combined_score * 0.85 + (result.combined_score * factor as f32) * 0.15;
         }
     }
 
@@ -207,6 +400,7 @@ pub async fn execute(
     // STAGE 4: Memory state accessibility filtering
     // ====================================================================
     if let Ok(cog) = cognitive.try_lock() {
+        let now = storage.clock().now();
         for result in &mut filtered_results {
             // Build a MemoryLifecycle from node data for the calculator
             let mut lifecycle = MemoryLifecycle::new();
@@ -225,7 +419,7 @@ pub async fn execute(
 
             let adjusted = cog
                 .accessibility_calc
-                .calculate(&lifecycle, result.combined_score as f64);
+                .calculate_at(&lifecycle, result.combined_score as f64, now);
             result.combined_score = adjusted as f32;
         }
     }
@@ -344,55 +538,48 @@ pub async fn execute(
     };
 
     // ====================================================================
-    // Auto-strengthen on access (Testing Effect)
+    // Search impressions (a fraction of the Testing Effect boost; opening a
+    // memory via memory(get) applies the full boost)
     // ====================================================================
     let ids: Vec<&str> = filtered_results.iter().map(|r| r.node.id.as_str()).collect();
-    let _ = storage.strengthen_batch_on_access(&ids);
+    let _ = storage.record_impressions(&ids);
 
     // Drop storage lock before acquiring cognitive for side effects
 
     // ====================================================================
     // STAGE 7: Side effects — predictive memory + reconsolidation
     // ====================================================================
-    if let Ok(mut cog) = cognitive.try_lock() {
+    if let Ok(cog) = cognitive.try_lock() {
         // 7A. Record query for predictive memory
         let _ = cog.predictive_memory.record_query(&args.query, &[]);
 
-        // 7B. Record each accessed memory for predictive/speculative models
+        // 7B. Record each accessed memory for predictive models
+        // (the speculative retriever is fed per tool call by the server)
         for result in &filtered_results {
             let _ = cog.predictive_memory.record_memory_access(
                 &result.node.id,
                 &result.node.content.chars().take(100).collect::<String>(),
                 &result.node.tags,
             );
-
-            cog.speculative_retriever.record_access(
-                &result.node.id,
-                None,                           // file_context
-                Some(args.query.as_str()),       // query_context
-                None,                            // was_helpful (unknown yet)
-            );
-
-            // 7C. Mark labile for reconsolidation window (5 min)
-            let snapshot = MemorySnapshot {
-                content: result.node.content.clone(),
-                tags: result.node.tags.clone(),
-                retention_strength: result.node.retention_strength,
-                storage_strength: result.node.storage_strength,
-                retrieval_strength: result.node.retrieval_strength,
-                connection_ids: vec![],
-                captured_at: Utc::now(),
-            };
-            cog.reconsolidation.mark_labile(&result.node.id, snapshot);
         }
     }
 
+    // 7C. Open the reconsolidation window on returned memories
+    let ids: Vec<&str> = filtered_results.iter().map(|r| r.node.id.as_str()).collect();
+    let _ = storage.mark_retrieved(&ids, Some(&args.query));
+
     // ====================================================================
     // Format and return
     // ====================================================================
     let mut formatted: Vec<Value> = filtered_results
         .iter()
-        .map(|r| format_search_result(r, detail_level))
+        .map(|r| {
+            let mut value = format_search_result(r, detail_level);
+            if let Some(ref breakdown) = r.score_breakdown {
+                value["scoreBreakdown"] = serde_json::json!(breakdown);
+            }
+            value
+        })
         .collect();
 
     // ====================================================================
@@ -433,6 +620,37 @@ pub async fn execute(
         "results": formatted,
     });
 
+    // Include the detected query intent that biased ranking
+    if let Some(intent) = intent {
+        response["intent"] = intent;
+    }
+    if served_from_warm_cache {
+        response["warmCache"] = serde_json::json!(true);
+    }
+    if let Some(at) = as_of {
+        response["asOf"] = serde_json::json!(at.to_rfc3339());
+    }
+    if let Some(profile) = ranking {
+        response["ranking"] = serde_json::json!(profile);
+    }
+    // Explain requested memories, including ones that missed the cut
+    if let Some(ids) = &args.explain_ids {
+        let explained: Vec<Value> = ids
+            .iter()
+            .map(|id| {
+                let returned = formatted.iter().any(|r| r["id"] == id.as_str());
+                match storage.explain_ranking_with_config(&args.query, id, overfetch_limit, &config) {
+                    Ok(breakdown) => serde_json::json!({
+                        "id": id,
+                        "returned": returned,
+                        "scoreBreakdown": breakdown,
+                    }),
+                    Err(e) => serde_json::json!({ "id": id, "error": e.to_string() }),
+                }
+            })
+            .collect();
+        response["explained"] = serde_json::json!(explained);
+    }
     // Include associations if any were found
     if !associations.is_empty() {
         response["associations"] = serde_json::json!(associations);
@@ -498,6 +716,7 @@ fn format_search_result(r: &vestige_core::SearchResult, detail_level: &str) -> V
             "validFrom": r.node.valid_from.map(|dt| dt.to_rfc3339()),
             "validUntil": r.node.valid_until.map(|dt| dt.to_rfc3339()),
             "matchType": format!("{:?}", r.match_type),
+            "sourceTrust": r.source_trust,
         }),
         // "summary" (default) — backwards compatible
         _ => serde_json::json!({
@@ -589,6 +808,9 @@ mod tests {
             tags: vec![],
             valid_from: None,
             valid_until: None,
+            namespace: None,
+            auto_tag: false,
+            on_duplicate: Default::default(),
         };
         let node = storage.ingest(input).unwrap();
         node.id
@@ -1011,6 +1233,44 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_search_explain_includes_score_breakdown() {
+        let (storage, _dir) = test_storage().await;
+        ingest_test_content(&storage, "The explain flag shows ranking signals.").await;
+
+        let args = serde_json::json!({ "query": "ranking signals", "explain": true });
+        let value = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        let results = value["results"].as_array().unwrap();
+        assert!(!results.is_empty());
+        let breakdown = &results[0]["scoreBreakdown"];
+        assert_eq!(breakdown["keywordRank"], 1);
+        assert!(breakdown["recencyTerm"].as_f64().unwrap() > 0.0);
+        assert!(breakdown["finalScore"].is_number());
+
+        let args = serde_json::json!({ "query": "ranking signals" });
+        let value = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        assert!(value["results"][0].get("scoreBreakdown").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_search_explains_requested_misses() {
+        let (storage, _dir) = test_storage().await;
+        ingest_test_content(&storage, "Ranking signals decide the order of results.").await;
+        let unrelated = ingest_test_content(&storage, "The office plants need water on Mondays.").await;
+
+        let args = serde_json::json!({
+            "query": "ranking signals",
+            "explain_ids": [unrelated, "missing"],
+        });
+        let value = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        let explained = value["explained"].as_array().unwrap();
+        assert_eq!(explained.len(), 2);
+        assert_eq!(explained[0]["returned"], false);
+        assert!(explained[0]["scoreBreakdown"]["ftsRank"].is_null());
+        assert!(explained[0]["scoreBreakdown"]["finalScore"].is_number());
+        assert!(explained[1]["error"].is_string());
+    }
+
     #[tokio::test]
     async fn test_search_detail_level_invalid_fails() {
         let (storage, _dir) = test_storage().await;
@@ -1108,4 +1368,189 @@ mod tests {
         assert_eq!(tb["minimum"], 100);
         assert_eq!(tb["maximum"], 10000);
     }
+
+    #[tokio::test]
+    async fn test_search_reports_detected_intent() {
+        let (storage, _dir) = test_storage().await;
+        ingest_test_content(&storage, "The worker keeps panicking with an error on startup.").await;
+
+        let args = serde_json::json!({
+            "query": "panicking with an error",
+            "detect_intent": true,
+            "min_similarity": 0.0
+        });
+        let value = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        assert_eq!(value["intent"]["type"], "debugging");
+        assert_eq!(value["intent"]["description"], "interpreted as debugging query");
+
+        // Off by default
+        let args = serde_json::json!({
+            "query": "panicking with an error",
+            "min_similarity": 0.0
+        });
+        let value = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        assert!(value.get("intent").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_search_opens_reconsolidation_window() {
+        let (storage, _dir) = test_storage().await;
+        let id = ingest_test_content(&storage, "The cache eviction policy is least recently used.").await;
+        assert!(!storage.is_labile(&id));
+
+        let args = serde_json::json!({ "query": "cache eviction policy", "min_similarity": 0.0 });
+        execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        assert!(storage.is_labile(&id));
+        assert!(storage.labile_remaining(&id).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_search_advanced_syntax_matches_exact_phrase() {
+        let (storage, _dir) = test_storage().await;
+        let exact = ingest_test_content(&storage, "Connection pool exhausted during the deploy.").await;
+        ingest_test_content(&storage, "The pool connection was exhausted overnight.").await;
+
+        let args = serde_json::json!({
+            "query": "\"connection pool exhausted\"",
+            "syntax": "advanced",
+            "min_similarity": 0.0,
+        });
+        let result = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        let ids: Vec<&str> = result["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|r| r["id"].as_str())
+            .collect();
+        assert_eq!(ids, vec![exact.as_str()]);
+
+        let args = serde_json::json!({ "query": "\"unbalanced OR OR content:x", "syntax": "advanced" });
+        assert!(execute(&storage, &test_cognitive(), Some(args)).await.is_ok());
+        let args = serde_json::json!({ "query": "pool", "syntax": "regex" });
+        assert!(execute(&storage, &test_cognitive(), Some(args)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_search_as_of_respects_validity() {
+        let (storage, _dir) = test_storage().await;
+        let now = chrono::Utc::now();
+        let superseded = storage.ingest(IngestInput {
+            content: "The staging database lives on db-old".to_string(),
+            node_type: "fact".to_string(),
+            valid_until: Some(now - chrono::Duration::days(30)),
+            ..Default::default()
+        }).unwrap().id;
+        let current = storage.ingest(IngestInput {
+            content: "The staging database lives on db-new".to_string(),
+            node_type: "fact".to_string(),
+            valid_from: Some(now - chrono::Duration::days(30)),
+            ..Default::default()
+        }).unwrap().id;
+
+        let args = serde_json::json!({
+            "query": "staging database",
+            "as_of": "today",
+            "min_similarity": 0.0,
+        });
+        let result = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        let ids: Vec<&str> = result["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|r| r["id"].as_str())
+            .collect();
+        assert_eq!(ids, vec![current.as_str()]);
+        assert!(!ids.contains(&superseded.as_str()));
+        assert!(result["asOf"].is_string());
+
+        // Nothing had been recorded yet two months ago
+        let args = serde_json::json!({ "query": "staging database", "as_of": "2 months ago" });
+        let result = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        assert_eq!(result["total"], 0);
+
+        let args = serde_json::json!({ "query": "staging database", "as_of": "whenever" });
+        assert!(execute(&storage, &test_cognitive(), Some(args)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_search_namespace_filter() {
+        let (storage, _dir) = test_storage().await;
+        let work = storage.ingest(IngestInput {
+            content: "The deploy pipeline runs on Fridays".to_string(),
+            node_type: "fact".to_string(),
+            namespace: Some("work".to_string()),
+            ..Default::default()
+        }).unwrap().id;
+        let personal = storage.ingest(IngestInput {
+            content: "The deploy of the garden shed happens on Fridays".to_string(),
+            node_type: "fact".to_string(),
+            namespace: Some("personal".to_string()),
+            ..Default::default()
+        }).unwrap().id;
+
+        let search = |namespace: Option<&str>| {
+            let mut args = serde_json::json!({ "query": "deploy", "min_similarity": 0.0 });
+            if let Some(ns) = namespace {
+                args["namespace"] = serde_json::json!(ns);
+            }
+            let storage = storage.clone();
+            async move {
+                let result = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+                result["results"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .filter_map(|r| r["id"].as_str().map(String::from))
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(search(Some("work")).await, vec![work.clone()]);
+        assert_eq!(search(Some("personal")).await, vec![personal.clone()]);
+        assert!(search(Some("other")).await.is_empty());
+        let all = search(None).await;
+        assert!(all.contains(&work) && all.contains(&personal));
+    }
+
+    #[tokio::test]
+    async fn test_search_ranking_profiles() {
+        let (storage, _dir) = test_storage().await;
+        ingest_test_content(&storage, "Ranking profiles trade recency for stability.").await;
+
+        let args = serde_json::json!({ "query": "ranking profiles", "ranking": "established", "explain": true });
+        let value = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        assert_eq!(value["ranking"]["recency_weight"], 0.0);
+        assert_eq!(value["results"][0]["scoreBreakdown"]["recencyTerm"], 0.0);
+
+        let custom = serde_json::json!({
+            "recency_weight": 0.6,
+            "importance_weight": 0.2,
+            "relevance_weight": 0.2,
+            "recency_half_life_hours": 12.0
+        });
+        let args = serde_json::json!({ "query": "ranking profiles", "ranking": custom });
+        assert!(execute(&storage, &test_cognitive(), Some(args)).await.is_ok());
+
+        let unbalanced = serde_json::json!({
+            "recency_weight": 0.6,
+            "importance_weight": 0.6,
+            "relevance_weight": 0.2,
+            "recency_half_life_hours": 12.0
+        });
+        let args = serde_json::json!({ "query": "ranking profiles", "ranking": unbalanced });
+        assert!(execute(&storage, &test_cognitive(), Some(args)).await.is_err());
+
+        let args = serde_json::json!({ "query": "ranking profiles", "ranking": "newest" });
+        assert!(execute(&storage, &test_cognitive(), Some(args)).await.is_err());
+    }
+
+    #[test]
+    fn test_established_ranking_skips_recency_boost() {
+        let searcher = TemporalSearcher::new();
+        let mut node = vestige_core::KnowledgeNode::default();
+        node.created_at = Utc::now() - chrono::Duration::days(400);
+
+        assert_eq!(temporal_factor(&searcher, &node, None, true), 1.0);
+        assert!(temporal_factor(&searcher, &node, None, false) < 1.0);
+    }
 }
@@ -1,10 +1,11 @@
 //! Session Context Tool — One-call session initialization (v1.8.0)
 //!
-//! Combines search, intentions, status, predictions, and codebase context
-//! into a single token-budgeted response. Replaces 5 separate calls at
-//! session start (~15K tokens → ~500-1000 tokens).
+//! Combines search, intentions, status, predictions, codebase context, and
+//! cross-project patterns into a single token-budgeted response. Replaces 5
+//! separate calls at session start (~15K tokens → ~500-1000 tokens).
 
 use std::collections::HashSet;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
@@ -12,8 +13,13 @@ use chrono::{DateTime, Duration, Utc};
 use serde::Deserialize;
 use serde_json::Value;
 
+use super::search_unified;
+use super::storage_error;
 use crate::cognitive::CognitiveEngine;
-use vestige_core::Storage;
+use crate::speculative;
+use vestige_core::codebase::GitAnalyzer;
+use vestige_core::codebase::tags::codebase_tag;
+use vestige_core::{FusionMethod, HybridSearchConfig, PredictionContext, ProjectContext, Storage};
 
 /// Input schema for session_context tool
 pub fn schema() -> Value {
@@ -58,6 +64,10 @@ pub fn schema() -> Value {
                 "type": "boolean",
                 "description": "Include memory predictions (default: true)",
                 "default": true
+            },
+            "namespace": {
+                "type": "string",
+                "description": "Only draw memories, intentions and stats from this namespace (default: all namespaces)"
             }
         }
     })
@@ -71,6 +81,7 @@ struct SessionContextArgs {
     include_status: Option<bool>,
     include_intentions: Option<bool>,
     include_predictions: Option<bool>,
+    namespace: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -111,6 +122,12 @@ pub async fn execute(
     let include_intentions = args.include_intentions.unwrap_or(true);
     let include_predictions = args.include_predictions.unwrap_or(true);
     let queries = args.queries.unwrap_or_else(|| vec!["user preferences".to_string()]);
+    let namespace = args.namespace.as_deref().filter(|ns| !ns.trim().is_empty());
+    let search_config = HybridSearchConfig {
+        fusion: FusionMethod::Linear { kw_weight: 0.3, sem_weight: 0.7 },
+        namespace: namespace.map(str::to_string),
+        ..Default::default()
+    };
 
     let mut context_parts: Vec<String> = Vec::new();
     let mut expandable_ids: Vec<String> = Vec::new();
@@ -120,12 +137,13 @@ pub async fn execute(
     // 1. Search queries — extract first sentence per result, dedup by ID
     // ====================================================================
     let mut seen_ids = HashSet::new();
+    let mut shown_ids: Vec<String> = Vec::new();
     let mut memory_lines: Vec<String> = Vec::new();
 
     for query in &queries {
         let results = storage
-            .hybrid_search(query, 5, 0.3, 0.7)
-            .map_err(|e| e.to_string())?;
+            .hybrid_search_with_config(query, 5, &search_config)
+            .map_err(storage_error)?;
 
         for r in results {
             if seen_ids.contains(&r.node.id) {
@@ -139,25 +157,56 @@ pub async fn execute(
                 expandable_ids.push(r.node.id.clone());
             } else {
                 memory_lines.push(line);
+                shown_ids.push(r.node.id.clone());
                 char_count += line_len;
             }
             seen_ids.insert(r.node.id.clone());
         }
     }
 
-    // Auto-strengthen accessed memories (Testing Effect)
-    let accessed_ids: Vec<&str> = seen_ids.iter().map(|s| s.as_str()).collect();
-    let _ = storage.strengthen_batch_on_access(&accessed_ids);
+    // Auto-strengthen the memories shown (Testing Effect); the ones left
+    // expandable were only surfaced, so they count as impressions
+    let shown: Vec<&str> = shown_ids.iter().map(|s| s.as_str()).collect();
+    let _ = storage.strengthen_batch_on_access(&shown);
+    let surfaced: Vec<&str> = expandable_ids.iter().map(|s| s.as_str()).collect();
+    let _ = storage.record_impressions(&surfaced);
 
     if !memory_lines.is_empty() {
         context_parts.push(format!("**Memories:**\n{}", memory_lines.join("\n")));
     }
 
+    // ====================================================================
+    // 1b. Working set — recent, intention-linked, and project memories
+    //     clustered into packets, using at most half the remaining budget
+    // ====================================================================
+    let project_tag = args
+        .context
+        .as_ref()
+        .and_then(|c| c.codebase.as_ref())
+        .map(|cb| codebase_tag(cb));
+    let working_set_budget = budget_chars.saturating_sub(char_count) / 4 / 2;
+    let working_set = match namespace {
+        Some(ns) => storage
+            .with_namespace(ns)
+            .build_session_overview(project_tag.as_deref(), working_set_budget),
+        None => storage.build_session_overview(project_tag.as_deref(), working_set_budget),
+    }
+    .map_err(storage_error)?;
+    if !working_set.packets.is_empty() {
+        let section = format!("**Working set:**\n{}", working_set.to_prompt());
+        char_count += section.len() + 1;
+        context_parts.push(section);
+    }
+
     // ====================================================================
     // 2. Intentions — find triggered + pending high-priority
     // ====================================================================
     if include_intentions {
-        let intentions = storage.get_active_intentions().map_err(|e| e.to_string())?;
+        let intentions = match namespace {
+            Some(ns) => storage.with_namespace(ns).get_active_intentions(),
+            None => storage.get_active_intentions(),
+        }
+        .map_err(storage_error)?;
         let now = Utc::now();
         let mut triggered_lines: Vec<String> = Vec::new();
 
@@ -203,7 +252,11 @@ pub async fn execute(
     // ====================================================================
     // 3. System status — compact one-liner
     // ====================================================================
-    let stats = storage.get_stats().map_err(|e| e.to_string())?;
+    let stats = match namespace {
+        Some(ns) => storage.with_namespace(ns).get_stats(),
+        None => storage.get_stats(),
+    }
+    .map_err(storage_error)?;
     let status = if stats.total_nodes == 0 {
         "empty"
     } else if stats.average_retention < 0.3 {
@@ -330,16 +383,96 @@ pub async fn execute(
         }
     }
 
+    // ====================================================================
+    // 4b. Speculative predictions from usage patterns — pre-warm search
+    // ====================================================================
+    let mut you_might_need: Vec<Value> = Vec::new();
+    if include_predictions {
+        let project = args
+            .context
+            .as_ref()
+            .and_then(|c| c.codebase.clone())
+            .or_else(speculative::default_project);
+        let prediction_ctx = PredictionContext {
+            recent_queries: queries.clone(),
+            project_path: project.map(PathBuf::from),
+            timestamp: Some(Utc::now()),
+            ..Default::default()
+        };
+
+        let (predictions, warm_queries) = {
+            let cog = cognitive.lock().await;
+            let predictions: Vec<_> = cog
+                .speculative_retriever
+                .predict_needed(&prediction_ctx)
+                .into_iter()
+                .take(5)
+                .collect();
+            let ids: Vec<String> = predictions.iter().map(|p| p.memory_id.clone()).collect();
+            let warm_queries: Vec<String> = cog
+                .speculative_retriever
+                .related_queries(&ids, 3)
+                .into_iter()
+                .filter(|q| !cog.warm_cache.contains(q))
+                .collect();
+            (predictions, warm_queries)
+        };
+
+        let mut need_lines: Vec<String> = Vec::new();
+        for p in &predictions {
+            let Ok(Some(node)) = storage.get_node(&p.memory_id) else {
+                continue;
+            };
+            if namespace.is_some() && node.namespace.as_deref() != namespace {
+                continue;
+            }
+            you_might_need.push(serde_json::json!({
+                "id": p.memory_id,
+                "confidence": p.confidence,
+                "reasoning": p.reasoning,
+            }));
+            let line = format!(
+                "- {} ({:.0}%)",
+                first_sentence(&node.content),
+                p.confidence * 100.0
+            );
+            let line_len = line.len() + 1;
+            if char_count + line_len <= budget_chars {
+                need_lines.push(line);
+                char_count += line_len;
+            }
+        }
+        if !need_lines.is_empty() {
+            context_parts.push(format!("**You might need:**\n{}", need_lines.join("\n")));
+        }
+
+        // Run the searches that usually lead to these memories ahead of time
+        for query in warm_queries {
+            if let Ok(results) = search_unified::prefetch(storage, &query) {
+                cognitive.lock().await.warm_cache.insert(&query, results);
+            }
+        }
+    }
+
     // ====================================================================
     // 5. Codebase patterns/decisions (if codebase specified)
     // ====================================================================
     if let Some(ref ctx) = args.context {
         if let Some(ref codebase) = ctx.codebase {
-            let codebase_tag = format!("codebase:{}", codebase);
+            let codebase_tag = codebase_tag(codebase);
             let mut cb_lines: Vec<String> = Vec::new();
 
+            let by_type = |node_type: &str| match namespace {
+                Some(ns) => storage.with_namespace(ns).get_nodes_by_type_and_tag(
+                    node_type,
+                    Some(&codebase_tag),
+                    3,
+                ),
+                None => storage.get_nodes_by_type_and_tag(node_type, Some(&codebase_tag), 3),
+            };
+
             // Get patterns
-            if let Ok(patterns) = storage.get_nodes_by_type_and_tag("pattern", Some(&codebase_tag), 3) {
+            if let Ok(patterns) = by_type("pattern") {
                 for p in &patterns {
                     let line = format!("- [pattern] {}", first_sentence(&p.content));
                     let line_len = line.len() + 1;
@@ -351,9 +484,7 @@ pub async fn execute(
             }
 
             // Get decisions
-            if let Ok(decisions) =
-                storage.get_nodes_by_type_and_tag("decision", Some(&codebase_tag), 3)
-            {
+            if let Ok(decisions) = by_type("decision") {
                 for d in &decisions {
                     let line = format!("- [decision] {}", first_sentence(&d.content));
                     let line_len = line.len() + 1;
@@ -371,7 +502,44 @@ pub async fn execute(
     }
 
     // ====================================================================
-    // 6. Assemble final response
+    // 6. Cross-project patterns for the current project
+    // ====================================================================
+    let project = match args.context.as_ref().and_then(|c| c.codebase.as_ref()) {
+        Some(name) => Some(ProjectContext {
+            name: Some(name.clone()),
+            ..Default::default()
+        }),
+        None => std::env::current_dir()
+            .ok()
+            .and_then(|cwd| GitAnalyzer::discover(&cwd).ok())
+            .map(|git| ProjectContext::from_path(git.repo_path())),
+    };
+
+    if let Some(project) = project
+        && let Ok(applicable) = storage.get_applicable_knowledge(&project)
+    {
+        let mut cp_lines: Vec<String> = Vec::new();
+        for knowledge in applicable.iter().take(3) {
+            let line = format!(
+                "- {} ({:.0}%): {}",
+                knowledge.pattern.pattern.name,
+                knowledge.applicability_confidence * 100.0,
+                knowledge.match_reason
+            );
+            let line_len = line.len() + 1;
+            if char_count + line_len <= budget_chars {
+                cp_lines.push(line);
+                char_count += line_len;
+            }
+        }
+
+        if !cp_lines.is_empty() {
+            context_parts.push(format!("**Cross-project:**\n{}", cp_lines.join("\n")));
+        }
+    }
+
+    // ====================================================================
+    // 7. Assemble final response
     // ====================================================================
     let header = format!("## Session ({} memories, {})\n", stats.total_nodes, status);
     let context_text = format!("{}{}", header, context_parts.join("\n\n"));
@@ -382,6 +550,8 @@ pub async fn execute(
         "tokensUsed": tokens_used,
         "tokenBudget": token_budget,
         "expandable": expandable_ids,
+        "youMightNeed": you_might_need,
+        "workingSet": working_set,
         "automationTriggers": {
             "needsDream": needs_dream,
             "needsBackup": needs_backup,
@@ -490,6 +660,9 @@ mod tests {
             tags: tags.into_iter().map(|s| s.to_string()).collect(),
             valid_from: None,
             valid_until: None,
+            namespace: None,
+            auto_tag: false,
+            on_duplicate: vestige_core::DuplicatePolicy::CreateAnyway,
         };
         let node = storage.ingest(input).unwrap();
         node.id
@@ -613,6 +786,12 @@ mod tests {
         let value = result.unwrap();
         // expandable should be a valid array (may be empty if all fit within budget)
         assert!(value["expandable"].is_array());
+
+        // Memories left expandable were only surfaced, not accessed
+        for id in value["expandable"].as_array().unwrap() {
+            let node = storage.get_node(id.as_str().unwrap()).unwrap().unwrap();
+            assert_eq!(node.last_accessed, node.created_at);
+        }
     }
 
     #[tokio::test]
@@ -662,6 +841,9 @@ mod tests {
             tags: vec!["pattern".to_string(), "codebase:vestige".to_string()],
             valid_from: None,
             valid_until: None,
+            namespace: None,
+            auto_tag: false,
+            on_duplicate: Default::default(),
         };
         storage.ingest(input).unwrap();
 
@@ -680,10 +862,102 @@ mod tests {
         assert!(ctx.contains("vestige"));
     }
 
+    #[tokio::test]
+    async fn test_cross_project_patterns_for_new_codebase() {
+        let (storage, _dir) = test_storage().await;
+        for project in ["alpha", "beta"] {
+            let tag = codebase_tag(project);
+            ingest_test_content(
+                &storage,
+                "Prefer builder pattern for config structs",
+                vec!["pattern", &tag],
+            )
+            .await;
+        }
+        storage.run_consolidation().unwrap();
+
+        let args = serde_json::json!({ "context": { "codebase": "gamma" } });
+        let value = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        let ctx = value["context"].as_str().unwrap();
+        assert!(ctx.contains("**Cross-project:**"));
+        assert!(ctx.contains("builder pattern"));
+    }
+
+    #[tokio::test]
+    async fn test_working_set_packets_within_budget() {
+        let (storage, _dir) = test_storage().await;
+        for i in 0..4 {
+            ingest_test_content(
+                &storage,
+                &format!("Postgres connection pool tuning note {}", i),
+                vec!["codebase:vestige"],
+            )
+            .await;
+        }
+        ingest_test_content(&storage, "Lunch order preferences for the offsite", vec![]).await;
+
+        let args = serde_json::json!({ "context": { "codebase": "vestige" } });
+        let value = execute(&storage, &test_cognitive(), Some(args)).await.unwrap();
+        assert!(value["context"].as_str().unwrap().contains("**Working set:**"));
+        let packets = value["workingSet"]["packets"].as_array().unwrap();
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[0]["memoryIds"].as_array().unwrap().len(), 4);
+        assert!(
+            value["workingSet"]["estimatedTokens"].as_u64().unwrap()
+                <= value["workingSet"]["budgetTokens"].as_u64().unwrap()
+        );
+    }
+
     // ========================================================================
     // HELPER TESTS
     // ========================================================================
 
+    #[tokio::test]
+    async fn test_usage_pattern_predicts_and_warms_search() {
+        let (storage, _dir) = test_storage().await;
+        let cognitive = test_cognitive();
+        let id = ingest_test_content(
+            &storage,
+            "Standup notes live in the team wiki under Rituals.",
+            vec![],
+        )
+        .await;
+
+        // The same search around this time of day on each of the last four days
+        for days_ago in 1..=4 {
+            storage
+                .record_usage(&vestige_core::UsageRecord {
+                    tool: "search".to_string(),
+                    query: Some("standup notes".to_string()),
+                    memory_ids: vec![id.clone()],
+                    project: Some("alpha".to_string()),
+                    recorded_at: Utc::now() - Duration::days(days_ago),
+                })
+                .unwrap();
+        }
+        let replayed = speculative::replay_usage_history(&storage, &*cognitive.lock().await);
+        assert_eq!(replayed, 4);
+
+        let args = serde_json::json!({
+            "queries": ["deployment checklist"],
+            "context": { "codebase": "alpha" }
+        });
+        let value = execute(&storage, &cognitive, Some(args)).await.unwrap();
+        assert!(value["context"].as_str().unwrap().contains("**You might need:**"));
+        let needed = value["youMightNeed"].as_array().unwrap();
+        assert_eq!(needed[0]["id"], id);
+        assert!(needed[0]["confidence"].as_f64().unwrap() > 0.0);
+
+        // The search that usually leads there was pre-fetched
+        let search = serde_json::json!({ "query": "standup notes" });
+        let result = search_unified::execute(&storage, &cognitive, Some(search))
+            .await
+            .unwrap();
+        assert_eq!(result["warmCache"], true);
+        assert_eq!(result["results"][0]["id"], id);
+        assert_eq!(cognitive.lock().await.warm_cache.stats().hits, 1);
+    }
+
     #[test]
     fn test_first_sentence_period() {
         assert_eq!(first_sentence("Hello world. More text here."), "Hello world.");
@@ -13,6 +13,9 @@
 //! v1.5.0: Enhanced with cognitive pipeline:
 //!   Pre-ingest: importance scoring (4-channel) + intent detection → auto-tag
 //!   Post-ingest: synaptic tagging + novelty model update + hippocampal indexing
+//!
+//! Emotional evaluation at ingest records valence and flashbulb encoding;
+//! flashbulb and high-arousal memories are waking-tagged for dream replay.
 
 use chrono::Utc;
 use serde::Deserialize;
@@ -21,8 +24,10 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::cognitive::CognitiveEngine;
+use super::storage_error;
 use vestige_core::{
-    ContentType, ImportanceContext, ImportanceEventType, ImportanceEvent, IngestInput, Storage,
+    ContentType, EmotionalEvaluation, ImportanceContext, ImportanceEventType, ImportanceEvent,
+    IngestInput, Storage,
 };
 
 /// Input schema for smart_ingest tool
@@ -40,7 +45,7 @@ pub fn schema() -> Value {
             },
             "node_type": {
                 "type": "string",
-                "description": "Type of knowledge: fact, concept, event, person, place, note, pattern, decision",
+                "description": "Type of knowledge: fact, concept, procedure, event, relationship, quote, code, question, insight, person, place, note, pattern, decision, bug_fix",
                 "default": "fact"
             },
             "tags": {
@@ -57,6 +62,15 @@ pub fn schema() -> Value {
                 "description": "Force creation of a new memory even if similar content exists",
                 "default": false
             },
+            "namespace": {
+                "type": "string",
+                "description": "Namespace to store the memory in; only memories in the same namespace are merged or updated (default: the default namespace)"
+            },
+            "autoTag": {
+                "type": "boolean",
+                "description": "Add suggested tags (from keywords and similar memories) that are confident enough. Suggestions are returned as suggestedTags either way",
+                "default": false
+            },
             "items": {
                 "type": "array",
                 "description": "Batch mode: array of items to save (max 20). Each runs through full cognitive pipeline with Prediction Error Gating. Use at session end or before context compaction.",
@@ -75,7 +89,7 @@ pub fn schema() -> Value {
                         },
                         "node_type": {
                             "type": "string",
-                            "description": "Type: fact, concept, event, person, place, note, pattern, decision",
+                            "description": "Type: fact, concept, procedure, event, relationship, quote, code, question, insight, person, place, note, pattern, decision, bug_fix",
                             "default": "fact"
                         },
                         "source": {
@@ -104,6 +118,8 @@ struct SmartIngestArgs {
     tags: Option<Vec<String>>,
     source: Option<String>,
     force_create: Option<bool>,
+    namespace: Option<String>,
+    auto_tag: Option<bool>,
     items: Option<Vec<BatchItem>>,
 }
 
@@ -130,9 +146,11 @@ pub async fn execute(
     };
 
     // Detect mode: batch (items present) vs single (content present)
+    let namespace = args.namespace.filter(|ns| !ns.trim().is_empty());
+    let auto_tag = args.auto_tag.unwrap_or(false);
     if let Some(items) = args.items {
         let global_force = args.force_create.unwrap_or(false);
-        return execute_batch(storage, cognitive, items, global_force).await;
+        return execute_batch(storage, cognitive, items, global_force, namespace, auto_tag).await;
     }
 
     // Single mode: content is required
@@ -143,21 +161,32 @@ pub async fn execute(
         return Err("Content cannot be empty".to_string());
     }
 
-    if content.len() > 1_000_000 {
-        return Err("Content too large (max 1MB)".to_string());
+    if content.len() > storage.max_content_bytes() {
+        return Err(content_too_large(storage, &content));
+    }
+    if let Some(node_type) = &args.node_type
+        && !storage.is_known_node_type(node_type)
+    {
+        return Err(unknown_node_type(storage, node_type));
     }
 
     // ====================================================================
     // COGNITIVE PRE-INGEST: importance scoring + intent detection + content analysis
     // ====================================================================
     let mut importance_composite = 0.0_f64;
+    let mut emotion = None;
     let mut tags = args.tags.unwrap_or_default();
 
-    if let Ok(cog) = cognitive.try_lock() {
+    if let Ok(mut cog) = cognitive.try_lock() {
         // 4A. Full 4-channel importance scoring
         let context = ImportanceContext::current();
         let importance = cog.importance_signals.compute_importance(&content, &context);
         importance_composite = importance.composite;
+        emotion = Some(cog.emotional_memory.evaluate_with_importance(
+            &content,
+            importance.novelty,
+            importance.arousal,
+        ));
 
         // 4B. Intent detection → auto-tag
         let intent_result = cog.intent_detector.detect_intent();
@@ -176,6 +205,13 @@ pub async fn execute(
         let _content_type = ContentType::detect(&content);
     }
 
+    // Suggested before ingest so the new memory is not its own neighbor
+    // (a lookup failure only costs the suggestions, not the ingest)
+    let suggested_tags = storage.suggest_tags(&content, 5).unwrap_or_else(|e| {
+        tracing::warn!("Tag suggestion failed, ingesting without suggestions: {}", e);
+        Vec::new()
+    });
+
     let input = IngestInput {
         content: content.clone(),
         node_type: args.node_type.unwrap_or_else(|| "fact".to_string()),
@@ -186,6 +222,9 @@ pub async fn execute(
         tags,
         valid_from: None,
         valid_until: None,
+        namespace: namespace.clone(),
+        auto_tag,
+        on_duplicate: Default::default(),
     };
 
     // ====================================================================
@@ -194,14 +233,14 @@ pub async fn execute(
 
     // Check if force_create is enabled
     if args.force_create.unwrap_or(false) {
-        let node = storage.ingest(input).map_err(|e| e.to_string())?;
+        let node = storage.ingest(input).map_err(storage_error)?;
         let node_id = node.id.clone();
         let node_content = node.content.clone();
         let node_type = node.node_type.clone();
         let has_embedding = node.has_embedding.unwrap_or(false);
 
         // Post-ingest cognitive side effects
-        run_post_ingest(cognitive, &node_id, &node_content, &node_type, importance_composite);
+        run_post_ingest(storage, cognitive, &node_id, &node_content, &node_type, importance_composite, emotion.as_ref());
 
         return Ok(serde_json::json!({
             "success": true,
@@ -211,6 +250,7 @@ pub async fn execute(
             "hasEmbedding": has_embedding,
             "predictionError": 1.0,
             "importanceScore": importance_composite,
+            "suggestedTags": suggested_tags,
             "reason": "Forced creation - skipped similarity check"
         }));
     }
@@ -218,14 +258,14 @@ pub async fn execute(
     // Use smart ingest with prediction error gating
     #[cfg(all(feature = "embeddings", feature = "vector-search"))]
     {
-        let result = storage.smart_ingest(input).map_err(|e| e.to_string())?;
+        let result = storage.smart_ingest(input).map_err(storage_error)?;
         let node_id = result.node.id.clone();
         let node_content = result.node.content.clone();
         let node_type = result.node.node_type.clone();
         let has_embedding = result.node.has_embedding.unwrap_or(false);
 
         // Post-ingest cognitive side effects
-        run_post_ingest(cognitive, &node_id, &node_content, &node_type, importance_composite);
+        run_post_ingest(storage, cognitive, &node_id, &node_content, &node_type, importance_composite, emotion.as_ref());
 
         Ok(serde_json::json!({
             "success": true,
@@ -236,7 +276,11 @@ pub async fn execute(
             "similarity": result.similarity,
             "predictionError": result.prediction_error,
             "supersededId": result.superseded_id,
+            "contradicts": result.contradicts,
+            "warning": contradiction_warning(storage, &result.contradicts),
+            "reconsolidation": result.reconsolidation.as_ref().map(|r| &r.change_summary),
             "importanceScore": importance_composite,
+            "suggestedTags": suggested_tags,
             "reason": result.reason,
             "explanation": match result.decision.as_str() {
                 "create" => "Created new memory - content was different enough from existing memories",
@@ -246,6 +290,7 @@ pub async fn execute(
                 "merge" => "Merged with related memories - content connects multiple topics",
                 "replace" => "Replaced existing memory content entirely",
                 "add_context" => "Added new content as context to existing memory",
+                "duplicate" => "Returned existing memory - content was an exact duplicate",
                 _ => "Memory processed successfully"
             }
         }))
@@ -253,12 +298,12 @@ pub async fn execute(
 
     #[cfg(not(all(feature = "embeddings", feature = "vector-search")))]
     {
-        let node = storage.ingest(input).map_err(|e| e.to_string())?;
+        let node = storage.ingest(input).map_err(storage_error)?;
         let node_id = node.id.clone();
         let node_content = node.content.clone();
         let node_type = node.node_type.clone();
 
-        run_post_ingest(cognitive, &node_id, &node_content, &node_type, importance_composite);
+        run_post_ingest(storage, cognitive, &node_id, &node_content, &node_type, importance_composite, emotion.as_ref());
 
         Ok(serde_json::json!({
             "success": true,
@@ -268,6 +313,7 @@ pub async fn execute(
             "hasEmbedding": false,
             "predictionError": 1.0,
             "importanceScore": importance_composite,
+            "suggestedTags": suggested_tags,
             "reason": "Embeddings not available - used regular ingest"
         }))
     }
@@ -283,6 +329,8 @@ async fn execute_batch(
     cognitive: &Arc<Mutex<CognitiveEngine>>,
     items: Vec<BatchItem>,
     global_force_create: bool,
+    namespace: Option<String>,
+    auto_tag: bool,
 ) -> Result<Value, String> {
     if items.is_empty() {
         return Err("Items array cannot be empty".to_string());
@@ -309,12 +357,11 @@ async fn execute_batch(
             continue;
         }
 
-        // Skip content > 1MB
-        if item.content.len() > 1_000_000 {
+        if item.content.len() > storage.max_content_bytes() {
             results.push(serde_json::json!({
                 "index": i,
                 "status": "skipped",
-                "reason": "Content too large (max 1MB)"
+                "reason": content_too_large(storage, &item.content)
             }));
             skipped += 1;
             continue;
@@ -327,12 +374,18 @@ async fn execute_batch(
         // COGNITIVE PRE-INGEST (per item)
         // ================================================================
         let mut importance_composite = 0.0_f64;
+        let mut emotion = None;
         let mut tags = item.tags.unwrap_or_default();
 
-        if let Ok(cog) = cognitive.try_lock() {
+        if let Ok(mut cog) = cognitive.try_lock() {
             let context = ImportanceContext::current();
             let importance = cog.importance_signals.compute_importance(&item.content, &context);
             importance_composite = importance.composite;
+            emotion = Some(cog.emotional_memory.evaluate_with_importance(
+                &item.content,
+                importance.novelty,
+                importance.arousal,
+            ));
 
             let intent_result = cog.intent_detector.detect_intent();
             if intent_result.confidence > 0.5 {
@@ -357,6 +410,9 @@ async fn execute_batch(
             tags,
             valid_from: None,
             valid_until: None,
+            namespace: namespace.clone(),
+            auto_tag,
+            on_duplicate: Default::default(),
         };
 
         // ================================================================
@@ -373,7 +429,7 @@ async fn execute_batch(
                     let node_type = node.node_type.clone();
 
                     created += 1;
-                    run_post_ingest(cognitive, &node_id, &node_content, &node_type, importance_composite);
+                    run_post_ingest(storage, cognitive, &node_id, &node_content, &node_type, importance_composite, emotion.as_ref());
 
                     results.push(serde_json::json!({
                         "index": i,
@@ -406,12 +462,12 @@ async fn execute_batch(
 
                     match result.decision.as_str() {
                         "create" | "supersede" | "replace" => created += 1,
-                        "update" | "reinforce" | "merge" | "add_context" => updated += 1,
+                        "update" | "reinforce" | "merge" | "add_context" | "duplicate" => updated += 1,
                         _ => created += 1,
                     }
 
                     // Post-ingest cognitive side effects
-                    run_post_ingest(cognitive, &node_id, &node_content, &node_type, importance_composite);
+                    run_post_ingest(storage, cognitive, &node_id, &node_content, &node_type, importance_composite, emotion.as_ref());
 
                     results.push(serde_json::json!({
                         "index": i,
@@ -419,6 +475,7 @@ async fn execute_batch(
                         "decision": result.decision,
                         "nodeId": node_id,
                         "similarity": result.similarity,
+                        "contradicts": result.contradicts,
                         "importanceScore": importance_composite,
                         "reason": result.reason
                     }));
@@ -443,7 +500,7 @@ async fn execute_batch(
                     let node_type = node.node_type.clone();
 
                     created += 1;
-                    run_post_ingest(cognitive, &node_id, &node_content, &node_type, importance_composite);
+                    run_post_ingest(storage, cognitive, &node_id, &node_content, &node_type, importance_composite, emotion.as_ref());
 
                     results.push(serde_json::json!({
                         "index": i,
@@ -480,17 +537,89 @@ async fn execute_batch(
     }))
 }
 
-/// Cognitive post-ingest side effects: synaptic tagging, novelty update, hippocampal indexing.
+/// Error for content over the storage size limit
+fn content_too_large(storage: &Storage, content: &str) -> String {
+    format!(
+        "Content too large ({} bytes, max {}). Split it into several smaller memories.",
+        content.len(),
+        storage.max_content_bytes()
+    )
+}
+
+/// Error for a node_type storage doesn't accept, listing the ones it does
+fn unknown_node_type(storage: &Storage, node_type: &str) -> String {
+    format!(
+        "Unknown node_type '{}'. Use one of: {}",
+        node_type,
+        storage.node_types().join(", ")
+    )
+}
+
+/// Warning naming the memories a new one probably contradicts, suggesting
+/// the outdated one be superseded
+#[cfg(all(feature = "embeddings", feature = "vector-search"))]
+fn contradiction_warning(storage: &Storage, ids: &[String]) -> Option<String> {
+    if ids.is_empty() {
+        return None;
+    }
+    let conflicting: Vec<String> = ids
+        .iter()
+        .map(|id| match storage.get_node(id) {
+            Ok(Some(node)) => {
+                let preview: String = node.content.chars().take(80).collect();
+                format!("{} (\"{}\")", id, preview)
+            }
+            _ => id.clone(),
+        })
+        .collect();
+    Some(format!(
+        "This memory probably contradicts: {}. If one is outdated, demote it with the 'memory' tool \
+         (action='demote') or edit it so only the current fact remains.",
+        conflicting.join("; ")
+    ))
+}
+
+/// Cognitive post-ingest side effects: emotional encoding, synaptic tagging, novelty update,
+/// hippocampal indexing.
 ///
 /// Uses try_lock() for non-blocking access. If cognitive is locked, side effects are skipped.
 fn run_post_ingest(
+    storage: &Storage,
     cognitive: &Arc<Mutex<CognitiveEngine>>,
     node_id: &str,
     content: &str,
     node_type: &str,
     importance_composite: f64,
+    emotion: Option<&EmotionalEvaluation>,
 ) {
+    // Persisted tags and events are captured at the next consolidation,
+    // so they survive restarts and don't depend on the cognitive lock
+    if importance_composite > 0.7 {
+        let event = ImportanceEvent::for_memory(node_id, ImportanceEventType::NoveltySpike);
+        if let Err(e) = storage.record_importance_event(&event) {
+            tracing::warn!("Failed to record importance event: {}", e);
+        }
+    } else if importance_composite > 0.3
+        && let Err(e) = storage.tag_for_capture(node_id)
+    {
+        tracing::warn!("Failed to tag memory for capture: {}", e);
+    }
+    if let Some(emotion) = emotion
+        && let Err(e) = storage.record_emotional_encoding(
+            node_id,
+            emotion.valence,
+            emotion.is_flashbulb,
+            emotion.needs_replay(),
+        )
+    {
+        tracing::warn!("Failed to record emotional encoding: {}", e);
+    }
+
     if let Ok(mut cog) = cognitive.try_lock() {
+        if let Some(emotion) = emotion {
+            cog.emotional_memory.record_encoding(node_id, emotion.valence, emotion.arousal);
+        }
+
         // 4C. Synaptic tagging for retroactive capture
         if importance_composite > 0.3 {
             cog.synaptic_tagging.tag_memory(node_id);
@@ -580,6 +709,64 @@ mod tests {
                 value["reason"].as_str().unwrap().contains("Embeddings not available"));
     }
 
+    #[tokio::test]
+    async fn test_smart_ingest_tags_high_arousal_for_replay() {
+        let (storage, _dir) = test_storage().await;
+        let cognitive = test_cognitive();
+        let ingest = |content: &str| {
+            let args = serde_json::json!({ "content": content, "forceCreate": true });
+            execute(&storage, &cognitive, Some(args))
+        };
+        let urgent = ingest("URGENT!!! Production is DOWN, critical crash, emergency rollback NOW!!!")
+            .await
+            .unwrap();
+        ingest("The readme lists the license.").await.unwrap();
+
+        let tagged = storage.get_waking_tagged_memories(10).unwrap();
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].id, urgent["nodeId"].as_str().unwrap());
+        assert!(tagged[0].emotional_valence.unwrap() < 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_smart_ingest_returns_and_applies_suggested_tags() {
+        let (storage, _dir) = test_storage().await;
+        for content in [
+            "Rust async functions return futures that do nothing until awaited",
+            "Tokio schedules async tasks on a work-stealing runtime",
+        ] {
+            storage
+                .ingest(IngestInput {
+                    content: content.to_string(),
+                    node_type: "fact".to_string(),
+                    tags: vec!["async".to_string()],
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+        let cognitive = test_cognitive();
+        let ingest = |auto_tag: bool| {
+            let args = serde_json::json!({
+                "content": "Dropping a Rust future cancels async tasks at the next await",
+                "forceCreate": true,
+                "autoTag": auto_tag
+            });
+            execute(&storage, &cognitive, Some(args))
+        };
+        let has_async = |value: &Value| {
+            let node = storage.get_node(value["nodeId"].as_str().unwrap()).unwrap().unwrap();
+            node.tags.contains(&"async".to_string())
+        };
+
+        let suggested_only = ingest(false).await.unwrap();
+        let suggestions = suggested_only["suggestedTags"].as_array().unwrap();
+        assert!(suggestions.iter().any(|s| s["tag"] == "async"));
+        assert!(!has_async(&suggested_only));
+
+        let applied = ingest(true).await.unwrap();
+        assert!(has_async(&applied));
+    }
+
     #[test]
     fn test_schema_has_required_fields() {
         let schema_value = schema();
@@ -611,7 +798,7 @@ mod tests {
     #[tokio::test]
     async fn test_smart_ingest_too_large_fails() {
         let (storage, _dir) = test_storage().await;
-        let large = "x".repeat(1_000_001);
+        let large = "x".repeat(storage.max_content_bytes() + 1);
         let args = serde_json::json!({ "content": large });
         let result = execute(&storage, &test_cognitive(), Some(args)).await;
         assert!(result.is_err());
@@ -619,14 +806,27 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_smart_ingest_exactly_1mb_succeeds() {
+    async fn test_smart_ingest_at_size_limit_succeeds() {
         let (storage, _dir) = test_storage().await;
-        let content = "x".repeat(1_000_000);
+        let content = "x".repeat(storage.max_content_bytes());
         let args = serde_json::json!({ "content": content });
         let result = execute(&storage, &test_cognitive(), Some(args)).await;
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_smart_ingest_unknown_node_type_lists_valid_types() {
+        let (storage, _dir) = test_storage().await;
+        let args = serde_json::json!({ "content": "Some gossip", "node_type": "rumor" });
+        let err = execute(&storage, &test_cognitive(), Some(args)).await.unwrap_err();
+        assert!(err.contains("Unknown node_type 'rumor'"));
+        assert!(err.contains("insight"));
+
+        storage.register_node_type("rumor").unwrap();
+        let args = serde_json::json!({ "content": "Some gossip", "node_type": "rumor" });
+        assert!(execute(&storage, &test_cognitive(), Some(args)).await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_smart_ingest_with_node_type() {
         let (storage, _dir) = test_storage().await;
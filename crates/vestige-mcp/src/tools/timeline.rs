@@ -13,6 +13,7 @@ use std::sync::Arc;
 use vestige_core::Storage;
 
 use super::search_unified::format_node;
+use super::storage_error;
 
 /// Input schema for memory_timeline tool
 pub fn schema() -> Value {
@@ -134,7 +135,7 @@ pub async fn execute(
     // Query memories in time range
     let mut results = storage
         .query_time_range(start, end, limit)
-        .map_err(|e| e.to_string())?;
+        .map_err(storage_error)?;
 
     // Post-query filters
     if let Some(ref node_type) = args.node_type {
@@ -204,6 +205,9 @@ mod tests {
             tags: vec!["timeline-test".to_string()],
             valid_from: None,
             valid_until: None,
+            namespace: None,
+            auto_tag: false,
+            on_duplicate: Default::default(),
         })
         .unwrap();
     }
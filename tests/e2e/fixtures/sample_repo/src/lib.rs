@@ -0,0 +1,6 @@
+pub mod network;
+pub mod storage;
+
+pub fn version() -> &'static str {
+    "0.1.0"
+}
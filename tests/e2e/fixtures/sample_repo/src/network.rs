@@ -0,0 +1,17 @@
+//! Custom error types for the network layer
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum NetworkError {
+    #[error("Connection refused: {0}")]
+    Refused(String),
+    #[error("Timed out after {0}ms")]
+    Timeout(u64),
+}
+
+pub type Result<T> = std::result::Result<T, NetworkError>;
+
+pub fn connect(addr: &str) -> Result<()> {
+    Err(NetworkError::Refused(addr.to_string()))
+}
@@ -0,0 +1,17 @@
+//! Custom error types for the storage layer
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Record not found: {0}")]
+    NotFound(String),
+}
+
+pub type Result<T> = std::result::Result<T, StorageError>;
+
+pub fn load(key: &str) -> Result<Vec<u8>> {
+    Err(StorageError::NotFound(key.to_string()))
+}
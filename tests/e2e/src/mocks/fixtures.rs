@@ -29,6 +29,9 @@ fn make_ingest_input(
         source,
         valid_from,
         valid_until,
+        namespace: None,
+        auto_tag: false,
+        on_duplicate: Default::default(),
     }
 }
 
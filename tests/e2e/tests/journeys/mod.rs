@@ -11,9 +11,11 @@
 //! 3. **Intentions Workflow**: Intent detection and memory relevance
 //! 4. **Spreading Activation**: Associative memory retrieval
 //! 5. **Import/Export**: Data portability and backup
+//! 6. **Pattern Analysis**: Learning recurring code patterns from a repository
 
 pub mod consolidation_workflow;
 pub mod import_export;
 pub mod ingest_recall_review;
 pub mod intentions_workflow;
+pub mod pattern_analysis;
 pub mod spreading_activation;
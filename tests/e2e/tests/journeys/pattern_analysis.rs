@@ -0,0 +1,162 @@
+//! # Pattern Analysis Journey Tests
+//!
+//! Tests learning recurring code patterns from a repository on disk. The
+//! fixture at `fixtures/sample_repo` is a tiny Rust project where two modules
+//! share the same thiserror-based error handling style.
+//!
+//! ## User Journey
+//!
+//! 1. User points Vestige at a repository
+//! 2. Source files are scanned, honouring .gitignore
+//! 3. Patterns recurring across files are stored with file references
+//! 4. Re-running the analysis refreshes patterns instead of duplicating them
+
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use vestige_core::codebase::{CodePattern, CodebaseMemory, CodebaseNode, PatternAnalysisConfig};
+
+// ============================================================================
+// HELPERS
+// ============================================================================
+
+const ERROR_PATTERN_ID: &str = "builtin-rust-error-handling";
+
+fn fixture_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/sample_repo")
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    std::fs::create_dir_all(to).unwrap();
+    for entry in std::fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            std::fs::copy(entry.path(), target).unwrap();
+        }
+    }
+}
+
+/// Copy the fixture into a fresh git repo, plus an ignored generated file
+/// that would otherwise match the error handling pattern
+fn sample_repo() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    copy_dir(&fixture_dir(), dir.path());
+    git2::Repository::init(dir.path()).unwrap();
+
+    let generated = dir.path().join("generated");
+    std::fs::create_dir_all(&generated).unwrap();
+    std::fs::copy(
+        dir.path().join("src/storage.rs"),
+        generated.join("bindings.rs"),
+    )
+    .unwrap();
+
+    dir
+}
+
+fn learned_patterns(memory: &CodebaseMemory) -> Vec<CodePattern> {
+    memory
+        .get_nodes_by_type("code_pattern")
+        .unwrap()
+        .into_iter()
+        .filter_map(|n| match n {
+            CodebaseNode::CodePattern(p) => Some(p),
+            _ => None,
+        })
+        .collect()
+}
+
+fn config() -> PatternAnalysisConfig {
+    PatternAnalysisConfig {
+        codebase: Some("sample".to_string()),
+        ..Default::default()
+    }
+}
+
+// ============================================================================
+// TEST 1: RECURRING PATTERNS ARE LEARNED WITH FILE REFERENCES
+// ============================================================================
+
+/// Test that a pattern shared by several files is stored with its files.
+///
+/// Validates:
+/// - The shared error handling pattern is detected
+/// - Example files point at both modules, relative to the repo root
+/// - The pattern is tagged with the codebase name
+#[test]
+fn test_recurring_pattern_is_learned() {
+    let repo = sample_repo();
+    let memory = CodebaseMemory::new(repo.path().to_path_buf()).unwrap();
+
+    let result = memory.analyze_patterns(repo.path(), &config()).unwrap();
+    assert!(result.patterns_detected >= 1, "Should detect a pattern");
+
+    let patterns = learned_patterns(&memory);
+    let error_pattern = patterns
+        .iter()
+        .find(|p| p.id == ERROR_PATTERN_ID)
+        .expect("Error handling pattern should be learned");
+
+    let mut files = error_pattern.example_files.clone();
+    files.sort();
+    assert_eq!(
+        files,
+        vec![PathBuf::from("src/network.rs"), PathBuf::from("src/storage.rs")]
+    );
+    assert_eq!(error_pattern.usage_count, 2);
+    assert!(error_pattern.tags.contains(&"codebase:sample".to_string()));
+}
+
+// ============================================================================
+// TEST 2: GITIGNORED FILES ARE SKIPPED
+// ============================================================================
+
+/// Test that files excluded by .gitignore never contribute to patterns.
+#[test]
+fn test_gitignored_files_are_skipped() {
+    let repo = sample_repo();
+    let memory = CodebaseMemory::new(repo.path().to_path_buf()).unwrap();
+
+    memory.analyze_patterns(repo.path(), &config()).unwrap();
+
+    for pattern in learned_patterns(&memory) {
+        assert!(
+            pattern
+                .example_files
+                .iter()
+                .all(|f| !f.starts_with("generated")),
+            "Pattern {} should not reference ignored files",
+            pattern.id
+        );
+    }
+}
+
+// ============================================================================
+// TEST 3: THRESHOLDS AND RE-RUNS
+// ============================================================================
+
+/// Test that a pattern must recur to be stored, and re-runs don't duplicate.
+#[test]
+fn test_min_occurrences_and_rerun() {
+    let repo = sample_repo();
+    let memory = CodebaseMemory::new(repo.path().to_path_buf()).unwrap();
+
+    // Requiring three files filters out the two-file error pattern
+    let strict = PatternAnalysisConfig {
+        min_occurrences: 3,
+        ..config()
+    };
+    memory.analyze_patterns(repo.path(), &strict).unwrap();
+    assert!(
+        learned_patterns(&memory)
+            .iter()
+            .all(|p| p.id != ERROR_PATTERN_ID)
+    );
+
+    memory.analyze_patterns(repo.path(), &config()).unwrap();
+    let first = learned_patterns(&memory).len();
+    memory.analyze_patterns(repo.path(), &config()).unwrap();
+    assert_eq!(learned_patterns(&memory).len(), first, "Re-run should not duplicate");
+}